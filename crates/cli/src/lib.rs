@@ -11,7 +11,7 @@ use clap::{
 use roc_build::link::{LinkType, LinkingStrategy};
 use roc_build::program::{
     handle_error_module, handle_loading_problem, standard_load_config, BuildFileError,
-    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
+    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, WasmFeatures, DEFAULT_ROC_FILENAME,
 };
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
@@ -20,6 +20,8 @@ use roc_load::{ExpectMetadata, Threading};
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
+use roc_problem::lint::{Lint, LintLevel, LintLevels};
+use roc_reporting::report::RenderTarget;
 use roc_target::Target;
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
@@ -28,6 +30,7 @@ use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::str::FromStr;
 use std::time::Instant;
 use strum::IntoEnumIterator;
 use target_lexicon::{Architecture, Triple};
@@ -44,6 +47,7 @@ pub const CMD_REPL: &str = "repl";
 pub const CMD_DOCS: &str = "docs";
 pub const CMD_CHECK: &str = "check";
 pub const CMD_VERSION: &str = "version";
+pub const CMD_EXPLAIN: &str = "explain";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
@@ -67,14 +71,24 @@ pub const FLAG_CHECK: &str = "check";
 pub const FLAG_STDIN: &str = "stdin";
 pub const FLAG_STDOUT: &str = "stdout";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_WASM_TAIL_CALLS: &str = "wasm-tail-calls";
+pub const FLAG_WASM_MULTI_VALUE: &str = "wasm-multi-value";
 pub const FLAG_OUTPUT: &str = "output";
 pub const FLAG_FUZZ: &str = "fuzz";
+pub const FLAG_INSTRUMENT_RC: &str = "instrument-rc";
+pub const FLAG_SPECIALIZATION_REPORT: &str = "specialization-report";
+pub const FLAG_ERROR_FORMAT: &str = "error-format";
+pub const FLAG_ALLOW: &str = "allow";
+pub const FLAG_WARN: &str = "warn";
+pub const FLAG_DENY: &str = "deny";
+pub const FLAG_VERBOSE_ERRORS: &str = "verbose-errors";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const ERROR_CODE: &str = "ERROR_CODE";
 
 const VERSION: &str = include_str!("../../../version.txt");
 const DEFAULT_GENERATED_DOCS_DIR: &str = "generated-docs";
@@ -140,12 +154,72 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(u32))
         .required(false);
 
+    let flag_wasm_tail_calls = Arg::new(FLAG_WASM_TAIL_CALLS)
+        .long(FLAG_WASM_TAIL_CALLS)
+        .help("Use the Wasm tail-call proposal for calls in tail position\n(This only applies when --dev also provided. The Wasm runtime must support it.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_wasm_multi_value = Arg::new(FLAG_WASM_MULTI_VALUE)
+        .long(FLAG_WASM_MULTI_VALUE)
+        .help("Use the Wasm multi-value proposal to return small data structures by value\n(This only applies when --dev also provided. The Wasm runtime must support it.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_fuzz = Arg::new(FLAG_FUZZ)
         .long(FLAG_FUZZ)
         .help("Instrument the roc binary for fuzzing with roc-fuzz")
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_instrument_rc = Arg::new(FLAG_INSTRUMENT_RC)
+        .long(FLAG_INSTRUMENT_RC)
+        .help("Count allocations, frees and refcount operations per proc and layout, and print them when the program exits")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_specialization_report = Arg::new(FLAG_SPECIALIZATION_REPORT)
+        .long(FLAG_SPECIALIZATION_REPORT)
+        .help("Print how many specializations were made of each function, for which layouts, and how many IR statements each one has, to stderr")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_error_format = Arg::new(FLAG_ERROR_FORMAT)
+        .long(FLAG_ERROR_FORMAT)
+        .help("How to print errors and warnings\n(`json` prints one JSON object per problem, on its own line, and nothing else.)")
+        .value_parser(["human", "json"])
+        .default_value("human")
+        .required(false);
+
+    let lint_names = || PossibleValuesParser::new(Lint::ALL.map(Lint::name));
+
+    let flag_allow = Arg::new(FLAG_ALLOW)
+        .long(FLAG_ALLOW)
+        .help("Don't report this kind of warning\n(Can be given more than once. `# lint:` comments in the source take precedence.)")
+        .value_parser(lint_names())
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_warn = Arg::new(FLAG_WARN)
+        .long(FLAG_WARN)
+        .help("Report this kind of problem as a warning\n(Can be given more than once. `# lint:` comments in the source take precedence.)")
+        .value_parser(lint_names())
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_deny = Arg::new(FLAG_DENY)
+        .long(FLAG_DENY)
+        .help("Report this kind of warning as an error\n(Can be given more than once. `# lint:` comments in the source take precedence.)")
+        .value_parser(lint_names())
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_verbose_errors = Arg::new(FLAG_VERBOSE_ERRORS)
+        .long(FLAG_VERBOSE_ERRORS)
+        .help("Explain type mismatches in more detail\n(Shows where the expected type came from and where the two types first differ.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .value_parser(value_parser!(PathBuf))
@@ -183,7 +257,16 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_instrument_rc.clone())
+            .arg(flag_specialization_report.clone())
+            .arg(flag_error_format.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_verbose_errors.clone())
             .arg(flag_wasm_stack_size_kb)
+            .arg(flag_wasm_tail_calls)
+            .arg(flag_wasm_multi_value)
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -234,6 +317,10 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_verbose_errors.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -258,6 +345,12 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_instrument_rc.clone())
+            .arg(flag_specialization_report.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_verbose_errors.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -273,6 +366,12 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_instrument_rc.clone())
+            .arg(flag_specialization_report.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_verbose_errors.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -313,6 +412,11 @@ pub fn build_app() -> Command {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_error_format)
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_verbose_errors.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -321,6 +425,14 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
             )
             )
+        .subcommand(Command::new(CMD_EXPLAIN)
+            .about("Explain an error code from a report header, like R0301")
+            .arg(
+                Arg::new(ERROR_CODE)
+                    .help("The error code to explain")
+                    .required(true),
+            )
+        )
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
@@ -405,6 +517,12 @@ pub fn build_app() -> Command {
         .arg(flag_linker)
         .arg(flag_prebuilt)
         .arg(flag_fuzz)
+        .arg(flag_instrument_rc)
+        .arg(flag_specialization_report)
+        .arg(flag_allow)
+        .arg(flag_warn)
+        .arg(flag_deny)
+        .arg(flag_verbose_errors)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true))
 }
@@ -416,6 +534,62 @@ pub enum BuildConfig {
     BuildAndRunIfNoErrors,
 }
 
+/// Only some subcommands have the --error-format flag. The others use the terminal.
+pub fn render_target_from_flags(matches: &ArgMatches) -> RenderTarget {
+    match matches.try_get_one::<String>(FLAG_ERROR_FORMAT) {
+        Ok(Some(format)) if format == "json" => RenderTarget::Json,
+        _ => RenderTarget::ColorTerminal,
+    }
+}
+
+/// Print the longer explanation for an error code like `R0301`, for `roc explain`
+pub fn explain(code: &str) -> i32 {
+    match roc_reporting::error::codes::lookup(code) {
+        Some(error_code) => {
+            println!("{}: {}\n", error_code.code, error_code.title);
+
+            match error_code.explanation {
+                Some(explanation) => print!("{explanation}"),
+                None => println!(
+                    "There is no longer explanation for this error yet. The report itself describes the problem."
+                ),
+            }
+
+            0
+        }
+        None => {
+            eprintln!(
+                "{code} is not a Roc error code. Error codes look like R0301, and are shown in the header of each report."
+            );
+
+            1
+        }
+    }
+}
+
+/// Lint levels from --allow, --warn and --deny. If a lint is given to more than one of them,
+/// the strictest level wins.
+pub fn lint_levels_from_flags(matches: &ArgMatches) -> LintLevels {
+    let mut lint_levels = LintLevels::default();
+
+    for (flag, level) in [
+        (FLAG_ALLOW, LintLevel::Allow),
+        (FLAG_WARN, LintLevel::Warn),
+        (FLAG_DENY, LintLevel::Deny),
+    ] {
+        if let Ok(Some(names)) = matches.try_get_many::<String>(flag) {
+            for name in names {
+                // clap has already checked that this is a lint name
+                if let Some(lint) = Lint::from_name(name) {
+                    lint_levels.set(lint, level);
+                }
+            }
+        }
+    }
+
+    lint_levels
+}
+
 fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     match (
         matches.get_flag(FLAG_OPTIMIZE),
@@ -445,6 +619,8 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let start_time = Instant::now();
     let arena = Bump::new();
     let opt_level = opt_level_from_flags(matches);
+    let lint_levels = lint_levels_from_flags(matches);
+    let verbose_errors = matches.get_flag(FLAG_VERBOSE_ERRORS);
 
     let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
         None => Threading::AllAvailable,
@@ -491,6 +667,9 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Test,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -505,10 +684,23 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             return handle_loading_problem(problem);
         }
         Err(LoadMonomorphizedError::ErrorModule(module)) => {
-            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+            return handle_error_module(
+                module,
+                start_time.elapsed(),
+                path.as_os_str(),
+                false,
+                RenderTarget::ColorTerminal,
+                &lint_levels,
+                verbose_errors,
+            );
         }
     };
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(
+        &mut loaded,
+        RenderTarget::ColorTerminal,
+        &lint_levels,
+        verbose_errors,
+    );
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
 
@@ -703,9 +895,20 @@ pub fn build(
         opt_level_from_flags(matches)
     };
 
+    // wasm32-wasi has the same triple as wasm32, so we need to look at the flag itself
+    let wasi_command = matches
+        .try_get_one::<String>(FLAG_TARGET)
+        .ok()
+        .flatten()
+        .and_then(|s| Target::from_str(s).ok())
+        == Some(Target::Wasm32Wasi);
+
     // Note: This allows using `--dev` with `--optimize`.
     // This means frontend optimizations and dev backend.
-    let code_gen_backend = if matches.get_flag(FLAG_DEV) {
+    let code_gen_backend = if wasi_command {
+        // Only the Wasm dev backend can generate a module without a host
+        CodeGenBackend::WasiCommand
+    } else if matches.get_flag(FLAG_DEV) {
         if matches!(triple.architecture, Architecture::Wasm32) {
             CodeGenBackend::Wasm
         } else {
@@ -736,7 +939,10 @@ pub fn build(
         Some(n) => Threading::AtMost(*n),
     };
 
-    let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
+    let wasm_dev_backend = matches!(
+        code_gen_backend,
+        CodeGenBackend::Wasm | CodeGenBackend::WasiCommand
+    );
 
     let linking_strategy = if wasm_dev_backend {
         LinkingStrategy::Additive
@@ -765,12 +971,22 @@ pub fn build(
         user_error!("Cannot instrument binary for fuzzing while using a dev backend.");
     }
 
+    let instrument_rc = matches.get_flag(FLAG_INSTRUMENT_RC);
+    if instrument_rc && matches!(code_gen_backend, CodeGenBackend::Wasm) {
+        user_error!("Cannot instrument refcounts while using the Wasm backend.");
+    }
+
     let wasm_dev_stack_bytes: Option<u32> = matches
         .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
         .ok()
         .flatten()
         .map(|x| x * 1024);
 
+    let wasm_features = WasmFeatures {
+        tail_calls: matches.try_get_one::<bool>(FLAG_WASM_TAIL_CALLS) == Ok(Some(&true)),
+        multi_value: matches.try_get_one::<bool>(FLAG_WASM_MULTI_VALUE) == Ok(Some(&true)),
+    };
+
     let build_ordering = match config {
         BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
         _ => BuildOrdering::AlwaysBuild,
@@ -782,9 +998,19 @@ pub fn build(
         emit_debug_info,
         emit_llvm_ir,
         fuzz,
+        instrument_rc,
+        specialization_report: matches.get_flag(FLAG_SPECIALIZATION_REPORT),
+        wasm_features,
     };
 
-    let load_config = standard_load_config(&triple, build_ordering, threading);
+    let render = render_target_from_flags(matches);
+    let lint_levels = lint_levels_from_flags(matches);
+    let verbose_errors = matches.get_flag(FLAG_VERBOSE_ERRORS);
+    let mut load_config = standard_load_config(&triple, build_ordering, threading);
+    load_config.render = render;
+    load_config.inline_and_fold = !matches!(code_gen_options.backend, CodeGenBackend::Llvm(_));
+    load_config.eval_constants = true;
+    load_config.stack_allocate = matches!(code_gen_options.backend, CodeGenBackend::Llvm(_));
 
     let res_binary_path = build_file(
         &arena,
@@ -799,6 +1025,8 @@ pub fn build(
         roc_cache_dir,
         load_config,
         out_path,
+        &lint_levels,
+        verbose_errors,
     );
 
     match res_binary_path {
@@ -821,6 +1049,11 @@ pub fn build(
                     // since the process is about to exit anyway.
                     // std::mem::forget(arena);
 
+                    if render == RenderTarget::Json {
+                        // Only the JSON diagnostics go to stdout
+                        return Ok(problems.exit_code());
+                    }
+
                     problems.print_to_stdout(total_time);
                     println!(" while successfully building:\n\n    {generated_filename}");
 
@@ -894,9 +1127,15 @@ pub fn build(
                 }
             }
         }
-        Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time, path.as_os_str(), true)
-        }
+        Err(BuildFileError::ErrorModule { module, total_time }) => handle_error_module(
+            module,
+            total_time,
+            path.as_os_str(),
+            true,
+            render,
+            &lint_levels,
+            verbose_errors,
+        ),
         Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
     }
 }
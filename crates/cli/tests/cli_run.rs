@@ -53,6 +53,8 @@ mod cli_run {
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT);
+    const DEV_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEV);
+    const INSTRUMENT_RC_FLAG: &str = concatcp!("--", roc_cli::FLAG_INSTRUMENT_RC);
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
 
@@ -553,7 +555,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── EXPECT FAILED in ...roc/roc/crates/cli_testing_examples/expects/expects.roc ─
+                ── EXPECT FAILED [R0501] in ...crates/cli_testing_examples/expects/expects.roc ─
 
                 This expectation failed:
 
@@ -586,7 +588,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── EXPECT FAILED in ...roc/roc/crates/cli_testing_examples/expects/expects.roc ─
+                ── EXPECT FAILED [R0501] in ...crates/cli_testing_examples/expects/expects.roc ─
 
                 This expectation failed:
 
@@ -598,7 +600,7 @@ mod cli_run {
                 a : Num *
                 a = 1
 
-                ── EXPECT FAILED in ...roc/roc/crates/cli_testing_examples/expects/expects.roc ─
+                ── EXPECT FAILED [R0501] in ...crates/cli_testing_examples/expects/expects.roc ─
 
                 This expectation failed:
 
@@ -610,7 +612,7 @@ mod cli_run {
                 a : Num *
                 a = 1
 
-                ── EXPECT FAILED in ...roc/roc/crates/cli_testing_examples/expects/expects.roc ─
+                ── EXPECT FAILED [R0501] in ...crates/cli_testing_examples/expects/expects.roc ─
 
                 This expectation failed:
 
@@ -1317,6 +1319,56 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[serial(instrument_rc)]
+    #[cfg_attr(windows, ignore)]
+    fn instrument_rc() {
+        let file = fixture_file("instrument-rc", "Main.roc");
+
+        let llvm: &[&str] = &[];
+        let dev: &[&str] = &[DEV_FLAG];
+
+        for flags in [llvm, dev] {
+            let out = run_roc(
+                [CMD_RUN, INSTRUMENT_RC_FLAG]
+                    .iter()
+                    .chain(flags)
+                    .chain(&[file.to_str().unwrap()]),
+                &[],
+                &[],
+            );
+
+            assert!(out.status.success(), "{flags:?} failed:\n{}", out.stderr);
+            assert!(out.stdout.ends_with("3892\n"), "{flags:?}:\n{}", out.stdout);
+
+            // e.g. "1234 allocations and 1233 frees in total"
+            let totals = out
+                .stderr
+                .lines()
+                .find(|line| line.ends_with("frees in total"))
+                .unwrap_or_else(|| panic!("{flags:?} printed no counts:\n{}", out.stderr));
+            let counts: Vec<u64> = totals
+                .split(' ')
+                .filter_map(|word| word.parse().ok())
+                .collect();
+
+            match counts.as_slice() {
+                [allocations, frees] => {
+                    assert!(*allocations > 0, "{flags:?}: {totals}");
+                    assert!(frees <= allocations, "{flags:?}: {totals}");
+                }
+                _ => panic!("{flags:?}: unexpected totals {totals:?}"),
+            }
+
+            // the refcount operations on the list of strings are counted per layout
+            assert!(
+                out.stderr.lines().any(|line| line.ends_with(" : List Str")),
+                "{flags:?} counted nothing for `List Str`:\n{}",
+                out.stderr
+            );
+        }
+    }
+
     #[test]
     #[serial(multi_dep_thunk)]
     #[cfg_attr(windows, ignore)]
@@ -1356,7 +1408,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── TYPE MISMATCH in tests/known_bad/TypeError.roc ──────────────────────────────
+                ── TYPE MISMATCH [R0301] in tests/known_bad/TypeError.roc ──────────────────────
 
                 Something is off with the body of the main definition:
 
@@ -1392,7 +1444,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNUSED IMPORT in ...nown_bad/UnusedImportButWithALongFileNameForTesting.roc ─
+                ── UNUSED IMPORT [R0103] in .../UnusedImportButWithALongFileNameForTesting.roc ─
 
                 Nothing from Symbol is used in this module.
 
@@ -1415,7 +1467,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── MISSING DEFINITION in tests/known_bad/ExposedNotDefined.roc ─────────────────
+                ── MISSING DEFINITION [R0104] in tests/known_bad/ExposedNotDefined.roc ─────────
 
                 bar is listed as exposed, but it isn't defined in this module.
 
@@ -1436,7 +1488,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNUSED IMPORT in tests/known_bad/UnusedImport.roc ───────────────────────────
+                ── UNUSED IMPORT [R0103] in tests/known_bad/UnusedImport.roc ───────────────────
 
                 Nothing from Symbol is used in this module.
 
@@ -1459,7 +1511,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNKNOWN GENERATES FUNCTION in tests/known_bad/UnknownGeneratesWith.roc ──────
+                ── UNKNOWN GENERATES FUNCTION [R0105] in ...known_bad/UnknownGeneratesWith.roc ─
 
                 I don't know how to generate the foobar function.
 
@@ -0,0 +1,12 @@
+app "instrument-rc"
+    packages { pf: "platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main =
+    List.range { start: At 1, end: At 1000 }
+    |> List.map Num.toStr
+    |> Str.joinWith ","
+    |> Str.countUtf8Bytes
+    |> Num.toStr
@@ -21,6 +21,9 @@ use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, Niche, RawFunctionLayout,
     STLayoutInterner, UnionLayout,
 };
+use std::time::{Duration, Instant};
+
+pub use morphic_lib::SummaryCache;
 
 // just using one module for now
 pub const MOD_APP: ModName = ModName(b"UserApp");
@@ -137,6 +140,21 @@ fn bytes_as_ascii(bytes: &[u8]) -> String {
     buf
 }
 
+/// How long the alias analysis took, and how much of it could be skipped thanks to the
+/// function summaries of a previous build.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AliasAnalysisTiming {
+    pub build_program: Duration,
+    pub solve: Duration,
+    pub summaries_reused: usize,
+    pub summaries_computed: usize,
+}
+
+/// Builds the morphic program for these procs and solves it.
+///
+/// When a `cache` is given, the summaries of functions that did not change since the build that
+/// filled it are reused instead of analyzed again, and the cache is updated with the summaries of
+/// this program.
 pub fn spec_program<'a, 'r, I1, I2>(
     arena: &'a Bump,
     interner: &'r STLayoutInterner<'a>,
@@ -144,11 +162,15 @@ pub fn spec_program<'a, 'r, I1, I2>(
     entry_point: roc_mono::ir::EntryPoint<'a>,
     procs: I1,
     hels: I2,
-) -> Result<morphic_lib::Solutions>
+    cache: Option<&mut SummaryCache>,
+) -> Result<(morphic_lib::Solutions, AliasAnalysisTiming)>
 where
     I1: Iterator<Item = &'r Proc<'a>>,
     I2: Iterator<Item = &'r HostExposedLambdaSet<'a>>,
 {
+    let mut timing = AliasAnalysisTiming::default();
+    let build_start = Instant::now();
+
     let main_module = {
         let mut m = ModDefBuilder::new();
 
@@ -348,10 +370,25 @@ where
         eprintln!("{}", program.to_source_string());
     }
 
-    match opt_level {
-        OptLevel::Development | OptLevel::Normal => morphic_lib::solve_trivial(program),
-        OptLevel::Optimize | OptLevel::Size => morphic_lib::solve(program),
-    }
+    timing.build_program = build_start.elapsed();
+    let solve_start = Instant::now();
+
+    let solutions = match opt_level {
+        OptLevel::Development | OptLevel::Normal => morphic_lib::solve_trivial(program)?,
+        OptLevel::Optimize | OptLevel::Size => match cache {
+            Some(cache) => {
+                let solutions = morphic_lib::solve_cached(program, cache)?;
+                timing.summaries_reused = cache.reused();
+                timing.summaries_computed = cache.computed();
+                solutions
+            }
+            None => morphic_lib::solve(program)?,
+        },
+    };
+
+    timing.solve = solve_start.elapsed();
+
+    Ok((solutions, timing))
 }
 
 /// if you want an "escape hatch" which allows you construct "best-case scenario" values
@@ -1584,6 +1621,11 @@ fn expr_spec<'a>(
 
             builder.add_make_tuple(block, &[])
         }
+        StackAlloc { .. } => {
+            // a fresh, unique allocation; the tag that reuses it is what the analysis tracks
+            let type_id = layout_spec(env, builder, interner, interner.get_repr(layout))?;
+            builder.add_unknown_with(block, &[], type_id)
+        }
         Alloca { initializer, .. } => {
             let initializer = &initializer.as_ref().map(|s| env.symbols[s]);
             let values = match initializer {
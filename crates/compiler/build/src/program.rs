@@ -3,16 +3,19 @@ use crate::link::{
 };
 use bumpalo::Bump;
 use inkwell::memory_buffer::MemoryBuffer;
+use roc_alias_analysis::{AliasAnalysisTiming, SummaryCache};
 use roc_error_macros::internal_error;
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
+pub use roc_gen_wasm::WasmFeatures;
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, LoadConfig, LoadMonomorphizedError,
     LoadedModule, LoadingProblem, MonomorphizedModule, Threading,
 };
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
+use roc_problem::lint::LintLevels;
 use roc_reporting::{
     cli::{report_problems, Problems},
     report::{RenderTarget, DEFAULT_PALETTE},
@@ -37,23 +40,44 @@ pub struct CodeGenTiming {
     pub generate_final_ir: Duration,
     pub code_gen_object: Duration,
     pub total: Duration,
+    pub alias_analysis: AliasAnalysisTiming,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
+) -> Problems {
     report_problems(
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.parse_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        &loaded.unoptimized_tail_calls,
+        render,
+        lint_levels,
+        verbose_errors,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(
+    loaded: &mut LoadedModule,
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
+) -> Problems {
     report_problems(
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.parse_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        &[],
+        render,
+        lint_levels,
+        verbose_errors,
     )
 }
 
@@ -78,6 +102,8 @@ pub enum CodeGenBackend {
     Assembly(AssemblyBackendMode),
     Llvm(LlvmBackendMode),
     Wasm,
+    /// Wasm dev backend, generating a self-contained WASI command module with no platform host
+    WasiCommand,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -87,6 +113,13 @@ pub struct CodeGenOptions {
     pub emit_debug_info: bool,
     pub emit_llvm_ir: bool,
     pub fuzz: bool,
+    /// Count allocations, frees and refcount operations per proc and layout
+    pub instrument_rc: bool,
+    /// Print the specializations that were made of each function to stderr, see
+    /// [roc_mono::debug::format_specialization_report]
+    pub specialization_report: bool,
+    /// Post-MVP Wasm proposals to use in the Wasm dev backend
+    pub wasm_features: WasmFeatures,
 }
 
 type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
@@ -100,12 +133,15 @@ pub fn gen_from_mono_module<'a>(
     code_gen_options: CodeGenOptions,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    roc_cache_dir: RocCacheDir<'_>,
 ) -> GenFromMono<'a> {
     let path = roc_file_path;
     let debug = code_gen_options.emit_debug_info;
     let emit_llvm_ir = code_gen_options.emit_llvm_ir;
     let fuzz = code_gen_options.fuzz;
+    let instrument_rc = code_gen_options.instrument_rc;
     let opt = code_gen_options.opt_level;
+    let wasm_features = code_gen_options.wasm_features;
 
     match code_gen_options.backend {
         CodeGenBackend::Wasm => gen_from_mono_module_dev(
@@ -114,15 +150,22 @@ pub fn gen_from_mono_module<'a>(
             target,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_features,
             AssemblyBackendMode::Binary, // dummy value, unused in practice
+            false,
         ),
+        CodeGenBackend::WasiCommand => {
+            gen_from_mono_module_wasi_command(arena, loaded, wasm_dev_stack_bytes, wasm_features)
+        }
         CodeGenBackend::Assembly(backend_mode) => gen_from_mono_module_dev(
             arena,
             loaded,
             target,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_features,
             backend_mode,
+            instrument_rc,
         ),
         CodeGenBackend::Llvm(backend_mode) => gen_from_mono_module_llvm(
             arena,
@@ -134,6 +177,8 @@ pub fn gen_from_mono_module<'a>(
             debug,
             emit_llvm_ir,
             fuzz,
+            instrument_rc,
+            roc_cache_dir,
         ),
     }
 }
@@ -152,6 +197,8 @@ fn gen_from_mono_module_llvm<'a>(
     emit_debug_info: bool,
     emit_llvm_ir: bool,
     fuzz: bool,
+    instrument_rc: bool,
+    roc_cache_dir: RocCacheDir<'_>,
 ) -> GenFromMono<'a> {
     use crate::target::{self, convert_opt_level};
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -213,6 +260,7 @@ fn gen_from_mono_module_llvm<'a>(
         module,
         target_info,
         mode: backend_mode,
+        instrument_rc,
 
         exposed_to_host: loaded
             .exposed_to_host
@@ -240,7 +288,21 @@ fn gen_from_mono_module_llvm<'a>(
         EntryPoint::Test => roc_mono::ir::EntryPoint::Expects { symbols: &[] },
     };
 
-    roc_gen_llvm::llvm::build::build_procedures(
+    // The function summaries of the alias analysis are kept in the roc cache dir, so the next
+    // build of this app only has to analyze the functions that changed. Only the optimizing builds
+    // run the full analysis, so the other builds have nothing to reuse.
+    let alias_analysis_cache_file = alias_analysis_cache_path(roc_cache_dir, roc_file_path);
+    let mut alias_analysis_cache = match (opt_level, &alias_analysis_cache_file) {
+        (OptLevel::Optimize | OptLevel::Size, Some(cache_file)) => Some(
+            std::fs::read(cache_file)
+                .ok()
+                .and_then(|bytes| SummaryCache::from_bytes(&bytes))
+                .unwrap_or_default(),
+        ),
+        _ => None,
+    };
+
+    let alias_analysis = roc_gen_llvm::llvm::build::build_procedures(
         &env,
         &loaded.layout_interner,
         opt_level,
@@ -249,8 +311,15 @@ fn gen_from_mono_module_llvm<'a>(
         entry_point,
         Some(&app_ll_file),
         &loaded.glue_layouts,
+        alias_analysis_cache.as_mut(),
     );
 
+    if let (Some(cache), Some(cache_file)) = (alias_analysis_cache, alias_analysis_cache_file) {
+        // the cache only speeds up the next build, so failing to write it is not an error
+        let _ = std::fs::create_dir_all(cache_file.parent().unwrap())
+            .and_then(|()| std::fs::write(&cache_file, cache.to_bytes()));
+    }
+
     // We are now finished building the LLVM IR.
     let generate_final_ir = all_code_gen_start.elapsed();
     let code_gen_object_start = Instant::now();
@@ -407,6 +476,7 @@ fn gen_from_mono_module_llvm<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis,
         },
         ExpectMetadata {
             interns: env.interns,
@@ -416,6 +486,27 @@ fn gen_from_mono_module_llvm<'a>(
     )
 }
 
+/// Where the alias analysis keeps an app's function summaries between builds. Like the type cache,
+/// this is keyed by the app's path, and only used if we are allowed to use the roc cache dir.
+fn alias_analysis_cache_path(
+    roc_cache_dir: RocCacheDir<'_>,
+    app_module_path: &Path,
+) -> Option<PathBuf> {
+    if let RocCacheDir::Persistent(cache_dir) = roc_cache_dir {
+        // The same app can be built from different working directories
+        let app_module_path = app_module_path.canonicalize().ok()?;
+        let key = blake3::hash(app_module_path.to_string_lossy().as_bytes());
+
+        Some(
+            cache_dir
+                .join("alias_analysis")
+                .join(format!("{}.dat", key.to_hex())),
+        )
+    } else {
+        None
+    }
+}
+
 #[cfg(feature = "target-wasm32")]
 fn gen_from_mono_module_dev<'a>(
     arena: &'a bumpalo::Bump,
@@ -423,7 +514,9 @@ fn gen_from_mono_module_dev<'a>(
     target: &target_lexicon::Triple,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_features: WasmFeatures,
     backend_mode: AssemblyBackendMode,
+    instrument_rc: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
@@ -433,9 +526,10 @@ fn gen_from_mono_module_dev<'a>(
             loaded,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_features,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, instrument_rc)
         }
         _ => todo!(),
     }
@@ -448,13 +542,15 @@ pub fn gen_from_mono_module_dev<'a>(
     target: &target_lexicon::Triple,
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
+    _wasm_features: WasmFeatures,
     backend_mode: AssemblyBackendMode,
+    instrument_rc: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
     match target.architecture {
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, instrument_rc)
         }
         _ => todo!(),
     }
@@ -466,6 +562,7 @@ fn gen_from_mono_module_dev_wasm32<'a>(
     loaded: MonomorphizedModule<'a>,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_features: WasmFeatures,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
     let MonomorphizedModule {
@@ -488,6 +585,7 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        features: wasm_features,
     };
 
     let host_bytes = std::fs::read(preprocessed_host_path).unwrap_or_else(|_| {
@@ -525,6 +623,81 @@ fn gen_from_mono_module_dev_wasm32<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis: AliasAnalysisTiming::default(),
+        },
+        ExpectMetadata {
+            interns,
+            layout_interner,
+            expectations: loaded.expectations,
+        },
+    )
+}
+
+#[cfg(feature = "target-wasm32")]
+fn gen_from_mono_module_wasi_command<'a>(
+    arena: &'a bumpalo::Bump,
+    loaded: MonomorphizedModule<'a>,
+    wasm_dev_stack_bytes: Option<u32>,
+    wasm_features: WasmFeatures,
+) -> GenFromMono<'a> {
+    let all_code_gen_start = Instant::now();
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        ..
+    } = loaded;
+
+    let exposed_to_host = loaded
+        .exposed_to_host
+        .top_level_values
+        .keys()
+        .copied()
+        .collect::<MutSet<_>>();
+
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        exposed_to_host,
+        stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        features: wasm_features,
+    };
+
+    // There is no platform host, so the builtins are the only other code we link
+    let builtins_module =
+        roc_gen_wasm::parse_host(arena, roc_bitcode::HOST_WASM).unwrap_or_else(|e| {
+            internal_error!(
+                "I ran into a problem with the Wasm builtins object file, at offset 0x{:x}:\n{}",
+                e.offset,
+                e.message
+            )
+        });
+
+    let final_binary_bytes = roc_gen_wasm::build_wasi_command_binary(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        builtins_module,
+        procedures,
+    )
+    .unwrap_or_else(|message| {
+        eprintln!("{message}");
+        std::process::exit(1);
+    });
+
+    let generate_final_ir = all_code_gen_start.elapsed();
+    let code_gen_object_start = Instant::now();
+    let code_gen_object = code_gen_object_start.elapsed();
+    let total = all_code_gen_start.elapsed();
+
+    (
+        CodeObject::Vector(final_binary_bytes),
+        CodeGenTiming {
+            generate_final_ir,
+            code_gen_object,
+            total,
+            alias_analysis: AliasAnalysisTiming::default(),
         },
         ExpectMetadata {
             interns,
@@ -534,11 +707,24 @@ fn gen_from_mono_module_dev_wasm32<'a>(
     )
 }
 
+#[cfg(not(feature = "target-wasm32"))]
+fn gen_from_mono_module_wasi_command<'a>(
+    _arena: &'a bumpalo::Bump,
+    _loaded: MonomorphizedModule<'a>,
+    _wasm_dev_stack_bytes: Option<u32>,
+    _wasm_features: WasmFeatures,
+) -> GenFromMono<'a> {
+    internal_error!(
+        "The wasm32-wasi target requires the compiler to be built with the `target-wasm32` feature"
+    )
+}
+
 fn gen_from_mono_module_dev_assembly<'a>(
     arena: &'a bumpalo::Bump,
     loaded: MonomorphizedModule<'a>,
     target: &target_lexicon::Triple,
     backend_mode: AssemblyBackendMode,
+    instrument_rc: bool,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
 
@@ -559,6 +745,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        instrument_rc,
     };
 
     let module_object =
@@ -580,6 +767,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
             generate_final_ir,
             code_gen_object,
             total,
+            alias_analysis: AliasAnalysisTiming::default(),
         },
         ExpectMetadata {
             interns,
@@ -644,10 +832,18 @@ pub fn handle_error_module(
     total_time: std::time::Duration,
     filename: &OsStr,
     print_run_anyway_hint: bool,
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
 ) -> std::io::Result<i32> {
     debug_assert!(module.total_problems() > 0);
 
-    let problems = report_problems_typechecked(&mut module);
+    let problems = report_problems_typechecked(&mut module, render, lint_levels, verbose_errors);
+
+    if render == RenderTarget::Json {
+        // Only the JSON diagnostics go to stdout
+        return Ok(problems.exit_code());
+    }
 
     problems.print_to_stdout(total_time);
 
@@ -711,6 +907,9 @@ pub fn standard_load_config(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     }
 }
 
@@ -728,8 +927,11 @@ pub fn build_file<'a>(
     roc_cache_dir: RocCacheDir<'_>,
     load_config: LoadConfig,
     out_path: Option<&Path>,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
+    let render = load_config.render;
 
     // Step 1: compile the app and generate the .o file
     let loaded =
@@ -746,9 +948,13 @@ pub fn build_file<'a>(
         linking_strategy,
         prebuilt_requested,
         wasm_dev_stack_bytes,
+        roc_cache_dir,
         loaded,
         compilation_start,
         out_path,
+        render,
+        lint_levels,
+        verbose_errors,
     )
 }
 
@@ -763,9 +969,13 @@ fn build_loaded_file<'a>(
     mut linking_strategy: LinkingStrategy,
     prebuilt_requested: bool,
     wasm_dev_stack_bytes: Option<u32>,
+    roc_cache_dir: RocCacheDir<'_>,
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
     out_path: Option<&Path>,
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let operating_system = roc_target::OperatingSystem::from(target.operating_system);
 
@@ -848,7 +1058,10 @@ fn build_loaded_file<'a>(
     };
 
     // We don't need to spawn a rebuild thread when using a prebuilt host.
-    let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None) {
+    // A WASI command module has no host at all.
+    let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None)
+        || matches!(code_gen_options.backend, CodeGenBackend::WasiCommand)
+    {
         None
     } else if is_platform_prebuilt {
         if !preprocessed_host_path.exists() {
@@ -916,7 +1129,7 @@ fn build_loaded_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, render, lint_levels, verbose_errors);
     let loaded = loaded;
 
     enum HostRebuildTiming {
@@ -942,6 +1155,19 @@ fn build_loaded_file<'a>(
         None
     };
 
+    if code_gen_options.specialization_report {
+        // stdout belongs to the program when this is `roc run` or `roc dev`
+        eprintln!(
+            "{}",
+            roc_mono::debug::format_specialization_report(
+                &loaded.interns,
+                &loaded.layout_interner,
+                &loaded.procedures,
+                &loaded.closure_parents,
+            )
+        );
+    }
+
     let (roc_app_bytes, code_gen_timing, expect_metadata) = gen_from_mono_module(
         arena,
         loaded,
@@ -950,6 +1176,7 @@ fn build_loaded_file<'a>(
         code_gen_options,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
+        roc_cache_dir,
     );
 
     buf.push('\n');
@@ -962,6 +1189,26 @@ fn build_loaded_file<'a>(
         "Generate final IR from Mono IR",
         code_gen_timing.generate_final_ir,
     );
+
+    let alias_analysis = code_gen_timing.alias_analysis;
+    report_timing(
+        buf,
+        "    Build alias analysis program",
+        alias_analysis.build_program,
+    );
+    report_timing(buf, "    Solve alias analysis", alias_analysis.solve);
+    if alias_analysis.summaries_reused + alias_analysis.summaries_computed > 0 {
+        use std::fmt::Write;
+
+        writeln!(
+            buf,
+            "                         {} of {} alias analysis summaries reused from the previous build",
+            alias_analysis.summaries_reused,
+            alias_analysis.summaries_reused + alias_analysis.summaries_computed,
+        )
+        .unwrap();
+    }
+
     report_timing(buf, "Generate object", code_gen_timing.code_gen_object);
     buf.push('\n');
     report_timing(buf, "Total", code_gen_timing.total);
@@ -1210,6 +1457,9 @@ pub fn check_file<'a>(
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
@@ -1223,11 +1473,13 @@ pub fn check_file<'a>(
         target_info,
         // TODO: we may not want this for just checking.
         function_kind: FunctionKind::LambdaSet,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render,
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, roc_cache_dir, load_config)?;
@@ -1274,7 +1526,10 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    Ok((
+        report_problems_typechecked(&mut loaded, render, lint_levels, verbose_errors),
+        compilation_end,
+    ))
 }
 
 pub fn build_str_test<'a>(
@@ -1291,6 +1546,9 @@ pub fn build_str_test<'a>(
         emit_debug_info: false,
         emit_llvm_ir: false,
         fuzz: false,
+        instrument_rc: false,
+        specialization_report: false,
+        wasm_features: Default::default(),
     };
 
     let emit_timings = false;
@@ -1303,6 +1561,7 @@ pub fn build_str_test<'a>(
     let threading = Threading::AtMost(2);
 
     let load_config = standard_load_config(&triple, build_ordering, threading);
+    let render = load_config.render;
 
     let compilation_start = std::time::Instant::now();
 
@@ -1327,9 +1586,13 @@ pub fn build_str_test<'a>(
         linking_strategy,
         assume_prebuild,
         wasm_dev_stack_bytes,
+        roc_cache_dir,
         loaded,
         compilation_start,
         None,
+        render,
+        &LintLevels::default(),
+        false,
     )
 }
 
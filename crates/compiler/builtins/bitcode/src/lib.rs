@@ -1,6 +1,8 @@
 use tempfile::NamedTempFile;
 
-const HOST_WASM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/builtins-wasm32.o"));
+/// The Wasm builtins as a relocatable object file.
+/// This can stand in for a host when there is no platform, as in the `wasm32-wasi` target.
+pub const HOST_WASM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/builtins-wasm32.o"));
 // TODO: in the future, we should use Zig's cross-compilation to generate and store these
 // for all targets, so that we can do cross-compilation!
 #[cfg(unix)]
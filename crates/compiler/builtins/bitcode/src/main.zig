@@ -5,6 +5,7 @@ const utils = @import("utils.zig");
 const expect = @import("expect.zig");
 const panic_utils = @import("panic.zig");
 const dbg_utils = @import("dbg.zig");
+const rc_stats = @import("rc_stats.zig");
 
 const ROC_BUILTINS = "roc_builtins";
 const NUM = "num";
@@ -249,6 +250,9 @@ comptime {
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
 
         exportUtilsFn(expect.readSharedBufferEnv, "read_env_shared_buffer");
+
+        exportUtilsFn(rc_stats.recordRcEvent, "record_rc_event");
+        exportUtilsFn(rc_stats.recordRcReuse, "record_rc_reuse");
     }
 
     if (builtin.target.cpu.arch == .aarch64) {
@@ -0,0 +1,173 @@
+const std = @import("std");
+const builtin = @import("builtin");
+
+// Refcount and allocation statistics for apps built with `--instrument-rc`.
+//
+// The generated code reports every refcount operation and every allocation of a tag or list
+// literal, together with the proc it happens in and the layout of the value. The counts are
+// printed to stderr when the process exits.
+
+pub const supported = builtin.target.cpu.arch != .wasm32;
+
+// Keep in sync with `RcEvent` in crates/compiler/builtins/src/bitcode.rs
+pub const RcEvent = enum(u8) {
+    Alloc = 0,
+    Free = 1,
+    Inc = 2,
+    Dec = 3,
+    ReuseHit = 4,
+};
+
+const EVENTS = @typeInfo(RcEvent).Enum.fields.len;
+const CAPACITY = 1024;
+
+const Site = struct {
+    proc_name: [*:0]const u8,
+    layout_name: [*:0]const u8,
+    counts: [EVENTS]u64,
+
+    fn total(self: *const Site) u64 {
+        var sum: u64 = 0;
+        for (self.counts) |count| {
+            sum += count;
+        }
+        return sum;
+    }
+};
+
+var sites: [CAPACITY]?Site = [_]?Site{null} ** CAPACITY;
+var overflow_site = Site{ .proc_name = "(other)", .layout_name = "(other)", .counts = [_]u64{0} ** EVENTS };
+
+// Frees happen inside the builtins, they are attributed to the site of the last reported event.
+var current_site: ?*Site = null;
+
+// Set by the first reported event, so programs built without `--instrument-rc` never write to
+// the counters below. They are not atomic, so they are only meaningful for single-threaded hosts.
+var instrumented = false;
+
+// All allocations and frees since the first reported event, including the ones made by builtins
+// like `List.append`
+var total_allocations: u64 = 0;
+var total_frees: u64 = 0;
+
+extern fn atexit(func: *const fn () callconv(.C) void) c_int;
+
+pub fn recordRcEvent(
+    event: u8,
+    amount: u64,
+    proc_name: [*:0]const u8,
+    layout_name: [*:0]const u8,
+) callconv(.C) void {
+    if (!instrumented) {
+        instrumented = true;
+        _ = atexit(dump);
+    }
+
+    const site = getSite(proc_name, layout_name);
+    site.counts[event] += amount;
+    current_site = site;
+}
+
+// A tag constructed with a reuse token is an allocation when the token is null
+pub fn recordRcReuse(
+    reuse_token: ?[*]u8,
+    proc_name: [*:0]const u8,
+    layout_name: [*:0]const u8,
+) callconv(.C) void {
+    const event = if (reuse_token == null) RcEvent.Alloc else RcEvent.ReuseHit;
+    recordRcEvent(@intFromEnum(event), 1, proc_name, layout_name);
+}
+
+pub inline fn countAllocation() void {
+    if (supported and instrumented) {
+        total_allocations += 1;
+    }
+}
+
+pub inline fn countFree() void {
+    if (supported and instrumented) {
+        total_frees += 1;
+
+        if (current_site) |site| {
+            site.counts[@intFromEnum(RcEvent.Free)] += 1;
+        }
+    }
+}
+
+fn getSite(proc_name: [*:0]const u8, layout_name: [*:0]const u8) *Site {
+    const proc = std.mem.span(proc_name);
+    const layout = std.mem.span(layout_name);
+
+    var hasher = std.hash.Wyhash.init(0);
+    hasher.update(proc);
+    hasher.update(&[_]u8{0});
+    hasher.update(layout);
+
+    var index = @as(usize, @intCast(hasher.final() % CAPACITY));
+    var probes: usize = 0;
+    while (probes < CAPACITY) : (probes += 1) {
+        if (sites[index]) |*site| {
+            if (std.mem.eql(u8, std.mem.span(site.proc_name), proc) and
+                std.mem.eql(u8, std.mem.span(site.layout_name), layout))
+            {
+                return site;
+            }
+        } else {
+            sites[index] = Site{ .proc_name = proc_name, .layout_name = layout_name, .counts = [_]u64{0} ** EVENTS };
+            return &sites[index].?;
+        }
+
+        index = (index + 1) % CAPACITY;
+    }
+
+    return &overflow_site;
+}
+
+fn busier(_: void, a: *const Site, b: *const Site) bool {
+    return a.total() > b.total();
+}
+
+fn dump() callconv(.C) void {
+    var sorted: [CAPACITY + 1]*const Site = undefined;
+    var length: usize = 0;
+    for (&sites) |*slot| {
+        if (slot.*) |*site| {
+            sorted[length] = site;
+            length += 1;
+        }
+    }
+    if (overflow_site.total() > 0) {
+        sorted[length] = &overflow_site;
+        length += 1;
+    }
+
+    std.mem.sort(*const Site, sorted[0..length], {}, busier);
+
+    const stderr = std.io.getStdErr().writer();
+    stderr.print("\n{s:>10} {s:>10} {s:>10} {s:>10} {s:>10}  proc : layout\n", .{ "allocs", "frees", "incs", "decs", "reuses" }) catch return;
+
+    for (sorted[0..length]) |site| {
+        const c = site.counts;
+        stderr.print("{d:>10} {d:>10} {d:>10} {d:>10} {d:>10}  {s} : {s}\n", .{
+            c[@intFromEnum(RcEvent.Alloc)],
+            c[@intFromEnum(RcEvent.Free)],
+            c[@intFromEnum(RcEvent.Inc)],
+            c[@intFromEnum(RcEvent.Dec)],
+            c[@intFromEnum(RcEvent.ReuseHit)],
+            std.mem.span(site.proc_name),
+            std.mem.span(site.layout_name),
+        }) catch return;
+    }
+
+    stderr.print("\n{d} allocations and {d} frees in total\n", .{ total_allocations, total_frees }) catch return;
+    stderr.print("Frees happen inside the builtins, so each one is counted for the proc and layout of the last reported event. The per-site frees are approximate.\n", .{}) catch return;
+}
+
+test "events of the same proc and layout share a site" {
+    const site = getSite("Test.main", "List I64");
+    site.counts[@intFromEnum(RcEvent.Inc)] += 2;
+
+    try std.testing.expectEqual(site, getSite("Test.main", "List I64"));
+    try std.testing.expect(site != getSite("Test.main", "Str"));
+    try std.testing.expectEqual(@as(u64, 2), getSite("Test.main", "List I64").counts[@intFromEnum(RcEvent.Inc)]);
+}
@@ -1,5 +1,6 @@
 const std = @import("std");
 const builtin = @import("builtin");
+const rc_stats = @import("rc_stats.zig");
 const Monotonic = std.builtin.AtomicOrder.Monotonic;
 
 const DEBUG_INCDEC = false;
@@ -320,6 +321,7 @@ inline fn free_ptr_to_refcount(
 
     // NOTE: we don't even check whether the refcount is "infinity" here!
     dealloc(allocation_ptr, alignment);
+    rc_stats.countFree();
 
     if (DEBUG_ALLOC and builtin.target.cpu.arch != .wasm32) {
         std.debug.print("💀 freed {*}\n", .{allocation_ptr});
@@ -451,6 +453,7 @@ pub fn allocateWithRefcount(
     const length = alignment + data_bytes;
 
     var new_bytes: [*]u8 = alloc(length, alignment) orelse unreachable;
+    rc_stats.countAllocation();
 
     if (DEBUG_ALLOC and builtin.target.cpu.arch != .wasm32) {
         std.debug.print("+ allocated {*} ({} bytes with alignment {})\n", .{ new_bytes, data_bytes, alignment });
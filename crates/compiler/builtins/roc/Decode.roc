@@ -24,6 +24,7 @@ interface Decode
         list,
         record,
         tuple,
+        tag,
         custom,
         decodeWith,
         fromBytesPartial,
@@ -115,6 +116,14 @@ DecoderFormatting implements
     ## `finalizer` should produce the tuple value from the decoded `state`.
     tuple : state, (state, Nat -> [Next (Decoder state fmt), TooLong]), (state -> Result val DecodeError) -> Decoder val fmt where fmt implements DecoderFormatting
 
+    ## `tag stepTag` decodes a value of a tag union.
+    ##
+    ## The format decodes the name of the tag and passes it to `stepTag`, which
+    ## returns a decoder for the tag's payloads, or `UnknownTag` if the tag
+    ## union has no tag of that name. The payload decoder decodes the payloads
+    ## like [tuple] decodes elements, and produces the whole tag union value.
+    tag : (Str -> [Payload (Decoder val fmt), UnknownTag]) -> Decoder val fmt where fmt implements DecoderFormatting
+
 ## Build a custom [Decoder] function. For example the implementation of
 ## `decodeBool` could be defined as follows;
 ##
@@ -0,0 +1,56 @@
+interface Default
+    exposes [
+        Default,
+        default,
+        defaultNum,
+        defaultBool,
+        defaultStr,
+        defaultList,
+    ] imports [
+        Bool.{ Bool },
+        List,
+        Str,
+        Num.{ Num },
+    ]
+
+## Defines a type which has a default value.
+##
+## `Default` can be derived for records whose fields all implement `Default`.
+## The default value of such a record has the default value in each of its
+## fields, so a large configuration record can be built by only updating the
+## fields that differ from the defaults:
+##
+## ```
+## Config : { retries : U8, name : Str, verbose : Bool }
+##
+## defaults : Config
+## defaults = Default.default {}
+##
+## config = { defaults & name: "server" }
+## ```
+##
+## The builtin default values are:
+##
+## 1. Zero for all numbers.
+## 2. `Bool.false` for [Bool].
+## 3. The empty string for [Str].
+## 4. The empty list for [List], and the empty dictionary for `Dict`.
+Default implements
+    ## Returns the default value of a type.
+    default : {} -> a where a implements Default
+
+## The default value of any number, which is zero.
+defaultNum : {} -> Num *
+defaultNum = \{} -> 0
+
+## The default value of [Bool], which is `Bool.false`.
+defaultBool : {} -> Bool
+defaultBool = \{} -> Bool.false
+
+## The default value of [Str], which is the empty string.
+defaultStr : {} -> Str
+defaultStr = \{} -> ""
+
+## The default value of [List], which is the empty list.
+defaultList : {} -> List *
+defaultList = \{} -> []
@@ -37,6 +37,7 @@ interface Dict
         Num.{ Nat, U64, F32, U32, U8, I8 },
         Hash.{ Hasher, Hash },
         Inspect.{ Inspect, Inspector, InspectFormatter },
+        Default.{ Default },
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you
@@ -111,6 +112,9 @@ Dict k v := {
         Inspect {
             toInspector: toInspectorDict,
         },
+        Default {
+            default: defaultDict,
+        },
     ]
 
 isEq : Dict k v, Dict k v -> Bool where v implements Eq
@@ -148,6 +152,9 @@ empty = \{} ->
         shifts: initialShifts,
     }
 
+defaultDict : {} -> Dict k v where k implements Hash & Eq
+defaultDict = \{} -> empty {}
+
 ## Return a dictionary with space allocated for a number of entries. This
 ## may provide a performance optimization if you know how many entries will be
 ## inserted.
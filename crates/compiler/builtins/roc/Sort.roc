@@ -0,0 +1,77 @@
+interface Sort
+    exposes [
+        Sort,
+        compare,
+        compareBool,
+        compareStr,
+        compareList,
+    ] imports [
+        Bool.{ Bool },
+        List,
+        Str,
+        Num.{ Nat },
+    ]
+
+## Defines a type whose values have a total order: any two values of the type
+## are either equal, or one of them comes before the other.
+##
+## `Sort` can be derived for records, tuples and tag unions whose contents
+## implement `Sort`:
+##
+## 1. Records are ordered by their fields, in alphabetical order of the field
+## names. Later fields are only compared if the earlier ones are equal.
+## 2. Tuples are ordered by their elements, from first to last.
+## 3. Tags of an opaque type, like `Priority := [Low, Medium, High] implements [Sort]`,
+## are ordered as they are declared. Other tag unions are ordered by their tag
+## names, in alphabetical order, since `[Low, High]` and `[High, Low]` are the
+## same type. Tags with the same name are ordered by their payloads, from first
+## to last.
+## 4. [Str] values are ordered by their UTF-8 bytes, and [List]s by their
+## elements; a list comes before any longer list that starts with it.
+## 5. Integers and [Dec] are ordered by value, and `Bool.false` comes before
+## `Bool.true`. Floating-point numbers do not implement `Sort`, because `NaN`
+## is not ordered relative to any number.
+Sort implements
+    ## Returns `LT` if the first value comes before the second, `GT` if it comes
+    ## after the second, and `EQ` if they are equal.
+    ##
+    ## ```
+    ## List.sortWith [{ x: 2, y: 1 }, { x: 1, y: 5 }] Sort.compare
+    ## ```
+    compare : a, a -> [LT, EQ, GT] where a implements Sort
+
+## Orders `Bool.false` before `Bool.true`.
+compareBool : Bool, Bool -> [LT, EQ, GT]
+compareBool = \a, b ->
+    if a == b then
+        EQ
+    else if a then
+        GT
+    else
+        LT
+
+## Orders strings by their UTF-8 bytes.
+compareStr : Str, Str -> [LT, EQ, GT]
+compareStr = \a, b -> compareList (Str.toUtf8 a) (Str.toUtf8 b)
+
+## Orders lists by their elements, from first to last.
+compareList : List a, List a -> [LT, EQ, GT] where a implements Sort
+compareList = \a, b -> compareListHelp a b 0
+
+compareListHelp : List a, List a, Nat -> [LT, EQ, GT] where a implements Sort
+compareListHelp = \a, b, index ->
+    when List.get a index is
+        Ok x ->
+            when List.get b index is
+                Ok y ->
+                    when compare x y is
+                        EQ -> compareListHelp a b (index + 1)
+                        order -> order
+
+                Err OutOfBounds -> GT
+
+        Err OutOfBounds ->
+            if index < List.len b then
+                LT
+            else
+                EQ
@@ -85,6 +85,7 @@ Json := {}
             list: decodeList,
             record: decodeRecord,
             tuple: decodeTuple,
+            tag: decodeTag,
         },
     ]
 
@@ -577,7 +578,15 @@ decodeTuple = \initialState, stepElem, finalizer -> Decode.custom \initialBytes,
 
         { rest: afterBracketBytes } <- initialBytes |> openBracket |> tryDecode
 
-        { val: endStateResult, rest: beforeClosingBracketBytes } <- decodeElems stepElem initialState 0 afterBracketBytes |> tryDecode
+        { val: endStateResult, rest: beforeClosingBracketBytes } <-
+            (
+                if List.first afterBracketBytes == Ok ']' then
+                    # An empty array, like the payload of a tag with no payloads
+                    { result: Ok initialState, rest: afterBracketBytes }
+                else
+                    decodeElems stepElem initialState 0 afterBracketBytes
+            )
+            |> tryDecode
 
         { rest: afterTupleBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
 
@@ -592,6 +601,64 @@ expect
 
     actual.result == Ok ("The Answer is", 42)
 
+# Tags are encoded as `{"A":[v1,v2]}`, see `encodeTag`
+decodeTag = \stepTag -> Decode.custom \bytes, @Json {} ->
+        countBytesBeforeName =
+            when List.walkUntil bytes (BeforeOpeningBrace 0) objectHelp is
+                ObjectFieldNameStart n -> n
+                _ -> 0
+
+        if countBytesBeforeName == 0 then
+            # Invalid tag, expected opening brace '{' followed by the tag name
+            { result: Err TooShort, rest: bytes }
+        else
+            bytesBeforeName = List.dropFirst bytes countBytesBeforeName
+
+            { val: tagName, rest: bytesAfterName } <- Decode.decodeWith bytesBeforeName decodeString json |> tryDecode
+
+            # Count the bytes until the payloads
+            countBytesBeforePayload =
+                when List.walkUntil bytesAfterName (BeforeColon 0) objectHelp is
+                    AfterColon n -> n
+                    _ -> 0
+
+            payloadBytes = List.dropFirst bytesAfterName countBytesBeforePayload
+
+            when stepTag tagName is
+                UnknownTag ->
+                    { result: Err TooShort, rest: bytes }
+
+                Payload payloadDecoder ->
+                    { val, rest: bytesAfterPayload } <- Decode.decodeWith payloadBytes payloadDecoder json |> tryDecode
+
+                    when List.walkUntil bytesAfterPayload (AfterObjectValue 0) objectHelp is
+                        AfterClosingBrace n -> { result: Ok val, rest: List.dropFirst bytesAfterPayload n }
+                        _ -> { result: Err TooShort, rest: bytesAfterPayload }
+
+# Test decode of tag with payloads
+expect
+    input = Str.toUtf8 "{\"A\":[\"The Answer is\",42]}"
+    actual : DecodeResult [A Str U8, B]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok (A "The Answer is" 42)
+
+# Test decode of tag without payloads
+expect
+    input = Str.toUtf8 "{\"B\":[]}"
+    actual : DecodeResult [A Str U8, B]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok B
+
+# Test decode of unknown tag
+expect
+    input = Str.toUtf8 "{\"C\":[]}"
+    actual : DecodeResult [A Str U8, B]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Err TooShort
+
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
     when List.get bytes 0 is
@@ -18,6 +18,20 @@ pub enum DecWidth {
     Dec,
 }
 
+/// What an `--instrument-rc` build reports to `roc_builtins.utils.record_rc_event`.
+/// Keep in sync with `RcEvent` in rc_stats.zig
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RcEvent {
+    Alloc = 0,
+    /// The builtins count the frees themselves; the generated code only marks the site of a free.
+    Free = 1,
+    Inc = 2,
+    Dec = 3,
+    /// A tag was constructed in existing memory (a reuse token or stack memory).
+    ReuseHit = 4,
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
 pub enum FloatWidth {
@@ -437,6 +451,8 @@ pub const UTILS_FREE_DATA_PTR: &str = "roc_builtins.utils.free_data_ptr";
 pub const UTILS_IS_UNIQUE: &str = "roc_builtins.utils.is_unique";
 pub const UTILS_DECREF_CHECK_NULL: &str = "roc_builtins.utils.decref_check_null";
 pub const UTILS_DICT_PSEUDO_SEED: &str = "roc_builtins.utils.dict_pseudo_seed";
+pub const UTILS_RECORD_RC_EVENT: &str = "roc_builtins.utils.record_rc_event";
+pub const UTILS_RECORD_RC_REUSE: &str = "roc_builtins.utils.record_rc_reuse";
 
 pub const UTILS_EXPECT_FAILED_START_SHARED_BUFFER: &str =
     "roc_builtins.utils.expect_failed_start_shared_buffer";
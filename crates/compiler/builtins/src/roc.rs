@@ -17,6 +17,8 @@ pub fn module_source(module_id: ModuleId) -> &'static str {
         ModuleId::HASH => HASH,
         ModuleId::INSPECT => INSPECT,
         ModuleId::JSON => JSON,
+        ModuleId::SORT => SORT,
+        ModuleId::DEFAULT => DEFAULT,
         _ => internal_error!(
             "ModuleId {:?} is not part of the standard library",
             module_id
@@ -37,3 +39,5 @@ const DECODE: &str = include_str!("../roc/Decode.roc");
 const HASH: &str = include_str!("../roc/Hash.roc");
 const INSPECT: &str = include_str!("../roc/Inspect.roc");
 const JSON: &str = include_str!("../roc/TotallyNotJson.roc");
+const SORT: &str = include_str!("../roc/Sort.roc");
+const DEFAULT: &str = include_str!("../roc/Default.roc");
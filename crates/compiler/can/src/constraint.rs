@@ -602,6 +602,7 @@ impl Constraints {
             | Constraint::Exhaustive { .. }
             | Constraint::Resolve(..)
             | Constraint::IngestedFile(..)
+            | Constraint::TypedHole(..)
             | Constraint::CheckCycle(..) => false,
         }
     }
@@ -685,6 +686,10 @@ impl Constraints {
     ) -> Constraint {
         Constraint::IngestedFile(type_index, file_path, bytes)
     }
+
+    pub fn typed_hole(&mut self, variable: Variable, region: Region) -> Constraint {
+        Constraint::TypedHole(variable, region)
+    }
 }
 
 roc_error_macros::assert_sizeof_default!(Constraint, 3 * 8);
@@ -787,6 +792,8 @@ pub enum Constraint {
     CheckCycle(Index<Cycle>, IllegalCycleMark),
 
     IngestedFile(TypeOrVar, Box<PathBuf>, Arc<Vec<u8>>),
+    /// A typed hole `_` in expression position. Its type is reported once solving is done.
+    TypedHole(Variable, Region),
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -865,6 +872,9 @@ impl std::fmt::Debug for Constraint {
             Self::IngestedFile(arg0, arg1, arg2) => {
                 write!(f, "IngestedFile({arg0:?}, {arg1:?}, {arg2:?})")
             }
+            Self::TypedHole(arg0, arg1) => {
+                write!(f, "TypedHole({arg0:?}, {arg1:?})")
+            }
         }
     }
 }
@@ -853,7 +853,7 @@ fn canonicalize_opaque<'a>(
                 let mut impls = Vec::with_capacity(num_members);
                 for &member in members.iter() {
                     let (derived_impl, impl_pat, impl_body) =
-                        derive::synthesize_member_impl(env, scope, name_str, member);
+                        derive::synthesize_member_impl(env, scope, name_str, &ann.value, member);
 
                     let derived_def = Loc::at(
                         derive::DERIVED_REGION,
@@ -978,6 +978,7 @@ pub(crate) fn canonicalize_defs<'a>(
                 env,
                 var_store,
                 value_def,
+                region,
                 scope,
                 &pending_abilities_in_scope,
                 &mut output,
@@ -1052,7 +1053,7 @@ fn canonicalize_value_defs<'a>(
                 // that get would have gotten added later in the defs list!
                 pending_value_defs.push(pending_def);
             }
-            PendingValue::SignatureDefMismatch => { /* skip */ }
+            PendingValue::SignatureDefMismatch | PendingValue::Malformed => { /* skip */ }
             PendingValue::Dbg(pending_dbg) => {
                 pending_dbgs.push(pending_dbg);
             }
@@ -2751,6 +2752,8 @@ enum PendingValue<'a> {
     Expect(PendingExpectOrDbg<'a>),
     ExpectFx(PendingExpectOrDbg<'a>),
     SignatureDefMismatch,
+    /// The def failed to parse; the syntax error has already been reported
+    Malformed,
 }
 
 struct PendingExpectOrDbg<'a> {
@@ -2758,10 +2761,12 @@ struct PendingExpectOrDbg<'a> {
     preceding_comment: Region,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn to_pending_value_def<'a>(
     env: &mut Env<'a>,
     var_store: &mut VarStore,
     def: &'a ast::ValueDef<'a>,
+    region: Region,
     scope: &mut Scope,
     pending_abilities_in_scope: &PendingAbilitiesInScope,
     output: &mut Output,
@@ -2874,6 +2879,60 @@ fn to_pending_value_def<'a>(
             condition,
             preceding_comment: *preceding_comment,
         }),
+
+        Malformed(text) => match malformed_def_name(text) {
+            // Bind the name the def would have had, as if it were an annotation without a body,
+            // so that uses of it don't cause more errors. If the annotation itself parsed, the
+            // name is already bound.
+            Some(name) if scope.lookup_str(name, region).is_err() => {
+                let name_region = Region::new(
+                    region.start(),
+                    region.start().bump_column(name.len() as u32),
+                );
+                let loc_pattern = env
+                    .arena
+                    .alloc(Loc::at(name_region, ast::Pattern::Identifier(name)));
+                let loc_ann = env
+                    .arena
+                    .alloc(Loc::at(name_region, ast::TypeAnnotation::Wildcard));
+
+                let loc_can_pattern = canonicalize_def_header_pattern(
+                    env,
+                    var_store,
+                    scope,
+                    pending_abilities_in_scope,
+                    output,
+                    pattern_type,
+                    &loc_pattern.value,
+                    loc_pattern.region,
+                );
+
+                PendingValue::Def(PendingValueDef::AnnotationOnly(
+                    loc_pattern,
+                    loc_can_pattern,
+                    loc_ann,
+                ))
+            }
+            _ => PendingValue::Malformed,
+        },
+    }
+}
+
+/// The name a malformed def like `name = ...` or `name : ...` was going to define, if any
+fn malformed_def_name(text: &str) -> Option<&str> {
+    let name_len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    let (name, rest) = text.split_at(name_len);
+    let rest = rest.trim_start_matches(' ');
+
+    let is_def = (rest.starts_with('=') && !rest.starts_with("=="))
+        || (rest.starts_with(':') && !rest.starts_with(":="));
+
+    if is_def && name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        Some(name)
+    } else {
+        None
     }
 }
 
@@ -6,9 +6,10 @@
 //!   - Derived impls for opaques are typically very small, effectively deferring the
 //!     implementation to the value they wrap.
 
+use bumpalo::Bump;
 use roc_error_macros::internal_error;
 use roc_module::{called_via::CalledVia, symbol::Symbol};
-use roc_parse::ast::{self, Collection};
+use roc_parse::ast::{self, Collection, ExtractSpaces};
 use roc_region::all::{Loc, Region};
 
 use crate::{env::Env, pattern::Pattern, scope::Scope};
@@ -213,6 +214,205 @@ fn is_eq<'a>(env: &mut Env<'a>, at_opaque: &'a str) -> ast::Expr<'a> {
     )
 }
 
+fn compare<'a>(
+    env: &mut Env<'a>,
+    at_opaque: &'a str,
+    opaque_ann: &'a ast::TypeAnnotation<'a>,
+) -> ast::Expr<'a> {
+    let alloc_pat = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+    let alloc_expr = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+
+    let payload1 = "#payload1";
+    let payload2 = "#payload2";
+
+    let opaque_ref = alloc_pat(ast::Pattern::OpaqueRef(at_opaque));
+    // \@Opaq payload1
+    let opaque1 = ast::Pattern::Apply(
+        opaque_ref,
+        &*env
+            .arena
+            .alloc([Loc::at(DERIVED_REGION, ast::Pattern::Identifier(payload1))]),
+    );
+    // \@Opaq payload2
+    let opaque2 = ast::Pattern::Apply(
+        opaque_ref,
+        &*env
+            .arena
+            .alloc([Loc::at(DERIVED_REGION, ast::Pattern::Identifier(payload2))]),
+    );
+
+    // Sort.compare payload1 payload2
+    let call_member = alloc_expr(ast::Expr::Apply(
+        alloc_expr(ast::Expr::Var {
+            module_name: "Sort",
+            ident: "compare",
+        }),
+        &*env.arena.alloc([
+            &*alloc_expr(ast::Expr::Var {
+                module_name: "",
+                ident: payload1,
+            }),
+            &*alloc_expr(ast::Expr::Var {
+                module_name: "",
+                ident: payload2,
+            }),
+        ]),
+        roc_module::called_via::CalledVia::Space,
+    ));
+
+    // The tags of a structural tag union can only be ordered by name, since `[A, B]` and `[B, A]`
+    // are the same type. An opaque type declares its tags in an order though, so it uses that:
+    //
+    // when Num.compare (rank payload1) (rank payload2) is
+    //     EQ -> Sort.compare payload1 payload2
+    //     order -> order
+    //
+    // The structural comparison then only ever sees values with the same tag.
+    let body = match declared_tags(opaque_ann) {
+        Some(tags) if tags.len() > 1 => {
+            let order = "#order";
+
+            // Num.compare (rank payload1) (rank payload2)
+            let compare_ranks = alloc_expr(ast::Expr::Apply(
+                alloc_expr(ast::Expr::Var {
+                    module_name: "Num",
+                    ident: "compare",
+                }),
+                &*env.arena.alloc([
+                    &*alloc_expr(tag_rank(env.arena, &tags, payload1)),
+                    &*alloc_expr(tag_rank(env.arena, &tags, payload2)),
+                ]),
+                CalledVia::Space,
+            ));
+
+            let branches = [
+                (ast::Pattern::Tag("EQ"), *call_member),
+                (
+                    ast::Pattern::Identifier(order),
+                    Loc::at(
+                        DERIVED_REGION,
+                        ast::Expr::Var {
+                            module_name: "",
+                            ident: order,
+                        },
+                    ),
+                ),
+            ];
+
+            alloc_expr(ast::Expr::When(
+                compare_ranks,
+                env.arena
+                    .alloc_slice_fill_iter(branches.into_iter().map(|(pattern, value)| {
+                        &*env.arena.alloc(ast::WhenBranch {
+                            patterns: env.arena.alloc([Loc::at(DERIVED_REGION, pattern)]),
+                            value,
+                            guard: None,
+                        })
+                    })),
+            ))
+        }
+        _ => call_member,
+    };
+
+    // \@Opaq payload1, @Opaq payload2 -> <body>
+    ast::Expr::Closure(
+        env.arena.alloc([
+            Loc::at(DERIVED_REGION, opaque1),
+            Loc::at(DERIVED_REGION, opaque2),
+        ]),
+        body,
+    )
+}
+
+/// The tags of a closed tag union annotation and their arities, in the order they are declared.
+fn declared_tags<'a>(ann: &'a ast::TypeAnnotation<'a>) -> Option<Vec<(&'a str, usize)>> {
+    match ann.extract_spaces().item {
+        ast::TypeAnnotation::TagUnion { ext: None, tags } => tags
+            .iter()
+            .map(|tag| match tag.value.extract_spaces().item {
+                ast::Tag::Apply { name, args } => Some((name.value, args.len())),
+                ast::Tag::Malformed(_) | ast::Tag::SpaceBefore(..) | ast::Tag::SpaceAfter(..) => {
+                    None
+                }
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// when payload is
+///     A _ .. _ -> 0
+///     ...
+///     Q _ .. _ -> n
+fn tag_rank<'a>(arena: &'a Bump, tags: &[(&'a str, usize)], payload: &'a str) -> ast::Expr<'a> {
+    let branches = tags.iter().enumerate().map(|(rank, (name, arity))| {
+        let tag = ast::Pattern::Tag(*name);
+        let pattern = if *arity == 0 {
+            tag
+        } else {
+            let underscores =
+                std::iter::repeat(Loc::at(DERIVED_REGION, ast::Pattern::Underscore("")));
+
+            ast::Pattern::Apply(
+                arena.alloc(Loc::at(DERIVED_REGION, tag)),
+                arena.alloc_slice_fill_iter(underscores.take(*arity)),
+            )
+        };
+
+        &*arena.alloc(ast::WhenBranch {
+            patterns: arena.alloc([Loc::at(DERIVED_REGION, pattern)]),
+            value: Loc::at(
+                DERIVED_REGION,
+                ast::Expr::Num(arena.alloc_str(&rank.to_string())),
+            ),
+            guard: None,
+        })
+    });
+
+    ast::Expr::When(
+        arena.alloc(Loc::at(
+            DERIVED_REGION,
+            ast::Expr::Var {
+                module_name: "",
+                ident: payload,
+            },
+        )),
+        arena.alloc_slice_fill_iter(branches),
+    )
+}
+
+fn default<'a>(env: &mut Env<'a>, at_opaque: &'a str) -> ast::Expr<'a> {
+    let alloc_expr = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+
+    // Default.default {}
+    let call_member = alloc_expr(ast::Expr::Apply(
+        alloc_expr(ast::Expr::Var {
+            module_name: "Default",
+            ident: "default",
+        }),
+        &*env
+            .arena
+            .alloc([&*alloc_expr(ast::Expr::Record(Collection::empty()))]),
+        CalledVia::Space,
+    ));
+
+    // @Opaq (Default.default {})
+    let wrap_opaque = alloc_expr(ast::Expr::Apply(
+        alloc_expr(ast::Expr::OpaqueRef(at_opaque)),
+        &*env.arena.alloc([&*call_member]),
+        CalledVia::Space,
+    ));
+
+    // \{} -> @Opaq (Default.default {})
+    ast::Expr::Closure(
+        env.arena.alloc([Loc::at(
+            DERIVED_REGION,
+            ast::Pattern::RecordDestructure(Collection::empty()),
+        )]),
+        wrap_opaque,
+    )
+}
+
 fn to_inspector<'a>(env: &mut Env<'a>, at_opaque: &'a str) -> ast::Expr<'a> {
     // Inspect for opaques as a tag so it prints `@Opaque payload`.
     let alloc_pat = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
@@ -306,6 +506,7 @@ pub(crate) fn synthesize_member_impl<'a>(
     env: &mut Env<'a>,
     scope: &mut Scope,
     opaque_name: &'a str,
+    opaque_ann: &'a ast::TypeAnnotation<'a>,
     ability_member: Symbol,
 ) -> (Symbol, Loc<Pattern>, &'a Loc<ast::Expr<'a>>) {
     // @Opaq
@@ -319,6 +520,11 @@ pub(crate) fn synthesize_member_impl<'a>(
         Symbol::DECODE_DECODER => (format!("#{opaque_name}_decoder"), decoder(env, at_opaque)),
         Symbol::HASH_HASH => (format!("#{opaque_name}_hash"), hash(env, at_opaque)),
         Symbol::BOOL_IS_EQ => (format!("#{opaque_name}_isEq"), is_eq(env, at_opaque)),
+        Symbol::SORT_COMPARE => (
+            format!("#{opaque_name}_compare"),
+            compare(env, at_opaque, opaque_ann),
+        ),
+        Symbol::DEFAULT_DEFAULT => (format!("#{opaque_name}_default"), default(env, at_opaque)),
         Symbol::INSPECT_TO_INSPECTOR => (
             format!("#{opaque_name}_toInspector"),
             to_inspector(env, at_opaque),
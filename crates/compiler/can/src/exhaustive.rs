@@ -471,21 +471,25 @@ pub fn sketch_when_branches(region: Region, patterns: &[expr::WhenBranch]) -> Sk
     // when x is
     //      #Guard y True -> "foo"
     //      #Guard _ _    -> "bar"
-    let any_has_guard = patterns.iter().any(|branch| branch.guard.is_some());
+    //
+    // A guard that is trivially true, like `if Bool.true`, doesn't refine its pattern at all, so
+    // that branch is checked as if it had no guard.
+    let guard_of = |branch: &WhenBranch| match &branch.guard {
+        Some(guard) if !is_trivially_true(&guard.value) => Guard::HasGuard,
+        _ => Guard::NoGuard,
+    };
+    let any_has_guard = patterns
+        .iter()
+        .any(|branch| matches!(guard_of(branch), Guard::HasGuard));
 
     use SketchedPattern as SP;
-    for WhenBranch {
-        patterns,
-        guard,
-        value: _,
-        redundant,
-    } in patterns
-    {
-        let guard = if guard.is_some() {
-            Guard::HasGuard
-        } else {
-            Guard::NoGuard
-        };
+    for branch in patterns {
+        let guard = guard_of(branch);
+        let WhenBranch {
+            patterns,
+            redundant,
+            ..
+        } = branch;
 
         for loc_pat in patterns {
             // Decompose each pattern in the branch into its own row.
@@ -536,6 +540,12 @@ pub fn sketch_when_branches(region: Region, patterns: &[expr::WhenBranch]) -> Sk
     }
 }
 
+/// Is this `when` guard always true, like `if Bool.true`? Such a guard is checked as if the branch
+/// had no guard, so it must be compiled that way too.
+pub fn is_trivially_true(guard: &expr::Expr) -> bool {
+    matches!(guard, expr::Expr::Var(Symbol::BOOL_TRUE, _))
+}
+
 pub fn sketch_pattern_to_rows(region: Region, pattern: &crate::pattern::Pattern) -> SketchedRows {
     let row = SketchedRow {
         patterns: vec![sketch_pattern(pattern)],
@@ -569,37 +579,43 @@ fn to_nonredundant_rows(
         overall_region,
     } = rows;
     let mut checked_rows = Vec::with_capacity(rows.len());
+    // The rows checked so far that have no guard. A guarded row can only be made redundant by
+    // these, since any earlier guard might be false.
+    let mut unguarded_rows = Vec::with_capacity(rows.len());
 
     let mut redundancies = vec![];
     let mut errors = vec![];
 
-    for (
-        row_number,
-        SketchedRow {
-            patterns,
-            guard,
-            region,
-            redundant_mark,
-        },
-    ) in rows.into_iter().enumerate()
-    {
-        let next_row: Vec<Pattern> = patterns
-            .into_iter()
-            .map(|pattern| pattern.reify(subs, real_var))
-            .collect::<Result<_, _>>()?;
-
-        let redundant_err = if !is_inhabited_row(&next_row) {
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            let patterns: Vec<Pattern> = (row.patterns.into_iter())
+                .map(|pattern| pattern.reify(subs, real_var))
+                .collect::<Result<_, _>>()?;
+
+            Ok((patterns, row.guard, row.region, row.redundant_mark))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (row_number, (next_row, guard, region, redundant_mark)) in rows.iter().enumerate() {
+        let redundant_err = if !is_inhabited_row(next_row) {
             Some(Error::Unmatchable {
                 overall_region,
-                branch_region: region,
+                branch_region: *region,
                 index: HumanIndex::zero_based(row_number),
             })
-        } else if !(matches!(guard, Guard::HasGuard)
-            || is_useful(checked_rows.clone(), next_row.clone()))
-        {
+        } else if !match guard {
+            Guard::NoGuard => is_useful(checked_rows.clone(), next_row.clone()),
+            // The other alternatives of a guarded branch still need its guard, so the branch is
+            // only redundant if none of them can match.
+            Guard::HasGuard => rows
+                .iter()
+                .filter(|(_, _, _, mark)| mark == redundant_mark)
+                .any(|(row, _, _, _)| is_useful(unguarded_rows.clone(), row.clone())),
+        } {
             Some(Error::Redundant {
                 overall_region,
-                branch_region: region,
+                branch_region: *region,
                 index: HumanIndex::zero_based(row_number),
             })
         } else {
@@ -608,10 +624,13 @@ fn to_nonredundant_rows(
 
         match redundant_err {
             None => {
-                checked_rows.push(next_row);
+                if matches!(guard, Guard::NoGuard) {
+                    unguarded_rows.push(next_row.clone());
+                }
+                checked_rows.push(next_row.clone());
             }
             Some(err) => {
-                redundancies.push(redundant_mark);
+                redundancies.push(*redundant_mark);
                 errors.push(err);
             }
         }
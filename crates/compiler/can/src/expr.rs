@@ -1019,19 +1019,19 @@ pub fn canonicalize_expr<'a>(
         ast::Expr::Var { module_name, ident } => {
             canonicalize_var_lookup(env, var_store, scope, module_name, ident, region)
         }
+        ast::Expr::Underscore("") => {
+            // a lone underscore is a typed hole; the type checker reports what belongs there
+            (TypedHole(var_store.fresh()), Output::default())
+        }
         ast::Expr::Underscore(name) => {
-            // we parse underscores, but they are not valid expression syntax
+            // we parse named underscores, but they are not valid expression syntax
 
             let problem = roc_problem::can::RuntimeError::MalformedIdentifier(
                 (*name).into(),
-                if name.is_empty() {
-                    roc_parse::ident::BadIdent::UnderscoreAlone(region.start())
-                } else {
-                    roc_parse::ident::BadIdent::UnderscoreAtStart {
-                        position: region.start(),
-                        // Check if there's an ignored identifier with this name in scope (for better error messages)
-                        declaration_region: scope.lookup_ignored_local(name),
-                    }
+                roc_parse::ident::BadIdent::UnderscoreAtStart {
+                    position: region.start(),
+                    // Check if there's an ignored identifier with this name in scope (for better error messages)
+                    declaration_region: scope.lookup_ignored_local(name),
                 },
                 region,
             );
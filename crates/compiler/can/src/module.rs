@@ -1262,6 +1262,23 @@ impl TypeState {
             solved_implementations,
         } = self;
 
+        Self::serialize_parts(
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+            writer,
+        )
+    }
+
+    /// Serializes a type state that has not been put together into a [TypeState].
+    pub fn serialize_parts(
+        subs: &Subs,
+        exposed_vars_by_symbol: &[(Symbol, Variable)],
+        abilities: &AbilitiesStore,
+        solved_implementations: &ResolvedImplementations,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<usize> {
         let written_subs = subs.serialize(exposed_vars_by_symbol, writer)?;
         let written_ab = abilities.serialize(writer)?;
         let written_solved_impls =
@@ -128,6 +128,7 @@ fn desugar_value_def<'a>(
                 preceding_comment: *preceding_comment,
             }
         }
+        Malformed(text) => Malformed(text),
     }
 }
 
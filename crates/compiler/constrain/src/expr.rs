@@ -1726,12 +1726,15 @@ pub fn constrain_expr(
         }
         TypedHole(var) => {
             // store the expected type for this position
-            constraints.equal_types_var(
+            let store_expected = constraints.equal_types_var(
                 *var,
                 expected,
                 Category::Storage(std::file!(), std::line!()),
                 region,
-            )
+            );
+            let hole = constraints.typed_hole(*var, region);
+
+            constraints.and_constraint([store_expected, hole])
         }
         RuntimeError(_) => {
             // Runtime Errors are always going to crash, so they don't introduce any new
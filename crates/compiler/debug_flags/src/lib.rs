@@ -127,6 +127,10 @@ flags! {
     /// Writes a pretty-printed mono IR to stderr after function specialization.
     ROC_PRINT_IR_AFTER_SPECIALIZATION
 
+    /// Writes a pretty-printed mono IR to stderr after small procs have been inlined and
+    /// constants folded.
+    ROC_PRINT_IR_AFTER_INLINING
+
     /// Writes a pretty-printed mono IR to stderr after insertion of reset/reuse
     /// instructions.
     ROC_PRINT_IR_AFTER_RESET_REUSE
@@ -139,6 +143,10 @@ flags! {
     /// has been applied.
     ROC_PRINT_IR_AFTER_TRMC
 
+    /// Writes a pretty-printed mono IR to stderr after boxes and recursive tags that don't
+    /// escape their proc have been moved to the stack.
+    ROC_PRINT_IR_AFTER_STACK_ALLOC
+
     /// Writes a pretty-printed mono IR to stderr after performing dropspecialization.
     /// Which inlines drop functions to remove pairs of alloc/dealloc instructions of its children.
     ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION
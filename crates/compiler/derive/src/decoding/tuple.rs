@@ -5,7 +5,7 @@ use roc_can::num::{IntBound, IntLitWidth};
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{
@@ -58,6 +58,24 @@ use super::wrap_in_decode_custom_decode_with;
 ///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt
 /// ```
 pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr, Variable) {
+    decoder_help(env, arity, Finish::Tuple)
+}
+
+/// What a tuple-shaped decoder builds out of the elements it decoded.
+pub(super) enum Finish<'a> {
+    /// The tuple `(e0, e1)` itself.
+    Tuple,
+    /// The tag `A e0 e1` of the tag union `union_var`, whose payloads for this tag have the
+    /// types `payload_vars`.
+    Tag {
+        union_var: Variable,
+        tag_name: TagName,
+        payload_vars: &'a [Variable],
+    },
+}
+
+/// Like [decoder], but the finalizer builds `finish` rather than always building a tuple.
+pub(super) fn decoder_help(env: &mut Env, arity: u32, finish: Finish) -> (Expr, Variable) {
     // The decoded type of each index in the tuple, e.g. (a, b).
     let mut index_vars = Vec::with_capacity(arity as _);
     // The type of each index in the decoding state, e.g. {e0: Result a [NoElem], e1: Result b [NoElem]}
@@ -80,6 +98,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
         state_var,
         &state_fields,
         &state_field_vars,
+        finish,
     );
 
     // stepElem = ...
@@ -746,6 +765,7 @@ fn finalizer(
     state_record_var: Variable,
     state_fields: &[Lowercase],
     state_field_vars: &[Variable],
+    finish: Finish,
 ) -> (Expr, Variable, Variable) {
     let state_arg_symbol = env.new_symbol("stateRecord");
     let mut tuple_elems = Vec::with_capacity(index_vars.len());
@@ -769,39 +789,65 @@ fn finalizer(
         tuple_elems.push((index_var, Box::new(Loc::at_zero(index_expr))));
     }
 
-    // The bottom of the happy path - return the decoded tuple (a, b) wrapped with
-    // "Ok".
-    let return_type_var;
-    let mut body = {
-        let subs = &mut env.subs;
-        let tuple_indices_iter = index_vars.iter().copied().enumerate();
-        let flat_type = FlatType::Tuple(
-            TupleElems::insert_into_subs(subs, tuple_indices_iter),
-            Variable::EMPTY_TUPLE,
-        );
-        let done_tuple_var = synth_var(subs, Content::Structure(flat_type));
-        let done_record = Expr::Tuple {
-            tuple_var: done_tuple_var,
-            elems: tuple_elems,
-        };
-
-        return_type_var = {
-            let flat_type = FlatType::TagUnion(
-                UnionTags::for_result(subs, done_tuple_var, decode_err_var),
-                TagExt::Any(Variable::EMPTY_TAG_UNION),
+    // The bottom of the happy path - return the decoded tuple (a, b), or the tag `A a b`,
+    // wrapped with "Ok".
+    let (done_var, done_expr) = match finish {
+        Finish::Tuple => {
+            let subs = &mut env.subs;
+            let tuple_indices_iter = index_vars.iter().copied().enumerate();
+            let flat_type = FlatType::Tuple(
+                TupleElems::insert_into_subs(subs, tuple_indices_iter),
+                Variable::EMPTY_TUPLE,
             );
+            let done_tuple_var = synth_var(subs, Content::Structure(flat_type));
+            let done_record = Expr::Tuple {
+                tuple_var: done_tuple_var,
+                elems: tuple_elems,
+            };
 
-            synth_var(subs, Content::Structure(flat_type))
-        };
+            (done_tuple_var, done_record)
+        }
+        Finish::Tag {
+            union_var,
+            tag_name,
+            payload_vars,
+        } => {
+            debug_assert_eq!(index_vars.len(), payload_vars.len());
+
+            for (&index_var, &payload_var) in index_vars.iter().zip(payload_vars) {
+                env.unify(index_var, payload_var);
+            }
 
-        Expr::Tag {
-            tag_union_var: return_type_var,
-            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-            name: "Ok".into(),
-            arguments: vec![(done_tuple_var, Loc::at_zero(done_record))],
+            let done_tag = Expr::Tag {
+                tag_union_var: union_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: tag_name,
+                arguments: tuple_elems
+                    .into_iter()
+                    .map(|(var, elem)| (var, *elem))
+                    .collect(),
+            };
+
+            (union_var, done_tag)
         }
     };
 
+    let return_type_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, done_var, decode_err_var),
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let mut body = Expr::Tag {
+        tag_union_var: return_type_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Ok".into(),
+        arguments: vec![(done_var, Loc::at_zero(done_expr))],
+    };
+
     // Unwrap each result in the decoded state
     //
     // when rec.e0 is
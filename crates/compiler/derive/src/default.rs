@@ -0,0 +1,185 @@
+//! Derivers for the `Default` ability.
+
+use std::iter::once;
+
+use roc_can::{
+    expr::{AnnotatedMark, ClosureData, Expr, Field, Recursive},
+    pattern::Pattern,
+};
+use roc_collections::SendMap;
+use roc_derive_key::default::FlatDefaultKey;
+use roc_module::{called_via::CalledVia, ident::Lowercase, symbol::Symbol};
+use roc_region::all::{Loc, Region};
+use roc_types::{
+    subs::{
+        Content, FlatType, LambdaSet, OptVariable, RecordFields, SubsSlice, UnionLambdas, Variable,
+        VariableSubsSlice,
+    },
+    types::RecordField,
+};
+
+use crate::{synth_var, util::Env, DerivedBody};
+
+pub(crate) fn derive_default(
+    env: &mut Env<'_>,
+    key: FlatDefaultKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = match key {
+        FlatDefaultKey::Record(fields) => default_record(env, def_symbol, fields),
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::DEFAULT_DEFAULT);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn default_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Variable, Expr) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, the default value of this record is
+    //
+    // default_rcd : {} -> { f1: t1, ..., fn: tn }
+    // default_rcd = \{} -> { f1: Default.default {}, ..., fn: Default.default {} }
+    let mut default_fields = SendMap::default();
+    for (field_name, field_var, _) in record_fields.iter_all() {
+        let field_name = env.subs[field_name].clone();
+        let field_var = env.subs[field_var];
+
+        let field = Field {
+            var: field_var,
+            region: Region::zero(),
+            loc_expr: Box::new(Loc::at_zero(call_default(env, field_var))),
+        };
+
+        default_fields.insert(field_name, field);
+    }
+
+    let body = Expr::Record {
+        record_var,
+        fields: default_fields,
+    };
+
+    build_outer_derived_closure(env, fn_name, (record_var, body))
+}
+
+/// Build a `Default.default {}` call that produces a value of type `val_var`.
+fn call_default(env: &mut Env<'_>, val_var: Variable) -> Expr {
+    // build `Default.default` function type.
+    //
+    // {} -[uls]-> a where a implements Default
+    let exposed_default_fn_var = env.import_builtin_symbol_var(Symbol::DEFAULT_DEFAULT);
+
+    // {} -[clos]-> (typeof val)
+    let this_arguments_slice =
+        VariableSubsSlice::insert_into_subs(env.subs, [Variable::EMPTY_RECORD]);
+    let this_default_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_default_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_default_clos_var,
+            val_var,
+        )),
+    );
+
+    //   {} -[uls]->  a where a implements Default
+    // ~ {} -[clos]-> (typeof val)
+    env.unify(exposed_default_fn_var, this_default_fn_var);
+
+    let default_fn_head = Expr::AbilityMember(Symbol::DEFAULT_DEFAULT, None, this_default_fn_var);
+    let default_fn_data = Box::new((
+        this_default_fn_var,
+        Loc::at_zero(default_fn_head),
+        this_default_clos_var,
+        val_var,
+    ));
+
+    let default_arguments = vec![(Variable::EMPTY_RECORD, Loc::at_zero(Expr::EmptyRecord))];
+
+    Expr::Call(default_fn_data, default_arguments, CalledVia::Space)
+}
+
+/// Wraps `body` in `\{} -> body`.
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // {} -[fn_name]-> body_var
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [Variable::EMPTY_RECORD]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let empty_record_pattern = Pattern::RecordDestructure {
+        whole_var: Variable::EMPTY_RECORD,
+        ext_var: Variable::EMPTY_RECORD,
+        destructs: vec![],
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            Variable::EMPTY_RECORD,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(empty_record_pattern),
+        )],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
@@ -17,9 +17,11 @@ use roc_types::subs::{
 use util::Env;
 
 mod decoding;
+mod default;
 mod encoding;
 mod hash;
 mod inspect;
+mod sort;
 mod util;
 
 pub(crate) const DERIVED_SYNTH: ModuleId = ModuleId::DERIVED_SYNTH;
@@ -79,6 +81,12 @@ fn build_derived_body(
             decoding::derive_decoder(&mut env, decoder_key, derived_symbol)
         }
         DeriveKey::Hash(hash_key) => hash::derive_hash(&mut env, hash_key, derived_symbol),
+        DeriveKey::Compare(compare_key) => {
+            sort::derive_compare(&mut env, compare_key, derived_symbol)
+        }
+        DeriveKey::Default(default_key) => {
+            default::derive_default(&mut env, default_key, derived_symbol)
+        }
         DeriveKey::ToInspector(to_inspector_key) => {
             inspect::derive_to_inspector(&mut env, to_inspector_key, derived_symbol)
         }
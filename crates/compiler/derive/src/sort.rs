@@ -0,0 +1,653 @@
+//! Derivers for the `Sort` ability.
+
+use std::iter::once;
+
+use roc_can::{
+    expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern},
+    pattern::Pattern,
+};
+use roc_derive_key::sort::FlatSortKey;
+use roc_module::{
+    called_via::CalledVia,
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_region::all::{Loc, Region};
+use roc_types::{
+    subs::{
+        Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
+        RedundantMark, SubsIndex, SubsSlice, TagExt, TupleElems, UnionLambdas, UnionTags, Variable,
+        VariableSubsSlice,
+    },
+    types::RecordField,
+};
+
+use crate::{
+    synth_var,
+    util::{Env, ExtensionKind},
+    DerivedBody,
+};
+
+pub(crate) fn derive_compare(
+    env: &mut Env<'_>,
+    key: FlatSortKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = match key {
+        FlatSortKey::Record(fields) => compare_record(env, def_symbol, fields),
+        FlatSortKey::Tuple(arity) => compare_tuple(env, def_symbol, arity),
+        FlatSortKey::TagUnion(tags) => {
+            if tags.len() == 1 {
+                compare_newtype_tag_union(env, def_symbol, tags.into_iter().next().unwrap())
+            } else {
+                compare_tag_union(env, def_symbol, tags)
+            }
+        }
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::SORT_COMPARE);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn compare_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Variable, Expr) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, a comparator for this record is
+    //
+    // compare_rcd : { f1: t1, ..., fn: tn }, { f1: t1, ..., fn: tn } -> [LT, EQ, GT]
+    // compare_rcd = \rcd1, rcd2 ->
+    //   when Sort.compare rcd1.f1 rcd2.f1 is
+    //     EQ ->
+    //       ...
+    //         Sort.compare rcd1.fn rcd2.fn
+    //     order -> order
+    //
+    // where the fields are compared in alphabetical order.
+    let rcd1_sym = env.new_symbol("rcd1");
+    let rcd2_sym = env.new_symbol("rcd2");
+
+    let comparisons: Vec<_> = record_fields
+        .iter_all()
+        .map(|(field_name, field_var, _)| {
+            let field_name = env.subs[field_name].clone();
+            let field_var = env.subs[field_var];
+
+            let mut field_access = |rcd_sym| Expr::RecordAccess {
+                record_var,
+                field_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    rcd_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                field: field_name.clone(),
+            };
+
+            let lhs = field_access(rcd1_sym);
+            let rhs = field_access(rcd2_sym);
+
+            (field_var, lhs, rhs)
+        })
+        .collect();
+
+    let body = compare_lexicographic(env, comparisons);
+
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        record_var,
+        (Pattern::Identifier(rcd1_sym), Pattern::Identifier(rcd2_sym)),
+        body,
+    )
+}
+
+fn compare_tuple(env: &mut Env<'_>, fn_name: Symbol, arity: u32) -> (Variable, Expr) {
+    // Suppose tup = (v1, ..., vn).
+    // Build a generalized type t_tup = (t1, ..., tn), with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many tuples of the same arity.
+    let (tuple_var, tuple_elems) = {
+        let flex_elems: Vec<_> = (0..arity)
+            .map(|i| (i as usize, env.subs.fresh_unnamed_flex_var()))
+            .collect();
+        let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+        let tuple_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+        );
+
+        (tuple_var, elems)
+    };
+
+    // Now, a comparator for this tuple is
+    //
+    // compare_tup : (t1, ..., tn), (t1, ..., tn) -> [LT, EQ, GT]
+    // compare_tup = \tup1, tup2 ->
+    //   when Sort.compare tup1.0 tup2.0 is
+    //     EQ ->
+    //       ...
+    //         Sort.compare tup1.n tup2.n
+    //     order -> order
+    let tup1_sym = env.new_symbol("tup1");
+    let tup2_sym = env.new_symbol("tup2");
+
+    let comparisons: Vec<_> = tuple_elems
+        .iter_all()
+        .map(|(elem_idx, elem_var)| {
+            let index = env.subs[elem_idx];
+            let elem_var = env.subs[elem_var];
+
+            let mut elem_access = |tup_sym| Expr::TupleAccess {
+                tuple_var,
+                elem_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    tup_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                index,
+            };
+
+            let lhs = elem_access(tup1_sym);
+            let rhs = elem_access(tup2_sym);
+
+            (elem_var, lhs, rhs)
+        })
+        .collect();
+
+    let body = compare_lexicographic(env, comparisons);
+
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        tuple_var,
+        (Pattern::Identifier(tup1_sym), Pattern::Identifier(tup2_sym)),
+        body,
+    )
+}
+
+/// Build a `compare` implementation for a non-singleton tag union.
+fn compare_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Variable, Expr) {
+    // Suppose tags = [ A p11 .. p1n, ..., Q pq1 .. pqm ]
+    // Build a generalized type t_tags = [ A t11 .. t1n, ..., Q tq1 .. tqm ],
+    // with fresh t1, ..., tqm, so that we can re-use the derived impl for many
+    // unions of the same tags and payloads.
+    let (union_var, union_tags) = {
+        let flex_tag_labels = tags
+            .into_iter()
+            .map(|(label, arity)| {
+                let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+                for var_index in variables_slice {
+                    env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+                }
+                (label, variables_slice)
+            })
+            .collect::<Vec<_>>();
+        let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels);
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        );
+
+        (tag_union_var, union_tags)
+    };
+
+    // Now, a comparator for this tag union is
+    //
+    // compare_union : [ A t11 .. t1n, ..., Q tq1 .. tqm ], [ A t11 .. t1n, ..., Q tq1 .. tqm ] -> [LT, EQ, GT]
+    // compare_union = \union1, union2 ->
+    //   when union1 is
+    //     A x11 .. x1n ->
+    //       when union2 is
+    //         A y11 .. y1n -> <compare x11 .. x1n against y11 .. y1n, like a tuple>
+    //         B _ .. _ -> LT
+    //         ...
+    //         Q _ .. _ -> LT
+    //     ...
+    //
+    // where the tags are in alphabetical order, so earlier tags come before later ones. A
+    // structural tag union has no other order; the derived impls of opaque types rank their tags
+    // as declared before they get here.
+    let union1_sym = env.new_symbol("union1");
+    let union2_sym = env.new_symbol("union2");
+
+    let tags: Vec<_> = union_tags
+        .iter_all()
+        .map(|(tag, payloads)| {
+            let tag_name = env.subs[tag].clone();
+            let payload_vars = env.subs.get_subs_slice(env.subs[payloads]).to_vec();
+            (tag_name, payload_vars)
+        })
+        .collect();
+
+    let order_var = ordering_var(env);
+
+    let outer_branches = (tags.iter().enumerate())
+        .map(|(outer_n, (outer_tag, outer_payload_vars))| {
+            // x11 .. x1n
+            let outer_payload_syms = payload_symbols(env, outer_payload_vars.len());
+
+            let inner_branches = (tags.iter().enumerate())
+                .map(|(inner_n, (inner_tag, inner_payload_vars))| {
+                    let (pattern, value) = if inner_n == outer_n {
+                        // y11 .. y1n
+                        let inner_payload_syms = payload_symbols(env, inner_payload_vars.len());
+
+                        let comparisons = (inner_payload_vars.iter())
+                            .zip(outer_payload_syms.iter().zip(inner_payload_syms.iter()))
+                            .map(|(var, (x, y))| (*var, Expr::Var(*x, *var), Expr::Var(*y, *var)))
+                            .collect();
+
+                        let (body_var, body) = compare_lexicographic(env, comparisons);
+                        env.unify(order_var, body_var);
+
+                        let pattern = tag_pattern(
+                            union_var,
+                            inner_tag.clone(),
+                            inner_payload_vars,
+                            inner_payload_syms.into_iter().map(Pattern::Identifier),
+                        );
+
+                        (pattern, body)
+                    } else {
+                        let order = if outer_n < inner_n { "LT" } else { "GT" };
+
+                        let pattern = tag_pattern(
+                            union_var,
+                            inner_tag.clone(),
+                            inner_payload_vars,
+                            std::iter::repeat(Pattern::Underscore),
+                        );
+
+                        (pattern, ordering_tag(env, order_var, order))
+                    };
+
+                    when_branch(pattern, value)
+                })
+                .collect();
+
+            // when union2 is ...
+            let inner_when = Expr::When {
+                loc_cond: Box::new(Loc::at_zero(Expr::Var(union2_sym, union_var))),
+                cond_var: union_var,
+                expr_var: order_var,
+                region: Region::zero(),
+                branches: inner_branches,
+                branches_cond_var: union_var,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            };
+
+            let pattern = tag_pattern(
+                union_var,
+                outer_tag.clone(),
+                outer_payload_vars,
+                outer_payload_syms.into_iter().map(Pattern::Identifier),
+            );
+
+            when_branch(pattern, inner_when)
+        })
+        .collect();
+
+    // when union1 is ...
+    let outer_when = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(union1_sym, union_var))),
+        cond_var: union_var,
+        expr_var: order_var,
+        region: Region::zero(),
+        branches: outer_branches,
+        branches_cond_var: union_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        union_var,
+        (
+            Pattern::Identifier(union1_sym),
+            Pattern::Identifier(union2_sym),
+        ),
+        (order_var, outer_when),
+    )
+}
+
+/// Build a `compare` implementation for a newtype (singleton) tag union.
+/// If a tag union is a newtype, we only need to compare its payloads.
+fn compare_newtype_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tag: (TagName, u16),
+) -> (Variable, Expr) {
+    // Suppose tags = [ A p1 .. pn ]
+    // Build a generalized type t_tags = [ A t1 .. tn ],
+    // with fresh t1, ..., tn, so that we can re-use the derived impl for many
+    // unions of the same tag and payload arity.
+    let (union_var, tag_name, payload_vars) = {
+        let (label, arity) = tag;
+
+        let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+        for var_index in variables_slice {
+            env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+        }
+
+        let variables_slices_slice =
+            SubsSlice::extend_new(&mut env.subs.variable_slices, [variables_slice]);
+        let tag_name_index = SubsIndex::push_new(&mut env.subs.tag_names, label.clone());
+
+        let union_tags = UnionTags::from_slices(tag_name_index.as_slice(), variables_slices_slice);
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        );
+
+        (
+            tag_union_var,
+            label,
+            env.subs.get_subs_slice(variables_slice).to_vec(),
+        )
+    };
+
+    // Now, a comparator for this tag union is
+    //
+    // compare_union : [ A t1 .. tn ], [ A t1 .. tn ] -> [LT, EQ, GT]
+    // compare_union = \A x1 .. xn, A y1 .. yn ->
+    //   <compare x1 .. xn against y1 .. yn, like a tuple>
+    let payload1_syms = payload_symbols(env, payload_vars.len());
+    let payload2_syms = payload_symbols(env, payload_vars.len());
+
+    let comparisons = (payload_vars.iter())
+        .zip(payload1_syms.iter().zip(payload2_syms.iter()))
+        .map(|(var, (x, y))| (*var, Expr::Var(*x, *var), Expr::Var(*y, *var)))
+        .collect();
+
+    let body = compare_lexicographic(env, comparisons);
+
+    let pattern1 = tag_pattern(
+        union_var,
+        tag_name.clone(),
+        &payload_vars,
+        payload1_syms.into_iter().map(Pattern::Identifier),
+    );
+    let pattern2 = tag_pattern(
+        union_var,
+        tag_name,
+        &payload_vars,
+        payload2_syms.into_iter().map(Pattern::Identifier),
+    );
+
+    build_outer_derived_closure(env, fn_name, union_var, (pattern1, pattern2), body)
+}
+
+/// Compares each pair of values in turn, stopping at the first pair that is not equal.
+/// With no pairs to compare, the result is `EQ`.
+fn compare_lexicographic(
+    env: &mut Env<'_>,
+    comparisons: Vec<(Variable, Expr, Expr)>,
+) -> (Variable, Expr) {
+    let mut calls: Vec<_> = comparisons
+        .into_iter()
+        .map(|(var, lhs, rhs)| call_sort_compare(env, (var, lhs), (var, rhs)))
+        .collect();
+
+    match calls.pop() {
+        Some(last) => calls
+            .into_iter()
+            .rev()
+            .fold(last, |rest, first| then_compare(env, first, rest)),
+        None => {
+            let order_var = ordering_var(env);
+            (order_var, ordering_tag(env, order_var, "EQ"))
+        }
+    }
+}
+
+/// when first is
+///   EQ -> rest
+///   order -> order
+fn then_compare(
+    env: &mut Env<'_>,
+    first: (Variable, Expr),
+    rest: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (first_var, first_expr) = first;
+    let (rest_var, rest_expr) = rest;
+
+    env.unify(first_var, rest_var);
+
+    let order_sym = env.new_symbol("order");
+
+    let eq_pattern = Pattern::AppliedTag {
+        whole_var: first_var,
+        tag_name: "EQ".into(),
+        ext_var: Variable::EMPTY_TAG_UNION,
+        arguments: vec![],
+    };
+
+    let branches = vec![
+        when_branch(eq_pattern, rest_expr),
+        when_branch(
+            Pattern::Identifier(order_sym),
+            Expr::Var(order_sym, first_var),
+        ),
+    ];
+
+    let when_expr = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(first_expr)),
+        cond_var: first_var,
+        expr_var: first_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: first_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    (first_var, when_expr)
+}
+
+fn call_sort_compare(
+    env: &mut Env<'_>,
+    lhs: (Variable, Expr),
+    rhs: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (lhs_var, lhs_expr) = lhs;
+    let (rhs_var, rhs_expr) = rhs;
+
+    // build `Sort.compare ...` function type.
+    //
+    // a, a -[uls]-> [LT, EQ, GT] where a implements Sort
+    let exposed_compare_fn_var = env.import_builtin_symbol_var(Symbol::SORT_COMPARE);
+
+    // (typeof lhs), (typeof rhs) -[clos]-> order
+    let this_arguments_slice = VariableSubsSlice::insert_into_subs(env.subs, [lhs_var, rhs_var]);
+    let this_compare_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_order_var = env.subs.fresh_unnamed_flex_var();
+    let this_compare_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_compare_clos_var,
+            this_order_var,
+        )),
+    );
+
+    //   a,           a            -[uls]->  [LT, EQ, GT] where a implements Sort
+    // ~ (typeof lhs), (typeof rhs) -[clos]-> order
+    env.unify(exposed_compare_fn_var, this_compare_fn_var);
+
+    let compare_fn_head = Expr::AbilityMember(Symbol::SORT_COMPARE, None, this_compare_fn_var);
+    let compare_fn_data = Box::new((
+        this_compare_fn_var,
+        Loc::at_zero(compare_fn_head),
+        this_compare_clos_var,
+        this_order_var,
+    ));
+
+    let compare_arguments = vec![
+        (lhs_var, Loc::at_zero(lhs_expr)),
+        (rhs_var, Loc::at_zero(rhs_expr)),
+    ];
+    let call_compare = Expr::Call(compare_fn_data, compare_arguments, CalledVia::Space);
+
+    (this_order_var, call_compare)
+}
+
+/// [LT, EQ, GT]
+fn ordering_var(env: &mut Env<'_>) -> Variable {
+    let tags = UnionTags::insert_into_subs::<_, Vec<Variable>>(
+        env.subs,
+        [
+            (TagName("EQ".into()), vec![]),
+            (TagName("GT".into()), vec![]),
+            (TagName("LT".into()), vec![]),
+        ],
+    );
+
+    synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    )
+}
+
+fn ordering_tag(env: &mut Env<'_>, order_var: Variable, name: &str) -> Expr {
+    Expr::Tag {
+        tag_union_var: order_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: name.into(),
+        arguments: vec![],
+    }
+}
+
+fn payload_symbols(env: &mut Env<'_>, count: usize) -> Vec<Symbol> {
+    std::iter::repeat_with(|| env.unique_symbol())
+        .take(count)
+        .collect()
+}
+
+fn tag_pattern(
+    union_var: Variable,
+    tag_name: TagName,
+    payload_vars: &[Variable],
+    payload_patterns: impl Iterator<Item = Pattern>,
+) -> Pattern {
+    Pattern::AppliedTag {
+        whole_var: union_var,
+        tag_name,
+        ext_var: Variable::EMPTY_TAG_UNION,
+        arguments: (payload_vars.iter())
+            .zip(payload_patterns)
+            .map(|(var, pattern)| (*var, Loc::at_zero(pattern)))
+            .collect(),
+    }
+}
+
+fn when_branch(pattern: Pattern, value: Expr) -> WhenBranch {
+    WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(pattern),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(value),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    }
+}
+
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    val_var: Variable,
+    patterns: (Pattern, Pattern),
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (pattern1, pattern2) = patterns;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // val, val -[fn_name]-> (order = body_var)
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [val_var, val_var]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(pattern1),
+            ),
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(pattern2),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
@@ -0,0 +1,135 @@
+use roc_module::{ident::Lowercase, symbol::Symbol};
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+
+use crate::{
+    util::{check_derivable_ext_var, debug_name_record},
+    DeriveError,
+};
+
+#[derive(Hash)]
+pub enum FlatDefault {
+    // `default` is always of form `{} -> a` where `a` is concrete, so all immediates must have
+    // exactly one lambda set!
+    SingleLambdaSetImmediate(Symbol),
+    Key(FlatDefaultKey),
+}
+
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub enum FlatDefaultKey {
+    // Unfortunate that we must allocate here, c'est la vie
+    Record(Vec<Lowercase>),
+}
+
+impl FlatDefaultKey {
+    pub(crate) fn debug_name(&self) -> String {
+        match self {
+            FlatDefaultKey::Record(fields) => debug_name_record(fields),
+        }
+    }
+}
+
+impl FlatDefault {
+    pub(crate) fn from_var(subs: &Subs, var: Variable) -> Result<FlatDefault, DeriveError> {
+        use DeriveError::*;
+        use FlatDefault::*;
+        match *subs.get_content_without_compacting(var) {
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::Apply(sym, _) => match sym {
+                    Symbol::LIST_LIST => Ok(SingleLambdaSetImmediate(Symbol::DEFAULT_DEFAULT_LIST)),
+                    Symbol::STR_STR => Ok(SingleLambdaSetImmediate(Symbol::DEFAULT_DEFAULT_STR)),
+                    _ => Err(Underivable),
+                },
+                FlatType::Record(fields, ext) => {
+                    let (fields_iter, ext) = fields.unsorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyRecord))
+                    })?;
+
+                    let mut field_names = Vec::with_capacity(fields.len());
+                    for (field_name, record_field) in fields_iter {
+                        if record_field.is_optional() {
+                            // Optional fields are compile-time-polymorphic, so there is no
+                            // concrete field to fill in
+                            return Err(Underivable);
+                        }
+                        field_names.push(field_name.clone());
+                    }
+
+                    field_names.sort();
+
+                    Ok(Key(FlatDefaultKey::Record(field_names)))
+                }
+                FlatType::EmptyRecord => Ok(Key(FlatDefaultKey::Record(vec![]))),
+                // Only records have a derived default value; there is no obvious choice of tag
+                // for a tag union.
+                FlatType::Tuple(..)
+                | FlatType::TagUnion(..)
+                | FlatType::RecursiveTagUnion(..)
+                | FlatType::FunctionOrTagUnion(..)
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion
+                | FlatType::Func(..) => Err(Underivable),
+            },
+            Content::Alias(sym, _, real_var, _) => match builtin_symbol_to_default_lambda(sym) {
+                Some(lambda) => Ok(lambda),
+                // Like `Hash`, opaques are unwrapped here because derivers are only used by the
+                // backend, which treats opaques like structural aliases.
+                None => Self::from_var(subs, real_var),
+            },
+            Content::RangedNumber(_) => {
+                // Every number has the same default, regardless of its width.
+                Ok(SingleLambdaSetImmediate(Symbol::DEFAULT_DEFAULT_NUM))
+            }
+            //
+            Content::RecursionVar { structure, .. } => Self::from_var(subs, structure),
+            //
+            Content::Error => Err(Underivable),
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(_, _)
+            | Content::RigidAbleVar(_, _) => Err(UnboundVar),
+            Content::LambdaSet(_) | Content::ErasedLambda => Err(Underivable),
+        }
+    }
+
+    pub fn from_builtin_symbol(symbol: Symbol) -> Result<FlatDefault, DeriveError> {
+        builtin_symbol_to_default_lambda(symbol).ok_or(DeriveError::Underivable)
+    }
+}
+
+const fn builtin_symbol_to_default_lambda(symbol: Symbol) -> Option<FlatDefault> {
+    use FlatDefault::*;
+    match symbol {
+        Symbol::BOOL_BOOL => Some(SingleLambdaSetImmediate(Symbol::DEFAULT_DEFAULT_BOOL)),
+        Symbol::NUM_U8
+        | Symbol::NUM_UNSIGNED8
+        | Symbol::NUM_U16
+        | Symbol::NUM_UNSIGNED16
+        | Symbol::NUM_U32
+        | Symbol::NUM_UNSIGNED32
+        | Symbol::NUM_U64
+        | Symbol::NUM_UNSIGNED64
+        | Symbol::NUM_U128
+        | Symbol::NUM_UNSIGNED128
+        | Symbol::NUM_I8
+        | Symbol::NUM_SIGNED8
+        | Symbol::NUM_I16
+        | Symbol::NUM_SIGNED16
+        | Symbol::NUM_I32
+        | Symbol::NUM_SIGNED32
+        | Symbol::NUM_I64
+        | Symbol::NUM_SIGNED64
+        | Symbol::NUM_I128
+        | Symbol::NUM_SIGNED128
+        | Symbol::NUM_NAT
+        | Symbol::NUM_NATURAL
+        | Symbol::NUM_DEC
+        | Symbol::NUM_DECIMAL
+        | Symbol::NUM_F32
+        | Symbol::NUM_BINARY32
+        | Symbol::NUM_F64
+        | Symbol::NUM_BINARY64 => Some(SingleLambdaSetImmediate(Symbol::DEFAULT_DEFAULT_NUM)),
+        _ => None,
+    }
+}
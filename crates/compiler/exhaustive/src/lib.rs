@@ -197,12 +197,49 @@ pub fn check(
         // if this debug_assert! ever fails, the theory is disproven
         debug_assert!(bad_patterns.iter().map(|v| v.len()).sum::<usize>() == bad_patterns.len());
         let heads = bad_patterns.into_iter().map(|mut v| v.remove(0)).collect();
-        errors.push(Error::Incomplete(region, context, heads));
+        errors.push(Error::Incomplete(
+            region,
+            context,
+            minimize_witnesses(heads),
+        ));
         return Err(errors);
     }
     Ok(())
 }
 
+/// Removes missing patterns that are duplicates of, or more specific than, another missing
+/// pattern, so that every pattern left describes values none of the others do.
+fn minimize_witnesses(witnesses: Vec<Pattern>) -> Vec<Pattern> {
+    let mut minimal: Vec<Pattern> = Vec::with_capacity(witnesses.len());
+
+    for witness in witnesses {
+        if minimal.iter().any(|kept| covers(kept, &witness)) {
+            continue;
+        }
+
+        minimal.retain(|kept| !covers(&witness, kept));
+        minimal.push(witness);
+    }
+
+    minimal
+}
+
+/// Does every value matched by `specific` also match `general`?
+fn covers(general: &Pattern, specific: &Pattern) -> bool {
+    match (general, specific) {
+        (Anything, _) => true,
+        (_, Anything) => false,
+        (Literal(l1), Literal(l2)) => l1 == l2,
+        (Ctor(_, id1, args1), Ctor(_, id2, args2)) => {
+            id1 == id2 && args1.iter().zip(args2).all(|(g, s)| covers(g, s))
+        }
+        (List(arity1, args1), List(arity2, args2)) => {
+            arity1 == arity2 && args1.iter().zip(args2).all(|(g, s)| covers(g, s))
+        }
+        _ => false,
+    }
+}
+
 /// EXHAUSTIVE PATTERNS
 
 /// INVARIANTS:
@@ -242,6 +279,21 @@ fn is_exhaustive(matrix: &RefPatternMatrix, n: usize) -> PatternMatrix {
             let alt_list = &alts.alternatives;
             let num_alts = alt_list.len();
 
+            let is_alt_exhaustive = |Ctor { arity, tag_id, .. }| {
+                let new_matrix: Vec<_> = matrix
+                    .iter()
+                    .filter_map(|r| specialize_row_by_ctor(tag_id, arity, r.to_owned()))
+                    .collect();
+                let rest: Vec<Vec<Pattern>> = is_exhaustive(&new_matrix, arity + n - 1);
+
+                let mut result = Vec::with_capacity(rest.len());
+                for row in rest {
+                    result.push(recover_ctor(alts.clone(), tag_id, arity, row));
+                }
+
+                result
+            };
+
             if num_seen < num_alts {
                 let new_matrix: Vec<_> = matrix
                     .iter()
@@ -249,37 +301,28 @@ fn is_exhaustive(matrix: &RefPatternMatrix, n: usize) -> PatternMatrix {
                     .collect();
                 let rest: Vec<Vec<Pattern>> = is_exhaustive(&new_matrix, n - 1);
 
-                let last = alt_list
-                    .iter()
-                    .filter_map(|r| is_missing(alts.clone(), &ctors, r));
-
                 let mut result = Vec::new();
 
-                for last_option in last {
-                    for mut row in rest.clone() {
-                        row.push(last_option.clone());
+                for alt in alt_list {
+                    match is_missing(alts.clone(), &ctors, alt) {
+                        Some(missing) => {
+                            for mut row in rest.clone() {
+                                row.push(missing.clone());
 
-                        result.push(row);
+                                result.push(row);
+                            }
+                        }
+                        None => {
+                            // A constructor that is matched can still be missing some of its
+                            // arguments, so look for those too to report every missing pattern
+                            // at once.
+                            result.extend(is_alt_exhaustive(alt.clone()));
+                        }
                     }
                 }
 
                 result
             } else {
-                let is_alt_exhaustive = |Ctor { arity, tag_id, .. }| {
-                    let new_matrix: Vec<_> = matrix
-                        .iter()
-                        .filter_map(|r| specialize_row_by_ctor(tag_id, arity, r.to_owned()))
-                        .collect();
-                    let rest: Vec<Vec<Pattern>> = is_exhaustive(&new_matrix, arity + n - 1);
-
-                    let mut result = Vec::with_capacity(rest.len());
-                    for row in rest {
-                        result.push(recover_ctor(alts.clone(), tag_id, arity, row));
-                    }
-
-                    result
-                };
-
                 alt_list
                     .iter()
                     .cloned()
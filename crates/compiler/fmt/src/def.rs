@@ -196,6 +196,7 @@ impl<'a> Formattable for ValueDef<'a> {
             Expect { condition, .. } => condition.is_multiline(),
             ExpectFx { condition, .. } => condition.is_multiline(),
             Dbg { condition, .. } => condition.is_multiline(),
+            Malformed(text) => text.contains('\n'),
         }
     }
 
@@ -220,6 +221,11 @@ impl<'a> Formattable for ValueDef<'a> {
             ExpectFx { condition, .. } => {
                 fmt_expect_fx(buf, condition, self.is_multiline(), indent)
             }
+            Malformed(text) => {
+                // The formatter never recovers from parse errors, but keep the text as it was
+                buf.indent(indent);
+                buf.push_str_allow_spaces(text);
+            }
             AnnotatedBody {
                 ann_pattern,
                 ann_type,
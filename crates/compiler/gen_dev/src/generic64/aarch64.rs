@@ -1370,6 +1370,22 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         });
     }
 
+    fn local_data_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        data: std::vec::Vec<u8>,
+        dst: AArch64GeneralReg,
+    ) {
+        // like `data_pointer`, an `adrp` and an `add` that are both relocated
+        buf.extend((0x9000_0000u32 | dst.id() as u32).to_le_bytes());
+        Self::add_reg64_reg64_imm32(buf, dst, dst, 0);
+
+        relocs.push(Relocation::LocalData {
+            offset: buf.len() as u64 - 8,
+            data,
+        });
+    }
+
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
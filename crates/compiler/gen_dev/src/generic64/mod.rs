@@ -3,14 +3,14 @@ use crate::{
     single_register_integers, Backend, Env, Relocation,
 };
 use bumpalo::collections::{CollectIn, Vec};
-use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
+use roc_builtins::bitcode::{self, FloatWidth, IntWidth, RcEvent};
 use roc_collections::all::MutMap;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::{CallerProc, CodeGenHelp, HelperOp};
 use roc_mono::ir::{
-    BranchInfo, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout,
-    SelfRecursive, Stmt,
+    static_list_bytes, BranchInfo, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal,
+    Param, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
@@ -259,6 +259,14 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         dst: GeneralReg,
     );
 
+    /// Loads the address of data that is emitted along with the current proc.
+    fn local_data_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        data: std::vec::Vec<u8>,
+        dst: GeneralReg,
+    );
+
     /// Jumps by an offset of offset bytes unconditionally.
     /// It should always generate the same number of bytes to enable replacement if offset changes.
     /// It returns the base offset to calculate the jump from (generally the instruction after the jump).
@@ -856,6 +864,9 @@ impl<
     fn target_info(&self) -> TargetInfo {
         self.storage_manager.target_info
     }
+    fn proc_name(&self) -> &str {
+        self.proc_name.as_deref().unwrap()
+    }
     fn module_interns_helpers_mut(
         &mut self,
     ) -> (
@@ -1074,6 +1085,12 @@ impl<
         ASM::data_pointer(&mut self.buf, &mut self.relocs, data_name, reg);
     }
 
+    fn build_local_data_pointer(&mut self, dst: &Symbol, data: std::vec::Vec<u8>) {
+        let reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+
+        ASM::local_data_pointer(&mut self.buf, &mut self.relocs, data, reg);
+    }
+
     fn build_fn_call(
         &mut self,
         dst: &Symbol,
@@ -3385,6 +3402,33 @@ impl<
         let element_width = self.layout_interner.stack_size(*element_in_layout) as u64;
         let element_alignment = self.layout_interner.alignment_bytes(*element_in_layout) as u64;
 
+        // A list of literals, like a constant that was evaluated at compile time, is static data
+        if let Some(bytes) = static_list_bytes(elements, element_layout) {
+            // The elements are preceded by a refcount of zero, which marks them as constant
+            let refcount_bytes = Ord::max(8, element_alignment);
+            let mut data = vec![0; refcount_bytes as usize];
+            data.extend(bytes);
+
+            let elements_symbol = self.debug_symbol("static_list_elements");
+            let ptr_reg = self
+                .storage_manager
+                .claim_general_reg(&mut self.buf, &elements_symbol);
+            ASM::local_data_pointer(&mut self.buf, &mut self.relocs, data, ptr_reg);
+            ASM::add_reg64_reg64_imm32(&mut self.buf, ptr_reg, ptr_reg, refcount_bytes as i32);
+
+            self.store_list(sym, ptr_reg, element_alignment, elements.len());
+            self.free_symbol(&elements_symbol);
+
+            return;
+        }
+
+        if self.env.instrument_rc {
+            let list_layout = self
+                .layout_interner
+                .insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(*element_in_layout)));
+            self.record_rc_event(RcEvent::Alloc, 1, list_layout);
+        }
+
         // load the total size of the data we want to store (excludes refcount)
         let data_bytes_symbol = self.debug_symbol("data_bytes");
         let data_bytes = element_width * elements.len() as u64;
@@ -3464,20 +3508,7 @@ impl<
             }
         }
 
-        // Setup list on stack.
-        self.storage_manager.with_tmp_general_reg(
-            &mut self.buf,
-            |storage_manager, buf, tmp_reg| {
-                let alignment = Ord::max(8, element_alignment) as u32;
-                let base_offset =
-                    storage_manager.claim_stack_area_with_alignment(*sym, 24, alignment);
-                ASM::mov_base32_reg64(buf, base_offset, ptr_reg);
-
-                ASM::mov_reg64_imm64(buf, tmp_reg, elements.len() as i64);
-                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
-                ASM::mov_base32_reg64(buf, base_offset + 16, tmp_reg);
-            },
-        );
+        self.store_list(sym, ptr_reg, element_alignment, elements.len());
         self.free_symbol(&allocation_symbol);
     }
 
@@ -5595,6 +5626,29 @@ impl<
         }
     }
 
+    /// Sets up a list on the stack, with a length and capacity of `len`.
+    fn store_list(
+        &mut self,
+        sym: &Symbol,
+        ptr_reg: GeneralReg,
+        element_alignment: u64,
+        len: usize,
+    ) {
+        self.storage_manager.with_tmp_general_reg(
+            &mut self.buf,
+            |storage_manager, buf, tmp_reg| {
+                let alignment = Ord::max(8, element_alignment) as u32;
+                let base_offset =
+                    storage_manager.claim_stack_area_with_alignment(*sym, 24, alignment);
+                ASM::mov_base32_reg64(buf, base_offset, ptr_reg);
+
+                ASM::mov_reg64_imm64(buf, tmp_reg, len as i64);
+                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                ASM::mov_base32_reg64(buf, base_offset + 16, tmp_reg);
+            },
+        );
+    }
+
     /// Loads the alignment bytes of `layout` into the given `symbol`
     fn load_layout_alignment(&mut self, layout: InLayout<'_>, symbol: Symbol) {
         let u32_layout = Layout::U32;
@@ -8,7 +8,7 @@
 use std::collections::hash_map::Entry;
 
 use bumpalo::{collections::Vec, Bump};
-use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
+use roc_builtins::bitcode::{self, FloatWidth, IntWidth, RcEvent};
 use roc_collections::all::{MutMap, MutSet};
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::ident::ModuleName;
@@ -73,6 +73,8 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// Report allocations and refcount operations to `roc_builtins.utils.record_rc_event`
+    pub instrument_rc: bool,
 }
 
 // These relocations likely will need a length.
@@ -208,7 +210,7 @@ impl<'a> LastSeenMap<'a> {
                             self.set_last_seen(*initializer, stmt);
                         }
                     }
-                    Expr::RuntimeErrorFunction(_) => {}
+                    Expr::RuntimeErrorFunction(_) | Expr::StackAlloc { .. } => {}
                     Expr::FunctionPointer { .. } => todo_lambda_erasure!(),
                     Expr::EmptyArray => {}
                 }
@@ -313,6 +315,8 @@ trait Backend<'a> {
     fn interner(&self) -> &STLayoutInterner<'a>;
     fn relocations_mut(&mut self) -> &mut Vec<'a, Relocation>;
     fn target_info(&self) -> TargetInfo;
+    /// The name of the proc that is currently being built
+    fn proc_name(&self) -> &str;
 
     fn interner_mut(&mut self) -> &mut STLayoutInterner<'a> {
         self.module_interns_helpers_mut().1
@@ -560,6 +564,11 @@ trait Backend<'a> {
                 let alignment = self.debug_symbol("alignment");
                 self.load_literal_i32(&alignment, alignment_bytes as i32);
 
+                if self.env().instrument_rc {
+                    // the builtins count the free itself
+                    self.record_rc_event(RcEvent::Free, 0, layout);
+                }
+
                 // NOTE: UTILS_FREE_DATA_PTR clears any tag id bits
 
                 self.build_fn_call(
@@ -579,6 +588,18 @@ trait Backend<'a> {
                 let sym = modify.get_symbol();
                 let layout = *self.layout_map().get(&sym).unwrap();
 
+                if self.env().instrument_rc && self.interner().contains_refcounted(layout) {
+                    match modify {
+                        ModifyRc::Inc(_, amount) => {
+                            self.record_rc_event(RcEvent::Inc, *amount, layout)
+                        }
+                        ModifyRc::Dec(_) | ModifyRc::DecRef(_) => {
+                            self.record_rc_event(RcEvent::Dec, 1, layout)
+                        }
+                        ModifyRc::Free(_) => unreachable!("handled above"),
+                    }
+                }
+
                 // Expand the Refcounting statement into more detailed IR with a function call
                 // If this layout requires a new RC proc, we get enough info to create a linker symbol
                 // for it. Here we don't create linker symbols at this time, but in Wasm backend, we do.
@@ -888,6 +909,17 @@ trait Backend<'a> {
             } => {
                 self.load_literal_symbols(arguments);
                 let reuse = reuse.map(|ru| ru.symbol);
+
+                if self.env().instrument_rc
+                    && !matches!(tag_layout, UnionLayout::NonRecursive(_))
+                    && !tag_layout.tag_is_null(*tag_id)
+                {
+                    match reuse {
+                        None => self.record_rc_event(RcEvent::Alloc, 1, *layout),
+                        Some(reuse) => self.record_rc_reuse(reuse, *layout),
+                    }
+                }
+
                 self.tag(sym, arguments, tag_layout, *tag_id, reuse);
             }
             Expr::NullPointer => {
@@ -942,6 +974,9 @@ trait Backend<'a> {
             } => {
                 self.build_alloca(*sym, *initializer, *element_layout);
             }
+            Expr::StackAlloc { .. } => {
+                internal_error!("stack allocations are only created for the LLVM backend")
+            }
             Expr::RuntimeErrorFunction(_) => todo!(),
         }
     }
@@ -2122,6 +2157,74 @@ trait Backend<'a> {
 
     fn build_fn_pointer(&mut self, dst: &Symbol, fn_name: String);
     fn build_data_pointer(&mut self, dst: &Symbol, data_name: String);
+    /// Point `dst` at a copy of `data` that is stored with the code
+    fn build_local_data_pointer(&mut self, dst: &Symbol, data: std::vec::Vec<u8>);
+
+    /// With `--instrument-rc`, report a refcount operation or allocation of a value of `layout`
+    /// in the current proc.
+    fn record_rc_event(&mut self, event: RcEvent, amount: u64, layout: InLayout<'a>) {
+        let event_symbol = self.debug_symbol("rc_event");
+        self.load_literal_i8(&event_symbol, event as i8);
+        let amount_symbol = self.debug_symbol("rc_event_amount");
+        self.load_literal_i64(&amount_symbol, amount as i64);
+
+        self.build_rc_stats_call(
+            bitcode::UTILS_RECORD_RC_EVENT,
+            &[event_symbol, amount_symbol],
+            &[Layout::U8, Layout::U64],
+            layout,
+        );
+
+        self.free_symbol(&event_symbol);
+        self.free_symbol(&amount_symbol);
+    }
+
+    /// Like `record_rc_event` for a tag that is constructed in the memory of `reuse` unless
+    /// that token is null, in which case the tag is a new allocation.
+    fn record_rc_reuse(&mut self, reuse: Symbol, layout: InLayout<'a>) {
+        self.build_rc_stats_call(
+            bitcode::UTILS_RECORD_RC_REUSE,
+            &[reuse],
+            &[Layout::OPAQUE_PTR],
+            layout,
+        );
+    }
+
+    fn build_rc_stats_call(
+        &mut self,
+        fn_name: &str,
+        args: &[Symbol],
+        arg_layouts: &[InLayout<'a>],
+        layout: InLayout<'a>,
+    ) {
+        let mut proc_name = self.proc_name().as_bytes().to_vec();
+        proc_name.push(0);
+        let proc_name_symbol = self.debug_symbol("rc_stats_proc");
+        self.build_local_data_pointer(&proc_name_symbol, proc_name);
+
+        let mut layout_name = self.interner().dbg(layout).into_bytes();
+        layout_name.push(0);
+        let layout_name_symbol = self.debug_symbol("rc_stats_layout");
+        self.build_local_data_pointer(&layout_name_symbol, layout_name);
+
+        let mut all_args = args.to_vec();
+        all_args.extend([proc_name_symbol, layout_name_symbol]);
+        let mut all_layouts = arg_layouts.to_vec();
+        all_layouts.extend([Layout::U64, Layout::U64]);
+
+        let dst = Symbol::DEV_TMP;
+        self.build_fn_call(
+            &dst,
+            fn_name.to_string(),
+            &all_args,
+            &all_layouts,
+            &Layout::UNIT,
+        );
+
+        self.free_symbol(&dst);
+        self.free_symbol(&proc_name_symbol);
+        self.free_symbol(&layout_name_symbol);
+    }
 
     /// Move a returned value into `dst`
     fn move_return_value(&mut self, dst: &Symbol, ret_layout: &InLayout<'a>);
@@ -405,6 +405,27 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     }
 }
 
+/// The relocations of an `adrp` and of the `add` after it, which together load an address
+fn aarch64_page_relocations() -> (RelocationKind, RelocationKind) {
+    if cfg!(target_os = "macos") {
+        (
+            RelocationKind::MachO {
+                value: object::macho::ARM64_RELOC_PAGE21,
+                relative: true,
+            },
+            RelocationKind::MachO {
+                value: object::macho::ARM64_RELOC_PAGEOFF12,
+                relative: false,
+            },
+        )
+    } else {
+        (
+            RelocationKind::Elf(object::elf::R_AARCH64_ADR_PREL_PG_HI21),
+            RelocationKind::Elf(object::elf::R_AARCH64_ADD_ABS_LO12_NC),
+        )
+    }
+}
+
 fn create_relocation(target_info: TargetInfo, symbol: SymbolId, offset: u64) -> write::Relocation {
     let (encoding, size, addend, kind) = match target_info.architecture {
         roc_target::Architecture::Aarch32 => todo!(),
@@ -977,14 +998,41 @@ fn build_proc<'a, B: Backend<'a>>(
                 };
                 local_data_index += 1;
                 let data_id = output.add_symbol(data_symbol);
-                output.add_symbol_data(data_id, data_section, data, 4);
-                write::Relocation {
-                    offset: offset + proc_offset,
-                    size: 32,
-                    kind: RelocationKind::Relative,
-                    encoding: RelocationEncoding::Generic,
-                    symbol: data_id,
-                    addend: -4,
+                output.add_symbol_data(data_id, data_section, data, 16);
+
+                if target_info.architecture == roc_target::Architecture::Aarch64 {
+                    // an `adrp` for the page of the data, and an `add` for the offset within it
+                    let (page, page_offset) = aarch64_page_relocations();
+
+                    relocations.push((
+                        section_id,
+                        write::Relocation {
+                            offset: offset + proc_offset,
+                            size: 21,
+                            kind: page,
+                            encoding: RelocationEncoding::Generic,
+                            symbol: data_id,
+                            addend: 0,
+                        },
+                    ));
+
+                    write::Relocation {
+                        offset: offset + proc_offset + 4,
+                        size: 12,
+                        kind: page_offset,
+                        encoding: RelocationEncoding::Generic,
+                        symbol: data_id,
+                        addend: 0,
+                    }
+                } else {
+                    write::Relocation {
+                        offset: offset + proc_offset,
+                        size: 32,
+                        kind: RelocationKind::Relative,
+                        encoding: RelocationEncoding::Generic,
+                        symbol: data_id,
+                        addend: -4,
+                    }
                 }
             }
             Relocation::LinkedData { offset, name } => {
@@ -6,7 +6,8 @@ use crate::llvm::convert::{
 use crate::llvm::expect::{clone_to_shared_memory, SharedMemoryPointer};
 use crate::llvm::memcpy::build_memcpy;
 use crate::llvm::refcounting::{
-    build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
+    build_reset, decrement_refcount_layout, increment_refcount_layout, rc_event, record_rc_event,
+    PointerToRefcount,
 };
 use crate::llvm::struct_::{struct_from_fields, RocStruct};
 use crate::llvm::{erased, fn_ptr};
@@ -36,7 +37,7 @@ use inkwell::{FloatPredicate, OptimizationLevel};
 use morphic_lib::{
     CalleeSpecVar, FuncName, FuncSpec, FuncSpecSolutions, ModSolutions, UpdateMode, UpdateModeVar,
 };
-use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
+use roc_builtins::bitcode::{self, FloatWidth, IntWidth, RcEvent};
 use roc_collections::all::{MutMap, MutSet};
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
@@ -739,6 +740,8 @@ pub struct Env<'a, 'ctx, 'env> {
     pub interns: Interns,
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
+    /// Report allocations and refcount operations to `roc_builtins.utils.record_rc_event`
+    pub instrument_rc: bool,
     pub exposed_to_host: MutSet<Symbol>,
 }
 
@@ -1703,6 +1706,29 @@ pub(crate) fn build_exp_expr<'a, 'ctx>(
         } => {
             let reuse_ptr = reuse.map(|ru| scope.load_symbol(&ru.symbol).into_pointer_value());
 
+            if env.instrument_rc
+                && !matches!(union_layout, UnionLayout::NonRecursive(_))
+                && !union_layout.tag_is_null(*tag_id)
+            {
+                let event = match reuse_ptr {
+                    None => rc_event(env, RcEvent::Alloc),
+                    Some(reuse_ptr) => {
+                        let is_null = env.builder.new_build_is_null(reuse_ptr, "is_null_ptr");
+
+                        env.builder
+                            .new_build_select(
+                                is_null,
+                                rc_event(env, RcEvent::Alloc),
+                                rc_event(env, RcEvent::ReuseHit),
+                                "rc_event",
+                            )
+                            .into_int_value()
+                    }
+                };
+
+                record_rc_event(env, layout_interner, parent, event, 1, layout);
+            }
+
             build_tag(
                 env,
                 layout_interner,
@@ -1930,9 +1956,15 @@ pub(crate) fn build_exp_expr<'a, 'ctx>(
         }
 
         EmptyArray => empty_polymorphic_list(env),
-        Array { elem_layout, elems } => {
-            list_literal(env, layout_interner, parent, scope, *elem_layout, elems)
-        }
+        Array { elem_layout, elems } => list_literal(
+            env,
+            layout_interner,
+            parent,
+            scope,
+            layout,
+            *elem_layout,
+            elems,
+        ),
         RuntimeErrorFunction(_) => todo!(),
 
         UnionAtIndex {
@@ -2226,6 +2258,8 @@ pub(crate) fn build_exp_expr<'a, 'ctx>(
 
             ptr.into()
         }
+
+        StackAlloc { tag_layout } => stack_alloc_union(env, layout_interner, *tag_layout).into(),
     }
 }
 
@@ -2483,10 +2517,12 @@ fn build_tag<'a, 'ctx>(
                 build_tag_fields(env, layout_interner, scope, fields, arguments);
 
             // Create the struct_type
-            let data_ptr = reserve_with_refcount_union_as_block_of_memory(
+            let data_ptr = allocate_tag(
                 env,
                 layout_interner,
-                *union_layout,
+                parent,
+                reuse_allocation,
+                union_layout,
                 &[fields],
             );
 
@@ -2910,6 +2946,50 @@ fn reserve_with_refcount_union_as_block_of_memory<'a, 'ctx>(
     )
 }
 
+/// Reserve stack memory for a union that is laid out like a heap allocation: the refcount is
+/// stored right before the data. The refcount is set to 0, which marks the value as read-only,
+/// so that incrementing or decrementing it never touches the allocator.
+fn stack_alloc_union<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+) -> PointerValue<'ctx> {
+    let fields: &[&[InLayout<'a>]] = match union_layout {
+        UnionLayout::Recursive(tags) => tags,
+        UnionLayout::NullableWrapped { other_tags, .. } => other_tags,
+        UnionLayout::NonNullableUnwrapped(fields) => env.arena.alloc([fields]),
+        UnionLayout::NullableUnwrapped { other_fields, .. } => env.arena.alloc([other_fields]),
+        UnionLayout::NonRecursive(_) => {
+            internal_error!("non-recursive unions are never heap-allocated")
+        }
+    };
+
+    let roc_union = if union_layout.stores_tag_id_as_data(env.target_info) {
+        RocUnion::tagged_from_slices(layout_interner, env.context, fields)
+    } else {
+        RocUnion::untagged_from_slices(layout_interner, env.context, fields)
+    };
+
+    // like `allocateWithRefcount`, put the data at an offset that is a multiple of its alignment,
+    // with the refcount in the word just before it
+    let ptr_width = env.target_info.ptr_width() as u32;
+    let extra_words = roc_union.tag_alignment().max(ptr_width) / ptr_width;
+    let prefix_type = env.ptr_int().array_type(extra_words);
+    let block_type = env
+        .context
+        .struct_type(&[prefix_type.into(), roc_union.struct_type().into()], false);
+
+    let block_ptr = entry_block_alloca_zerofill(env, block_type.into(), "stack_tag");
+    let data_ptr = env
+        .builder
+        .new_build_struct_gep(block_type, block_ptr, 1, "stack_tag_data");
+
+    PointerToRefcount::from_ptr_to_data(env, data_ptr)
+        .set_refcount(env, env.ptr_int().const_zero());
+
+    data_ptr
+}
+
 fn reserve_with_refcount_help<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     basic_type: impl BasicType<'ctx>,
@@ -2964,12 +3044,20 @@ fn list_literal<'a, 'ctx>(
     layout_interner: &STLayoutInterner<'a>,
     parent: FunctionValue<'ctx>,
     scope: &Scope<'a, 'ctx>,
+    list_layout: InLayout<'a>,
     element_layout: InLayout<'a>,
     elems: &[ListLiteralElement],
 ) -> BasicValueEnum<'ctx> {
     let ctx = env.context;
     let builder = env.builder;
 
+    let record_allocation = || {
+        if env.instrument_rc {
+            let event = rc_event(env, RcEvent::Alloc);
+            record_rc_event(env, layout_interner, parent, event, 1, list_layout);
+        }
+    };
+
     let element_type = basic_type_from_layout(
         env,
         layout_interner,
@@ -2979,10 +3067,9 @@ fn list_literal<'a, 'ctx>(
     let list_length = elems.len();
     let list_length_intval = env.ptr_int().const_int(list_length as _, false);
 
-    // TODO re-enable, currently causes morphic segfaults because it tries to update
-    // constants in-place...
-    // if element_type.is_int_type() {
-    if false {
+    // Morphic models lists of only literals as static lists, so they are never updated
+    // in-place and can live in a constant global.
+    if element_type.is_int_type() {
         let element_type = element_type.into_int_type();
         let element_width = layout_interner.stack_size(element_layout);
         let size = list_length * element_width as usize;
@@ -3084,6 +3171,7 @@ fn list_literal<'a, 'ctx>(
             super::build_list::store_list(env, ptr, list_length_intval).into()
         } else {
             // some of our elements are non-constant, so we must allocate space on the heap
+            record_allocation();
             let ptr = allocate_list(env, layout_interner, element_layout, list_length_intval);
 
             // then, copy the relevant segment from the constant section into the heap
@@ -3110,6 +3198,7 @@ fn list_literal<'a, 'ctx>(
             super::build_list::store_list(env, ptr, list_length_intval).into()
         }
     } else {
+        record_allocation();
         let ptr = allocate_list(env, layout_interner, element_layout, list_length_intval);
 
         // Copy the elements from the list literal into the array
@@ -3444,6 +3533,12 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                 Inc(symbol, inc_amount) => {
                     let (value, layout) = scope.load_symbol_and_layout(symbol);
                     if layout_interner.contains_refcounted(layout) {
+                        if env.instrument_rc {
+                            let event = rc_event(env, RcEvent::Inc);
+                            let amount = *inc_amount;
+                            record_rc_event(env, layout_interner, parent, event, amount, layout);
+                        }
+
                         increment_refcount_layout(
                             env,
                             layout_interner,
@@ -3468,6 +3563,11 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                     let (value, layout) = scope.load_symbol_and_layout(symbol);
 
                     if layout_interner.contains_refcounted(layout) {
+                        if env.instrument_rc {
+                            let event = rc_event(env, RcEvent::Dec);
+                            record_rc_event(env, layout_interner, parent, event, 1, layout);
+                        }
+
                         decrement_refcount_layout(env, layout_interner, layout_ids, value, layout);
                     }
 
@@ -3484,6 +3584,11 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                 DecRef(symbol) => {
                     let (value, layout) = scope.load_symbol_and_layout(symbol);
 
+                    if env.instrument_rc && layout_interner.contains_refcounted(layout) {
+                        let event = rc_event(env, RcEvent::Dec);
+                        record_rc_event(env, layout_interner, parent, event, 1, layout);
+                    }
+
                     match layout_interner.runtime_representation(layout) {
                         LayoutRepr::Builtin(Builtin::Str) => todo!(),
                         LayoutRepr::Builtin(Builtin::List(element_layout)) => {
@@ -3558,6 +3663,12 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                     let (value, layout) = scope.load_symbol_and_layout(symbol);
                     let alignment = layout_interner.allocation_alignment_bytes(layout);
 
+                    if env.instrument_rc {
+                        // the builtins count the free itself
+                        let event = rc_event(env, RcEvent::Free);
+                        record_rc_event(env, layout_interner, parent, event, 0, layout);
+                    }
+
                     debug_assert!(value.is_pointer_value());
                     let value = value.into_pointer_value();
 
@@ -5562,8 +5673,9 @@ pub fn build_procedures<'a>(
     entry_point: EntryPoint<'a>,
     debug_output_file: Option<&Path>,
     glue_layouts: &GlueLayouts<'a>,
-) {
-    let mod_solutions = build_procedures_help(
+    alias_analysis_cache: Option<&mut roc_alias_analysis::SummaryCache>,
+) -> roc_alias_analysis::AliasAnalysisTiming {
+    let (mod_solutions, alias_analysis_timing) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5571,6 +5683,7 @@ pub fn build_procedures<'a>(
         host_exposed_lambda_sets,
         entry_point,
         debug_output_file,
+        alias_analysis_cache,
     );
 
     let niche = Niche::NONE;
@@ -5608,6 +5721,8 @@ pub fn build_procedures<'a>(
             getter_name,
         );
     }
+
+    alias_analysis_timing
 }
 
 pub fn build_wasm_test_wrapper<'a, 'ctx>(
@@ -5617,7 +5732,7 @@ pub fn build_wasm_test_wrapper<'a, 'ctx>(
     procedures: MutMap<(Symbol, ProcLayout<'a>), roc_mono::ir::Proc<'a>>,
     entry_point: SingleEntryPoint<'a>,
 ) -> (&'static str, FunctionValue<'ctx>) {
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5625,6 +5740,7 @@ pub fn build_wasm_test_wrapper<'a, 'ctx>(
         vec![],
         EntryPoint::Single(entry_point),
         Some(&std::env::temp_dir().join("test.ll")),
+        None,
     );
 
     promote_to_wasm_test_wrapper(
@@ -5644,7 +5760,7 @@ pub fn build_procedures_return_main<'a, 'ctx>(
     host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     entry_point: SingleEntryPoint<'a>,
 ) -> (&'static str, FunctionValue<'ctx>) {
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5652,6 +5768,7 @@ pub fn build_procedures_return_main<'a, 'ctx>(
         host_exposed_lambda_sets,
         EntryPoint::Single(entry_point),
         Some(&std::env::temp_dir().join("test.ll")),
+        None,
     );
 
     promote_to_main_function(
@@ -5672,7 +5789,7 @@ pub fn build_procedures_expose_expects<'a>(
 ) -> Vec<'a, &'a str> {
     let entry_point = EntryPoint::Expects { symbols: expects };
 
-    let mod_solutions = build_procedures_help(
+    let (mod_solutions, _) = build_procedures_help(
         env,
         layout_interner,
         opt_level,
@@ -5680,6 +5797,7 @@ pub fn build_procedures_expose_expects<'a>(
         vec![],
         entry_point,
         Some(&std::env::temp_dir().join("test.ll")),
+        None,
     );
 
     let captures_niche = Niche::NONE;
@@ -5743,20 +5861,22 @@ fn build_procedures_help<'a>(
     host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     entry_point: EntryPoint<'a>,
     debug_output_file: Option<&Path>,
-) -> &'a ModSolutions {
+    alias_analysis_cache: Option<&mut roc_alias_analysis::SummaryCache>,
+) -> (&'a ModSolutions, roc_alias_analysis::AliasAnalysisTiming) {
     let mut layout_ids = roc_mono::layout::LayoutIds::default();
     let mut scope = Scope::default();
 
     let it1 = procedures.iter().map(|x| x.1);
     let it2 = host_exposed_lambda_sets.iter().map(|(_, _, hels)| hels);
 
-    let solutions = match roc_alias_analysis::spec_program(
+    let (solutions, alias_analysis_timing) = match roc_alias_analysis::spec_program(
         env.arena,
         layout_interner,
         opt_level,
         entry_point,
         it1,
         it2,
+        alias_analysis_cache,
     ) {
         Err(e) => panic!("Error in alias analysis: {e}"),
         Ok(solutions) => solutions,
@@ -5858,7 +5978,7 @@ fn build_procedures_help<'a>(
         }
     }
 
-    mod_solutions
+    (mod_solutions, alias_analysis_timing)
 }
 
 pub enum FuncBorrowSpec {
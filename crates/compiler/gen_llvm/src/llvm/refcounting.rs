@@ -16,6 +16,7 @@ use inkwell::module::Linkage;
 use inkwell::types::{AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
 use inkwell::values::{BasicValueEnum, FunctionValue, InstructionValue, IntValue, PointerValue};
 use inkwell::{AddressSpace, IntPredicate};
+use roc_builtins::bitcode::RcEvent;
 use roc_module::symbol::Interns;
 use roc_module::symbol::Symbol;
 use roc_mono::ir::ErasedField;
@@ -481,6 +482,56 @@ fn modify_refcount_erased_help<'ctx>(
     }
 }
 
+/// With `--instrument-rc`, report an allocation or refcount operation on a value of this layout
+/// in the current proc to the builtins, which print the totals when the program exits.
+pub fn record_rc_event<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    event: IntValue<'ctx>,
+    amount: u64,
+    layout: InLayout<'a>,
+) {
+    let proc_name = rc_stats_name(env, parent.get_name().to_str().unwrap());
+    let layout_name = rc_stats_name(env, &layout_interner.dbg(layout));
+
+    call_void_bitcode_fn(
+        env,
+        &[
+            event.into(),
+            env.context.i64_type().const_int(amount, false).into(),
+            proc_name.into(),
+            layout_name.into(),
+        ],
+        roc_builtins::bitcode::UTILS_RECORD_RC_EVENT,
+    );
+}
+
+pub fn rc_event<'ctx>(env: &Env<'_, 'ctx, '_>, event: RcEvent) -> IntValue<'ctx> {
+    env.context.i8_type().const_int(event as u64, false)
+}
+
+/// A nul-terminated constant string, shared by all events with the same proc or layout.
+fn rc_stats_name<'ctx>(env: &Env<'_, 'ctx, '_>, name: &str) -> PointerValue<'ctx> {
+    let global_name = format!("roc_rc_stats.{name}");
+    let global = env.module.get_global(&global_name).unwrap_or_else(|| {
+        let bytes = env.context.const_string(name.as_bytes(), true);
+        let global = env.module.add_global(bytes.get_type(), None, &global_name);
+
+        global.set_constant(true);
+        global.set_unnamed_addr(true);
+        global.set_linkage(Linkage::Private);
+        global.set_initializer(&bytes);
+        global
+    });
+
+    env.builder.new_build_pointer_cast(
+        global.as_pointer_value(),
+        env.context.i8_type().ptr_type(AddressSpace::default()),
+        "rc_stats_name",
+    )
+}
+
 pub fn increment_refcount_layout<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
     layout_interner: &STLayoutInterner<'a>,
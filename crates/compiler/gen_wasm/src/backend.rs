@@ -8,8 +8,8 @@ use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc,
-    Param, Proc, ProcLayout, Stmt,
+    static_list_bytes, BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement,
+    Literal, ModifyRc, Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
@@ -23,16 +23,18 @@ use roc_wasm_module::sections::{
     MemorySection, NameSection,
 };
 use roc_wasm_module::{
-    round_up_to_alignment, Align, ExportType, LocalId, Signature, SymInfo, ValueType, WasmModule,
+    round_up_to_alignment, Align, ExportType, LocalId, MultiValueSignature, Signature, SymInfo,
+    ValueType, WasmModule,
 };
 
 use crate::code_builder::CodeBuilder;
+use crate::layout::StackMemoryFormat;
 use crate::layout::{ReturnMethod, WasmLayout};
 use crate::low_level::{call_higher_order_lowlevel, LowLevelCall};
-use crate::storage::{AddressValue, Storage, StoredValue, StoredVarKind};
+use crate::storage::{AddressValue, StackMemoryLocation, Storage, StoredValue, StoredVarKind};
 use crate::{
-    copy_memory, CopyMemoryConfig, Env, DEBUG_SETTINGS, MEMORY_NAME, PTR_SIZE, PTR_TYPE,
-    TARGET_INFO,
+    copy_memory, load_multi_value, store_multi_value, CopyMemoryConfig, Env, DEBUG_SETTINGS,
+    MEMORY_NAME, PTR_SIZE, PTR_TYPE, TARGET_INFO,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -71,6 +73,9 @@ pub struct WasmBackend<'a, 'r> {
     pub code_builder: CodeBuilder<'a>,
     pub storage: Storage<'a>,
 
+    /// how the current procedure returns its value
+    return_method: ReturnMethod,
+
     /// how many blocks deep are we (used for jumps)
     block_depth: u32,
     joinpoint_label_map: MutMap<JoinPointId, (u32, Vec<'a, StoredValue>)>,
@@ -138,6 +143,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             can_relocate_heap: has_heap_base && has_heap_end,
 
             // Function-level data
+            return_method: ReturnMethod::NoReturnValue,
             block_depth: 0,
             joinpoint_label_map: MutMap::default(),
             code_builder: CodeBuilder::new(env.arena),
@@ -422,13 +428,40 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
+    /// Procedures that can be called from the host or from Zig builtins must use the C calling convention.
+    /// Calls between Roc procedures can use the multi-value proposal, if it's enabled.
+    fn proc_return_method(
+        &self,
+        proc_sym: Symbol,
+        source: ProcSource,
+        ret_layout: &WasmLayout,
+    ) -> ReturnMethod {
+        let is_roc_only =
+            matches!(source, ProcSource::Roc) && !self.env.exposed_to_host.contains(&proc_sym);
+
+        if self.env.features.multi_value && is_roc_only {
+            ret_layout.multi_value_return_method()
+        } else {
+            ret_layout.return_method()
+        }
+    }
+
     fn start_proc(&mut self, proc: &Proc<'a>) {
         use ReturnMethod::*;
         let ret_layout = WasmLayout::new(self.layout_interner, proc.ret_layout);
 
-        let ret_type = match ret_layout.return_method() {
+        let proc_sym = proc.name.name();
+        let source = self
+            .proc_lookup
+            .iter()
+            .find(|lookup| lookup.name == proc_sym)
+            .map(|lookup| lookup.source)
+            .unwrap_or(ProcSource::Roc);
+        self.return_method = self.proc_return_method(proc_sym, source, &ret_layout);
+
+        let ret_type = match self.return_method {
             Primitive(ty, _) => Some(ty),
-            NoReturnValue => None,
+            NoReturnValue | MultiValue(_) => None,
             WriteToPointerArg => {
                 self.storage.arg_types.push(PTR_TYPE);
                 None
@@ -451,10 +484,22 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.storage.return_var = Some(ret_var);
         }
 
-        self.module.add_function_signature(Signature {
-            param_types: self.storage.arg_types.clone(),
-            ret_type,
-        });
+        if let MultiValue(ret_types) = self.return_method {
+            for ty in ret_types {
+                let ret_var = self.storage.create_anonymous_local(*ty);
+                self.storage.multi_value_return_vars.push((ret_var, *ty));
+            }
+            self.module
+                .add_multi_value_function_signature(MultiValueSignature {
+                    param_types: self.storage.arg_types.clone(),
+                    ret_types,
+                });
+        } else {
+            self.module.add_function_signature(Signature {
+                param_types: self.storage.arg_types.clone(),
+                ret_type,
+            });
+        }
     }
 
     fn finalize_proc(&mut self) {
@@ -464,6 +509,9 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         if let Some(ret_var) = self.storage.return_var {
             self.code_builder.get_local(ret_var);
         }
+        for (ret_var, _) in self.storage.multi_value_return_vars.iter() {
+            self.code_builder.get_local(*ret_var);
+        }
 
         // Write local declarations and stack frame push/pop code
         self.code_builder.build_fn_header_and_footer(
@@ -525,8 +573,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             x => internal_error!("Higher-order wrapper: invalid return layout {:?}", x),
         };
 
-        let ret_type_and_size = match inner_ret_layout.return_method() {
-            ReturnMethod::NoReturnValue => None,
+        let inner_return_method = self.proc_return_method(
+            self.proc_lookup[inner_lookup_idx].name,
+            self.proc_lookup[inner_lookup_idx].source,
+            &inner_ret_layout,
+        );
+        let ret_type_and_size = match inner_return_method {
+            ReturnMethod::NoReturnValue | ReturnMethod::MultiValue(_) => None,
             ReturnMethod::Primitive(ty, size) => {
                 // If the inner function returns a primitive, load the address to store it at
                 // After the call, it will be under the call result in the value stack
@@ -585,6 +638,34 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         let inner_wasm_fn_index = self.fn_index_offset + inner_lookup_idx as u32;
         self.code_builder.call(inner_wasm_fn_index);
 
+        // If the inner function returns multiple values, store them to the heap return pointer.
+        // The value locals are declared after the wrapper's params.
+        let mut local_types = Vec::with_capacity_in(2, self.env.arena);
+        if let ReturnMethod::MultiValue(ret_types) = inner_return_method {
+            let first_local = wrapper_arg_layouts.len() as u32;
+            let mut value_locals = Vec::with_capacity_in(ret_types.len(), self.env.arena);
+            for (i, ty) in ret_types.iter().enumerate() {
+                value_locals.push((LocalId(first_local + i as u32), *ty));
+                local_types.push(*ty);
+            }
+            for (local_id, _) in value_locals.iter().rev() {
+                self.code_builder.set_local(*local_id);
+            }
+            let alignment_bytes = match inner_ret_layout {
+                WasmLayout::StackMemory {
+                    alignment_bytes, ..
+                } => alignment_bytes,
+                WasmLayout::Primitive(..) => internal_error!("Multi-value return of a primitive"),
+            };
+            store_multi_value(
+                &mut self.code_builder,
+                heap_return_ptr_id,
+                0,
+                alignment_bytes,
+                &value_locals,
+            );
+        }
+
         // If the inner function returns a primitive, store it to the address we loaded at the very beginning
         if let Some((ty, size)) = ret_type_and_size {
             match (ty, size) {
@@ -600,8 +681,9 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             }
         }
 
-        // Write empty function header (local variables array with zero length)
-        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+        // Write function header (no local variables, unless we needed them for multi-value returns)
+        self.code_builder
+            .build_fn_header_and_footer(&local_types, 0, None);
 
         self.module.add_function_signature(Signature {
             param_types: bumpalo::vec![in self.env.arena; I32; wrapper_arg_layouts.len()],
@@ -761,9 +843,37 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 print!("\nlet {:?} = {}", sym, expr.to_pretty(200, true));
             }
 
-            let kind = match following {
-                Stmt::Ret(ret_sym) if *sym == *ret_sym => StoredVarKind::ReturnValue,
-                _ => StoredVarKind::Variable,
+            let is_returned = matches!(following, Stmt::Ret(ret_sym) if *sym == *ret_sym);
+
+            if is_returned && self.env.features.tail_calls {
+                if let Expr::Call(roc_mono::ir::Call {
+                    call_type:
+                        CallType::ByName {
+                            name: func_sym,
+                            arg_layouts,
+                            ret_layout: result,
+                            ..
+                        },
+                    arguments,
+                }) = expr
+                {
+                    let proc_layout = ProcLayout {
+                        arguments: arg_layouts,
+                        result: *result,
+                        niche: func_sym.niche(),
+                    };
+                    if self.stmt_tail_call(*sym, *layout, func_sym.name(), &proc_layout, arguments)
+                    {
+                        return;
+                    }
+                }
+            }
+
+            // Multi-value returns come from local variables, not a return pointer
+            let kind = if is_returned && self.return_method == ReturnMethod::WriteToPointerArg {
+                StoredVarKind::ReturnValue
+            } else {
+                StoredVarKind::Variable
             };
 
             self.stmt_let_store_expr(*sym, *layout, expr, kind);
@@ -794,9 +904,107 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
+    /// Generate a `return_call` for `let x = f args; ret x`, so that the caller's Wasm stack frame is reused.
+    /// Returns false if the call is not a valid tail call, so that we generate a normal call instead.
+    fn stmt_tail_call(
+        &mut self,
+        ret_sym: Symbol,
+        ret_layout: InLayout<'a>,
+        func_sym: Symbol,
+        proc_layout: &ProcLayout<'a>,
+        arguments: &'a [Symbol],
+    ) -> bool {
+        use ReturnMethod::*;
+
+        if !matches!(
+            LowLevelWrapperType::from_symbol(func_sym),
+            LowLevelWrapperType::NotALowLevelWrapper
+        ) {
+            return false;
+        }
+
+        // The callee's results become ours, so the Wasm result types must match
+        let roc_proc_index = self.lookup_proc_index(func_sym, proc_layout);
+        let wasm_layout = WasmLayout::new(self.layout_interner, ret_layout);
+        let callee_return_method = self.proc_return_method(
+            func_sym,
+            self.proc_lookup[roc_proc_index].source,
+            &wasm_layout,
+        );
+        let same_result_types = match (callee_return_method, self.return_method) {
+            (Primitive(callee_ty, _), Primitive(caller_ty, _)) => callee_ty == caller_ty,
+            (callee, caller) => callee == caller,
+        };
+        if !same_result_types {
+            return false;
+        }
+
+        // Our stack frame is released before the call, so the callee can't have any pointers into it
+        let points_into_frame = |sym: &Symbol| match self.storage.get(sym) {
+            StoredValue::StackMemory {
+                location: StackMemoryLocation::FrameOffset(_),
+                format: StackMemoryFormat::DataStructure,
+                ..
+            } => true,
+            StoredValue::StackMemory { .. } => false,
+            StoredValue::Local { .. } => matches!(
+                self.layout_interner
+                    .get_repr(self.storage.symbol_layouts[sym]),
+                LayoutRepr::Ptr(_)
+            ),
+        };
+        if arguments.iter().any(points_into_frame) {
+            return false;
+        }
+
+        let kind = if callee_return_method == WriteToPointerArg {
+            StoredVarKind::ReturnValue
+        } else {
+            StoredVarKind::Variable
+        };
+        self.storage
+            .allocate_var(self.layout_interner, ret_layout, ret_sym, kind);
+
+        if callee_return_method == WriteToPointerArg {
+            self.code_builder.get_local(LocalId(0));
+        }
+        self.storage.load_symbols(&mut self.code_builder, arguments);
+
+        let wasm_fn_index = self.fn_index_offset + roc_proc_index as u32;
+        self.code_builder.return_call(wasm_fn_index);
+
+        true
+    }
+
     fn stmt_ret(&mut self, sym: Symbol) {
         use crate::storage::StoredValue::*;
 
+        if let ReturnMethod::MultiValue(ret_types) = self.return_method {
+            let (location, alignment_bytes) = match self.storage.get(&sym) {
+                StackMemory {
+                    location,
+                    alignment_bytes,
+                    ..
+                } => (location.clone(), *alignment_bytes),
+                Local { .. } => internal_error!("Multi-value return of a primitive"),
+            };
+            let (from_ptr, from_offset) =
+                location.local_and_offset(self.storage.stack_frame_pointer);
+            load_multi_value(
+                &mut self.code_builder,
+                from_ptr,
+                from_offset,
+                alignment_bytes,
+                ret_types,
+            );
+            for i in (0..ret_types.len()).rev() {
+                let (ret_var, _) = self.storage.multi_value_return_vars[i];
+                self.code_builder.set_local(ret_var);
+            }
+            self.code_builder.br(self.block_depth - 1);
+            return;
+        }
+
         match self.storage.get(&sym) {
             StackMemory {
                 location,
@@ -1128,6 +1336,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 element_layout,
             } => self.expr_alloca(*initializer, *element_layout, storage),
 
+            Expr::StackAlloc { .. } => {
+                internal_error!("stack allocations are only created for the LLVM backend")
+            }
+
             Expr::RuntimeErrorFunction(_) => {
                 todo!("Expression `{}`", expr.to_pretty(100, false))
             }
@@ -1232,7 +1444,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.code_builder.i32_store(Align::Bytes4, offset + 8);
         } else {
             let bytes = string.as_bytes();
-            let elements_addr = self.store_bytes_in_data_section(bytes);
+            let elements_addr = self.store_bytes_in_data_section(bytes, 1);
 
             // ptr
             self.code_builder.get_local(local_id);
@@ -1251,12 +1463,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         };
     }
 
-    /// Create a string constant in the module data section
+    /// Create a string or list constant in the module data section
     /// Return the data we need for code gen: linker symbol index and memory address
-    fn store_bytes_in_data_section(&mut self, bytes: &[u8]) -> u32 {
-        // Place the segment at a 4-byte aligned offset
-        let segment_addr = round_up_to_alignment!(self.module.data.end_addr, PTR_SIZE);
-        let elements_addr = segment_addr + PTR_SIZE;
+    fn store_bytes_in_data_section(&mut self, bytes: &[u8], alignment: u32) -> u32 {
+        // Place the elements at an aligned offset, right after a 4-byte refcount
+        let alignment = alignment.max(PTR_SIZE);
+        let elements_addr = round_up_to_alignment!(self.module.data.end_addr + PTR_SIZE, alignment);
+        let segment_addr = elements_addr - PTR_SIZE;
         let length_with_refcount = 4 + bytes.len();
         self.module.data.end_addr = segment_addr + length_with_refcount as u32;
 
@@ -1360,15 +1573,58 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             return self.expr_call_low_level(lowlevel, arguments, ret_sym, ret_layout, ret_storage);
         }
 
-        self.storage.load_symbols_for_call(
-            &mut self.code_builder,
-            arguments,
-            ret_sym,
+        let roc_proc_index = self.lookup_proc_index(func_sym, proc_layout);
+        let return_method = self.proc_return_method(
+            func_sym,
+            self.proc_lookup[roc_proc_index].source,
             &wasm_layout,
         );
 
-        let roc_proc_index = self
-            .proc_lookup
+        if let ReturnMethod::MultiValue(_) = return_method {
+            self.storage.load_symbols(&mut self.code_builder, arguments);
+        } else {
+            self.storage.load_symbols_for_call(
+                &mut self.code_builder,
+                arguments,
+                ret_sym,
+                &wasm_layout,
+            );
+        }
+
+        let wasm_fn_index = self.fn_index_offset + roc_proc_index as u32;
+
+        self.code_builder.call(wasm_fn_index);
+
+        // Store the returned values into the stack memory for the return symbol
+        if let ReturnMethod::MultiValue(ret_types) = return_method {
+            let (location, alignment_bytes) = match ret_storage {
+                StoredValue::StackMemory {
+                    location,
+                    alignment_bytes,
+                    ..
+                } => (location.clone(), *alignment_bytes),
+                StoredValue::Local { .. } => internal_error!("Multi-value return of a primitive"),
+            };
+            let mut value_locals = Vec::with_capacity_in(ret_types.len(), self.env.arena);
+            for ty in ret_types {
+                value_locals.push((self.storage.create_anonymous_local(*ty), *ty));
+            }
+            for (local_id, _) in value_locals.iter().rev() {
+                self.code_builder.set_local(*local_id);
+            }
+            let (to_ptr, to_offset) = location.local_and_offset(self.storage.stack_frame_pointer);
+            store_multi_value(
+                &mut self.code_builder,
+                to_ptr,
+                to_offset,
+                alignment_bytes,
+                &value_locals,
+            );
+        }
+    }
+
+    fn lookup_proc_index(&self, func_sym: Symbol, proc_layout: &ProcLayout<'a>) -> usize {
+        self.proc_lookup
             .iter()
             .position(|lookup| lookup.name == func_sym && &lookup.layout == proc_layout)
             .unwrap_or_else(|| {
@@ -1378,11 +1634,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                     proc_layout,
                     self.proc_lookup
                 );
-            });
-
-        let wasm_fn_index = self.fn_index_offset + roc_proc_index as u32;
-
-        self.code_builder.call(wasm_fn_index);
+            })
     }
 
     fn expr_call_low_level(
@@ -1554,16 +1806,36 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     ) {
         if let StoredValue::StackMemory { location, .. } = storage {
             let size = self.layout_interner.stack_size(elem_layout) * (elems.len() as u32);
+            let heap_alignment = self.layout_interner.alignment_bytes(elem_layout);
+
+            let (stack_local_id, stack_offset) =
+                location.local_and_offset(self.storage.stack_frame_pointer);
+
+            // A list of literals, like a constant that was evaluated at compile time, is static data
+            if let Some(bytes) =
+                static_list_bytes(elems, self.layout_interner.get_repr(elem_layout))
+            {
+                let elements_addr = self.store_bytes_in_data_section(&bytes, heap_alignment);
+
+                self.code_builder.get_local(stack_local_id);
+                self.code_builder.i32_const(elements_addr as i32);
+                self.code_builder.i32_store(Align::Bytes4, stack_offset);
+
+                for field in [Builtin::WRAPPER_LEN, Builtin::WRAPPER_CAPACITY] {
+                    self.code_builder.get_local(stack_local_id);
+                    self.code_builder.i32_const(elems.len() as i32);
+                    self.code_builder
+                        .i32_store(Align::Bytes4, stack_offset + 4 * field);
+                }
+
+                return;
+            }
 
             // Allocate heap space and store its address in a local variable
             let heap_local_id = self.storage.create_anonymous_local(PTR_TYPE);
-            let heap_alignment = self.layout_interner.alignment_bytes(elem_layout);
             self.allocate_with_refcount(Some(size), heap_alignment, 1);
             self.code_builder.set_local(heap_local_id);
 
-            let (stack_local_id, stack_offset) =
-                location.local_and_offset(self.storage.stack_frame_pointer);
-
             // elements pointer
             self.code_builder.get_local(stack_local_id);
             self.code_builder.get_local(heap_local_id);
@@ -76,6 +76,9 @@ pub struct CodeBuilder<'a> {
 
     /// Keep track of which local variables have been set
     set_locals: BitVec<u32>,
+
+    /// Code locations of tail calls. The stack frame must be popped before each one.
+    tail_call_positions: Vec<'a, usize>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -90,6 +93,7 @@ impl<'a> CodeBuilder<'a> {
             inner_length: Vec::with_capacity_in(5, arena),
             import_relocations: Vec::with_capacity_in(0, arena),
             set_locals: BitVec::with_capacity(64),
+            tail_call_positions: Vec::with_capacity_in(0, arena),
         }
     }
 
@@ -101,6 +105,7 @@ impl<'a> CodeBuilder<'a> {
         self.inner_length.clear();
         self.import_relocations.clear();
         self.set_locals.clear();
+        self.tail_call_positions.clear();
     }
 
     /**********************************************************
@@ -173,6 +178,23 @@ impl<'a> CodeBuilder<'a> {
         self.set_global(STACK_POINTER_GLOBAL_ID);
     }
 
+    /// Generate instruction bytes to release the stack frame just before each tail call,
+    /// since we never get to the footer on those code paths.
+    fn build_tail_call_frame_pops(&mut self, frame_size: i32, frame_pointer: LocalId) {
+        for at in self.tail_call_positions.iter().copied() {
+            let start = self.insert_bytes.len();
+            self.insert_bytes.push(GETLOCAL as u8);
+            self.insert_bytes.encode_u32(frame_pointer.0);
+            self.insert_bytes.push(I32CONST as u8);
+            self.insert_bytes.encode_i32(frame_size);
+            self.insert_bytes.push(I32ADD as u8);
+            self.insert_bytes.push(SETGLOBAL as u8);
+            self.insert_bytes.encode_u32(STACK_POINTER_GLOBAL_ID);
+            let end = self.insert_bytes.len();
+            self.insertions.push(Insertion { at, start, end });
+        }
+    }
+
     /// Build the function header: local declarations, stack frame push/pop code, and function length
     /// After this, all bytes have been generated (but not yet serialized) and we know the final size.
     pub fn build_fn_header_and_footer(
@@ -187,6 +209,7 @@ impl<'a> CodeBuilder<'a> {
             if let Some(frame_ptr_id) = frame_pointer {
                 let aligned_size = round_up_to_alignment!(frame_size, FRAME_ALIGNMENT_BYTES);
                 self.build_stack_frame_push(aligned_size, frame_ptr_id);
+                self.build_tail_call_frame_pops(aligned_size, frame_ptr_id);
                 self.build_stack_frame_pop(aligned_size, frame_ptr_id); // footer
             }
         }
@@ -348,6 +371,14 @@ impl<'a> CodeBuilder<'a> {
         log_instruction!("{:10}\t{}", format!("{CALL:?}"), function_index);
     }
 
+    /// Tail call (requires the Wasm tail-call proposal)
+    pub fn return_call(&mut self, function_index: u32) {
+        self.tail_call_positions.push(self.code.len());
+        self.inst_base(RETURNCALL);
+        self.code.encode_padded_u32(function_index);
+        log_instruction!("{:10}\t{}", format!("{RETURNCALL:?}"), function_index);
+    }
+
     pub fn call_import(&mut self, function_index: u32) {
         self.import_relocations
             .push((self.code.len(), function_index));
@@ -13,6 +13,9 @@ pub enum ReturnMethod {
     WriteToPointerArg,
     /// This layout is empty and requires no return value or argument (e.g. refcount helpers)
     NoReturnValue,
+    /// This layout is returned as several Wasm values, using the multi-value proposal.
+    /// The values are the raw bytes of the data structure, split into chunks.
+    MultiValue(&'static [ValueType]),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -126,6 +129,32 @@ impl WasmLayout {
             Self::StackMemory { size, format, .. } => stack_memory_return_method(*size, *format),
         }
     }
+
+    /// Return method for calls between Roc procedures, when the multi-value proposal is enabled.
+    /// Small data structures are returned by value, instead of writing to a pointer arg.
+    pub fn multi_value_return_method(&self) -> ReturnMethod {
+        match self {
+            Self::StackMemory { size, .. } => match multi_value_chunks(*size) {
+                Some(ret_types) => ReturnMethod::MultiValue(ret_types),
+                None => self.return_method(),
+            },
+            Self::Primitive(..) => self.return_method(),
+        }
+    }
+}
+
+/// Split a data structure into Wasm values, so that it can be returned using the multi-value proposal.
+/// We use the same chunk sizes as `copy_memory`. Other sizes are still returned by pointer.
+pub fn multi_value_chunks(size: u32) -> Option<&'static [ValueType]> {
+    use ValueType::*;
+
+    match size {
+        4 => Some(&[I32]),
+        8 => Some(&[I64]),
+        12 => Some(&[I64, I32]), // Str, List
+        16 => Some(&[I64, I64]), // I128, Dec
+        _ => None,
+    }
 }
 
 /// The Wasm argument types to use when passing structs or 128-bit numbers
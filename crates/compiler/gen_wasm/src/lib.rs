@@ -4,6 +4,7 @@ mod code_builder;
 mod layout;
 mod low_level;
 mod storage;
+mod wasi_command;
 
 // Helpers for interfacing to a Wasm module from outside
 pub mod wasm32_result;
@@ -14,10 +15,11 @@ use bumpalo::collections::Vec;
 use bumpalo::{self, Bump};
 
 use roc_collections::all::{MutMap, MutSet};
+use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{Proc, ProcLayout};
-use roc_mono::layout::{LayoutIds, STLayoutInterner};
+use roc_mono::layout::{Layout, LayoutIds, STLayoutInterner};
 use roc_target::TargetInfo;
 use roc_wasm_module::parse::ParseError;
 use roc_wasm_module::{Align, LocalId, ValueType, WasmModule};
@@ -46,12 +48,23 @@ pub struct Env<'a> {
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    pub features: WasmFeatures,
 }
 
 impl Env<'_> {
     pub const DEFAULT_STACK_BYTES: u32 = 1024 * 1024;
 }
 
+/// Post-MVP Wasm proposals that we can generate code for.
+/// They're off by default, because not all Wasm runtimes support them yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmFeatures {
+    /// Use `return_call` for calls in tail position, so that mutual recursion doesn't grow the stack
+    pub tail_calls: bool,
+    /// Return small data structures from Roc procedures as multiple values, rather than via stack memory
+    pub multi_value: bool,
+}
+
 /// Parse the preprocessed host binary
 /// If successful, the module can be passed to build_app_binary
 pub fn parse_host<'a>(arena: &'a Bump, host_bytes: &[u8]) -> Result<WasmModule<'a>, ParseError> {
@@ -81,6 +94,43 @@ pub fn build_app_binary<'a, 'r>(
     buffer
 }
 
+/// Generate a self-contained WASI command module, for apps that have no platform host.
+/// The app must expose `main : Str`, which is written to stdout by the generated `_start` function.
+///   builtins_module  parsed module from the Wasm builtins object file, which takes the place of the host
+pub fn build_wasi_command_binary<'a, 'r>(
+    env: &'r Env<'a>,
+    layout_interner: &'r mut STLayoutInterner<'a>,
+    interns: &'r mut Interns,
+    mut builtins_module: WasmModule<'a>,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> Result<std::vec::Vec<u8>, String> {
+    let main_is_str = procedures.keys().any(|(sym, proc_layout)| {
+        env.exposed_to_host.contains(sym)
+            && proc_layout.arguments.is_empty()
+            && proc_layout.result == Layout::STR
+    });
+    if !main_is_str {
+        return Err(
+            "The wasm32-wasi target has no platform, so the app must provide `main : Str`"
+                .to_string(),
+        );
+    }
+
+    let fd_write = wasi_command::insert_runtime(env.arena, &mut builtins_module)?;
+
+    let (mut wasm_module, mut called_fns, main_fn_index) =
+        build_app_module(env, layout_interner, interns, builtins_module, procedures);
+
+    wasi_command::insert_start_function(env.arena, &mut wasm_module, main_fn_index, fd_write);
+    called_fns.push(true);
+
+    wasm_module.eliminate_dead_code(env.arena, called_fns);
+
+    let mut buffer = std::vec::Vec::with_capacity(wasm_module.size());
+    wasm_module.serialize(&mut buffer);
+    Ok(buffer)
+}
+
 /// Generate an unserialized Wasm module
 /// Shared by all consumers of gen_wasm: roc_build, roc_repl_wasm, and test_gen
 /// (roc_repl_wasm and test_gen will add more generated code for a wrapper function
@@ -99,8 +149,9 @@ pub fn build_app_module<'a, 'r>(
     let mut maybe_main_fn_index = None;
 
     // Adjust Wasm function indices to account for functions from the object file
-    let fn_index_offset: u32 =
-        host_module.import.function_count() as u32 + host_module.code.function_count;
+    let fn_index_offset: u32 = host_module.import.function_count() as u32
+        + host_module.code.dead_import_dummy_count
+        + host_module.code.function_count;
 
     // Pre-pass over the procedure names & layouts
     // Create a lookup to tell us the final index of each proc in the output file
@@ -232,6 +283,58 @@ pub fn copy_memory(code_builder: &mut CodeBuilder, config: CopyMemoryConfig) {
     }
 }
 
+/// Load a data structure from memory as a sequence of Wasm values, to return it using the multi-value proposal
+pub fn load_multi_value(
+    code_builder: &mut CodeBuilder,
+    from_ptr: LocalId,
+    from_offset: u32,
+    alignment_bytes: u32,
+    value_types: &[ValueType],
+) {
+    let mut offset = from_offset;
+    for ty in value_types {
+        code_builder.get_local(from_ptr);
+        match ty {
+            ValueType::I64 => {
+                code_builder.i64_load(Align::from(alignment_bytes.min(8)), offset);
+                offset += 8;
+            }
+            ValueType::I32 => {
+                code_builder.i32_load(Align::from(alignment_bytes.min(4)), offset);
+                offset += 4;
+            }
+            _ => internal_error!("Multi-value chunks must be integers, not {:?}", ty),
+        }
+    }
+}
+
+/// Store a data structure that was returned as a sequence of Wasm values, using the multi-value proposal.
+/// The values must already be in local variables, since the VM stack has them in the wrong order for storing.
+pub fn store_multi_value(
+    code_builder: &mut CodeBuilder,
+    to_ptr: LocalId,
+    to_offset: u32,
+    alignment_bytes: u32,
+    value_locals: &[(LocalId, ValueType)],
+) {
+    let mut offset = to_offset;
+    for (local_id, ty) in value_locals {
+        code_builder.get_local(to_ptr);
+        code_builder.get_local(*local_id);
+        match ty {
+            ValueType::I64 => {
+                code_builder.i64_store(Align::from(alignment_bytes.min(8)), offset);
+                offset += 8;
+            }
+            ValueType::I32 => {
+                code_builder.i32_store(Align::from(alignment_bytes.min(4)), offset);
+                offset += 4;
+            }
+            _ => internal_error!("Multi-value chunks must be integers, not {:?}", ty),
+        }
+    }
+}
+
 pub struct WasmDebugSettings {
     proc_start_end: bool,
     user_procs_ir: bool,
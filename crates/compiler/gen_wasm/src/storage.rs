@@ -61,6 +61,8 @@ pub enum AddressValue {
 #[derive(Debug)]
 pub struct Storage<'a> {
     pub return_var: Option<LocalId>,
+    /// Return values for procedures using the Wasm multi-value proposal
+    pub multi_value_return_vars: Vec<'a, (LocalId, ValueType)>,
     pub arg_types: Vec<'a, ValueType>,
     pub local_types: Vec<'a, ValueType>,
     pub symbol_layouts: MutMap<Symbol, InLayout<'a>>,
@@ -73,6 +75,7 @@ impl<'a> Storage<'a> {
     pub fn new(arena: &'a Bump) -> Self {
         Storage {
             return_var: None,
+            multi_value_return_vars: Vec::with_capacity_in(2, arena),
             arg_types: Vec::with_capacity_in(8, arena),
             local_types: Vec::with_capacity_in(32, arena),
             symbol_layouts: MutMap::default(),
@@ -84,6 +87,7 @@ impl<'a> Storage<'a> {
 
     pub fn clear(&mut self) {
         self.return_var = None;
+        self.multi_value_return_vars.clear();
         self.arg_types.clear();
         self.local_types.clear();
         self.symbol_layouts.clear();
@@ -0,0 +1,754 @@
+/*
+Generate the runtime for a self-contained WASI command module, for apps that have no platform host.
+
+Normally the host provides `roc_alloc`, `roc_panic` and friends. Here we generate them in Wasm instead,
+and link them to the Zig builtins in the same way as host-to-app calls (see `link_host_to_app_calls`).
+The only imports left in the final binary are WASI functions, so it runs in any WASI runtime.
+
+The allocator is a simple bump allocator that never frees memory. That's fine for CLI-style programs
+that run to completion, but not for long-running ones.
+*/
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+
+use roc_error_macros::internal_error;
+use roc_wasm_module::linking::{SymInfo, WasmObjectSymbol};
+use roc_wasm_module::sections::{DataMode, DataSegment, MemorySection, NameSection};
+use roc_wasm_module::{round_up_to_alignment, Align, LocalId, Signature, ValueType, WasmModule};
+
+use crate::code_builder::CodeBuilder;
+use crate::PTR_SIZE;
+
+pub const WASI_MODULE_NAME: &str = "wasi_snapshot_preview1";
+pub const START_NAME: &str = "_start";
+
+const STDOUT: i32 = 1;
+const STDERR: i32 = 2;
+const EXIT_CODE_PANIC: i32 = 1;
+
+/// Functions the builtins may import, and which runtime function implements each of them
+const LINKED_NAMES: [(&str, RuntimeFn); 9] = [
+    ("roc_alloc", RuntimeFn::Alloc),
+    ("roc_realloc", RuntimeFn::Realloc),
+    ("roc_dealloc", RuntimeFn::Dealloc),
+    ("roc_panic", RuntimeFn::Panic),
+    ("roc_dbg", RuntimeFn::Dbg),
+    ("roc_memset", RuntimeFn::Memset),
+    ("memset", RuntimeFn::Memset),
+    ("memcpy", RuntimeFn::Memmove),
+    ("memmove", RuntimeFn::Memmove),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RuntimeFn {
+    Alloc,
+    Realloc,
+    Dealloc,
+    Panic,
+    Dbg,
+    Memset,
+    Memmove,
+}
+
+impl RuntimeFn {
+    const ALL: [Self; 7] = [
+        Self::Alloc,
+        Self::Realloc,
+        Self::Dealloc,
+        Self::Panic,
+        Self::Dbg,
+        Self::Memset,
+        Self::Memmove,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Alloc => "roc_alloc",
+            Self::Realloc => "roc_realloc",
+            Self::Dealloc => "roc_dealloc",
+            Self::Panic => "roc_panic",
+            Self::Dbg => "roc_dbg",
+            Self::Memset => "roc_memset",
+            Self::Memmove => "memmove",
+        }
+    }
+
+    fn signature<'a>(self, arena: &'a Bump) -> Signature<'a> {
+        use ValueType::*;
+        let (params, ret_type): (&[ValueType], _) = match self {
+            Self::Alloc => (&[I32, I32], Some(I32)),
+            Self::Realloc => (&[I32, I32, I32, I32], Some(I32)),
+            Self::Dealloc => (&[I32, I32], None),
+            Self::Panic => (&[I32, I32], None),
+            Self::Dbg => (&[I32, I32, I32], None),
+            Self::Memset => (&[I32, I32, I32], Some(I32)),
+            Self::Memmove => (&[I32, I32, I32], Some(I32)),
+        };
+        Signature {
+            param_types: Vec::from_iter_in(params.iter().copied(), arena),
+            ret_type,
+        }
+    }
+}
+
+/// Indices of the WASI functions we call, and addresses of our static data
+struct RuntimeData {
+    fd_write: u32,
+    proc_exit: u32,
+    heap_ptr_addr: u32,
+    panic_prefix: (u32, u32),
+    newline: (u32, u32),
+    dbg_open: (u32, u32),
+    dbg_close: (u32, u32),
+    dbg_equals: (u32, u32),
+}
+
+/// Link the builtins module to a generated runtime, so that it no longer needs a host.
+/// This must be done before the module is passed to the backend, since the runtime
+/// functions take the places of host functions in the function index space.
+/// Returns the function index of `fd_write`, for use by `insert_start_function`.
+pub fn insert_runtime<'a>(arena: &'a Bump, module: &mut WasmModule<'a>) -> Result<u32, String> {
+    // Linking will leave gaps in the debug names, unless we create them first
+    if module.names.function_names.is_empty() {
+        module.names =
+            NameSection::from_imports_and_linking_data(arena, &module.import, &module.linking);
+    }
+
+    let first_runtime_fn_index = module.import.function_count() as u32
+        + module.code.dead_import_dummy_count
+        + module.code.function_count;
+
+    let runtime_fn_index = |runtime_fn: RuntimeFn| {
+        let position = RuntimeFn::ALL
+            .iter()
+            .position(|f| *f == runtime_fn)
+            .unwrap();
+        first_runtime_fn_index + position as u32
+    };
+
+    // Only link names the builtins actually import. Others would be exported instead.
+    let host_to_app_map = Vec::from_iter_in(
+        LINKED_NAMES
+            .iter()
+            .filter(|(name, _)| module.import.imports.iter().any(|imp| imp.name == *name))
+            .map(|(name, runtime_fn)| (*name, runtime_fn_index(*runtime_fn))),
+        arena,
+    );
+    module.link_host_to_app_calls(arena, host_to_app_map);
+
+    // Any other imported functions would have to come from a host, but there isn't one
+    let unresolved = Vec::from_iter_in(
+        module
+            .import
+            .imports
+            .iter()
+            .filter(|imp| imp.is_function())
+            .map(|imp| imp.name),
+        arena,
+    );
+    if !unresolved.is_empty() {
+        return Err(format!(
+            "A WASI command module has no host, but the builtins import these functions: {}",
+            unresolved.join(", ")
+        ));
+    }
+
+    let mut import_wasi_fn = |name: &'a str, params: &[ValueType], ret_type| {
+        let signature = Signature {
+            param_types: Vec::from_iter_in(params.iter().copied(), arena),
+            ret_type,
+        };
+        module
+            .import_fn_into_dummy_slot(WASI_MODULE_NAME, name, signature)
+            .ok_or_else(|| {
+                format!("There is no room in the function index space to import WASI `{name}`")
+            })
+    };
+    use ValueType::I32;
+    let fd_write = import_wasi_fn("fd_write", &[I32, I32, I32, I32], Some(I32))?;
+    let proc_exit = import_wasi_fn("proc_exit", &[I32], None)?;
+
+    let heap_ptr_addr = round_up_to_alignment!(module.data.end_addr, PTR_SIZE);
+    module.data.end_addr = heap_ptr_addr + PTR_SIZE;
+
+    let data = RuntimeData {
+        fd_write,
+        proc_exit,
+        heap_ptr_addr,
+        panic_prefix: store_static_bytes(arena, module, b"Roc crashed: "),
+        newline: store_static_bytes(arena, module, b"\n"),
+        dbg_open: store_static_bytes(arena, module, b"["),
+        dbg_close: store_static_bytes(arena, module, b"] "),
+        dbg_equals: store_static_bytes(arena, module, b" = "),
+    };
+
+    for runtime_fn in RuntimeFn::ALL {
+        let index = runtime_fn_index(runtime_fn);
+        debug_assert_eq!(
+            index,
+            module.import.function_count() as u32
+                + module.code.dead_import_dummy_count
+                + module.code.function_count
+        );
+
+        module.add_function_signature(runtime_fn.signature(arena));
+
+        let mut code_builder = CodeBuilder::new(arena);
+        build_runtime_fn(&mut code_builder, &data, runtime_fn);
+        code_builder.insert_into_module(module);
+
+        // Let the backend find this function when the Roc app calls it
+        module
+            .linking
+            .symbol_table
+            .push(SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed {
+                flags: 0,
+                index,
+                name: runtime_fn.name(),
+            }));
+        module.names.append_function(index, runtime_fn.name());
+    }
+
+    Ok(fd_write)
+}
+
+/// Generate a `_start` function that calls the app's `main : Str` and writes the result to stdout.
+/// Like the test wrapper in `wasm32_result`, this goes after all the app's procedures.
+pub fn insert_start_function<'a>(
+    arena: &'a Bump,
+    module: &mut WasmModule<'a>,
+    main_fn_index: u32,
+    fd_write: u32,
+) {
+    let index = module.import.function_count() as u32
+        + module.code.dead_import_dummy_count
+        + module.code.function_count;
+
+    module.add_function_signature(Signature {
+        param_types: Vec::with_capacity_in(0, arena),
+        ret_type: None,
+    });
+
+    module.export.append(roc_wasm_module::Export {
+        name: START_NAME,
+        ty: roc_wasm_module::ExportType::Func,
+        index,
+    });
+
+    module
+        .linking
+        .symbol_table
+        .push(SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed {
+            flags: 0,
+            index,
+            name: START_NAME,
+        }));
+    module.names.append_function(index, START_NAME);
+
+    // Locals
+    let frame_ptr = LocalId(0);
+    let bytes = LocalId(1);
+    let len = LocalId(2);
+    let str_ptr = LocalId(3);
+    let local_types = &[ValueType::I32; 4];
+
+    // The Str goes above the 12 bytes used by write_bytes
+    let mut code_builder = CodeBuilder::new(arena);
+    code_builder.get_local(frame_ptr);
+    code_builder.i32_const(16);
+    code_builder.i32_add();
+    code_builder.tee_local(str_ptr);
+    code_builder.call(main_fn_index);
+    write_str(
+        &mut code_builder,
+        fd_write,
+        STDOUT,
+        str_ptr,
+        bytes,
+        len,
+        frame_ptr,
+    );
+    code_builder.build_fn_header_and_footer(local_types, 32, Some(frame_ptr));
+    code_builder.insert_into_module(module);
+}
+
+/// Store some bytes in the data section, returning their address and length
+fn store_static_bytes<'a>(
+    arena: &'a Bump,
+    module: &mut WasmModule<'a>,
+    bytes: &[u8],
+) -> (u32, u32) {
+    let addr = module.data.end_addr;
+    module.data.end_addr += bytes.len() as u32;
+    module.data.append_segment(DataSegment {
+        mode: DataMode::active_at(addr),
+        init: Vec::from_iter_in(bytes.iter().copied(), arena),
+    });
+    (addr, bytes.len() as u32)
+}
+
+fn build_runtime_fn(code_builder: &mut CodeBuilder, data: &RuntimeData, runtime_fn: RuntimeFn) {
+    use ValueType::I32;
+
+    match runtime_fn {
+        RuntimeFn::Alloc => {
+            // (size, alignment) -> ptr
+            let size = LocalId(0);
+            let alignment = LocalId(1);
+            let ptr = LocalId(2);
+            bump_alloc(code_builder, data.heap_ptr_addr, size, alignment, ptr);
+            code_builder.get_local(ptr);
+            code_builder.build_fn_header_and_footer(&[I32], 0, None);
+        }
+        RuntimeFn::Realloc => {
+            // (ptr, new_size, old_size, alignment) -> new_ptr
+            let old_ptr = LocalId(0);
+            let new_size = LocalId(1);
+            let old_size = LocalId(2);
+            let alignment = LocalId(3);
+            let new_ptr = LocalId(4);
+            let i = LocalId(5);
+            bump_alloc(
+                code_builder,
+                data.heap_ptr_addr,
+                new_size,
+                alignment,
+                new_ptr,
+            );
+
+            // copy min(old_size, new_size) bytes
+            code_builder.get_local(old_size);
+            code_builder.get_local(new_size);
+            code_builder.get_local(old_size);
+            code_builder.get_local(new_size);
+            code_builder.i32_lt_u();
+            code_builder.select();
+            code_builder.set_local(old_size);
+            copy_forward(code_builder, new_ptr, old_ptr, old_size, i);
+
+            code_builder.get_local(new_ptr);
+            code_builder.build_fn_header_and_footer(&[I32, I32], 0, None);
+        }
+        RuntimeFn::Dealloc => {
+            // A bump allocator never frees anything
+            code_builder.build_fn_header_and_footer(&[], 0, None);
+        }
+        RuntimeFn::Panic => {
+            // (msg: *RocStr, tag)
+            let msg = LocalId(0);
+            let frame_ptr = LocalId(2);
+            let bytes = LocalId(3);
+            let len = LocalId(4);
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.panic_prefix,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_str(
+                code_builder,
+                data.fd_write,
+                STDERR,
+                msg,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.newline,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            code_builder.i32_const(EXIT_CODE_PANIC);
+            code_builder.call_import(data.proc_exit);
+            code_builder.unreachable_();
+            code_builder.build_fn_header_and_footer(&[I32; 3], 16, Some(frame_ptr));
+        }
+        RuntimeFn::Dbg => {
+            // (loc: *RocStr, msg: *RocStr, src: *RocStr)
+            // Same format as our other platforms: "[loc] src = msg\n"
+            let loc = LocalId(0);
+            let msg = LocalId(1);
+            let src = LocalId(2);
+            let frame_ptr = LocalId(3);
+            let bytes = LocalId(4);
+            let len = LocalId(5);
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.dbg_open,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_str(
+                code_builder,
+                data.fd_write,
+                STDERR,
+                loc,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.dbg_close,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_str(
+                code_builder,
+                data.fd_write,
+                STDERR,
+                src,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.dbg_equals,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_str(
+                code_builder,
+                data.fd_write,
+                STDERR,
+                msg,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            write_static(
+                code_builder,
+                data,
+                STDERR,
+                data.newline,
+                bytes,
+                len,
+                frame_ptr,
+            );
+            code_builder.build_fn_header_and_footer(&[I32; 3], 16, Some(frame_ptr));
+        }
+        RuntimeFn::Memset => {
+            // (dst, value, size) -> dst
+            let dst = LocalId(0);
+            let value = LocalId(1);
+            let size = LocalId(2);
+            let i = LocalId(3);
+            code_builder.block();
+            code_builder.loop_();
+            {
+                code_builder.get_local(i);
+                code_builder.get_local(size);
+                code_builder.i32_ge_u();
+                code_builder.br_if(1);
+
+                code_builder.get_local(dst);
+                code_builder.get_local(i);
+                code_builder.i32_add();
+                code_builder.get_local(value);
+                code_builder.i32_store8(Align::Bytes1, 0);
+
+                increment(code_builder, i);
+                code_builder.br(0);
+            }
+            code_builder.end();
+            code_builder.end();
+            code_builder.get_local(dst);
+            code_builder.build_fn_header_and_footer(&[I32], 0, None);
+        }
+        RuntimeFn::Memmove => {
+            // (dst, src, size) -> dst
+            let dst = LocalId(0);
+            let src = LocalId(1);
+            let size = LocalId(2);
+            let i = LocalId(3);
+
+            // If the destination is below the source, a forward copy never overwrites bytes
+            // before we read them. Otherwise, copy backwards.
+            code_builder.get_local(dst);
+            code_builder.get_local(src);
+            code_builder.i32_le_u();
+            code_builder.if_();
+            copy_forward(code_builder, dst, src, size, i);
+            code_builder.else_();
+            {
+                code_builder.block();
+                code_builder.loop_();
+                {
+                    code_builder.get_local(size);
+                    code_builder.i32_eqz();
+                    code_builder.br_if(1);
+
+                    code_builder.get_local(size);
+                    code_builder.i32_const(1);
+                    code_builder.i32_sub();
+                    code_builder.set_local(size);
+
+                    code_builder.get_local(dst);
+                    code_builder.get_local(size);
+                    code_builder.i32_add();
+                    code_builder.get_local(src);
+                    code_builder.get_local(size);
+                    code_builder.i32_add();
+                    code_builder.i32_load8_u(Align::Bytes1, 0);
+                    code_builder.i32_store8(Align::Bytes1, 0);
+
+                    code_builder.br(0);
+                }
+                code_builder.end();
+                code_builder.end();
+            }
+            code_builder.end();
+            code_builder.get_local(dst);
+            code_builder.build_fn_header_and_footer(&[I32], 0, None);
+        }
+    }
+}
+
+fn increment(code_builder: &mut CodeBuilder, local: LocalId) {
+    code_builder.get_local(local);
+    code_builder.i32_const(1);
+    code_builder.i32_add();
+    code_builder.set_local(local);
+}
+
+/// Push the current end of memory onto the VM stack
+fn memory_end(code_builder: &mut CodeBuilder) {
+    code_builder.memory_size();
+    code_builder.i32_const(MemorySection::PAGE_SIZE.trailing_zeros() as i32);
+    code_builder.i32_shl();
+}
+
+/// Allocate from the top of memory, growing it as needed.
+/// The heap starts at the end of the initial memory, above the stack.
+fn bump_alloc(
+    code_builder: &mut CodeBuilder,
+    heap_ptr_addr: u32,
+    size: LocalId,
+    alignment: LocalId,
+    ptr: LocalId,
+) {
+    // Load the heap pointer, initializing it on the first call
+    code_builder.i32_const(heap_ptr_addr as i32);
+    code_builder.i32_load(Align::Bytes4, 0);
+    code_builder.set_local(ptr);
+    code_builder.get_local(ptr);
+    code_builder.i32_eqz();
+    code_builder.if_();
+    memory_end(code_builder);
+    code_builder.set_local(ptr);
+    code_builder.end();
+
+    // ptr = (ptr + alignment - 1) & -alignment
+    code_builder.get_local(ptr);
+    code_builder.get_local(alignment);
+    code_builder.i32_add();
+    code_builder.i32_const(1);
+    code_builder.i32_sub();
+    code_builder.i32_const(0);
+    code_builder.get_local(alignment);
+    code_builder.i32_sub();
+    code_builder.i32_and();
+    code_builder.set_local(ptr);
+
+    // Store the new heap pointer. We'll use it again to calculate how much memory we need.
+    code_builder.i32_const(heap_ptr_addr as i32);
+    code_builder.get_local(ptr);
+    code_builder.get_local(size);
+    code_builder.i32_add();
+    code_builder.i32_store(Align::Bytes4, 0);
+
+    // Grow memory by enough pages to fit the allocation
+    code_builder.i32_const(heap_ptr_addr as i32);
+    code_builder.i32_load(Align::Bytes4, 0);
+    memory_end(code_builder);
+    code_builder.i32_gt_u();
+    code_builder.if_();
+    {
+        code_builder.i32_const(heap_ptr_addr as i32);
+        code_builder.i32_load(Align::Bytes4, 0);
+        memory_end(code_builder);
+        code_builder.i32_sub();
+        code_builder.i32_const(MemorySection::PAGE_SIZE as i32 - 1);
+        code_builder.i32_add();
+        code_builder.i32_const(MemorySection::PAGE_SIZE.trailing_zeros() as i32);
+        code_builder.i32_shr_u();
+        code_builder.memory_grow();
+
+        // Out of memory
+        code_builder.i32_const(-1);
+        code_builder.i32_eq();
+        code_builder.if_();
+        code_builder.unreachable_();
+        code_builder.end();
+    }
+    code_builder.end();
+}
+
+fn copy_forward(
+    code_builder: &mut CodeBuilder,
+    dst: LocalId,
+    src: LocalId,
+    size: LocalId,
+    i: LocalId,
+) {
+    code_builder.i32_const(0);
+    code_builder.set_local(i);
+    code_builder.block();
+    code_builder.loop_();
+    {
+        code_builder.get_local(i);
+        code_builder.get_local(size);
+        code_builder.i32_ge_u();
+        code_builder.br_if(1);
+
+        code_builder.get_local(dst);
+        code_builder.get_local(i);
+        code_builder.i32_add();
+        code_builder.get_local(src);
+        code_builder.get_local(i);
+        code_builder.i32_add();
+        code_builder.i32_load8_u(Align::Bytes1, 0);
+        code_builder.i32_store8(Align::Bytes1, 0);
+
+        increment(code_builder, i);
+        code_builder.br(0);
+    }
+    code_builder.end();
+    code_builder.end();
+}
+
+fn write_static(
+    code_builder: &mut CodeBuilder,
+    data: &RuntimeData,
+    fd: i32,
+    (addr, len): (u32, u32),
+    bytes_local: LocalId,
+    len_local: LocalId,
+    frame_ptr: LocalId,
+) {
+    code_builder.i32_const(addr as i32);
+    code_builder.set_local(bytes_local);
+    code_builder.i32_const(len as i32);
+    code_builder.set_local(len_local);
+    write_bytes(
+        code_builder,
+        data.fd_write,
+        fd,
+        bytes_local,
+        len_local,
+        frame_ptr,
+    );
+}
+
+/// Write a RocStr to a file descriptor, handling the small string representation
+fn write_str(
+    code_builder: &mut CodeBuilder,
+    fd_write: u32,
+    fd: i32,
+    str_ptr: LocalId,
+    bytes: LocalId,
+    len: LocalId,
+    frame_ptr: LocalId,
+) {
+    // Small strings have the high bit set in the last byte, which also holds the length
+    code_builder.get_local(str_ptr);
+    code_builder.i32_load8_s(Align::Bytes1, 11);
+    code_builder.i32_const(0);
+    code_builder.i32_lt_s();
+    code_builder.if_();
+    {
+        code_builder.get_local(str_ptr);
+        code_builder.set_local(bytes);
+        code_builder.get_local(str_ptr);
+        code_builder.i32_load8_u(Align::Bytes1, 11);
+        code_builder.i32_const(0x7f);
+        code_builder.i32_and();
+        code_builder.set_local(len);
+    }
+    code_builder.else_();
+    {
+        // The high bit of the length marks a seamless slice
+        code_builder.get_local(str_ptr);
+        code_builder.i32_load(Align::Bytes4, 0);
+        code_builder.set_local(bytes);
+        code_builder.get_local(str_ptr);
+        code_builder.i32_load(Align::Bytes4, 4);
+        code_builder.i32_const(i32::MAX);
+        code_builder.i32_and();
+        code_builder.set_local(len);
+    }
+    code_builder.end();
+
+    write_bytes(code_builder, fd_write, fd, bytes, len, frame_ptr);
+}
+
+/// Call WASI `fd_write` until all the bytes are written, or it returns an error.
+/// Uses 12 bytes of stack frame for the iovec and the number of bytes written.
+fn write_bytes(
+    code_builder: &mut CodeBuilder,
+    fd_write: u32,
+    fd: i32,
+    bytes: LocalId,
+    len: LocalId,
+    frame_ptr: LocalId,
+) {
+    code_builder.block();
+    code_builder.loop_();
+    {
+        code_builder.get_local(len);
+        code_builder.i32_eqz();
+        code_builder.br_if(1);
+
+        // iovec { buf, buf_len }
+        code_builder.get_local(frame_ptr);
+        code_builder.get_local(bytes);
+        code_builder.i32_store(Align::Bytes4, 0);
+        code_builder.get_local(frame_ptr);
+        code_builder.get_local(len);
+        code_builder.i32_store(Align::Bytes4, 4);
+
+        code_builder.i32_const(fd);
+        code_builder.get_local(frame_ptr);
+        code_builder.i32_const(1);
+        code_builder.get_local(frame_ptr);
+        code_builder.i32_const(8);
+        code_builder.i32_add();
+        code_builder.call_import(fd_write);
+        code_builder.br_if(1); // non-zero errno
+
+        code_builder.get_local(bytes);
+        code_builder.get_local(frame_ptr);
+        code_builder.i32_load(Align::Bytes4, 8);
+        code_builder.i32_add();
+        code_builder.set_local(bytes);
+
+        code_builder.get_local(len);
+        code_builder.get_local(frame_ptr);
+        code_builder.i32_load(Align::Bytes4, 8);
+        code_builder.i32_sub();
+        code_builder.set_local(len);
+
+        code_builder.br(0);
+    }
+    code_builder.end();
+    code_builder.end();
+}
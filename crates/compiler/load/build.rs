@@ -26,6 +26,8 @@ const MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::HASH, "Hash.roc"),
     (ModuleId::INSPECT, "Inspect.roc"),
     (ModuleId::JSON, "TotallyNotJson.roc"),
+    (ModuleId::SORT, "Sort.roc"),
+    (ModuleId::DEFAULT, "Default.roc"),
 ];
 
 fn main() {
@@ -97,8 +99,13 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
     let problems = report_problems(
         &module.sources,
         &module.interns,
+        &mut module.parse_problems,
         &mut module.can_problems,
         &mut module.type_problems,
+        &[],
+        roc_reporting::report::RenderTarget::ColorTerminal,
+        &Default::default(),
+        false,
     );
 
     if problems.errors + problems.warnings > 0 {
@@ -71,6 +71,9 @@ pub fn load_single_threaded<'a>(
         render,
         palette,
         exec_mode,
+        false,
+        false,
+        false,
         roc_cache_dir,
     )
 }
@@ -242,6 +245,8 @@ fn read_cached_types() -> MutMap<ModuleId, TypeState> {
     let mod_decode = include_bytes_align_as!(u128, concat!(env!("OUT_DIR"), "/Decode.dat"));
     let mod_hash = include_bytes_align_as!(u128, concat!(env!("OUT_DIR"), "/Hash.dat"));
     let mod_inspect = include_bytes_align_as!(u128, concat!(env!("OUT_DIR"), "/Inspect.dat"));
+    let mod_sort = include_bytes_align_as!(u128, concat!(env!("OUT_DIR"), "/Sort.dat"));
+    let mod_default = include_bytes_align_as!(u128, concat!(env!("OUT_DIR"), "/Default.dat"));
 
     let mut output = MutMap::default();
 
@@ -265,6 +270,8 @@ fn read_cached_types() -> MutMap<ModuleId, TypeState> {
 
         output.insert(ModuleId::HASH, deserialize_help(mod_hash));
         output.insert(ModuleId::INSPECT, deserialize_help(mod_inspect));
+        output.insert(ModuleId::SORT, deserialize_help(mod_sort));
+        output.insert(ModuleId::DEFAULT, deserialize_help(mod_default));
     }
 
     output
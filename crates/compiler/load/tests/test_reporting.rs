@@ -19,8 +19,10 @@ mod test_reporting {
     use roc_parse::module::parse_header;
     use roc_parse::state::State;
     use roc_parse::test_helpers::parse_expr_with;
+    use roc_problem::lint::{Lint, LintLevel, LintLevels};
     use roc_problem::Severity;
     use roc_region::all::LineInfo;
+    use roc_reporting::error::r#type::missing_patterns;
     use roc_reporting::report::{
         can_problem, parse_problem, type_problem, RenderTarget, Report, ANSI_STYLE_CODES,
         DEFAULT_PALETTE,
@@ -41,6 +43,7 @@ mod test_reporting {
     fn to_simple_report(doc: RocDocBuilder) -> Report {
         Report {
             title: "".to_string(),
+            code: None,
             doc,
             filename: filename_from_string(r"/code/proj/Main.roc"),
             severity: Severity::RuntimeError,
@@ -129,6 +132,9 @@ mod test_reporting {
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
                 function_kind: FunctionKind::LambdaSet,
+                inline_and_fold: false,
+                eval_constants: false,
+                stack_allocate: false,
             };
             let result = roc_load::load_and_typecheck(
                 arena,
@@ -152,6 +158,7 @@ mod test_reporting {
     ) -> Result<
         (
             String,
+            Vec<String>,
             Vec<TypeError>,
             Vec<roc_problem::can::Problem>,
             ModuleId,
@@ -162,16 +169,30 @@ mod test_reporting {
         let (module_src, result) = run_load_and_infer(subdir, arena, expr_src);
         let LoadedModule {
             module_id: home,
+            mut parse_problems,
             mut can_problems,
             mut type_problems,
             interns,
             ..
         } = result?;
 
+        let parse_problems = parse_problems
+            .remove(&home)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|problem| problem.report)
+            .collect();
         let can_problems = can_problems.remove(&home).unwrap_or_default();
         let type_problems = type_problems.remove(&home).unwrap_or_default();
 
-        Ok((module_src, type_problems, can_problems, home, interns))
+        Ok((
+            module_src,
+            parse_problems,
+            type_problems,
+            can_problems,
+            home,
+            interns,
+        ))
     }
 
     fn list_reports_new<F>(subdir: &str, arena: &Bump, src: &str, finalize_render: F) -> String
@@ -186,7 +207,19 @@ mod test_reporting {
 
         match infer_expr_help_new(subdir, arena, src) {
             Err(LoadingProblem::FormattedReport(fail)) => fail,
-            Ok((module_src, type_problems, can_problems, home, interns)) => {
+            Ok((module_src, parse_problems, type_problems, can_problems, home, interns)) => {
+                // Syntax errors were rendered when the module was loaded; any other problems
+                // are about the defs that still parsed.
+                if !parse_problems.is_empty() {
+                    buf.push_str(&parse_problems.join("\n\n"));
+
+                    if can_problems.is_empty() && type_problems.is_empty() {
+                        return buf;
+                    }
+
+                    buf.push_str("\n\n");
+                }
+
                 let lines = LineInfo::new(&module_src);
                 let src_lines: Vec<&str> = module_src.split('\n').collect();
                 let mut reports = Vec::new();
@@ -459,6 +492,488 @@ mod test_reporting {
         }
     }
 
+    /// Render each report as a line of JSON, like `--error-format json`
+    fn json_reports(test_name: &str, src: &str) -> String {
+        let arena = Bump::new();
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let (module_src, _, type_problems, can_problems, home, interns) =
+            infer_expr_help_new(test_name, &arena, src).expect("failed to load");
+        let lines = LineInfo::new(&module_src);
+        let src_lines: Vec<&str> = module_src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let mut buf = String::new();
+        for problem in can_problems {
+            let report = can_problem(&alloc, &lines, filename.clone(), problem);
+            report.render(RenderTarget::Json, &mut buf, &alloc, &DEFAULT_PALETTE);
+        }
+        for problem in type_problems {
+            let missing_patterns = missing_patterns(&interns, &problem);
+            if let Some(report) = type_problem(&alloc, &lines, filename.clone(), problem) {
+                report.render_json_with_missing_patterns(&mut buf, missing_patterns);
+            }
+        }
+        buf
+    }
+
+    #[test]
+    fn json_unused_def() {
+        let json = json_reports(
+            "json_unused_def",
+            indoc!(
+                r"
+                x = 1
+                y = 2
+
+                x
+                "
+            ),
+        );
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"severity":"warning","code":"R0101","title":"UNUSED DEFINITION","#,
+                r#""file":"/code/proj/Main.roc","range":{"start":{"line":5,"column":5},"end":{"line":5,"column":6}},"#,
+                r#""ranges":[{"start":{"line":5,"column":5},"end":{"line":5,"column":6}}],"#,
+                r#""message":"`y` is not used anywhere in your code.\n\n5│      y = 2\n        ^\n\nIf you didn't intend on using `y` then remove it so future readers of\nyour code don't wonder why it is there.","#,
+                r#""hints":[]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn json_type_mismatch_with_tip() {
+        let json = json_reports(
+            "json_type_mismatch_with_tip",
+            indoc!(
+                r"
+                [1, 2.2, 0x3]
+                "
+            ),
+        );
+
+        assert_eq!(json.lines().count(), 1);
+        assert!(json.starts_with(r#"{"severity":"error","code":"R0301","title":"TYPE MISMATCH","#));
+        assert!(json
+            .contains(r#""range":{"start":{"line":4,"column":14},"end":{"line":4,"column":17}}"#));
+        assert!(json.contains(
+            r#""hints":[{"kind":"tip","message":"You can convert between integers and fractions using functions\nlike `Num.toFrac` and `Num.round`."}]"#
+        ));
+        assert!(!json.contains("Tip:"));
+    }
+
+    #[test]
+    fn json_missing_patterns() {
+        let json = json_reports(
+            "json_missing_patterns",
+            indoc!(
+                r#"
+                x : [A [B, C], D, E]
+                y : Bool
+                when x is
+                    A B -> ""
+                    D if y -> ""
+                "#
+            ),
+        );
+
+        assert_eq!(json.lines().count(), 1);
+        assert!(json.starts_with(r#"{"severity":"error","code":"R0351","title":"UNSAFE PATTERN","#));
+        assert!(json.ends_with(concat!(
+            r#""hints":[],"missing_patterns":["A C","D","E"]}"#,
+            "\n"
+        )));
+    }
+
+    #[test]
+    fn json_missing_patterns_are_written_as_source() {
+        let json = json_reports(
+            "json_missing_patterns_are_written_as_source",
+            indoc!(
+                r#"
+                x : { a : [Ok [Just Str, None], Err U8], b : Str }
+                when x is
+                    { a: Ok None, b } -> b
+                    { a: Err 0, b: "none" } -> ""
+                "#
+            ),
+        );
+
+        assert_eq!(json.lines().count(), 1);
+        assert!(json.ends_with(concat!(
+            r#""hints":[],"missing_patterns":["{ a: Err _, b }","{ a: Ok (Just _), b }"]}"#,
+            "\n"
+        )));
+    }
+
+    /// Render the errors of a module through the CLI, with `--verbose-errors`
+    fn verbose_errors(test_name: &str, src: &str) -> String {
+        let arena = Bump::new();
+        let (_, result) = run_load_and_infer(test_name, &arena, src);
+        let mut loaded = result.expect("failed to load");
+
+        let problems = roc_reporting::cli::render_problems(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.parse_problems,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+            &[],
+            RenderTarget::Generic,
+            &LintLevels::default(),
+            true,
+        );
+
+        problems.errors.join("\n")
+    }
+
+    #[test]
+    fn verbose_errors_trace_mismatch_to_distant_def() {
+        let report = verbose_errors(
+            "verbose_errors_trace_mismatch_to_distant_def",
+            indoc!(
+                r#"
+                greet : { name : Str } -> Str
+                greet = \{ name } -> "Hello, \(name)!"
+
+                unrelated = 42
+
+                greet { name: 1 }
+                "#
+            ),
+        );
+
+        insta::assert_snapshot!(report, @r"
+        ── TYPE MISMATCH [R0301] in ...e_errors_trace_mismatch_to_distant_def/Test.roc ─
+
+        This 1st argument to `greet` has an unexpected type:
+
+        9│      greet { name: 1 }
+                      ^^^^^^^^^^^
+
+        The argument is a record of type:
+
+            { name : Num * }
+
+        But `greet` needs its 1st argument to be:
+
+            { name : Str }
+
+        Note: Here is how I came to expect that type:
+            - It is the 1st argument to `greet` on line 9.
+            - `greet` is defined on line 5.
+            - The two types first differ in the `.name` field.
+        ");
+    }
+
+    #[test]
+    fn error_codes_are_unique() {
+        use roc_reporting::error::codes::ERROR_CODES;
+
+        // The tables have an entry for every variant of the problem enums, since they are
+        // generated from the same lists as the exhaustive matches that give each variant its code.
+        let error_codes: Vec<_> = ERROR_CODES.iter().flat_map(|table| table.iter()).collect();
+
+        let mut codes: Vec<_> = error_codes
+            .iter()
+            .map(|error_code| error_code.code)
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+
+        assert_eq!(codes.len(), error_codes.len());
+
+        for error_code in error_codes {
+            let code = error_code.code;
+
+            assert!(
+                code.len() == 5
+                    && code.starts_with('R')
+                    && code[1..].chars().all(|c| c.is_ascii_digit()),
+                "{code} should look like R0301"
+            );
+            assert!(!error_code.title.is_empty(), "{code} has no title");
+            assert!(
+                error_code
+                    .explanation
+                    .map_or(true, |text| !text.trim().is_empty()),
+                "{code} has an empty explanation"
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_error_code() {
+        use roc_reporting::error::codes::lookup;
+
+        assert_eq!(lookup("R0301").unwrap().title, "TYPE MISMATCH");
+        assert_eq!(lookup("r301").unwrap().title, "TYPE MISMATCH");
+        assert!(lookup("R0301").unwrap().explanation.is_some());
+        assert_eq!(lookup("R9999"), None);
+        assert_eq!(lookup("0301"), None);
+    }
+
+    fn problems_with_lint_levels(
+        test_name: &str,
+        src: &str,
+        lint_levels: &LintLevels,
+    ) -> roc_reporting::cli::Problems {
+        let arena = Bump::new();
+        let (_, result) = run_load_and_infer(test_name, &arena, src);
+        let mut loaded = result.expect("failed to load");
+
+        roc_reporting::cli::report_problems(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.parse_problems,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+            &[],
+            RenderTarget::Generic,
+            lint_levels,
+            false,
+        )
+    }
+
+    const UNUSED_DEF_SRC: &str = indoc!(
+        r"
+        x = 1
+        y = 2
+
+        x
+        "
+    );
+
+    #[test]
+    fn lint_default_level() {
+        let problems =
+            problems_with_lint_levels("lint_default_level", UNUSED_DEF_SRC, &LintLevels::default());
+
+        assert_eq!((problems.errors, problems.warnings), (0, 1));
+        assert_eq!(problems.exit_code(), 2);
+    }
+
+    #[test]
+    fn lint_denied_by_flag() {
+        let mut lint_levels = LintLevels::default();
+        lint_levels.set(Lint::UnusedDef, LintLevel::Deny);
+
+        let problems =
+            problems_with_lint_levels("lint_denied_by_flag", UNUSED_DEF_SRC, &lint_levels);
+
+        assert_eq!((problems.errors, problems.warnings), (1, 0));
+        assert_eq!(problems.exit_code(), 1);
+    }
+
+    #[test]
+    fn lint_directive_overrides_flag() {
+        let mut lint_levels = LintLevels::default();
+        lint_levels.set(Lint::UnusedDef, LintLevel::Deny);
+
+        let problems = problems_with_lint_levels(
+            "lint_directive_overrides_flag",
+            indoc!(
+                r"
+                x = 1
+                # lint: allow unused-def
+                y = 2
+
+                x
+                "
+            ),
+            &lint_levels,
+        );
+
+        assert_eq!((problems.errors, problems.warnings), (0, 0));
+        assert_eq!(problems.exit_code(), 0);
+    }
+
+    #[test]
+    fn lint_module_directive() {
+        let problems = problems_with_lint_levels(
+            "lint_module_directive",
+            indoc!(
+                r"
+                # lint-module: deny unused-def
+                x = 1
+                y = 2
+
+                x
+                "
+            ),
+            &LintLevels::default(),
+        );
+
+        assert_eq!((problems.errors, problems.warnings), (1, 0));
+    }
+
+    const UNOPTIMIZED_TAIL_CALL_SRC: &str = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        LinkedList : [Nil, Cons U64 LinkedList]
+
+        countdown : U64 -> LinkedList
+        countdown = \n ->
+            if n == 0 then
+                Nil
+            else if n % 2 == 0 then
+                Cons n (countdown (n - 1))
+            else
+                ping (n - 1)
+
+        ping : U64 -> LinkedList
+        ping = \n ->
+            if n % 3 == 0 then
+                countdown n
+            else
+                pong (n - 1)
+
+        pong : U64 -> LinkedList
+        pong = \n -> ping n
+
+        main = countdown 10
+        "#
+    );
+
+    fn load_and_monomorphize<'a>(
+        arena: &'a Bump,
+        src: &'a str,
+    ) -> roc_load::MonomorphizedModule<'a> {
+        let load_config = LoadConfig {
+            target_info: roc_target::TargetInfo::default_x86_64(),
+            render: RenderTarget::Generic,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Executable,
+            function_kind: FunctionKind::LambdaSet,
+            inline_and_fold: false,
+            eval_constants: false,
+            stack_allocate: false,
+        };
+
+        roc_load::load_and_monomorphize_from_str(
+            arena,
+            PathBuf::from("Test.roc"),
+            src,
+            PathBuf::from("fake/test/path"),
+            RocCacheDir::Disallowed,
+            load_config,
+        )
+        .expect("failed to load")
+    }
+
+    /// Like `problems_with_lint_levels`, but also reports the problems found after type checking
+    fn mono_problems_with_lint_levels(
+        src: &str,
+        lint_levels: &LintLevels,
+    ) -> roc_reporting::cli::Problems {
+        let arena = Bump::new();
+        let mut loaded = load_and_monomorphize(&arena, src);
+
+        roc_reporting::cli::report_problems(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.parse_problems,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+            &loaded.unoptimized_tail_calls,
+            RenderTarget::Generic,
+            lint_levels,
+            false,
+        )
+    }
+
+    #[test]
+    fn unoptimized_tail_call_report() {
+        use roc_reporting::error::mono::unoptimized_tail_call;
+
+        let arena = Bump::new();
+        let loaded = load_and_monomorphize(&arena, UNOPTIMIZED_TAIL_CALL_SRC);
+        let src_lines: Vec<&str> = UNOPTIMIZED_TAIL_CALL_SRC.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, loaded.module_id, &loaded.interns);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        // `ping` and `pong` can't be merged, because `countdown` is in the same cycle of tail calls
+        // and builds a list with recursive calls
+        assert_eq!(loaded.unoptimized_tail_calls.len(), 2);
+        let tail_call = loaded.unoptimized_tail_calls[0];
+
+        let mut buf = String::new();
+        unoptimized_tail_call(&alloc, filename.clone(), tail_call).render(
+            RenderTarget::Generic,
+            &mut buf,
+            &alloc,
+            &DEFAULT_PALETTE,
+        );
+
+        let mut json = String::new();
+        unoptimized_tail_call(&alloc, filename, tail_call).render_json(&mut json);
+
+        assert_eq!(
+            buf,
+            indoc!(
+                r#"
+                ── UNOPTIMIZED TAIL CALL [R0601] in /code/proj/Main.roc ────────────────────────
+
+                The call from `ping` to `pong` is in tail position, but I could not turn
+                it into a jump. Every time it is made, the stack grows, so deep
+                recursion through it can overflow the stack.
+
+                This can happen when one of the functions that call each other in tail
+                position also builds up a data structure with recursive calls.
+
+                Tip: If the recursion never gets deep, you can silence this warning by
+                adding this comment to the module:
+
+                    # lint-module: allow unoptimized-tail-call"#
+            )
+        );
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"severity":"warning","code":"R0601","title":"UNOPTIMIZED TAIL CALL","file":"/code/proj/Main.roc","range":null,"ranges":[],"message":"The call from `ping` to `pong` is in tail position, but I could not turn\nit into a jump. Every time it is made, the stack grows, so deep\nrecursion through it can overflow the stack.\n\nThis can happen when one of the functions that call each other in tail\nposition also builds up a data structure with recursive calls.\n\n    # lint-module: allow unoptimized-tail-call","hints":[{"kind":"tip","message":"If the recursion never gets deep, you can silence this warning by\nadding this comment to the module:"}]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn unoptimized_tail_call_is_a_warning() {
+        let problems =
+            mono_problems_with_lint_levels(UNOPTIMIZED_TAIL_CALL_SRC, &LintLevels::default());
+
+        assert_eq!((problems.errors, problems.warnings), (0, 2));
+        assert_eq!(problems.exit_code(), 2);
+    }
+
+    #[test]
+    fn unoptimized_tail_call_denied_by_flag() {
+        let mut lint_levels = LintLevels::default();
+        lint_levels.set(Lint::UnoptimizedTailCall, LintLevel::Deny);
+
+        let problems = mono_problems_with_lint_levels(UNOPTIMIZED_TAIL_CALL_SRC, &lint_levels);
+
+        assert_eq!((problems.errors, problems.warnings), (2, 0));
+        assert_eq!(problems.exit_code(), 1);
+    }
+
+    #[test]
+    fn unoptimized_tail_call_allowed_by_module_directive() {
+        let src = UNOPTIMIZED_TAIL_CALL_SRC.replacen(
+            "\n\n",
+            "\n\n# lint-module: allow unoptimized-tail-call\n",
+            1,
+        );
+        let problems = mono_problems_with_lint_levels(&src, &LintLevels::default());
+
+        assert_eq!((problems.errors, problems.warnings), (0, 0));
+        assert_eq!(problems.exit_code(), 0);
+    }
+
     fn human_readable(str: &str) -> String {
         str.replace(ANSI_STYLE_CODES.red, "<red>")
             .replace(ANSI_STYLE_CODES.white, "<white>")
@@ -480,7 +995,7 @@ mod test_reporting {
         "
         ),
         @r"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [R0212] in /code/proj/Main.roc ──────────────────────────────────
 
     The List module does not expose `isempty`:
 
@@ -507,7 +1022,7 @@ mod test_reporting {
         "
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `y` is not used anywhere in your code.
 
@@ -532,7 +1047,7 @@ mod test_reporting {
        "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0201] in /code/proj/Main.roc ───────────────────────────────
 
     The `i` name is first defined here:
 
@@ -564,7 +1079,7 @@ mod test_reporting {
        "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
     The `Booly` name is first defined here:
 
@@ -596,7 +1111,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0109] in /code/proj/Main.roc ───────────────────────────────
 
     Using != and == together requires parentheses, to clarify how they
     should be grouped.
@@ -625,7 +1140,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-        ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+        ── UNRECOGNIZED NAME [R0155] in /code/proj/Main.roc ────────────────────────────
 
         Nothing is named `bar` in this scope.
 
@@ -649,7 +1164,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `true` in this scope.
 
@@ -681,7 +1196,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0109] in /code/proj/Main.roc ───────────────────────────────
 
     Using more than one == like this requires parentheses, to clarify how
     things should be grouped.
@@ -707,7 +1222,7 @@ mod test_reporting {
          "#
         ),
         @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [R0106] in /code/proj/Main.roc ──────────────────────────────
 
     `box` doesn't use `htmlChildren`.
 
@@ -720,7 +1235,7 @@ mod test_reporting {
     at the start of a variable name is a way of saying that the variable
     is not used.
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `y` is not used anywhere in your code.
 
@@ -804,7 +1319,7 @@ mod test_reporting {
             ),
             indoc!(
                 r"
-                <cyan>── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────<reset>
+                <cyan>── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────<reset>
 
                 Nothing is named `theAdmin` in this scope.
 
@@ -830,7 +1345,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -856,7 +1371,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` guard condition needs to be a Bool:
 
@@ -881,7 +1396,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -908,7 +1423,7 @@ mod test_reporting {
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The 3rd branch of this `if` does not match all the previous branches:
 
@@ -938,7 +1453,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The 2nd branch of this `when` does not match all the previous branches:
 
@@ -976,7 +1491,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -1020,7 +1535,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This list contains elements with different types:
 
@@ -1047,7 +1562,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This list contains elements with different types:
 
@@ -1080,7 +1595,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     I cannot update the `.foo` field like this:
 
@@ -1110,7 +1625,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1135,7 +1650,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1161,7 +1676,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1174,7 +1689,7 @@ mod test_reporting {
 
         List ∞ -> *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1201,7 +1716,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1237,7 +1752,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1271,7 +1786,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1297,7 +1812,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1335,7 +1850,7 @@ mod test_reporting {
         // against that extra variable, rather than possibly having to translate a `Type`
         // again.
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1348,7 +1863,7 @@ mod test_reporting {
 
         List ∞ -> List *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1375,7 +1890,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1388,7 +1903,7 @@ mod test_reporting {
 
         List ∞ -> List *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0303] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1416,7 +1931,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1450,7 +1965,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1487,7 +2002,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1524,7 +2039,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the `then` branch of this `if` expression:
 
@@ -1558,7 +2073,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -1591,7 +2106,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -1623,7 +2138,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The `x` value is not a function, but it was given 1 argument:
 
@@ -1645,7 +2160,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The `f` function expects 1 argument, but it got 2 instead:
 
@@ -1667,7 +2182,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [R0301] in /code/proj/Main.roc ─────────────────────────────────
 
     The `f` function expects 2 arguments, but it got only 1:
 
@@ -1688,7 +2203,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1717,7 +2232,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 2nd pattern in this `when` does not match the previous ones:
 
@@ -1743,7 +2258,7 @@ mod test_reporting {
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1771,7 +2286,7 @@ mod test_reporting {
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1800,7 +2315,7 @@ mod test_reporting {
              "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `foo` in this scope.
 
@@ -1856,7 +2371,7 @@ mod test_reporting {
         ),
         // Just putting this here. We should probably handle or-patterns better
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 2nd pattern in this branch does not match the previous ones:
 
@@ -1884,7 +2399,7 @@ mod test_reporting {
         ),
         // Maybe this should specifically say the pattern doesn't work?
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1912,7 +2427,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of this definition:
 
@@ -1943,7 +2458,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     This integer pattern is malformed:
 
@@ -1964,7 +2479,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     This float pattern is malformed:
 
@@ -1985,7 +2500,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     This hex integer pattern is malformed:
 
@@ -2006,7 +2521,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     This octal integer pattern is malformed:
 
@@ -2027,7 +2542,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     This binary integer pattern is malformed:
 
@@ -2049,7 +2564,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -2099,7 +2614,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -2133,7 +2648,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2168,7 +2683,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2204,7 +2719,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `ok` in this scope.
 
@@ -2235,7 +2750,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `ok` is not used anywhere in your code.
 
@@ -2245,7 +2760,7 @@ mod test_reporting {
     If you didn't intend on using `ok` then remove it so future readers of
     your code don't wonder why it is there.
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2277,7 +2792,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [R0304] in /code/proj/Main.roc ──────────────────────────
 
     `f` is defined directly in terms of itself:
 
@@ -2304,7 +2819,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [R0221] in /code/proj/Main.roc ──────────────────────────
 
     The `foo` definition is causing a very tricky infinite loop:
 
@@ -2332,7 +2847,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2354,7 +2869,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2387,7 +2902,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `r` record doesn’t have a `foo` field:
 
@@ -2415,7 +2930,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2445,7 +2960,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2470,7 +2985,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2498,7 +3013,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2526,7 +3041,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2560,7 +3075,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2603,7 +3118,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This pattern does not cover all the possibilities:
 
@@ -2618,7 +3133,7 @@ mod test_reporting {
     matching in function arguments, put a `when` in the function body to
     account for all possibilities.
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2655,7 +3170,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -2686,7 +3201,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2713,7 +3228,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2741,7 +3256,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2770,7 +3285,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2799,7 +3314,7 @@ mod test_reporting {
         ),
         // Tip: Looks like a record field guard is not exhaustive. Learn more about record pattern matches at TODO.
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2828,7 +3343,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2854,7 +3369,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2881,7 +3396,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 2nd pattern is redundant:
 
@@ -2909,7 +3424,7 @@ mod test_reporting {
         ),
         // de-aliases the alias to give a better error message
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -2947,7 +3462,7 @@ mod test_reporting {
         ),
         // should not report Bar as unused!
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0202] in /code/proj/Main.roc ─────────────────────────────────
 
     The `Foo` alias is self-recursive in an invalid way:
 
@@ -2973,7 +3488,7 @@ mod test_reporting {
         ),
         // should not report Bar as unused!
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `Foo` alias is self-recursive in an invalid way:
 
@@ -2993,7 +3508,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [R0116] in /code/proj/Main.roc ─────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3017,7 +3532,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [R0116] in /code/proj/Main.roc ─────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3045,7 +3560,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [R0116] in /code/proj/Main.roc ─────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3080,7 +3595,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [R0116] in /code/proj/Main.roc ─────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3113,7 +3628,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [R0117] in /code/proj/Main.roc ─────────────────────────
 
     This record type defines the `.foo` field twice!
 
@@ -3141,7 +3656,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── DUPLICATE TAG NAME in /code/proj/Main.roc ───────────────────────────────────
+    ── DUPLICATE TAG NAME [R0119] in /code/proj/Main.roc ───────────────────────────
 
     This tag union type defines the `Foo` tag twice!
 
@@ -3170,7 +3685,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── NAMING PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── NAMING PROBLEM [R0120] in /code/proj/Main.roc ───────────────────────────────
 
     This annotation does not match the definition immediately following
     it:
@@ -3206,7 +3721,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0121] in /code/proj/Main.roc ───────────────────────────────
 
     This definition of `MyAlias` has an unexpected pattern:
 
@@ -3215,7 +3730,7 @@ mod test_reporting {
 
     Only type variables like `a` or `value` can occur in this position.
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `MyAlias` is not used anywhere in your code.
 
@@ -3238,7 +3753,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0121] in /code/proj/Main.roc ───────────────────────────────
 
     This definition of `Age` has an unexpected pattern:
 
@@ -3260,7 +3775,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ──────────────────────
 
     The `Num` opaque expects 1 type argument, but it got 2 instead:
 
@@ -3282,7 +3797,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ──────────────────────
 
     The `Num` opaque expects 1 type argument, but it got 2 instead:
 
@@ -3306,7 +3821,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO FEW TYPE ARGUMENTS in /code/proj/Main.roc ───────────────────────────────
+    ── TOO FEW TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ───────────────────────
 
     The `Pair` alias expects 2 type arguments, but it got 1 instead:
 
@@ -3330,7 +3845,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ──────────────────────
 
     The `Pair` alias expects 2 type arguments, but it got 3 instead:
 
@@ -3353,7 +3868,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNUSED TYPE ALIAS PARAMETER in /code/proj/Main.roc ──────────────────────────
+    ── UNUSED TYPE ALIAS PARAMETER [R0114] in /code/proj/Main.roc ──────────────────
 
     The `a` type parameter is not used in the `Foo` alias definition:
 
@@ -3375,7 +3890,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── ARGUMENTS BEFORE EQUALS in tmp/elm_function_syntax/Test.roc ─────────────────
+    ── ARGUMENTS BEFORE EQUALS [R1112] in tmp/elm_function_syntax/Test.roc ─────────
 
     I am partway through parsing a definition, but I got stuck here:
 
@@ -3403,7 +3918,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -3452,7 +3967,7 @@ mod test_reporting {
         // TODO render tag unions across multiple lines
         // TODO do not show recursion var if the recursion var does not render on the surface of a type
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -3506,7 +4021,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal is too big:
 
@@ -3518,7 +4033,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal is too small:
 
@@ -3530,7 +4045,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal is too big:
 
@@ -3542,7 +4057,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal is too small:
 
@@ -3554,7 +4069,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -3583,7 +4098,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0219] in /code/proj/Main.roc ───────────────────────────────
 
     This float literal is too big:
 
@@ -3595,7 +4110,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0219] in /code/proj/Main.roc ───────────────────────────────
 
     This float literal is too small:
 
@@ -3628,7 +4143,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -3640,7 +4155,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This hex integer literal contains an invalid digit:
 
@@ -3652,7 +4167,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This octal integer literal contains an invalid digit:
 
@@ -3664,7 +4179,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This binary integer literal contains an invalid digit:
 
@@ -3694,7 +4209,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This hex integer literal contains no digits:
 
@@ -3706,7 +4221,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This octal integer literal contains no digits:
 
@@ -3718,7 +4233,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This binary integer literal contains no digits:
 
@@ -3742,7 +4257,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0219] in /code/proj/Main.roc ───────────────────────────────
 
     This float literal contains an invalid digit:
 
@@ -3773,7 +4288,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0218] in /code/proj/Main.roc ───────────────────────────────
 
     This expression cannot be updated:
 
@@ -3782,7 +4297,7 @@ mod test_reporting {
 
     Only variables can be updated with record update syntax.
 
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [R0213] in /code/proj/Main.roc ──────────────────────────
 
     The `Test` module is not imported:
 
@@ -3797,7 +4312,7 @@ mod test_reporting {
         Dict
         Hash
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0218] in /code/proj/Main.roc ───────────────────────────────
 
     This expression cannot be updated:
 
@@ -3816,7 +4331,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [R0213] in /code/proj/Main.roc ──────────────────────────
 
     The `Foo` module is not imported:
 
@@ -3841,7 +4356,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -3869,7 +4384,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -3898,7 +4413,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of this definition:
 
@@ -3929,7 +4444,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -3962,7 +4477,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -3995,7 +4510,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -4026,7 +4541,7 @@ mod test_reporting {
                 "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to this function has an unexpected type:
 
@@ -4060,7 +4575,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -4094,7 +4609,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -4122,7 +4637,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── BAD OPTIONAL VALUE in /code/proj/Main.roc ───────────────────────────────────
+    ── BAD OPTIONAL VALUE [R0118] in /code/proj/Main.roc ───────────────────────────
 
     This record uses an optional value for the `.y` field in an incorrect
     context!
@@ -4159,7 +4674,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -4204,7 +4719,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [R0106] in /code/proj/Main.roc ──────────────────────────────
 
     `f` doesn't use `foo`.
 
@@ -4226,7 +4741,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -4247,7 +4762,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -4267,7 +4782,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse a record field access here:
 
@@ -4289,7 +4804,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/type_annotation_double_colon/Test.roc ───────────────
+    ── UNKNOWN OPERATOR [R1103] in tmp/type_annotation_double_colon/Test.roc ───────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -4321,7 +4836,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This value is not a function, but it was given 3 arguments:
 
@@ -4340,7 +4855,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TAG UNION TYPE in tmp/tag_union_open/Test.roc ────────────────────
+    ── UNFINISHED TAG UNION TYPE [R1225] in tmp/tag_union_open/Test.roc ────────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4362,7 +4877,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TAG UNION TYPE in tmp/tag_union_end/Test.roc ─────────────────────
+    ── UNFINISHED TAG UNION TYPE [R1225] in tmp/tag_union_end/Test.roc ─────────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4384,7 +4899,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── WEIRD TAG NAME in tmp/tag_union_lowercase_tag_name/Test.roc ─────────────────
+    ── WEIRD TAG NAME [R1225] in tmp/tag_union_lowercase_tag_name/Test.roc ─────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4405,7 +4920,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── WEIRD TAG NAME in tmp/tag_union_second_lowercase_tag_name/Test.roc ──────────
+    ── WEIRD TAG NAME [R1225] in tmp/tag_union_second_lowercase_tag_name/Test.roc ──
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4426,7 +4941,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_open/Test.roc ─────────────────────
+    ── UNFINISHED RECORD TYPE [R1216] in tmp/record_type_open/Test.roc ─────────────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4449,7 +4964,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_open_indent/Test.roc ──────────────
+    ── UNFINISHED RECORD TYPE [R1216] in tmp/record_type_open_indent/Test.roc ──────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4472,7 +4987,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_end/Test.roc ──────────────────────
+    ── UNFINISHED RECORD TYPE [R1216] in tmp/record_type_end/Test.roc ──────────────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4494,7 +5009,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_keyword_field_name/Test.roc ───────
+    ── UNFINISHED RECORD TYPE [R1218] in ...ecord_type_keyword_field_name/Test.roc ─
 
     I just started parsing a record type, but I got stuck on this field
     name:
@@ -4516,7 +5031,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_missing_comma/Test.roc ────────────
+    ── UNFINISHED RECORD TYPE [R1216] in tmp/record_type_missing_comma/Test.roc ────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4533,7 +5048,7 @@ mod test_reporting {
         record_type_tab,
         "f : { foo \t }",
         @r"
-    ── TAB CHARACTER in tmp/record_type_tab/Test.roc ───────────────────────────────
+    ── TAB CHARACTER [R1091] in tmp/record_type_tab/Test.roc ───────────────────────
 
     I encountered a tab character:
 
@@ -4548,7 +5063,7 @@ mod test_reporting {
         comment_with_tab,
         "# comment with a \t\n4",
         @r"
-    ── TAB CHARACTER in tmp/comment_with_tab/Test.roc ──────────────────────────────
+    ── TAB CHARACTER [R1091] in tmp/comment_with_tab/Test.roc ──────────────────────
 
     I encountered a tab character:
 
@@ -4563,7 +5078,7 @@ mod test_reporting {
         comment_with_control_character,
         "# comment with a \x07\n",
         @r"
-    ── ASCII CONTROL CHARACTER in tmp/comment_with_control_character/Test.roc ──────
+    ── ASCII CONTROL CHARACTER [R1093] in ...mment_with_control_character/Test.roc ─
 
     I encountered an ASCII control character:
 
@@ -4578,7 +5093,7 @@ mod test_reporting {
         record_type_carriage_return,
         "f : { \r foo }",
         @r"
-    ── MISPLACED CARRIAGE RETURN in tmp/record_type_carriage_return/Test.roc ───────
+    ── MISPLACED CARRIAGE RETURN [R1092] in ...ecord_type_carriage_return/Test.roc ─
 
     I encountered a stray carriage return (\r):
 
@@ -4598,7 +5113,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/type_in_parens_start/Test.roc ─────────────────
+    ── UNFINISHED PARENTHESES [R1228] in tmp/type_in_parens_start/Test.roc ─────────
 
     I am partway through parsing a type in parentheses, but I got stuck
     here:
@@ -4621,7 +5136,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/type_in_parens_end/Test.roc ───────────────────
+    ── UNFINISHED PARENTHESES [R1228] in tmp/type_in_parens_end/Test.roc ───────────
 
     I am partway through parsing a type in parentheses, but I got stuck
     here:
@@ -4646,7 +5161,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0216] in /code/proj/Main.roc ───────────────────────────────
 
     I am confused by this type name:
 
@@ -4657,7 +5172,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── DOUBLE DOT ──────────────────────────────────────────────────────────────────
+    //                ── DOUBLE DOT [R0310] ──────────────────────────────────────────────────────────
     //
     //                I encountered two dots in a row:
     //
@@ -4676,7 +5191,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0216] in /code/proj/Main.roc ───────────────────────────────
 
     I am confused by this type name:
 
@@ -4687,7 +5202,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── TRAILING DOT ────────────────────────────────────────────────────────────────
+    //                ── TRAILING DOT [R0342] ────────────────────────────────────────────────────────
     //
     //                I encountered a dot with nothing after it:
     //
@@ -4705,7 +5220,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_apply_stray_dot/Test.roc ────────────────────────
+    ── UNFINISHED TYPE [R1208] in tmp/type_apply_stray_dot/Test.roc ────────────────
 
     I just started parsing a type, but I got stuck here:
 
@@ -4726,7 +5241,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0216] in /code/proj/Main.roc ───────────────────────────────
 
     I am confused by this type name:
 
@@ -4737,7 +5252,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── WEIRD QUALIFIED NAME ────────────────────────────────────────────────────────
+    //                ── WEIRD QUALIFIED NAME [R0373] ────────────────────────────────────────────────
     //
     //                I encountered a number at the start of a qualified name segment:
     //
@@ -4757,7 +5272,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0216] in /code/proj/Main.roc ───────────────────────────────
 
     I am confused by this type name:
 
@@ -4777,7 +5292,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── MISSING FINAL EXPRESSION in tmp/def_missing_final_expression/Test.roc ───────
+    ── MISSING FINAL EXPRESSION [R1104] in ...ef_missing_final_expression/Test.roc ─
 
     I am partway through parsing a definition, but I got stuck here:
 
@@ -4805,7 +5320,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in tmp/expression_indentation_end/Test.roc ─────
+    ── INDENT ENDS AFTER EXPRESSION [R1122] in ...ression_indentation_end/Test.roc ─
 
     I am partway through parsing an expression, but I got stuck here:
 
@@ -4831,7 +5346,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED INLINE ALIAS in tmp/type_inline_alias/Test.roc ───────────────────
+    ── UNFINISHED INLINE ALIAS [R1215] in tmp/type_inline_alias/Test.roc ───────────
 
     I just started parsing an inline type alias, but I got stuck here:
 
@@ -4853,7 +5368,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── DOUBLE COMMA in tmp/type_double_comma/Test.roc ──────────────────────────────
+    ── DOUBLE COMMA [R1210] in tmp/type_double_comma/Test.roc ──────────────────────
 
     I just started parsing a function argument type, but I encountered two
     commas in a row:
@@ -4876,7 +5391,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_argument_no_arrow/Test.roc ──────────────────────
+    ── UNFINISHED TYPE [R1214] in tmp/type_argument_no_arrow/Test.roc ──────────────
 
     I am partway through parsing a type, but I got stuck here:
 
@@ -4899,7 +5414,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_argument_arrow_then_nothing/Test.roc ────────────
+    ── UNFINISHED TYPE [R1213] in tmp/type_argument_arrow_then_nothing/Test.roc ────
 
     I just started parsing a type, but I got stuck here:
 
@@ -4924,7 +5439,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `myDict` definition:
 
@@ -4959,7 +5474,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `myDict` definition:
 
@@ -4991,7 +5506,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── IF GUARD NO CONDITION in tmp/if_guard_without_condition/Test.roc ────────────
+    ── IF GUARD NO CONDITION [R1096] in tmp/if_guard_without_condition/Test.roc ────
 
     I just started parsing an if guard, but there is no guard condition:
 
@@ -5016,7 +5531,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PATTERN in tmp/empty_or_pattern/Test.roc ─────────────────────────
+    ── UNFINISHED PATTERN [R1187] in tmp/empty_or_pattern/Test.roc ─────────────────
 
     I just started parsing a pattern, but I got stuck here:
 
@@ -5041,7 +5556,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── MISSING ARROW in tmp/pattern_binds_keyword/Test.roc ─────────────────────────
+    ── MISSING ARROW [R1159] in tmp/pattern_binds_keyword/Test.roc ─────────────────
 
     I am partway through parsing a `when` expression, but got stuck here:
 
@@ -5077,7 +5592,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED WHEN in tmp/when_missing_arrow/Test.roc ──────────────────────────
+    ── UNFINISHED WHEN [R1163] in tmp/when_missing_arrow/Test.roc ──────────────────
 
     I was partway through parsing a `when` expression, but I got stuck here:
 
@@ -5110,7 +5625,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED ARGUMENT LIST in tmp/lambda_double_comma/Test.roc ────────────────
+    ── UNFINISHED ARGUMENT LIST [R1151] in tmp/lambda_double_comma/Test.roc ────────
 
     I am partway through parsing a function argument list, but I got stuck
     at this comma:
@@ -5131,7 +5646,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED ARGUMENT LIST in tmp/lambda_leading_comma/Test.roc ───────────────
+    ── UNFINISHED ARGUMENT LIST [R1151] in tmp/lambda_leading_comma/Test.roc ───────
 
     I am partway through parsing a function argument list, but I got stuck
     at this comma:
@@ -5147,7 +5662,7 @@ mod test_reporting {
     // this should get better with time
     // TODO this formerly gave
     //
-    //                ── UNFINISHED WHEN ─────────────────────────────────────────────────────────────
+    //                ── UNFINISHED WHEN [R0359] ─────────────────────────────────────────────────────
     //
     //                I was partway through parsing a `when` expression, but I got stuck here:
     //
@@ -5178,12 +5693,25 @@ mod test_reporting {
             "
         ),
         @r"
-    ── NOT END OF FILE in tmp/when_outdented_branch/Test.roc ───────────────────────
+    ── NOT END OF FILE [R1010] in tmp/when_outdented_branch/Test.roc ───────────────
 
     I expected to reach the end of the file, but got stuck here:
 
     6│       2 -> 2
              ^
+
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    4│>      when 4 is
+    5│>          5 -> 2
+
+    Other possibilities include:
+
+        _
+
+    I would have to crash if I saw one of those! Add branches for them!
     "
     );
 
@@ -5197,7 +5725,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNEXPECTED ARROW in tmp/when_over_indented_underscore/Test.roc ──────────────
+    ── UNEXPECTED ARROW [R1103] in tmp/when_over_indented_underscore/Test.roc ──────
 
     I am parsing a `when` expression right now, but this arrow is confusing
     me:
@@ -5234,7 +5762,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNEXPECTED ARROW in tmp/when_over_indented_int/Test.roc ─────────────────────
+    ── UNEXPECTED ARROW [R1103] in tmp/when_over_indented_int/Test.roc ─────────────
 
     I am parsing a `when` expression right now, but this arrow is confusing
     me:
@@ -5274,7 +5802,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED IF in tmp/if_outdented_then/Test.roc ─────────────────────────────
+    ── UNFINISHED IF [R1176] in tmp/if_outdented_then/Test.roc ─────────────────────
 
     I was partway through parsing an `if` expression, but I got stuck here:
 
@@ -5294,7 +5822,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED IF in tmp/if_missing_else/Test.roc ───────────────────────────────
+    ── UNFINISHED IF [R1177] in tmp/if_missing_else/Test.roc ───────────────────────
 
     I was partway through parsing an `if` expression, but I got stuck here:
 
@@ -5313,7 +5841,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED LIST in tmp/list_double_comma/Test.roc ───────────────────────────
+    ── UNFINISHED LIST [R1156] in tmp/list_double_comma/Test.roc ───────────────────
 
     I am partway through started parsing a list, but I got stuck here:
 
@@ -5333,7 +5861,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED LIST in tmp/list_without_end/Test.roc ────────────────────────────
+    ── UNFINISHED LIST [R1156] in tmp/list_without_end/Test.roc ────────────────────
 
     I am partway through started parsing a list, but I got stuck here:
 
@@ -5351,6 +5879,51 @@ mod test_reporting {
     "
     );
 
+    test_report!(
+        syntax_error_then_type_error,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            broken = [1, 2
+
+            main : Str
+            main = List.len broken
+            "#
+        ),
+        @r"
+    ── UNFINISHED LIST [R1156] in tmp/syntax_error_then_type_error/Test.roc ────────
+
+    I am partway through started parsing a list, but I got stuck here:
+
+    3│  broken = [1, 2
+                     ^
+
+    I was expecting to see a closing square bracket before this, so try
+    adding a ] and see if that helps?
+
+    Note: When I get stuck like this, it usually means that there is a
+    missing parenthesis or bracket somewhere earlier. It could also be a
+    stray keyword or operator.
+
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
+
+    Something is off with the body of the `main` definition:
+
+    5│  main : Str
+    6│  main = List.len broken
+               ^^^^^^^^^^^^^^^
+
+    This `len` call produces:
+
+        Nat
+
+    But the type annotation on `main` says it should be:
+
+        Str
+    "
+    );
+
     test_report!(
         number_double_dot,
         indoc!(
@@ -5359,7 +5932,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0219] in /code/proj/Main.roc ───────────────────────────────
 
     This float literal contains an invalid digit:
 
@@ -5377,7 +5950,7 @@ mod test_reporting {
         unicode_not_hex,
         r#""abc\u(zzzz)def""#,
         @r#"
-    ── WEIRD CODE POINT in tmp/unicode_not_hex/Test.roc ────────────────────────────
+    ── WEIRD CODE POINT [R1126] in tmp/unicode_not_hex/Test.roc ────────────────────
 
     I am partway through parsing a unicode code point, but I got stuck
     here:
@@ -5395,7 +5968,7 @@ mod test_reporting {
         unicode_too_large,
         r#""abc\u(110000)def""#,
         @r#"
-    ── INVALID UNICODE in /code/proj/Main.roc ──────────────────────────────────────
+    ── INVALID UNICODE [R0124] in /code/proj/Main.roc ──────────────────────────────
 
     This unicode code point is invalid:
 
@@ -5410,7 +5983,7 @@ mod test_reporting {
         weird_escape,
         r#""abc\qdef""#,
         @r#"
-    ── WEIRD ESCAPE in tmp/weird_escape/Test.roc ───────────────────────────────────
+    ── WEIRD ESCAPE [R1130] in tmp/weird_escape/Test.roc ───────────────────────────
 
     I was partway through parsing a  string literal, but I got stuck here:
 
@@ -5434,7 +6007,7 @@ mod test_reporting {
         single_quote_too_long,
         r"'abcdef'",
         @r#"
-    ── INVALID SCALAR in tmp/single_quote_too_long/Test.roc ────────────────────────
+    ── INVALID SCALAR [R1135] in tmp/single_quote_too_long/Test.roc ────────────────
 
     I am part way through parsing this scalar literal (character literal),
     but it's too long to fit in a U32 so it's not a valid scalar.
@@ -5451,7 +6024,7 @@ mod test_reporting {
         single_no_end,
         r#""there is no end"#,
         @r#"
-    ── ENDLESS STRING in tmp/single_no_end/Test.roc ────────────────────────────────
+    ── ENDLESS STRING [R1127] in tmp/single_no_end/Test.roc ────────────────────────
 
     I cannot find the end of this string:
 
@@ -5467,7 +6040,7 @@ mod test_reporting {
         multi_no_end,
         r#""""there is no end"#,
         @r#"
-    ── ENDLESS STRING in tmp/multi_no_end/Test.roc ─────────────────────────────────
+    ── ENDLESS STRING [R1128] in tmp/multi_no_end/Test.roc ─────────────────────────
 
     I cannot find the end of this block string:
 
@@ -5483,7 +6056,7 @@ mod test_reporting {
         multi_insufficient_indent,
         "    \"\"\"\n  testing\n    \"\"\"", // 4 space indent on the start, 2 space on the `testing` line
         @r#"
-    ── INSUFFICIENT INDENT IN MULTI-LINE STRING in ...insufficient_indent/Test.roc ─
+    ── INSUFFICIENT INDENT IN MULTI-LINE STRING [R1132] in ...ient_indent/Test.roc ─
 
     This multiline string is not sufficiently indented:
 
@@ -5505,7 +6078,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in tmp/dbg_without_final_expression/Test.roc ───
+    ── INDENT ENDS AFTER EXPRESSION [R1122] in ...ithout_final_expression/Test.roc ─
 
     I am partway through parsing a dbg statement, but I got stuck here:
 
@@ -5527,7 +6100,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in ...expect_without_final_expression/Test.roc ─
+    ── INDENT ENDS AFTER EXPRESSION [R1122] in ...ithout_final_expression/Test.roc ─
 
     I am partway through parsing an expect statement, but I got stuck
     here:
@@ -5553,7 +6126,7 @@ mod test_reporting {
             "#,
             ),
             @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -5579,7 +6152,7 @@ mod test_reporting {
                 $name,
                 &format!(r#"if Bool.true then "abc" else 1 {} 2"#, $op),
                 |golden| assert_eq!(golden, format!(
-r#"── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+r#"── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
 This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -5622,7 +6195,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `foo` record doesn’t have a `if` field:
 
@@ -5641,7 +6214,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [R0212] in /code/proj/Main.roc ──────────────────────────────────
 
     The Num module does not expose `if`:
 
@@ -5665,7 +6238,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse a record field access here:
 
@@ -5684,7 +6257,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am very confused by this field access:
 
@@ -5703,7 +6276,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am very confused by this field access
 
@@ -5724,7 +6297,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NAMING PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── NAMING PROBLEM [R0204] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse an identifier here:
 
@@ -5758,7 +6331,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/case_of/Test.roc ────────────────────────────────────
+    ── UNKNOWN OPERATOR [R0360] in tmp/case_of/Test.roc ────────────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -5791,7 +6364,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `bar` in this scope.
 
@@ -5816,7 +6389,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/invalid_operator/Test.roc ───────────────────────────
+    ── UNKNOWN OPERATOR [R1103] in tmp/invalid_operator/Test.roc ───────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -5841,7 +6414,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/double_plus/Test.roc ────────────────────────────────
+    ── UNKNOWN OPERATOR [R1103] in tmp/double_plus/Test.roc ────────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -5868,7 +6441,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/inline_hastype/Test.roc ─────────────────────────────
+    ── UNKNOWN OPERATOR [R1103] in tmp/inline_hastype/Test.roc ─────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -5899,7 +6472,7 @@ All branches in an `if` must have the same type!
         |golden| pretty_assertions::assert_eq!(
             golden,
             &format!(
-                r#"── UNKNOWN OPERATOR in tmp/wild_case_arrow/Test.roc ────────────────────────────
+                r#"── UNKNOWN OPERATOR [R1103] in tmp/wild_case_arrow/Test.roc ────────────────────
 
 This looks like an operator, but it's not one I recognize!
 
@@ -5932,7 +6505,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [R1026] in /code/proj/Main.roc ───────────────────────────────
 
                 I am partway through parsing a provides list, but I got stuck here:
 
@@ -5967,7 +6540,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [R1021] in /code/proj/Main.roc ───────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -5993,7 +6566,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r#"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [R1022] in /code/proj/Main.roc ───────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6020,7 +6593,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r#"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [R1023] in /code/proj/Main.roc ───────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6057,7 +6630,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r#"
-                ── BAD REQUIRES in /code/proj/Main.roc ─────────────────────────────────────────
+                ── BAD REQUIRES [R1040] in /code/proj/Main.roc ─────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6085,7 +6658,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r"
-                ── WEIRD IMPORTS in /code/proj/Main.roc ────────────────────────────────────────
+                ── WEIRD IMPORTS [R1061] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6112,7 +6685,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r"
-                ── WEIRD EXPOSES in /code/proj/Main.roc ────────────────────────────────────────
+                ── WEIRD EXPOSES [R1033] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing an `exposes` list, but I got stuck here:
 
@@ -6140,7 +6713,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r"
-                ── WEIRD MODULE NAME in /code/proj/Main.roc ────────────────────────────────────
+                ── WEIRD MODULE NAME [R1012] in /code/proj/Main.roc ────────────────────────────
 
                 I am partway through parsing a header, but got stuck here:
 
@@ -6166,7 +6739,7 @@ In roc, functions are always written as a lambda, like{}
             ),
             indoc!(
                 r#"
-                ── WEIRD APP NAME in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD APP NAME [R1013] in /code/proj/Main.roc ───────────────────────────────
 
                 I am partway through parsing a header, but got stuck here:
 
@@ -6190,7 +6763,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This value is not a function, but it was given 2 arguments:
 
@@ -6211,7 +6784,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This value is not a function, but it was given 2 arguments:
 
@@ -6233,7 +6806,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -6259,7 +6832,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_open/Test.roc ───────────────
+    ── UNFINISHED PARENTHESES [R1202] in tmp/pattern_in_parens_open/Test.roc ───────
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6282,7 +6855,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_end_comma/Test.roc ──────────
+    ── UNFINISHED PARENTHESES [R1202] in tmp/pattern_in_parens_end_comma/Test.roc ──
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6305,7 +6878,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_end/Test.roc ────────────────
+    ── UNFINISHED PARENTHESES [R1202] in tmp/pattern_in_parens_end/Test.roc ────────
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6329,7 +6902,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED FUNCTION in tmp/unfinished_closure_pattern_in_parens/Test.roc ────
+    ── UNFINISHED FUNCTION [R1152] in ...nished_closure_pattern_in_parens/Test.roc ─
 
     I was partway through parsing a  function, but I got stuck here:
 
@@ -6349,7 +6922,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_indent_open/Test.roc ────────
+    ── UNFINISHED PARENTHESES [R1202] in ...pattern_in_parens_indent_open/Test.roc ─
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6374,7 +6947,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `map` has an unexpected type:
 
@@ -6402,7 +6975,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `expect` condition needs to be a Bool:
 
@@ -6429,7 +7002,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to * has an unexpected type:
 
@@ -6444,7 +7017,7 @@ In roc, functions are always written as a lambda, like{}
 
         Num *
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `mult` definition:
 
@@ -6473,7 +7046,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to * has an unexpected type:
 
@@ -6488,7 +7061,7 @@ In roc, functions are always written as a lambda, like{}
 
         Num a
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `mult` definition:
 
@@ -6523,7 +7096,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -6533,7 +7106,7 @@ In roc, functions are always written as a lambda, like{}
     All builtin aliases are in scope by default, so I need this alias to
     have a different name!
 
-    ── TOO FEW TYPE ARGUMENTS in /code/proj/Main.roc ───────────────────────────────
+    ── TOO FEW TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ───────────────────────
 
     The `Result` alias expects 2 type arguments, but it got 1 instead:
 
@@ -6561,7 +7134,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -6571,7 +7144,7 @@ In roc, functions are always written as a lambda, like{}
     All builtin aliases are in scope by default, so I need this alias to
     have a different name!
 
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [R0151] in /code/proj/Main.roc ──────────────────────
 
     The `Result` alias expects 2 type arguments, but it got 3 instead:
 
@@ -6593,7 +7166,7 @@ In roc, functions are always written as a lambda, like{}
         ),
         // TODO: We should tell the user that we inferred `_` as `a`
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6627,7 +7200,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6659,7 +7232,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6699,7 +7272,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `inner` definition:
 
@@ -6728,7 +7301,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── NOT AN INLINE ALIAS in tmp/error_inline_alias_not_an_alias/Test.roc ─────────
+    ── NOT AN INLINE ALIAS [R1237] in tmp/error_inline_alias_not_an_alias/Test.roc ─
 
     The inline type after this `as` is not a type alias:
 
@@ -6748,7 +7321,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── QUALIFIED ALIAS NAME in tmp/error_inline_alias_qualified/Test.roc ───────────
+    ── QUALIFIED ALIAS NAME [R1238] in tmp/error_inline_alias_qualified/Test.roc ───
 
     This type alias has a qualified name:
 
@@ -6768,7 +7341,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE ARGUMENT NOT LOWERCASE in ..._inline_alias_argument_uppercase/Test.roc ─
+    ── TYPE ARGUMENT NOT LOWERCASE [R1239] in ...alias_argument_uppercase/Test.roc ─
 
     This alias type argument is not lowercase:
 
@@ -6792,7 +7365,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `isEmpty` has an unexpected type:
 
@@ -6828,7 +7401,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `c` has an unexpected type:
 
@@ -6861,7 +7434,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -6884,7 +7457,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -6906,7 +7479,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -6931,7 +7504,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 1st argument to `job` is weird:
 
@@ -6966,7 +7539,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `job` definition:
 
@@ -6997,7 +7570,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── NESTED DATATYPE in /code/proj/Main.roc ──────────────────────────────────────
+    ── NESTED DATATYPE [R0125] in /code/proj/Main.roc ──────────────────────────────
 
     `Nested` is a nested datatype. Here is one recursive usage of it:
 
@@ -7025,7 +7598,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── NESTED DATATYPE in /code/proj/Main.roc ──────────────────────────────────────
+    ── NESTED DATATYPE [R0125] in /code/proj/Main.roc ──────────────────────────────
 
     `Nested` is a nested datatype. Here is one recursive usage of it:
 
@@ -7073,7 +7646,7 @@ In roc, functions are always written as a lambda, like{}
 
                     let real = format!(indoc!(
                         r"
-                        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+                        ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
                         This 1st argument to `use` has an unexpected type:
 
@@ -7140,7 +7713,7 @@ In roc, functions are always written as a lambda, like{}
 
                     let real = format!(indoc!(
                         r"
-                        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+                        ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
                         The branches of this `when` expression don't match the condition:
 
@@ -7192,7 +7765,7 @@ In roc, functions are always written as a lambda, like{}
         ),
         // TODO: link to number suffixes
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -7215,7 +7788,7 @@ In roc, functions are always written as a lambda, like{}
         ),
         // TODO: link to number suffixes
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0220] in /code/proj/Main.roc ───────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -7237,7 +7810,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CONFLICTING NUMBER SUFFIX in /code/proj/Main.roc ────────────────────────────
+    ── CONFLICTING NUMBER SUFFIX [R0220] in /code/proj/Main.roc ────────────────────
 
     This number literal is an integer, but it has a float suffix:
 
@@ -7254,7 +7827,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CONFLICTING NUMBER SUFFIX in /code/proj/Main.roc ────────────────────────────
+    ── CONFLICTING NUMBER SUFFIX [R0219] in /code/proj/Main.roc ────────────────────
 
     This number literal is a float, but it has an integer suffix:
 
@@ -7267,7 +7840,7 @@ In roc, functions are always written as a lambda, like{}
         u8_overflow,
         "256u8",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7283,7 +7856,7 @@ In roc, functions are always written as a lambda, like{}
         negative_u8,
         "-1u8",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7299,7 +7872,7 @@ In roc, functions are always written as a lambda, like{}
         u16_overflow,
         "65536u16",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7315,7 +7888,7 @@ In roc, functions are always written as a lambda, like{}
         negative_u16,
         "-1u16",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7331,7 +7904,7 @@ In roc, functions are always written as a lambda, like{}
         u32_overflow,
         "4_294_967_296u32",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7347,7 +7920,7 @@ In roc, functions are always written as a lambda, like{}
         negative_u32,
         "-1u32",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7363,7 +7936,7 @@ In roc, functions are always written as a lambda, like{}
         u64_overflow,
         "18_446_744_073_709_551_616u64",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7379,7 +7952,7 @@ In roc, functions are always written as a lambda, like{}
         negative_u64,
         "-1u64",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7395,7 +7968,7 @@ In roc, functions are always written as a lambda, like{}
         negative_u128,
         "-1u128",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7411,7 +7984,7 @@ In roc, functions are always written as a lambda, like{}
         i8_overflow,
         "128i8",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7427,7 +8000,7 @@ In roc, functions are always written as a lambda, like{}
         i8_underflow,
         "-129i8",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7443,7 +8016,7 @@ In roc, functions are always written as a lambda, like{}
         i16_overflow,
         "32768i16",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7459,7 +8032,7 @@ In roc, functions are always written as a lambda, like{}
         i16_underflow,
         "-32769i16",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7475,7 +8048,7 @@ In roc, functions are always written as a lambda, like{}
         i32_overflow,
         "2_147_483_648i32",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7491,7 +8064,7 @@ In roc, functions are always written as a lambda, like{}
         i32_underflow,
         "-2_147_483_649i32",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7507,7 +8080,7 @@ In roc, functions are always written as a lambda, like{}
         i64_overflow,
         "9_223_372_036_854_775_808i64",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7523,7 +8096,7 @@ In roc, functions are always written as a lambda, like{}
         i64_underflow,
         "-9_223_372_036_854_775_809i64",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ─────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7539,7 +8112,7 @@ In roc, functions are always written as a lambda, like{}
         i128_overflow,
         "170_141_183_460_469_231_731_687_303_715_884_105_728i128",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [R0220] in /code/proj/Main.roc ──────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7561,7 +8134,7 @@ In roc, functions are always written as a lambda, like{}
         // TODO: this error message could be improved, e.g. something like "This argument can
         // be used as ... because of its literal value"
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7587,7 +8160,7 @@ In roc, functions are always written as a lambda, like{}
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7614,7 +8187,7 @@ In roc, functions are always written as a lambda, like{}
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7641,7 +8214,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -7672,7 +8245,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `R` alias is self-recursive in an invalid way:
 
@@ -7695,7 +8268,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `R` alias is self-recursive in an invalid way:
 
@@ -7719,7 +8292,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `Foo` alias is recursive in an invalid way:
 
@@ -7754,7 +8327,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -7774,7 +8347,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [R0208] in /code/proj/Main.roc ──────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -7795,7 +8368,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [R0208] in /code/proj/Main.roc ──────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -7809,7 +8382,7 @@ In roc, functions are always written as a lambda, like{}
 
     Note: It looks like there are no opaque types declared in this scope yet!
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `Age` is not used anywhere in your code.
 
@@ -7832,7 +8405,7 @@ In roc, functions are always written as a lambda, like{}
         // and checking it during can. The reason the error appears is because it is parsed as
         // Apply(Error(OtherModule), [@Age, 21])
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [R0208] in /code/proj/Main.roc ──────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -7841,7 +8414,7 @@ In roc, functions are always written as a lambda, like{}
 
     Note: It looks like there are no opaque types declared in this scope yet!
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -7868,7 +8441,7 @@ In roc, functions are always written as a lambda, like{}
         // `@Age` can be linked to the declaration of `Age` inside `age`, and a suggestion to
         // raise that declaration to the outer scope.
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `Age` is not used anywhere in your code.
 
@@ -7878,7 +8451,7 @@ In roc, functions are always written as a lambda, like{}
     If you didn't intend on using `Age` then remove it so future readers of
     your code don't wonder why it is there.
 
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [R0208] in /code/proj/Main.roc ──────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -7899,7 +8472,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [R0213] in /code/proj/Main.roc ──────────────────────────
 
     The `Task` module is not imported:
 
@@ -7931,7 +8504,7 @@ In roc, functions are always written as a lambda, like{}
         // TODO(opaques): error could be improved by saying that the opaque definition demands
         // that the argument be a U8, and linking to the definitin!
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -7960,7 +8533,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -7990,7 +8563,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `v` definition:
 
@@ -8028,7 +8601,7 @@ In roc, functions are always written as a lambda, like{}
         // TODO(opaques): error could be improved by saying that the user-provided pattern
         // probably wants to change "Age" to "@Age"!
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -8063,7 +8636,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 2nd pattern in this `when` does not match the previous ones:
 
@@ -8094,7 +8667,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -8132,7 +8705,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -8165,7 +8738,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `y` has an unexpected type:
 
@@ -8193,21 +8766,164 @@ In roc, functions are always written as a lambda, like{}
         indoc!(
             r#"
             x : [A]
+            y : Bool
+            when x is
+                A if y -> ""
+            "#
+        ),
+        @r#"
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    6│>      when x is
+    7│>          A if y -> ""
+
+    Other possibilities include:
+
+        A    (note the lack of an if clause)
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "#
+    );
+
+    test_report!(
+        guarded_branch_after_catch_all_is_redundant,
+        indoc!(
+            r#"
+            x : [A, B]
+            y : Bool
+            when x is
+                _ -> ""
+                A if y -> ""
+            "#
+        ),
+        @r#"
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
+
+    The 2nd pattern is redundant:
+
+    6│      when x is
+    7│          _ -> ""
+    8│          A if y -> ""
+                ^
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "#
+    );
+
+    test_report!(
+        guarded_or_pattern_with_every_alternative_covered_is_redundant,
+        indoc!(
+            r#"
+            x : [A, B, C]
+            y : Bool
+            when x is
+                A | B -> ""
+                B | A if y -> ""
+                C -> ""
+            "#
+        ),
+        @r#"
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
+
+    The 3rd pattern is redundant:
+
+    6│       when x is
+    7│           A | B -> ""
+    8│>          B | A if y -> ""
+    9│           C -> ""
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
+
+    The 4th pattern is redundant:
+
+    6│       when x is
+    7│           A | B -> ""
+    8│>          B | A if y -> ""
+    9│           C -> ""
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "#
+    );
+
+    test_no_problem!(
+        guarded_or_pattern_with_a_reachable_alternative_is_not_redundant,
+        indoc!(
+            r#"
+            x : [A, B, C]
+            y : Bool
+            when x is
+                A -> ""
+                A | B if y -> ""
+                _ -> ""
+            "#
+        )
+    );
+
+    test_report!(
+        branch_after_trivially_true_guard_is_redundant,
+        indoc!(
+            r#"
+            x : [A, B]
             when x is
                 A if Bool.true -> ""
+                A -> ""
+                B -> ""
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
+
+    The 2nd pattern is redundant:
+
+    5│       when x is
+    6│           A if Bool.true -> ""
+    7│>          A -> ""
+    8│           B -> ""
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "#
+    );
+
+    test_no_problem!(
+        trivially_true_guard_is_exhaustive,
+        indoc!(
+            r#"
+            x : [A]
+            when x is
+                A if Bool.true -> ""
+            "#
+        )
+    );
+
+    test_report!(
+        non_exhaustive_reports_missing_nested_patterns,
+        indoc!(
+            r#"
+            x : [A [B, C], D]
+            when x is
+                A B -> ""
+            "#
+        ),
+        @r#"
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
     5│>      when x is
-    6│>          A if Bool.true -> ""
+    6│>          A B -> ""
 
     Other possibilities include:
 
-        A    (note the lack of an if clause)
+        A C
+        D
 
     I would have to crash if I saw one of those! Add branches for them!
     "#
@@ -8222,7 +8938,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── INVALID_EXTENSION_TYPE in /code/proj/Main.roc ───────────────────────────────
+    ── INVALID_EXTENSION_TYPE [R0126] in /code/proj/Main.roc ───────────────────────
 
     This record extension type is invalid:
 
@@ -8243,7 +8959,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── INVALID_EXTENSION_TYPE in /code/proj/Main.roc ───────────────────────────────
+    ── INVALID_EXTENSION_TYPE [R0126] in /code/proj/Main.roc ───────────────────────
 
     This tag union extension type is invalid:
 
@@ -8270,7 +8986,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `UnknownType` in this scope.
 
@@ -8284,7 +9000,7 @@ In roc, functions are always written as a lambda, like{}
         Unsigned16
         Unsigned64
 
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `UnknownType` in this scope.
 
@@ -8311,7 +9027,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED ABILITY in tmp/ability_first_demand_not_indented_enough/Test.roc ─
+    ── UNFINISHED ABILITY [R1168] in ..._first_demand_not_indented_enough/Test.roc ─
 
     I was partway through parsing an ability definition, but I got stuck
     here:
@@ -8336,7 +9052,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-        ── UNFINISHED ABILITY in tmp/ability_demands_not_indented_with_first/Test.roc ──
+        ── UNFINISHED ABILITY [R1168] in ...y_demands_not_indented_with_first/Test.roc ─
 
         I was partway through parsing an ability definition, but I got stuck
         here:
@@ -8359,7 +9075,7 @@ In roc, functions are always written as a lambda, like{}
                 "
         ),
         @r"
-        ── UNFINISHED ABILITY in tmp/ability_demand_value_has_args/Test.roc ────────────
+        ── UNFINISHED ABILITY [R1170] in tmp/ability_demand_value_has_args/Test.roc ────
 
         I was partway through parsing an ability definition, but I got stuck
         here:
@@ -8383,7 +9099,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNFINISHED ABILITY in tmp/ability_non_signature_expression/Test.roc ─────────
+    ── UNFINISHED ABILITY [R1169] in tmp/ability_non_signature_expression/Test.roc ─
 
     I was partway through parsing an ability definition, but I got stuck
     here:
@@ -8406,7 +9122,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNBOUND TYPE VARIABLE in /code/proj/Main.roc ────────────────────────────────
+    ── UNBOUND TYPE VARIABLE [R0115] in /code/proj/Main.roc ────────────────────────
 
     The definition of `I` has an unbound type variable:
 
@@ -8428,7 +9144,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNBOUND TYPE VARIABLE in /code/proj/Main.roc ────────────────────────────────
+    ── UNBOUND TYPE VARIABLE [R0115] in /code/proj/Main.roc ────────────────────────
 
     The definition of `I` has an unbound type variable:
 
@@ -8450,7 +9166,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNBOUND TYPE VARIABLE in /code/proj/Main.roc ────────────────────────────────
+    ── UNBOUND TYPE VARIABLE [R0115] in /code/proj/Main.roc ────────────────────────
 
     The definition of `I` has 2 unbound type variables.
 
@@ -8474,7 +9190,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNBOUND TYPE VARIABLE in /code/proj/Main.roc ────────────────────────────────
+    ── UNBOUND TYPE VARIABLE [R0115] in /code/proj/Main.roc ────────────────────────
 
     The definition of `I` has an unbound type variable:
 
@@ -8496,7 +9212,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNBOUND TYPE VARIABLE in /code/proj/Main.roc ────────────────────────────────
+    ── UNBOUND TYPE VARIABLE [R0115] in /code/proj/Main.roc ────────────────────────
 
     The definition of `I` has an unbound type variable:
 
@@ -8519,7 +9235,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── ABILITY HAS TYPE VARIABLES in /code/proj/Main.roc ───────────────────────────
+    ── ABILITY HAS TYPE VARIABLES [R0127] in /code/proj/Main.roc ───────────────────
 
     The definition of the `MHash` ability includes type variables:
 
@@ -8529,7 +9245,7 @@ In roc, functions are always written as a lambda, like{}
     Abilities cannot depend on type variables, but their member values
     can!
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `MHash` is not used anywhere in your code.
 
@@ -8551,7 +9267,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── IMPLEMENTS CLAUSE IS NOT AN ABILITY in /code/proj/Main.roc ──────────────────
+    ── IMPLEMENTS CLAUSE IS NOT AN ABILITY [R0128] in /code/proj/Main.roc ──────────
 
     The type referenced in this "implements" clause is not an ability:
 
@@ -8570,7 +9286,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+        ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
         The `a` name is first defined here:
 
@@ -8599,7 +9315,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+        ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
         The `Ability` name is first defined here:
 
@@ -8626,7 +9342,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE in /code/proj/Main.roc ─────────────
+        ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE [R0131] in /code/proj/Main.roc ─────
 
         The definition of the ability member `ab` does not include an `implements`
         clause binding a type variable to the ability `Ability`:
@@ -8641,7 +9357,7 @@ In roc, functions are always written as a lambda, like{}
 
         Otherwise, the function does not need to be part of the ability!
 
-        ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+        ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
         `Ability` is not used anywhere in your code.
 
@@ -8663,7 +9379,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── ABILITY MEMBER BINDS MULTIPLE VARIABLES in /code/proj/Main.roc ──────────────
+        ── ABILITY MEMBER BINDS MULTIPLE VARIABLES [R0132] in /code/proj/Main.roc ──────
 
         The definition of the ability member `eq` includes multiple variables
         bound to the `MEq`` ability:`
@@ -8691,7 +9407,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── ILLEGAL IMPLEMENTS CLAUSE in /code/proj/Main.roc ────────────────────────────
+    ── ILLEGAL IMPLEMENTS CLAUSE [R0129] in /code/proj/Main.roc ────────────────────
 
     An `implements` clause is not allowed here:
 
@@ -8701,7 +9417,7 @@ In roc, functions are always written as a lambda, like{}
     `implements` clauses can only be specified on the top-level type
     annotations.
 
-    ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE in /code/proj/Main.roc ─────────────
+    ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE [R0131] in /code/proj/Main.roc ─────
 
     The definition of the ability member `hash` does not include an
     `implements` clause binding a type variable to the ability `MHash`:
@@ -8732,7 +9448,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+        ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
         Something is off with this specialization of `hash`:
 
@@ -8765,7 +9481,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -8791,7 +9507,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `hash` is not used anywhere in your code.
 
@@ -8820,7 +9536,7 @@ In roc, functions are always written as a lambda, like{}
         ),
         // TODO: the error message here could be seriously improved!
         @r"
-    ── OVERLOADED SPECIALIZATION in /code/proj/Main.roc ────────────────────────────
+    ── OVERLOADED SPECIALIZATION [R0148] in /code/proj/Main.roc ────────────────────
 
     This ability member specialization is already claimed to specialize
     another opaque type:
@@ -8833,7 +9549,7 @@ In roc, functions are always written as a lambda, like{}
     Ability specializations can only provide implementations for one
     opaque type, since all opaque types are different!
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This specialization of `hash` is overly general:
 
@@ -8873,7 +9589,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── OVERLOADED SPECIALIZATION in /code/proj/Main.roc ────────────────────────────
+    ── OVERLOADED SPECIALIZATION [R0148] in /code/proj/Main.roc ────────────────────
 
     This ability member specialization is already claimed to specialize
     another opaque type:
@@ -8904,7 +9620,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with this specialization of `eq`:
 
@@ -8939,7 +9655,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `hash` definition:
 
@@ -8980,7 +9696,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -8993,7 +9709,7 @@ In roc, functions are always written as a lambda, like{}
 
     Only builtin abilities can have generated implementations!
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9018,7 +9734,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── ABILITY NOT ON TOP-LEVEL in /code/proj/Main.roc ─────────────────────────────
+        ── ABILITY NOT ON TOP-LEVEL [R0133] in /code/proj/Main.roc ─────────────────────
 
         This ability definition is not on the top-level of a module:
 
@@ -9046,7 +9762,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `hashable` definition:
 
@@ -9092,7 +9808,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── ABILITY USED AS TYPE in /code/proj/Main.roc ─────────────────────────────────
+    ── ABILITY USED AS TYPE [R0134] in /code/proj/Main.roc ─────────────────────────
 
     You are attempting to use the ability `MHash` as a type directly:
 
@@ -9106,7 +9822,7 @@ In roc, functions are always written as a lambda, like{}
 
         a implements MHash
 
-    ── ABILITY USED AS TYPE in /code/proj/Main.roc ─────────────────────────────────
+    ── ABILITY USED AS TYPE [R0134] in /code/proj/Main.roc ─────────────────────────
 
     You are attempting to use the ability `MHash` as a type directly:
 
@@ -9136,7 +9852,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -9189,7 +9905,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-        ── WEIRD IMPORTS in tmp/imports_missing_comma/Test.roc ─────────────────────────
+        ── WEIRD IMPORTS [R1065] in tmp/imports_missing_comma/Test.roc ─────────────────
 
         I am partway through parsing a imports list, but I got stuck here:
 
@@ -9214,7 +9930,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-        ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+        ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
         This `when` does not cover all the possibilities:
 
@@ -9261,7 +9977,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-        ── SPECIALIZATION NOT ON TOP-LEVEL in /code/proj/Main.roc ──────────────────────
+        ── SPECIALIZATION NOT ON TOP-LEVEL [R0135] in /code/proj/Main.roc ──────────────
 
         This specialization of the `default` ability member is in a nested
         scope:
@@ -9286,7 +10002,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -9322,7 +10038,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0209] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `remove` has an unexpected type:
 
@@ -9343,7 +10059,7 @@ In roc, functions are always written as a lambda, like{}
     change the type annotation to be more specific? Maybe change the code
     to be more general?
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0201] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `new`:
 
@@ -9356,7 +10072,7 @@ In roc, functions are always written as a lambda, like{}
 
         { set : Set ∞ }
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [R0201] in /code/proj/Main.roc ────────────────────────────────
 
     I'm inferring a weird self-referential type for `goal`:
 
@@ -9386,7 +10102,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-        ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+        ── CIRCULAR DEFINITION [R0304] in /code/proj/Main.roc ──────────────────────────
 
         The `t1` definition is causing a very tricky infinite loop:
 
@@ -9414,7 +10130,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9442,7 +10158,7 @@ In roc, functions are always written as a lambda, like{}
         // TODO: this error message is quite unfortunate. We should remove the duplication, and
         // also support regions that point to things in other modules. See also https://github.com/roc-lang/roc/issues/3056.
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9479,7 +10195,7 @@ In roc, functions are always written as a lambda, like{}
                 "#
         ),
         @r"
-            ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+            ── CIRCULAR DEFINITION [R0304] in /code/proj/Main.roc ──────────────────────────
 
             The `t1` definition is causing a very tricky infinite loop:
 
@@ -9509,7 +10225,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── IMPLEMENTATION NOT FOUND in /code/proj/Main.roc ─────────────────────────────
+    ── IMPLEMENTATION NOT FOUND [R0137] in /code/proj/Main.roc ─────────────────────
 
     An implementation of `eq` could not be found in this scope:
 
@@ -9520,7 +10236,7 @@ In roc, functions are always written as a lambda, like{}
     another variable that implements this ability member, like
     { eq: myeq }
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9547,7 +10263,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
     Nothing is named `aMEq` in this scope.
 
@@ -9561,7 +10277,7 @@ In roc, functions are always written as a lambda, like{}
         myMEq
         eq
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9588,7 +10304,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── OPTIONAL ABILITY IMPLEMENTATION in /code/proj/Main.roc ──────────────────────
+    ── OPTIONAL ABILITY IMPLEMENTATION [R0139] in /code/proj/Main.roc ──────────────
 
     Ability implementations cannot be optional:
 
@@ -9599,7 +10315,7 @@ In roc, functions are always written as a lambda, like{}
 
 
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9626,7 +10342,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── OPTIONAL ABILITY IMPLEMENTATION in /code/proj/Main.roc ──────────────────────
+    ── OPTIONAL ABILITY IMPLEMENTATION [R0139] in /code/proj/Main.roc ──────────────
 
     Ability implementations cannot be optional:
 
@@ -9639,7 +10355,7 @@ In roc, functions are always written as a lambda, like{}
     record of implementations. For example,    implements [Encoding] will
     attempt to derive `Encoding`
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `Encoding` ability:
 
@@ -9664,7 +10380,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── QUALIFIED ABILITY IMPLEMENTATION in /code/proj/Main.roc ─────────────────────
+    ── QUALIFIED ABILITY IMPLEMENTATION [R0140] in /code/proj/Main.roc ─────────────
 
     This ability implementation is qualified:
 
@@ -9674,7 +10390,7 @@ In roc, functions are always written as a lambda, like{}
     Custom implementations must be defined in the local scope, and
     unqualified.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9699,7 +10415,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── ABILITY IMPLEMENTATION NOT IDENTIFIER in /code/proj/Main.roc ────────────────
+    ── ABILITY IMPLEMENTATION NOT IDENTIFIER [R0141] in /code/proj/Main.roc ────────
 
     This ability implementation is not an identifier:
 
@@ -9711,7 +10427,7 @@ In roc, functions are always written as a lambda, like{}
 
     Tip: consider defining this expression as a variable.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0145] in /code/proj/Main.roc ────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9738,7 +10454,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── DUPLICATE IMPLEMENTATION in /code/proj/Main.roc ─────────────────────────────
+    ── DUPLICATE IMPLEMENTATION [R0142] in /code/proj/Main.roc ─────────────────────
 
     This ability member implementation is duplicate:
 
@@ -9766,7 +10482,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── NOT AN ABILITY in /code/proj/Main.roc ───────────────────────────────────────
+    ── NOT AN ABILITY [R0143] in /code/proj/Main.roc ───────────────────────────────
 
     This identifier is not an ability in scope:
 
@@ -9789,7 +10505,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── ILLEGAL DERIVE in /code/proj/Main.roc ───────────────────────────────────────
+    ── ILLEGAL DERIVE [R0136] in /code/proj/Main.roc ───────────────────────────────
 
     This ability cannot be derived:
 
@@ -9798,7 +10514,8 @@ In roc, functions are always written as a lambda, like{}
 
     Only builtin abilities can be derived.
 
-    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`, `Inspect`
+    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`, `Inspect`,
+    `Sort`, `Default`
     "
     );
 
@@ -9812,7 +10529,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Encoding` ability for `A`:
 
@@ -9837,7 +10554,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Encoding` ability for `A`:
 
@@ -9889,7 +10606,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0201] in /code/proj/Main.roc ───────────────────────────────
 
     The `main` name is first defined here:
 
@@ -9904,7 +10621,7 @@ In roc, functions are always written as a lambda, like{}
     Since these variables have the same name, it's easy to use the wrong
     one by accident. Give one of them a new name.
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -9938,7 +10655,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+        ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
         Something is off with the body of the `withOpen` definition:
 
@@ -9973,7 +10690,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -10020,7 +10737,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `foo` has an unexpected type:
 
@@ -10065,7 +10782,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -10090,7 +10807,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -10127,7 +10844,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the 2nd branch of this `when` expression:
 
@@ -10164,7 +10881,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `map` has an unexpected type:
 
@@ -10190,7 +10907,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [R0146] in /code/proj/Main.roc ───────────────
 
         `x` is not bound in all patterns of this `when` branch
 
@@ -10201,7 +10918,7 @@ In roc, functions are always written as a lambda, like{}
         of the branch. Otherwise, the program would crash when it tries to use
         an identifier that wasn't bound!
 
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [R0146] in /code/proj/Main.roc ───────────────
 
         `y` is not bound in all patterns of this `when` branch
 
@@ -10212,7 +10929,7 @@ In roc, functions are always written as a lambda, like{}
         of the branch. Otherwise, the program would crash when it tries to use
         an identifier that wasn't bound!
 
-        ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+        ── UNUSED DEFINITION [R0107] in /code/proj/Main.roc ────────────────────────────
 
         `y` is not used in this `when` branch.
 
@@ -10234,7 +10951,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -10260,7 +10977,7 @@ In roc, functions are always written as a lambda, like{}
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+                r"── UNRECOGNIZED NAME [R0207] in /code/proj/Main.roc ────────────────────────────
 
                 Nothing is named `foo` in this scope.
 
@@ -10297,20 +11014,98 @@ In roc, functions are always written as a lambda, like{}
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                r"── TYPED HOLE [R0313] in /code/proj/Main.roc ───────────────────────────────────
 
-                An underscore is being used as a variable here:
+                This hole needs to be filled in:
 
                 6│      f 1 _ 1
                             ^
 
-                An underscore can be used to ignore a value when pattern matching, but
-                it cannot be used as a variable.
+                It is expected to have the type:
+
+                    Num *
+
+                These values are in scope:
+
+                    f : Num a, Num a, Num a -> Num a
+
+                Note: A program that reaches a hole while running will crash, so every
+                hole needs to be filled in eventually.
                 "
             ),
         )
     );
 
+    test_report!(
+        typed_hole_in_function_body,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            greet : Str, U8 -> Str
+            greet = \name, age ->
+                greeting = Str.concat "Hello, " name
+                when age is
+                    0 -> greeting
+                    _ -> Str.concat greeting _
+
+            main = greet "Ann" 3
+            "#
+        ),
+        @r###"
+    ── TYPED HOLE [R0313] in /code/proj/Main.roc ───────────────────────────────────
+
+    This hole needs to be filled in:
+
+    8│          _ -> Str.concat greeting _
+                                         ^
+
+    It is expected to have the type:
+
+        Str
+
+    These values are in scope:
+
+        greeting : Str
+        age : U8
+        name : Str
+
+    These values in scope have a type that fits the hole:
+
+        greeting
+        name
+
+    Note: A program that reaches a hole while running will crash, so every
+    hole needs to be filled in eventually.
+    "###
+    );
+
+    test_report!(
+        typed_hole_with_unconstrained_type,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = _
+            "#
+        ),
+        @r###"
+    ── TYPED HOLE [R0313] in /code/proj/Main.roc ───────────────────────────────────
+
+    This hole needs to be filled in:
+
+    3│  main = _
+               ^
+
+    It is expected to have the type:
+
+        *
+
+    Note: A program that reaches a hole while running will crash, so every
+    hole needs to be filled in eventually.
+    "###
+    );
+
     test_report!(
         call_with_declared_identifier_starting_with_underscore,
         indoc!(
@@ -10323,7 +11118,7 @@ In roc, functions are always written as a lambda, like{}
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                r"── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
                 This variable's name starts with an underscore:
 
@@ -10356,7 +11151,7 @@ In roc, functions are always written as a lambda, like{}
             golden,
             indoc!(
                 r"
-                ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
                 This variable's name starts with an underscore:
 
@@ -10383,7 +11178,7 @@ In roc, functions are always written as a lambda, like{}
             golden,
             indoc!(
                 r"
-                ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                ── SYNTAX PROBLEM [R0215] in /code/proj/Main.roc ───────────────────────────────
 
                 Underscores are not allowed in identifier names:
 
@@ -10410,7 +11205,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── BAD RECORD BUILDER in tmp/optional_field_in_record_builder/Test.roc ─────────
+    ── BAD RECORD BUILDER [R1119] in tmp/optional_field_in_record_builder/Test.roc ─
 
     I am partway through parsing a record builder, and I found an optional
     field:
@@ -10439,7 +11234,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── BAD RECORD UPDATE in tmp/record_update_builder/Test.roc ─────────────────────
+    ── BAD RECORD UPDATE [R1120] in tmp/record_update_builder/Test.roc ─────────────
 
     I am partway through parsing a record update, and I found a record
     builder field:
@@ -10465,7 +11260,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── MULTIPLE RECORD BUILDERS in /code/proj/Main.roc ─────────────────────────────
+    ── MULTIPLE RECORD BUILDERS [R0233] in /code/proj/Main.roc ─────────────────────
 
     This function is applied to multiple record builders:
 
@@ -10488,7 +11283,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── UNAPPLIED RECORD BUILDER in /code/proj/Main.roc ─────────────────────────────
+    ── UNAPPLIED RECORD BUILDER [R0234] in /code/proj/Main.roc ─────────────────────
 
     This record builder was not applied to a function:
 
@@ -10513,7 +11308,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This value is not a function, but it was given 1 argument:
 
@@ -10545,7 +11340,7 @@ In roc, functions are always written as a lambda, like{}
     //         "#
     //     ),
     //     @r#"
-    // ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    // ── TOO MANY ARGS [R0208] in /code/proj/Main.roc ────────────────────────────────
 
     // This value is an opaque type, so it cannot be called with an argument:
 
@@ -10572,7 +11367,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10584,7 +11379,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10596,7 +11391,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10608,7 +11403,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10638,7 +11433,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10650,7 +11445,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10662,7 +11457,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10674,7 +11469,7 @@ In roc, functions are always written as a lambda, like{}
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [R0147] in /code/proj/Main.roc ───────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10702,7 +11497,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0101] in /code/proj/Main.roc ────────────────────────────
 
     `hash` is not used anywhere in your code.
 
@@ -10729,7 +11524,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── WRONG SPECIALIZATION TYPE in /code/proj/Main.roc ────────────────────────────
+    ── WRONG SPECIALIZATION TYPE [R0310] in /code/proj/Main.roc ────────────────────
 
     This specialization of `hash` is not for the expected type:
 
@@ -10752,7 +11547,7 @@ In roc, functions are always written as a lambda, like{}
                 "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -10782,7 +11577,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [R0112] in /code/proj/Main.roc ─────────────────────────────────
 
     The `Recursive` opaque is self-recursive in an invalid way:
 
@@ -10804,7 +11599,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Decoding` ability for `A`:
 
@@ -10829,7 +11624,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Decoding` ability for `A`:
 
@@ -10884,7 +11679,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -10915,7 +11710,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -10945,7 +11740,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [R0106] in /code/proj/Main.roc ──────────────────────────────
 
     This function doesn't use `x`.
 
@@ -10977,7 +11772,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -11010,7 +11805,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -11047,7 +11842,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the `then` branch of this `if` expression:
 
@@ -11079,7 +11874,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [R0107] in /code/proj/Main.roc ────────────────────────────
 
     `foo` is not used in this `when` branch.
 
@@ -11105,7 +11900,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11134,7 +11929,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11200,7 +11995,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -11228,7 +12023,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -11255,7 +12050,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [R0353] in /code/proj/Main.roc ──────────────────────────
 
     The 2nd pattern will never be matched:
 
@@ -11282,7 +12077,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [R0353] in /code/proj/Main.roc ──────────────────────────
 
     The 2nd pattern will never be matched:
 
@@ -11294,7 +12089,7 @@ In roc, functions are always written as a lambda, like{}
     It's impossible to create a value of this shape, so this pattern can
     be safely removed!
 
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [R0353] in /code/proj/Main.roc ──────────────────────────
 
     The 3rd pattern will never be matched:
 
@@ -11319,7 +12114,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [R0111] in /code/proj/Main.roc ───────────────────────────────
 
     This opaque type has the same name as a builtin:
 
@@ -11341,7 +12136,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNUSED IMPORT in /code/proj/Main.roc ────────────────────────────────────────
+    ── UNUSED IMPORT [R0102] in /code/proj/Main.roc ────────────────────────────────
 
     `List.concat` is not used in this module.
 
@@ -11393,7 +12188,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [R0304] in /code/proj/Main.roc ──────────────────────────
 
     `main` is defined directly in terms of itself:
 
@@ -11415,7 +12210,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -11441,7 +12236,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -11469,7 +12264,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Hash` ability for `A`:
 
@@ -11494,7 +12289,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Hash` ability for `A`:
 
@@ -11577,7 +12372,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11604,7 +12399,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11650,7 +12445,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11686,7 +12481,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `shiftRightZfBy` has an unexpected type:
 
@@ -11701,7 +12496,7 @@ In roc, functions are always written as a lambda, like{}
 
         U8
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `shiftRightBy` has an unexpected type:
 
@@ -11716,7 +12511,7 @@ In roc, functions are always written as a lambda, like{}
 
         U8
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `shiftLeftBy` has an unexpected type:
 
@@ -11744,7 +12539,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `contains` has an unexpected type:
 
@@ -11771,7 +12566,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -11796,7 +12591,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -11826,7 +12621,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -11852,7 +12647,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -11868,6 +12663,83 @@ In roc, functions are always written as a lambda, like{}
     "
     );
 
+    test_report!(
+        derive_sort_for_f64,
+        indoc!(
+            r#"
+             app "test" provides [A] to "./platform"
+
+             A := F64 implements [Sort]
+             "#
+        ),
+        @r"
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
+
+    I can't derive an implementation of the `Sort` ability for `A`:
+
+    3│  A := F64 implements [Sort]
+                             ^^^^
+
+    Note: I can't derive `Sort.compare` for floating-point types. That's
+    because Roc's floating-point numbers cannot be put in a total order -
+    in Roc, `NaN` is neither less than, equal to, nor greater than any
+    other number. If a type doesn't have a total order, it cannot support
+    the `Sort` ability!
+
+    Tip: You can define a custom implementation of `Sort` for `A`.
+    "
+    );
+
+    test_report!(
+        derive_default_for_tag_union,
+        indoc!(
+            r#"
+             app "test" imports [Default.{ Default }] provides [A] to "./platform"
+
+             A := [B, C] implements [Default]
+             "#
+        ),
+        @r"
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
+
+    I can't derive an implementation of the `Default` ability for `A`:
+
+    3│  A := [B, C] implements [Default]
+                                ^^^^^^^
+
+    Note: I can only derive `Default.default` for records, because there is
+    no single obvious default for a tag union or a tuple.
+
+    Tip: You can define a custom implementation of `Default` for `A`.
+    "
+    );
+
+    test_report!(
+        derive_default_for_record_with_optional_field,
+        indoc!(
+            r#"
+             app "test" imports [Default.{ Default }] provides [A] to "./platform"
+
+             A := { x : Str, y ? Str } implements [Default]
+             "#
+        ),
+        @r"
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
+
+    I can't derive an implementation of the `Default` ability for `A`:
+
+    3│  A := { x : Str, y ? Str } implements [Default]
+                                              ^^^^^^^
+
+    Note: I can't derive `Default.default` for a record with an optional
+    field, which in this case is `.y`. Optional fields only get their
+    default where the record is destructured, so there is no value I could
+    put in it here.
+
+    Tip: You can define a custom implementation of `Default` for `A`.
+    "
+    );
+
     test_report!(
         derive_eq_for_non_eq_opaque,
         indoc!(
@@ -11880,7 +12752,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -11963,7 +12835,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11990,7 +12862,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12019,7 +12891,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12042,7 +12914,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12082,7 +12954,7 @@ In roc, functions are always written as a lambda, like{}
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12120,7 +12992,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [R0212] in /code/proj/Main.roc ──────────────────────────────────
 
     The Bool module does not expose `structuralEq`:
 
@@ -12134,7 +13006,7 @@ In roc, functions are always written as a lambda, like{}
         Bool.false
         Bool.isEq
 
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [R0212] in /code/proj/Main.roc ──────────────────────────────────
 
     The Bool module does not expose `structuralNotEq`:
 
@@ -12162,7 +13034,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12187,7 +13059,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     The 1st argument to `foo` is weird:
 
@@ -12216,7 +13088,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Hash` ability for `F`:
 
@@ -12227,7 +13099,7 @@ In roc, functions are always written as a lambda, like{}
 
     Tip: You can define a custom implementation of `Hash` for `F`.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Eq` ability for `F`:
 
@@ -12238,7 +13110,7 @@ In roc, functions are always written as a lambda, like{}
 
     Tip: You can define a custom implementation of `Eq` for `F`.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [R0306] in /code/proj/Main.roc ────────────
 
     I can't derive an implementation of the `Encoding` ability for `F`:
 
@@ -12261,7 +13133,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── DUPLICATE BOUND ABILITY in /code/proj/Main.roc ──────────────────────────────
+    ── DUPLICATE BOUND ABILITY [R0130] in /code/proj/Main.roc ──────────────────────
 
     I already saw that this type variable is bound to the `Hash` ability
     once before:
@@ -12287,7 +13159,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12324,7 +13196,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12362,7 +13234,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12395,7 +13267,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNFINISHED LIST PATTERN in tmp/list_pattern_not_terminated/Test.roc ─────────
+    ── UNFINISHED LIST PATTERN [R1198] in tmp/list_pattern_not_terminated/Test.roc ─
 
     I am partway through parsing a list pattern, but I got stuck here:
 
@@ -12416,7 +13288,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── INCORRECT REST PATTERN in tmp/list_pattern_weird_rest_pattern/Test.roc ──────
+    ── INCORRECT REST PATTERN [R1200] in ...st_pattern_weird_rest_pattern/Test.roc ─
 
     It looks like you may trying to write a list rest pattern, but it's
     not the form I expect:
@@ -12438,7 +13310,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── UNNECESSARY WILDCARD in /code/proj/Main.roc ─────────────────────────────────
+    ── UNNECESSARY WILDCARD [R0149] in /code/proj/Main.roc ─────────────────────────
 
     This type annotation has a wildcard type variable (`*`) that isn't
     needed.
@@ -12465,7 +13337,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── MULTIPLE LIST REST PATTERNS in /code/proj/Main.roc ──────────────────────────
+    ── MULTIPLE LIST REST PATTERNS [R0150] in /code/proj/Main.roc ──────────────────
 
     This list pattern match has multiple rest patterns:
 
@@ -12475,7 +13347,7 @@ In roc, functions are always written as a lambda, like{}
     I only support compiling list patterns with one .. pattern! Can you
     remove this additional one?
 
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12499,7 +13371,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── MULTIPLE LIST REST PATTERNS in /code/proj/Main.roc ──────────────────────────
+    ── MULTIPLE LIST REST PATTERNS [R0150] in /code/proj/Main.roc ──────────────────
 
     This list pattern match has multiple rest patterns:
 
@@ -12509,7 +13381,7 @@ In roc, functions are always written as a lambda, like{}
     I only support compiling list patterns with one .. pattern! Can you
     remove this additional one?
 
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12533,7 +13405,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0302] in /code/proj/Main.roc ────────────────────────────────
 
     This list element doesn't match the types of other elements in the
     pattern:
@@ -12560,7 +13432,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -12593,7 +13465,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12633,7 +13505,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12677,7 +13549,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
     
     The 2nd pattern is redundant:
     
@@ -12743,7 +13615,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12770,7 +13642,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12782,6 +13654,7 @@ In roc, functions are always written as a lambda, like{}
         []
         [_]
         [_, A, ..]
+        [B, B, ..]
 
     I would have to crash if I saw one of those! Add branches for them!
     "#
@@ -12813,7 +13686,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12840,7 +13713,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12851,6 +13724,7 @@ In roc, functions are always written as a lambda, like{}
 
         []
         [_]
+        [.., A, A]
         [.., _, B]
 
     I would have to crash if I saw one of those! Add branches for them!
@@ -12889,7 +13763,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12901,6 +13775,10 @@ In roc, functions are always written as a lambda, like{}
 
     Other possibilities include:
 
+        [A, .., A]
+        [_, .., A]
+        [B, .., B]
+        [_, .., B]
         [_, .., _]
 
     I would have to crash if I saw one of those! Add branches for them!
@@ -12920,7 +13798,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -12952,7 +13830,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13001,7 +13879,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13035,7 +13913,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13063,7 +13941,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13091,7 +13969,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13119,7 +13997,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [R0352] in /code/proj/Main.roc ────────────────────────────
 
     The 2nd pattern is redundant:
 
@@ -13140,7 +14018,7 @@ In roc, functions are always written as a lambda, like{}
             l : List [A]
 
             when l is
-                [ A, .. ] if Bool.true -> ""
+                [ A, .. ] if List.len l > 1 -> ""
                 [ A, .. ] -> ""
                 _ -> ""
             "#
@@ -13158,7 +14036,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13189,7 +14067,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This value passed to `crash` is not a string:
 
@@ -13214,7 +14092,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
     @r"
-    ── UNAPPLIED CRASH in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNAPPLIED CRASH [R0152] in /code/proj/Main.roc ──────────────────────────────
 
     This `crash` doesn't have a message given to it:
 
@@ -13235,7 +14113,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── OVERAPPLIED CRASH in /code/proj/Main.roc ────────────────────────────────────
+    ── OVERAPPLIED CRASH [R0153] in /code/proj/Main.roc ────────────────────────────
 
     This `crash` has too many values given to it:
 
@@ -13271,7 +14149,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13313,7 +14191,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── DEFINITION ONLY USED IN RECURSION in /code/proj/Main.roc ────────────────────
+    ── DEFINITION ONLY USED IN RECURSION [R0108] in /code/proj/Main.roc ────────────
 
     This definition is only used in recursion with itself:
 
@@ -13346,7 +14224,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── DEFINITIONs ONLY USED IN RECURSION in /code/proj/Main.roc ───────────────────
+    ── DEFINITIONs ONLY USED IN RECURSION [R0108] in /code/proj/Main.roc ───────────
 
     These 2 definitions are only used in mutual recursion with themselves:
 
@@ -13382,7 +14260,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── DEFINITION ONLY USED IN RECURSION in /code/proj/Main.roc ────────────────────
+    ── DEFINITION ONLY USED IN RECURSION [R0108] in /code/proj/Main.roc ────────────
 
     This definition is only used in recursion with itself:
 
@@ -13418,7 +14296,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r#"
-    ── DEFINITIONs ONLY USED IN RECURSION in /code/proj/Main.roc ───────────────────
+    ── DEFINITIONs ONLY USED IN RECURSION [R0108] in /code/proj/Main.roc ───────────
 
     These 2 definitions are only used in mutual recursion with themselves:
 
@@ -13457,7 +14335,7 @@ In roc, functions are always written as a lambda, like{}
             {one, str}
         "#),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     This 2nd argument to `concat` has an unexpected type:
 
@@ -13488,7 +14366,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -13525,7 +14403,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -13561,7 +14439,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `main` definition:
 
@@ -13599,7 +14477,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `main` definition:
 
@@ -13650,7 +14528,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13679,7 +14557,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13729,7 +14607,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13769,7 +14647,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0307] in /code/proj/Main.roc ────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13809,7 +14687,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [R0351] in /code/proj/Main.roc ───────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13857,7 +14735,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r#"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     The `parser` value is an opaque type, so it cannot be called with an
     argument:
@@ -13881,7 +14759,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -13912,7 +14790,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -13946,7 +14824,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -13980,7 +14858,7 @@ In roc, functions are always written as a lambda, like{}
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [R0301] in /code/proj/Main.roc ────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -14008,7 +14886,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [R0301] in /code/proj/Main.roc ─────────────────────────────────
 
     The `sub` function expects 2 arguments, but it got only 1:
 
@@ -14028,7 +14906,7 @@ In roc, functions are always written as a lambda, like{}
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [R0301] in /code/proj/Main.roc ─────────────────────────────────
 
     The `sub` function expects 2 arguments, but it got only 1:
 
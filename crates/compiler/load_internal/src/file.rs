@@ -7,6 +7,7 @@ use crate::module::{
     ModuleTiming, MonomorphizedModule, ParsedModule, ToplevelExpects, TypeCheckedModule,
 };
 use crate::module_cache::ModuleCache;
+use crate::type_cache::{self, TypeCache, VariablesFingerprint};
 use bumpalo::{collections::CollectIn, Bump};
 use crossbeam::channel::{bounded, Sender};
 use crossbeam::deque::{Injector, Stealer, Worker};
@@ -25,9 +26,9 @@ use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
-    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_IR_AFTER_TRMC,
-    ROC_PRINT_LOAD_LOG,
+    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_INLINING,
+    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION,
+    ROC_PRINT_IR_AFTER_STACK_ALLOC, ROC_PRINT_IR_AFTER_TRMC, ROC_PRINT_LOAD_LOG,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::internal_error;
@@ -38,29 +39,33 @@ use roc_module::symbol::{
     PackageQualified, Symbol,
 };
 use roc_mono::ir::{
-    CapturedSymbols, ExternalSpecializations, GlueLayouts, HostExposedLambdaSets, PartialProc,
-    Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
+    CapturedSymbols, ClosureParents, ExternalSpecializations, GlueLayouts, HostExposedLambdaSets,
+    PartialProc, Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
 };
 use roc_mono::layout::{
     GlobalLayoutInterner, LambdaName, Layout, LayoutCache, LayoutProblem, Niche, STLayoutInterner,
 };
 use roc_mono::reset_reuse;
-use roc_mono::{drop_specialization, inc_dec};
+use roc_mono::{borrow, drop_specialization, inc_dec};
 use roc_packaging::cache::RocCacheDir;
 use roc_parse::ast::{
     self, CommentOrNewline, Expr, ExtractSpaces, Pattern, Spaced, StrLiteral, ValueDef,
 };
+use roc_parse::expr::toplevel_defs_with_recovery;
 use roc_parse::header::{
     ExposedName, HeaderType, ImportsEntry, PackageEntry, PackageHeader, PlatformHeader, To,
     TypedIdent,
 };
-use roc_parse::module::module_defs;
-use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
+use roc_parse::parser::{FileError, SourceError, SyntaxError};
+use roc_problem::mono::UnoptimizedTailCall;
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
 #[cfg(not(target_family = "wasm"))]
+use roc_reporting::error::codes;
 use roc_reporting::report::to_https_problem_report_string;
-use roc_reporting::report::{to_file_problem_report_string, Palette, RenderTarget};
+use roc_reporting::report::{
+    to_file_problem_report_string, Palette, RecoveredParseProblem, RenderTarget,
+};
 use roc_solve::module::{extract_module_owned_implementations, SolveConfig, Solved, SolvedModule};
 use roc_solve::FunctionKind;
 use roc_solve_problem::TypeError;
@@ -110,6 +115,14 @@ pub struct LoadConfig {
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
     pub function_kind: FunctionKind,
+    /// Inline small procs and fold constants in the mono IR. LLVM does this by itself, but the
+    /// dev backends generate code for the mono IR as it is.
+    pub inline_and_fold: bool,
+    /// Evaluate top-level constants at compile time, so that they become static data.
+    pub eval_constants: bool,
+    /// Allocate boxes and recursive tags that never escape their proc on the stack. Only the
+    /// LLVM backend supports this.
+    pub stack_allocate: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -288,6 +301,8 @@ fn start_phase<'a>(
                     }
                 }
 
+                let cached_variables = load_cached_types(state, &parsed);
+
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
@@ -302,6 +317,8 @@ fn start_phase<'a>(
                     aliases,
                     abilities_store,
                     skip_constraint_gen,
+                    cached_variables,
+                    cached_types: state.cached_types.clone(),
                     exposed_module_ids: state.exposed_modules,
                 }
             }
@@ -326,6 +343,21 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                // Write the types to the cache once they're solved, unless they came from there.
+                let type_cache_path = match (
+                    &state.type_cache,
+                    state.module_cache.type_cache_keys.get(&module_id),
+                ) {
+                    (Some(type_cache), Some(key))
+                        if !module_id.is_builtin()
+                            && !state.cached_types.lock().contains_key(&module_id) =>
+                    {
+                        let (module_path, _) = &state.module_cache.sources[&module_id];
+                        Some(type_cache.path(module_path, *key))
+                    }
+                    _ => None,
+                };
+
                 #[cfg(debug_assertions)]
                 let checkmate = if roc_checkmate::is_checkmate_enabled() {
                     Some(roc_checkmate::Collector::new())
@@ -348,6 +380,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_types.clone(),
+                    type_cache_path,
                     derived_module,
                     //
                     #[cfg(debug_assertions)]
@@ -542,6 +575,37 @@ fn start_phase<'a>(
     vec![task]
 }
 
+/// Keys the module in the type cache by its source and the keys of its dependencies. If an earlier
+/// build already solved a module with the same key, its types are used instead of solving again,
+/// and this returns the fingerprint of the variables they were solved with.
+fn load_cached_types(state: &mut State, parsed: &ParsedModule) -> Option<VariablesFingerprint> {
+    let type_cache = state.type_cache.as_ref()?;
+
+    let module_id = parsed.module_id;
+    let dep_keys: Option<Vec<_>> = parsed
+        .imported_modules
+        .keys()
+        .chain(parsed.deps_by_name.values())
+        .map(|dep_id| state.module_cache.type_cache_keys.get(dep_id).copied())
+        .collect();
+
+    // A module can't be cached if any of its dependencies can't be.
+    let dep_keys = dep_keys?;
+
+    let key = type_cache.key(module_id, parsed.src, state.function_kind, dep_keys);
+    state.module_cache.type_cache_keys.insert(module_id, key);
+
+    // The builtins' types are already part of the compiler.
+    if module_id.is_builtin() {
+        return None;
+    }
+
+    let (type_state, variables) = type_cache.read(&parsed.module_path, key)?;
+    state.cached_types.lock().insert(module_id, type_state);
+
+    Some(variables)
+}
+
 /// Values used to render expect output
 pub struct ExpectMetadata<'a> {
     pub interns: Interns,
@@ -606,6 +670,7 @@ enum Msg<'a> {
         procs_base: ProcsBase<'a>,
         procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
         host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+        closure_parents: ClosureParents,
         update_mode_ids: UpdateModeIds,
         module_timing: ModuleTiming,
         subs: Subs,
@@ -701,7 +766,9 @@ struct State<'a> {
     pub module_cache: ModuleCache<'a>,
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub unoptimized_tail_calls: Vec<UnoptimizedTailCall>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    pub closure_parents: ClosureParents,
     pub toplevel_expects: ToplevelExpects,
     pub exposed_to_host: ExposedToHost,
 
@@ -732,15 +799,21 @@ struct State<'a> {
     pub render: RenderTarget,
     pub palette: Palette,
     pub exec_mode: ExecutionMode,
+    inline_and_fold: bool,
+    eval_constants: bool,
+    stack_allocate: bool,
 
     /// All abilities across all modules.
     pub world_abilities: WorldAbilities,
 
     make_specializations_pass: MakeSpecializationsPass,
 
-    // cached types (used for builtin modules, could include packages in the future too)
+    // cached types (used for builtin modules, and for other modules found in the type cache)
     cached_types: CachedTypeState,
 
+    /// Where the solved types of user and package modules are cached between builds
+    type_cache: Option<TypeCache>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
@@ -761,10 +834,14 @@ impl<'a> State<'a> {
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_types: MutMap<ModuleId, TypeState>,
+        roc_cache_dir: RocCacheDir<'_>,
         render: RenderTarget,
         palette: Palette,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
+        inline_and_fold: bool,
+        eval_constants: bool,
+        stack_allocate: bool,
     ) -> Self {
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let cache_dir = roc_packaging::cache::roc_cache_dir();
@@ -783,7 +860,9 @@ impl<'a> State<'a> {
             module_cache: ModuleCache::default(),
             dependencies,
             procedures: MutMap::default(),
+            unoptimized_tail_calls: Vec::new(),
             host_exposed_lambda_sets: std::vec::Vec::new(),
+            closure_parents: MutMap::default(),
             toplevel_expects: ToplevelExpects::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
@@ -798,9 +877,13 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            type_cache: TypeCache::new(roc_cache_dir),
             render,
             palette,
             exec_mode,
+            inline_and_fold,
+            eval_constants,
+            stack_allocate,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
             layout_interner: GlobalLayoutInterner::with_capacity(128, target_info),
@@ -875,6 +958,9 @@ enum BuildTask<'a> {
         abilities_store: PendingAbilitiesStore,
         exposed_module_ids: &'a [ModuleId],
         skip_constraint_gen: bool,
+        /// Set if the module's types come from the type cache
+        cached_variables: Option<VariablesFingerprint>,
+        cached_types: CachedTypeState,
     },
     Solve {
         module: Module,
@@ -890,6 +976,7 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedTypeState,
+        type_cache_path: Option<PathBuf>,
         derived_module: SharedDerivedModule,
 
         #[cfg(debug_assertions)]
@@ -1055,6 +1142,9 @@ pub fn load_and_typecheck_str<'a>(
         threading,
         exec_mode: ExecutionMode::Check,
         function_kind,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     };
 
     match load(
@@ -1323,6 +1413,9 @@ pub fn load<'a>(
             load_config.render,
             load_config.palette,
             load_config.exec_mode,
+            load_config.inline_and_fold,
+            load_config.eval_constants,
+            load_config.stack_allocate,
             roc_cache_dir,
         ),
         Threads::Many(threads) => load_multi_threaded(
@@ -1336,6 +1429,9 @@ pub fn load<'a>(
             load_config.palette,
             threads,
             load_config.exec_mode,
+            load_config.inline_and_fold,
+            load_config.eval_constants,
+            load_config.stack_allocate,
             roc_cache_dir,
         ),
     }
@@ -1352,6 +1448,9 @@ pub fn load_single_threaded<'a>(
     render: RenderTarget,
     palette: Palette,
     exec_mode: ExecutionMode,
+    inline_and_fold: bool,
+    eval_constants: bool,
+    stack_allocate: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1382,10 +1481,14 @@ pub fn load_single_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        roc_cache_dir,
         render,
         palette,
         number_of_workers,
         exec_mode,
+        inline_and_fold,
+        eval_constants,
+        stack_allocate,
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1679,6 +1782,9 @@ fn load_multi_threaded<'a>(
     palette: Palette,
     available_threads: usize,
     exec_mode: ExecutionMode,
+    inline_and_fold: bool,
+    eval_constants: bool,
+    stack_allocate: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1724,10 +1830,14 @@ fn load_multi_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        roc_cache_dir,
         render,
         palette,
         num_workers,
         exec_mode,
+        inline_and_fold,
+        eval_constants,
+        stack_allocate,
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2111,6 +2221,22 @@ macro_rules! debug_print_ir {
 
             let result = procs_string.join("\n");
 
+            eprintln!("{}", result);
+        })
+    };
+    ($state:expr, $interner:expr, $borrow_signatures:expr, $flag:path) => {
+        dbg_do!($flag, {
+            let procs_string = $state
+                .procedures
+                .iter()
+                .map(|(key, proc)| {
+                    let signature = $borrow_signatures.get(key);
+                    proc.to_pretty_with_borrow_signature($interner, 200, true, signature)
+                })
+                .collect::<Vec<_>>();
+
+            let result = procs_string.join("\n");
+
             eprintln!("{}", result);
         })
     };
@@ -2461,6 +2587,7 @@ fn update<'a>(
                 extend_header_with_builtin(header, ModuleId::DECODE);
                 extend_header_with_builtin(header, ModuleId::HASH);
                 extend_header_with_builtin(header, ModuleId::INSPECT);
+                extend_header_with_builtin(header, ModuleId::SORT);
             }
 
             state
@@ -2503,7 +2630,7 @@ fn update<'a>(
 
             Ok(state)
         }
-        Parsed(parsed) => {
+        Parsed(mut parsed) => {
             state
                 .module_cache
                 .sources
@@ -2511,6 +2638,12 @@ fn update<'a>(
 
             let module_id = parsed.module_id;
 
+            if !parsed.parse_problems.is_empty() {
+                let reports = render_recovered_parse_problems(&state, &mut parsed);
+
+                state.module_cache.parse_problems.insert(module_id, reports);
+            }
+
             state.module_cache.parsed.insert(module_id, parsed);
 
             let work = state.dependencies.notify(module_id, Phase::Parse);
@@ -2819,6 +2952,7 @@ fn update<'a>(
             procs_base,
             procedures,
             host_exposed_lambda_sets,
+            closure_parents,
             external_specializations_requested,
             module_timing,
             layout_cache,
@@ -2839,6 +2973,7 @@ fn update<'a>(
             state
                 .host_exposed_lambda_sets
                 .extend(host_exposed_lambda_sets);
+            state.closure_parents.extend(closure_parents);
             state.module_cache.late_specializations.insert(
                 module_id,
                 LateSpecializationsModule {
@@ -2954,9 +3089,34 @@ fn update<'a>(
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_SPECIALIZATION);
                     debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
 
+                    if state.inline_and_fold {
+                        roc_mono::inline::inline_and_fold(
+                            arena,
+                            &layout_interner,
+                            module_id,
+                            state.constrained_ident_ids.get_mut(&module_id).unwrap(),
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_INLINING);
+                        debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
+                    }
+
+                    if state.eval_constants {
+                        roc_mono_interp::eval_constants(
+                            arena,
+                            &mut layout_interner,
+                            module_id,
+                            state.constrained_ident_ids.get_mut(&module_id).unwrap(),
+                            &mut state.procedures,
+                        );
+
+                        debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
+                    }
+
                     let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
 
-                    roc_mono::tail_recursion::apply_trmc(
+                    state.unoptimized_tail_calls = roc_mono::tail_recursion::apply_trmc(
                         arena,
                         &mut layout_interner,
                         module_id,
@@ -2966,13 +3126,38 @@ fn update<'a>(
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_TRMC);
 
+                    // The host, and `roc test` for expects, call these procs without knowing
+                    // about borrowed parameters.
+                    let called_from_host = (state.exposed_to_host.top_level_values.keys())
+                        .chain(state.exposed_to_host.getters.iter())
+                        .chain(state.toplevel_expects.pure.keys())
+                        .chain(state.toplevel_expects.fx.keys())
+                        .copied()
+                        .chain(
+                            (state.host_exposed_lambda_sets.iter())
+                                .flat_map(|(lambda_name, _, set)| [lambda_name.name(), set.symbol]),
+                        );
+
+                    let borrow_signatures = borrow::infer_borrow_signatures(
+                        arena,
+                        &layout_interner,
+                        &state.procedures,
+                        called_from_host,
+                    );
+
                     inc_dec::insert_inc_dec_operations(
                         arena,
                         &layout_interner,
+                        &borrow_signatures,
                         &mut state.procedures,
                     );
 
-                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_REFCOUNT);
+                    debug_print_ir!(
+                        state,
+                        &layout_interner,
+                        borrow_signatures,
+                        ROC_PRINT_IR_AFTER_REFCOUNT
+                    );
 
                     drop_specialization::specialize_drops(
                         arena,
@@ -3000,6 +3185,19 @@ fn update<'a>(
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_RESET_REUSE);
 
+                    if state.stack_allocate {
+                        roc_mono::stack_alloc::stack_allocate_tags(
+                            arena,
+                            &layout_interner,
+                            module_id,
+                            ident_ids,
+                            &mut update_mode_ids,
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_STACK_ALLOC);
+                    }
+
                     // This is not safe with the new non-recursive RC updates that we do for tag unions
                     //
                     // Proc::optimize_refcount_operations(
@@ -3258,13 +3456,16 @@ fn finish_specialization<'a>(
     let State {
         toplevel_expects,
         procedures,
+        unoptimized_tail_calls,
         host_exposed_lambda_sets,
+        closure_parents,
         module_cache,
         platform_data,
         ..
     } = state;
 
     let ModuleCache {
+        parse_problems,
         type_problems,
         can_problems,
         sources,
@@ -3285,6 +3486,7 @@ fn finish_specialization<'a>(
     };
 
     Ok(MonomorphizedModule {
+        parse_problems,
         can_problems,
         type_problems,
         expectations: module_expectations,
@@ -3294,7 +3496,9 @@ fn finish_specialization<'a>(
         interns,
         layout_interner,
         procedures,
+        unoptimized_tail_calls,
         host_exposed_lambda_sets,
+        closure_parents,
         entry_point,
         sources,
         timings: state.timings,
@@ -3386,6 +3590,7 @@ fn finish(
         module_id: state.root_id,
         interns,
         solved,
+        parse_problems: state.module_cache.parse_problems,
         can_problems: state.module_cache.can_problems,
         type_problems: state.module_cache.type_problems,
         declarations_by_id,
@@ -3671,6 +3876,8 @@ fn load_module<'a>(
         "Hash", ModuleId::HASH
         "Inspect", ModuleId::INSPECT
         "TotallyNotJson", ModuleId::JSON
+        "Sort", ModuleId::SORT
+        "Default", ModuleId::DEFAULT
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, &module_name, arc_shorthands);
@@ -4650,6 +4857,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedTypeState,
+        type_cache_path: Option<PathBuf>,
         derived_module: SharedDerivedModule,
 
         #[cfg(debug_assertions)] checkmate: Option<roc_checkmate::Collector>,
@@ -4674,6 +4882,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            type_cache_path,
             derived_module,
 
             #[cfg(debug_assertions)]
@@ -5075,10 +5284,11 @@ fn run_solve<'a>(
     constraint: ConstraintSoa,
     function_kind: FunctionKind,
     pending_derives: PendingDerives,
-    var_store: VarStore,
+    mut var_store: VarStore,
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_types: CachedTypeState,
+    type_cache_path: Option<PathBuf>,
     derived_module: SharedDerivedModule,
 
     #[cfg(debug_assertions)] checkmate: Option<roc_checkmate::Collector>,
@@ -5095,55 +5305,43 @@ fn run_solve<'a>(
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
-    let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    function_kind,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                    //
-                    #[cfg(debug_assertions)]
-                    checkmate,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
+    let cached_type_state = cached_types.lock().remove(&module_id);
 
-                    #[cfg(debug_assertions)]
-                    checkmate: None,
-                },
-            }
-        } else {
-            run_solve_solve(
-                exposed_for_module,
-                types,
-                constraints,
-                constraint,
-                function_kind,
-                pending_derives,
-                var_store,
-                module,
-                derived_module,
-                //
-                #[cfg(debug_assertions)]
-                checkmate,
-            )
-        }
+    // The variables have to be fingerprinted before solving adds to them
+    let variables = type_cache_path
+        .as_ref()
+        .map(|_| type_cache::variables_fingerprint(&mut var_store, &decls));
+
+    let solve_result = match cached_type_state {
+        None => run_solve_solve(
+            exposed_for_module,
+            types,
+            constraints,
+            constraint,
+            function_kind,
+            pending_derives,
+            var_store,
+            module,
+            derived_module,
+            //
+            #[cfg(debug_assertions)]
+            checkmate,
+        ),
+        Some(TypeState {
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        }) => SolveResult {
+            solved: Solved(subs),
+            solved_implementations,
+            exposed_vars_by_symbol,
+            problems: vec![],
+            abilities_store: abilities,
+
+            #[cfg(debug_assertions)]
+            checkmate: None,
+        },
     };
 
     let SolveResult {
@@ -5157,6 +5355,20 @@ fn run_solve<'a>(
         checkmate,
     } = solve_result;
 
+    // Types with problems are solved again every time, so the problems are reported every time.
+    if let (Some(path), Some(variables)) =
+        (type_cache_path.filter(|_| problems.is_empty()), variables)
+    {
+        type_cache::write(
+            &path,
+            variables,
+            solved_subs.inner(),
+            &exposed_vars_by_symbol,
+            &abilities_store,
+            &solved_implementations,
+        );
+    }
+
     let exposed_types = roc_solve::module::exposed_types_storage_subs(
         module_id,
         &mut solved_subs,
@@ -5331,6 +5543,8 @@ fn canonicalize_and_constrain<'a>(
     imported_abilities_state: PendingAbilitiesStore,
     parsed: ParsedModule<'a>,
     skip_constraint_gen: bool,
+    cached_variables: Option<VariablesFingerprint>,
+    cached_types: &CachedTypeState,
     exposed_module_ids: &[ModuleId],
 ) -> CanAndCon {
     let canonicalize_start = Instant::now();
@@ -5429,6 +5643,22 @@ fn canonicalize_and_constrain<'a>(
 
     let mut constraints = Constraints::new();
 
+    // Cached types can only be used if canonicalization created the same variables as in the
+    // build that solved them. Otherwise, drop them and solve the module again.
+    let skip_constraint_gen = match cached_variables {
+        Some(cached_variables)
+            if cached_variables
+                != type_cache::variables_fingerprint(
+                    &mut var_store,
+                    &module_output.declarations,
+                ) =>
+        {
+            cached_types.lock().remove(&module_id);
+            false
+        }
+        _ => skip_constraint_gen,
+    };
+
     let constraint = if skip_constraint_gen {
         roc_can::constraint::Constraint::True
     } else {
@@ -5475,6 +5705,8 @@ fn canonicalize_and_constrain<'a>(
                         | ModuleId::SET
                         | ModuleId::HASH
                         | ModuleId::INSPECT
+                        | ModuleId::SORT
+                        | ModuleId::DEFAULT
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -5523,14 +5755,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
     let parse_state = header.parse_state;
-    let mut parsed_defs = match module_defs().parse(arena, parse_state.clone(), 0) {
-        Ok((_, success, _state)) => success,
-        Err((_, fail)) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &parse_state),
-            ));
-        }
-    };
+    let (mut parsed_defs, parse_problems) = toplevel_defs_with_recovery(arena, parse_state);
     for value in header.defined_values.into_iter() {
         // TODO: should these have a region?
         parsed_defs.push_value_def(value, Region::zero(), &[], &[]);
@@ -5571,6 +5796,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         exposed_ident_ids,
         exposed_imports,
         parsed_defs,
+        parse_problems,
         symbols_from_requires,
         header_type,
         header_comments: header_docs,
@@ -5740,6 +5966,7 @@ fn make_specializations<'a>(
     );
 
     let external_specializations_requested = procs.externals_we_need.clone();
+    let closure_parents = std::mem::take(&mut procs.closure_parents);
     let (procedures, host_exposed_lambda_sets, restored_procs_base) =
         procs.get_specialized_procs_without_rc();
 
@@ -5758,6 +5985,7 @@ fn make_specializations<'a>(
         procs_base: restored_procs_base,
         procedures,
         host_exposed_lambda_sets,
+        closure_parents,
         update_mode_ids,
         subs,
         expectations,
@@ -6388,6 +6616,8 @@ fn run_task<'a>(
             aliases,
             abilities_store,
             skip_constraint_gen,
+            cached_variables,
+            cached_types,
             exposed_module_ids,
         } => {
             let can_and_con = canonicalize_and_constrain(
@@ -6399,6 +6629,8 @@ fn run_task<'a>(
                 abilities_store,
                 parsed,
                 skip_constraint_gen,
+                cached_variables,
+                &cached_types,
                 exposed_module_ids,
             );
 
@@ -6418,6 +6650,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_path,
             derived_module,
 
             #[cfg(debug_assertions)]
@@ -6436,6 +6669,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_path,
             derived_module,
             //
             #[cfg(debug_assertions)]
@@ -6558,6 +6792,7 @@ fn to_import_cycle_report(
         filename,
         doc,
         title: "IMPORT CYCLE".to_string(),
+        code: Some(codes::IMPORT_CYCLE),
         severity: Severity::RuntimeError,
     };
 
@@ -6607,6 +6842,7 @@ fn to_incorrect_module_name_report<'a>(
         filename,
         doc,
         title: "INCORRECT MODULE NAME".to_string(),
+        code: Some(codes::INCORRECT_MODULE_NAME),
         severity: Severity::RuntimeError,
     };
 
@@ -6660,6 +6896,47 @@ fn to_parse_problem_report<'a>(
     buf
 }
 
+/// Renders the syntax errors that parsing this module recovered from. The defs they were in were
+/// skipped, so the rest of the module can still be checked.
+fn render_recovered_parse_problems<'a>(
+    state: &State<'a>,
+    parsed: &mut ParsedModule<'a>,
+) -> Vec<RecoveredParseProblem> {
+    let module_ids = { (*state.arc_modules).lock().clone() }.into_module_ids();
+
+    parsed
+        .parse_problems
+        .drain(..)
+        .map(|Loc { region, value }| {
+            let code = codes::syntax_error(&value);
+            let problem = FileError {
+                problem: SourceError {
+                    problem: value,
+                    bytes: parsed.src.as_bytes(),
+                },
+                filename: parsed.module_path.clone(),
+            };
+
+            // parsing did not add anything to IdentIds
+            let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
+
+            let report = to_parse_problem_report(
+                problem,
+                module_ids.clone(),
+                root_exposed_ident_ids,
+                state.render,
+                state.palette,
+            );
+
+            RecoveredParseProblem {
+                region,
+                code,
+                report,
+            }
+        })
+        .collect()
+}
+
 fn report_cannot_run(
     module_id: ModuleId,
     filename: PathBuf,
@@ -6690,6 +6967,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::NO_PLATFORM),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6705,6 +6983,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::ROOT_IS_INTERFACE),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6720,6 +6999,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::ROOT_IS_HOSTED),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6735,6 +7015,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::ROOT_IS_PLATFORM),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -11,11 +11,16 @@ use roc_module::ident::Ident;
 use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, PQModuleName, PackageQualified, Symbol,
 };
-use roc_mono::ir::{GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLayout, ProcsBase};
+use roc_mono::ir::{
+    ClosureParents, GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLayout, ProcsBase,
+};
 use roc_mono::layout::{LayoutCache, STLayoutInterner};
 use roc_parse::ast::{CommentOrNewline, Defs, TypeAnnotation, ValueDef};
 use roc_parse::header::{HeaderType, PackageName};
+use roc_parse::parser::SyntaxError;
+use roc_problem::mono::UnoptimizedTailCall;
 use roc_region::all::{Loc, Region};
+use roc_reporting::report::RecoveredParseProblem;
 use roc_solve::module::Solved;
 use roc_solve_problem::TypeError;
 use roc_types::subs::{ExposedTypesStorageSubs, Subs, VarStore, Variable};
@@ -32,6 +37,8 @@ pub struct LoadedModule {
     pub module_id: ModuleId,
     pub interns: Interns,
     pub solved: Solved<Subs>,
+    /// Syntax errors that parsing recovered from
+    pub parse_problems: MutMap<ModuleId, Vec<RecoveredParseProblem>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
@@ -52,6 +59,10 @@ impl LoadedModule {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.parse_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -169,10 +180,16 @@ pub struct MonomorphizedModule<'a> {
     pub interns: Interns,
     pub subs: Subs,
     pub layout_interner: STLayoutInterner<'a>,
+    /// Syntax errors that parsing recovered from
+    pub parse_problems: MutMap<ModuleId, Vec<RecoveredParseProblem>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    /// Recursive calls in tail position that still grow the stack
+    pub unoptimized_tail_calls: Vec<UnoptimizedTailCall>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    /// The function each anonymous closure was defined in
+    pub closure_parents: ClosureParents,
     pub toplevel_expects: ToplevelExpects,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
@@ -194,6 +211,8 @@ pub struct ParsedModule<'a> {
     pub exposed_ident_ids: IdentIds,
     pub exposed_imports: MutMap<Ident, (Symbol, Region)>,
     pub parsed_defs: Defs<'a>,
+    /// Syntax errors in defs that were skipped, so the rest of the module could be parsed
+    pub parse_problems: Vec<Loc<SyntaxError<'a>>>,
     pub symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     pub header_type: HeaderType<'a>,
     pub header_comments: &'a [CommentOrNewline<'a>],
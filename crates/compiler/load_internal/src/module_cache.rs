@@ -3,12 +3,14 @@ use crate::module::{
     CheckedModule, ConstrainedModule, FoundSpecializationsModule, LateSpecializationsModule,
     ModuleHeader, ParsedModule, TypeCheckedModule,
 };
+use crate::type_cache::TypeCacheKey;
 use roc_can::abilities::PendingAbilitiesStore;
 use roc_collections::{MutMap, MutSet, VecMap};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{ModuleId, PQModuleName, Symbol};
 use roc_mono::ir::ExternalSpecializations;
 use roc_problem::Severity;
+use roc_reporting::report::RecoveredParseProblem;
 use roc_solve_problem::TypeError;
 use roc_types::types::Alias;
 use std::path::PathBuf;
@@ -35,8 +37,10 @@ pub(crate) struct ModuleCache<'a> {
     pub(crate) imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub(crate) top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     pub(crate) documentation: VecMap<ModuleId, ModuleDocumentation>,
+    pub(crate) parse_problems: MutMap<ModuleId, Vec<RecoveredParseProblem>>,
     pub(crate) can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub(crate) type_problems: MutMap<ModuleId, Vec<TypeError>>,
+    pub(crate) type_cache_keys: MutMap<ModuleId, TypeCacheKey>,
 
     pub(crate) sources: MutMap<ModuleId, (PathBuf, &'a str)>,
 }
@@ -57,7 +61,11 @@ impl<'a> ModuleCache<'a> {
     }
 
     pub fn has_errors(&self) -> bool {
-        self.has_can_errors() || self.has_type_errors()
+        self.parse_problems
+            .values()
+            .any(|problems| !problems.is_empty())
+            || self.has_can_errors()
+            || self.has_type_errors()
     }
 }
 
@@ -88,6 +96,8 @@ impl Default for ModuleCache<'_> {
             HASH,
             INSPECT,
             JSON,
+            SORT,
+            DEFAULT,
         }
 
         Self {
@@ -105,8 +115,10 @@ impl Default for ModuleCache<'_> {
             imports: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
+            parse_problems: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
+            type_cache_keys: Default::default(),
             sources: Default::default(),
         }
     }
@@ -0,0 +1,272 @@
+//! An on-disk cache of the solved types of user and package modules.
+//!
+//! The builtins' types are solved when the compiler itself is built (see `roc_load/build.rs`).
+//! Every other module has its types written here the first time they are solved, so that later
+//! builds can skip constraint generation and solving for modules whose source and dependencies
+//! have not changed.
+//!
+//! Only the solved types are cached, not the canonical declarations, so such a module is still
+//! parsed and canonicalized. The cached types refer to the variables canonicalization created,
+//! so they are only correct if canonicalizing the same source gives the same variables in every
+//! build. Each entry records a fingerprint of those variables, and an entry whose fingerprint
+//! doesn't match is solved again instead of being used.
+//!
+//! Each module only keeps the types from its latest build, so that editing a module (or one of its
+//! dependencies, or upgrading the compiler) replaces its entry rather than growing the cache.
+use roc_can::abilities::AbilitiesStore;
+use roc_can::expr::Declarations;
+use roc_can::module::{ResolvedImplementations, TypeState};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_packaging::cache::RocCacheDir;
+use roc_solve::FunctionKind;
+use roc_types::subs::{Subs, VarStore, Variable};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Identifies the solved types of one module: a hash of the compiler, the module's id and
+/// source, and the keys of the modules it depends on.
+pub(crate) type TypeCacheKey = blake3::Hash;
+
+/// The variables canonicalization created for a module, see [variables_fingerprint].
+pub(crate) type VariablesFingerprint = blake3::Hash;
+
+/// Starts every file in the cache.
+const MAGIC: &[u8; 8] = b"roctypes";
+
+/// The magic bytes, a checksum of the serialized types and the fingerprint of the module's
+/// variables, padded so that the serialized types are aligned the same way as the builtins' are.
+const HEADER_LEN: usize = 80;
+
+#[derive(Debug, Clone)]
+pub(crate) struct TypeCache {
+    dir: PathBuf,
+    /// The serialized types can only be read by the exact compiler that wrote them.
+    compiler: blake3::Hash,
+}
+
+impl TypeCache {
+    /// There is no type cache unless we are allowed to use the roc cache dir, so tests and
+    /// build scripts always solve everything.
+    pub(crate) fn new(roc_cache_dir: RocCacheDir<'_>) -> Option<Self> {
+        if let RocCacheDir::Persistent(cache_dir) = roc_cache_dir {
+            Some(Self {
+                dir: cache_dir.join("types"),
+                compiler: compiler_fingerprint()?,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn key(
+        &self,
+        module_id: ModuleId,
+        src: &str,
+        function_kind: FunctionKind,
+        deps: impl IntoIterator<Item = TypeCacheKey>,
+    ) -> TypeCacheKey {
+        let mut deps: Vec<_> = deps.into_iter().collect();
+        deps.sort_unstable_by_key(|key| *key.as_bytes());
+        deps.dedup();
+
+        let mut hasher = KeyHasher(blake3::Hasher::new());
+
+        hasher.0.update(self.compiler.as_bytes());
+        // Solved types refer to other modules by their ids, which depend on the order modules
+        // were discovered in, so the same source can have different types from build to build.
+        module_id.hash(&mut hasher);
+        (function_kind as u8).hash(&mut hasher);
+        src.hash(&mut hasher);
+
+        for dep in deps {
+            hasher.0.update(dep.as_bytes());
+        }
+
+        hasher.0.finalize()
+    }
+
+    /// Entries are named after the module's source file and then the key, so that writing a new
+    /// entry can find and evict the ones from the module's earlier builds.
+    pub(crate) fn path(&self, module_path: &Path, key: TypeCacheKey) -> PathBuf {
+        let mut hasher = KeyHasher(blake3::Hasher::new());
+        module_path.hash(&mut hasher);
+        let module_hex = hasher.0.finalize().to_hex();
+
+        self.dir
+            .join(format!("{}-{}.dat", &module_hex[..16], key.to_hex()))
+    }
+
+    /// The types written for this key by an earlier build, if there are any and they are intact,
+    /// and the fingerprint of the variables they refer to.
+    pub(crate) fn read(
+        &self,
+        module_path: &Path,
+        key: TypeCacheKey,
+    ) -> Option<(TypeState, VariablesFingerprint)> {
+        let mut file = std::fs::File::open(self.path(module_path, key)).ok()?;
+        let len = file.metadata().ok()?.len() as usize;
+
+        // Deserializing reinterprets the bytes in place, so they need the alignment the builtins'
+        // types get from `include_bytes_align_as!`.
+        let mut buffer = vec![0u128; len / 16 + 1];
+        // SAFETY: the slice covers `len` of the buffer's initialized bytes, and any bytes make a valid u128
+        let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, len) };
+        file.read_exact(bytes).ok()?;
+
+        if bytes.len() < HEADER_LEN {
+            return None;
+        }
+
+        let (header, contents) = bytes.split_at(HEADER_LEN);
+        let (magic, header) = header.split_at(MAGIC.len());
+        let (checksum, header) = header.split_at(blake3::OUT_LEN);
+
+        if magic != MAGIC || checksum != blake3::hash(contents).as_bytes() {
+            return None;
+        }
+
+        let variables: [u8; blake3::OUT_LEN] = header[..blake3::OUT_LEN].try_into().ok()?;
+        let (type_state, read) = TypeState::deserialize(contents);
+
+        (read == contents.len()).then_some((type_state, variables.into()))
+    }
+}
+
+/// Identifies the variables canonicalization created for a module: how many there are, and the
+/// ones of its top-level definitions. Cached types can only be used with the variables they were
+/// solved with.
+pub(crate) fn variables_fingerprint(
+    var_store: &mut VarStore,
+    decls: &Declarations,
+) -> VariablesFingerprint {
+    let mut hasher = KeyHasher(blake3::Hasher::new());
+
+    var_store.peek().hash(&mut hasher);
+    for (symbol, var) in decls.symbols.iter().zip(decls.variables.iter()) {
+        symbol.value.hash(&mut hasher);
+        var.hash(&mut hasher);
+    }
+
+    hasher.0.finalize()
+}
+
+/// Writes a module's solved types to the cache. The cache is only an optimization, so if this
+/// fails, the module's types will just be solved again next time.
+pub(crate) fn write(
+    path: &Path,
+    variables: VariablesFingerprint,
+    subs: &Subs,
+    exposed_vars_by_symbol: &[(Symbol, Variable)],
+    abilities: &AbilitiesStore,
+    solved_implementations: &ResolvedImplementations,
+) {
+    // Derived implementations are numbered in the order they are first needed in a build, so
+    // types that refer to them only make sense in the build that solved them.
+    let refers_to_derived = subs.symbol_names.iter().any(|symbol| {
+        matches!(
+            symbol.module_id(),
+            ModuleId::DERIVED_GEN | ModuleId::DERIVED_SYNTH
+        )
+    });
+
+    if !refers_to_derived {
+        let _ = write_help(
+            path,
+            variables,
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        );
+    }
+}
+
+fn write_help(
+    path: &Path,
+    variables: VariablesFingerprint,
+    subs: &Subs,
+    exposed_vars_by_symbol: &[(Symbol, Variable)],
+    abilities: &AbilitiesStore,
+    solved_implementations: &ResolvedImplementations,
+) -> io::Result<()> {
+    let mut contents = Vec::new();
+
+    TypeState::serialize_parts(
+        subs,
+        exposed_vars_by_symbol,
+        abilities,
+        solved_implementations,
+        &mut contents,
+    )?;
+
+    let dir = path.parent().unwrap();
+    std::fs::create_dir_all(dir)?;
+
+    // Other builds may be reading the cache at the same time, so only move the file into place
+    // once it is complete.
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+
+    file.write_all(MAGIC)?;
+    file.write_all(blake3::hash(&contents).as_bytes())?;
+    file.write_all(variables.as_bytes())?;
+    file.write_all(&[0; HEADER_LEN - MAGIC.len() - 2 * blake3::OUT_LEN])?;
+    file.write_all(&contents)?;
+    file.persist(path)?;
+
+    evict_earlier_builds(path)
+}
+
+/// Removes the other entries for the same module as `path`. Another build of the module may be
+/// reading one of them at the same time, but then it just solves its types again.
+fn evict_earlier_builds(path: &Path) -> io::Result<()> {
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    let module_prefix = match file_name.split_once('-') {
+        Some((module_hex, _)) => format!("{module_hex}-"),
+        None => return Ok(()),
+    };
+
+    for entry in std::fs::read_dir(path.parent().unwrap())? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if name.starts_with(&module_prefix) && name != file_name {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+/// The size and modification time of the running compiler, which change whenever it is rebuilt.
+fn compiler_fingerprint() -> Option<blake3::Hash> {
+    let metadata = std::env::current_exe().ok()?.metadata().ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&metadata.len().to_le_bytes());
+    hasher.update(&modified.as_nanos().to_le_bytes());
+
+    Some(hasher.finalize())
+}
+
+/// Feeds values that implement [Hash] into a [blake3::Hasher], which is stable across builds
+/// unlike the standard library's hashers.
+struct KeyHasher(blake3::Hasher);
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        let hash = self.0.finalize();
+
+        u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
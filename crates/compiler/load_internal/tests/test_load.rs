@@ -36,20 +36,21 @@ use roc_types::pretty_print::DebugPrint;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-fn load_and_typecheck(
-    arena: &Bump,
+fn load_and_typecheck<'a>(
+    arena: &'a Bump,
     filename: PathBuf,
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
     function_kind: FunctionKind,
-) -> Result<LoadedModule, LoadingProblem> {
+    roc_cache_dir: RocCacheDir<'_>,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
     use LoadResult::*;
 
     let load_start = LoadStart::from_path(
         arena,
         filename,
         RenderTarget::Generic,
-        RocCacheDir::Disallowed,
+        roc_cache_dir,
         DEFAULT_PALETTE,
     )?;
     let load_config = LoadConfig {
@@ -59,6 +60,9 @@ fn load_and_typecheck(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     };
 
     match roc_load_internal::file::load(
@@ -66,7 +70,7 @@ fn load_and_typecheck(
         load_start,
         exposed_types,
         Default::default(), // these tests will re-compile the builtins
-        RocCacheDir::Disallowed,
+        roc_cache_dir,
         load_config,
     )? {
         Monomorphized(_) => unreachable!(""),
@@ -113,6 +117,16 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
         Ok(Err(LoadingProblem::FormattedReport(buf))) => Err(buf),
         Ok(Err(loading_problem)) => Err(format!("{loading_problem:?}")),
         Ok(Ok(mut loaded_module)) => {
+            let parse_problems: Vec<String> = loaded_module
+                .parse_problems
+                .drain()
+                .flat_map(|(_, problems)| problems)
+                .map(|problem| problem.report)
+                .collect();
+            if !parse_problems.is_empty() {
+                return Err(parse_problems.join("\n\n"));
+            }
+
             let home = loaded_module.module_id;
             let (filepath, src) = loaded_module.sources.get(&home).unwrap();
 
@@ -185,6 +199,7 @@ fn multiple_modules_help<'a>(
             Default::default(),
             TARGET_INFO,
             FunctionKind::LambdaSet,
+            RocCacheDir::Disallowed,
         )
     };
 
@@ -205,6 +220,7 @@ fn load_fixture(
         subs_by_module,
         TARGET_INFO,
         FunctionKind::LambdaSet,
+        RocCacheDir::Disallowed,
     );
     let mut loaded_module = match loaded {
         Ok(x) => x,
@@ -356,6 +372,140 @@ fn import_transitive_alias() {
     assert!(multiple_modules("import_transitive_alias", modules).is_ok());
 }
 
+#[test]
+fn type_cache_reuses_and_invalidates_solved_types() {
+    let src_dir = roc_test_utils::TmpDir::new("tmp/type_cache_reuses_and_invalidates_solved_types");
+    let cache_dir =
+        roc_test_utils::TmpDir::new("tmp/type_cache_reuses_and_invalidates_solved_types_cache");
+
+    let write_dep = |value: &str| {
+        std::fs::write(
+            src_dir.path().join("Dep.roc"),
+            format!("interface Dep exposes [value] imports []\n\nvalue = {value}\n"),
+        )
+        .unwrap()
+    };
+    std::fs::write(
+        src_dir.path().join("Main.roc"),
+        "interface Main exposes [main, both] imports [Dep]\n\nmain = Dep.value\n\nboth = [main, Dep.value]\n",
+    )
+    .unwrap();
+
+    let load = |expected_main_type: &str, expected_both_type: &str| {
+        let arena = Bump::new();
+        let loaded = load_and_typecheck(
+            &arena,
+            src_dir.path().join("Main.roc"),
+            Default::default(),
+            TARGET_INFO,
+            FunctionKind::LambdaSet,
+            RocCacheDir::Persistent(cache_dir.path()),
+        )
+        .expect("Test module failed to load");
+
+        expect_types(
+            loaded,
+            hashmap! { "main" => expected_main_type, "both" => expected_both_type },
+        );
+
+        // Every write replaces the file, so unchanged modification times mean nothing was solved.
+        let mut cached: Vec<_> = std::fs::read_dir(cache_dir.path().join("types"))
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                (entry.path(), entry.metadata().unwrap().modified().unwrap())
+            })
+            .collect();
+        cached.sort();
+        cached
+    };
+
+    write_dep("1");
+    let first = load("Num *", "List (Num *)");
+    assert_eq!(first.len(), 2);
+
+    let second = load("Num *", "List (Num *)");
+    assert_eq!(first, second);
+
+    // Main's source is unchanged, but its types depend on Dep's, so both are solved again and
+    // replace their entries from the first build.
+    write_dep("\"one\"");
+    let third = load("Str", "List Str");
+    assert_eq!(third.len(), 2);
+    assert!(third.iter().all(|entry| !first.contains(entry)));
+}
+
+#[test]
+fn type_cache_needs_the_same_variables() {
+    let src_dir = roc_test_utils::TmpDir::new("tmp/type_cache_needs_the_same_variables");
+    let cache_dir = roc_test_utils::TmpDir::new("tmp/type_cache_needs_the_same_variables_cache");
+
+    // Closures, patterns, records and abilities all create variables during canonicalization
+    std::fs::write(
+        src_dir.path().join("Main.roc"),
+        indoc!(
+            r#"
+            interface Main exposes [main] imports []
+
+            Shape := [Square U64, Rect { w : U64, h : U64 }] implements [Eq]
+
+            area = \@Shape shape ->
+                when shape is
+                    Square side -> side * side
+                    Rect { w, h } -> w * h
+
+            main =
+                shapes = [@Shape (Square 1), @Shape (Rect { w: 2, h: 3 })]
+                List.map shapes \shape -> (area shape, shape == @Shape (Square 1))
+            "#
+        ),
+    )
+    .unwrap();
+
+    let load = || {
+        let arena = Bump::new();
+        let loaded = load_and_typecheck(
+            &arena,
+            src_dir.path().join("Main.roc"),
+            Default::default(),
+            TARGET_INFO,
+            FunctionKind::LambdaSet,
+            RocCacheDir::Persistent(cache_dir.path()),
+        )
+        .expect("Test module failed to load");
+
+        expect_types(
+            loaded,
+            hashmap! {
+                "#Shape_isEq" => "Shape, Shape -> Bool",
+                "area" => "Shape -> U64",
+                "main" => "List ( U64, Bool )*",
+            },
+        );
+
+        let entry = std::fs::read_dir(cache_dir.path().join("types"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        (entry.path(), entry.metadata().unwrap().modified().unwrap())
+    };
+
+    // If canonicalizing the same source numbered its variables differently, the cached types
+    // would be dropped and solved again, which writes the entry again.
+    let (path, first) = load();
+    let (_, second) = load();
+    assert_eq!(first, second);
+
+    // An entry solved with other variables is solved again rather than used
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[40] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    let (_, third) = load();
+    assert_ne!(second, third);
+    assert_ne!(std::fs::read(&path).unwrap(), bytes);
+}
+
 #[test]
 fn interface_with_deps() {
     let subs_by_module = Default::default();
@@ -368,6 +518,7 @@ fn interface_with_deps() {
         subs_by_module,
         TARGET_INFO,
         FunctionKind::LambdaSet,
+        RocCacheDir::Disallowed,
     );
 
     let mut loaded_module = loaded.expect("Test module failed to load");
@@ -641,7 +792,7 @@ fn parse_problem() {
             report,
             indoc!(
                 "
-                    ── UNFINISHED LIST in tmp/parse_problem/Main.roc ───────────────────────────────
+                    ── UNFINISHED LIST [R1156] in tmp/parse_problem/Main.roc ───────────────────────
 
                     I am partway through started parsing a list, but I got stuck here:
 
@@ -847,7 +998,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
         err,
         indoc!(
             r"
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [R0209] in ...e_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -861,7 +1012,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [R0209] in ...e_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -875,7 +1026,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── UNUSED IMPORT in ...aque_wrapped_unwrapped_outside_defining_module/Main.roc ─
+                ── UNUSED IMPORT [R0103] in ...pped_unwrapped_outside_defining_module/Main.roc ─
 
                 Nothing from Age is used in this module.
 
@@ -930,7 +1081,7 @@ fn issue_2863_module_type_does_not_exist() {
                 report,
                 indoc!(
                     "
-                        ── UNRECOGNIZED NAME in tmp/issue_2863_module_type_does_not_exist/main.roc ─────
+                        ── UNRECOGNIZED NAME [R0207] in ...ue_2863_module_type_does_not_exist/main.roc ─
 
                         Nothing is named `DoesNotExist` in this scope.
 
@@ -1006,7 +1157,7 @@ fn module_doesnt_match_file_path() {
         err,
         indoc!(
             r"
-            ── WEIRD MODULE NAME in tmp/module_doesnt_match_file_path/Age.roc ──────────────
+            ── WEIRD MODULE NAME [R1017] in tmp/module_doesnt_match_file_path/Age.roc ──────
 
             This module name does not correspond with the file path it is defined
             in:
@@ -1039,7 +1190,7 @@ fn module_cyclic_import_itself() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_itself/Age.roc ─────────────────────
+            ── IMPORT CYCLE [R0407] in tmp/module_cyclic_import_itself/Age.roc ─────────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -1084,7 +1235,7 @@ fn module_cyclic_import_transitive() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_transitive/Age.roc ─────────────────
+            ── IMPORT CYCLE [R0407] in tmp/module_cyclic_import_transitive/Age.roc ─────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -1132,7 +1283,7 @@ fn nested_module_has_incorrect_name() {
         err,
         indoc!(
             r"
-            ── INCORRECT MODULE NAME in tmp/nested_module_has_incorrect_name/Dep/Foo.roc ───
+            ── INCORRECT MODULE NAME [R0408] in ...d_module_has_incorrect_name/Dep/Foo.roc ─
 
             This module has a different name than I expected:
 
@@ -1213,7 +1364,7 @@ fn non_roc_file_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/non_roc_file_extension/main.md ────────────────────────
+        ── NOT A ROC FILE [R0418] in tmp/non_roc_file_extension/main.md ────────────────
 
         I expected a file with extension `.roc` or without extension.
         Instead I received a file with extension `.md`."
@@ -1245,7 +1396,7 @@ fn roc_file_no_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/roc_file_no_extension/main ────────────────────────────
+        ── NOT A ROC FILE [R0418] in tmp/roc_file_no_extension/main ────────────────────
 
         I expected a file with either:
         - extension `.roc`
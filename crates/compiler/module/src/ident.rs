@@ -115,6 +115,8 @@ impl ModuleName {
     pub const HASH: &'static str = "Hash";
     pub const INSPECT: &'static str = "Inspect";
     pub const JSON: &'static str = "TotallyNotJson";
+    pub const SORT: &'static str = "Sort";
+    pub const DEFAULT: &'static str = "Default";
 
     pub fn as_str(&self) -> &str {
         self.0.as_str()
@@ -57,6 +57,8 @@ pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
         Symbol::INSPECT_INSPECT_ABILITY,
         &[Symbol::INSPECT_TO_INSPECTOR],
     ),
+    (Symbol::SORT_SORT_ABILITY, &[Symbol::SORT_COMPARE]),
+    (Symbol::DEFAULT_DEFAULT_ABILITY, &[Symbol::DEFAULT_DEFAULT]),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -1575,6 +1577,7 @@ define_builtins! {
         26 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
         27 DECODE_FROM_BYTES: "fromBytes"
         28 DECODE_MAP_RESULT: "mapResult"
+        29 DECODE_TAG: "tag"
     }
     13 HASH: "Hash" => {
         0 HASH_HASH_ABILITY: "Hash" exposed_type=true
@@ -1646,6 +1649,21 @@ define_builtins! {
         5 JSON_ARRAY_CLOSING_STATE: "ArrayClosingState"
         6 JSON_OBJECT_STATE: "ObjectState"
     }
+    16 SORT: "Sort" => {
+        0 SORT_SORT_ABILITY: "Sort" exposed_type=true
+        1 SORT_COMPARE: "compare"
+        2 SORT_COMPARE_BOOL: "compareBool"
+        3 SORT_COMPARE_STR: "compareStr"
+        4 SORT_COMPARE_LIST: "compareList"
+    }
+    17 DEFAULT: "Default" => {
+        0 DEFAULT_DEFAULT_ABILITY: "Default"
+        1 DEFAULT_DEFAULT: "default"
+        2 DEFAULT_DEFAULT_NUM: "defaultNum"
+        3 DEFAULT_DEFAULT_BOOL: "defaultBool"
+        4 DEFAULT_DEFAULT_STR: "defaultStr"
+        5 DEFAULT_DEFAULT_LIST: "defaultList"
+    }
 
-    num_modules: 16 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    num_modules: 18 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -0,0 +1,453 @@
+//! Inference of borrowed parameters, based on the borrow inference of Lean 4
+//! (Ullrich and de Moura, Counting Immutable Beans: Reference Counting Optimized for
+//! Purely Functional Programming, <https://arxiv.org/abs/1908.05647>).
+//!
+//! By default a proc takes ownership of its arguments: the caller increments an argument that
+//! it still needs after the call, and the proc decrements it when it is done. For a parameter
+//! that is only read, like the list in a `List.get`, that is a pointless increment and
+//! decrement on every call. A parameter is borrowed when the proc never consumes it, so the
+//! caller keeps ownership and no reference counting happens at all.
+//!
+//! A parameter is owned when it is returned, stored in a data structure, passed to an owned join
+//! point parameter or passed as an owned argument, which depends on the signatures of other
+//! procs. The signatures are found with a fixed point over the call graph: all parameters start
+//! out borrowed, and a proc is revisited whenever one of the procs it calls gets a new owned
+//! parameter.
+//!
+//! Tail-recursive procs are turned into a join point that jumps to itself, so the parameters of
+//! join points are inferred too. A join point parameter is borrowed when the join point does not
+//! consume it and every jump passes it a value that is borrowed for the whole proc.
+
+use std::collections::VecDeque;
+
+use bumpalo::Bump;
+use roc_collections::{MutMap, MutSet};
+use roc_module::low_level::{LowLevel, LowLevelWrapperType};
+use roc_module::symbol::Symbol;
+
+use crate::inc_dec::lowlevel_borrow_signature;
+use crate::inline::{called_proc, for_each_expr};
+use crate::ir::{
+    Call, CallType, ErasedField, Expr, JoinPointId, ListLiteralElement, Param, Proc, ProcLayout,
+    Stmt,
+};
+use crate::layout::{InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout};
+use crate::low_level::HigherOrder;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ownership {
+    Owned,
+    Borrowed,
+}
+
+impl Ownership {
+    pub fn is_owned(&self) -> bool {
+        matches!(self, Ownership::Owned)
+    }
+
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Ownership::Borrowed)
+    }
+}
+
+/// The ownership of the parameters of every proc, except the lowlevel wrappers.
+#[derive(Debug, Default)]
+pub struct BorrowSignatures<'a> {
+    pub procs: MutMap<(Symbol, ProcLayout<'a>), &'a [Ownership]>,
+    /// The join point parameters of every proc that are borrowed.
+    pub join_points: MutMap<(Symbol, ProcLayout<'a>), MutSet<Symbol>>,
+}
+
+impl<'a> BorrowSignatures<'a> {
+    pub fn get(&self, key: &(Symbol, ProcLayout<'a>)) -> Option<&'a [Ownership]> {
+        self.procs.get(key).copied()
+    }
+
+    pub fn borrowed_join_point_params(
+        &self,
+        key: &(Symbol, ProcLayout<'a>),
+    ) -> Option<&MutSet<Symbol>> {
+        self.join_points.get(key)
+    }
+}
+
+type ProcKey<'a> = (Symbol, ProcLayout<'a>);
+
+/// Infer which parameters of the procs can be borrowed.
+///
+/// Procs that are called from outside of the mono IR must own all their parameters: the procs
+/// exposed to the host, procs passed to higher-order lowlevels like `List.map`, and procs that
+/// are called through a function pointer.
+pub fn infer_borrow_signatures<'a>(
+    arena: &'a Bump,
+    interner: &STLayoutInterner<'a>,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    host_exposed: impl IntoIterator<Item = Symbol>,
+) -> BorrowSignatures<'a> {
+    let mut escaping: MutSet<Symbol> = host_exposed.into_iter().collect();
+    let mut callers: MutMap<ProcKey<'a>, MutSet<ProcKey<'a>>> = MutMap::default();
+
+    for (key, proc) in procs.iter() {
+        for_each_expr(&proc.body, &mut |expr| match expr {
+            Expr::FunctionPointer { lambda_name } => {
+                escaping.insert(lambda_name.name());
+            }
+            Expr::Call(Call {
+                call_type: CallType::HigherOrder(higher_order),
+                ..
+            }) => {
+                escaping.insert(higher_order.passed_function.name.name());
+            }
+            _ => {
+                if let Some(callee) = called_proc(expr) {
+                    callers.entry(callee).or_default().insert(*key);
+                }
+            }
+        });
+    }
+
+    let mut signatures: MutMap<ProcKey<'a>, std::vec::Vec<Ownership>> = procs
+        .iter()
+        .filter(|((symbol, _), _)| {
+            matches!(
+                LowLevelWrapperType::from_symbol(*symbol),
+                LowLevelWrapperType::NotALowLevelWrapper
+            )
+        })
+        .map(|(key, proc)| {
+            (
+                *key,
+                initial_signature(interner, proc, escaping.contains(&key.0)),
+            )
+        })
+        .collect();
+
+    let mut join_points = MutMap::default();
+    let mut worklist: VecDeque<ProcKey<'a>> = signatures.keys().copied().collect();
+    let mut queued: MutSet<ProcKey<'a>> = worklist.iter().copied().collect();
+
+    while let Some(key) = worklist.pop_front() {
+        queued.remove(&key);
+
+        let proc = &procs[&key];
+        let (owned, borrowed_join_point_params) =
+            collect_owned_proc(arena, interner, &signatures, &signatures[&key], proc);
+        join_points.insert(key, borrowed_join_point_params);
+
+        let signature = signatures.get_mut(&key).unwrap();
+        let mut changed = false;
+        for ((_, symbol), ownership) in proc.args.iter().zip(signature.iter_mut()) {
+            if ownership.is_borrowed() && owned.contains(symbol) {
+                *ownership = Ownership::Owned;
+                changed = true;
+            }
+        }
+
+        // the callers may now pass an owned argument where they used to lend it
+        if changed {
+            for caller in callers.get(&key).into_iter().flatten() {
+                if signatures.contains_key(caller) && queued.insert(*caller) {
+                    worklist.push_back(*caller);
+                }
+            }
+        }
+    }
+
+    BorrowSignatures {
+        procs: signatures
+            .into_iter()
+            .map(|(key, signature)| (key, &*arena.alloc_slice_copy(&signature)))
+            .collect(),
+        join_points,
+    }
+}
+
+/// Parameters start out borrowed, unless they are not reference counted, the proc is called
+/// from outside of the mono IR, or the parameter could be reused for a tag the proc creates.
+fn initial_signature<'a>(
+    interner: &STLayoutInterner<'a>,
+    proc: &Proc<'a>,
+    escaping: bool,
+) -> std::vec::Vec<Ownership> {
+    let created_unions = created_unions(proc);
+
+    proc.args
+        .iter()
+        .map(|(layout, _)| {
+            if !escaping && borrowable(interner, &created_unions, *layout) {
+                Ownership::Borrowed
+            } else {
+                Ownership::Owned
+            }
+        })
+        .collect()
+}
+
+fn created_unions<'a>(proc: &Proc<'a>) -> std::vec::Vec<UnionLayout<'a>> {
+    let mut created_unions: std::vec::Vec<UnionLayout<'a>> = std::vec::Vec::new();
+    for_each_expr(&proc.body, &mut |expr| {
+        if let Expr::Tag { tag_layout, .. } = expr {
+            if !created_unions.contains(tag_layout) {
+                created_unions.push(*tag_layout);
+            }
+        }
+    });
+
+    created_unions
+}
+
+fn borrowable<'a>(
+    interner: &STLayoutInterner<'a>,
+    created_unions: &[UnionLayout<'a>],
+    layout: InLayout<'a>,
+) -> bool {
+    // Reuse only happens for a value that the proc owns, so a parameter is kept owned if its
+    // memory could be reused. Otherwise a `map` over a linked list would allocate a new list.
+    let reusable = match interner.get_repr(layout) {
+        LayoutRepr::Union(union_layout) => created_unions.contains(&union_layout),
+        _ => false,
+    };
+
+    !reusable && interner.contains_refcounted(layout)
+}
+
+/// Collect the symbols that the body of a proc consumes, and the join point parameters that can
+/// be borrowed.
+fn collect_owned_proc<'a>(
+    arena: &'a Bump,
+    interner: &STLayoutInterner<'a>,
+    signatures: &MutMap<ProcKey<'a>, std::vec::Vec<Ownership>>,
+    signature: &[Ownership],
+    proc: &Proc<'a>,
+) -> (MutSet<Symbol>, MutSet<Symbol>) {
+    let created_unions = created_unions(proc);
+
+    let mut join_points: MutMap<JoinPointId, &[Param<'a>]> = MutMap::default();
+    let mut jumps = std::vec::Vec::new();
+    for_each_stmt(&proc.body, &mut |stmt| match stmt {
+        Stmt::Join { id, parameters, .. } => {
+            join_points.insert(*id, parameters);
+        }
+        Stmt::Jump(id, arguments) => jumps.push((*id, *arguments)),
+        _ => {}
+    });
+
+    let mut borrowed_join_point_params: MutSet<Symbol> = (join_points.values())
+        .flat_map(|parameters| parameters.iter())
+        .filter(|param| borrowable(interner, &created_unions, param.layout))
+        .map(|param| param.symbol)
+        .collect();
+
+    // the candidates only ever shrink, so this terminates
+    loop {
+        let mut owned = MutSet::default();
+        collect_owned(
+            arena,
+            signatures,
+            &join_points,
+            &borrowed_join_point_params,
+            &proc.body,
+            &mut owned,
+        );
+
+        let borrowed: MutSet<Symbol> = (proc.args.iter().zip(signature))
+            .filter(|((_, symbol), ownership)| ownership.is_borrowed() && !owned.contains(symbol))
+            .map(|((_, symbol), _)| *symbol)
+            .chain(borrowed_join_point_params.iter().copied())
+            .collect();
+
+        let mut still_borrowed = borrowed_join_point_params.clone();
+        still_borrowed.retain(|symbol| !owned.contains(symbol));
+        for (id, arguments) in jumps.iter() {
+            for (argument, param) in arguments.iter().zip(join_points[id].iter()) {
+                if !borrowed.contains(argument) {
+                    still_borrowed.remove(&param.symbol);
+                }
+            }
+        }
+
+        if still_borrowed == borrowed_join_point_params {
+            return (owned, still_borrowed);
+        }
+
+        borrowed_join_point_params = still_borrowed;
+    }
+}
+
+pub(crate) fn for_each_stmt<'b, 'a>(stmt: &'b Stmt<'a>, f: &mut impl FnMut(&'b Stmt<'a>)) {
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        f(stmt);
+
+        match stmt {
+            Stmt::Let(_, _, _, rest) | Stmt::Refcounting(_, rest) => stack.push(rest),
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => stack.push(remainder),
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+        }
+    }
+}
+
+/// Collect the symbols that a statement consumes, given the current signatures.
+fn collect_owned<'a>(
+    arena: &'a Bump,
+    signatures: &MutMap<ProcKey<'a>, std::vec::Vec<Ownership>>,
+    join_points: &MutMap<JoinPointId, &[Param<'a>]>,
+    borrowed_join_point_params: &MutSet<Symbol>,
+    stmt: &Stmt<'a>,
+    owned: &mut MutSet<Symbol>,
+) {
+    // A field that is consumed would need an increment if its structure were borrowed, while an
+    // owned structure can hand over its fields when it is dropped.
+    let mut projections = std::vec::Vec::new();
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(binding, expr, _, rest) => {
+                match expr {
+                    Expr::StructAtIndex { structure, .. }
+                    | Expr::UnionAtIndex { structure, .. }
+                    | Expr::GetElementPointer { structure, .. } => {
+                        projections.push((*binding, *structure));
+                    }
+                    _ => collect_owned_expr(arena, signatures, expr, owned),
+                }
+                stack.push(rest);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Ret(symbol) => {
+                owned.insert(*symbol);
+            }
+            Stmt::Refcounting(_, rest) => stack.push(rest),
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => stack.push(remainder),
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Jump(id, arguments) => owned.extend(
+                (arguments.iter().zip(join_points[id].iter()))
+                    .filter(|(_, param)| !borrowed_join_point_params.contains(&param.symbol))
+                    .map(|(argument, _)| *argument),
+            ),
+            Stmt::Crash(..) => {}
+        }
+    }
+
+    // projections come in the order they are bound, so a projection of a projection is handled
+    // in the same pass
+    for (binding, structure) in projections.into_iter().rev() {
+        if owned.contains(&binding) {
+            owned.insert(structure);
+        }
+    }
+}
+
+fn collect_owned_expr<'a>(
+    arena: &'a Bump,
+    signatures: &MutMap<ProcKey<'a>, std::vec::Vec<Ownership>>,
+    expr: &Expr<'a>,
+    owned: &mut MutSet<Symbol>,
+) {
+    match expr {
+        Expr::Tag { arguments, .. } | Expr::Struct(arguments) => {
+            owned.extend(arguments.iter().copied());
+        }
+        Expr::Array { elems, .. } => {
+            owned.extend(elems.iter().filter_map(|elem| match elem {
+                ListLiteralElement::Symbol(symbol) => Some(*symbol),
+                ListLiteralElement::Literal(_) => None,
+            }));
+        }
+        Expr::ErasedMake { value, .. } => owned.extend(value.iter().copied()),
+        Expr::ErasedLoad {
+            symbol,
+            field: ErasedField::Value,
+        } => {
+            owned.insert(*symbol);
+        }
+        Expr::Alloca { initializer, .. } => owned.extend(initializer.iter().copied()),
+        Expr::Call(Call {
+            call_type,
+            arguments,
+        }) => match call_type.clone().replace_lowlevel_wrapper() {
+            CallType::ByName { .. } => {
+                let signature = called_proc(expr).and_then(|callee| signatures.get(&callee));
+
+                match signature {
+                    Some(signature) => {
+                        owned.extend(arguments.iter().zip(signature).filter_map(
+                            |(symbol, ownership)| ownership.is_owned().then_some(*symbol),
+                        ))
+                    }
+                    None => owned.extend(arguments.iter().copied()),
+                }
+            }
+            CallType::ByPointer { .. } => owned.extend(arguments.iter().copied()),
+            CallType::Foreign { .. } => {}
+            CallType::LowLevel {
+                op: LowLevel::ListGetUnsafe,
+                ..
+            } => {}
+            CallType::LowLevel { op, .. } => {
+                let signature = lowlevel_borrow_signature(arena, op);
+
+                owned.extend(
+                    arguments
+                        .iter()
+                        .zip(signature)
+                        .filter_map(|(symbol, ownership)| ownership.is_owned().then_some(*symbol)),
+                );
+            }
+            // the lists are consumed, the captured environment is borrowed
+            CallType::HigherOrder(higher_order) => match higher_order.op {
+                HigherOrder::ListMap { xs } | HigherOrder::ListSortWith { xs } => {
+                    owned.insert(xs);
+                }
+                HigherOrder::ListMap2 { xs, ys } => owned.extend([xs, ys]),
+                HigherOrder::ListMap3 { xs, ys, zs } => owned.extend([xs, ys, zs]),
+                HigherOrder::ListMap4 { xs, ys, zs, ws } => owned.extend([xs, ys, zs, ws]),
+            },
+        },
+        Expr::Literal(_)
+        | Expr::NullPointer
+        | Expr::StructAtIndex { .. }
+        | Expr::GetTagId { .. }
+        | Expr::UnionAtIndex { .. }
+        | Expr::GetElementPointer { .. }
+        | Expr::EmptyArray
+        | Expr::ErasedLoad { .. }
+        | Expr::FunctionPointer { .. }
+        | Expr::Reset { .. }
+        | Expr::ResetRef { .. }
+        | Expr::StackAlloc { .. }
+        | Expr::RuntimeErrorFunction(_) => {}
+    }
+}
@@ -544,6 +544,7 @@ impl<'a, 'r> Ctx<'a, 'r> {
 
                 None
             }
+            Expr::StackAlloc { .. } => None,
             Expr::RuntimeErrorFunction(_) => None,
         }
     }
@@ -5,7 +5,7 @@ use ven_pretty::{text, Arena, DocAllocator, DocBuilder};
 
 use crate::{
     ir::{ErasedField, Parens, ProcLayout},
-    layout::LayoutInterner,
+    layout::{LayoutInterner, Niche},
 };
 
 use super::{
@@ -487,7 +487,7 @@ where
     (title, docs_before, doc)
 }
 
-fn format_symbol<'d>(f: &'d Arena<'d>, interns: &'d Interns, symbol: Symbol) -> Doc<'d> {
+pub(super) fn format_symbol<'d>(f: &'d Arena<'d>, interns: &'d Interns, symbol: Symbol) -> Doc<'d> {
     f.text(symbol.module_string(interns).to_string())
         .append(f.text("."))
         .append(f.text(symbol.as_str(interns)))
@@ -534,7 +534,7 @@ where
     ])
 }
 
-fn format_proc_layout<'a, 'd, I>(
+pub(super) fn format_proc_layout<'a, 'd, I>(
     f: &'d Arena<'d>,
     interner: &I,
     proc_layout: ProcLayout<'a>,
@@ -558,10 +558,12 @@ where
         f.reflow(" -> "),
         interner.to_doc_top(result, f),
     ]);
-    let niche = (f.text("("))
-        .append(captures_niche.to_doc(f, interner, &mut Default::default()))
-        .append(f.text(")"));
-    f.concat([fun, f.space(), niche])
+    if captures_niche == Niche::NONE {
+        fun
+    } else {
+        let niche = captures_niche.to_doc(f, interner, &mut Default::default());
+        f.concat([fun, f.space(), niche])
+    }
 }
 
 fn stack<'d>(f: &'d Arena<'d>, docs: impl IntoIterator<Item = Doc<'d>>) -> Doc<'d> {
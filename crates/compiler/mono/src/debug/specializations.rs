@@ -0,0 +1,162 @@
+use std::fmt::Display;
+
+use roc_collections::MutMap;
+use roc_module::symbol::{Interns, Symbol};
+use ven_pretty::{text, Arena, DocAllocator, DocBuilder};
+
+use crate::{
+    ir::{ClosureParents, Proc, ProcLayout, Stmt},
+    layout::LayoutInterner,
+};
+
+use super::report::{format_proc_layout, format_symbol};
+
+struct Specialization<'a> {
+    layout: ProcLayout<'a>,
+    size: usize,
+}
+
+struct Group<'a> {
+    symbol: Symbol,
+    specializations: Vec<Specialization<'a>>,
+    total_size: usize,
+}
+
+/// Lists every function with the number of specializations that were made of it, the layouts
+/// (including the captures niche of its lambda set) that each one was made for, and their size.
+/// Anonymous closures are named after the function they were defined in.
+///
+/// The size of a specialization is the number of statements in its mono IR. That is not the size
+/// of the machine code the backends generate, but it grows with it, so sorting functions by their
+/// total size still puts the ones that blow up the binary first.
+pub fn format_specialization_report<'a, I>(
+    interns: &Interns,
+    interner: &I,
+    procedures: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    closure_parents: &ClosureParents,
+) -> impl Display
+where
+    I: LayoutInterner<'a>,
+{
+    let mut groups: MutMap<Symbol, Group<'a>> = MutMap::default();
+
+    for ((symbol, layout), proc) in procedures.iter() {
+        let size = stmt_size(&proc.body);
+        let group = groups.entry(*symbol).or_insert_with(|| Group {
+            symbol: *symbol,
+            specializations: Vec::new(),
+            total_size: 0,
+        });
+
+        group.specializations.push(Specialization {
+            layout: *layout,
+            size,
+        });
+        group.total_size += size;
+    }
+
+    let mut groups: Vec<_> = groups.into_values().collect();
+    for group in groups.iter_mut() {
+        group
+            .specializations
+            .sort_by_key(|specialization| std::cmp::Reverse(specialization.size));
+    }
+    groups.sort_by(|a, b| {
+        (b.total_size, b.specializations.len())
+            .cmp(&(a.total_size, a.specializations.len()))
+            .then_with(|| a.symbol.as_str(interns).cmp(b.symbol.as_str(interns)))
+    });
+
+    let f = Arena::new();
+    let header = text!(
+        f,
+        "{} of {}, {} in total",
+        count(procedures.len(), "specialization"),
+        count(groups.len(), "function"),
+        count(
+            groups.iter().map(|group| group.total_size).sum(),
+            "IR statement"
+        )
+    );
+
+    let group_docs = groups.iter().map(|group| {
+        let title = format_function(&f, interns, closure_parents, group.symbol).append(text!(
+            f,
+            ": {}, {}",
+            count(group.specializations.len(), "specialization"),
+            count(group.total_size, "IR statement")
+        ));
+
+        let lines = group.specializations.iter().map(|specialization| {
+            text!(f, "{:>8}  ", specialization.size).append(format_proc_layout(
+                &f,
+                interner,
+                specialization.layout,
+            ))
+        });
+
+        title.append(
+            f.hardline()
+                .append(f.intersperse(lines, f.hardline()))
+                .nest(4),
+        )
+    });
+
+    let all = f.intersperse(
+        std::iter::once(header).chain(group_docs),
+        f.hardline().append(f.hardline()),
+    );
+    all.1.pretty(200).to_string()
+}
+
+fn count(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {noun}"),
+        _ => format!("{n} {noun}s"),
+    }
+}
+
+/// Anonymous closures only have a number for a name, so we say which function they are in.
+fn format_function<'d>(
+    f: &'d Arena<'d>,
+    interns: &'d Interns,
+    closure_parents: &ClosureParents,
+    symbol: Symbol,
+) -> DocBuilder<'d, Arena<'d>> {
+    match closure_parents.get(&symbol) {
+        Some(parent) => text!(f, "closure #{} in ", symbol.as_str(interns))
+            .append(format_function(f, interns, closure_parents, *parent)),
+        None => format_symbol(f, interns, symbol),
+    }
+}
+
+fn stmt_size(stmt: &Stmt) -> usize {
+    match stmt {
+        Stmt::Let(_, _, _, rest)
+        | Stmt::Refcounting(_, rest)
+        | Stmt::Expect {
+            remainder: rest, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: rest, ..
+        }
+        | Stmt::Dbg {
+            remainder: rest, ..
+        } => 1 + stmt_size(rest),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            1 + branches
+                .iter()
+                .map(|(_, _, branch)| stmt_size(branch))
+                .sum::<usize>()
+                + stmt_size(default_branch.1)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => 1 + stmt_size(body) + stmt_size(remainder),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => 1,
+    }
+}
@@ -248,6 +248,7 @@ fn specialize_drops_stmt<'a, 'i>(
                     | FunctionPointer { .. }
                     | GetTagId { .. }
                     | Alloca { .. }
+                    | StackAlloc { .. }
                     | EmptyArray
                     | NullPointer => { /* do nothing */ }
                 }
@@ -14,6 +14,8 @@ use roc_error_macros::internal_error;
 use roc_module::low_level::LowLevel;
 use roc_module::{low_level::LowLevelWrapperType, symbol::Symbol};
 
+use crate::borrow::{BorrowSignatures, Ownership};
+use crate::inline::called_proc;
 use crate::ir::ErasedField;
 use crate::{
     ir::{
@@ -26,10 +28,12 @@ use crate::{
 
 /**
 Insert the reference count operations for procedures.
+Parameters that are borrowed according to the borrow signatures are neither consumed nor dropped by the procedure.
 */
 pub fn insert_inc_dec_operations<'a>(
     arena: &'a Bump,
     layout_interner: &STLayoutInterner<'a>,
+    borrow_signatures: &BorrowSignatures<'a>,
     procedures: &mut HashMap<(Symbol, ProcLayout), Proc<'a>, BuildHasherDefault<WyHash>>,
 ) {
     // All calls to lowlevels are wrapped in another function to help with type inference and return/parameter layouts.
@@ -37,13 +41,22 @@ pub fn insert_inc_dec_operations<'a>(
     // Thus, these rc operations are performed on the caller of the wrapper instead, and we skip rc on the lowlevel.
     // It might be possible to inline the lowlevels at this point already,
     // but previous attempt conflicted as the parameters layouts and return layout do not match.
-    for ((symbol, _layout), proc) in procedures.iter_mut() {
+    for (key, proc) in procedures.iter_mut() {
         if matches!(
-            LowLevelWrapperType::from_symbol(*symbol),
+            LowLevelWrapperType::from_symbol(key.0),
             LowLevelWrapperType::NotALowLevelWrapper
         ) {
             let symbol_rc_types_env = SymbolRcTypesEnv::from_layout_interner(layout_interner);
-            insert_inc_dec_operations_proc(arena, symbol_rc_types_env, proc);
+            let signature = borrow_signatures.get(key);
+            let borrowed_join_point_params = borrow_signatures.borrowed_join_point_params(key);
+            insert_inc_dec_operations_proc(
+                arena,
+                symbol_rc_types_env,
+                borrow_signatures,
+                signature,
+                borrowed_join_point_params,
+                proc,
+            );
         }
     }
 }
@@ -222,22 +235,6 @@ impl<'a, 'i> SymbolRcTypesEnv<'a, 'i> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Ownership {
-    Owned,
-    Borrowed,
-}
-
-impl Ownership {
-    fn is_owned(&self) -> bool {
-        matches!(self, Ownership::Owned)
-    }
-
-    fn is_borrowed(&self) -> bool {
-        matches!(self, Ownership::Borrowed)
-    }
-}
-
 type SymbolsOwnership = MutMap<Symbol, Ownership>;
 
 /**
@@ -250,15 +247,22 @@ The environment for the reference counting pass.
 Contains the symbols rc types and the ownership.
 */
 #[derive(Clone)]
-struct RefcountEnvironment<'v> {
+struct RefcountEnvironment<'v, 'a> {
     // Keep track which symbols are reference counted and which are not.
     symbols_rc_types: &'v SymbolRcTypes,
     // The Koka implementation assumes everything that is not owned to be borrowed.
     symbols_ownership: SymbolsOwnership,
     jointpoint_closures: MutMap<JoinPointId, JoinPointConsumption>,
+    // Which parameters the called procedures borrow.
+    borrow_signatures: &'v BorrowSignatures<'a>,
+    // The parameters of the current procedure that it borrows from its caller, the borrowed join point parameters, and the values projected out of them.
+    // These stay alive for the whole procedure, so they are never owned.
+    borrowed_symbols: MutSet<Symbol>,
+    // The parameters of the join points in scope, to know which arguments of a jump are borrowed.
+    joinpoint_parameters: MutMap<JoinPointId, &'a [Param<'a>]>,
 }
 
-impl<'v> RefcountEnvironment<'v> {
+impl<'v, 'a> RefcountEnvironment<'v, 'a> {
     /**
     Retrieve the rc type of a symbol.
     */
@@ -403,22 +407,44 @@ impl<'v> RefcountEnvironment<'v> {
 fn insert_inc_dec_operations_proc<'a>(
     arena: &'a Bump,
     mut symbol_rc_types_env: SymbolRcTypesEnv<'a, '_>,
+    borrow_signatures: &BorrowSignatures<'a>,
+    signature: Option<&[Ownership]>,
+    borrowed_join_point_params: Option<&MutSet<Symbol>>,
     proc: &mut Proc<'a>,
 ) {
     // Clone the symbol_rc_types_env and insert the symbols in the current procedure.
     // As the symbols should be limited in scope for the current proc.
     symbol_rc_types_env.insert_symbols_rc_type_proc(proc);
 
+    let mut borrowed_symbols: MutSet<Symbol> = match signature {
+        Some(signature) => proc
+            .args
+            .iter()
+            .zip(signature)
+            .filter_map(|((_layout, symbol), ownership)| ownership.is_borrowed().then_some(*symbol))
+            .collect(),
+        None => MutSet::default(),
+    };
+    borrowed_symbols.extend(borrowed_join_point_params.into_iter().flatten().copied());
+
     let mut environment = RefcountEnvironment {
         symbols_rc_types: &symbol_rc_types_env.symbols_rc_type,
         symbols_ownership: MutMap::default(),
         jointpoint_closures: MutMap::default(),
+        borrow_signatures,
+        borrowed_symbols,
+        joinpoint_parameters: MutMap::default(),
     };
 
     // Add all arguments to the environment (if they are reference counted)
+    // Borrowed arguments are owned by the caller, so every use of them needs an increment.
     let proc_symbols = proc.args.iter().map(|(_layout, symbol)| symbol);
     for symbol in proc_symbols.clone() {
         environment.add_symbol(*symbol);
+
+        if environment.borrowed_symbols.contains(symbol) {
+            environment.consume_symbol(symbol);
+        }
     }
 
     // Update the body with reference count statements.
@@ -450,7 +476,7 @@ Assuming that a symbol can only be defined once (no binding to the same symbol m
 */
 fn insert_refcount_operations_stmt<'v, 'a>(
     arena: &'a Bump,
-    environment: &mut RefcountEnvironment<'v>,
+    environment: &mut RefcountEnvironment<'v, 'a>,
     stmt: &Stmt<'a>,
 ) -> &'a Stmt<'a> {
     match &stmt {
@@ -474,8 +500,18 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                 "All let bindings should be in the vector"
             );
 
-            for (binding, _, _) in triples.iter() {
+            for (binding, expr, _) in triples.iter() {
                 environment.add_symbol(**binding); // Add the bound symbol to the environment. As it can be used in the continuation.
+
+                // A field of a borrowed value lives as long as that value, so it is borrowed as well.
+                if let Expr::StructAtIndex { structure, .. }
+                | Expr::UnionAtIndex { structure, .. } = expr
+                {
+                    if environment.borrowed_symbols.contains(structure) {
+                        environment.borrowed_symbols.insert(**binding);
+                        environment.consume_symbol(binding);
+                    }
+                }
             }
 
             triples
@@ -563,7 +599,7 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                         let consumed =
                             branch_envs
                                 .iter()
-                                .any(|branch_env: &&RefcountEnvironment<'v>| {
+                                .any(|branch_env: &&RefcountEnvironment<'v, 'a>| {
                                     match branch_env.get_symbol_ownership(symbol) {
                                         None => internal_error!(
                                             "symbol {symbol:?} in the current env should be in the branch's env"
@@ -720,10 +756,12 @@ fn insert_refcount_operations_stmt<'v, 'a>(
         } => {
             // Assuming that the values in the closure of the body of this jointpoint are already bound.
             // Assuming that all symbols are still owned. (So that we can determine what symbols got consumed in the join point.)
+            // Except for borrowed parameters, which are never owned.
             debug_assert!(environment
                 .symbols_ownership
                 .iter()
-                .all(|(_, ownership)| ownership.is_owned()));
+                .all(|(symbol, ownership)| ownership.is_owned()
+                    || environment.borrowed_symbols.contains(symbol)));
 
             let mut body_env = environment.clone();
 
@@ -732,9 +770,19 @@ fn insert_refcount_operations_stmt<'v, 'a>(
                 .map(|Param { symbol, .. }| *symbol)
                 .collect::<MutSet<_>>();
             for symbol in parameter_symbols_set.iter().copied() {
-                body_env.add_symbol(symbol)
+                body_env.add_symbol(symbol);
+
+                // Borrowed parameters are owned by the jumps, so every use of them needs an increment.
+                if body_env.borrowed_symbols.contains(&symbol) {
+                    body_env.consume_symbol(&symbol);
+                }
             }
 
+            // The join point can jump to itself.
+            body_env
+                .joinpoint_parameters
+                .insert(*joinpoint_id, parameters);
+
             /*
             We use a fixed point iteration to determine what symbols are consumed in the join point.
             We need to do this because the join point might be called recursively.
@@ -811,8 +859,12 @@ fn insert_refcount_operations_stmt<'v, 'a>(
             );
 
             environment.add_joinpoint_consumption(*joinpoint_id, joinpoint_consumption);
+            environment
+                .joinpoint_parameters
+                .insert(*joinpoint_id, parameters);
             let new_remainder = insert_refcount_operations_stmt(arena, environment, remainder);
             environment.remove_joinpoint_consumption(*joinpoint_id);
+            environment.joinpoint_parameters.remove(joinpoint_id);
 
             arena.alloc(Stmt::Join {
                 id: *joinpoint_id,
@@ -829,13 +881,26 @@ fn insert_refcount_operations_stmt<'v, 'a>(
 
             let new_jump = arena.alloc(Stmt::Jump(*joinpoint_id, arguments));
 
+            // Borrowed parameters of the join point don't take ownership of their argument.
+            let parameters = environment
+                .joinpoint_parameters
+                .get(joinpoint_id)
+                .copied()
+                .unwrap_or_default();
+            let owned_arguments = arguments
+                .iter()
+                .enumerate()
+                .filter_map(|(index, argument)| match parameters.get(index) {
+                    Some(Param { symbol, .. }) if environment.borrowed_symbols.contains(symbol) => {
+                        None
+                    }
+                    _ => Some(*argument),
+                });
+
+            let owned_usages = environment.owned_usages(owned_arguments);
+
             // Note that this should only insert increments if a later join point has a current parameter as consumed closure.
-            consume_and_insert_inc_stmts(
-                arena,
-                environment,
-                environment.owned_usages(arguments.iter().copied()),
-                new_jump,
-            )
+            consume_and_insert_inc_stmts(arena, environment, owned_usages, new_jump)
         }
         Stmt::Crash(symbol, crash_tag) => {
             // We don't have to worry about reference counting *after* the crash.
@@ -855,7 +920,7 @@ fn insert_refcount_operations_stmt<'v, 'a>(
 
 fn insert_refcount_operations_binding<'a>(
     arena: &'a Bump,
-    environment: &mut RefcountEnvironment,
+    environment: &mut RefcountEnvironment<'_, 'a>,
     binding: &Symbol,
     expr: &Expr<'a>,
     layout: &InLayout<'a>,
@@ -938,7 +1003,8 @@ fn insert_refcount_operations_binding<'a>(
             let newer_stmt = if matches!(
                 environment.get_symbol_rc_type(binding),
                 VarRcType::ReferenceCounted
-            ) {
+            ) && !environment.borrowed_symbols.contains(binding)
+            {
                 match expr {
                     Expr::StructAtIndex { .. }
                     | Expr::UnionAtIndex { .. }
@@ -979,12 +1045,32 @@ fn insert_refcount_operations_binding<'a>(
         }) => {
             match call_type.clone().replace_lowlevel_wrapper() {
                 // A by name call refers to a normal function call.
-                // Normal functions take all their parameters as owned, so we can mark them all as such.
-                CallType::ByName { .. } => {
-                    let new_let = new_let!(stmt);
+                // Normal functions take their parameters as owned, unless the borrow signature says otherwise.
+                CallType::ByName { .. } => match called_proc(expr)
+                    .and_then(|callee| environment.borrow_signatures.get(&callee))
+                {
+                    Some(borrow_signature) => {
+                        let arguments_with_borrow_signature = arguments
+                            .iter()
+                            .copied()
+                            .zip(borrow_signature.iter().copied());
+                        let owned_arguments = arguments_with_borrow_signature.clone().filter_map(
+                            |(symbol, ownership)| ownership.is_owned().then_some(symbol),
+                        );
+                        let borrowed_arguments =
+                            arguments_with_borrow_signature.filter_map(|(symbol, ownership)| {
+                                ownership.is_borrowed().then_some(symbol)
+                            });
+                        let new_stmt = dec_borrowed!(borrowed_arguments, stmt);
+                        let new_let = new_let!(new_stmt);
+                        inc_owned!(owned_arguments, new_let)
+                    }
+                    None => {
+                        let new_let = new_let!(stmt);
 
-                    inc_owned!(arguments.iter().copied(), new_let)
-                }
+                        inc_owned!(arguments.iter().copied(), new_let)
+                    }
+                },
                 // A normal Roc function call, but we don't actually know where its target is.
                 // As such, we assume that it takes all parameters as owned, as will the function
                 // itself.
@@ -1142,6 +1228,9 @@ fn insert_refcount_operations_binding<'a>(
         Expr::Reset { .. } | Expr::ResetRef { .. } => {
             unreachable!("Reset(ref) should not exist at this point")
         }
+        Expr::StackAlloc { .. } => {
+            unreachable!("StackAlloc should not exist at this point")
+        }
         Expr::Alloca { initializer, .. } => {
             let new_let = new_let!(stmt);
 
@@ -1265,7 +1354,7 @@ fn insert_dec_stmt<'a>(
 /**
  * Retrieve the borrow signature of a low-level operation.
  */
-fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[Ownership] {
+pub(crate) fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[Ownership] {
     use LowLevel::*;
 
     // TODO is true or false more efficient for non-refcounted layouts?
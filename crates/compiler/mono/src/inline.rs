@@ -0,0 +1,1216 @@
+//! Inlining of small procs, constant folding and dead binding elimination on the mono IR.
+//!
+//! The LLVM backend gets all of this from LLVM, but the dev backends generate code for the mono
+//! IR as it is, so without this pass every call to a tiny wrapper like `Num.add` is a real call and
+//! every operation on literals happens at runtime.
+//!
+//! This runs right after specialization, before refcounting instructions are inserted, so a
+//! binding can be dropped without having to account for the refcount of its value.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::{MutMap, MutSet, ReferenceMatrix};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{
+    BranchInfo, Call, CallSpecId, CallType, Expr, HigherOrderLowLevel, JoinPointId,
+    ListLiteralElement, Literal, ModifyRc, Param, PassedFunction, Proc, ProcLayout, SelfRecursive,
+    Stmt,
+};
+use crate::layout::{
+    Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
+};
+use crate::low_level::HigherOrder;
+
+/// Procs whose body has at most this many statements are inlined into their callers.
+const INLINE_SIZE_LIMIT: usize = 8;
+
+type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// Inline calls to small non-recursive procs, fold lowlevel operations on literals, and remove
+/// bindings that are never used.
+pub fn inline_and_fold<'a, 'i>(
+    arena: &'a Bump,
+    interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    procs: &mut Procs<'a>,
+) {
+    let candidates: std::vec::Vec<_> = procs
+        .iter()
+        .filter(|(_, proc)| is_inline_candidate(proc))
+        .map(|(key, _)| *key)
+        .collect();
+
+    let candidate_indices: MutMap<_, _> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (*key, index))
+        .collect();
+
+    let mut matrix = ReferenceMatrix::new(candidates.len());
+
+    for (row, key) in candidates.iter().enumerate() {
+        for_each_expr(&procs[key].body, &mut |expr| {
+            if let Some(callee) = called_proc(expr) {
+                if let Some(col) = candidate_indices.get(&callee) {
+                    matrix.set_row_col(row, *col, true);
+                }
+            }
+        });
+    }
+
+    let mut inlinable = MutMap::default();
+    let mut simplified = MutSet::default();
+
+    // Callees come before their callers here, so a candidate has the procs it calls inlined
+    // before we decide whether it is still small enough to be inlined itself. Candidates that
+    // (mutually) recurse are never inlined; that would never end.
+    for (group, _) in matrix.strongly_connected_components_all().groups() {
+        let mut members = group.iter_ones();
+
+        let index = match (members.next(), members.next()) {
+            (Some(index), None) if !matrix.get_row_col(index, index) => index,
+            _ => continue,
+        };
+
+        let key = candidates[index];
+        let proc = procs.get_mut(&key).unwrap();
+
+        simplify_proc(arena, interner, home, ident_ids, &inlinable, proc);
+        simplified.insert(key);
+
+        if is_inline_candidate(proc) {
+            inlinable.insert(key, proc.clone());
+        }
+    }
+
+    for (key, proc) in procs.iter_mut() {
+        if !simplified.contains(key) {
+            simplify_proc(arena, interner, home, ident_ids, &inlinable, proc);
+        }
+    }
+}
+
+fn is_inline_candidate(proc: &Proc) -> bool {
+    let mut budget = INLINE_SIZE_LIMIT;
+
+    !proc.is_erased
+        && matches!(proc.is_self_recursive, SelfRecursive::NotSelfRecursive)
+        && fits_inline_budget(&proc.body, &mut budget)
+}
+
+/// Expects and dbgs refer to the source of the module they were written in, so they stay put.
+fn fits_inline_budget(stmt: &Stmt, budget: &mut usize) -> bool {
+    if *budget == 0 {
+        return false;
+    }
+
+    *budget -= 1;
+
+    match stmt {
+        Stmt::Let(_, _, _, rest) | Stmt::Refcounting(_, rest) => fits_inline_budget(rest, budget),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            branches
+                .iter()
+                .all(|(_, _, branch)| fits_inline_budget(branch, budget))
+                && fits_inline_budget(default_branch.1, budget)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => fits_inline_budget(body, budget) && fits_inline_budget(remainder, budget),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => true,
+        Stmt::Expect { .. } | Stmt::ExpectFx { .. } | Stmt::Dbg { .. } => false,
+    }
+}
+
+/// A body that is just a sequence of bindings can be spliced into its caller directly, anything
+/// else needs a join point to continue at.
+fn is_straight_line(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Let(_, _, _, rest) => is_straight_line(rest),
+        Stmt::Ret(_) => true,
+        _ => false,
+    }
+}
+
+pub(crate) fn called_proc<'a>(expr: &Expr<'a>) -> Option<(Symbol, ProcLayout<'a>)> {
+    match expr {
+        Expr::Call(Call {
+            call_type:
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    ..
+                },
+            ..
+        }) => Some((
+            name.name(),
+            ProcLayout {
+                arguments: arg_layouts,
+                result: *ret_layout,
+                niche: name.niche(),
+            },
+        )),
+        _ => None,
+    }
+}
+
+pub(crate) fn for_each_expr<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut(&Expr<'a>)) {
+    match stmt {
+        Stmt::Let(_, expr, _, rest) => {
+            f(expr);
+            for_each_expr(rest, f);
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            for (_, _, branch) in branches.iter() {
+                for_each_expr(branch, f);
+            }
+            for_each_expr(default_branch.1, f);
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => {
+            for_each_expr(body, f);
+            for_each_expr(remainder, f);
+        }
+        Stmt::Refcounting(_, rest)
+        | Stmt::Expect {
+            remainder: rest, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: rest, ..
+        }
+        | Stmt::Dbg {
+            remainder: rest, ..
+        } => for_each_expr(rest, f),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+    }
+}
+
+fn simplify_proc<'a, 'i>(
+    arena: &'a Bump,
+    interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    inlinable: &'i Procs<'a>,
+    proc: &mut Proc<'a>,
+) {
+    // Inlined calls get fresh specialization ids, since every call in a proc needs its own.
+    let mut max_call_spec_id = CallSpecId::BACKEND_DUMMY;
+    for_each_expr(&proc.body, &mut |expr| {
+        if let Expr::Call(Call { call_type, .. }) = expr {
+            let id = match call_type {
+                CallType::ByName {
+                    specialization_id, ..
+                } => *specialization_id,
+                CallType::HigherOrder(higher_order) => {
+                    higher_order.passed_function.specialization_id
+                }
+                _ => return,
+            };
+
+            max_call_spec_id = max_call_spec_id.max(id);
+        }
+    });
+
+    let mut env = Env {
+        arena,
+        interner,
+        home,
+        ident_ids,
+        inlinable,
+        next_call_spec_id: max_call_spec_id.next(),
+        substitutions: MutMap::default(),
+        literals: MutMap::default(),
+        tags: MutMap::default(),
+        used: MutSet::default(),
+    };
+
+    let body = arena.alloc(proc.body.clone());
+    proc.body = simplify_stmt(&mut env, body, None).clone();
+}
+
+struct Env<'a, 'i> {
+    arena: &'a Bump,
+    interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    inlinable: &'i Procs<'a>,
+    next_call_spec_id: CallSpecId,
+    /// Symbols that have been replaced by another symbol, like the result of an inlined call by
+    /// the symbol its body returns
+    substitutions: MutMap<Symbol, Symbol>,
+    /// Symbols that are bound to a literal, with their layouts
+    literals: MutMap<Symbol, (Literal<'a>, InLayout<'a>)>,
+    /// Symbols that are bound to a tag, with its id
+    tags: MutMap<Symbol, TagIdIntType>,
+    /// Symbols used by the statements simplified so far. After simplifying what follows a binding,
+    /// this tells whether the binding is needed. Sibling branches may bind the same symbol, which
+    /// can only keep a binding that is not needed.
+    used: MutSet<Symbol>,
+}
+
+impl<'a, 'i> Env<'a, 'i> {
+    fn unique_symbol(&mut self) -> Symbol {
+        let ident_id = self.ident_ids.gen_unique();
+
+        Symbol::new(self.home, ident_id)
+    }
+
+    fn fresh_call_spec_id(&mut self) -> CallSpecId {
+        let id = self.next_call_spec_id;
+        self.next_call_spec_id = id.next();
+
+        id
+    }
+
+    fn substitute(&self, symbol: Symbol) -> Symbol {
+        self.substitutions.get(&symbol).copied().unwrap_or(symbol)
+    }
+
+    fn use_symbol(&mut self, symbol: Symbol) -> Symbol {
+        let symbol = self.substitute(symbol);
+        self.used.insert(symbol);
+
+        symbol
+    }
+
+    fn use_symbols(&mut self, symbols: &[Symbol]) -> &'a [Symbol] {
+        let arena = self.arena;
+        let symbols = Vec::from_iter_in(symbols.iter().map(|s| self.use_symbol(*s)), arena);
+
+        symbols.into_bump_slice()
+    }
+
+    fn use_branch_info(&mut self, info: &BranchInfo<'a>) -> BranchInfo<'a> {
+        map_branch_info(info, &mut |symbol| self.use_symbol(symbol))
+    }
+}
+
+/// Where the body of an inlined call continues once it returns.
+struct Continuation<'a, 'c> {
+    /// The symbol the result of the call was bound to
+    symbol: Symbol,
+    rest: &'a Stmt<'a>,
+    outer: Option<&'c Continuation<'a, 'c>>,
+}
+
+fn simplify_branch<'a>(
+    env: &mut Env<'a, '_>,
+    stmt: &'a Stmt<'a>,
+    cont: Option<&Continuation<'a, '_>>,
+) -> &'a Stmt<'a> {
+    let substitutions = env.substitutions.clone();
+    let literals = env.literals.clone();
+    let tags = env.tags.clone();
+
+    let stmt = simplify_stmt(env, stmt, cont);
+
+    env.substitutions = substitutions;
+    env.literals = literals;
+    env.tags = tags;
+
+    stmt
+}
+
+fn simplify_stmt<'a>(
+    env: &mut Env<'a, '_>,
+    stmt: &'a Stmt<'a>,
+    cont: Option<&Continuation<'a, '_>>,
+) -> &'a Stmt<'a> {
+    match stmt {
+        Stmt::Let(symbol, expr, layout, rest) => {
+            let substitutions = &env.substitutions;
+            let expr = map_expr(env.arena, expr, &mut |s| {
+                substitutions.get(&s).copied().unwrap_or(s)
+            });
+
+            let inlinable = env.inlinable;
+            if let Some(callee) = called_proc(&expr).and_then(|key| inlinable.get(&key)) {
+                let arguments = match &expr {
+                    Expr::Call(call) => call.arguments,
+                    _ => unreachable!(),
+                };
+
+                return inline_call(env, *symbol, *layout, callee, arguments, rest, cont);
+            }
+
+            let expr = fold_expr(env, expr, *layout);
+
+            match expr {
+                Expr::Literal(literal) => {
+                    env.literals.insert(*symbol, (literal, *layout));
+                }
+                Expr::Tag { tag_id, .. } => {
+                    env.tags.insert(*symbol, tag_id);
+                }
+                _ => {}
+            }
+
+            let rest = simplify_stmt(env, rest, cont);
+
+            if !env.used.contains(symbol) && is_pure(&expr) {
+                return rest;
+            }
+
+            for_each_symbol(&expr, &mut |s| {
+                env.used.insert(s);
+            });
+
+            env.arena.alloc(Stmt::Let(*symbol, expr, *layout, rest))
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let cond_symbol = env.substitute(*cond_symbol);
+
+            // The condition may have been folded to a literal, then only one branch can be taken.
+            if let Some(label) = env
+                .literals
+                .get(&cond_symbol)
+                .and_then(|(literal, _)| switch_label(literal))
+            {
+                let taken = branches
+                    .iter()
+                    .find(|(branch_label, _, _)| *branch_label == label)
+                    .map_or(default_branch.1, |(_, _, branch)| branch);
+
+                return simplify_stmt(env, taken, cont);
+            }
+
+            // Branches may bind the same symbols, so what one branch learns must not leak into the
+            // next.
+            let mut new_branches = Vec::with_capacity_in(branches.len(), env.arena);
+            for (label, info, branch) in branches.iter() {
+                let info = env.use_branch_info(info);
+                let branch = simplify_branch(env, branch, cont);
+
+                new_branches.push((*label, info, branch.clone()));
+            }
+
+            let default_info = env.use_branch_info(&default_branch.0);
+            let default_stmt = simplify_branch(env, default_branch.1, cont);
+
+            env.used.insert(cond_symbol);
+
+            env.arena.alloc(Stmt::Switch {
+                cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch: (default_info, default_stmt),
+                ret_layout: *ret_layout,
+            })
+        }
+        Stmt::Ret(symbol) => {
+            let symbol = env.substitute(*symbol);
+
+            match cont {
+                Some(cont) => {
+                    env.substitutions.insert(cont.symbol, symbol);
+
+                    simplify_stmt(env, cont.rest, cont.outer)
+                }
+                None => {
+                    env.used.insert(symbol);
+
+                    env.arena.alloc(Stmt::Ret(symbol))
+                }
+            }
+        }
+        Stmt::Refcounting(modify_rc, rest) => {
+            let rest = simplify_stmt(env, rest, cont);
+            let modify_rc = map_modify_rc(*modify_rc, &mut |s| env.use_symbol(s));
+
+            env.arena.alloc(Stmt::Refcounting(modify_rc, rest))
+        }
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => {
+            let remainder = simplify_stmt(env, remainder, cont);
+
+            env.arena.alloc(Stmt::Expect {
+                condition: env.use_symbol(*condition),
+                region: *region,
+                lookups: env.use_symbols(lookups),
+                variables,
+                remainder,
+            })
+        }
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => {
+            let remainder = simplify_stmt(env, remainder, cont);
+
+            env.arena.alloc(Stmt::ExpectFx {
+                condition: env.use_symbol(*condition),
+                region: *region,
+                lookups: env.use_symbols(lookups),
+                variables,
+                remainder,
+            })
+        }
+        Stmt::Dbg {
+            source_location,
+            source,
+            symbol,
+            variable,
+            remainder,
+        } => {
+            let remainder = simplify_stmt(env, remainder, cont);
+
+            env.arena.alloc(Stmt::Dbg {
+                source_location,
+                source,
+                symbol: env.use_symbol(*symbol),
+                variable: *variable,
+                remainder,
+            })
+        }
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let body = simplify_stmt(env, body, cont);
+            let remainder = simplify_stmt(env, remainder, cont);
+
+            env.arena.alloc(Stmt::Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Stmt::Jump(id, arguments) => {
+            let arguments = env.use_symbols(arguments);
+
+            env.arena.alloc(Stmt::Jump(*id, arguments))
+        }
+        Stmt::Crash(message, tag) => {
+            let message = env.use_symbol(*message);
+
+            env.arena.alloc(Stmt::Crash(message, *tag))
+        }
+    }
+}
+
+/// Replace `let symbol = callee arguments` followed by `rest` with the body of the callee.
+fn inline_call<'a>(
+    env: &mut Env<'a, '_>,
+    symbol: Symbol,
+    layout: InLayout<'a>,
+    callee: &Proc<'a>,
+    arguments: &[Symbol],
+    rest: &'a Stmt<'a>,
+    cont: Option<&Continuation<'a, '_>>,
+) -> &'a Stmt<'a> {
+    let mut renames: MutMap<Symbol, Symbol> = callee
+        .args
+        .iter()
+        .map(|(_, param)| *param)
+        .zip(arguments.iter().copied())
+        .collect();
+
+    if is_straight_line(&callee.body) {
+        // The body returns into `rest`, with the result of the call replaced by what it returns.
+        let body = rename_stmt(env, &callee.body, &mut renames, None);
+        let cont = Continuation {
+            symbol,
+            rest,
+            outer: cont,
+        };
+
+        simplify_stmt(env, body, Some(&cont))
+    } else if cont.is_none() && matches!(rest, Stmt::Ret(returned) if *returned == symbol) {
+        // A tail call, so the body can return directly.
+        let body = rename_stmt(env, &callee.body, &mut renames, None);
+
+        simplify_stmt(env, body, None)
+    } else {
+        // join id(symbol) = rest in <body, jumping to id wherever it returns>
+        let id = JoinPointId(env.unique_symbol());
+        let body = rename_stmt(env, &callee.body, &mut renames, Some(id));
+
+        let remainder = simplify_stmt(env, body, None);
+        let rest = simplify_stmt(env, rest, cont);
+
+        env.arena.alloc(Stmt::Join {
+            id,
+            parameters: env.arena.alloc([Param { symbol, layout }]),
+            body: rest,
+            remainder,
+        })
+    }
+}
+
+/// Copy the body of an inlined proc, giving everything it binds a fresh name, and jumping to
+/// `ret_jump` instead of returning if there is one.
+fn rename_stmt<'a>(
+    env: &mut Env<'a, '_>,
+    stmt: &Stmt<'a>,
+    renames: &mut MutMap<Symbol, Symbol>,
+    ret_jump: Option<JoinPointId>,
+) -> &'a Stmt<'a> {
+    let arena = env.arena;
+    let rename = |renames: &MutMap<Symbol, Symbol>, symbol: Symbol| {
+        renames.get(&symbol).copied().unwrap_or(symbol)
+    };
+
+    let new_stmt = match stmt {
+        Stmt::Let(symbol, expr, layout, rest) => {
+            let mut expr = map_expr(arena, expr, &mut |s| rename(renames, s));
+            refresh_call_spec_id(env, &mut expr);
+
+            let new_symbol = env.unique_symbol();
+            renames.insert(*symbol, new_symbol);
+
+            let rest = rename_stmt(env, rest, renames, ret_jump);
+
+            Stmt::Let(new_symbol, expr, *layout, rest)
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+            for (label, info, branch) in branches.iter() {
+                let info = map_branch_info(info, &mut |s| rename(renames, s));
+                let branch = rename_stmt(env, branch, renames, ret_jump);
+
+                new_branches.push((*label, info, branch.clone()));
+            }
+
+            let default_info = map_branch_info(&default_branch.0, &mut |s| rename(renames, s));
+            let default_stmt = rename_stmt(env, default_branch.1, renames, ret_jump);
+
+            Stmt::Switch {
+                cond_symbol: rename(renames, *cond_symbol),
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch: (default_info, default_stmt),
+                ret_layout: *ret_layout,
+            }
+        }
+        Stmt::Ret(symbol) => {
+            let symbol = rename(renames, *symbol);
+
+            match ret_jump {
+                Some(id) => Stmt::Jump(id, arena.alloc([symbol])),
+                None => Stmt::Ret(symbol),
+            }
+        }
+        Stmt::Refcounting(modify_rc, rest) => {
+            let modify_rc = map_modify_rc(*modify_rc, &mut |s| rename(renames, s));
+            let rest = rename_stmt(env, rest, renames, ret_jump);
+
+            Stmt::Refcounting(modify_rc, rest)
+        }
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let new_id = JoinPointId(env.unique_symbol());
+            renames.insert(id.0, new_id.0);
+
+            let parameters = Vec::from_iter_in(
+                parameters.iter().map(|param| {
+                    let symbol = env.unique_symbol();
+                    renames.insert(param.symbol, symbol);
+
+                    Param {
+                        symbol,
+                        layout: param.layout,
+                    }
+                }),
+                arena,
+            );
+
+            let body = rename_stmt(env, body, renames, ret_jump);
+            let remainder = rename_stmt(env, remainder, renames, ret_jump);
+
+            Stmt::Join {
+                id: new_id,
+                parameters: parameters.into_bump_slice(),
+                body,
+                remainder,
+            }
+        }
+        Stmt::Jump(id, arguments) => {
+            let arguments = Vec::from_iter_in(arguments.iter().map(|s| rename(renames, *s)), arena);
+
+            Stmt::Jump(
+                JoinPointId(rename(renames, id.0)),
+                arguments.into_bump_slice(),
+            )
+        }
+        Stmt::Crash(message, tag) => Stmt::Crash(rename(renames, *message), *tag),
+        Stmt::Expect { .. } | Stmt::ExpectFx { .. } | Stmt::Dbg { .. } => {
+            unreachable!("procs with expects or dbgs are not inlined")
+        }
+    };
+
+    arena.alloc(new_stmt)
+}
+
+fn refresh_call_spec_id<'a>(env: &mut Env<'a, '_>, expr: &mut Expr<'a>) {
+    if let Expr::Call(Call { call_type, .. }) = expr {
+        match call_type {
+            CallType::ByName {
+                specialization_id, ..
+            } => *specialization_id = env.fresh_call_spec_id(),
+            CallType::HigherOrder(higher_order) => {
+                let specialization_id = env.fresh_call_spec_id();
+
+                *higher_order = env.arena.alloc(HigherOrderLowLevel {
+                    passed_function: PassedFunction {
+                        specialization_id,
+                        ..higher_order.passed_function
+                    },
+                    ..**higher_order
+                });
+            }
+            CallType::ByPointer { .. } | CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+        }
+    }
+}
+
+/// Expressions that can be dropped if their result is never used: they have no effects and
+/// cannot crash.
+fn is_pure(expr: &Expr) -> bool {
+    use LowLevel::*;
+
+    match expr {
+        Expr::Literal(_)
+        | Expr::Struct(_)
+        | Expr::NullPointer
+        | Expr::StructAtIndex { .. }
+        | Expr::GetTagId { .. }
+        | Expr::UnionAtIndex { .. }
+        | Expr::GetElementPointer { .. }
+        | Expr::Array { .. }
+        | Expr::EmptyArray
+        | Expr::ErasedMake { .. }
+        | Expr::ErasedLoad { .. }
+        | Expr::FunctionPointer { .. } => true,
+        Expr::Tag { reuse, .. } => reuse.is_none(),
+        Expr::Call(Call {
+            call_type: CallType::LowLevel { op, .. },
+            ..
+        }) => matches!(
+            op,
+            NumAddWrap
+                | NumAddChecked
+                | NumAddSaturated
+                | NumSubWrap
+                | NumSubChecked
+                | NumSubSaturated
+                | NumMulWrap
+                | NumMulChecked
+                | NumMulSaturated
+                | NumGt
+                | NumGte
+                | NumLt
+                | NumLte
+                | NumCompare
+                | NumIsNan
+                | NumIsInfinite
+                | NumIsFinite
+                | NumBitwiseAnd
+                | NumBitwiseXor
+                | NumBitwiseOr
+                | NumIntCast
+                | NumToFrac
+                | NumToFloatCast
+                | NumToIntChecked
+                | NumToFloatChecked
+                | NumCountLeadingZeroBits
+                | NumCountTrailingZeroBits
+                | NumCountOneBits
+                | Eq
+                | NotEq
+                | And
+                | Or
+                | Not
+                | ListLen
+                | ListGetCapacity
+                | StrIsEmpty
+                | StrCountUtf8Bytes
+        ),
+        Expr::Call(_)
+        | Expr::Alloca { .. }
+        | Expr::Reset { .. }
+        | Expr::ResetRef { .. }
+        | Expr::StackAlloc { .. }
+        | Expr::RuntimeErrorFunction(_) => false,
+    }
+}
+
+/// Evaluate a lowlevel operation whose arguments are all literals. Operations that would crash
+/// at runtime, like an overflowing `Num.add`, are left alone.
+fn fold_expr<'a>(env: &Env<'a, '_>, expr: Expr<'a>, layout: InLayout<'a>) -> Expr<'a> {
+    let (op, arguments) = match &expr {
+        Expr::Call(Call {
+            call_type: CallType::LowLevel { op, .. },
+            arguments,
+        }) => (*op, *arguments),
+        Expr::GetTagId { structure, .. } => {
+            // The tag of a union that was built in this proc is known.
+            return env
+                .tags
+                .get(structure)
+                .and_then(|tag_id| {
+                    int_width(env.interner, layout)
+                        .and_then(|width| int_literal(width, *tag_id as i128))
+                })
+                .map_or(expr, Expr::Literal);
+        }
+        _ => return expr,
+    };
+
+    let mut literals = std::vec::Vec::with_capacity(arguments.len());
+    for argument in arguments.iter() {
+        match env.literals.get(argument) {
+            Some(literal) => literals.push(*literal),
+            None => return expr,
+        }
+    }
+
+    match fold_lowlevel(env.interner, op, &literals) {
+        Some(literal) => Expr::Literal(literal),
+        None => expr,
+    }
+}
+
+/// The result of a lowlevel operation on literals, if it can be computed at compile time.
+fn fold_lowlevel<'a>(
+    interner: &STLayoutInterner<'a>,
+    op: LowLevel,
+    literals: &[(Literal<'a>, InLayout<'a>)],
+) -> Option<Literal<'a>> {
+    match literals {
+        [(Literal::Bool(a), _)] => fold_bool_unary(op, *a),
+        [(Literal::Bool(a), _), (Literal::Bool(b), _)] => fold_bool_binary(op, *a, *b),
+        [(Literal::Int(a), layout)] => int_width(interner, *layout)
+            .and_then(|width| fold_int_unary(op, width, i128::from_ne_bytes(*a))),
+        [(Literal::Int(a), a_layout), (Literal::Int(b), b_layout)] => {
+            match (
+                int_width(interner, *a_layout),
+                int_width(interner, *b_layout),
+            ) {
+                (Some(width), Some(b_width)) if width == b_width => {
+                    fold_int_binary(op, width, i128::from_ne_bytes(*a), i128::from_ne_bytes(*b))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn fold_bool_unary<'a>(op: LowLevel, a: bool) -> Option<Literal<'a>> {
+    match op {
+        LowLevel::Not => Some(Literal::Bool(!a)),
+        _ => None,
+    }
+}
+
+fn fold_bool_binary<'a>(op: LowLevel, a: bool, b: bool) -> Option<Literal<'a>> {
+    let result = match op {
+        LowLevel::And => a && b,
+        LowLevel::Or => a || b,
+        LowLevel::Eq => a == b,
+        LowLevel::NotEq => a != b,
+        _ => return None,
+    };
+
+    Some(Literal::Bool(result))
+}
+
+fn fold_int_unary<'a>(op: LowLevel, width: IntWidth, a: i128) -> Option<Literal<'a>> {
+    match op {
+        LowLevel::NumNeg => int_literal(width, a.checked_neg()?),
+        _ => None,
+    }
+}
+
+fn fold_int_binary<'a>(op: LowLevel, width: IntWidth, a: i128, b: i128) -> Option<Literal<'a>> {
+    use LowLevel::*;
+
+    match op {
+        NumAdd => int_literal(width, a.checked_add(b)?),
+        NumSub => int_literal(width, a.checked_sub(b)?),
+        NumMul => int_literal(width, a.checked_mul(b)?),
+        NumAddWrap => Some(wrapping_int_literal(width, a.wrapping_add(b))),
+        NumSubWrap => Some(wrapping_int_literal(width, a.wrapping_sub(b))),
+        NumMulWrap => Some(wrapping_int_literal(width, a.wrapping_mul(b))),
+        NumDivTruncUnchecked => int_literal(width, a.checked_div(b)?),
+        NumRemUnchecked => int_literal(width, a.checked_rem(b)?),
+        NumBitwiseAnd => int_literal(width, a & b),
+        NumBitwiseOr => int_literal(width, a | b),
+        NumBitwiseXor => int_literal(width, a ^ b),
+        NumGt => Some(Literal::Bool(a > b)),
+        NumGte => Some(Literal::Bool(a >= b)),
+        NumLt => Some(Literal::Bool(a < b)),
+        NumLte => Some(Literal::Bool(a <= b)),
+        Eq => Some(Literal::Bool(a == b)),
+        NotEq => Some(Literal::Bool(a != b)),
+        _ => None,
+    }
+}
+
+/// The width of an integer layout whose literals are stored as an i128. That is every one but
+/// U128, which has literals of its own.
+fn int_width<'a>(interner: &STLayoutInterner<'a>, layout: InLayout<'a>) -> Option<IntWidth> {
+    match interner.get_repr(layout) {
+        LayoutRepr::Builtin(Builtin::Int(IntWidth::U128)) => None,
+        LayoutRepr::Builtin(Builtin::Int(width)) => Some(width),
+        _ => None,
+    }
+}
+
+/// The literal for `value`, if it fits in an integer of this width.
+fn int_literal<'a>(width: IntWidth, value: i128) -> Option<Literal<'a>> {
+    let fits = match width {
+        IntWidth::I128 => true,
+        _ => {
+            let bits = width.stack_size() * 8;
+            let (min, max) = if width.is_signed() {
+                (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+            } else {
+                (0, (1i128 << bits) - 1)
+            };
+
+            min <= value && value <= max
+        }
+    };
+
+    fits.then(|| Literal::Int(value.to_ne_bytes()))
+}
+
+/// The literal for `value` wrapped around to fit in an integer of this width.
+fn wrapping_int_literal<'a>(width: IntWidth, value: i128) -> Literal<'a> {
+    let wrapped = match width {
+        IntWidth::I128 => value,
+        _ => {
+            let bits = width.stack_size() * 8;
+            let truncated = value & ((1i128 << bits) - 1);
+
+            if width.is_signed() && truncated >= 1i128 << (bits - 1) {
+                truncated - (1i128 << bits)
+            } else {
+                truncated
+            }
+        }
+    };
+
+    Literal::Int(wrapped.to_ne_bytes())
+}
+
+/// The label of the switch branch taken when the condition is this literal.
+fn switch_label(literal: &Literal) -> Option<u64> {
+    match literal {
+        Literal::Int(bytes) => Some(i128::from_ne_bytes(*bytes) as u64),
+        Literal::Bool(value) => Some(*value as u64),
+        Literal::Byte(value) => Some(*value as u64),
+        Literal::U128(_) | Literal::Float(_) | Literal::Decimal(_) | Literal::Str(_) => None,
+    }
+}
+
+pub(crate) fn map_branch_info<'a>(
+    info: &BranchInfo<'a>,
+    f: &mut impl FnMut(Symbol) -> Symbol,
+) -> BranchInfo<'a> {
+    match info {
+        BranchInfo::None => BranchInfo::None,
+        BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        } => BranchInfo::Constructor {
+            scrutinee: f(*scrutinee),
+            layout: *layout,
+            tag_id: *tag_id,
+        },
+        BranchInfo::List { scrutinee, len } => BranchInfo::List {
+            scrutinee: f(*scrutinee),
+            len: *len,
+        },
+        BranchInfo::Unique { scrutinee, unique } => BranchInfo::Unique {
+            scrutinee: f(*scrutinee),
+            unique: *unique,
+        },
+    }
+}
+
+pub(crate) fn map_modify_rc(modify_rc: ModifyRc, f: &mut impl FnMut(Symbol) -> Symbol) -> ModifyRc {
+    match modify_rc {
+        ModifyRc::Inc(symbol, count) => ModifyRc::Inc(f(symbol), count),
+        ModifyRc::Dec(symbol) => ModifyRc::Dec(f(symbol)),
+        ModifyRc::DecRef(symbol) => ModifyRc::DecRef(f(symbol)),
+        ModifyRc::Free(symbol) => ModifyRc::Free(f(symbol)),
+    }
+}
+
+/// Call `f` on every symbol the expression uses.
+fn for_each_symbol(expr: &Expr, f: &mut impl FnMut(Symbol)) {
+    match expr {
+        Expr::Call(Call {
+            call_type,
+            arguments,
+        }) => {
+            match call_type {
+                CallType::ByPointer { pointer, .. } => f(*pointer),
+                CallType::HigherOrder(higher_order) => {
+                    map_higher_order(higher_order.op, &mut |symbol| {
+                        f(symbol);
+                        symbol
+                    });
+                    f(higher_order.passed_function.captured_environment);
+                }
+                CallType::ByName { .. } | CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+            }
+
+            arguments.iter().copied().for_each(f);
+        }
+        Expr::Tag {
+            arguments, reuse, ..
+        } => {
+            arguments.iter().copied().for_each(&mut *f);
+            if let Some(reuse) = reuse {
+                f(reuse.symbol);
+            }
+        }
+        Expr::Struct(fields) => fields.iter().copied().for_each(f),
+        Expr::StructAtIndex { structure, .. }
+        | Expr::GetTagId { structure, .. }
+        | Expr::UnionAtIndex { structure, .. }
+        | Expr::GetElementPointer { structure, .. }
+        | Expr::ErasedLoad {
+            symbol: structure, ..
+        }
+        | Expr::Reset {
+            symbol: structure, ..
+        }
+        | Expr::ResetRef {
+            symbol: structure, ..
+        } => f(*structure),
+        Expr::Array { elems, .. } => elems
+            .iter()
+            .filter_map(ListLiteralElement::to_symbol)
+            .for_each(f),
+        Expr::ErasedMake { value, callee } => {
+            value.iter().copied().for_each(&mut *f);
+            f(*callee);
+        }
+        Expr::Alloca { initializer, .. } => initializer.iter().copied().for_each(f),
+        Expr::Literal(_)
+        | Expr::NullPointer
+        | Expr::EmptyArray
+        | Expr::FunctionPointer { .. }
+        | Expr::StackAlloc { .. }
+        | Expr::RuntimeErrorFunction(_) => {}
+    }
+}
+
+fn map_higher_order(op: HigherOrder, f: &mut impl FnMut(Symbol) -> Symbol) -> HigherOrder {
+    match op {
+        HigherOrder::ListMap { xs } => HigherOrder::ListMap { xs: f(xs) },
+        HigherOrder::ListMap2 { xs, ys } => HigherOrder::ListMap2 {
+            xs: f(xs),
+            ys: f(ys),
+        },
+        HigherOrder::ListMap3 { xs, ys, zs } => HigherOrder::ListMap3 {
+            xs: f(xs),
+            ys: f(ys),
+            zs: f(zs),
+        },
+        HigherOrder::ListMap4 { xs, ys, zs, ws } => HigherOrder::ListMap4 {
+            xs: f(xs),
+            ys: f(ys),
+            zs: f(zs),
+            ws: f(ws),
+        },
+        HigherOrder::ListSortWith { xs } => HigherOrder::ListSortWith { xs: f(xs) },
+    }
+}
+
+/// Apply `f` to every symbol the expression uses.
+pub(crate) fn map_expr<'a>(
+    arena: &'a Bump,
+    expr: &Expr<'a>,
+    f: &mut impl FnMut(Symbol) -> Symbol,
+) -> Expr<'a> {
+    let map_symbols = |symbols: &[Symbol], f: &mut dyn FnMut(Symbol) -> Symbol| {
+        Vec::from_iter_in(symbols.iter().map(|s| f(*s)), arena).into_bump_slice()
+    };
+
+    match expr {
+        Expr::Call(Call {
+            call_type,
+            arguments,
+        }) => {
+            let call_type = match call_type {
+                CallType::ByPointer {
+                    pointer,
+                    ret_layout,
+                    arg_layouts,
+                } => CallType::ByPointer {
+                    pointer: f(*pointer),
+                    ret_layout: *ret_layout,
+                    arg_layouts,
+                },
+                CallType::HigherOrder(higher_order) => {
+                    let op = map_higher_order(higher_order.op, f);
+                    let captured_environment = f(higher_order.passed_function.captured_environment);
+
+                    CallType::HigherOrder(arena.alloc(HigherOrderLowLevel {
+                        op,
+                        passed_function: PassedFunction {
+                            captured_environment,
+                            ..higher_order.passed_function
+                        },
+                        ..**higher_order
+                    }))
+                }
+                CallType::ByName { .. } | CallType::Foreign { .. } | CallType::LowLevel { .. } => {
+                    call_type.clone()
+                }
+            };
+
+            Expr::Call(Call {
+                call_type,
+                arguments: map_symbols(arguments, f),
+            })
+        }
+        Expr::Tag {
+            tag_layout,
+            tag_id,
+            arguments,
+            reuse,
+        } => Expr::Tag {
+            tag_layout: *tag_layout,
+            tag_id: *tag_id,
+            arguments: map_symbols(arguments, f),
+            reuse: reuse.map(|reuse| crate::ir::ReuseToken {
+                symbol: f(reuse.symbol),
+                ..reuse
+            }),
+        },
+        Expr::Struct(fields) => Expr::Struct(map_symbols(fields, f)),
+        Expr::StructAtIndex {
+            index,
+            field_layouts,
+            structure,
+        } => Expr::StructAtIndex {
+            index: *index,
+            field_layouts,
+            structure: f(*structure),
+        },
+        Expr::GetTagId {
+            structure,
+            union_layout,
+        } => Expr::GetTagId {
+            structure: f(*structure),
+            union_layout: *union_layout,
+        },
+        Expr::UnionAtIndex {
+            structure,
+            tag_id,
+            union_layout,
+            index,
+        } => Expr::UnionAtIndex {
+            structure: f(*structure),
+            tag_id: *tag_id,
+            union_layout: *union_layout,
+            index: *index,
+        },
+        Expr::GetElementPointer {
+            structure,
+            union_layout,
+            indices,
+        } => Expr::GetElementPointer {
+            structure: f(*structure),
+            union_layout: *union_layout,
+            indices,
+        },
+        Expr::Array { elem_layout, elems } => {
+            let elems = Vec::from_iter_in(
+                elems.iter().map(|elem| match elem {
+                    ListLiteralElement::Symbol(symbol) => ListLiteralElement::Symbol(f(*symbol)),
+                    ListLiteralElement::Literal(_) => *elem,
+                }),
+                arena,
+            );
+
+            Expr::Array {
+                elem_layout: *elem_layout,
+                elems: elems.into_bump_slice(),
+            }
+        }
+        Expr::ErasedMake { value, callee } => Expr::ErasedMake {
+            value: value.map(&mut *f),
+            callee: f(*callee),
+        },
+        Expr::ErasedLoad { symbol, field } => Expr::ErasedLoad {
+            symbol: f(*symbol),
+            field: *field,
+        },
+        Expr::Alloca {
+            element_layout,
+            initializer,
+        } => Expr::Alloca {
+            element_layout: *element_layout,
+            initializer: initializer.map(&mut *f),
+        },
+        Expr::Reset {
+            symbol,
+            update_mode,
+        } => Expr::Reset {
+            symbol: f(*symbol),
+            update_mode: *update_mode,
+        },
+        Expr::ResetRef {
+            symbol,
+            update_mode,
+        } => Expr::ResetRef {
+            symbol: f(*symbol),
+            update_mode: *update_mode,
+        },
+        Expr::Literal(_)
+        | Expr::NullPointer
+        | Expr::EmptyArray
+        | Expr::FunctionPointer { .. }
+        | Expr::StackAlloc { .. }
+        | Expr::RuntimeErrorFunction(_) => expr.clone(),
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::manual_map)]
 
+use crate::borrow::Ownership;
 use crate::ir::erased::{build_erased_function, ResolvedErasedLambda};
 use crate::ir::literal::{make_num_literal, IntOrFloatValue};
 use crate::layout::{
@@ -41,7 +42,7 @@ use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder};
 
 use pattern::{from_can_pattern, store_pattern, Pattern};
 
-pub use literal::{ListLiteralElement, Literal};
+pub use literal::{static_list_bytes, ListLiteralElement, Literal};
 
 mod boxed;
 mod decision_tree;
@@ -346,16 +347,44 @@ impl<'a> Proc<'a> {
         A: Clone,
         I: LayoutInterner<'a>,
     {
-        let args_doc = self.args.iter().map(|(layout, symbol)| {
-            let arg_doc = symbol_to_doc(alloc, *symbol, pretty);
-            if pretty_print_ir_symbols() {
-                arg_doc
-                    .append(alloc.reflow(": "))
-                    .append(interner.to_doc_top(*layout, alloc))
-            } else {
-                arg_doc
-            }
-        });
+        self.to_doc_help(alloc, interner, pretty, None)
+    }
+
+    fn to_doc_help<'b, D, A, I>(
+        &'b self,
+        alloc: &'b D,
+        interner: &'b I,
+        pretty: bool,
+        borrow_signature: Option<&'b [Ownership]>,
+    ) -> DocBuilder<'b, D, A>
+    where
+        D: DocAllocator<'b, A>,
+        D::Doc: Clone,
+        A: Clone,
+        I: LayoutInterner<'a>,
+    {
+        let args_doc = self
+            .args
+            .iter()
+            .enumerate()
+            .map(|(index, (layout, symbol))| {
+                let borrowed = borrow_signature.and_then(|signature| signature.get(index));
+
+                let arg_doc = if matches!(borrowed, Some(Ownership::Borrowed)) {
+                    alloc
+                        .text("borrowed ")
+                        .append(symbol_to_doc(alloc, *symbol, pretty))
+                } else {
+                    symbol_to_doc(alloc, *symbol, pretty)
+                };
+                if pretty_print_ir_symbols() {
+                    arg_doc
+                        .append(alloc.reflow(": "))
+                        .append(interner.to_doc_top(*layout, alloc))
+                } else {
+                    arg_doc
+                }
+            });
 
         if pretty_print_ir_symbols() {
             alloc
@@ -396,6 +425,27 @@ impl<'a> Proc<'a> {
         w.push(b'\n');
         String::from_utf8(w).unwrap()
     }
+
+    /// Like [Proc::to_pretty], but marks the parameters that the proc borrows from its caller.
+    pub fn to_pretty_with_borrow_signature<I>(
+        &self,
+        interner: &I,
+        width: usize,
+        pretty: bool,
+        borrow_signature: Option<&[Ownership]>,
+    ) -> String
+    where
+        I: LayoutInterner<'a>,
+    {
+        let allocator = BoxAllocator;
+        let mut w = std::vec::Vec::new();
+        self.to_doc_help::<_, (), _>(&allocator, interner, pretty, borrow_signature)
+            .1
+            .render(width, &mut w)
+            .unwrap();
+        w.push(b'\n');
+        String::from_utf8(w).unwrap()
+    }
 }
 
 /// A host-exposed function must be specialized; it's a seed for subsequent specializations
@@ -898,6 +948,9 @@ impl<'a> SpecializationStack<'a> {
 pub type HostExposedLambdaSets<'a> =
     std::vec::Vec<(LambdaName<'a>, Symbol, HostExposedLambdaSet<'a>)>;
 
+/// The function each anonymous closure was defined in, so debug output can name the closure
+pub type ClosureParents = MutMap<Symbol, Symbol>;
+
 #[derive(Clone, Debug)]
 pub struct Procs<'a> {
     pub partial_procs: PartialProcs<'a>,
@@ -909,6 +962,7 @@ pub struct Procs<'a> {
     pub externals_we_need: BumpMap<ModuleId, ExternalSpecializations<'a>>,
     symbol_specializations: SymbolSpecializations<'a>,
     specialization_stack: SpecializationStack<'a>,
+    pub closure_parents: ClosureParents,
 
     pub imported_module_thunks: &'a [Symbol],
     pub module_thunks: &'a [Symbol],
@@ -927,6 +981,7 @@ impl<'a> Procs<'a> {
             host_exposed_lambda_sets: std::vec::Vec::new(),
             symbol_specializations: Default::default(),
             specialization_stack: SpecializationStack(Vec::with_capacity_in(16, arena)),
+            closure_parents: MutMap::default(),
 
             imported_module_thunks: &[],
             module_thunks: &[],
@@ -1038,6 +1093,10 @@ impl<'a> Procs<'a> {
 
         let top_level = ProcLayout::from_raw_named(env.arena, name, raw_layout);
 
+        if let Some(parent) = self.specialization_stack.0.last() {
+            self.closure_parents.insert(name.name(), *parent);
+        }
+
         // anonymous functions cannot reference themselves, therefore cannot be tail-recursive
         // EXCEPT when the closure conversion makes it tail-recursive.
         let is_self_recursive = match top_level
@@ -1727,7 +1786,7 @@ impl<'a> Call<'a> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CallSpecId {
     id: u32,
 }
@@ -1737,6 +1796,11 @@ impl CallSpecId {
         self.id.to_ne_bytes()
     }
 
+    /// The id after this one, for passes that add calls to a proc after specialization
+    pub(crate) fn next(self) -> Self {
+        Self { id: self.id + 1 }
+    }
+
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* specialization so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
@@ -1752,6 +1816,11 @@ impl UpdateModeId {
         self.id.to_ne_bytes()
     }
 
+    /// The id after this one, for passes that build new procs after specialization
+    pub(crate) fn next(self) -> Self {
+        Self { id: self.id + 1 }
+    }
+
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* alias analysis so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
@@ -1947,6 +2016,12 @@ pub enum Expr<'a> {
         update_mode: UpdateModeId,
     },
 
+    // Stack memory for a tag that never escapes its proc, laid out like a heap allocation with a read-only refcount.
+    // Used as the reuse token of that tag, so that constructing it does not allocate.
+    StackAlloc {
+        tag_layout: UnionLayout<'a>,
+    },
+
     RuntimeErrorFunction(&'a str),
 }
 
@@ -2084,6 +2159,7 @@ impl<'a> Expr<'a> {
                 .append(", id: ")
                 .append(format!("{update_mode:?}"))
                 .append(" }"),
+            StackAlloc { .. } => alloc.text("StackAlloc"),
             Struct(args) => {
                 let it = args.iter().map(|s| symbol_to_doc(alloc, *s, pretty));
 
@@ -7255,6 +7331,12 @@ fn to_opt_branches<'a>(
             continue;
         }
 
+        // A trivially true guard may have made the branches after it redundant, so it must not
+        // be compiled into a check that can fall through to them.
+        let guard = when_branch
+            .guard
+            .filter(|guard| !roc_can::exhaustive::is_trivially_true(&guard.value));
+
         for loc_pattern in when_branch.patterns {
             match from_can_pattern(env, procs, layout_cache, &loc_pattern.pattern.value) {
                 Ok((mono_pattern, assignments)) => {
@@ -7288,13 +7370,13 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((mono_pattern, guard.clone(), loc_expr.value));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
-                        when_branch.guard.clone(),
+                        guard.clone(),
                         roc_can::expr::Expr::RuntimeError(runtime_error),
                     ));
                 }
@@ -7871,6 +7953,10 @@ fn substitute_in_expr<'a>(
             unreachable!("reset(ref) has not been introduced yet")
         }
 
+        StackAlloc { .. } => {
+            unreachable!("stack allocations have not been introduced yet")
+        }
+
         Struct(args) => {
             let mut did_change = false;
             let new_args = Vec::from_iter_in(
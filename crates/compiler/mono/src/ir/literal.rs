@@ -36,6 +36,38 @@ pub enum Literal<'a> {
     Byte(u8),
 }
 
+impl<'a> Literal<'a> {
+    /// The little-endian bytes of this literal when it is stored in memory with this layout, for
+    /// backends that put literals in static data. A string refers to its bytes rather than
+    /// containing them, so it has none.
+    pub fn to_le_bytes(&self, repr: LayoutRepr) -> Option<std::vec::Vec<u8>> {
+        let bytes = match (self, repr) {
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Int(width))) => {
+                let bytes = i128::from_ne_bytes(*bytes).to_le_bytes();
+
+                bytes[..width.stack_size() as usize].to_vec()
+            }
+            (Literal::U128(bytes), LayoutRepr::Builtin(Builtin::Int(IntWidth::U128))) => {
+                u128::from_ne_bytes(*bytes).to_le_bytes().to_vec()
+            }
+            (Literal::Float(n), LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32))) => {
+                (*n as f32).to_le_bytes().to_vec()
+            }
+            (Literal::Float(n), LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64))) => {
+                n.to_le_bytes().to_vec()
+            }
+            (Literal::Decimal(bytes), LayoutRepr::Builtin(Builtin::Decimal)) => {
+                i128::from_ne_bytes(*bytes).to_le_bytes().to_vec()
+            }
+            (Literal::Bool(b), LayoutRepr::Builtin(Builtin::Bool)) => vec![*b as u8],
+            (Literal::Byte(b), LayoutRepr::Builtin(Builtin::Int(IntWidth::U8))) => vec![*b],
+            _ => return None,
+        };
+
+        Some(bytes)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ListLiteralElement<'a> {
     Literal(Literal<'a>),
@@ -51,6 +83,23 @@ impl<'a> ListLiteralElement<'a> {
     }
 }
 
+/// The little-endian bytes of the elements of a list literal, if they are all literals that can be
+/// put in static data.
+pub fn static_list_bytes(
+    elems: &[ListLiteralElement],
+    elem_repr: LayoutRepr,
+) -> Option<std::vec::Vec<u8>> {
+    let mut bytes = std::vec::Vec::new();
+    for elem in elems {
+        match elem {
+            ListLiteralElement::Literal(literal) => bytes.extend(literal.to_le_bytes(elem_repr)?),
+            ListLiteralElement::Symbol(_) => return None,
+        }
+    }
+
+    Some(bytes)
+}
+
 pub enum NumLiteral {
     Int([u8; 16], IntWidth),
     U128([u8; 16]),
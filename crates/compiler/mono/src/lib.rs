@@ -9,13 +9,16 @@
 // Not a useful lint for us
 #![allow(clippy::too_many_arguments)]
 
+pub mod borrow;
 pub mod code_gen_help;
 pub mod drop_specialization;
 pub mod inc_dec;
+pub mod inline;
 pub mod ir;
 pub mod layout;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod stack_alloc;
 pub mod tail_recursion;
 
 pub mod debug;
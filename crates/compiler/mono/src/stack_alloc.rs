@@ -0,0 +1,480 @@
+//! Escape analysis that moves heap-allocated tags to the stack.
+//!
+//! Boxes and the tags of recursive unions are allocated on the heap and reference counted. When
+//! such a value never leaves the proc that creates it, its memory can live in the stack frame of
+//! that proc instead. A value escapes when it is returned, stored in a list or in a structure that
+//! escapes, passed to a join point parameter that escapes, captured by a closure, or passed to a
+//! proc that lets it escape. Which parameters escape is found with a fixed point over the call
+//! graph: all parameters start out not escaping, and a proc is revisited whenever one of the procs
+//! it calls gets a new escaping parameter.
+//!
+//! The stack memory is laid out like a heap allocation with a read-only refcount, and becomes the
+//! reuse token of the tag. The refcount operations on the tag are removed, and those on its
+//! aliases, like the parameter of a callee, do nothing at runtime. A read-only value never drops
+//! its fields, so a tag only moves to the stack when all its reference counted fields are on the
+//! stack as well.
+
+use std::collections::VecDeque;
+
+use bumpalo::Bump;
+use roc_collections::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::borrow::for_each_stmt;
+use crate::inline::called_proc;
+use crate::ir::{
+    BranchInfo, Call, CallType, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Param,
+    Proc, ProcLayout, ReuseToken, Stmt, UpdateModeIds,
+};
+use crate::layout::{InLayout, LayoutInterner, STLayoutInterner, UnionLayout};
+
+type ProcKey<'a> = (Symbol, ProcLayout<'a>);
+
+/// Allocate the boxes and recursive tags that never escape their proc on the stack.
+///
+/// This runs after reference counting and reset/reuse, and the backend has to support
+/// [Expr::StackAlloc].
+pub fn stack_allocate_tags<'a>(
+    arena: &'a Bump,
+    interner: &STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    update_mode_ids: &mut UpdateModeIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let escaping_params = infer_escaping_params(procs);
+
+    for proc in procs.values_mut() {
+        let escaping = escaping_symbols(&escaping_params, proc);
+        let stack_tags = stack_tags(interner, &escaping, proc);
+
+        if stack_tags.is_empty() {
+            continue;
+        }
+
+        let tokens = stack_tags
+            .into_iter()
+            .map(|symbol| (symbol, Symbol::new(home, ident_ids.gen_unique())))
+            .collect();
+
+        let body = rewrite_stmt(arena, update_mode_ids, &tokens, &proc.body);
+        proc.body = body.clone();
+    }
+}
+
+/// Find for every proc which of its parameters escape.
+fn infer_escaping_params<'a>(
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> MutMap<ProcKey<'a>, std::vec::Vec<bool>> {
+    let mut callers: MutMap<ProcKey<'a>, MutSet<ProcKey<'a>>> = MutMap::default();
+
+    for (key, proc) in procs.iter() {
+        for_each_stmt(&proc.body, &mut |stmt| {
+            if let Stmt::Let(_, expr, _, _) = stmt {
+                if let Some(callee) = called_proc(expr) {
+                    callers.entry(callee).or_default().insert(*key);
+                }
+            }
+        });
+    }
+
+    let mut escaping_params: MutMap<ProcKey<'a>, std::vec::Vec<bool>> = procs
+        .iter()
+        .map(|(key, proc)| (*key, vec![false; proc.args.len()]))
+        .collect();
+
+    let mut worklist: VecDeque<ProcKey<'a>> = escaping_params.keys().copied().collect();
+    let mut queued: MutSet<ProcKey<'a>> = worklist.iter().copied().collect();
+
+    while let Some(key) = worklist.pop_front() {
+        queued.remove(&key);
+
+        let proc = &procs[&key];
+        let escaping = escaping_symbols(&escaping_params, proc);
+
+        let params = escaping_params.get_mut(&key).unwrap();
+        let mut changed = false;
+        for ((_, symbol), escapes) in proc.args.iter().zip(params.iter_mut()) {
+            if !*escapes && escaping.contains(symbol) {
+                *escapes = true;
+                changed = true;
+            }
+        }
+
+        // the callers may now pass a value that escapes
+        if changed {
+            for caller in callers.get(&key).into_iter().flatten() {
+                if queued.insert(*caller) {
+                    worklist.push_back(*caller);
+                }
+            }
+        }
+    }
+
+    escaping_params
+}
+
+/// Collect the symbols of a proc whose values may outlive the proc.
+fn escaping_symbols<'a>(
+    escaping_params: &MutMap<ProcKey<'a>, std::vec::Vec<bool>>,
+    proc: &Proc<'a>,
+) -> MutSet<Symbol> {
+    let mut join_points: MutMap<JoinPointId, &[Param<'a>]> = MutMap::default();
+    for_each_stmt(&proc.body, &mut |stmt| {
+        if let Stmt::Join { id, parameters, .. } = stmt {
+            join_points.insert(*id, parameters);
+        }
+    });
+
+    let mut escaping = std::vec::Vec::new();
+    // if the key escapes, so do the values
+    let mut flows_into: MutMap<Symbol, std::vec::Vec<Symbol>> = MutMap::default();
+    let mut flow = |from: Symbol, into: Symbol| flows_into.entry(into).or_default().push(from);
+
+    for_each_stmt(&proc.body, &mut |stmt| match stmt {
+        Stmt::Let(binding, expr, _, _) => match expr {
+            Expr::Tag { arguments, .. } | Expr::Struct(arguments) => {
+                for argument in arguments.iter() {
+                    flow(*argument, *binding);
+                }
+            }
+            // a field is part of its structure
+            Expr::StructAtIndex { structure, .. }
+            | Expr::UnionAtIndex { structure, .. }
+            | Expr::GetElementPointer { structure, .. } => flow(*structure, *binding),
+            Expr::Array { elems, .. } => {
+                escaping.extend(elems.iter().filter_map(ListLiteralElement::to_symbol));
+            }
+            Expr::ErasedMake { value, .. } => escaping.extend(value.iter().copied()),
+            Expr::Alloca { initializer, .. } => escaping.extend(initializer.iter().copied()),
+            Expr::Call(Call {
+                call_type,
+                arguments,
+            }) => match call_type {
+                CallType::ByName { .. } => {
+                    let params = called_proc(expr).and_then(|callee| escaping_params.get(&callee));
+
+                    match params {
+                        Some(params) => escaping.extend(
+                            (arguments.iter().zip(params))
+                                .filter(|(_, escapes)| **escapes)
+                                .map(|(argument, _)| *argument),
+                        ),
+                        None => escaping.extend(arguments.iter().copied()),
+                    }
+                }
+                // these only read their arguments
+                CallType::LowLevel {
+                    op: LowLevel::Eq | LowLevel::NotEq | LowLevel::RefCountIsUnique,
+                    ..
+                } => {}
+                CallType::LowLevel { .. }
+                | CallType::ByPointer { .. }
+                | CallType::Foreign { .. }
+                | CallType::HigherOrder(_) => escaping.extend(arguments.iter().copied()),
+            },
+            Expr::Literal(_)
+            | Expr::NullPointer
+            | Expr::GetTagId { .. }
+            | Expr::EmptyArray
+            | Expr::ErasedLoad { .. }
+            | Expr::FunctionPointer { .. }
+            | Expr::Reset { .. }
+            | Expr::ResetRef { .. }
+            | Expr::StackAlloc { .. }
+            | Expr::RuntimeErrorFunction(_) => {}
+        },
+        Stmt::Ret(symbol) | Stmt::Crash(symbol, _) => escaping.push(*symbol),
+        Stmt::Jump(id, arguments) => {
+            for (argument, param) in arguments.iter().zip(join_points[id].iter()) {
+                flow(*argument, param.symbol);
+            }
+        }
+        // the host reads these values
+        Stmt::Expect { lookups, .. } | Stmt::ExpectFx { lookups, .. } => {
+            escaping.extend(lookups.iter().copied());
+        }
+        Stmt::Dbg { symbol, .. } => escaping.push(*symbol),
+        Stmt::Switch { .. } | Stmt::Refcounting(..) | Stmt::Join { .. } => {}
+    });
+
+    let mut escaped = MutSet::default();
+    while let Some(symbol) = escaping.pop() {
+        if escaped.insert(symbol) {
+            escaping.extend(flows_into.get(&symbol).into_iter().flatten().copied());
+        }
+    }
+
+    escaped
+}
+
+/// Find the tags of a proc that can be allocated on the stack.
+fn stack_tags<'a>(
+    interner: &STLayoutInterner<'a>,
+    escaping: &MutSet<Symbol>,
+    proc: &Proc<'a>,
+) -> MutSet<Symbol> {
+    let mut layouts: MutMap<Symbol, InLayout<'a>> = proc
+        .args
+        .iter()
+        .map(|(layout, symbol)| (*symbol, *layout))
+        .collect();
+
+    // the stack memory of a tag is reused every time its definition is evaluated again
+    let mut looping_join_points = MutSet::default();
+    for_each_stmt(&proc.body, &mut |stmt| match stmt {
+        Stmt::Let(binding, _, layout, _) => {
+            layouts.insert(*binding, *layout);
+        }
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            ..
+        } => {
+            layouts.extend(parameters.iter().map(|param| (param.symbol, param.layout)));
+
+            let mut loops = false;
+            for_each_stmt(body, &mut |stmt| {
+                loops |= matches!(stmt, Stmt::Jump(target, _) if target == id);
+            });
+
+            if loops {
+                looping_join_points.insert(*id);
+            }
+        }
+        _ => {}
+    });
+
+    let mut candidates: MutMap<Symbol, &[Symbol]> = MutMap::default();
+    // tags without fields can be represented as a null pointer, they are never allocated
+    let mut null_tags = MutSet::default();
+    let mut stack = vec![(&proc.body, false)];
+    while let Some((stmt, in_loop)) = stack.pop() {
+        match stmt {
+            Stmt::Let(binding, expr, _, rest) => {
+                if let Expr::Tag {
+                    tag_layout,
+                    tag_id,
+                    arguments,
+                    reuse: None,
+                } = expr
+                {
+                    if tag_layout.tag_is_null(*tag_id) {
+                        null_tags.insert(*binding);
+                    } else if !matches!(tag_layout, UnionLayout::NonRecursive(_))
+                        && !in_loop
+                        && !escaping.contains(binding)
+                    {
+                        candidates.insert(*binding, arguments);
+                    }
+                }
+
+                stack.push((rest, in_loop));
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| (branch, in_loop)));
+                stack.push((default_branch.1, in_loop));
+            }
+            Stmt::Refcounting(_, rest) => stack.push((rest, in_loop)),
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => stack.push((remainder, in_loop)),
+            Stmt::Join {
+                id,
+                body,
+                remainder,
+                ..
+            } => {
+                stack.push((body, in_loop || looping_join_points.contains(id)));
+                stack.push((remainder, in_loop));
+            }
+            Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+        }
+    }
+
+    // a tag on the stack never drops its fields, so they must be on the stack as well
+    loop {
+        let on_heap: std::vec::Vec<Symbol> = candidates
+            .iter()
+            .filter(|(_, arguments)| {
+                arguments.iter().any(|argument| {
+                    interner.contains_refcounted(layouts[argument])
+                        && !candidates.contains_key(argument)
+                        && !null_tags.contains(argument)
+                })
+            })
+            .map(|(symbol, _)| *symbol)
+            .collect();
+
+        if on_heap.is_empty() {
+            break;
+        }
+
+        for symbol in on_heap {
+            candidates.remove(&symbol);
+        }
+    }
+
+    candidates.into_keys().collect()
+}
+
+fn rewrite_stmt<'a>(
+    arena: &'a Bump,
+    update_mode_ids: &mut UpdateModeIds,
+    tokens: &MutMap<Symbol, Symbol>,
+    stmt: &Stmt<'a>,
+) -> &'a Stmt<'a> {
+    match stmt {
+        Stmt::Let(binding, expr, layout, rest) => {
+            let rest = rewrite_stmt(arena, update_mode_ids, tokens, rest);
+
+            match (tokens.get(binding), expr) {
+                (
+                    Some(token),
+                    Expr::Tag {
+                        tag_layout,
+                        tag_id,
+                        arguments,
+                        reuse: None,
+                    },
+                ) => {
+                    let tag = Expr::Tag {
+                        tag_layout: *tag_layout,
+                        tag_id: *tag_id,
+                        arguments,
+                        reuse: Some(ReuseToken {
+                            symbol: *token,
+                            update_tag_id: true,
+                            update_mode: update_mode_ids.next_id(),
+                        }),
+                    };
+
+                    let tag_stmt = arena.alloc(Stmt::Let(*binding, tag, *layout, rest));
+                    let stack_alloc = Expr::StackAlloc {
+                        tag_layout: *tag_layout,
+                    };
+
+                    arena.alloc(Stmt::Let(*token, stack_alloc, *layout, tag_stmt))
+                }
+                // the read-only refcount of stack memory is never unique
+                (
+                    None,
+                    Expr::Call(Call {
+                        call_type:
+                            CallType::LowLevel {
+                                op: LowLevel::RefCountIsUnique,
+                                ..
+                            },
+                        arguments: [argument],
+                    }),
+                ) if tokens.contains_key(argument) => arena.alloc(Stmt::Let(
+                    *binding,
+                    Expr::Literal(Literal::Bool(false)),
+                    *layout,
+                    rest,
+                )),
+                _ => arena.alloc(Stmt::Let(*binding, expr.clone(), *layout, rest)),
+            }
+        }
+        Stmt::Refcounting(modify_rc, rest) => {
+            let rest = rewrite_stmt(arena, update_mode_ids, tokens, rest);
+
+            let symbol = match modify_rc {
+                ModifyRc::Inc(symbol, _)
+                | ModifyRc::Dec(symbol)
+                | ModifyRc::DecRef(symbol)
+                | ModifyRc::Free(symbol) => symbol,
+            };
+
+            if tokens.contains_key(symbol) {
+                rest
+            } else {
+                arena.alloc(Stmt::Refcounting(*modify_rc, rest))
+            }
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let branches = arena.alloc_slice_fill_iter(branches.iter().map(
+                |(label, info, branch): &(u64, BranchInfo<'a>, Stmt<'a>)| {
+                    let branch = rewrite_stmt(arena, update_mode_ids, tokens, branch);
+
+                    (*label, info.clone(), branch.clone())
+                },
+            ));
+            let default_branch = (
+                default_branch.0.clone(),
+                rewrite_stmt(arena, update_mode_ids, tokens, default_branch.1),
+            );
+
+            arena.alloc(Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches,
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: rewrite_stmt(arena, update_mode_ids, tokens, remainder),
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: rewrite_stmt(arena, update_mode_ids, tokens, remainder),
+        }),
+        Stmt::Dbg {
+            source_location,
+            source,
+            symbol,
+            variable,
+            remainder,
+        } => arena.alloc(Stmt::Dbg {
+            source_location,
+            source,
+            symbol: *symbol,
+            variable: *variable,
+            remainder: rewrite_stmt(arena, update_mode_ids, tokens, remainder),
+        }),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => arena.alloc(Stmt::Join {
+            id: *id,
+            parameters,
+            body: rewrite_stmt(arena, update_mode_ids, tokens, body),
+            remainder: rewrite_stmt(arena, update_mode_ids, tokens, remainder),
+        }),
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => arena.alloc(stmt.clone()),
+    }
+}
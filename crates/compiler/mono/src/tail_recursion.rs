@@ -1,17 +1,20 @@
 #![allow(clippy::manual_map)]
 
+use crate::inline::{called_proc, map_branch_info, map_expr, map_modify_rc};
 use crate::ir::{
-    Call, CallType, Expr, JoinPointId, Param, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
+    BranchInfo, Call, CallSpecId, CallType, Expr, HigherOrderLowLevel, JoinPointId, Param,
+    PassedFunction, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
 };
 use crate::layout::{
-    InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
-    UnionLayout,
+    InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, Niche, STLayoutInterner,
+    TagIdIntType, UnionLayout,
 };
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
-use roc_collections::{MutMap, VecMap};
+use roc_collections::{MutMap, MutSet, ReferenceMatrix, VecMap};
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_problem::mono::UnoptimizedTailCall;
 
 pub struct Env<'a, 'i> {
     arena: &'a Bump,
@@ -33,13 +36,17 @@ impl<'a, 'i> Env<'a, 'i> {
     }
 }
 
+type ProcKey<'a> = (Symbol, ProcLayout<'a>);
+
+/// Turn (mutually) recursive tail calls into jumps, and recursive calls that are arguments of a
+/// constructor into loops too. Returns the recursive tail calls that are still calls.
 pub fn apply_trmc<'a, 'i>(
     arena: &'a Bump,
     interner: &'i mut STLayoutInterner<'a>,
     home: ModuleId,
     ident_ids: &'i mut IdentIds,
     procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
-) {
+) -> std::vec::Vec<UnoptimizedTailCall> {
     let mut env = Env {
         arena,
         interner,
@@ -49,6 +56,8 @@ pub fn apply_trmc<'a, 'i>(
 
     let env = &mut env;
 
+    merge_mutual_tail_calls(env, procs);
+
     for proc in procs.values_mut() {
         use self::SelfRecursive::*;
         if let SelfRecursive(id) = proc.is_self_recursive {
@@ -84,6 +93,597 @@ pub fn apply_trmc<'a, 'i>(
             }
         }
     }
+
+    unoptimized_tail_calls(procs)
+}
+
+/// Which procs call which in tail position. Only procs that make and receive such calls are in
+/// the graph, since the others cannot be part of a cycle.
+struct TailCallGraph<'a> {
+    procs: std::vec::Vec<ProcKey<'a>>,
+    /// The proc of a row calls the proc of a column in tail position
+    matrix: ReferenceMatrix,
+}
+
+impl<'a> TailCallGraph<'a> {
+    fn new(procs: &MutMap<ProcKey<'a>, Proc<'a>>) -> Self {
+        let mut edges = std::vec::Vec::new();
+
+        for (caller, proc) in procs.iter() {
+            for_each_tail_call(&proc.body, &mut std::vec::Vec::new(), &mut |expr| {
+                if let Some(callee) = called_proc(expr) {
+                    if matches!(procs.get(&callee), Some(callee_proc) if callee_proc.ret_layout == proc.ret_layout)
+                    {
+                        edges.push((*caller, callee));
+                    }
+                }
+            });
+        }
+
+        let callees: MutSet<_> = edges.iter().map(|(_, callee)| *callee).collect();
+
+        let mut indices = MutMap::default();
+        let mut nodes = std::vec::Vec::new();
+        for (caller, _) in edges.iter() {
+            if callees.contains(caller) && !indices.contains_key(caller) {
+                indices.insert(*caller, nodes.len());
+                nodes.push(*caller);
+            }
+        }
+
+        let mut matrix = ReferenceMatrix::new(nodes.len());
+        for (caller, callee) in edges {
+            if let (Some(row), Some(col)) = (indices.get(&caller), indices.get(&callee)) {
+                matrix.set_row_col(*row, *col, true);
+            }
+        }
+
+        Self {
+            procs: nodes,
+            matrix,
+        }
+    }
+
+    /// The groups of procs that (mutually) recurse through tail calls
+    fn cycles(&self) -> std::vec::Vec<std::vec::Vec<usize>> {
+        let sccs = self.matrix.strongly_connected_components_all();
+
+        sccs.groups()
+            .map(|(group, _)| group.iter_ones().collect::<std::vec::Vec<_>>())
+            .filter(|members| match members.as_slice() {
+                [] => false,
+                [single] => self.matrix.get_row_col(*single, *single),
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+/// Call `f` on every call in tail position: its result is returned right away, or passed to a
+/// join point that returns it right away.
+fn for_each_tail_call<'a>(
+    stmt: &Stmt<'a>,
+    returning: &mut std::vec::Vec<JoinPointId>,
+    f: &mut impl FnMut(&Expr<'a>),
+) {
+    match stmt {
+        Stmt::Let(symbol, expr, _, next) => {
+            if !returns(*symbol, next, returning) {
+                for_each_tail_call(next, returning, f)
+            } else if let Expr::Call(_) = expr {
+                f(expr)
+            }
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            for (_, _, branch) in branches.iter() {
+                for_each_tail_call(branch, returning, f);
+            }
+            for_each_tail_call(default_branch.1, returning, f);
+        }
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            for_each_tail_call(body, returning, f);
+
+            let returns_parameter = returns_parameter(parameters, body);
+            if returns_parameter {
+                returning.push(*id);
+            }
+            for_each_tail_call(remainder, returning, f);
+            if returns_parameter {
+                returning.pop();
+            }
+        }
+        Stmt::Refcounting(_, rest)
+        | Stmt::Expect {
+            remainder: rest, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: rest, ..
+        }
+        | Stmt::Dbg {
+            remainder: rest, ..
+        } => for_each_tail_call(rest, returning, f),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+    }
+}
+
+/// Whether `next` returns `symbol` right away, or passes it to a join point that does
+fn returns(symbol: Symbol, next: &Stmt, returning: &[JoinPointId]) -> bool {
+    match next {
+        Stmt::Ret(returned) => *returned == symbol,
+        Stmt::Jump(id, arguments) => {
+            arguments.len() == 1 && arguments[0] == symbol && returning.contains(id)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the body of a join point returns its only parameter right away
+fn returns_parameter(parameters: &[Param], body: &Stmt) -> bool {
+    matches!((parameters, body), ([param], Stmt::Ret(returned)) if param.symbol == *returned)
+}
+
+fn unoptimized_tail_calls<'a>(
+    procs: &MutMap<ProcKey<'a>, Proc<'a>>,
+) -> std::vec::Vec<UnoptimizedTailCall> {
+    let graph = TailCallGraph::new(procs);
+    let mut tail_calls = std::vec::Vec::new();
+
+    for cycle in graph.cycles() {
+        for caller in cycle.iter() {
+            for callee in cycle.iter() {
+                if graph.matrix.get_row_col(*caller, *callee) {
+                    tail_calls.push(UnoptimizedTailCall {
+                        caller: graph.procs[*caller].0,
+                        callee: graph.procs[*callee].0,
+                    });
+                }
+            }
+        }
+    }
+
+    // specializations of the same function make the same calls
+    tail_calls.sort();
+    tail_calls.dedup();
+
+    tail_calls
+}
+
+/// Merge every group of procs that call each other in tail position into one proc, in which these
+/// calls are jumps to a join point that dispatches to the body of the called proc.
+///
+/// e.g.
+///
+/// ```elm
+/// isEven n = if n == 0 then True else isOdd (n - 1)
+/// isOdd n = if n == 0 then False else isEven (n - 1)
+/// ```
+///
+/// becomes
+///
+/// ```elm
+/// isEvenOrOdd initial =
+///     let joinpoint dispatch state =
+///             when state is
+///                 IsEven n -> if n == 0 then True else jump dispatch (IsOdd (n - 1))
+///                 IsOdd n -> if n == 0 then False else jump dispatch (IsEven (n - 1))
+///     in
+///         jump dispatch initial
+///
+/// isEven n = isEvenOrOdd (IsEven n)
+/// isOdd n = isEvenOrOdd (IsOdd n)
+/// ```
+///
+/// The state is a non-recursive tag union of the arguments of each proc, so it lives on the stack.
+/// A proc that only calls itself is left to `make_tail_recursive`.
+fn merge_mutual_tail_calls<'a>(env: &mut Env<'a, '_>, procs: &mut MutMap<ProcKey<'a>, Proc<'a>>) {
+    let graph = TailCallGraph::new(procs);
+
+    for cycle in graph.cycles() {
+        if cycle.len() < 2 {
+            continue;
+        }
+
+        let members: std::vec::Vec<_> = cycle.iter().map(|index| graph.procs[*index]).collect();
+
+        // TRMC needs the recursive calls to stay calls to the proc itself
+        if members
+            .iter()
+            .any(|key| !trmc_candidates(env.interner, &procs[key]).is_empty())
+        {
+            continue;
+        }
+
+        merge_procs(env, procs, &members);
+    }
+}
+
+fn merge_procs<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut MutMap<ProcKey<'a>, Proc<'a>>,
+    members: &[ProcKey<'a>],
+) {
+    let arena = env.arena;
+    let ret_layout = procs[&members[0]].ret_layout;
+
+    let tags = Vec::from_iter_in(members.iter().map(|(_, layout)| layout.arguments), arena);
+    let union_layout = UnionLayout::NonRecursive(tags.into_bump_slice());
+    let state_layout = env
+        .interner
+        .insert_direct_no_semantic(LayoutRepr::Union(union_layout));
+
+    let merged_name = LambdaName::no_niche(env.named_unique_symbol("mutual_tail_calls"));
+    let merged_layout = ProcLayout {
+        arguments: arena.alloc([state_layout]),
+        result: ret_layout,
+        niche: Niche::NONE,
+    };
+
+    let dispatch = Dispatch {
+        id: JoinPointId(env.named_unique_symbol("dispatch")),
+        union_layout,
+        state_layout,
+        tag_ids: (members.iter().enumerate())
+            .map(|(index, key)| (*key, index as TagIdIntType))
+            .collect(),
+    };
+
+    let initial = env.named_unique_symbol("initial");
+    let state = env.named_unique_symbol("state");
+    let tag = env.named_unique_symbol("tag");
+
+    let mut merge_env = MergeEnv {
+        env,
+        dispatch: &dispatch,
+        renames: MutMap::default(),
+        returning: std::vec::Vec::new(),
+        next_call_spec_id: CallSpecId::BACKEND_DUMMY.next(),
+        next_update_mode_id: UpdateModeId::BACKEND_DUMMY.next(),
+    };
+
+    let mut branches = Vec::with_capacity_in(members.len(), arena);
+    for (index, key) in members.iter().enumerate() {
+        let proc = &procs[key];
+        let tag_id = index as TagIdIntType;
+
+        let arguments = Vec::from_iter_in(
+            proc.args.iter().map(|(_, symbol)| merge_env.bind(*symbol)),
+            arena,
+        );
+
+        let mut body = merge_env.copy_stmt(&proc.body);
+        for (field_index, ((layout, _), symbol)) in
+            proc.args.iter().zip(arguments).enumerate().rev()
+        {
+            let field = Expr::UnionAtIndex {
+                structure: state,
+                tag_id,
+                union_layout,
+                index: field_index as u64,
+            };
+
+            body = arena.alloc(Stmt::Let(symbol, field, *layout, body));
+        }
+
+        branches.push((tag_id as u64, BranchInfo::None, body.clone()));
+    }
+
+    let env = merge_env.env;
+
+    let (_, default_info, default_body) = branches.pop().unwrap();
+    let switch = Stmt::Switch {
+        cond_symbol: tag,
+        cond_layout: union_layout.tag_id_layout(),
+        branches: branches.into_bump_slice(),
+        default_branch: (default_info, arena.alloc(default_body)),
+        ret_layout,
+    };
+
+    let get_tag_id = Expr::GetTagId {
+        structure: state,
+        union_layout,
+    };
+
+    let body = Stmt::Join {
+        id: dispatch.id,
+        parameters: arena.alloc([Param {
+            symbol: state,
+            layout: state_layout,
+        }]),
+        body: arena.alloc(Stmt::Let(
+            tag,
+            get_tag_id,
+            union_layout.tag_id_layout(),
+            arena.alloc(switch),
+        )),
+        remainder: arena.alloc(Stmt::Jump(dispatch.id, arena.alloc([initial]))),
+    };
+
+    let merged = Proc {
+        name: merged_name,
+        args: arena.alloc([(state_layout, initial)]),
+        body,
+        closure_data_layout: None,
+        ret_layout,
+        is_self_recursive: SelfRecursive::NotSelfRecursive,
+        is_erased: false,
+    };
+
+    // the merged procs now just put their arguments in the state
+    for (index, key) in members.iter().enumerate() {
+        let proc = procs.get_mut(key).unwrap();
+        let tag_id = index as TagIdIntType;
+
+        let state = env.unique_symbol();
+        let result = env.unique_symbol();
+
+        let call = Call {
+            call_type: CallType::ByName {
+                name: merged_name,
+                ret_layout,
+                arg_layouts: merged_layout.arguments,
+                specialization_id: CallSpecId::BACKEND_DUMMY.next(),
+            },
+            arguments: arena.alloc([state]),
+        };
+
+        let tag = Expr::Tag {
+            tag_layout: union_layout,
+            tag_id,
+            arguments: Vec::from_iter_in(proc.args.iter().map(|(_, symbol)| *symbol), arena)
+                .into_bump_slice(),
+            reuse: None,
+        };
+
+        let ret = arena.alloc(Stmt::Ret(result));
+        let call = arena.alloc(Stmt::Let(result, Expr::Call(call), ret_layout, ret));
+
+        proc.body = Stmt::Let(state, tag, state_layout, call);
+        proc.is_self_recursive = SelfRecursive::NotSelfRecursive;
+    }
+
+    procs.insert((merged_name.name(), merged_layout), merged);
+}
+
+struct Dispatch<'a> {
+    id: JoinPointId,
+    union_layout: UnionLayout<'a>,
+    state_layout: InLayout<'a>,
+    tag_ids: MutMap<ProcKey<'a>, TagIdIntType>,
+}
+
+/// Copies the bodies of merged procs into the merged proc. Everything they bind gets a fresh name
+/// and their calls get fresh ids, because the bodies of several procs (possibly specializations of
+/// the same function) end up in one proc.
+struct MergeEnv<'a, 'r, 'i> {
+    env: &'r mut Env<'a, 'i>,
+    dispatch: &'r Dispatch<'a>,
+    renames: MutMap<Symbol, Symbol>,
+    /// Join points of the original body that return their argument right away
+    returning: std::vec::Vec<JoinPointId>,
+    next_call_spec_id: CallSpecId,
+    next_update_mode_id: UpdateModeId,
+}
+
+impl<'a, 'r, 'i> MergeEnv<'a, 'r, 'i> {
+    fn rename(&self, symbol: Symbol) -> Symbol {
+        self.renames.get(&symbol).copied().unwrap_or(symbol)
+    }
+
+    fn rename_all(&self, symbols: &[Symbol]) -> &'a [Symbol] {
+        Vec::from_iter_in(symbols.iter().map(|s| self.rename(*s)), self.env.arena).into_bump_slice()
+    }
+
+    fn bind(&mut self, symbol: Symbol) -> Symbol {
+        let new_symbol = self.env.unique_symbol();
+        self.renames.insert(symbol, new_symbol);
+
+        new_symbol
+    }
+
+    fn fresh_call_spec_id(&mut self) -> CallSpecId {
+        let id = self.next_call_spec_id;
+        self.next_call_spec_id = id.next();
+
+        id
+    }
+
+    fn fresh_update_mode_id(&mut self, update_mode: UpdateModeId) -> UpdateModeId {
+        if update_mode == UpdateModeId::BACKEND_DUMMY {
+            return update_mode;
+        }
+
+        let id = self.next_update_mode_id;
+        self.next_update_mode_id = id.next();
+
+        id
+    }
+
+    fn refresh_ids(&mut self, expr: &mut Expr<'a>) {
+        if let Expr::Call(Call { call_type, .. }) = expr {
+            match call_type {
+                CallType::ByName {
+                    specialization_id, ..
+                } => *specialization_id = self.fresh_call_spec_id(),
+                CallType::LowLevel { update_mode, .. } => {
+                    *update_mode = self.fresh_update_mode_id(*update_mode)
+                }
+                CallType::HigherOrder(higher_order) => {
+                    let specialization_id = self.fresh_call_spec_id();
+                    let update_mode = self.fresh_update_mode_id(higher_order.update_mode);
+
+                    *higher_order = self.env.arena.alloc(HigherOrderLowLevel {
+                        update_mode,
+                        passed_function: PassedFunction {
+                            specialization_id,
+                            ..higher_order.passed_function
+                        },
+                        ..**higher_order
+                    });
+                }
+                CallType::ByPointer { .. } | CallType::Foreign { .. } => {}
+            }
+        }
+    }
+
+    fn copy_stmt(&mut self, stmt: &Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.env.arena;
+        let dispatch = self.dispatch;
+
+        let new_stmt = match stmt {
+            Stmt::Let(symbol, expr, layout, next) => {
+                let tag_id = called_proc(expr).and_then(|key| dispatch.tag_ids.get(&key));
+
+                match (tag_id, expr) {
+                    (Some(tag_id), Expr::Call(call)) if returns(*symbol, next, &self.returning) => {
+                        // the tail call becomes a jump to the body of the called proc
+                        let tag = Expr::Tag {
+                            tag_layout: dispatch.union_layout,
+                            tag_id: *tag_id,
+                            arguments: self.rename_all(call.arguments),
+                            reuse: None,
+                        };
+
+                        let state = self.env.unique_symbol();
+                        let jump = Stmt::Jump(dispatch.id, arena.alloc([state]));
+
+                        Stmt::Let(state, tag, dispatch.state_layout, arena.alloc(jump))
+                    }
+                    _ => {
+                        let renames = &self.renames;
+                        let mut expr =
+                            map_expr(arena, expr, &mut |s| renames.get(&s).copied().unwrap_or(s));
+                        self.refresh_ids(&mut expr);
+
+                        let symbol = self.bind(*symbol);
+                        let next = self.copy_stmt(next);
+
+                        Stmt::Let(symbol, expr, *layout, next)
+                    }
+                }
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+                for (label, info, branch) in branches.iter() {
+                    let info = map_branch_info(info, &mut |s| self.rename(s));
+                    let branch = self.copy_stmt(branch);
+
+                    new_branches.push((*label, info, branch.clone()));
+                }
+
+                let default_info = map_branch_info(&default_branch.0, &mut |s| self.rename(s));
+                let default_stmt = self.copy_stmt(default_branch.1);
+
+                Stmt::Switch {
+                    cond_symbol: self.rename(*cond_symbol),
+                    cond_layout: *cond_layout,
+                    branches: new_branches.into_bump_slice(),
+                    default_branch: (default_info, default_stmt),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Ret(symbol) => Stmt::Ret(self.rename(*symbol)),
+            Stmt::Refcounting(modify_rc, next) => {
+                let modify_rc = map_modify_rc(*modify_rc, &mut |s| self.rename(s));
+
+                Stmt::Refcounting(modify_rc, self.copy_stmt(next))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: self.rename(*condition),
+                region: *region,
+                lookups: self.rename_all(lookups),
+                variables,
+                remainder: self.copy_stmt(remainder),
+            },
+            Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::ExpectFx {
+                condition: self.rename(*condition),
+                region: *region,
+                lookups: self.rename_all(lookups),
+                variables,
+                remainder: self.copy_stmt(remainder),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: self.rename(*symbol),
+                variable: *variable,
+                remainder: self.copy_stmt(remainder),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let returns_parameter = returns_parameter(parameters, body);
+
+                let new_id = JoinPointId(self.bind(id.0));
+
+                let parameters = Vec::from_iter_in(
+                    parameters.iter().map(|param| Param {
+                        symbol: self.bind(param.symbol),
+                        layout: param.layout,
+                    }),
+                    arena,
+                );
+
+                let body = self.copy_stmt(body);
+
+                if returns_parameter {
+                    self.returning.push(*id);
+                }
+                let remainder = self.copy_stmt(remainder);
+                if returns_parameter {
+                    self.returning.pop();
+                }
+
+                Stmt::Join {
+                    id: new_id,
+                    parameters: parameters.into_bump_slice(),
+                    body,
+                    remainder,
+                }
+            }
+            Stmt::Jump(id, arguments) => {
+                Stmt::Jump(JoinPointId(self.rename(id.0)), self.rename_all(arguments))
+            }
+            Stmt::Crash(message, tag) => Stmt::Crash(self.rename(*message), *tag),
+        };
+
+        arena.alloc(new_stmt)
+    }
 }
 
 /// Make tail calls into loops (using join points)
@@ -1107,7 +1707,7 @@ fn expr_contains_symbol(expr: &Expr, needle: Symbol) -> bool {
         }),
         Expr::EmptyArray => false,
         Expr::Reset { symbol, .. } | Expr::ResetRef { symbol, .. } => needle == *symbol,
-        Expr::RuntimeErrorFunction(_) => false,
+        Expr::StackAlloc { .. } | Expr::RuntimeErrorFunction(_) => false,
         Expr::ErasedMake { value, callee } => {
             value.map(|v| v == needle).unwrap_or(false) || needle == *callee
         }
@@ -0,0 +1,224 @@
+//! Compile-time evaluation of top-level constants.
+//!
+//! A top-level value that is not a function, like a lookup table built from list literals, is
+//! specialized to a proc without arguments that is called every time the value is used, so the
+//! value is computed over and over at runtime. This runs such procs in the [Interpreter] and
+//! replaces their bodies by the construction of the resulting value, which consists of literals
+//! only. A list of literals is static data in every backend, so a use of the constant no longer
+//! allocates or computes anything.
+//!
+//! Evaluation gives up on crashes, expects and `dbg`, on anything the interpreter cannot run,
+//! and on values that take too long to compute or are too large to embed in the binary. The proc
+//! is then left as it is.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_mono::ir::{Expr, ListLiteralElement, Literal, Stmt};
+use roc_mono::layout::{
+    Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType, UnionLayout,
+};
+
+use crate::heap::{Data, Heap};
+use crate::value::{sign_extend, Value};
+use crate::{Interpreter, Procs};
+
+/// The number of statements that may be executed to evaluate one constant.
+const EVALUATION_FUEL: usize = 1 << 20;
+
+/// The number of values, counting every element and field, a constant may consist of.
+const MAX_CONSTANT_SIZE: usize = 1 << 12;
+
+/// Evaluate procs without arguments at compile time, and replace their bodies by the construction
+/// of their value.
+pub fn eval_constants<'a>(
+    arena: &'a Bump,
+    interner: &mut STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    procs: &mut Procs<'a>,
+) {
+    let mut evaluated = std::vec::Vec::new();
+
+    for (key, proc) in procs.iter() {
+        if !proc.args.is_empty() || proc.is_erased {
+            continue;
+        }
+
+        let mut interpreter = Interpreter::new(interner, procs)
+            .with_fuel(EVALUATION_FUEL)
+            .without_effects();
+
+        let value = match interpreter.call_specialization(key, std::vec::Vec::new()) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let heap = interpreter.into_heap();
+
+        let mut reifier = Reifier {
+            arena,
+            interner: &mut *interner,
+            heap: &heap,
+            home,
+            ident_ids: &mut *ident_ids,
+            size: 0,
+        };
+
+        let mut bindings = std::vec::Vec::new();
+        if let Some(symbol) = reifier.reify(&value, proc.ret_layout, &mut bindings) {
+            let mut body = Stmt::Ret(symbol);
+            for (symbol, expr, layout) in bindings.into_iter().rev() {
+                body = Stmt::Let(symbol, expr, layout, arena.alloc(body));
+            }
+
+            evaluated.push((*key, body));
+        }
+    }
+
+    for (key, body) in evaluated {
+        procs.get_mut(&key).unwrap().body = body;
+    }
+}
+
+/// Builds the statements that construct a value of the interpreter.
+struct Reifier<'r, 'a> {
+    arena: &'a Bump,
+    interner: &'r mut STLayoutInterner<'a>,
+    heap: &'r Heap<'a>,
+    home: ModuleId,
+    ident_ids: &'r mut IdentIds,
+    /// The number of values reified so far
+    size: usize,
+}
+
+impl<'r, 'a> Reifier<'r, 'a> {
+    fn reify(
+        &mut self,
+        value: &Value<'a>,
+        layout: InLayout<'a>,
+        bindings: &mut std::vec::Vec<(Symbol, Expr<'a>, InLayout<'a>)>,
+    ) -> Option<Symbol> {
+        self.size += 1;
+        if self.size > MAX_CONSTANT_SIZE {
+            return None;
+        }
+
+        // a copy of the reference, so the values in the heap are not borrowed from `self`
+        let heap = self.heap;
+        let repr = self.interner.get_repr(layout);
+
+        if let LayoutRepr::LambdaSet(lambda_set) = repr {
+            return self.reify(value, lambda_set.runtime_representation(), bindings);
+        }
+
+        let expr = match (value, repr) {
+            (Value::Struct(fields), LayoutRepr::Struct(field_layouts)) => {
+                let mut symbols = Vec::with_capacity_in(fields.len(), self.arena);
+                for (field, field_layout) in fields.iter().zip(field_layouts.iter()) {
+                    symbols.push(self.reify(field, *field_layout, bindings)?);
+                }
+
+                Expr::Struct(symbols.into_bump_slice())
+            }
+            (Value::Tag(tag_id, arguments), LayoutRepr::Union(union_layout)) => {
+                self.tag(union_layout, *tag_id, arguments, bindings)?
+            }
+            (Value::Null, LayoutRepr::Union(union_layout)) => {
+                let nullable_id = match union_layout {
+                    UnionLayout::NullableWrapped { nullable_id, .. } => nullable_id,
+                    UnionLayout::NullableUnwrapped { nullable_id, .. } => nullable_id as _,
+                    _ => return None,
+                };
+
+                self.tag(union_layout, nullable_id, &[], bindings)?
+            }
+            (Value::Heap(id), _) => match (heap.get(*id).ok()?, repr) {
+                (Data::Tag(tag_id, arguments), LayoutRepr::Union(union_layout)) => {
+                    self.tag(union_layout, *tag_id, arguments, bindings)?
+                }
+                (Data::List { elements, .. }, LayoutRepr::Builtin(Builtin::List(elem_layout))) => {
+                    self.list(elements, elem_layout, bindings)?
+                }
+                (Data::Str(_), _) => Expr::Literal(self.literal(value, layout)?),
+                _ => return None,
+            },
+            _ => Expr::Literal(self.literal(value, layout)?),
+        };
+
+        let symbol = Symbol::new(self.home, self.ident_ids.gen_unique());
+        bindings.push((symbol, expr, layout));
+
+        Some(symbol)
+    }
+
+    fn tag(
+        &mut self,
+        union_layout: UnionLayout<'a>,
+        tag_id: TagIdIntType,
+        arguments: &[Value<'a>],
+        bindings: &mut std::vec::Vec<(Symbol, Expr<'a>, InLayout<'a>)>,
+    ) -> Option<Expr<'a>> {
+        let mut symbols = Vec::with_capacity_in(arguments.len(), self.arena);
+        for (index, argument) in arguments.iter().enumerate() {
+            let argument_layout = union_layout.layout_at(&mut *self.interner, tag_id, index);
+            symbols.push(self.reify(argument, argument_layout, bindings)?);
+        }
+
+        Some(Expr::Tag {
+            tag_layout: union_layout,
+            tag_id,
+            arguments: symbols.into_bump_slice(),
+            reuse: None,
+        })
+    }
+
+    fn list(
+        &mut self,
+        elements: &[Value<'a>],
+        elem_layout: InLayout<'a>,
+        bindings: &mut std::vec::Vec<(Symbol, Expr<'a>, InLayout<'a>)>,
+    ) -> Option<Expr<'a>> {
+        if elements.is_empty() {
+            return Some(Expr::EmptyArray);
+        }
+
+        // Literal elements are stored in the list directly.
+        self.size += elements.len();
+        if self.size > MAX_CONSTANT_SIZE {
+            return None;
+        }
+
+        let mut elems = Vec::with_capacity_in(elements.len(), self.arena);
+        for element in elements {
+            elems.push(match self.literal(element, elem_layout) {
+                Some(literal) => ListLiteralElement::Literal(literal),
+                None => ListLiteralElement::Symbol(self.reify(element, elem_layout, bindings)?),
+            });
+        }
+
+        Some(Expr::Array {
+            elem_layout,
+            elems: elems.into_bump_slice(),
+        })
+    }
+
+    /// The literal of a number, boolean or string
+    fn literal(&self, value: &Value<'a>, layout: InLayout<'a>) -> Option<Literal<'a>> {
+        let literal = match (value, self.interner.get_repr(layout)) {
+            (Value::Int(IntWidth::U128, bits), _) => Literal::U128(bits.to_ne_bytes()),
+            (Value::Int(width, bits), _) => {
+                Literal::Int((sign_extend(*width, *bits) as i128).to_ne_bytes())
+            }
+            (Value::Float(_, float), _) => Literal::Float(*float),
+            (Value::Dec(dec), _) => Literal::Decimal(dec.to_ne_bytes()),
+            (Value::Bool(b), _) => Literal::Bool(*b),
+            (Value::Heap(_), LayoutRepr::Builtin(Builtin::Str)) => {
+                Literal::Str(self.arena.alloc_str(self.heap.get_str(value).ok()?))
+            }
+            _ => return None,
+        };
+
+        Some(literal)
+    }
+}
@@ -0,0 +1,651 @@
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+use roc_mono::ir::{
+    CallType, ErasedField, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Param, Proc,
+    ProcLayout, Stmt,
+};
+use roc_mono::layout::{
+    Builtin, InLayout, LambdaName, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
+    UnionLayout,
+};
+use roc_region::all::Region;
+
+use crate::heap::{Data, Heap};
+use crate::num::{self, DEC_ONE};
+use crate::value::{Pointer, Value};
+use crate::InterpError;
+
+pub type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// Evaluates procs of the mono IR, after reference counting and the other optimizations.
+pub struct Interpreter<'a, 'r> {
+    pub(crate) interner: &'r STLayoutInterner<'a>,
+    procs: &'r Procs<'a>,
+    pub(crate) heap: Heap<'a>,
+    failed_expects: Vec<Region>,
+    dbg_output: Vec<(&'a str, String)>,
+    /// The number of statements that may still be executed, if that is limited
+    fuel: Option<usize>,
+    /// Whether `expect`s and `dbg`s may be evaluated, or are an error
+    allow_effects: bool,
+}
+
+struct Frame<'a, 'r> {
+    values: MutMap<Symbol, Value<'a>>,
+    join_points: MutMap<JoinPointId, (&'a [Param<'a>], &'a Stmt<'a>)>,
+    /// Where to store the result of the call this frame is waiting for, and what to do next.
+    continuation: Option<(Symbol, &'r Stmt<'a>)>,
+}
+
+impl<'a, 'r> Frame<'a, 'r> {
+    fn new(proc: &Proc<'a>, arguments: Vec<Value<'a>>) -> Self {
+        let values = proc
+            .args
+            .iter()
+            .map(|(_, symbol)| *symbol)
+            .zip(arguments)
+            .collect();
+
+        Frame {
+            values,
+            join_points: MutMap::default(),
+            continuation: None,
+        }
+    }
+}
+
+/// The values bound in the current proc.
+pub(crate) struct Scope<'f, 'a>(&'f MutMap<Symbol, Value<'a>>);
+
+impl<'f, 'a> Scope<'f, 'a> {
+    pub(crate) fn get(&self, symbol: Symbol) -> Result<&'f Value<'a>, InterpError> {
+        self.0
+            .get(&symbol)
+            .ok_or(InterpError::UnboundSymbol(symbol))
+    }
+
+    pub(crate) fn get_all(&self, symbols: &[Symbol]) -> Result<Vec<Value<'a>>, InterpError> {
+        symbols
+            .iter()
+            .map(|symbol| self.get(*symbol).cloned())
+            .collect()
+    }
+}
+
+impl<'a, 'r> Interpreter<'a, 'r> {
+    pub fn new(interner: &'r STLayoutInterner<'a>, procs: &'r Procs<'a>) -> Self {
+        Interpreter {
+            interner,
+            procs,
+            heap: Heap::default(),
+            failed_expects: Vec::new(),
+            dbg_output: Vec::new(),
+            fuel: None,
+            allow_effects: true,
+        }
+    }
+
+    /// Give up with [InterpError::OutOfFuel] after executing this many statements.
+    pub fn with_fuel(mut self, fuel: usize) -> Self {
+        self.fuel = Some(fuel);
+        self
+    }
+
+    /// Fail on `expect`s and `dbg`s instead of evaluating them, for callers that would lose
+    /// their output.
+    pub fn without_effects(mut self) -> Self {
+        self.allow_effects = false;
+        self
+    }
+
+    /// Call the specialization of the proc with the given name that takes the given number of
+    /// arguments. The arguments are consumed, and the result is owned by the caller.
+    pub fn call(
+        &mut self,
+        name: Symbol,
+        arguments: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, InterpError> {
+        let mut candidates = self.procs.iter().filter(|((symbol, layout), _)| {
+            *symbol == name && layout.arguments.len() == arguments.len()
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some((_, proc)), None) => self.run(proc, arguments),
+            (None, _) => Err(InterpError::MissingProc(name)),
+            (Some(_), Some(_)) => Err(InterpError::AmbiguousProc(name)),
+        }
+    }
+
+    /// Call a specific specialization of a proc.
+    pub fn call_specialization(
+        &mut self,
+        key: &(Symbol, ProcLayout<'a>),
+        arguments: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, InterpError> {
+        match self.procs.get(key) {
+            Some(proc) => self.run(proc, arguments),
+            None => Err(InterpError::MissingProc(key.0)),
+        }
+    }
+
+    pub fn heap(&self) -> &Heap<'a> {
+        &self.heap
+    }
+
+    pub fn heap_mut(&mut self) -> &mut Heap<'a> {
+        &mut self.heap
+    }
+
+    pub fn into_heap(self) -> Heap<'a> {
+        self.heap
+    }
+
+    /// Give up ownership of a value, freeing whatever is no longer referenced.
+    pub fn drop_value(&mut self, value: Value<'a>) -> Result<(), InterpError> {
+        self.heap.dec(value)
+    }
+
+    /// The number of allocations that were not freed. Once every result is dropped, anything
+    /// that is still alive was leaked by the program.
+    pub fn live_allocations(&self) -> usize {
+        self.heap.live_allocations()
+    }
+
+    /// The regions of the `expect`s that failed so far.
+    pub fn failed_expects(&self) -> &[Region] {
+        &self.failed_expects
+    }
+
+    /// The source locations and rendered values of the `dbg`s evaluated so far.
+    pub fn dbg_output(&self) -> &[(&'a str, String)] {
+        &self.dbg_output
+    }
+
+    pub(crate) fn run(
+        &mut self,
+        proc: &'r Proc<'a>,
+        arguments: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, InterpError> {
+        // an explicit stack of frames, so that deep recursion in the program does not
+        // overflow the stack of the interpreter
+        let mut frames = vec![Frame::new(proc, arguments)];
+        let mut stmt: &'r Stmt<'a> = &proc.body;
+
+        loop {
+            if let Some(fuel) = self.fuel.as_mut() {
+                *fuel = fuel.checked_sub(1).ok_or(InterpError::OutOfFuel)?;
+            }
+
+            if !self.allow_effects
+                && matches!(
+                    stmt,
+                    Stmt::Expect { .. } | Stmt::ExpectFx { .. } | Stmt::Dbg { .. }
+                )
+            {
+                return Err(InterpError::Unsupported(
+                    "evaluating an expect or dbg".to_string(),
+                ));
+            }
+
+            let frame = frames.last_mut().unwrap();
+
+            stmt = match stmt {
+                Stmt::Let(symbol, expr, layout, rest) => {
+                    if let Expr::Call(call) = expr {
+                        let scope = Scope(&frame.values);
+
+                        if let Some(callee) = self.callee(&scope, &call.call_type)? {
+                            let arguments = scope.get_all(call.arguments)?;
+
+                            frame.continuation = Some((*symbol, rest));
+                            frames.push(Frame::new(callee, arguments));
+                            stmt = &callee.body;
+                            continue;
+                        }
+                    }
+
+                    let value = self.eval_expr(&Scope(&frame.values), expr, *layout)?;
+                    frame.values.insert(*symbol, value);
+                    rest
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let condition = Scope(&frame.values).get(*cond_symbol)?.as_u128()? as u64;
+
+                    branches
+                        .iter()
+                        .find(|(label, _, _)| *label == condition)
+                        .map_or(default_branch.1, |(_, _, branch)| branch)
+                }
+                Stmt::Ret(symbol) => {
+                    let value = Scope(&frame.values).get(*symbol)?.clone();
+                    frames.pop();
+
+                    match frames.last_mut() {
+                        None => return Ok(value),
+                        Some(caller) => {
+                            let (symbol, rest) = caller.continuation.take().unwrap();
+                            caller.values.insert(symbol, value);
+                            rest
+                        }
+                    }
+                }
+                Stmt::Refcounting(modify, rest) => {
+                    self.modify_rc(&Scope(&frame.values), *modify)?;
+                    rest
+                }
+                Stmt::Expect {
+                    condition,
+                    region,
+                    remainder,
+                    ..
+                }
+                | Stmt::ExpectFx {
+                    condition,
+                    region,
+                    remainder,
+                    ..
+                } => {
+                    if !Scope(&frame.values).get(*condition)?.as_bool()? {
+                        self.failed_expects.push(*region);
+                    }
+
+                    remainder
+                }
+                Stmt::Dbg {
+                    source_location,
+                    symbol,
+                    remainder,
+                    ..
+                } => {
+                    let rendered = self.render(Scope(&frame.values).get(*symbol)?)?;
+                    self.dbg_output.push((source_location, rendered));
+                    remainder
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    frame.join_points.insert(*id, (parameters, body));
+                    remainder
+                }
+                Stmt::Jump(id, arguments) => {
+                    let (parameters, body) = frame.join_points[id];
+                    let arguments = Scope(&frame.values).get_all(arguments)?;
+
+                    for (parameter, argument) in parameters.iter().zip(arguments) {
+                        frame.values.insert(parameter.symbol, argument);
+                    }
+
+                    body
+                }
+                Stmt::Crash(symbol, tag) => {
+                    let message = self
+                        .heap
+                        .get_str(Scope(&frame.values).get(*symbol)?)?
+                        .to_string();
+
+                    return Err(InterpError::Crash { message, tag: *tag });
+                }
+            };
+        }
+    }
+
+    /// The proc that is called, for calls that run a proc of the program.
+    fn callee(
+        &self,
+        scope: &Scope<'_, 'a>,
+        call_type: &CallType<'a>,
+    ) -> Result<Option<&'r Proc<'a>>, InterpError> {
+        match call_type {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } => self.lookup(*name, arg_layouts, *ret_layout).map(Some),
+            CallType::ByPointer {
+                pointer,
+                ret_layout,
+                arg_layouts,
+            } => match scope.get(*pointer)? {
+                Value::FunctionPointer(name) => {
+                    self.lookup(*name, arg_layouts, *ret_layout).map(Some)
+                }
+                _ => Err(InterpError::InvalidValue("expected a function pointer")),
+            },
+            CallType::Foreign { .. } | CallType::LowLevel { .. } | CallType::HigherOrder(_) => {
+                Ok(None)
+            }
+        }
+    }
+
+    pub(crate) fn lookup(
+        &self,
+        name: LambdaName<'a>,
+        arguments: &'a [InLayout<'a>],
+        result: InLayout<'a>,
+    ) -> Result<&'r Proc<'a>, InterpError> {
+        let layout = ProcLayout {
+            arguments,
+            result,
+            niche: name.niche(),
+        };
+
+        self.procs
+            .get(&(name.name(), layout))
+            .ok_or(InterpError::MissingProc(name.name()))
+    }
+
+    fn modify_rc(&mut self, scope: &Scope<'_, 'a>, modify: ModifyRc) -> Result<(), InterpError> {
+        match modify {
+            ModifyRc::Inc(symbol, amount) => self.heap.inc(scope.get(symbol)?, amount as usize),
+            ModifyRc::Dec(symbol) => self.heap.dec(scope.get(symbol)?.clone()),
+            ModifyRc::DecRef(symbol) => self.heap.decref(scope.get(symbol)?),
+            ModifyRc::Free(symbol) => match scope.get(symbol)? {
+                Value::Null => Ok(()),
+                value => self.heap.free(value.as_alloc()?),
+            },
+        }
+    }
+
+    fn eval_expr(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal, layout),
+            Expr::Call(call) => match &call.call_type {
+                CallType::LowLevel { op, .. } => {
+                    self.eval_lowlevel(scope, *op, call.arguments, layout)
+                }
+                CallType::HigherOrder(higher_order) => {
+                    self.eval_higher_order(scope, higher_order, layout)
+                }
+                CallType::Foreign { foreign_symbol, .. } => Err(InterpError::Unsupported(format!(
+                    "calling the foreign function {}",
+                    foreign_symbol.as_str()
+                ))),
+                CallType::ByName { .. } | CallType::ByPointer { .. } => {
+                    unreachable!("calls of procs are evaluated by the interpreter loop")
+                }
+            },
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse,
+            } => {
+                let arguments = scope.get_all(arguments)?;
+                let token = match reuse {
+                    Some(token) => Some(scope.get(token.symbol)?),
+                    None => None,
+                };
+
+                self.tag(*tag_layout, *tag_id, arguments, token)
+            }
+            Expr::Struct(fields) => Ok(Value::Struct(scope.get_all(fields)?)),
+            Expr::NullPointer => Ok(Value::Null),
+            Expr::StructAtIndex {
+                index, structure, ..
+            } => Ok(scope.get(*structure)?.fields()?[*index as usize].clone()),
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => {
+                let tag_id = self.tag_id(scope.get(*structure)?, *union_layout)?;
+                Ok(self.int(layout, tag_id as u128))
+            }
+            Expr::UnionAtIndex {
+                structure, index, ..
+            } => {
+                let fields = match scope.get(*structure)? {
+                    Value::Tag(_, fields) => fields.as_slice(),
+                    value => self.heap.get_tag(value)?.1,
+                };
+
+                Ok(fields[*index as usize].clone())
+            }
+            Expr::GetElementPointer {
+                structure, indices, ..
+            } => {
+                // the first index is the tag id, the others lead to the field
+                let alloc = scope.get(*structure)?.as_alloc()?;
+                let path = indices[1..].iter().map(|index| *index as usize).collect();
+
+                Ok(Value::Pointer(Pointer { alloc, path }))
+            }
+            Expr::Array { elem_layout, elems } => {
+                let mut elements = Vec::with_capacity(elems.len());
+                for elem in elems.iter() {
+                    elements.push(match elem {
+                        ListLiteralElement::Literal(literal) => {
+                            self.literal(literal, *elem_layout)?
+                        }
+                        ListLiteralElement::Symbol(symbol) => scope.get(*symbol)?.clone(),
+                    });
+                }
+
+                Ok(self.list(elements))
+            }
+            Expr::EmptyArray => Ok(self.list(Vec::new())),
+            Expr::ErasedMake { value, callee } => {
+                let value = match value {
+                    Some(value) => Some(Box::new(scope.get(*value)?.clone())),
+                    None => None,
+                };
+
+                Ok(Value::Erased {
+                    value,
+                    callee: Box::new(scope.get(*callee)?.clone()),
+                })
+            }
+            Expr::ErasedLoad { symbol, field } => match scope.get(*symbol)? {
+                Value::Erased { value, callee } => Ok(match field {
+                    ErasedField::Value | ErasedField::ValuePtr => {
+                        value.as_deref().cloned().unwrap_or(Value::Null)
+                    }
+                    ErasedField::Callee => (**callee).clone(),
+                }),
+                _ => Err(InterpError::InvalidValue("expected an erased value")),
+            },
+            Expr::FunctionPointer { lambda_name } => Ok(Value::FunctionPointer(*lambda_name)),
+            Expr::Alloca { initializer, .. } => {
+                let value = match initializer {
+                    Some(initializer) => scope.get(*initializer)?.clone(),
+                    None => Value::Null,
+                };
+
+                let alloc = self.heap.alloc_static(Data::Cell(value));
+                Ok(Value::Pointer(Pointer {
+                    alloc,
+                    path: Vec::new(),
+                }))
+            }
+            Expr::StackAlloc { .. } => {
+                // a stack slot is never freed, and the tag that reuses it overwrites the data
+                let alloc = self.heap.alloc_static(Data::Tag(0, Vec::new()));
+                Ok(Value::Heap(alloc))
+            }
+            Expr::Reset { symbol, .. } => self.reset(scope.get(*symbol)?, true),
+            Expr::ResetRef { symbol, .. } => self.reset(scope.get(*symbol)?, false),
+            Expr::RuntimeErrorFunction(message) => Err(num::crash(message)),
+        }
+    }
+
+    /// Turn a unique tag into a reuse token, or give up the reference to a shared one.
+    fn reset(&mut self, value: &Value<'a>, dec_children: bool) -> Result<Value<'a>, InterpError> {
+        let id = match value {
+            Value::Null => return Ok(Value::Null),
+            value => value.as_alloc()?,
+        };
+
+        if !self.heap.is_unique(id)? {
+            self.heap.decref(value)?;
+            return Ok(Value::Null);
+        }
+
+        let (tag_id, fields) = self.heap.get_tag(value)?;
+        let (tag_id, fields) = (tag_id, fields.to_vec());
+
+        // the fields are either released here, or were moved out before
+        self.heap.replace(id, Data::Tag(tag_id, Vec::new()))?;
+        if dec_children {
+            for field in fields {
+                self.heap.dec(field)?;
+            }
+        }
+
+        Ok(Value::Heap(id))
+    }
+
+    fn tag(
+        &mut self,
+        tag_layout: UnionLayout<'a>,
+        tag_id: TagIdIntType,
+        arguments: Vec<Value<'a>>,
+        token: Option<&Value<'a>>,
+    ) -> Result<Value<'a>, InterpError> {
+        match tag_layout {
+            UnionLayout::NonRecursive(_) => Ok(Value::Tag(tag_id, arguments)),
+            _ if tag_layout.tag_is_null(tag_id) => Ok(Value::Null),
+            _ => match token {
+                Some(Value::Heap(id)) => {
+                    self.heap.replace(*id, Data::Tag(tag_id, arguments))?;
+                    Ok(Value::Heap(*id))
+                }
+                _ => Ok(Value::Heap(self.heap.alloc(Data::Tag(tag_id, arguments)))),
+            },
+        }
+    }
+
+    fn tag_id(
+        &self,
+        value: &Value<'a>,
+        union_layout: UnionLayout<'a>,
+    ) -> Result<TagIdIntType, InterpError> {
+        match (value, union_layout) {
+            (Value::Tag(tag_id, _), _) => Ok(*tag_id),
+            (Value::Null, UnionLayout::NullableWrapped { nullable_id, .. }) => Ok(nullable_id),
+            (Value::Null, UnionLayout::NullableUnwrapped { nullable_id, .. }) => {
+                Ok(nullable_id as TagIdIntType)
+            }
+            (value, _) => self.heap.get_tag(value).map(|(tag_id, _)| tag_id),
+        }
+    }
+
+    pub(crate) fn list(&mut self, elements: Vec<Value<'a>>) -> Value<'a> {
+        Value::Heap(self.heap.alloc(Data::List {
+            capacity: elements.len(),
+            elements,
+        }))
+    }
+
+    pub(crate) fn str(&mut self, string: String) -> Value<'a> {
+        Value::Heap(self.heap.alloc(Data::Str(string)))
+    }
+
+    /// An integer or boolean with the given layout.
+    pub(crate) fn int(&self, layout: InLayout<'a>, value: u128) -> Value<'a> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Value::Int(width, value),
+            LayoutRepr::Builtin(Builtin::Bool) => Value::Bool(value != 0),
+            _ => Value::Int(IntWidth::U64, value),
+        }
+    }
+
+    fn literal(
+        &mut self,
+        literal: &Literal<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        let repr = self.interner.get_repr(layout);
+
+        match (literal, repr) {
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Int(width))) => {
+                Ok(Value::int(width, i128::from_ne_bytes(*bytes)))
+            }
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Float(width))) => {
+                Ok(num::float(width, i128::from_ne_bytes(*bytes) as f64))
+            }
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Decimal)) => {
+                Ok(Value::Dec(i128::from_ne_bytes(*bytes) * DEC_ONE))
+            }
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Bool)) => {
+                Ok(Value::Bool(i128::from_ne_bytes(*bytes) != 0))
+            }
+            (Literal::U128(bytes), LayoutRepr::Builtin(Builtin::Int(width))) => {
+                Ok(Value::Int(width, u128::from_ne_bytes(*bytes)))
+            }
+            (Literal::Float(f), LayoutRepr::Builtin(Builtin::Float(width))) => {
+                Ok(num::float(width, *f))
+            }
+            (Literal::Float(f), LayoutRepr::Builtin(Builtin::Decimal)) => {
+                num::to_dec(&Value::Float(roc_builtins::bitcode::FloatWidth::F64, *f))
+            }
+            (Literal::Decimal(bytes), _) => Ok(Value::Dec(i128::from_ne_bytes(*bytes))),
+            (Literal::Bool(b), _) => Ok(Value::Bool(*b)),
+            (Literal::Byte(b), _) => Ok(self.int(layout, *b as u128)),
+            (Literal::Str(string), _) => Ok(Value::Heap(self.heap.str_literal(string))),
+            _ => Err(InterpError::InvalidValue(
+                "literal does not match its layout",
+            )),
+        }
+    }
+
+    /// Render a value for debugging, without the type information to show tag names.
+    pub fn render(&self, value: &Value<'a>) -> Result<String, InterpError> {
+        let mut rendered = String::new();
+        self.render_help(value, &mut rendered)?;
+        Ok(rendered)
+    }
+
+    fn render_help(&self, value: &Value<'a>, buf: &mut String) -> Result<(), InterpError> {
+        use std::fmt::Write;
+
+        macro_rules! render_all {
+            ($open:expr, $values:expr, $separator:expr, $close:expr) => {{
+                buf.push_str($open);
+                for (i, value) in $values.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str($separator);
+                    }
+                    self.render_help(value, buf)?;
+                }
+                buf.push_str($close);
+            }};
+        }
+
+        match value {
+            Value::Int(..) | Value::Float(..) | Value::Dec(_) => buf.push_str(&num::to_str(value)?),
+            Value::Bool(b) => write!(buf, "{b}").unwrap(),
+            Value::Struct(fields) => render_all!("{", fields, ", ", "}"),
+            Value::Tag(tag_id, fields) => {
+                render_all!(&format!("TagId({tag_id})("), fields, ", ", ")")
+            }
+            Value::Heap(id) => match self.heap.get(*id)? {
+                Data::Str(string) => write!(buf, "{string:?}").unwrap(),
+                Data::List { elements, .. } => render_all!("[", elements, ", ", "]"),
+                Data::Tag(tag_id, fields) => {
+                    render_all!(&format!("TagId({tag_id})("), fields, ", ", ")")
+                }
+                Data::Cell(value) => self.render_help(value, buf)?,
+            },
+            Value::Null => buf.push_str("Null"),
+            Value::Pointer(_) => buf.push_str("<pointer>"),
+            Value::FunctionPointer(name) => write!(buf, "<function {:?}>", name.name()).unwrap(),
+            Value::Erased { .. } => buf.push_str("<erased>"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+use roc_collections::MutMap;
+use roc_mono::layout::TagIdIntType;
+
+use crate::value::{Pointer, Value};
+use crate::InterpError;
+
+/// Identifies an allocation on the [Heap]. Ids are never reused, so a use of a freed allocation
+/// is always detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AllocId(usize);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data<'a> {
+    Str(String),
+    List {
+        elements: Vec<Value<'a>>,
+        capacity: usize,
+    },
+    /// A tag of a recursive union, or a boxed value.
+    Tag(TagIdIntType, Vec<Value<'a>>),
+    /// A stack slot created by `Alloca`.
+    Cell(Value<'a>),
+}
+
+#[derive(Debug)]
+struct Allocation<'a> {
+    /// `None` for constants and stack slots, which are never freed.
+    refcount: Option<usize>,
+    /// `None` once the allocation is freed.
+    data: Option<Data<'a>>,
+}
+
+/// Memory with reference counting, mirroring what the backends do with `roc_alloc` and the
+/// refcount in front of every allocation.
+#[derive(Debug, Default)]
+pub struct Heap<'a> {
+    allocations: Vec<Allocation<'a>>,
+    constants: MutMap<&'a str, AllocId>,
+    live: usize,
+}
+
+impl<'a> Heap<'a> {
+    /// Allocate data with a reference count of one.
+    pub fn alloc(&mut self, data: Data<'a>) -> AllocId {
+        self.live += 1;
+        self.push(Some(1), data)
+    }
+
+    /// Allocate data that is never freed, like a stack slot.
+    pub fn alloc_static(&mut self, data: Data<'a>) -> AllocId {
+        self.push(None, data)
+    }
+
+    /// The allocation of a string literal. Like in the backends, string literals are constants.
+    pub fn str_literal(&mut self, string: &'a str) -> AllocId {
+        if let Some(id) = self.constants.get(string) {
+            return *id;
+        }
+
+        let id = self.alloc_static(Data::Str(string.to_string()));
+        self.constants.insert(string, id);
+        id
+    }
+
+    fn push(&mut self, refcount: Option<usize>, data: Data<'a>) -> AllocId {
+        self.allocations.push(Allocation {
+            refcount,
+            data: Some(data),
+        });
+
+        AllocId(self.allocations.len() - 1)
+    }
+
+    /// The number of allocations that were not freed yet, not counting constants.
+    pub fn live_allocations(&self) -> usize {
+        self.live
+    }
+
+    pub fn get(&self, id: AllocId) -> Result<&Data<'a>, InterpError> {
+        self.allocations[id.0]
+            .data
+            .as_ref()
+            .ok_or(InterpError::UseAfterFree)
+    }
+
+    pub fn get_mut(&mut self, id: AllocId) -> Result<&mut Data<'a>, InterpError> {
+        self.allocations[id.0]
+            .data
+            .as_mut()
+            .ok_or(InterpError::UseAfterFree)
+    }
+
+    /// Replace the data of a unique allocation, which is how reuse tokens are consumed.
+    pub fn replace(&mut self, id: AllocId, data: Data<'a>) -> Result<(), InterpError> {
+        *self.get_mut(id)? = data;
+        Ok(())
+    }
+
+    pub fn is_unique(&self, id: AllocId) -> Result<bool, InterpError> {
+        let allocation = &self.allocations[id.0];
+
+        match allocation.data {
+            None => Err(InterpError::UseAfterFree),
+            Some(_) => Ok(allocation.refcount == Some(1)),
+        }
+    }
+
+    pub fn get_str(&self, value: &Value<'a>) -> Result<&str, InterpError> {
+        match self.get(value.as_alloc()?)? {
+            Data::Str(string) => Ok(string),
+            _ => Err(InterpError::InvalidValue("expected a string")),
+        }
+    }
+
+    pub fn get_list(&self, value: &Value<'a>) -> Result<&[Value<'a>], InterpError> {
+        match self.get(value.as_alloc()?)? {
+            Data::List { elements, .. } => Ok(elements),
+            _ => Err(InterpError::InvalidValue("expected a list")),
+        }
+    }
+
+    pub fn get_tag(&self, value: &Value<'a>) -> Result<(TagIdIntType, &[Value<'a>]), InterpError> {
+        match self.get(value.as_alloc()?)? {
+            Data::Tag(tag_id, fields) => Ok((*tag_id, fields)),
+            _ => Err(InterpError::InvalidValue("expected a tag")),
+        }
+    }
+
+    pub fn load(&self, pointer: &Pointer) -> Result<&Value<'a>, InterpError> {
+        let (first, rest) = match (self.get(pointer.alloc)?, pointer.path.split_first()) {
+            (Data::Cell(value), _) => (value, pointer.path.as_slice()),
+            (Data::Tag(_, fields), Some((index, rest))) => (&fields[*index], rest),
+            _ => return Err(InterpError::InvalidValue("invalid pointer")),
+        };
+
+        rest.iter().try_fold(first, |value, index| {
+            value
+                .fields()?
+                .get(*index)
+                .ok_or(InterpError::InvalidValue("invalid pointer"))
+        })
+    }
+
+    pub fn store(&mut self, pointer: &Pointer, new: Value<'a>) -> Result<(), InterpError> {
+        let (first, rest) = match (self.get_mut(pointer.alloc)?, pointer.path.split_first()) {
+            (Data::Cell(value), _) => (value, pointer.path.as_slice()),
+            (Data::Tag(_, fields), Some((index, rest))) => (&mut fields[*index], rest),
+            _ => return Err(InterpError::InvalidValue("invalid pointer")),
+        };
+
+        let target = rest.iter().try_fold(first, |value, index| match value {
+            Value::Struct(fields) | Value::Tag(_, fields) => fields
+                .get_mut(*index)
+                .ok_or(InterpError::InvalidValue("invalid pointer")),
+            _ => Err(InterpError::InvalidValue("invalid pointer")),
+        })?;
+
+        *target = new;
+        Ok(())
+    }
+
+    /// Increment the reference counts of all allocations the value refers to directly.
+    pub fn inc(&mut self, value: &Value<'a>, amount: usize) -> Result<(), InterpError> {
+        match value {
+            Value::Heap(id) => {
+                let allocation = &mut self.allocations[id.0];
+
+                match (allocation.data.is_some(), &mut allocation.refcount) {
+                    (false, _) => return Err(InterpError::UseAfterFree),
+                    (true, None) => {}
+                    (true, Some(refcount)) => *refcount += amount,
+                }
+            }
+            Value::Struct(fields) | Value::Tag(_, fields) => {
+                for field in fields {
+                    self.inc(field, amount)?;
+                }
+            }
+            Value::Erased {
+                value: Some(value), ..
+            } => self.inc(value, amount)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Decrement the reference counts of all allocations the value refers to directly. Data
+    /// whose reference count drops to zero is freed, and its children are decremented in turn.
+    pub fn dec(&mut self, value: Value<'a>) -> Result<(), InterpError> {
+        // a work list rather than recursion, so that long linked lists can be freed
+        let mut pending = vec![value];
+
+        while let Some(value) = pending.pop() {
+            match value {
+                Value::Heap(id) => {
+                    if let Some(data) = self.release(id)? {
+                        pending.extend(children(data));
+                    }
+                }
+                Value::Struct(fields) | Value::Tag(_, fields) => pending.extend(fields),
+                Value::Erased {
+                    value: Some(value), ..
+                } => pending.push(*value),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decrement the reference counts of the allocations the value refers to directly, without
+    /// decrementing the children of data that is freed.
+    pub fn decref(&mut self, value: &Value<'a>) -> Result<(), InterpError> {
+        match value {
+            Value::Heap(id) => self.release(*id).map(|_| ()),
+            Value::Struct(fields) | Value::Tag(_, fields) => {
+                fields.iter().try_for_each(|field| self.decref(field))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Free an allocation regardless of its reference count, without decrementing its children.
+    pub fn free(&mut self, id: AllocId) -> Result<(), InterpError> {
+        let allocation = &mut self.allocations[id.0];
+
+        match (allocation.refcount, allocation.data.take()) {
+            (_, None) => Err(InterpError::DoubleFree),
+            (None, Some(data)) => {
+                allocation.data = Some(data);
+                Ok(())
+            }
+            (Some(_), Some(_)) => {
+                self.live -= 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Decrement the reference count of an allocation, returning its data if it was freed.
+    fn release(&mut self, id: AllocId) -> Result<Option<Data<'a>>, InterpError> {
+        let allocation = &mut self.allocations[id.0];
+
+        match (allocation.data.is_some(), &mut allocation.refcount) {
+            (false, _) => Err(InterpError::DoubleFree),
+            (true, None) => Ok(None),
+            (true, Some(1)) => {
+                self.live -= 1;
+                Ok(allocation.data.take())
+            }
+            (true, Some(refcount)) => {
+                *refcount -= 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Take the elements out of a list that is consumed. The elements of a unique list are moved
+    /// out and the list is freed; a shared list keeps its elements, so they are incremented.
+    pub fn take_elements(&mut self, list: &Value<'a>) -> Result<Vec<Value<'a>>, InterpError> {
+        let id = list.as_alloc()?;
+
+        if self.is_unique(id)? {
+            match self.release(id)? {
+                Some(Data::List { elements, .. }) => Ok(elements),
+                _ => Err(InterpError::InvalidValue("expected a list")),
+            }
+        } else {
+            let elements = self.get_list(list)?.to_vec();
+            for element in elements.iter() {
+                self.inc(element, 1)?;
+            }
+
+            self.release(id)?;
+            Ok(elements)
+        }
+    }
+
+    /// Make sure a list that is consumed can be updated in place, by copying it if it is shared.
+    pub fn make_unique(&mut self, list: &Value<'a>) -> Result<AllocId, InterpError> {
+        let id = list.as_alloc()?;
+
+        if self.is_unique(id)? {
+            Ok(id)
+        } else {
+            let capacity = match self.get(id)? {
+                Data::List { capacity, .. } => *capacity,
+                _ => return Err(InterpError::InvalidValue("expected a list")),
+            };
+
+            let elements = self.take_elements(list)?;
+            Ok(self.alloc(Data::List {
+                capacity: capacity.max(elements.len()),
+                elements,
+            }))
+        }
+    }
+
+    /// Whether two values are equal, comparing the data they refer to.
+    pub fn equals(&self, a: &Value<'a>, b: &Value<'a>) -> Result<bool, InterpError> {
+        let mut pending = vec![(a, b)];
+
+        while let Some((a, b)) = pending.pop() {
+            match (a, b) {
+                (Value::Heap(x), Value::Heap(y)) => match (self.get(*x)?, self.get(*y)?) {
+                    (Data::Str(x), Data::Str(y)) if x == y => {}
+                    (Data::List { elements: xs, .. }, Data::List { elements: ys, .. })
+                        if xs.len() == ys.len() =>
+                    {
+                        pending.extend(xs.iter().zip(ys));
+                    }
+                    (Data::Tag(x, xs), Data::Tag(y, ys)) if x == y && xs.len() == ys.len() => {
+                        pending.extend(xs.iter().zip(ys));
+                    }
+                    (Data::Cell(x), Data::Cell(y)) => pending.push((x, y)),
+                    _ => return Ok(false),
+                },
+                (Value::Struct(xs), Value::Struct(ys)) if xs.len() == ys.len() => {
+                    pending.extend(xs.iter().zip(ys));
+                }
+                (Value::Tag(x, xs), Value::Tag(y, ys)) if x == y && xs.len() == ys.len() => {
+                    pending.extend(xs.iter().zip(ys));
+                }
+                (a, b) => {
+                    if a != b {
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn children(data: Data<'_>) -> Vec<Value<'_>> {
+    match data {
+        Data::Str(_) => Vec::new(),
+        Data::List { elements, .. } => elements,
+        Data::Tag(_, fields) => fields,
+        Data::Cell(value) => vec![value],
+    }
+}
@@ -0,0 +1,49 @@
+//! An interpreter for Roc's mono IR.
+//!
+//! It evaluates the procs that the backends would compile, after reference counting and the
+//! other optimizations, so it can run Roc programs without LLVM or the Zig builtins. Every
+//! allocation is tracked on a [Heap] with its reference count, which makes the interpreter a
+//! reference for the backends: a use after free, a double free and every leaked allocation are
+//! detected precisely, instead of showing up as a crash or as garbage much later.
+#![warn(clippy::dbg_macro)]
+// See github.com/roc-lang/roc/issues/800 for discussion of the large_enum_variant check.
+#![allow(clippy::large_enum_variant)]
+
+mod const_eval;
+mod eval;
+mod heap;
+mod low_level;
+mod num;
+mod value;
+
+use roc_module::symbol::Symbol;
+use roc_mono::ir::CrashTag;
+
+pub use const_eval::eval_constants;
+pub use eval::{Interpreter, Procs};
+pub use heap::{AllocId, Data, Heap};
+pub use value::{Pointer, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpError {
+    /// The program crashed, with `crash` or with an error of a builtin.
+    Crash {
+        message: String,
+        tag: CrashTag,
+    },
+    UseAfterFree,
+    DoubleFree,
+    NullDereference,
+    /// There is no specialization of the proc with a matching layout.
+    MissingProc(Symbol),
+    /// There are several specializations of the proc, so the caller must pick one.
+    AmbiguousProc(Symbol),
+    UnboundSymbol(Symbol),
+    /// The program uses something that only makes sense in a compiled program, like a foreign
+    /// call.
+    Unsupported(String),
+    /// A value does not have the shape its layout requires. This is a bug in the compiler.
+    InvalidValue(&'static str),
+    /// The program executed more statements than the interpreter was allowed to.
+    OutOfFuel,
+}
@@ -0,0 +1,646 @@
+use std::cmp::Ordering;
+
+use roc_builtins::bitcode::IntWidth;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+use roc_mono::ir::HigherOrderLowLevel;
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr};
+use roc_mono::low_level::HigherOrder;
+
+use crate::eval::{Interpreter, Scope};
+use crate::heap::Data;
+use crate::num::{self, ArithOp, BitOp, CountOp, DivOp, OnOverflow, RoundOp};
+use crate::value::Value;
+use crate::InterpError;
+
+/// The seed `Dict` uses for hashing. The backends pick a random one, but evaluation should be
+/// deterministic.
+const PSEUDO_SEED: u128 = 0x5eed_5eed_5eed_5eed;
+
+impl<'a, 'r> Interpreter<'a, 'r> {
+    /// Evaluate a lowlevel operation. Arguments that the operation owns (see the borrow
+    /// signatures in `inc_dec`) are consumed, the others are left alone.
+    pub(crate) fn eval_lowlevel(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        op: LowLevel,
+        arguments: &[Symbol],
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        use LowLevel::*;
+
+        let args = scope.get_all(arguments)?;
+
+        macro_rules! arith {
+            ($op:expr, $on_overflow:expr) => {
+                num::arith($op, $on_overflow, &args[0], &args[1])
+            };
+        }
+
+        macro_rules! compare {
+            ($($ordering:pat_param)|+) => {
+                Ok(Value::Bool(matches!(
+                    num::compare(&args[0], &args[1])?,
+                    $($ordering)|+
+                )))
+            };
+        }
+
+        match op {
+            StrConcat => {
+                let mut string = self.take_str(&args[0])?;
+                string.push_str(self.heap.get_str(&args[1])?);
+                Ok(self.str(string))
+            }
+            StrJoinWith => {
+                let separator = self.heap.get_str(&args[1])?;
+                let strings = self
+                    .heap
+                    .get_list(&args[0])?
+                    .iter()
+                    .map(|string| self.heap.get_str(string))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let joined = strings.join(separator);
+                Ok(self.str(joined))
+            }
+            StrIsEmpty => Ok(Value::Bool(self.heap.get_str(&args[0])?.is_empty())),
+            StrStartsWith => {
+                let prefix = self.heap.get_str(&args[1])?;
+                Ok(Value::Bool(
+                    self.heap.get_str(&args[0])?.starts_with(prefix),
+                ))
+            }
+            StrEndsWith => {
+                let suffix = self.heap.get_str(&args[1])?;
+                Ok(Value::Bool(self.heap.get_str(&args[0])?.ends_with(suffix)))
+            }
+            StrSplit => {
+                let string = self.heap.get_str(&args[0])?;
+                let delimiter = self.heap.get_str(&args[1])?;
+
+                let parts: Vec<String> = if delimiter.is_empty() {
+                    vec![string.to_string()]
+                } else {
+                    string.split(delimiter).map(str::to_string).collect()
+                };
+
+                let elements = parts.into_iter().map(|part| self.str(part)).collect();
+                Ok(self.list(elements))
+            }
+            StrCountUtf8Bytes => {
+                let length = self.heap.get_str(&args[0])?.len();
+                Ok(self.int(layout, length as u128))
+            }
+            StrFromInt | StrFromFloat | NumToStr => {
+                let string = num::to_str(&args[0])?;
+                Ok(self.str(string))
+            }
+            StrFromUtf8Range => {
+                let start = args[1].as_usize()?;
+                let count = args[2].as_usize()?;
+                let bytes = self.take_bytes(&args[0])?;
+
+                let range = &bytes[start.min(bytes.len())..(start + count).min(bytes.len())];
+                let (string, byte_index, problem) = match std::str::from_utf8(range) {
+                    Ok(string) => (string.to_string(), 0, None),
+                    Err(error) => {
+                        let index = error.valid_up_to();
+                        (
+                            String::new(),
+                            start + index,
+                            Some(utf8_problem(&range[index..])),
+                        )
+                    }
+                };
+
+                let string = self.str(string);
+                Ok(Value::Struct(vec![
+                    Value::Int(IntWidth::U64, byte_index as u128),
+                    string,
+                    Value::Bool(problem.is_none()),
+                    Value::Int(IntWidth::U8, problem.unwrap_or(3) as u128),
+                ]))
+            }
+            StrToUtf8 => {
+                let string = self.take_str(&args[0])?;
+                let bytes = string
+                    .bytes()
+                    .map(|byte| Value::Int(IntWidth::U8, byte as u128))
+                    .collect();
+
+                Ok(self.list(bytes))
+            }
+            StrRepeat => {
+                let repeated = self.heap.get_str(&args[0])?.repeat(args[1].as_usize()?);
+                Ok(self.str(repeated))
+            }
+            StrTrim | StrTrimStart | StrTrimEnd => {
+                let string = self.take_str(&args[0])?;
+                let trimmed = match op {
+                    StrTrim => string.trim(),
+                    StrTrimStart => string.trim_start(),
+                    _ => string.trim_end(),
+                };
+
+                Ok(self.str(trimmed.to_string()))
+            }
+            StrToNum => {
+                let number_layout = self.struct_fields(layout)?[0];
+                let kind = self.zero(number_layout)?;
+                let (number, failed) = num::parse(self.heap.get_str(&args[0])?, &kind)?;
+
+                Ok(Value::Struct(vec![
+                    number,
+                    Value::Int(IntWidth::U8, failed as u128),
+                ]))
+            }
+            StrGetUnsafe => {
+                let index = args[1].as_usize()?;
+                let byte = self.heap.get_str(&args[0])?.as_bytes()[index];
+                Ok(self.int(layout, byte as u128))
+            }
+            StrSubstringUnsafe => {
+                let start = args[1].as_usize()?;
+                let length = args[2].as_usize()?;
+                let string = self.take_str(&args[0])?;
+
+                let bytes = &string.as_bytes()[start..start + length];
+                Ok(self.str(String::from_utf8_lossy(bytes).into_owned()))
+            }
+            StrReserve | StrReleaseExcessCapacity => {
+                let string = self.take_str(&args[0])?;
+                Ok(self.str(string))
+            }
+            StrWithCapacity => Ok(self.str(String::with_capacity(args[0].as_usize()?))),
+            ListLen => {
+                let length = self.heap.get_list(&args[0])?.len();
+                Ok(self.int(layout, length as u128))
+            }
+            ListWithCapacity => {
+                let capacity = args[0].as_usize()?;
+                Ok(self.list_with_capacity(Vec::new(), capacity))
+            }
+            ListReserve => {
+                let spare = args[1].as_usize()?;
+                let capacity = self.capacity(&args[0])?;
+                let elements = self.heap.take_elements(&args[0])?;
+
+                let capacity = capacity.max(elements.len().saturating_add(spare));
+                Ok(self.list_with_capacity(elements, capacity))
+            }
+            ListReleaseExcessCapacity | ListClone => {
+                let elements = self.heap.take_elements(&args[0])?;
+                Ok(self.list(elements))
+            }
+            ListAppendUnsafe => {
+                let mut elements = self.heap.take_elements(&args[0])?;
+                elements.push(args[1].clone());
+                Ok(self.list(elements))
+            }
+            ListPrepend => {
+                let mut elements = self.heap.take_elements(&args[0])?;
+                elements.insert(0, args[1].clone());
+                Ok(self.list(elements))
+            }
+            ListConcat => {
+                let mut elements = self.heap.take_elements(&args[0])?;
+                elements.extend(self.heap.take_elements(&args[1])?);
+                Ok(self.list(elements))
+            }
+            ListGetUnsafe => {
+                let index = args[1].as_usize()?;
+
+                // the element is copied, the IR increments it if needed
+                match self.heap.get_list(&args[0])?.get(index) {
+                    Some(element) => Ok(element.clone()),
+                    None => Err(InterpError::InvalidValue("list index out of bounds")),
+                }
+            }
+            ListReplaceUnsafe => {
+                let index = args[1].as_usize()?;
+                let id = self.heap.make_unique(&args[0])?;
+
+                let mut value = args[2].clone();
+                if let Data::List { elements, .. } = self.heap.get_mut(id)? {
+                    if let Some(element) = elements.get_mut(index) {
+                        std::mem::swap(element, &mut value);
+                    }
+                }
+
+                // the fields of the result are sorted, so the list comes first unless the
+                // value is a list too
+                let list = Value::Heap(id);
+                let list_first = matches!(
+                    self.interner.get_repr(self.struct_fields(layout)?[0]),
+                    LayoutRepr::Builtin(Builtin::List(_))
+                );
+
+                Ok(Value::Struct(if list_first {
+                    vec![list, value]
+                } else {
+                    vec![value, list]
+                }))
+            }
+            ListSublist => {
+                let start = args[1].as_usize()?;
+                let length = args[2].as_usize()?;
+                let mut elements = self.heap.take_elements(&args[0])?;
+
+                let start = start.min(elements.len());
+                let end = start.saturating_add(length).min(elements.len());
+
+                let rest = elements.split_off(end);
+                let kept = elements.split_off(start);
+                for element in elements.into_iter().chain(rest) {
+                    self.heap.dec(element)?;
+                }
+
+                Ok(self.list(kept))
+            }
+            ListDropAt => {
+                let index = args[1].as_usize()?;
+                let mut elements = self.heap.take_elements(&args[0])?;
+
+                if index < elements.len() {
+                    let dropped = elements.remove(index);
+                    self.heap.dec(dropped)?;
+                }
+
+                Ok(self.list(elements))
+            }
+            ListSwap => {
+                let i = args[1].as_usize()?;
+                let j = args[2].as_usize()?;
+                let mut elements = self.heap.take_elements(&args[0])?;
+
+                if i < elements.len() && j < elements.len() {
+                    elements.swap(i, j);
+                }
+
+                Ok(self.list(elements))
+            }
+            ListGetCapacity => {
+                let capacity = self.capacity(&args[0])?;
+                Ok(self.int(layout, capacity as u128))
+            }
+            ListIsUnique | RefCountIsUnique => match &args[0] {
+                Value::Heap(id) => Ok(Value::Bool(self.heap.is_unique(*id)?)),
+                _ => Ok(Value::Bool(true)),
+            },
+            ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
+                unreachable!("higher-order lowlevels are evaluated by eval_higher_order")
+            }
+            NumAdd => arith!(ArithOp::Add, OnOverflow::Crash),
+            NumAddWrap => arith!(ArithOp::Add, OnOverflow::Wrap),
+            NumAddSaturated => arith!(ArithOp::Add, OnOverflow::Saturate),
+            NumAddChecked => num::arith_checked(ArithOp::Add, &args[0], &args[1]),
+            NumSub => arith!(ArithOp::Sub, OnOverflow::Crash),
+            NumSubWrap => arith!(ArithOp::Sub, OnOverflow::Wrap),
+            NumSubSaturated => arith!(ArithOp::Sub, OnOverflow::Saturate),
+            NumSubChecked => num::arith_checked(ArithOp::Sub, &args[0], &args[1]),
+            NumMul => arith!(ArithOp::Mul, OnOverflow::Crash),
+            NumMulWrap => arith!(ArithOp::Mul, OnOverflow::Wrap),
+            NumMulSaturated => arith!(ArithOp::Mul, OnOverflow::Saturate),
+            NumMulChecked => num::arith_checked(ArithOp::Mul, &args[0], &args[1]),
+            NumGt => compare!(Ordering::Greater),
+            NumGte => compare!(Ordering::Greater | Ordering::Equal),
+            NumLt => compare!(Ordering::Less),
+            NumLte => compare!(Ordering::Less | Ordering::Equal),
+            NumCompare => {
+                // the tag ids of `[EQ, GT, LT]`
+                let tag_id = match num::compare(&args[0], &args[1])? {
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                    Ordering::Less => 2,
+                };
+
+                Ok(Value::Int(IntWidth::U8, tag_id))
+            }
+            NumDivFrac => num::div_frac(&args[0], &args[1]),
+            NumDivTruncUnchecked => num::div_int(DivOp::Trunc, &args[0], &args[1]),
+            NumDivCeilUnchecked => num::div_int(DivOp::Ceil, &args[0], &args[1]),
+            NumRemUnchecked => num::div_int(DivOp::Rem, &args[0], &args[1]),
+            NumIsMultipleOf => Ok(Value::Bool(num::is_multiple_of(&args[0], &args[1])?)),
+            NumAbs => num::abs(&args[0]),
+            NumNeg => num::neg(&args[0]),
+            NumSin => num::float_fn(&args[0], f64::sin),
+            NumCos => num::float_fn(&args[0], f64::cos),
+            NumTan => num::float_fn(&args[0], f64::tan),
+            NumAtan => num::float_fn(&args[0], f64::atan),
+            NumAcos => num::float_fn(&args[0], f64::acos),
+            NumAsin => num::float_fn(&args[0], f64::asin),
+            NumSqrtUnchecked => num::float_fn(&args[0], f64::sqrt),
+            NumLogUnchecked => num::float_fn(&args[0], f64::ln),
+            NumPow => num::pow(&args[0], &args[1]),
+            NumPowInt => num::pow_int(&args[0], &args[1]),
+            NumRound => num::round(RoundOp::Round, &args[0], self.int_width(layout)?),
+            NumFloor => num::round(RoundOp::Floor, &args[0], self.int_width(layout)?),
+            NumCeiling => num::round(RoundOp::Ceiling, &args[0], self.int_width(layout)?),
+            NumToFrac | NumToFloatCast => match self.interner.get_repr(layout) {
+                LayoutRepr::Builtin(Builtin::Decimal) => num::to_dec(&args[0]),
+                LayoutRepr::Builtin(Builtin::Float(width)) => {
+                    Ok(num::float(width, num::to_f64(&args[0])?))
+                }
+                _ => Err(InterpError::InvalidValue("expected a fraction layout")),
+            },
+            NumIsNan => Ok(Value::Bool(num::float_predicate(&args[0], f64::is_nan)?)),
+            NumIsInfinite => Ok(Value::Bool(num::float_predicate(
+                &args[0],
+                f64::is_infinite,
+            )?)),
+            NumIsFinite => Ok(Value::Bool(num::float_predicate(&args[0], f64::is_finite)?)),
+            NumBytesToU16 => self.bytes_to_int(&args[0], &args[1], IntWidth::U16),
+            NumBytesToU32 => self.bytes_to_int(&args[0], &args[1], IntWidth::U32),
+            NumBytesToU64 => self.bytes_to_int(&args[0], &args[1], IntWidth::U64),
+            NumBytesToU128 => self.bytes_to_int(&args[0], &args[1], IntWidth::U128),
+            NumBitwiseAnd => num::bitwise(BitOp::And, &args[0], &args[1]),
+            NumBitwiseXor => num::bitwise(BitOp::Xor, &args[0], &args[1]),
+            NumBitwiseOr => num::bitwise(BitOp::Or, &args[0], &args[1]),
+            NumShiftLeftBy => num::bitwise(BitOp::ShiftLeft, &args[0], &args[1]),
+            NumShiftRightBy => num::bitwise(BitOp::ShiftRight, &args[0], &args[1]),
+            NumShiftRightZfBy => num::bitwise(BitOp::ShiftRightZeroFill, &args[0], &args[1]),
+            NumIntCast => num::int_cast(&args[0], self.int_width(layout)?),
+            NumToIntChecked => {
+                let width = self.int_width(self.struct_fields(layout)?[0])?;
+                num::int_cast_checked(&args[0], width)
+            }
+            NumToFloatChecked => match self.interner.get_repr(self.struct_fields(layout)?[0]) {
+                LayoutRepr::Builtin(Builtin::Float(width)) => {
+                    num::float_cast_checked(&args[0], width)
+                }
+                _ => Err(InterpError::InvalidValue("expected a float layout")),
+            },
+            NumCountLeadingZeroBits => self.count_bits(CountOp::LeadingZeros, &args[0]),
+            NumCountTrailingZeroBits => self.count_bits(CountOp::TrailingZeros, &args[0]),
+            NumCountOneBits => self.count_bits(CountOp::Ones, &args[0]),
+            I128OfDec => match args[0] {
+                Value::Dec(d) => Ok(Value::Int(IntWidth::I128, d as u128)),
+                _ => Err(InterpError::InvalidValue("expected a decimal")),
+            },
+            Eq => Ok(Value::Bool(self.heap.equals(&args[0], &args[1])?)),
+            NotEq => Ok(Value::Bool(!self.heap.equals(&args[0], &args[1])?)),
+            And => Ok(Value::Bool(args[0].as_bool()? && args[1].as_bool()?)),
+            Or => Ok(Value::Bool(args[0].as_bool()? || args[1].as_bool()?)),
+            Not => Ok(Value::Bool(!args[0].as_bool()?)),
+            PtrCast | PtrClearTagId => Ok(args[0].clone()),
+            PtrStore => match &args[0] {
+                Value::Pointer(pointer) => {
+                    self.heap.store(pointer, args[1].clone())?;
+                    Ok(Value::UNIT)
+                }
+                _ => Err(InterpError::InvalidValue("expected a pointer")),
+            },
+            PtrLoad => match &args[0] {
+                Value::Pointer(pointer) => self.heap.load(pointer).cloned(),
+                _ => Err(InterpError::InvalidValue("expected a pointer")),
+            },
+            DictPseudoSeed => Ok(Value::Int(IntWidth::U64, PSEUDO_SEED)),
+            Unreachable => Err(num::crash("Reached code that should be unreachable")),
+            Hash | RefCountIncRcPtr | RefCountDecRcPtr | RefCountIncDataPtr
+            | RefCountDecDataPtr | BoxExpr | UnboxExpr | SetJmp | LongJmp | SetLongJmpBuffer => {
+                Err(InterpError::Unsupported(format!("the lowlevel {op:?}")))
+            }
+        }
+    }
+
+    /// Evaluate `List.map` and friends, which call a proc of the program on the elements.
+    pub(crate) fn eval_higher_order(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        higher_order: &HigherOrderLowLevel<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        let function = &higher_order.passed_function;
+        let proc = self.lookup(
+            function.name,
+            function.argument_layouts,
+            function.return_layout,
+        )?;
+
+        // the lowlevel borrows the captured environment, but the proc owns it
+        let environment = if proc.args.len() > higher_order.op.function_arity() {
+            Some(scope.get(function.captured_environment)?.clone())
+        } else {
+            None
+        };
+
+        let call = |interp: &mut Self, mut arguments: Vec<Value<'a>>| {
+            if let Some(environment) = &environment {
+                interp.heap.inc(environment, 1)?;
+                arguments.push(environment.clone());
+            }
+
+            interp.run(proc, arguments)
+        };
+
+        let lists = match higher_order.op {
+            HigherOrder::ListMap { xs } => vec![xs],
+            HigherOrder::ListMap2 { xs, ys } => vec![xs, ys],
+            HigherOrder::ListMap3 { xs, ys, zs } => vec![xs, ys, zs],
+            HigherOrder::ListMap4 { xs, ys, zs, ws } => vec![xs, ys, zs, ws],
+            HigherOrder::ListSortWith { xs } => {
+                let id = self.heap.make_unique(scope.get(xs)?)?;
+                let mut elements = match self.heap.get_mut(id)? {
+                    Data::List { elements, .. } => std::mem::take(elements),
+                    _ => return Err(InterpError::InvalidValue("expected a list")),
+                };
+
+                // an insertion sort, because the comparison can fail
+                for i in 1..elements.len() {
+                    let mut j = i;
+                    while j > 0 {
+                        let (a, b) = (elements[j - 1].clone(), elements[j].clone());
+                        self.heap.inc(&a, 1)?;
+                        self.heap.inc(&b, 1)?;
+
+                        // `GT` is the tag with id 1
+                        let ordering = call(self, vec![a, b])?;
+                        if ordering.as_u128()? != 1 {
+                            break;
+                        }
+
+                        elements.swap(j - 1, j);
+                        j -= 1;
+                    }
+                }
+
+                if let Data::List { elements: slot, .. } = self.heap.get_mut(id)? {
+                    *slot = elements;
+                }
+
+                return Ok(Value::Heap(id));
+            }
+        };
+
+        // the IR releases the allocations of the lists after the call, so the elements are
+        // moved out of unique lists and copied out of shared ones
+        let mut columns = Vec::with_capacity(lists.len());
+        for list in lists {
+            let list = scope.get(list)?;
+            let unique = self.heap.is_unique(list.as_alloc()?)?;
+            columns.push((unique, self.heap.get_list(list)?.to_vec()));
+        }
+
+        let length = columns.iter().map(|(_, elements)| elements.len()).min();
+        let length = length.unwrap_or(0);
+
+        let mut results = Vec::with_capacity(length);
+        for i in 0..length {
+            let mut arguments = Vec::with_capacity(columns.len());
+            for (unique, elements) in columns.iter() {
+                if !unique {
+                    self.heap.inc(&elements[i], 1)?;
+                }
+
+                arguments.push(elements[i].clone());
+            }
+
+            results.push(call(self, arguments)?);
+        }
+
+        // the elements past the end of the shortest list are not passed to the function
+        for (unique, elements) in columns {
+            if unique {
+                for element in elements.into_iter().skip(length) {
+                    self.heap.dec(element)?;
+                }
+            }
+        }
+
+        debug_assert!(matches!(
+            self.interner.get_repr(layout),
+            LayoutRepr::Builtin(Builtin::List(_))
+        ));
+
+        Ok(self.list(results))
+    }
+
+    /// The contents of a string that is consumed.
+    fn take_str(&mut self, value: &Value<'a>) -> Result<String, InterpError> {
+        let string = self.heap.get_str(value)?.to_string();
+        self.heap.dec(value.clone())?;
+        Ok(string)
+    }
+
+    /// The bytes of a `List U8` that is consumed.
+    fn take_bytes(&mut self, value: &Value<'a>) -> Result<Vec<u8>, InterpError> {
+        let bytes = self
+            .heap
+            .get_list(value)?
+            .iter()
+            .map(|byte| byte.as_u128().map(|byte| byte as u8))
+            .collect::<Result<_, _>>()?;
+
+        self.heap.dec(value.clone())?;
+        Ok(bytes)
+    }
+
+    fn bytes_to_int(
+        &self,
+        list: &Value<'a>,
+        index: &Value<'a>,
+        width: IntWidth,
+    ) -> Result<Value<'a>, InterpError> {
+        let index = index.as_usize()?;
+        let elements = self.heap.get_list(list)?;
+
+        let mut bits = 0;
+        for (i, byte) in elements[index..index + width.stack_size() as usize]
+            .iter()
+            .enumerate()
+        {
+            bits |= byte.as_u128()? << (8 * i);
+        }
+
+        Ok(Value::Int(width, bits))
+    }
+
+    fn count_bits(&self, op: CountOp, value: &Value<'a>) -> Result<Value<'a>, InterpError> {
+        Ok(Value::Int(
+            IntWidth::U8,
+            num::count_bits(op, value)? as u128,
+        ))
+    }
+
+    fn capacity(&self, list: &Value<'a>) -> Result<usize, InterpError> {
+        match self.heap.get(list.as_alloc()?)? {
+            Data::List { capacity, .. } => Ok(*capacity),
+            _ => Err(InterpError::InvalidValue("expected a list")),
+        }
+    }
+
+    fn list_with_capacity(&mut self, elements: Vec<Value<'a>>, capacity: usize) -> Value<'a> {
+        Value::Heap(self.heap.alloc(Data::List {
+            capacity: capacity.max(elements.len()),
+            elements,
+        }))
+    }
+
+    fn int_width(&self, layout: InLayout<'a>) -> Result<IntWidth, InterpError> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Ok(width),
+            _ => Err(InterpError::InvalidValue("expected an integer layout")),
+        }
+    }
+
+    fn struct_fields(&self, layout: InLayout<'a>) -> Result<&'a [InLayout<'a>], InterpError> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Struct(field_layouts) => Ok(field_layouts),
+            _ => Err(InterpError::InvalidValue("expected a struct layout")),
+        }
+    }
+
+    /// The number zero with the given layout.
+    fn zero(&self, layout: InLayout<'a>) -> Result<Value<'a>, InterpError> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Ok(Value::Int(width, 0)),
+            LayoutRepr::Builtin(Builtin::Float(width)) => Ok(Value::Float(width, 0.0)),
+            LayoutRepr::Builtin(Builtin::Decimal) => Ok(Value::Dec(0)),
+            _ => Err(InterpError::InvalidValue("expected a number layout")),
+        }
+    }
+}
+
+/// The `Utf8ByteProblem` of invalid UTF-8 that starts at the given bytes, decoded like the Zig
+/// builtins do.
+fn utf8_problem(bytes: &[u8]) -> u8 {
+    const CODEPOINT_TOO_LARGE: u8 = 0;
+    const ENCODES_SURROGATE_HALF: u8 = 1;
+    const EXPECTED_CONTINUATION: u8 = 2;
+    const INVALID_START_BYTE: u8 = 3;
+    const OVERLONG_ENCODING: u8 = 4;
+    const UNEXPECTED_END_OF_SEQUENCE: u8 = 5;
+
+    let (length, initial, minimum) = match bytes[0] {
+        0x00..=0x7f => return INVALID_START_BYTE,
+        0xc0..=0xdf => (2, bytes[0] & 0x1f, 0x80),
+        0xe0..=0xef => (3, bytes[0] & 0x0f, 0x800),
+        0xf0..=0xf7 => (4, bytes[0] & 0x07, 0x10000),
+        _ => return INVALID_START_BYTE,
+    };
+
+    if bytes.len() < length {
+        return UNEXPECTED_END_OF_SEQUENCE;
+    }
+
+    let mut codepoint = initial as u32;
+    for byte in &bytes[1..length] {
+        if byte & 0xc0 != 0x80 {
+            return EXPECTED_CONTINUATION;
+        }
+
+        codepoint = (codepoint << 6) | (byte & 0x3f) as u32;
+    }
+
+    if codepoint < minimum {
+        OVERLONG_ENCODING
+    } else if (0xd800..=0xdfff).contains(&codepoint) {
+        ENCODES_SURROGATE_HALF
+    } else if codepoint > 0x10ffff {
+        CODEPOINT_TOO_LARGE
+    } else {
+        // `from_utf8` only stops at invalid bytes
+        INVALID_START_BYTE
+    }
+}
@@ -0,0 +1,743 @@
+//! Arithmetic on the numbers of the mono IR, with the overflow behavior of the Zig builtins.
+
+use std::cmp::Ordering;
+
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_std::RocDec;
+
+use crate::value::{sign_extend, truncate, Value};
+use crate::InterpError;
+
+/// The representation of `1` as a decimal.
+pub(crate) const DEC_ONE: i128 = 1_000_000_000_000_000_000;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// What to do when an integer operation overflows.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum OnOverflow {
+    Crash,
+    Wrap,
+    Saturate,
+}
+
+pub(crate) fn crash(message: &str) -> InterpError {
+    InterpError::Crash {
+        message: message.to_string(),
+        tag: roc_mono::ir::CrashTag::Roc,
+    }
+}
+
+fn overflow_message(op: ArithOp, decimal: bool) -> &'static str {
+    match (op, decimal) {
+        (ArithOp::Add, false) => "Integer addition overflowed!",
+        (ArithOp::Sub, false) => "Integer subtraction overflowed!",
+        (ArithOp::Mul, false) => "Integer multiplication overflowed!",
+        (ArithOp::Add, true) => "Decimal addition overflowed!",
+        (ArithOp::Sub, true) => "Decimal subtraction overflowed!",
+        (ArithOp::Mul, true) => "Decimal multiplication overflowed!",
+    }
+}
+
+/// Split an integer into its sign and magnitude, which can represent every integer width.
+fn to_parts(width: IntWidth, bits: u128) -> (bool, u128) {
+    let extended = sign_extend(width, bits);
+
+    if width.is_signed() && (extended as i128) < 0 {
+        (true, (extended as i128).unsigned_abs())
+    } else {
+        (false, extended)
+    }
+}
+
+fn from_parts(width: IntWidth, negative: bool, magnitude: u128) -> u128 {
+    if negative {
+        truncate(width, magnitude.wrapping_neg())
+    } else {
+        truncate(width, magnitude)
+    }
+}
+
+fn fits(width: IntWidth, negative: bool, magnitude: u128) -> bool {
+    let bits = width.stack_size() * 8;
+
+    match (width.is_signed(), negative) {
+        (true, true) => magnitude <= 1u128 << (bits - 1),
+        (true, false) => magnitude < 1u128 << (bits - 1),
+        (false, true) => magnitude == 0,
+        (false, false) => bits == 128 || magnitude < 1u128 << bits,
+    }
+}
+
+fn int_max(width: IntWidth) -> u128 {
+    let bits = width.stack_size() * 8;
+
+    match (width.is_signed(), bits) {
+        (true, _) => (1u128 << (bits - 1)) - 1,
+        (false, 128) => u128::MAX,
+        (false, _) => (1u128 << bits) - 1,
+    }
+}
+
+fn int_min(width: IntWidth) -> u128 {
+    let bits = width.stack_size() * 8;
+
+    if width.is_signed() {
+        truncate(width, 1u128 << (bits - 1))
+    } else {
+        0
+    }
+}
+
+/// Apply an arithmetic operation to two integers, returning the wrapped result and whether the
+/// operation overflowed.
+fn int_arith(op: ArithOp, width: IntWidth, a: u128, b: u128) -> (u128, bool) {
+    if width.is_signed() {
+        let a = sign_extend(width, a) as i128;
+        let b = sign_extend(width, b) as i128;
+
+        let (result, overflowed) = match op {
+            ArithOp::Add => a.overflowing_add(b),
+            ArithOp::Sub => a.overflowing_sub(b),
+            ArithOp::Mul => a.overflowing_mul(b),
+        };
+
+        let (negative, magnitude) = (result < 0, result.unsigned_abs());
+        (
+            truncate(width, result as u128),
+            overflowed || !fits(width, negative, magnitude),
+        )
+    } else {
+        let a = truncate(width, a);
+        let b = truncate(width, b);
+
+        let (result, overflowed) = match op {
+            ArithOp::Add => a.overflowing_add(b),
+            ArithOp::Sub => a.overflowing_sub(b),
+            ArithOp::Mul => a.overflowing_mul(b),
+        };
+
+        (
+            truncate(width, result),
+            overflowed || result > int_max(width),
+        )
+    }
+}
+
+fn saturated(op: ArithOp, width: IntWidth, a: u128, b: u128) -> u128 {
+    let (a_negative, _) = to_parts(width, a);
+    let (b_negative, _) = to_parts(width, b);
+
+    let towards_max = match op {
+        ArithOp::Add => !b_negative,
+        ArithOp::Sub => b_negative,
+        ArithOp::Mul => a_negative == b_negative,
+    };
+
+    if towards_max {
+        int_max(width)
+    } else {
+        int_min(width)
+    }
+}
+
+fn round_float(width: FloatWidth, value: f64) -> f64 {
+    match width {
+        FloatWidth::F32 => value as f32 as f64,
+        FloatWidth::F64 => value,
+    }
+}
+
+fn dec_arith(op: ArithOp, a: i128, b: i128) -> Option<i128> {
+    match op {
+        ArithOp::Add => a.checked_add(b),
+        ArithOp::Sub => a.checked_sub(b),
+        ArithOp::Mul => dec_mul(a, b),
+    }
+}
+
+pub(crate) fn arith(
+    op: ArithOp,
+    on_overflow: OnOverflow,
+    a: &Value,
+    b: &Value,
+) -> Result<Value<'static>, InterpError> {
+    match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => {
+            let (result, overflowed) = int_arith(op, *width, *a, *b);
+
+            match (overflowed, on_overflow) {
+                (false, _) | (true, OnOverflow::Wrap) => Ok(Value::Int(*width, result)),
+                (true, OnOverflow::Saturate) => {
+                    Ok(Value::Int(*width, saturated(op, *width, *a, *b)))
+                }
+                (true, OnOverflow::Crash) => Err(crash(overflow_message(op, false))),
+            }
+        }
+        (Value::Float(width, a), Value::Float(_, b)) => {
+            let result = match op {
+                ArithOp::Add => a + b,
+                ArithOp::Sub => a - b,
+                ArithOp::Mul => a * b,
+            };
+
+            Ok(Value::Float(*width, round_float(*width, result)))
+        }
+        (Value::Dec(a), Value::Dec(b)) => match (dec_arith(op, *a, *b), on_overflow) {
+            (Some(result), _) => Ok(Value::Dec(result)),
+            (None, OnOverflow::Crash) => Err(crash(overflow_message(op, true))),
+            (None, _) => {
+                let towards_max = match op {
+                    ArithOp::Add => *b >= 0,
+                    ArithOp::Sub => *b < 0,
+                    ArithOp::Mul => (*a < 0) == (*b < 0),
+                };
+
+                Ok(Value::Dec(if towards_max { i128::MAX } else { i128::MIN }))
+            }
+        },
+        _ => Err(InterpError::InvalidValue(
+            "expected two numbers of the same kind",
+        )),
+    }
+}
+
+/// The result of a checked operation: the wrapped value and whether it overflowed.
+pub(crate) fn arith_checked(
+    op: ArithOp,
+    a: &Value,
+    b: &Value,
+) -> Result<Value<'static>, InterpError> {
+    let (value, overflowed) = match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => {
+            let (result, overflowed) = int_arith(op, *width, *a, *b);
+            (Value::Int(*width, result), overflowed)
+        }
+        (Value::Dec(a), Value::Dec(b)) => match dec_arith(op, *a, *b) {
+            Some(result) => (Value::Dec(result), false),
+            None => (Value::Dec(0), true),
+        },
+        (Value::Float(..), Value::Float(..)) => {
+            let value = arith(op, OnOverflow::Wrap, a, b)?;
+            let overflowed = matches!(value, Value::Float(_, f) if f.is_infinite());
+            (value, overflowed)
+        }
+        _ => return Err(InterpError::InvalidValue("expected two numbers")),
+    };
+
+    Ok(Value::Struct(vec![value, Value::Bool(overflowed)]))
+}
+
+pub(crate) fn compare(a: &Value, b: &Value) -> Result<Ordering, InterpError> {
+    match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => {
+            if width.is_signed() {
+                let a = sign_extend(*width, *a) as i128;
+                let b = sign_extend(*width, *b) as i128;
+                Ok(a.cmp(&b))
+            } else {
+                Ok(a.cmp(b))
+            }
+        }
+        (Value::Float(_, a), Value::Float(_, b)) => Ok(a.partial_cmp(b).unwrap_or(Ordering::Equal)),
+        (Value::Dec(a), Value::Dec(b)) => Ok(a.cmp(b)),
+        _ => Err(InterpError::InvalidValue("expected two numbers")),
+    }
+}
+
+pub(crate) fn div_frac(a: &Value, b: &Value) -> Result<Value<'static>, InterpError> {
+    match (a, b) {
+        (Value::Float(width, a), Value::Float(_, b)) => {
+            Ok(Value::Float(*width, round_float(*width, a / b)))
+        }
+        (Value::Dec(_), Value::Dec(0)) => Err(crash("Decimal division by zero!")),
+        (Value::Dec(a), Value::Dec(b)) => match dec_div(*a, *b) {
+            Some(result) => Ok(Value::Dec(result)),
+            None => Err(crash("Decimal division overflowed!")),
+        },
+        _ => Err(InterpError::InvalidValue("expected two fractions")),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum DivOp {
+    Trunc,
+    Ceil,
+    Rem,
+}
+
+pub(crate) fn div_int(op: DivOp, a: &Value, b: &Value) -> Result<Value<'static>, InterpError> {
+    let (width, a, b) = match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => (*width, *a, *b),
+        _ => return Err(InterpError::InvalidValue("expected two integers")),
+    };
+
+    if b == 0 {
+        return Err(crash("Integer division by 0!"));
+    }
+
+    let result = if width.is_signed() {
+        let a = sign_extend(width, a) as i128;
+        let b = sign_extend(width, b) as i128;
+
+        let result = match op {
+            DivOp::Trunc => a.wrapping_div(b),
+            DivOp::Rem => a.wrapping_rem(b),
+            DivOp::Ceil => {
+                let quotient = a.wrapping_div(b);
+                if a.wrapping_rem(b) != 0 && (a < 0) == (b < 0) {
+                    quotient + 1
+                } else {
+                    quotient
+                }
+            }
+        };
+
+        result as u128
+    } else {
+        match op {
+            DivOp::Trunc => a / b,
+            DivOp::Rem => a % b,
+            DivOp::Ceil => a / b + (a % b != 0) as u128,
+        }
+    };
+
+    Ok(Value::Int(width, truncate(width, result)))
+}
+
+pub(crate) fn is_multiple_of(a: &Value, b: &Value) -> Result<bool, InterpError> {
+    match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => {
+            let (_, a_magnitude) = to_parts(*width, *a);
+            let (_, b_magnitude) = to_parts(*width, *b);
+
+            if b_magnitude == 0 {
+                Ok(a_magnitude == 0)
+            } else {
+                Ok(a_magnitude % b_magnitude == 0)
+            }
+        }
+        _ => Err(InterpError::InvalidValue("expected two integers")),
+    }
+}
+
+pub(crate) fn abs(value: &Value) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Int(width, bits) => {
+            let (negative, magnitude) = to_parts(*width, *bits);
+            if negative && !fits(*width, false, magnitude) {
+                Err(crash(
+                    "Integer absolute overflowed because its argument is the minimum value",
+                ))
+            } else {
+                Ok(Value::Int(*width, from_parts(*width, false, magnitude)))
+            }
+        }
+        Value::Float(width, f) => Ok(Value::Float(*width, f.abs())),
+        Value::Dec(d) => d
+            .checked_abs()
+            .map(Value::Dec)
+            .ok_or_else(|| crash("Decimal absolute value overflowed!")),
+        _ => Err(InterpError::InvalidValue("expected a number")),
+    }
+}
+
+pub(crate) fn neg(value: &Value) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Int(width, bits) => {
+            let (negative, magnitude) = to_parts(*width, *bits);
+            if magnitude != 0 && !fits(*width, !negative, magnitude) {
+                Err(crash(
+                    "Integer negation overflowed because its argument is the minimum value",
+                ))
+            } else {
+                Ok(Value::Int(*width, from_parts(*width, !negative, magnitude)))
+            }
+        }
+        Value::Float(width, f) => Ok(Value::Float(*width, -f)),
+        Value::Dec(d) => d
+            .checked_neg()
+            .map(Value::Dec)
+            .ok_or_else(|| crash("Decimal negation overflowed!")),
+        _ => Err(InterpError::InvalidValue("expected a number")),
+    }
+}
+
+pub(crate) fn pow_int(base: &Value, exponent: &Value) -> Result<Value<'static>, InterpError> {
+    let (width, base, exponent) = match (base, exponent) {
+        (Value::Int(width, base), Value::Int(_, exponent)) => (*width, *base, *exponent),
+        _ => return Err(InterpError::InvalidValue("expected two integers")),
+    };
+
+    let (exponent_negative, mut exponent) = to_parts(width, exponent);
+    if exponent_negative {
+        let (base_negative, base_magnitude) = to_parts(width, base);
+
+        let result = match (base_negative, base_magnitude) {
+            (_, 1) if exponent % 2 == 1 => base,
+            (_, 1) => 1,
+            _ => 0,
+        };
+
+        return Ok(Value::Int(width, result));
+    }
+
+    let mut result = 1;
+    let mut factor = base;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            let (product, overflowed) = int_arith(ArithOp::Mul, width, result, factor);
+            if overflowed {
+                return Err(crash("Integer raised to power overflowed!"));
+            }
+
+            result = product;
+        }
+
+        exponent >>= 1;
+        if exponent > 0 {
+            let (square, overflowed) = int_arith(ArithOp::Mul, width, factor, factor);
+            if overflowed {
+                return Err(crash("Integer raised to power overflowed!"));
+            }
+
+            factor = square;
+        }
+    }
+
+    Ok(Value::Int(width, result))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum BitOp {
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+    ShiftRightZeroFill,
+}
+
+pub(crate) fn bitwise(op: BitOp, a: &Value, b: &Value) -> Result<Value<'static>, InterpError> {
+    let (width, a, b) = match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => (*width, *a, *b),
+        _ => return Err(InterpError::InvalidValue("expected two integers")),
+    };
+
+    let bits = width.stack_size() as u128 * 8;
+
+    let result = match op {
+        BitOp::And => a & b,
+        BitOp::Or => a | b,
+        BitOp::Xor => a ^ b,
+        BitOp::ShiftLeft if b >= bits => 0,
+        BitOp::ShiftLeft => a << b,
+        BitOp::ShiftRight if width.is_signed() => {
+            (sign_extend(width, a) as i128 >> b.min(bits - 1)) as u128
+        }
+        BitOp::ShiftRight | BitOp::ShiftRightZeroFill if b >= bits => 0,
+        BitOp::ShiftRight | BitOp::ShiftRightZeroFill => a >> b,
+    };
+
+    Ok(Value::Int(width, truncate(width, result)))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum CountOp {
+    LeadingZeros,
+    TrailingZeros,
+    Ones,
+}
+
+pub(crate) fn count_bits(op: CountOp, value: &Value) -> Result<u32, InterpError> {
+    let (width, bits) = match value {
+        Value::Int(width, bits) => (*width, *bits),
+        _ => return Err(InterpError::InvalidValue("expected an integer")),
+    };
+
+    let size = width.stack_size() * 8;
+
+    Ok(match op {
+        CountOp::LeadingZeros => bits.leading_zeros() - (128 - size),
+        CountOp::TrailingZeros => bits.trailing_zeros().min(size),
+        CountOp::Ones => bits.count_ones(),
+    })
+}
+
+/// Convert an integer to another width, wrapping it if it does not fit.
+pub(crate) fn int_cast(value: &Value, width: IntWidth) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Int(from, bits) => Ok(Value::Int(
+            width,
+            truncate(width, sign_extend(*from, *bits)),
+        )),
+        _ => Err(InterpError::InvalidValue("expected an integer")),
+    }
+}
+
+/// Convert an integer to another width, returning the converted value and whether it was out
+/// of bounds.
+pub(crate) fn int_cast_checked(
+    value: &Value,
+    width: IntWidth,
+) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Int(from, bits) => {
+            let (negative, magnitude) = to_parts(*from, *bits);
+
+            let (result, out_of_bounds) = if fits(width, negative, magnitude) {
+                (from_parts(width, negative, magnitude), false)
+            } else {
+                (0, true)
+            };
+
+            Ok(Value::Struct(vec![
+                Value::Int(width, result),
+                Value::Bool(out_of_bounds),
+            ]))
+        }
+        _ => Err(InterpError::InvalidValue("expected an integer")),
+    }
+}
+
+pub(crate) fn to_f64(value: &Value) -> Result<f64, InterpError> {
+    match value {
+        Value::Int(width, bits) => {
+            let (negative, magnitude) = to_parts(*width, *bits);
+            let float = magnitude as f64;
+            Ok(if negative { -float } else { float })
+        }
+        Value::Float(_, f) => Ok(*f),
+        Value::Dec(d) => Ok(*d as f64 / DEC_ONE as f64),
+        _ => Err(InterpError::InvalidValue("expected a number")),
+    }
+}
+
+pub(crate) fn float(width: FloatWidth, value: f64) -> Value<'static> {
+    Value::Float(width, round_float(width, value))
+}
+
+pub(crate) fn float_cast_checked(
+    value: &Value,
+    width: FloatWidth,
+) -> Result<Value<'static>, InterpError> {
+    let float = to_f64(value)?;
+    let converted = round_float(width, float);
+    let out_of_bounds = converted.is_infinite() && float.is_finite();
+
+    Ok(Value::Struct(vec![
+        Value::Float(width, converted),
+        Value::Bool(out_of_bounds),
+    ]))
+}
+
+pub(crate) fn to_dec(value: &Value) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Int(width, bits) => {
+            let (negative, magnitude) = to_parts(*width, *bits);
+            let scaled = i128::try_from(magnitude)
+                .ok()
+                .and_then(|magnitude| magnitude.checked_mul(DEC_ONE))
+                .ok_or_else(|| crash("Decimal conversion overflowed!"))?;
+
+            Ok(Value::Dec(if negative { -scaled } else { scaled }))
+        }
+        Value::Float(_, f) => Ok(Value::Dec((f * DEC_ONE as f64) as i128)),
+        Value::Dec(d) => Ok(Value::Dec(*d)),
+        _ => Err(InterpError::InvalidValue("expected a number")),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RoundOp {
+    Round,
+    Floor,
+    Ceiling,
+}
+
+/// Round a fraction to an integer of the given width.
+pub(crate) fn round(
+    op: RoundOp,
+    value: &Value,
+    width: IntWidth,
+) -> Result<Value<'static>, InterpError> {
+    let rounded = match value {
+        Value::Float(_, f) => {
+            let rounded = match op {
+                RoundOp::Round => f.round(),
+                RoundOp::Floor => f.floor(),
+                RoundOp::Ceiling => f.ceil(),
+            };
+
+            rounded as i128
+        }
+        Value::Dec(d) => match op {
+            RoundOp::Floor => d.div_euclid(DEC_ONE),
+            RoundOp::Ceiling => -(-d).div_euclid(DEC_ONE),
+            RoundOp::Round => {
+                let half = if *d < 0 { -DEC_ONE / 2 } else { DEC_ONE / 2 };
+                (d + half) / DEC_ONE
+            }
+        },
+        Value::Int(..) => return int_cast(value, width),
+        _ => return Err(InterpError::InvalidValue("expected a number")),
+    };
+
+    Ok(Value::int(width, rounded))
+}
+
+/// Apply a function on floats, converting decimals to and from floats like the Zig builtins do.
+pub(crate) fn float_fn(
+    value: &Value,
+    f: impl Fn(f64) -> f64,
+) -> Result<Value<'static>, InterpError> {
+    match value {
+        Value::Float(width, x) => Ok(float(*width, f(*x))),
+        Value::Dec(_) => to_dec(&Value::Float(FloatWidth::F64, f(to_f64(value)?))),
+        _ => Err(InterpError::InvalidValue("expected a fraction")),
+    }
+}
+
+pub(crate) fn float_predicate(value: &Value, f: impl Fn(f64) -> bool) -> Result<bool, InterpError> {
+    match value {
+        Value::Float(_, x) => Ok(f(*x)),
+        Value::Dec(_) => Ok(f(to_f64(value)?)),
+        _ => Err(InterpError::InvalidValue("expected a fraction")),
+    }
+}
+
+pub(crate) fn pow(a: &Value, b: &Value) -> Result<Value<'static>, InterpError> {
+    let exponent = to_f64(b)?;
+    float_fn(a, |base| base.powf(exponent))
+}
+
+pub(crate) fn to_str(value: &Value) -> Result<String, InterpError> {
+    match value {
+        Value::Int(width, bits) => {
+            let (negative, magnitude) = to_parts(*width, *bits);
+            Ok(if negative {
+                format!("-{magnitude}")
+            } else {
+                magnitude.to_string()
+            })
+        }
+        Value::Float(width, f) => Ok(if f.is_nan() {
+            "nan".to_string()
+        } else {
+            match width {
+                FloatWidth::F32 => (*f as f32).to_string(),
+                FloatWidth::F64 => f.to_string(),
+            }
+        }),
+        Value::Dec(d) => Ok(RocDec::new(*d).to_string()),
+        _ => Err(InterpError::InvalidValue("expected a number")),
+    }
+}
+
+/// Parse a number of the kind of the given example value, returning the number and whether it
+/// could not be parsed.
+pub(crate) fn parse(string: &str, kind: &Value) -> Result<(Value<'static>, bool), InterpError> {
+    let parsed = match kind {
+        Value::Int(width, _) => {
+            let (negative, digits) = match string.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, string.strip_prefix('+').unwrap_or(string)),
+            };
+
+            match digits.parse::<u128>() {
+                Ok(magnitude) if !digits.starts_with('+') && fits(*width, negative, magnitude) => {
+                    Some(Value::Int(*width, from_parts(*width, negative, magnitude)))
+                }
+                _ => None,
+            }
+        }
+        Value::Float(width, _) => string.parse::<f64>().ok().map(|f| float(*width, f)),
+        Value::Dec(_) => {
+            RocDec::from_str(string).map(|d| Value::Dec(i128::from_ne_bytes(d.to_ne_bytes())))
+        }
+        _ => return Err(InterpError::InvalidValue("expected a number")),
+    };
+
+    match parsed {
+        Some(value) => Ok((value, false)),
+        None => Ok((zero_like(kind), true)),
+    }
+}
+
+fn zero_like(kind: &Value) -> Value<'static> {
+    match kind {
+        Value::Int(width, _) => Value::Int(*width, 0),
+        Value::Float(width, _) => Value::Float(*width, 0.0),
+        _ => Value::Dec(0),
+    }
+}
+
+/// Multiply two 128-bit numbers into a 256-bit number, as its high and low halves.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let middle = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+
+    let lo = (middle << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (middle >> 64);
+
+    (hi, lo)
+}
+
+/// Divide a 256-bit number by a 128-bit number, if the quotient fits in 128 bits.
+fn div_wide(hi: u128, lo: u128, divisor: u128) -> Option<u128> {
+    if hi >= divisor {
+        return None;
+    }
+
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+
+    Some(quotient)
+}
+
+fn with_sign(negative: bool, magnitude: u128) -> Option<i128> {
+    if negative {
+        0i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(magnitude).ok()
+    }
+}
+
+fn dec_mul(a: i128, b: i128) -> Option<i128> {
+    let (hi, lo) = mul_wide(a.unsigned_abs(), b.unsigned_abs());
+    let magnitude = div_wide(hi, lo, DEC_ONE as u128)?;
+
+    with_sign((a < 0) != (b < 0), magnitude)
+}
+
+fn dec_div(a: i128, b: i128) -> Option<i128> {
+    let (hi, lo) = mul_wide(a.unsigned_abs(), DEC_ONE as u128);
+    let magnitude = div_wide(hi, lo, b.unsigned_abs())?;
+
+    with_sign((a < 0) != (b < 0), magnitude)
+}
@@ -0,0 +1,108 @@
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_mono::layout::{LambdaName, TagIdIntType};
+
+use crate::heap::AllocId;
+use crate::InterpError;
+
+/// A value of the mono IR.
+///
+/// Strings, lists and the tags of recursive unions and boxes live on the [Heap](crate::Heap),
+/// and are represented by the allocation that holds them. Everything else is stored inline.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    /// An integer, as its two's complement representation truncated to the width.
+    Int(IntWidth, u128),
+    Float(FloatWidth, f64),
+    /// A decimal, scaled by 10^18.
+    Dec(i128),
+    Bool(bool),
+    Struct(Vec<Value<'a>>),
+    /// A tag of a non-recursive union.
+    Tag(TagIdIntType, Vec<Value<'a>>),
+    /// A string, list, boxed value or tag of a recursive union.
+    Heap(AllocId),
+    /// The null pointer, which stands for the nullable tag of a nullable union.
+    Null,
+    /// A pointer to a value stored in an allocation, as created by `Alloca` and
+    /// `GetElementPointer`.
+    Pointer(Pointer),
+    FunctionPointer(LambdaName<'a>),
+    Erased {
+        value: Option<Box<Value<'a>>>,
+        callee: Box<Value<'a>>,
+    },
+}
+
+/// The location of a value in an allocation. The path leads from the outermost value of the
+/// allocation through the fields of tags and structs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pointer {
+    pub alloc: AllocId,
+    pub path: Vec<usize>,
+}
+
+impl<'a> Value<'a> {
+    pub const UNIT: Value<'static> = Value::Struct(Vec::new());
+
+    /// Create an integer of the given width, wrapping it if it does not fit.
+    pub fn int(width: IntWidth, value: i128) -> Self {
+        Value::Int(width, truncate(width, value as u128))
+    }
+
+    pub fn as_bool(&self) -> Result<bool, InterpError> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(InterpError::InvalidValue("expected a boolean")),
+        }
+    }
+
+    /// The value of an integer, as an unsigned number. Signed integers are sign extended first.
+    pub fn as_u128(&self) -> Result<u128, InterpError> {
+        match self {
+            Value::Int(width, bits) => Ok(sign_extend(*width, *bits)),
+            Value::Bool(b) => Ok(*b as u128),
+            _ => Err(InterpError::InvalidValue("expected an integer")),
+        }
+    }
+
+    pub fn as_usize(&self) -> Result<usize, InterpError> {
+        match self {
+            Value::Int(_, bits) => Ok(usize::try_from(*bits).unwrap_or(usize::MAX)),
+            _ => Err(InterpError::InvalidValue("expected an index")),
+        }
+    }
+
+    pub fn as_alloc(&self) -> Result<AllocId, InterpError> {
+        match self {
+            Value::Heap(id) => Ok(*id),
+            Value::Null => Err(InterpError::NullDereference),
+            _ => Err(InterpError::InvalidValue("expected a heap value")),
+        }
+    }
+
+    pub fn fields(&self) -> Result<&[Value<'a>], InterpError> {
+        match self {
+            Value::Struct(fields) | Value::Tag(_, fields) => Ok(fields),
+            _ => Err(InterpError::InvalidValue("expected a struct or tag")),
+        }
+    }
+}
+
+/// Truncate the bits of an integer to the given width.
+pub(crate) fn truncate(width: IntWidth, bits: u128) -> u128 {
+    match width.stack_size() {
+        16 => bits,
+        size => bits & ((1u128 << (size * 8)) - 1),
+    }
+}
+
+/// Extend the bits of an integer of the given width to 128 bits, respecting its sign.
+pub(crate) fn sign_extend(width: IntWidth, bits: u128) -> u128 {
+    let shift = 128 - width.stack_size() * 8;
+
+    if width.is_signed() {
+        (((bits << shift) as i128) >> shift) as u128
+    } else {
+        bits
+    }
+}
@@ -0,0 +1,318 @@
+#[macro_use]
+extern crate indoc;
+
+use bumpalo::Bump;
+use pretty_assertions::assert_eq;
+use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_mono_interp::{Data, Heap, InterpError, Interpreter, Value};
+use roc_packaging::cache::RocCacheDir;
+use std::path::PathBuf;
+
+const TARGET_INFO: roc_target::TargetInfo = roc_target::TargetInfo::default_x86_64();
+
+fn promote_expr_to_module(src: &str) -> String {
+    let mut buffer = String::from("app \"test\" provides [main] to \"./platform\"\n\nmain =\n");
+
+    for line in src.lines() {
+        // indent the body!
+        buffer.push_str("    ");
+        buffer.push_str(line);
+        buffer.push('\n');
+    }
+
+    buffer
+}
+
+/// Evaluate `main`, and return the rendered result once everything was freed.
+fn eval(src: &str) -> Result<String, InterpError> {
+    let arena = &Bump::new();
+
+    let module_src = if src.starts_with("app") {
+        src.to_string()
+    } else {
+        promote_expr_to_module(src)
+    };
+
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        function_kind: FunctionKind::LambdaSet,
+        threading: Threading::Single,
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        exec_mode: ExecutionMode::Executable,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
+    };
+
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        &module_src,
+        PathBuf::from("fake/test/path"),
+        RocCacheDir::Disallowed,
+        load_config,
+    );
+
+    let loaded = match loaded {
+        Ok(x) => x,
+        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
+            report,
+        ))) => {
+            println!("{report}");
+            panic!();
+        }
+        Err(e) => panic!("{e:?}"),
+    };
+
+    let main = *loaded
+        .exposed_to_host
+        .top_level_values
+        .keys()
+        .next()
+        .unwrap();
+
+    let mut interpreter = Interpreter::new(&loaded.layout_interner, &loaded.procedures);
+    let result = interpreter.call(main, vec![])?;
+    let rendered = interpreter.render(&result)?;
+
+    interpreter.drop_value(result)?;
+    assert_eq!(
+        interpreter.live_allocations(),
+        0,
+        "the program leaked memory"
+    );
+
+    Ok(rendered)
+}
+
+fn crash_message(result: Result<String, InterpError>) -> String {
+    match result {
+        Err(InterpError::Crash { message, .. }) => message,
+        other => panic!("expected a crash, got {other:?}"),
+    }
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(eval("1 + 2 * 3").unwrap(), "7");
+    assert_eq!(eval("Num.toStr (7 // 2)").unwrap(), "\"3\"");
+    assert_eq!(eval("1.5f64 * 2").unwrap(), "3");
+    assert_eq!(eval("0.1 + 0.2").unwrap(), "0.3");
+    assert_eq!(eval("Num.addWrap 255u8 1").unwrap(), "0");
+    assert_eq!(eval("Num.subSaturated 0u8 1").unwrap(), "0");
+    assert_eq!(eval("-7i8 % 3").unwrap(), "-1");
+}
+
+#[test]
+fn integer_overflow_crashes() {
+    assert_eq!(
+        crash_message(eval("Num.maxI64 + 1")),
+        "Integer addition overflowed!"
+    );
+}
+
+#[test]
+fn checked_arithmetic() {
+    let src = indoc!(
+        r#"
+        when Num.addChecked 250u8 10 is
+            Ok n -> Num.toStr n
+            Err Overflow -> "overflow"
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "\"overflow\"");
+}
+
+#[test]
+fn strings() {
+    assert_eq!(
+        eval(r#"Str.concat "Hello, " "World!""#).unwrap(),
+        "\"Hello, World!\""
+    );
+    assert_eq!(
+        eval(r#"Str.joinWith (Str.split "a,b,c" ",") "-""#).unwrap(),
+        "\"a-b-c\""
+    );
+    assert_eq!(eval(r#"Str.toI64 "-42""#).unwrap(), "TagId(1)(-42)");
+    assert_eq!(
+        eval(r#"Str.fromUtf8 [82, 111, 99]"#).unwrap(),
+        "TagId(1)(\"Roc\")"
+    );
+}
+
+#[test]
+fn list_map_with_captured_environment() {
+    let src = indoc!(
+        r#"
+        offset = Str.countUtf8Bytes "abc"
+
+        List.map [1, 2, 3] \x -> x + offset
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "[4, 5, 6]");
+}
+
+#[test]
+fn list_of_strings() {
+    let src = indoc!(
+        r#"
+        names = ["a", "bb", "ccc"]
+
+        List.map2 names (List.reverse names) \x, y -> Str.concat x y
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), r#"["accc", "bbbb", "ccca"]"#);
+}
+
+#[test]
+fn list_sort_with() {
+    assert_eq!(
+        eval("List.sortWith [3, 1, 2] Num.compare").unwrap(),
+        "[1, 2, 3]"
+    );
+}
+
+#[test]
+fn walk_with_records() {
+    let src = indoc!(
+        r#"
+        List.walk [1, 2, 3, 4] { sum: 0, count: 0 } \state, x ->
+            { sum: state.sum + x, count: state.count + 1 }
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "{4, 10}");
+}
+
+#[test]
+fn recursive_union() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        LinkedList a : [Nil, Cons a (LinkedList a)]
+
+        map : LinkedList a, (a -> b) -> LinkedList b
+        map = \list, f ->
+            when list is
+                Nil -> Nil
+                Cons x xs -> Cons (f x) (map xs f)
+
+        sum : LinkedList I64 -> I64
+        sum = \list ->
+            when list is
+                Nil -> 0
+                Cons x xs -> x + sum xs
+
+        build : I64, LinkedList I64 -> LinkedList I64
+        build = \n, acc ->
+            if n == 0 then acc else build (n - 1) (Cons n acc)
+
+        main =
+            list = build 100 Nil
+
+            sum (map list \x -> x * 2)
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "10100");
+}
+
+#[test]
+fn deep_recursion_does_not_overflow() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        count : I64 -> I64
+        count = \n ->
+            if n == 0 then 0 else 1 + count (n - 1)
+
+        main = count 100000
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "100000");
+}
+
+#[test]
+fn borrowed_parameters() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        totalLength : List Str, Nat, Nat -> Nat
+        totalLength = \names, index, acc ->
+            when List.get names index is
+                Ok name -> totalLength names (index + 1) (acc + Str.countUtf8Bytes name)
+                Err OutOfBounds -> acc
+
+        longest : List Str, Str -> Str
+        longest = \names, default ->
+            List.walk names default \best, name ->
+                if Str.countUtf8Bytes name > Str.countUtf8Bytes best then name else best
+
+        main =
+            names = ["a", "bb", "ccc"]
+
+            { total: totalLength names 0 0, longest: longest names "", names }
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), r#"{"ccc", ["a", "bb", "ccc"], 6}"#);
+}
+
+#[test]
+fn crash_in_program() {
+    let src = indoc!(
+        r#"
+        if Str.isEmpty "" then crash "oops" else 1
+        "#
+    );
+
+    assert_eq!(crash_message(eval(src)), "oops");
+}
+
+#[test]
+fn shared_list_is_copied_on_update() {
+    let src = indoc!(
+        r#"
+        list = [1, 2, 3]
+        other = List.set list 0 10
+
+        { a: list, b: other }
+        "#
+    );
+
+    assert_eq!(eval(src).unwrap(), "{[1, 2, 3], [10, 2, 3]}");
+}
+
+#[test]
+fn boxed_values() {
+    assert_eq!(
+        eval(r#"Box.unbox (Box.box (Str.concat "a" "b"))"#).unwrap(),
+        "\"ab\""
+    );
+}
+
+#[test]
+fn heap_detects_double_free() {
+    let mut heap = Heap::default();
+
+    let string = Value::Heap(heap.alloc(Data::Str("hello".to_string())));
+    let list = heap.alloc(Data::List {
+        elements: vec![string.clone()],
+        capacity: 1,
+    });
+    assert_eq!(heap.live_allocations(), 2);
+
+    heap.dec(Value::Heap(list)).unwrap();
+    assert_eq!(heap.live_allocations(), 0);
+
+    assert_eq!(heap.dec(string), Err(InterpError::DoubleFree));
+    assert_eq!(heap.get(list), Err(InterpError::UseAfterFree));
+}
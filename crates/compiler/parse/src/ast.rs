@@ -455,6 +455,10 @@ pub enum ValueDef<'a> {
         condition: &'a Loc<Expr<'a>>,
         preceding_comment: Region,
     },
+
+    /// A top-level def that failed to parse, kept as its source text.
+    /// Parsing resumes after it, so the rest of the module can still be checked.
+    Malformed(&'a str),
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -1802,6 +1806,7 @@ impl<'a> Malformed for ValueDef<'a> {
                 condition,
                 preceding_comment: _,
             } => condition.is_malformed(),
+            ValueDef::Malformed(_) => true,
         }
     }
 }
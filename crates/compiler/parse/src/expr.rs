@@ -13,7 +13,7 @@ use crate::parser::{
     self, backtrackable, increment_min_indent, line_min_indent, optional, reset_min_indent,
     sep_by1, sep_by1_e, set_min_indent, specialize, specialize_ref, then, word1, word1_indent,
     word2, EClosure, EExpect, EExpr, EIf, EInParens, EList, ENumber, EPattern, ERecord, EString,
-    EType, EWhen, Either, ParseResult, Parser,
+    EType, EWhen, Either, ParseResult, Parser, SyntaxError,
 };
 use crate::pattern::{closure_param, loc_implements_parser};
 use crate::state::State;
@@ -942,83 +942,77 @@ fn parse_defs_end<'a>(
 
         global_state = match parse_single_def(options, min_indent, arena, state) {
             Ok((_, Some(single_def), next_state)) => {
-                let region = single_def.region;
-                let spaces_before_current = single_def.spaces_before;
+                push_single_def(arena, &mut defs, single_def);
 
-                match single_def.type_or_value {
-                    Either::First(type_def) => {
-                        defs.push_type_def(type_def, region, spaces_before_current, &[]);
-                    }
-                    Either::Second(value_def) => {
-                        // If we got a ValueDef::Body, check if a type annotation preceded it.
-                        // If so, we may need to combine them into an AnnotatedBody.
-                        let joined = match value_def {
-                            ValueDef::Body(loc_pattern, loc_def_expr)
-                                if spaces_before_current.len() <= 1 =>
-                            {
-                                let region =
-                                    Region::span_across(&loc_pattern.region, &loc_def_expr.region);
-
-                                match defs.last() {
-                                    Some(Err(ValueDef::Annotation(ann_pattern, ann_type))) => {
-                                        let (value_def, region) = join_ann_to_body!(
-                                            arena,
-                                            loc_pattern,
-                                            loc_def_expr,
-                                            ann_pattern,
-                                            ann_type,
-                                            spaces_before_current,
-                                            region
-                                        );
-
-                                        defs.replace_with_value_def(
-                                            defs.tags.len() - 1,
-                                            value_def,
-                                            region,
-                                        );
-
-                                        true
-                                    }
-                                    Some(Ok(TypeDef::Alias {
-                                        header,
-                                        ann: ann_type,
-                                    })) => {
-                                        let (value_def, region) = join_alias_to_body!(
-                                            arena,
-                                            loc_pattern,
-                                            loc_def_expr,
-                                            header,
-                                            ann_type,
-                                            spaces_before_current,
-                                            region
-                                        );
-
-                                        defs.replace_with_value_def(
-                                            defs.tags.len() - 1,
-                                            value_def,
-                                            region,
-                                        );
-
-                                        true
-                                    }
-                                    _ => false,
-                                }
-                            }
-                            _ => false,
-                        };
+                next_state
+            }
+            Ok((progress, None, s)) => return Ok((progress, defs, s)),
+            Err((progress, err)) => return Err((progress, err)),
+        };
+    }
+}
+
+/// Adds a def to the end of `defs`, joining a body to the annotation right before it
+fn push_single_def<'a>(arena: &'a Bump, defs: &mut Defs<'a>, single_def: SingleDef<'a>) {
+    let region = single_def.region;
+    let spaces_before_current = single_def.spaces_before;
+
+    match single_def.type_or_value {
+        Either::First(type_def) => {
+            defs.push_type_def(type_def, region, spaces_before_current, &[]);
+        }
+        Either::Second(value_def) => {
+            // If we got a ValueDef::Body, check if a type annotation preceded it.
+            // If so, we may need to combine them into an AnnotatedBody.
+            let joined = match value_def {
+                ValueDef::Body(loc_pattern, loc_def_expr) if spaces_before_current.len() <= 1 => {
+                    let region = Region::span_across(&loc_pattern.region, &loc_def_expr.region);
+
+                    match defs.last() {
+                        Some(Err(ValueDef::Annotation(ann_pattern, ann_type))) => {
+                            let (value_def, region) = join_ann_to_body!(
+                                arena,
+                                loc_pattern,
+                                loc_def_expr,
+                                ann_pattern,
+                                ann_type,
+                                spaces_before_current,
+                                region
+                            );
+
+                            defs.replace_with_value_def(defs.tags.len() - 1, value_def, region);
+
+                            true
+                        }
+                        Some(Ok(TypeDef::Alias {
+                            header,
+                            ann: ann_type,
+                        })) => {
+                            let (value_def, region) = join_alias_to_body!(
+                                arena,
+                                loc_pattern,
+                                loc_def_expr,
+                                header,
+                                ann_type,
+                                spaces_before_current,
+                                region
+                            );
 
-                        if !joined {
-                            // the previous and current def can't be joined up
-                            defs.push_value_def(value_def, region, spaces_before_current, &[]);
+                            defs.replace_with_value_def(defs.tags.len() - 1, value_def, region);
+
+                            true
                         }
+                        _ => false,
                     }
                 }
+                _ => false,
+            };
 
-                next_state
+            if !joined {
+                // the previous and current def can't be joined up
+                defs.push_value_def(value_def, region, spaces_before_current, &[]);
             }
-            Ok((progress, None, s)) => return Ok((progress, defs, s)),
-            Err((progress, err)) => return Err((progress, err)),
-        };
+        }
     }
 }
 
@@ -2043,6 +2037,130 @@ pub fn toplevel_defs<'a>() -> impl Parser<'a, Defs<'a>, EExpr<'a>> {
     }
 }
 
+/// Like [toplevel_defs], but doesn't stop at the first def that fails to parse.
+/// Instead, the broken def becomes a [ValueDef::Malformed] running up to the next line that is
+/// indented like a top-level def, and parsing resumes from there. That way the rest of the
+/// module can still be canonicalized and type checked.
+///
+/// Returns the defs along with the problems in source order, each at the region of the def it
+/// made us skip. The whole input is consumed.
+pub fn toplevel_defs_with_recovery<'a>(
+    arena: &'a Bump,
+    state: State<'a>,
+) -> (Defs<'a>, std::vec::Vec<Loc<SyntaxError<'a>>>) {
+    let parse_all = skip_second!(toplevel_defs(), expr_end());
+
+    if let Ok((_, defs, _)) = parse_all.parse(arena, state.clone(), 0) {
+        return (defs, std::vec::Vec::new());
+    }
+
+    let options = ExprParseOptions {
+        accept_multi_backpassing: true,
+        check_for_arrow: true,
+    };
+
+    let start_column = match space0_e(EExpr::IndentStart).parse(arena, state.clone(), 0) {
+        Ok((_, _, state)) => state.column(),
+        Err(_) => 0,
+    };
+
+    let mut defs = Defs::default();
+    let mut problems = std::vec::Vec::new();
+    let mut state = state;
+
+    loop {
+        let def_start = state.clone();
+
+        let problem = match parse_single_def(options, start_column, arena, state) {
+            Ok((_, Some(single_def), next_state)) => {
+                push_single_def(arena, &mut defs, single_def);
+                state = next_state;
+
+                continue;
+            }
+            Ok((_, None, next_state)) => {
+                match space0_e(EExpr::IndentEnd).parse(arena, next_state.clone(), start_column) {
+                    Ok((_, final_space, end)) if end.has_reached_end() => {
+                        if let Some(last) = defs.space_after.len().checked_sub(1) {
+                            let after =
+                                Slice::extend_new(&mut defs.spaces, final_space.iter().copied());
+                            defs.space_after[last] = after;
+                        }
+
+                        break;
+                    }
+                    Ok((_, _, stuck)) => SyntaxError::NotEndOfFile(stuck.pos()),
+                    Err((_, fail)) => SyntaxError::Expr(fail, next_state.pos()),
+                }
+            }
+            Err((_, fail)) => SyntaxError::Expr(fail, def_start.pos()),
+        };
+
+        // Keep the comments before the broken def, in case they document the defs after it
+        let (spaces_before, broken) =
+            match space0_e(EExpr::IndentStart).parse(arena, def_start.clone(), start_column) {
+                Ok((_, spaces, broken)) => (spaces, broken),
+                Err(_) => (&[] as &[_], def_start),
+            };
+
+        let bytes = broken.original_bytes();
+        let start = broken.pos().offset as usize
+            + broken
+                .bytes()
+                .iter()
+                .take_while(|b| b.is_ascii_whitespace())
+                .count();
+        let resume = next_toplevel_line(bytes, start, start_column);
+
+        let text = std::str::from_utf8(&bytes[start..resume])
+            .unwrap_or_default()
+            .trim_end();
+
+        let region = Region::new(
+            Position::new(start as u32),
+            Position::new((start + text.len()) as u32),
+        );
+
+        if !text.is_empty() {
+            defs.push_value_def(ValueDef::Malformed(text), region, spaces_before, &[]);
+        }
+
+        problems.push(Loc::at(region, problem));
+
+        if resume == bytes.len() {
+            break;
+        }
+
+        state = broken.advance_to_line_start(resume);
+    }
+
+    (defs, problems)
+}
+
+/// The offset of the first line after `start` that is indented no more than `column`, which is
+/// where the next top-level def would begin. Returns the end of the input if there is none.
+fn next_toplevel_line(bytes: &[u8], start: usize, column: u32) -> usize {
+    let mut offset = start;
+
+    while let Some(newline) = bytes[offset..].iter().position(|b| *b == b'\n') {
+        offset += newline + 1;
+
+        let line = &bytes[offset..];
+        let indent = line.iter().take_while(|b| **b == b' ').count();
+
+        match line.get(indent) {
+            // Blank lines don't start anything
+            None | Some(b'\n' | b'\r') => {}
+            // Closing brackets may be outdented to the level of the def they close
+            Some(b')' | b']' | b'}') => {}
+            Some(_) if indent <= column as usize => return offset,
+            Some(_) => {}
+        }
+    }
+
+    bytes.len()
+}
+
 // PARSER HELPERS
 
 fn closure_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EClosure<'a>> {
@@ -105,6 +105,19 @@ impl<'a> State<'a> {
         self
     }
 
+    /// Skip ahead to the start of a later line, e.g. to resume parsing after a syntax error
+    #[must_use]
+    pub(crate) fn advance_to_line_start(mut self, offset: usize) -> State<'a> {
+        debug_assert!(offset >= self.offset);
+        debug_assert!(offset == 0 || self.original_bytes[offset - 1] == b'\n');
+
+        self.offset = offset;
+        self.line_start = self.pos();
+        self.line_start_after_whitespace = self.line_start;
+
+        self
+    }
+
     #[must_use]
     #[inline(always)]
     pub(crate) const fn mark_current_indent(mut self) -> State<'a> {
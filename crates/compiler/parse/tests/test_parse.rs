@@ -22,6 +22,7 @@ mod test_parse {
     use roc_parse::ast::StrSegment::*;
     use roc_parse::ast::{self, EscapedChar};
     use roc_parse::ast::{CommentOrNewline, StrLiteral::*};
+    use roc_parse::expr::toplevel_defs_with_recovery;
     use roc_parse::module::module_defs;
     use roc_parse::parser::{Parser, SyntaxError};
     use roc_parse::state::State;
@@ -384,6 +385,79 @@ mod test_parse {
         }
     }
 
+    #[test]
+    fn recover_from_broken_toplevel_def() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r"
+            x = 1
+
+            y = [1, 2
+
+            z = x + 1
+            "
+        );
+
+        let (defs, problems) = toplevel_defs_with_recovery(arena, State::new(src.as_bytes()));
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            &src[problems[0].region.start().offset as usize
+                ..problems[0].region.end().offset as usize],
+            "y = [1, 2"
+        );
+        assert_eq!(defs.value_defs.len(), 3);
+        assert!(matches!(defs.value_defs[0], ast::ValueDef::Body(..)));
+        assert_eq!(defs.value_defs[1], ast::ValueDef::Malformed("y = [1, 2"));
+        assert!(matches!(defs.value_defs[2], ast::ValueDef::Body(..)));
+    }
+
+    #[test]
+    fn recovery_keeps_outdented_closing_bracket_in_broken_def() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r"
+            x = [
+                1 +,
+            ]
+
+            y = 2
+            "
+        );
+
+        let (defs, problems) = toplevel_defs_with_recovery(arena, State::new(src.as_bytes()));
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(defs.value_defs.len(), 2);
+        assert_eq!(
+            defs.value_defs[0],
+            ast::ValueDef::Malformed("x = [\n    1 +,\n]")
+        );
+        assert!(matches!(defs.value_defs[1], ast::ValueDef::Body(..)));
+    }
+
+    #[test]
+    fn recovery_on_valid_defs_matches_module_defs() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r"
+            x : I64
+            x = 1
+
+            # a comment
+            y = x + 1
+            "
+        );
+
+        let (defs, problems) = toplevel_defs_with_recovery(arena, State::new(src.as_bytes()));
+        let (_, expected, _) = module_defs()
+            .parse(arena, State::new(src.as_bytes()), 0)
+            .unwrap();
+
+        assert!(problems.is_empty());
+        assert_eq!(defs, expected);
+    }
+
     #[test]
     fn parse_expr_size() {
         assert_eq!(std::mem::size_of::<roc_parse::ast::Expr>(), 40);
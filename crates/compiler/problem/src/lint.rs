@@ -0,0 +1,344 @@
+//! Lint levels decide how some warnings are reported: not at all, as warnings, or as errors.
+//!
+//! Levels can be set for a whole build (e.g. `roc check --deny unused-import`), and with
+//! comments in the source, which take precedence:
+//!
+//! ```text
+//! # lint-module: deny unused-import
+//!
+//! # lint: allow unused-argument, unused-def
+//! generated = \a, b -> a
+//! ```
+//!
+//! A `lint-module` directive applies to the whole module, wherever it appears. A `lint`
+//! directive applies to the definition right below it, including its type annotation.
+//! Directives have to be on their own line.
+//! Problems found after type checking, like `unoptimized-tail-call`, are not tied to a
+//! definition, so only `lint-module` directives apply to them.
+use roc_parse::highlight::{highlight, Token};
+use roc_region::all::{LineInfo, Region};
+
+use crate::can::Problem;
+use crate::Severity;
+
+/// A kind of problem whose severity can be configured
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    UnusedDef,
+    UnusedImport,
+    UnusedArgument,
+    UnusedBranchDef,
+    Shadowing,
+    UnoptimizedTailCall,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::UnusedDef,
+        Lint::UnusedImport,
+        Lint::UnusedArgument,
+        Lint::UnusedBranchDef,
+        Lint::Shadowing,
+        Lint::UnoptimizedTailCall,
+    ];
+
+    /// The name used on the command line and in lint directives
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedDef => "unused-def",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnusedArgument => "unused-argument",
+            Lint::UnusedBranchDef => "unused-branch-def",
+            Lint::Shadowing => "shadowing",
+            Lint::UnoptimizedTailCall => "unoptimized-tail-call",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    /// The lint this problem belongs to, and the region its level is looked up at
+    pub fn of_problem(problem: &Problem) -> Option<(Lint, Region)> {
+        match problem {
+            Problem::UnusedDef(_, region) => Some((Lint::UnusedDef, *region)),
+            Problem::UnusedImport(_, region) | Problem::UnusedModuleImport(_, region) => {
+                Some((Lint::UnusedImport, *region))
+            }
+            Problem::UnusedArgument(_, _, _, region) => Some((Lint::UnusedArgument, *region)),
+            Problem::UnusedBranchDef(_, region) => Some((Lint::UnusedBranchDef, *region)),
+            // The shadowing definition is the one that needs to be allowed, not the original
+            Problem::Shadowing { shadow, .. } => Some((Lint::Shadowing, shadow.region)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    pub fn from_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+
+    /// The severity to report a problem with, or None if it should not be reported
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Allow => None,
+            LintLevel::Warn => Some(Severity::Warning),
+            LintLevel::Deny => Some(Severity::RuntimeError),
+        }
+    }
+}
+
+/// Lint levels that apply to a whole build, e.g. from command-line flags
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LintLevels {
+    /// Later entries take precedence
+    levels: Vec<(Lint, LintLevel)>,
+}
+
+impl LintLevels {
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.push((lint, level));
+    }
+
+    pub fn get(&self, lint: Lint) -> Option<LintLevel> {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(l, _)| *l == lint)
+            .map(|(_, level)| *level)
+    }
+
+    /// The level this problem should be reported at, or None to use its default severity.
+    /// Lint directives in the source take precedence over these levels.
+    pub fn level(
+        &self,
+        problem: &Problem,
+        directives: &LintDirectives,
+        line_of: impl Fn(Region) -> u32,
+    ) -> Option<LintLevel> {
+        let (lint, region) = Lint::of_problem(problem)?;
+
+        directives
+            .get(lint, line_of(region))
+            .or_else(|| self.get(lint))
+    }
+
+    /// The level of a lint whose problems are about a module as a whole, rather than a region
+    pub fn module_level(&self, lint: Lint, directives: &LintDirectives) -> Option<LintLevel> {
+        directives.module.get(lint).or_else(|| self.get(lint))
+    }
+}
+
+/// The lint directives in the comments of one module
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LintDirectives {
+    module: LintLevels,
+    /// Directives that apply to a range of lines (0-based, end exclusive), in source order
+    scoped: Vec<(u32, u32, Lint, LintLevel)>,
+}
+
+impl LintDirectives {
+    /// Finds the directives in the comments of a module. The comments come from the parser, so
+    /// a `#` in a string is never mistaken for one.
+    pub fn parse(src: &str) -> LintDirectives {
+        let lines: Vec<&str> = src.lines().collect();
+        let line_info = LineInfo::new(src);
+        let mut directives = LintDirectives::default();
+
+        // The comments that have a line to themselves, by line
+        let mut comments: Vec<Option<&str>> = vec![None; lines.len()];
+        for token in highlight(src) {
+            if token.value != Token::LineComment {
+                continue;
+            }
+            let start = line_info.convert_pos(token.region.start());
+            let line = start.line as usize;
+            if line < lines.len() && start.column as usize == indent(lines[line]) {
+                let text =
+                    &src[token.region.start().offset as usize..token.region.end().offset as usize];
+                comments[line] = Some(text.trim_end());
+            }
+        }
+
+        for (line_index, comment) in comments.iter().enumerate() {
+            let (is_module, level, lints) = match comment.and_then(parse_directive) {
+                Some(directive) => directive,
+                None => continue,
+            };
+
+            if is_module {
+                for lint in lints {
+                    directives.module.set(lint, level);
+                }
+            } else if let Some((start, end)) = def_below(&lines, &comments, line_index) {
+                for lint in lints {
+                    directives.scoped.push((start, end, lint, level));
+                }
+            }
+        }
+
+        directives
+    }
+
+    /// The level set for this lint at this line (0-based), if any.
+    /// The innermost definition directive wins over outer ones, and over module directives.
+    pub fn get(&self, lint: Lint, line: u32) -> Option<LintLevel> {
+        self.scoped
+            .iter()
+            .filter(|(start, end, l, _)| *l == lint && (*start..*end).contains(&line))
+            .max_by_key(|(start, _, _, _)| *start)
+            .map(|(_, _, _, level)| *level)
+            .or_else(|| self.module.get(lint))
+    }
+}
+
+/// Parses `# lint: allow unused-argument, unused-def` or `# lint-module: deny unused-import`.
+/// Returns whether it is a module directive, the level, and the lints it names.
+/// Lint names we don't know are skipped.
+fn parse_directive(comment: &str) -> Option<(bool, LintLevel, Vec<Lint>)> {
+    let comment = comment.strip_prefix('#')?;
+
+    // Doc comments are never directives
+    if comment.starts_with('#') {
+        return None;
+    }
+
+    let comment = comment.trim_start();
+    let (is_module, rest) = if let Some(rest) = comment.strip_prefix("lint-module:") {
+        (true, rest)
+    } else {
+        (false, comment.strip_prefix("lint:")?)
+    };
+
+    let mut words = rest.split(|c: char| c.is_whitespace() || c == ',');
+    let level = LintLevel::from_name(words.find(|word| !word.is_empty())?)?;
+    let lints = words.filter_map(Lint::from_name).collect();
+
+    Some((is_module, level, lints))
+}
+
+/// The lines of the definition below a directive, from the directive up to the next line
+/// that is indented no more than the definition itself.
+fn def_below(
+    lines: &[&str],
+    comments: &[Option<&str>],
+    directive_index: usize,
+) -> Option<(u32, u32)> {
+    let is_code = |index: usize| !lines[index].trim().is_empty() && comments[index].is_none();
+
+    let def_index = (directive_index + 1..lines.len()).find(|index| is_code(*index))?;
+    let def_line = lines[def_index];
+    let def_indent = indent(def_line);
+
+    if def_indent < indent(lines[directive_index]) {
+        // The directive is at the end of a block, so there is no definition for it
+        return None;
+    }
+
+    let mut end = lines.len();
+    let mut annotation_body_allowed = is_annotation(def_line);
+
+    for (index, line) in lines.iter().enumerate().skip(def_index + 1) {
+        if is_code(index) && indent(line) <= def_indent {
+            if annotation_body_allowed {
+                // The body that goes with the annotation is part of the same definition
+                annotation_body_allowed = false;
+                continue;
+            }
+
+            end = index;
+            break;
+        }
+    }
+
+    Some((directive_index as u32, end as u32))
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Whether this line starts a type annotation like `name : Type`
+fn is_annotation(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let name_len = trimmed
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(trimmed.len());
+    let rest = trimmed[name_len..].trim_start();
+
+    name_len > 0
+        && trimmed.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && rest.starts_with(':')
+        && !rest.starts_with(":=")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn module_directive() {
+        let directives = LintDirectives::parse("x = 1\n\n# lint-module: deny unused-import\n");
+
+        assert_eq!(directives.get(Lint::UnusedImport, 0), Some(LintLevel::Deny));
+        assert_eq!(directives.get(Lint::UnusedDef, 0), None);
+    }
+
+    #[test]
+    fn def_directive_covers_annotation_and_body() {
+        let src = "x = 1\n\n# lint: allow unused-argument, unused-def\nf : I64 -> I64\nf = \\a ->\n    1\n\ng = \\b -> 2\n";
+        let directives = LintDirectives::parse(src);
+
+        assert_eq!(directives.get(Lint::UnusedArgument, 0), None);
+        assert_eq!(
+            directives.get(Lint::UnusedArgument, 4),
+            Some(LintLevel::Allow)
+        );
+        assert_eq!(directives.get(Lint::UnusedDef, 5), Some(LintLevel::Allow));
+        assert_eq!(directives.get(Lint::UnusedArgument, 7), None);
+    }
+
+    #[test]
+    fn inner_directive_wins() {
+        let src = "# lint-module: deny unused-def\n# lint: warn unused-def\nmain =\n    # lint: allow unused-def\n    y = 2\n    z = 3\n\n    1\n";
+        let directives = LintDirectives::parse(src);
+
+        assert_eq!(directives.get(Lint::UnusedDef, 4), Some(LintLevel::Allow));
+        assert_eq!(directives.get(Lint::UnusedDef, 5), Some(LintLevel::Warn));
+        assert_eq!(directives.get(Lint::UnusedDef, 8), Some(LintLevel::Deny));
+    }
+
+    #[test]
+    fn directive_in_string_is_not_a_comment() {
+        let src = "x =\n    \"\"\"\n    # lint-module: deny unused-def\n    \"\"\"\n\n# lint-module: allow unused-import\n";
+        let directives = LintDirectives::parse(src);
+
+        assert_eq!(directives.get(Lint::UnusedDef, 0), None);
+        assert_eq!(
+            directives.get(Lint::UnusedImport, 0),
+            Some(LintLevel::Allow)
+        );
+    }
+
+    #[test]
+    fn not_directives() {
+        let directives = LintDirectives::parse(
+            "## lint: allow unused-def\nx = 1\n# lint: maybe unused-def\ny = 2\n",
+        );
+
+        assert_eq!(directives, LintDirectives::default());
+    }
+}
@@ -0,0 +1,10 @@
+use roc_module::symbol::Symbol;
+
+/// A call in tail position that could not be turned into a jump, so every time it is made the
+/// stack grows. Only calls that are part of a (mutual) recursion are reported: those are the ones
+/// that can overflow the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnoptimizedTailCall {
+    pub caller: Symbol,
+    pub callee: Symbol,
+}
@@ -191,6 +191,8 @@ pub enum Target {
     WinArm64,
     #[strum(serialize = "wasm32")]
     Wasm32,
+    #[strum(serialize = "wasm32-wasi")]
+    Wasm32Wasi,
 }
 
 const MACOS: target_lexicon::OperatingSystem = target_lexicon::OperatingSystem::MacOSX {
@@ -261,7 +263,7 @@ impl Target {
                 environment: Environment::Unknown,
                 binary_format: BinaryFormat::Macho,
             },
-            Target::Wasm32 => Triple {
+            Target::Wasm32 | Target::Wasm32Wasi => Triple {
                 architecture: Architecture::Wasm32,
                 vendor: Vendor::Unknown,
                 operating_system: OperatingSystem::Wasi,
@@ -2,6 +2,7 @@ use bumpalo::Bump;
 use roc_can::{constraint::Constraints, module::ExposedByModule};
 use roc_checkmate::with_checkmate;
 use roc_derive::SharedDerivedModule;
+use roc_module::symbol::ModuleId;
 use roc_types::subs::{Content, Descriptor, Mark, OptVariable, Rank, Subs, Variable};
 use roc_unify::Env as UEnv;
 
@@ -25,6 +26,7 @@ pub struct SolveEnv<'a> {
 
 /// Environment necessary for inference.
 pub struct InferenceEnv<'a> {
+    pub home: ModuleId,
     pub constraints: &'a Constraints,
     pub function_kind: FunctionKind,
     pub arena: &'a Bump,
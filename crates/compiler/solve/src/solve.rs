@@ -20,16 +20,18 @@ use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::ROC_VERIFY_RIGID_LET_GENERALIZED;
 use roc_error_macros::internal_error;
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{ModuleId, Symbol};
 use roc_problem::can::CycleEntry;
-use roc_region::all::Loc;
-use roc_solve_problem::TypeError;
+use roc_region::all::{Loc, Region};
+use roc_solve_problem::{MismatchTrace, TypeError};
 use roc_solve_schema::UnificationMode;
 use roc_types::subs::{
     self, Content, FlatType, GetSubsSlice, Mark, OptVariable, Rank, Subs, TagExt, UlsOfVar,
     Variable,
 };
-use roc_types::types::{Category, Polarity, Reason, RecordField, Type, TypeExtension, Types, Uls};
+use roc_types::types::{
+    Category, MismatchStep, Polarity, Reason, RecordField, Type, TypeExtension, Types, Uls,
+};
 use roc_unify::unify::{
     unify, unify_introduced_ability_specialization, Obligated, SpecializationLsetCollector,
     Unified::*,
@@ -121,7 +123,7 @@ fn run_help(
 ) -> RunSolveOutput {
     let subs = &mut owned_subs;
     let SolveConfig {
-        home: _,
+        home,
         constraints,
         root_constraint,
         mut types,
@@ -150,6 +152,7 @@ fn run_help(
     };
 
     let mut env = InferenceEnv {
+        home,
         arena: &arena,
         constraints,
         function_kind,
@@ -175,6 +178,8 @@ fn run_help(
     } = obligation_cache.check_derives(env.subs, abilities_store, pending_derives);
     problems.extend(derives_problems);
 
+    let mut typed_holes = Vec::new();
+
     let state = solve(
         &mut env,
         types,
@@ -186,6 +191,16 @@ fn run_help(
         abilities_store,
         &mut obligation_cache,
         &mut awaiting_specializations,
+        &mut typed_holes,
+    );
+
+    report_typed_holes(
+        &mut env,
+        home,
+        problems,
+        abilities_store,
+        &mut obligation_cache,
+        typed_holes,
     );
 
     RunSolveOutput {
@@ -244,6 +259,7 @@ fn solve(
     abilities_store: &mut AbilitiesStore,
     obligation_cache: &mut ObligationCache,
     awaiting_specializations: &mut AwaitingSpecializations,
+    typed_holes: &mut Vec<PendingTypedHole>,
 ) -> State {
     let initial = Work::Constraint {
         scope: &Scope::default(),
@@ -309,7 +325,7 @@ fn solve(
                         *loc_var,
                     );
 
-                    new_scope.insert_symbol_var_if_vacant(*symbol, loc_var.value);
+                    new_scope.insert_symbol_var_if_vacant(*symbol, loc_var.value, loc_var.region);
                 }
 
                 stack.push(Work::Constraint {
@@ -438,7 +454,7 @@ fn solve(
                         *loc_var,
                     );
 
-                    new_scope.insert_symbol_var_if_vacant(*symbol, loc_var.value);
+                    new_scope.insert_symbol_var_if_vacant(*symbol, loc_var.value, loc_var.region);
                 }
 
                 // Note that this vars_by_symbol is the one returned by the
@@ -534,7 +550,7 @@ fn solve(
 
                         state
                     }
-                    Failure(vars, actual_type, expected_type, _bad_impls) => {
+                    Failure(vars, actual_type, expected_type, _bad_impls, path) => {
                         env.introduce(rank, &vars);
 
                         let problem = TypeError::BadExpr(
@@ -542,6 +558,7 @@ fn solve(
                             category.clone(),
                             actual_type,
                             expectation.replace_ref(expected_type),
+                            mismatch_trace(env.home, scope, expectation, path),
                         );
 
                         problems.push(problem);
@@ -650,7 +667,7 @@ fn solve(
                                 state
                             }
 
-                            Failure(vars, actual_type, expected_type, _bad_impls) => {
+                            Failure(vars, actual_type, expected_type, _bad_impls, path) => {
                                 env.introduce(rank, &vars);
 
                                 let problem = TypeError::BadExpr(
@@ -658,6 +675,7 @@ fn solve(
                                     Category::Lookup(*symbol),
                                     actual_type,
                                     expectation.replace_ref(expected_type),
+                                    mismatch_trace(env.home, scope, expectation, path),
                                 );
 
                                 problems.push(problem);
@@ -752,7 +770,7 @@ fn solve(
 
                         state
                     }
-                    Failure(vars, actual_type, expected_type, _bad_impls) => {
+                    Failure(vars, actual_type, expected_type, _bad_impls, path) => {
                         env.introduce(rank, &vars);
 
                         let problem = TypeError::BadPattern(
@@ -760,6 +778,10 @@ fn solve(
                             category.clone(),
                             actual_type,
                             expectation.replace_ref(expected_type),
+                            MismatchTrace {
+                                path,
+                                origin_def: None,
+                            },
                         );
 
                         problems.push(problem);
@@ -968,7 +990,7 @@ fn solve(
 
                         state
                     }
-                    Failure(vars, actual_type, expected_to_include_type, _bad_impls) => {
+                    Failure(vars, actual_type, expected_to_include_type, _bad_impls, path) => {
                         env.introduce(rank, &vars);
 
                         let problem = TypeError::BadPattern(
@@ -976,6 +998,10 @@ fn solve(
                             pattern_category.clone(),
                             expected_to_include_type,
                             PExpected::NoExpectation(actual_type),
+                            MismatchTrace {
+                                path,
+                                origin_def: None,
+                            },
                         );
                         problems.push(problem);
 
@@ -1138,7 +1164,7 @@ fn solve(
                                 UnificationMode::EQ,
                                 cond_polarity,
                             ) {
-                                Failure(vars, actual_type, expected_type, _bad_impls) => {
+                                Failure(vars, actual_type, expected_type, _bad_impls, path) => {
                                     env.introduce(rank, &vars);
 
                                     // Figure out the problem - it might be pattern or value
@@ -1153,6 +1179,7 @@ fn solve(
                                                 real_category,
                                                 actual_type,
                                                 expected.replace_ref(expected_type),
+                                                mismatch_trace(env.home, scope, expected, path),
                                             )
                                         }
 
@@ -1165,6 +1192,10 @@ fn solve(
                                                 real_category,
                                                 expected_type,
                                                 expected.replace_ref(actual_type),
+                                                MismatchTrace {
+                                                    path,
+                                                    origin_def: None,
+                                                },
                                             )
                                         }
                                     };
@@ -1379,7 +1410,7 @@ fn solve(
 
                             state
                         }
-                        Failure(vars, actual_type, _, _) => {
+                        Failure(vars, actual_type, ..) => {
                             env.introduce(rank, &vars);
 
                             let problem = TypeError::IngestedFileUnsupportedType(
@@ -1392,12 +1423,159 @@ fn solve(
                     }
                 }
             }
+            TypedHole(var, region) => {
+                // The hole may be constrained further by constraints solved after this one, so
+                // only remember it and the values in scope here; it's reported once solving is done.
+                typed_holes.push(PendingTypedHole {
+                    var: *var,
+                    region: *region,
+                    in_scope: scope.vars_by_symbol().collect(),
+                });
+
+                state
+            }
         };
     }
 
     state
 }
 
+/// Explains a failed unification against `expectation`: where in the types it failed, and, when
+/// the expectation comes from a function of this module, where that function is defined.
+fn mismatch_trace<T>(
+    home: ModuleId,
+    scope: &Scope,
+    expectation: &Expected<T>,
+    path: Vec<MismatchStep>,
+) -> MismatchTrace {
+    let origin = match expectation {
+        Expected::ForReason(
+            Reason::FnArg {
+                name: Some(symbol), ..
+            }
+            | Reason::TypedArg {
+                name: Some(symbol), ..
+            }
+            | Reason::FnCall {
+                name: Some(symbol), ..
+            },
+            _,
+            _,
+        ) => Some(*symbol),
+        _ => None,
+    };
+
+    MismatchTrace {
+        path,
+        origin_def: origin
+            .filter(|symbol| symbol.module_id() == home)
+            .and_then(|symbol| scope.get_region_by_symbol(&symbol)),
+    }
+}
+
+/// A typed hole `_` seen while solving, waiting to be reported.
+struct PendingTypedHole {
+    var: Variable,
+    region: Region,
+    in_scope: Vec<(Symbol, Variable)>,
+}
+
+/// Reports each typed hole with its solved type, the types of the values of this module that
+/// are in scope at the hole, and the values in scope that could fill it.
+fn report_typed_holes(
+    env: &mut InferenceEnv,
+    home: ModuleId,
+    problems: &mut Vec<TypeError>,
+    abilities_store: &AbilitiesStore,
+    obligation_cache: &mut ObligationCache,
+    typed_holes: Vec<PendingTypedHole>,
+) {
+    for PendingTypedHole {
+        var,
+        region,
+        mut in_scope,
+    } in typed_holes
+    {
+        // Values bound closest to the hole are the most interesting, so list them first.
+        in_scope.reverse();
+
+        // Anything fits a hole whose type is unconstrained, so suggesting values is only noise.
+        let candidates = match env.subs.get_content_without_compacting(var) {
+            Content::FlexVar(_) | Content::Error => vec![],
+            _ => in_scope
+                .iter()
+                .filter(|(_, candidate_var)| {
+                    fits_typed_hole(env, abilities_store, obligation_cache, var, *candidate_var)
+                })
+                .map(|(symbol, _)| *symbol)
+                .collect(),
+        };
+
+        let typ = env.subs.var_to_error_type(var, Polarity::OF_VALUE);
+
+        let locals = in_scope
+            .into_iter()
+            .filter(|(symbol, _)| symbol.module_id() == home)
+            .map(|(symbol, local_var)| {
+                let local_type = env.subs.var_to_error_type(local_var, Polarity::OF_VALUE);
+                (symbol, local_type)
+            })
+            .collect();
+
+        problems.push(TypeError::TypedHole {
+            region,
+            typ,
+            locals,
+            candidates,
+        });
+    }
+}
+
+/// Whether a value of type `candidate_var` could be used where the hole `hole_var` is.
+/// Neither type is changed.
+fn fits_typed_hole(
+    env: &mut InferenceEnv,
+    abilities_store: &AbilitiesStore,
+    obligation_cache: &mut ObligationCache,
+    hole_var: Variable,
+    candidate_var: Variable,
+) -> bool {
+    // Like a lookup, use a copy of the candidate's type so that a generalized type is
+    // instantiated afresh.
+    let candidate_var = {
+        let mut solve_env = env.as_solve_env();
+        let solve_env = &mut solve_env;
+        deep_copy_var_in(solve_env, Rank::toplevel(), candidate_var, solve_env.arena)
+    };
+
+    let snapshot = env.subs.snapshot();
+
+    let fits = match unify(
+        &mut env.uenv(),
+        candidate_var,
+        hole_var,
+        UnificationMode::EQ,
+        Polarity::OF_VALUE,
+    ) {
+        Success {
+            must_implement_ability,
+            ..
+        } => obligation_cache
+            .check_obligations(
+                env.subs,
+                abilities_store,
+                must_implement_ability,
+                AbilityImplError::DoesNotImplement,
+            )
+            .is_empty(),
+        Failure(..) => false,
+    };
+
+    env.subs.rollback_to(snapshot);
+
+    fits
+}
+
 fn chase_alias_content(subs: &Subs, mut var: Variable) -> (Variable, &Content) {
     loop {
         match subs.get_content_without_compacting(var) {
@@ -1717,6 +1895,7 @@ fn check_ability_specialization(
                             Category::AbilityMemberSpecialization(ability_member),
                             actual_type,
                             Expected::ForReason(reason, expected_type, symbol_loc_var.region),
+                            MismatchTrace::default(),
                         );
 
                         problems.push(problem);
@@ -1726,7 +1905,7 @@ fn check_ability_specialization(
                 }
             }
 
-            Failure(vars, expected_type, actual_type, unimplemented_abilities) => {
+            Failure(vars, expected_type, actual_type, unimplemented_abilities, path) => {
                 env.subs.commit_snapshot(snapshot);
                 env.introduce(rank, &vars);
 
@@ -1741,6 +1920,10 @@ fn check_ability_specialization(
                     Category::AbilityMemberSpecialization(ability_member),
                     actual_type,
                     Expected::ForReason(reason, expected_type, symbol_loc_var.region),
+                    MismatchTrace {
+                        path,
+                        origin_def: None,
+                    },
                 );
 
                 problems.push(problem);
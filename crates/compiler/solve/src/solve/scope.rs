@@ -1,4 +1,5 @@
 use roc_module::symbol::Symbol;
+use roc_region::all::Region;
 use roc_types::subs::Variable;
 
 /// The scope of the solver, as symbols are introduced.
@@ -6,6 +7,7 @@ use roc_types::subs::Variable;
 pub struct Scope {
     symbols: Vec<Symbol>,
     variables: Vec<Variable>,
+    regions: Vec<Region>,
 }
 
 impl Scope {
@@ -24,13 +26,22 @@ impl Scope {
             .map(|index| self.variables[index])
     }
 
+    /// Where the symbol is defined
+    pub fn get_region_by_symbol(&self, symbol: &Symbol) -> Option<Region> {
+        self.symbols
+            .iter()
+            .position(|s| s == symbol)
+            .map(|index| self.regions[index])
+    }
+
     #[inline(always)]
-    pub fn insert_symbol_var_if_vacant(&mut self, symbol: Symbol, var: Variable) {
+    pub fn insert_symbol_var_if_vacant(&mut self, symbol: Symbol, var: Variable, region: Region) {
         match self.symbols.iter().position(|s| *s == symbol) {
             None => {
                 // symbol is not in vars_by_symbol yet; insert it
                 self.symbols.push(symbol);
                 self.variables.push(var);
+                self.regions.push(region);
             }
             Some(_) => {
                 // do nothing
@@ -5,7 +5,7 @@ use roc_collections::soa::{Index, Slice};
 use roc_error_macros::internal_error;
 use roc_module::{ident::TagName, symbol::Symbol};
 use roc_region::all::Loc;
-use roc_solve_problem::TypeError;
+use roc_solve_problem::{MismatchTrace, TypeError};
 use roc_solve_schema::UnificationMode;
 use roc_types::{
     subs::{
@@ -814,7 +814,7 @@ pub(crate) fn type_to_var_help(
                             .drain()
                             .all(|(_, vals)| vals.is_empty()));
                     }
-                    Unified::Failure(_vars, actual_type, expected_type, _bad_impls) => {
+                    Unified::Failure(_vars, actual_type, expected_type, _bad_impls, path) => {
                         // No introduction needed
 
                         let problem = TypeError::BadExpr(
@@ -822,6 +822,10 @@ pub(crate) fn type_to_var_help(
                             category,
                             actual_type,
                             Expected::NoExpectation(expected_type),
+                            MismatchTrace {
+                                path,
+                                origin_def: None,
+                            },
                         );
 
                         problems.push(problem);
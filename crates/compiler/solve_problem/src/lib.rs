@@ -6,12 +6,24 @@ use roc_module::{ident::Lowercase, symbol::Symbol};
 use roc_problem::{can::CycleEntry, Severity};
 use roc_region::all::Region;
 
-use roc_types::types::{Category, ErrorType, PatternCategory};
+use roc_types::types::{Category, ErrorType, MismatchStep, PatternCategory};
 
 #[derive(Debug, Clone)]
 pub enum TypeError {
-    BadExpr(Region, Category, ErrorType, Expected<ErrorType>),
-    BadPattern(Region, PatternCategory, ErrorType, PExpected<ErrorType>),
+    BadExpr(
+        Region,
+        Category,
+        ErrorType,
+        Expected<ErrorType>,
+        MismatchTrace,
+    ),
+    BadPattern(
+        Region,
+        PatternCategory,
+        ErrorType,
+        PExpected<ErrorType>,
+        MismatchTrace,
+    ),
     CircularType(Region, Symbol, ErrorType),
     CircularDef(Vec<CycleEntry>),
     UnexposedLookup(Region, Symbol),
@@ -33,6 +45,15 @@ pub enum TypeError {
     },
     IngestedFileBadUtf8(Box<PathBuf>, Utf8Error),
     IngestedFileUnsupportedType(Box<PathBuf>, ErrorType),
+    TypedHole {
+        region: Region,
+        /// The type the hole is expected to have
+        typ: ErrorType,
+        /// Values of this module in scope at the hole, innermost first
+        locals: Vec<(Symbol, ErrorType)>,
+        /// Values in scope whose type fits the hole
+        candidates: Vec<Symbol>,
+    },
 }
 
 impl TypeError {
@@ -54,6 +75,7 @@ impl TypeError {
             TypeError::WrongSpecialization { .. } => RuntimeError,
             TypeError::IngestedFileBadUtf8(..) => Fatal,
             TypeError::IngestedFileUnsupportedType(..) => Fatal,
+            TypeError::TypedHole { .. } => RuntimeError,
         }
     }
 
@@ -66,6 +88,7 @@ impl TypeError {
             | TypeError::BadExprMissingAbility(region, ..)
             | TypeError::StructuralSpecialization { region, .. }
             | TypeError::WrongSpecialization { region, .. }
+            | TypeError::TypedHole { region, .. }
             | TypeError::BadPatternMissingAbility(region, ..) => Some(*region),
             TypeError::UnfulfilledAbility(ab, ..) => ab.region(),
             TypeError::Exhaustive(e) => Some(e.region()),
@@ -76,6 +99,15 @@ impl TypeError {
     }
 }
 
+/// How a type mismatch came about, beyond the two types and the expectation.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct MismatchTrace {
+    /// Where in the two types unification first failed, outermost step first.
+    pub path: Vec<MismatchStep>,
+    /// Where the function the expectation comes from is defined, if it is in the same module.
+    pub origin_def: Option<Region>,
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Unfulfilled {
     /// No claimed implementation of an ability for an opaque type.
@@ -124,6 +156,8 @@ pub enum NotDerivableContext {
     Encode(NotDerivableEncode),
     Decode(NotDerivableDecode),
     Eq(NotDerivableEq),
+    Sort(NotDerivableSort),
+    Default(NotDerivableDefault),
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -141,3 +175,14 @@ pub enum NotDerivableDecode {
 pub enum NotDerivableEq {
     FloatingPoint,
 }
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum NotDerivableSort {
+    FloatingPoint,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum NotDerivableDefault {
+    NotARecord,
+    OptionalRecordField(Lowercase),
+}
@@ -33,6 +33,17 @@ test_key_eq! {
     same_tuple_fields_diff_types:
         v!((v!(U8), v!(U16),)), v!((v!(U32), v!(U64),))
 
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+
     list_list_diff_types:
         v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
     str_str:
@@ -49,6 +60,13 @@ test_key_neq! {
 
     different_tuple_arities:
         v!((v!(U8), v!(U16),)), v!((v!(U8), v!(U16), v!(U32),))
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_tag_union_tag_arities:
+        v!([ A v!(U8) ]), v!([ A v!(U8) v!(U16) ])
 }
 
 #[test]
@@ -101,6 +119,27 @@ fn derivable_record_with_record_ext() {
     );
 }
 
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Decoder,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Decoder,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
 #[test]
 fn list() {
     derive_test(Decoder, v!(Symbol::LIST_LIST v!(STR)), |golden| {
@@ -231,3 +270,94 @@ fn tuple_2_fields() {
         )
     })
 }
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Decoder, v!([A v!(U8) v!(STR), B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str, B]
+        # Decoder [A val val1, B] fmt where fmt implements DecoderFormatting, val implements Decoding, val1 implements Decoding
+        # List U8, fmt -[[custom3(35)]]-> { rest : List U8, result : [Err [TooShort], Ok [A val val1, B]] } where fmt implements DecoderFormatting, val implements Decoding, val1 implements Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom3(35)]]
+        #Derived.decoder_[A 2,B 0] =
+          custom
+            \#Derived.bytes5, #Derived.fmt5 ->
+              decodeWith
+                #Derived.bytes5
+                (tag
+                  \#Derived.tagName ->
+                    when #Derived.tagName is
+                      "A" ->
+                        Payload (custom
+                          \#Derived.bytes3, #Derived.fmt3 ->
+                            decodeWith
+                              #Derived.bytes3
+                              (tuple
+                                { e1: Err NoElem, e0: Err NoElem }
+                                \#Derived.stateRecord2, #Derived.index ->
+                                  when #Derived.index is
+                                    0 ->
+                                      Next (custom
+                                        \#Derived.bytes, #Derived.fmt ->
+                                          when decodeWith
+                                              #Derived.bytes
+                                              decoder
+                                              #Derived.fmt is
+                                            #Derived.rec ->
+                                              {
+                                                result: when #Derived.rec.result is
+                                                    Ok #Derived.val ->
+                                                      Ok {
+                                                      stateRecord2 & e0: Ok #Derived.val
+                                                      }
+                                                    Err #Derived.err ->
+                                                      Err #Derived.err,
+                                                rest: #Derived.rec.rest
+                                              })
+                                    1 ->
+                                      Next (custom
+                                        \#Derived.bytes2, #Derived.fmt2 ->
+                                          when decodeWith
+                                              #Derived.bytes2
+                                              decoder
+                                              #Derived.fmt2 is
+                                            #Derived.rec2 ->
+                                              {
+                                                result: when #Derived.rec2.result is
+                                                    Ok #Derived.val2 ->
+                                                      Ok {
+                                                      stateRecord2 &
+                                                        e1: Ok #Derived.val2
+                                                      }
+                                                    Err #Derived.err2 ->
+                                                      Err #Derived.err2,
+                                                rest: #Derived.rec2.rest
+                                              })
+                                    _ -> TooLong
+                                \#Derived.stateRecord ->
+                                  when #Derived.stateRecord.e0 is
+                                    Ok #Derived.0 ->
+                                      when #Derived.stateRecord.e1 is
+                                        Ok #Derived.1 -> Ok (A #Derived.0 #Derived.1)
+                                        _ -> Err TooShort
+                                    _ -> Err TooShort)
+                              #Derived.fmt3)
+                      "B" ->
+                        Payload (custom
+                          \#Derived.bytes4, #Derived.fmt4 ->
+                            decodeWith
+                              #Derived.bytes4
+                              (tuple
+                                { }
+                                \#Derived.stateRecord4, #Derived.index2 ->
+                                  when #Derived.index2 is
+                                    _ -> TooLong
+                                \#Derived.stateRecord3 -> Ok B)
+                              #Derived.fmt4)
+                      _ -> UnknownTag)
+                #Derived.fmt5
+        "###
+        )
+    })
+}
@@ -0,0 +1,111 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_derivable, check_single_lset_immediate, check_underivable, derive_test},
+    v,
+};
+use insta::assert_snapshot;
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+use roc_derive_key::{default::FlatDefaultKey, DeriveBuiltin::Default, DeriveError, DeriveKey};
+
+test_key_eq! {
+    Default,
+
+    same_record:
+        v!({ a: v!(U8), }), v!({ a: v!(U8), })
+    same_record_fields_diff_types:
+        v!({ a: v!(U8), }), v!({ a: v!(STR), })
+    same_record_fields_any_order:
+        v!({ a: v!(U8), b: v!(U8), c: v!(U8), }),
+        v!({ c: v!(U8), a: v!(U8), b: v!(U8), })
+    explicit_empty_record_and_implicit_empty_record:
+        v!(EMPTY_RECORD), v!({})
+}
+
+test_key_neq! {
+    Default,
+
+    different_record_fields:
+        v!({ a: v!(U8), }), v!({ b: v!(U8), })
+    record_empty_vs_nonempty:
+        v!(EMPTY_RECORD), v!({ a: v!(U8), })
+}
+
+#[test]
+fn immediates() {
+    check_single_lset_immediate(Default, v!(U8), Symbol::DEFAULT_DEFAULT_NUM);
+    check_single_lset_immediate(Default, v!(I128), Symbol::DEFAULT_DEFAULT_NUM);
+    check_single_lset_immediate(Default, v!(DEC), Symbol::DEFAULT_DEFAULT_NUM);
+    check_single_lset_immediate(Default, v!(F64), Symbol::DEFAULT_DEFAULT_NUM);
+    check_single_lset_immediate(Default, v!(STR), Symbol::DEFAULT_DEFAULT_STR);
+    check_single_lset_immediate(
+        Default,
+        v!(Symbol::LIST_LIST v!(U8)),
+        Symbol::DEFAULT_DEFAULT_LIST,
+    );
+}
+
+#[test]
+fn optional_record_field_derive_error() {
+    check_underivable(Default, v!({ ?a: v!(U8), }), DeriveError::Underivable);
+}
+
+#[test]
+fn tag_union_derive_error() {
+    check_underivable(Default, v!([ A v!(U8), B ]), DeriveError::Underivable);
+}
+
+#[test]
+fn derivable_record_ext_flex_var() {
+    check_derivable(
+        Default,
+        v!({ a: v!(STR), }* ),
+        DeriveKey::Default(FlatDefaultKey::Record(vec!["a".into()])),
+    );
+}
+
+#[test]
+fn derivable_record_with_record_ext() {
+    check_derivable(
+        Default,
+        v!({ b: v!(STR), }{ a: v!(STR), } ),
+        DeriveKey::Default(FlatDefaultKey::Record(vec!["a".into(), "b".into()])),
+    );
+}
+
+#[test]
+fn empty_record() {
+    derive_test(Default, v!(EMPTY_RECORD), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for {}
+        # {} -[[default_{}(0)]]-> {}
+        # {} -[[default_{}(0)]]-> {}
+        # Specialization lambda sets:
+        #   @<1>: [[default_{}(0)]]
+        #Derived.default_{} = \{} -> { }
+        "###
+        )
+    })
+}
+
+#[test]
+fn two_field_record() {
+    derive_test(Default, v!({ a: v!(U8), b: v!(STR), }), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for { a : U8, b : Str }
+        # {} -[[default_{a,b}(0)]]-> { a : a, b : a1 } where a implements Default, a1 implements Default
+        # {} -[[default_{a,b}(0)]]-> { a : a, b : a1 } where a implements Default, a1 implements Default
+        # Specialization lambda sets:
+        #   @<1>: [[default_{a,b}(0)]]
+        #Derived.default_{a,b} = \{} -> { b: default {}, a: default {} }
+        "###
+        )
+    })
+}
@@ -0,0 +1,271 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_derivable, check_single_lset_immediate, check_underivable, derive_test},
+    v,
+};
+use insta::assert_snapshot;
+use roc_module::symbol::Symbol;
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+
+use roc_derive_key::{sort::FlatSortKey, DeriveBuiltin::Compare, DeriveError, DeriveKey};
+
+fn empty_tuple(subs: &mut Subs) -> Variable {
+    roc_derive::synth_var(subs, Content::Structure(FlatType::EmptyTuple))
+}
+
+test_key_eq! {
+    Compare,
+
+    same_record:
+        v!({ a: v!(U8), }), v!({ a: v!(U8), })
+    same_record_fields_diff_types:
+        v!({ a: v!(U8), }), v!({ a: v!(STR), })
+    same_record_fields_any_order:
+        v!({ a: v!(U8), b: v!(U8), c: v!(U8), }),
+        v!({ c: v!(U8), a: v!(U8), b: v!(U8), })
+    explicit_empty_record_and_implicit_empty_record:
+        v!(EMPTY_RECORD), v!({})
+
+    same_tuple:
+        v!((v!(U8), v!(U16),)), v!((v!(U8), v!(U16),))
+    same_tuple_fields_diff_types:
+        v!((v!(U8), v!(U16),)), v!((v!(U32), v!(U64),))
+    explicit_empty_tuple_and_implicit_empty_tuple:
+        empty_tuple, v!(())
+
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
+    same_recursive_tag_union:
+        v!([ Nil, Cons v!(^lst)] as lst), v!([ Nil, Cons v!(^lst)] as lst)
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+}
+
+test_key_neq! {
+    Compare,
+
+    different_record_fields:
+        v!({ a: v!(U8), }), v!({ b: v!(U8), })
+    record_empty_vs_nonempty:
+        v!(EMPTY_RECORD), v!({ a: v!(U8), })
+
+    different_tuple_arities:
+        v!((v!(U8), v!(U16),)), v!((v!(U8), v!(U16), v!(U32),))
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_recursive_tag_union_tags:
+        v!([ Nil, Cons v!(^lst) ] as lst), v!([ Nil, Next v!(^lst) ] as lst)
+}
+
+#[test]
+fn immediates() {
+    check_single_lset_immediate(Compare, v!(U8), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(U16), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(U32), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(U64), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(U128), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(I8), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(I16), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(I32), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(I64), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(I128), Symbol::NUM_COMPARE);
+    check_single_lset_immediate(Compare, v!(STR), Symbol::SORT_COMPARE_STR);
+    check_single_lset_immediate(
+        Compare,
+        v!(Symbol::LIST_LIST v!(U8)),
+        Symbol::SORT_COMPARE_LIST,
+    );
+    check_single_lset_immediate(
+        Compare,
+        v!(Symbol::LIST_LIST v!(STR)),
+        Symbol::SORT_COMPARE_LIST,
+    );
+}
+
+#[test]
+fn optional_record_field_derive_error() {
+    check_underivable(Compare, v!({ ?a: v!(U8), }), DeriveError::Underivable);
+}
+
+#[test]
+fn derivable_empty_tuple() {
+    check_derivable(
+        Compare,
+        empty_tuple,
+        DeriveKey::Compare(FlatSortKey::Tuple(0)),
+    );
+}
+
+#[test]
+fn derivable_record_ext_flex_var() {
+    check_derivable(
+        Compare,
+        v!({ a: v!(STR), }* ),
+        DeriveKey::Compare(FlatSortKey::Record(vec!["a".into()])),
+    );
+}
+
+#[test]
+fn derivable_record_with_record_ext() {
+    check_derivable(
+        Compare,
+        v!({ b: v!(STR), }{ a: v!(STR), } ),
+        DeriveKey::Compare(FlatSortKey::Record(vec!["a".into(), "b".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Compare,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Compare(FlatSortKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
+#[test]
+fn empty_record() {
+    derive_test(Compare, v!(EMPTY_RECORD), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for {}
+        # {}, {} -[[compare_{}(0)]]-> [EQ, GT, LT]
+        # {}, {} -[[compare_{}(0)]]-> [EQ, GT, LT]
+        # Specialization lambda sets:
+        #   @<1>: [[compare_{}(0)]]
+        #Derived.compare_{} = \#Derived.rcd1, #Derived.rcd2 -> EQ
+        "###
+        )
+    })
+}
+
+#[test]
+fn two_field_record() {
+    derive_test(Compare, v!({ a: v!(U8), b: v!(STR), }), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for { a : U8, b : Str }
+        # { a : a, b : a1 }, { a : a, b : a1 } -[[compare_{a,b}(0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # { a : a, b : a1 }, { a : a, b : a1 } -[[compare_{a,b}(0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # Specialization lambda sets:
+        #   @<1>: [[compare_{a,b}(0)]]
+        #Derived.compare_{a,b} =
+          \#Derived.rcd1, #Derived.rcd2 ->
+            when compare #Derived.rcd1.a #Derived.rcd2.a is
+              EQ -> compare #Derived.rcd1.b #Derived.rcd2.b
+              #Derived.order -> #Derived.order
+        "###
+        )
+    })
+}
+
+#[test]
+fn two_element_tuple() {
+    derive_test(Compare, v!((v!(U8), v!(STR),)), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for ( U8, Str )*
+        # ( a, a1 )b, ( a, a1 )b -[[compare_(arity:2)(0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # ( a, a1 )b, ( a, a1 )b -[[compare_(arity:2)(0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # Specialization lambda sets:
+        #   @<1>: [[compare_(arity:2)(0)]]
+        #Derived.compare_(arity:2) =
+          \#Derived.tup1, #Derived.tup2 ->
+            when compare #Derived.tup1.0 #Derived.tup2.0 is
+              EQ -> compare #Derived.tup1.1 #Derived.tup2.1
+              #Derived.order -> #Derived.order
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_one_label_newtype() {
+    derive_test(Compare, v!([A v!(U8) v!(STR)]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str]
+        # [A a a1], [A a a1] -[[compare_[A 2](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # [A a a1], [A a a1] -[[compare_[A 2](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # Specialization lambda sets:
+        #   @<1>: [[compare_[A 2](0)]]
+        #Derived.compare_[A 2] =
+          \A #Derived.1 #Derived.2, A #Derived.3 #Derived.4 ->
+            when compare #Derived.1 #Derived.3 is
+              EQ -> compare #Derived.2 #Derived.4
+              #Derived.order -> #Derived.order
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Compare, v!([A v!(U8) v!(STR), B v!(STR)]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str, B Str]
+        # [A a a1, B a2], [A a a1, B a2] -[[compare_[A 2,B 1](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort, a2 implements Sort
+        # [A a a1, B a2], [A a a1, B a2] -[[compare_[A 2,B 1](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort, a2 implements Sort
+        # Specialization lambda sets:
+        #   @<1>: [[compare_[A 2,B 1](0)]]
+        #Derived.compare_[A 2,B 1] =
+          \#Derived.union1, #Derived.union2 ->
+            when #Derived.union1 is
+              A #Derived.3 #Derived.4 ->
+                when #Derived.union2 is
+                  A #Derived.5 #Derived.6 ->
+                    when compare #Derived.3 #Derived.5 is
+                      EQ -> compare #Derived.4 #Derived.6
+                      #Derived.order -> #Derived.order
+                  B _ -> LT
+              B #Derived.8 ->
+                when #Derived.union2 is
+                  A _ _ -> GT
+                  B #Derived.9 -> compare #Derived.8 #Derived.9
+        "###
+        )
+    })
+}
+
+#[test]
+fn recursive_tag_union() {
+    derive_test(Compare, v!([Nil, Cons v!(U8) v!(^lst) ] as lst), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [Cons U8 $rec, Nil] as $rec
+        # [Cons a a1, Nil], [Cons a a1, Nil] -[[compare_[Cons 2,Nil 0](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # [Cons a a1, Nil], [Cons a a1, Nil] -[[compare_[Cons 2,Nil 0](0)]]-> [EQ, GT, LT] where a implements Sort, a1 implements Sort
+        # Specialization lambda sets:
+        #   @<1>: [[compare_[Cons 2,Nil 0](0)]]
+        #Derived.compare_[Cons 2,Nil 0] =
+          \#Derived.union1, #Derived.union2 ->
+            when #Derived.union1 is
+              Cons #Derived.3 #Derived.4 ->
+                when #Derived.union2 is
+                  Cons #Derived.5 #Derived.6 ->
+                    when compare #Derived.3 #Derived.5 is
+                      EQ -> compare #Derived.4 #Derived.6
+                      #Derived.order -> #Derived.order
+                  Nil -> LT
+              Nil ->
+                when #Derived.union2 is
+                  Cons _ _ -> GT
+                  Nil -> EQ
+        "###
+        )
+    })
+}
@@ -1,8 +1,10 @@
 #![cfg(test)]
 
 mod decoding;
+mod default;
 mod encoding;
 mod eq;
 mod hash;
+mod sort;
 
 mod util;
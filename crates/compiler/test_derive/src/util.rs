@@ -73,6 +73,16 @@ fn module_source_and_path(builtin: DeriveBuiltin) -> (ModuleId, &'static str, Pa
             module_source(ModuleId::INSPECT),
             builtins_path.join("Inspect.roc"),
         ),
+        DeriveBuiltin::Compare => (
+            ModuleId::SORT,
+            module_source(ModuleId::SORT),
+            builtins_path.join("Sort.roc"),
+        ),
+        DeriveBuiltin::Default => (
+            ModuleId::DEFAULT,
+            module_source(ModuleId::DEFAULT),
+            builtins_path.join("Default.roc"),
+        ),
     }
 }
 
@@ -1447,6 +1447,59 @@ fn decode_tuple_of_tuples() {
     )
 }
 
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                decoded : Result (List [A Str U8, B]) _
+                decoded = Str.toUtf8 "[{\"A\":[\"ab\",10]},{\"B\":[]}]" |> Decode.fromBytes TotallyNotJson.json
+
+                when decoded is
+                    Ok [A "ab" 10, B] -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn encode_then_decode_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                value : [Leaf Str, Node Str Str]
+                value = Node "ab" "cd"
+
+                decoded : Result [Leaf Str, Node Str Str] _
+                decoded = Encode.toBytes value TotallyNotJson.json |> Decode.fromBytes TotallyNotJson.json
+
+                when decoded is
+                    Ok (Node "ab" "cd") -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -2146,6 +2199,243 @@ mod eq {
     }
 }
 
+mod sort {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::RocStr;
+
+    #[test]
+    fn compare_immediates() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                toStr = \order ->
+                    when order is
+                        LT -> "LT"
+                        EQ -> "EQ"
+                        GT -> "GT"
+
+                main =
+                    [
+                        Sort.compare 1u8 2u8,
+                        Sort.compare "b" "a",
+                        Sort.compare [1u8, 2] [1u8, 2],
+                        Sort.compare [1u8] [1u8, 2],
+                        Sort.compare Bool.true Bool.false,
+                    ]
+                    |> List.map toStr
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("LT,GT,EQ,LT,GT"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derive_sort_for_record() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [{ b: "x", a: 2u8 }, { b: "z", a: 1u8 }, { b: "y", a: 1u8 }]
+                    |> List.sortWith Sort.compare
+                    |> List.map .b
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("y,z,x"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derive_sort_for_tuple() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [(2u8, "c"), (1u8, "b"), (2u8, "a")]
+                    |> List.sortWith Sort.compare
+                    |> List.map .1
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("b,a,c"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derive_sort_for_tag_union() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                toStr = \tag ->
+                    when tag is
+                        A n -> "A\(Num.toStr n)"
+                        B s -> "B\(s)"
+                        C -> "C"
+
+                main =
+                    [C, B "y", A 2u8, B "x", A 1u8]
+                    |> List.sortWith Sort.compare
+                    |> List.map toStr
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("A1,A2,Bx,By,C"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derive_sort_for_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Q := U8 implements [Sort]
+
+                main = Sort.compare (@Q 15) (@Q 16) == LT
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derive_sort_for_opaque_tag_union_in_declared_order() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Priority := [Low, Medium U8, High] implements [Sort]
+
+                toStr = \@Priority priority ->
+                    when priority is
+                        Low -> "L"
+                        Medium n -> "M\(Num.toStr n)"
+                        High -> "H"
+
+                main =
+                    [@Priority High, @Priority (Medium 2), @Priority Low, @Priority (Medium 1)]
+                    |> List.sortWith Sort.compare
+                    |> List.map toStr
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("L,M1,M2,H"),
+            RocStr
+        )
+    }
+}
+
+mod default {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::RocStr;
+
+    #[test]
+    fn default_immediates() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [Default] provides [main] to "./platform"
+
+                main =
+                    n : U8
+                    n = Default.default {}
+
+                    f : F64
+                    f = Default.default {}
+
+                    b : Bool
+                    b = Default.default {}
+
+                    s : Str
+                    s = Default.default {}
+
+                    l : List Str
+                    l = Default.default {}
+
+                    d : Dict Str U8
+                    d = Default.default {}
+
+                    n == 0 && Num.isZero f && !b && s == "" && List.isEmpty l && Dict.isEmpty d
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derive_default_for_record() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [Default] provides [main] to "./platform"
+
+                Config : { retries : U8, name : Str, nested : { verbose : Bool, tags : List Str } }
+
+                main =
+                    defaults : Config
+                    defaults = Default.default {}
+
+                    config = { defaults & name: "server" }
+
+                    Str.joinWith [config.name, Num.toStr config.retries, Num.toStr (List.len config.nested.tags)] ","
+                "#
+            ),
+            RocStr::from("server,0,0"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derive_default_for_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [Default.{ Default }] provides [main] to "./platform"
+
+                Q := { count : U8 } implements [Default, Eq]
+
+                main =
+                    q : Q
+                    q = Default.default {}
+
+                    q == @Q { count: 0 }
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn issue_4772_weakened_monomorphic_destructure() {
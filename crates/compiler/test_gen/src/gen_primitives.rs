@@ -3637,6 +3637,64 @@ fn mutual_recursion_top_level_defs() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn mutual_tail_recursion_does_not_grow_the_stack() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [ main ] to "./platform"
+
+            isEven = \n ->
+                when n is
+                    0 -> Bool.true
+                    _ -> isOdd (n - 1)
+
+            isOdd = \n ->
+                when n is
+                    0 -> Bool.false
+                    _ -> isEven (n - 1)
+
+            main = isOdd 1_000_001
+            "#
+        ),
+        true,
+        bool
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn mutual_tail_recursion_state_machine() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [ main ] to "./platform"
+
+            space = \bytes, i, letters ->
+                when List.get bytes i is
+                    Err _ -> letters
+                    Ok 32 -> space bytes (i + 1) letters
+                    Ok _ -> word bytes (i + 1) letters 1
+
+            word = \bytes, i, letters, length ->
+                when List.get bytes i is
+                    Err _ -> letters + length
+                    Ok 32 -> space bytes (i + 1) (letters + length)
+                    Ok _ -> word bytes (i + 1) letters (length + 1)
+
+            main : Nat
+            main =
+                bytes = List.join (List.repeat [97, 97, 32] 300_000)
+
+                space bytes 0 0
+            "#
+        ),
+        600_000,
+        usize
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn polymorphic_lambda_captures_polymorphic_value() {
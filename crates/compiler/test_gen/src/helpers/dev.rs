@@ -63,6 +63,10 @@ pub fn helper(
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
         function_kind: FunctionKind::LambdaSet,
+        // `roc build` inlines and folds for this backend too
+        inline_and_fold: true,
+        eval_constants: cfg!(feature = "eval-constants"),
+        stack_allocate: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -139,15 +143,18 @@ pub fn helper(
     for (home, (module_path, src)) in loaded.sources {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator, DEFAULT_PALETTE};
 
+        let parse_problems = loaded.parse_problems.remove(&home).unwrap_or_default();
         let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
         let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
 
-        let error_count = can_problems.len() + type_problems.len();
+        let error_count = parse_problems.len() + can_problems.len() + type_problems.len();
 
         if error_count == 0 {
             continue;
         }
 
+        lines.extend(parse_problems.into_iter().map(|problem| problem.report));
+
         let line_info = LineInfo::new(&src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
@@ -196,6 +203,7 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        instrument_rc: false,
     };
 
     let target = target_lexicon::Triple::host();
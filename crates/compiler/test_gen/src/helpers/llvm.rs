@@ -78,6 +78,9 @@ fn create_llvm_module<'a>(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        inline_and_fold: false,
+        eval_constants: cfg!(feature = "eval-constants"),
+        stack_allocate: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -115,15 +118,18 @@ fn create_llvm_module<'a>(
     for (home, (module_path, src)) in loaded.sources {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 
+        let parse_problems = loaded.parse_problems.remove(&home).unwrap_or_default();
         let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
         let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
 
-        let error_count = can_problems.len() + type_problems.len();
+        let error_count = parse_problems.len() + can_problems.len() + type_problems.len();
 
         if error_count == 0 {
             continue;
         }
 
+        lines.extend(parse_problems.into_iter().map(|problem| problem.report));
+
         let line_info = LineInfo::new(&src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
@@ -229,6 +235,7 @@ fn create_llvm_module<'a>(
         module,
         target_info,
         mode: config.mode,
+        instrument_rc: false,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
     };
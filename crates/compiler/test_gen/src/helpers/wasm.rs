@@ -3,7 +3,7 @@ use crate::helpers::from_wasm32_memory::FromWasm32Memory;
 use bumpalo::Bump;
 use roc_collections::all::MutSet;
 use roc_gen_wasm::wasm32_result::Wasm32Result;
-use roc_gen_wasm::DEBUG_SETTINGS;
+use roc_gen_wasm::{WasmFeatures, DEBUG_SETTINGS};
 use roc_load::{ExecutionMode, LoadConfig, Threading};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
@@ -52,12 +52,13 @@ pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
     src: &str,
     test_wrapper_type_info: PhantomData<T>,
+    features: WasmFeatures,
 ) -> Vec<u8> {
     let platform_bytes = include_bytes!(host_bytes_path!());
     println!("Loading test host {}", host_bytes_path!());
 
     let compiled_bytes =
-        compile_roc_to_wasm_bytes(arena, platform_bytes, src, test_wrapper_type_info);
+        compile_roc_to_wasm_bytes(arena, platform_bytes, src, test_wrapper_type_info, features);
 
     if write_final_wasm() {
         let build_dir_hash = crate::helpers::src_hash(src);
@@ -72,6 +73,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     host_bytes: &[u8],
     src: &str,
     _test_wrapper_type_info: PhantomData<T>,
+    features: WasmFeatures,
 ) -> Vec<u8> {
     let filename = PathBuf::from("Test.roc");
     let src_dir = PathBuf::from("fake/test/path");
@@ -94,6 +96,10 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
         function_kind: FunctionKind::LambdaSet,
+        // `roc build` inlines and folds for this backend too
+        inline_and_fold: true,
+        eval_constants: cfg!(feature = "eval-constants"),
+        stack_allocate: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -129,6 +135,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         module_id,
         exposed_to_host,
         stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        features,
     };
 
     let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap_or_else(|e| {
@@ -175,13 +182,17 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
 }
 
 #[allow(dead_code)]
-pub fn assert_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
+pub fn assert_evals_to_help<T>(
+    src: &str,
+    phantom: PhantomData<T>,
+    features: WasmFeatures,
+) -> Result<T, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom, features);
 
     run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
 }
@@ -264,7 +275,8 @@ where
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes =
+        crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom, Default::default());
 
     let require_relocatable = false;
     let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
@@ -377,7 +389,8 @@ macro_rules! assert_evals_to {
     ($src:expr, $expected:expr, $ty:ty, $transform:expr, $ignore_problems: expr) => {{
         let phantom = std::marker::PhantomData;
         let _ = $ignore_problems; // Always ignore "problems"! One backend (LLVM) is enough to cover them.
-        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom) {
+        let features = Default::default();
+        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom, features) {
             Err(msg) => panic!("{}", msg),
             Ok(actual) => {
                 assert_eq!($transform(actual), $expected)
@@ -26,4 +26,10 @@ pub mod wasm_str;
 #[cfg(feature = "gen-wasm")]
 pub mod wasm_linking;
 
+#[cfg(feature = "gen-wasm")]
+pub mod wasi_command;
+
+#[cfg(feature = "gen-wasm")]
+pub mod wasm_features;
+
 pub use helpers::platform_functions::*;
@@ -0,0 +1,206 @@
+#![cfg(feature = "gen-wasm")]
+
+use bumpalo::Bump;
+use indoc::indoc;
+use std::panic::{catch_unwind, panic_any, resume_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use roc_collections::MutSet;
+use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_packaging::cache::RocCacheDir;
+use roc_reporting::report::DEFAULT_PALETTE;
+use roc_solve::FunctionKind;
+use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher, WasiFile};
+use roc_wasm_module::{Value, WasmModule};
+
+const STDOUT: usize = 1;
+const STDERR: usize = 2;
+
+/// Build a WASI command module for an app with no platform, the same way `roc build --target=wasm32-wasi` does
+fn build_wasi_command(arena: &Bump, src: &str) -> Result<Vec<u8>, String> {
+    let load_config = LoadConfig {
+        target_info: roc_target::TargetInfo::default_wasm32(),
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+        function_kind: FunctionKind::LambdaSet,
+        inline_and_fold: true,
+        eval_constants: false,
+        stack_allocate: false,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .expect("failed to load module");
+
+    let roc_load::MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        exposed_to_host,
+        mut layout_interner,
+        ..
+    } = loaded;
+
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        exposed_to_host: exposed_to_host
+            .top_level_values
+            .keys()
+            .copied()
+            .collect::<MutSet<_>>(),
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        features: Default::default(),
+    };
+
+    let builtins_module = roc_gen_wasm::parse_host(arena, roc_bitcode::HOST_WASM).unwrap();
+
+    roc_gen_wasm::build_wasi_command_binary(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        builtins_module,
+        procedures,
+    )
+}
+
+/// Thrown from `proc_exit`, since the real WASI dispatcher would exit the test process
+struct ProcExit(i32);
+
+/// Captures stdout and stderr in memory
+struct CommandDispatcher<'a> {
+    wasi: WasiDispatcher<'a>,
+}
+
+impl ImportDispatcher for CommandDispatcher<'_> {
+    fn dispatch(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Option<Value> {
+        assert_eq!(
+            module_name,
+            wasi::MODULE_NAME,
+            "A WASI command should only import WASI functions, but it imports {module_name}.{function_name}"
+        );
+        if function_name == "proc_exit" {
+            panic_any(ProcExit(arguments[0].expect_i32().unwrap()));
+        }
+        self.wasi.dispatch(function_name, arguments, memory)
+    }
+}
+
+struct CommandOutput {
+    /// `None` if `_start` returned without calling `proc_exit`
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+}
+
+fn run_wasi_command(src: &str) -> CommandOutput {
+    let arena = Bump::new();
+    let bytes = build_wasi_command(&arena, src).unwrap();
+
+    let require_relocatable = false;
+    let module = WasmModule::preload(&arena, &bytes, require_relocatable).unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    wasi.files[STDOUT] = WasiFile::WriteOnly(vec![]);
+    wasi.files[STDERR] = WasiFile::WriteOnly(vec![]);
+    let dispatcher = CommandDispatcher { wasi };
+
+    let is_debug_mode = false;
+    let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap();
+
+    let exit_code = match catch_unwind(AssertUnwindSafe(|| inst.call_export("_start", []))) {
+        Ok(result) => {
+            assert_eq!(result, Ok(None), "`_start` should return nothing");
+            None
+        }
+        Err(payload) => match payload.downcast::<ProcExit>() {
+            Ok(proc_exit) => Some(proc_exit.0),
+            Err(payload) => resume_unwind(payload),
+        },
+    };
+
+    let output = |fd: usize| match &inst.import_dispatcher.wasi.files[fd] {
+        WasiFile::WriteOnly(bytes) => String::from_utf8(bytes.clone()).unwrap(),
+        _ => unreachable!(),
+    };
+
+    CommandOutput {
+        exit_code,
+        stdout: output(STDOUT),
+        stderr: output(STDERR),
+    }
+}
+
+#[test]
+fn start_writes_main_to_stdout() {
+    let output = run_wasi_command(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main : Str
+        main =
+            names = ["Roc", "WASI"]
+            "Hello, \(Str.joinWith names " and ")!"
+        "#
+    ));
+
+    assert_eq!(output.exit_code, None);
+    assert_eq!(output.stdout, "Hello, Roc and WASI!");
+    assert_eq!(output.stderr, "");
+}
+
+#[test]
+fn crash_exits_with_code_1() {
+    let output = run_wasi_command(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main : Str
+        main =
+            when List.first [] is
+                Ok str -> str
+                Err _ -> crash "the list was empty"
+        "#
+    ));
+
+    assert_eq!(output.exit_code, Some(1));
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.stderr, "Roc crashed: the list was empty\n");
+}
+
+#[test]
+fn main_must_be_a_str() {
+    let arena = Bump::new();
+    let result = build_wasi_command(
+        &arena,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : U64
+            main = 42
+            "#
+        ),
+    );
+
+    assert_eq!(
+        result,
+        Err(
+            "The wasm32-wasi target has no platform, so the app must provide `main : Str`"
+                .to_string()
+        )
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "gen-wasm")]
+
+use crate::helpers::from_wasm32_memory::FromWasm32Memory;
+use crate::helpers::wasm::assert_evals_to_help;
+use indoc::indoc;
+use roc_gen_wasm::wasm32_result::Wasm32Result;
+use roc_gen_wasm::WasmFeatures;
+use roc_std::RocStr;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+const ALL_FEATURES: WasmFeatures = WasmFeatures {
+    tail_calls: true,
+    multi_value: true,
+};
+
+/// Run the test with the post-MVP features on, then check we get the same answer without them
+fn assert_evals_to_with_features<T>(src: &str, expected: T)
+where
+    T: FromWasm32Memory + Wasm32Result + PartialEq + Debug,
+{
+    for features in [ALL_FEATURES, WasmFeatures::default()] {
+        match assert_evals_to_help::<T>(src, PhantomData, features) {
+            Err(msg) => panic!("{features:?}: {msg}"),
+            Ok(actual) => assert_eq!(actual, expected, "{features:?}"),
+        }
+    }
+}
+
+#[test]
+fn deep_self_recursion() {
+    assert_evals_to_with_features(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            sum : U64 -> U64
+            sum = \n ->
+                if n == 0 then
+                    0
+                else
+                    n + sum (n - 1)
+
+            main = sum 100_000
+            "#
+        ),
+        5_000_050_000u64,
+    );
+}
+
+#[test]
+fn deep_mutual_recursion_in_tail_position() {
+    assert_evals_to_with_features(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            isEven : U64 -> Bool
+            isEven = \n ->
+                if n == 0 then
+                    Bool.true
+                else
+                    isOdd (n - 1)
+
+            isOdd : U64 -> Bool
+            isOdd = \n ->
+                if n == 0 then
+                    Bool.false
+                else
+                    isEven (n - 1)
+
+            main = isEven 1_000_001
+            "#
+        ),
+        false,
+    );
+}
+
+#[test]
+fn multi_value_return_of_record() {
+    assert_evals_to_with_features(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            fibPair : U64 -> { a : U64, b : U64 }
+            fibPair = \n ->
+                if n == 0 then
+                    { a: 0, b: 1 }
+                else
+                    { a, b } = fibPair (n - 1)
+                    { a: b, b: a + b }
+
+            main = (fibPair 90).a
+            "#
+        ),
+        2_880_067_194_370_816_120u64,
+    );
+}
+
+#[test]
+fn multi_value_return_of_str() {
+    assert_evals_to_with_features(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            shout : Str, U64 -> Str
+            shout = \str, n ->
+                if n == 0 then
+                    str
+                else
+                    Str.concat (shout str (n - 1)) "!"
+
+            main = shout "Hello, World" 30
+            "#
+        ),
+        RocStr::from("Hello, World!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!"),
+    );
+}
@@ -77,9 +77,25 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: bool, no_check: bool) {
-    use roc_packaging::cache::RocCacheDir;
-    use std::path::PathBuf;
+/// The arguments of a `#[mono_test]`
+struct MonoTestOptions<'a> {
+    mode: &'a str,
+    allow_type_errors: bool,
+    no_check: bool,
+    inline: bool,
+    eval_constants: bool,
+    stack_allocate: bool,
+}
+
+fn compiles_to_ir(test_name: &str, src: &str, options: MonoTestOptions) {
+    let MonoTestOptions {
+        mode,
+        allow_type_errors,
+        no_check,
+        inline,
+        eval_constants,
+        stack_allocate,
+    } = options;
 
     let exec_mode = match mode {
         "exec" => ExecutionMode::Executable,
@@ -89,9 +105,6 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
 
     let arena = &Bump::new();
 
-    let filename = PathBuf::from("Test.roc");
-    let src_dir = PathBuf::from("fake/test/path");
-
     let module_src;
     let temp;
     if src.starts_with("app") || src.starts_with("interface") {
@@ -103,7 +116,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         module_src = &temp;
     }
 
-    let load_config = LoadConfig {
+    let load_config = |inline_and_fold| LoadConfig {
         target_info: TARGET_INFO,
         // TODO parameterize
         function_kind: FunctionKind::LambdaSet,
@@ -111,26 +124,12 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        inline_and_fold,
+        eval_constants,
+        stack_allocate,
     };
-    let loaded = roc_load::load_and_monomorphize_from_str(
-        arena,
-        filename,
-        module_src,
-        src_dir,
-        RocCacheDir::Disallowed,
-        load_config,
-    );
 
-    let mut loaded = match loaded {
-        Ok(x) => x,
-        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
-            report,
-        ))) => {
-            println!("{report}");
-            panic!();
-        }
-        Err(e) => panic!("{e:?}"),
-    };
+    let mut loaded = load_module(arena, module_src, load_config(inline));
 
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
@@ -157,11 +156,58 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
 
     if !no_check {
         check_procedures(arena, &interns, &mut layout_interner, &procedures);
+
+        if !inline {
+            // `roc build` inlines and folds for the non-LLVM backends, so every program that is
+            // checked here must also be valid IR after that pass.
+            let inlined = load_module(arena, module_src, load_config(true));
+
+            let MonomorphizedModule {
+                procedures,
+                mut layout_interner,
+                interns,
+                ..
+            } = inlined;
+
+            check_procedures(arena, &interns, &mut layout_interner, &procedures);
+        }
     }
 
     verify_procedures(test_name, layout_interner, procedures, main_fn_symbol);
 }
 
+fn load_module<'a>(
+    arena: &'a Bump,
+    module_src: &'a str,
+    load_config: LoadConfig,
+) -> roc_load::MonomorphizedModule<'a> {
+    use roc_packaging::cache::RocCacheDir;
+    use std::path::PathBuf;
+
+    let filename = PathBuf::from("Test.roc");
+    let src_dir = PathBuf::from("fake/test/path");
+
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        filename,
+        module_src,
+        src_dir,
+        RocCacheDir::Disallowed,
+        load_config,
+    );
+
+    match loaded {
+        Ok(x) => x,
+        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
+            report,
+        ))) => {
+            println!("{report}");
+            panic!();
+        }
+        Err(e) => panic!("{e:?}"),
+    }
+}
+
 fn check_procedures<'a>(
     arena: &'a Bump,
     interns: &Interns,
@@ -204,6 +250,11 @@ fn verify_procedures<'a>(
 
     let result = procs_string.join("\n");
 
+    verify_snapshot(test_name, &result);
+}
+
+/// Writes the output of a test to `generated/`, and fails if it differs from what is checked in
+fn verify_snapshot(test_name: &str, result: &str) {
     let path = format!("generated/{test_name}.txt");
     std::fs::create_dir_all("generated").unwrap();
     std::fs::write(&path, result).unwrap();
@@ -2808,7 +2859,8 @@ fn recursive_closure_with_transiently_used_capture() {
     )
 }
 
-#[mono_test]
+// The guards are trivially true, so the `_` branch is reported as redundant
+#[mono_test(allow_type_errors = "true")]
 fn when_guard_appears_multiple_times_in_compiled_decision_tree_issue_5176() {
     indoc!(
         r#"
@@ -2826,6 +2878,24 @@ fn when_guard_appears_multiple_times_in_compiled_decision_tree_issue_5176() {
     )
 }
 
+#[mono_test]
+fn when_non_trivial_guard_appears_multiple_times_in_compiled_decision_tree() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        go : U8 -> U8
+        go = \byte ->
+            when byte is
+                15 if byte > 10 -> 1
+                b if b > 10 -> b + 2
+                _ -> 3
+
+        main = go '.'
+        "#
+    )
+}
+
 #[mono_test]
 fn recursive_lambda_set_resolved_only_upon_specialization() {
     indoc!(
@@ -3500,3 +3570,129 @@ fn issue_6174() {
         "
     )
 }
+
+#[mono_test(inline = "true")]
+fn inline_and_fold_arithmetic() {
+    indoc!(
+        r"
+        double = \n -> n * 2
+        increment = \n -> n + 1
+
+        increment (double 20u64)
+        "
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_branching_callee() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        clamp = \n -> if n > 100u64 then 100 else n
+
+        main = \x -> clamp x + 1
+        "#
+    )
+}
+
+#[mono_test(inline = "true")]
+fn inline_drops_unused_binding() {
+    indoc!(
+        r"
+        f = \n ->
+            unused = Num.mulWrap n 3
+            n + 1
+
+        f 41u8
+        "
+    )
+}
+
+#[mono_test(eval_constants = "true")]
+fn eval_constant_lookup_table() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        table : List U64
+        table = List.concat [1, 2, 3] (List.range { start: At 4, end: At 8 })
+
+        main = \i -> List.get table i
+        "#
+    )
+}
+
+#[mono_test(eval_constants = "true")]
+fn eval_constant_record_of_tags() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        config = { name: Str.concat "lookup" "table", limit: 10u8 * 4, mode: if 1u8 > 2 then Fast else Safe }
+
+        main = config
+        "#
+    )
+}
+
+#[mono_test(stack_allocate = "true")]
+fn stack_allocate_is_nil() {
+    r"
+    ConsList a : [Cons a (ConsList a), Nil]
+
+    isNil : ConsList a -> Bool
+    isNil = \list ->
+        when list is
+            Nil -> Bool.true
+            Cons _ _ -> Bool.false
+
+    isNil (Cons 0x2 Nil)
+    "
+}
+
+#[test]
+fn specialization_report() {
+    let arena = &Bump::new();
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        wrap = \x -> [x, x]
+
+        pick = \f, x -> f x
+
+        main =
+            a = wrap 1u8
+            b = wrap "hi"
+            c = wrap { x: 1u64, y: 2u64 }
+            d = pick (\n -> n + 1u8) 41
+            e = pick (\s -> Str.concat s "!") "hey"
+
+            List.len a + List.len b + List.len c + Num.toU64 d + Num.toU64 (Str.countUtf8Bytes e)
+        "#
+    );
+
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        function_kind: FunctionKind::LambdaSet,
+        threading: Threading::Single,
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        exec_mode: ExecutionMode::Executable,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
+    };
+
+    let loaded = load_module(arena, src, load_config);
+
+    let report = roc_mono::debug::format_specialization_report(
+        &loaded.interns,
+        &loaded.layout_interner,
+        &loaded.procedures,
+        &loaded.closure_parents,
+    );
+
+    verify_snapshot("specialization_report", &report.to_string());
+}
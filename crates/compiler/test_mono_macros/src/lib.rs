@@ -10,6 +10,9 @@ pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
     let mut allow_type_errors = false;
     let mut mode = "exec".to_owned();
     let mut large_stack = false;
+    let mut inline = false;
+    let mut eval_constants = false;
+    let mut stack_allocate = false;
     for arg in syn::parse_macro_input!(args as syn::AttributeArgs) {
         use syn::{Lit, Meta, MetaNameValue, NestedMeta};
         if let NestedMeta::Meta(Meta::NameValue(MetaNameValue {
@@ -30,6 +33,15 @@ pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
             if path.is_ident("large_stack") {
                 large_stack = true;
             }
+            if path.is_ident("inline") {
+                inline = true;
+            }
+            if path.is_ident("eval_constants") {
+                eval_constants = true;
+            }
+            if path.is_ident("stack_allocate") {
+                stack_allocate = true;
+            }
         }
     }
 
@@ -44,14 +56,25 @@ pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
     let visibility = &task_fn.vis;
     let attributes = task_fn.attrs;
 
+    let options = quote! {
+        MonoTestOptions {
+            mode: #mode,
+            allow_type_errors: #allow_type_errors,
+            no_check: #no_check,
+            inline: #inline,
+            eval_constants: #eval_constants,
+            stack_allocate: #stack_allocate,
+        }
+    };
+
     let result = quote! {
         #[test]
         #(#attributes)*
         #visibility fn #name(#args) {
             if #large_stack {
-                with_larger_debug_stack(|| compiles_to_ir(#name_str, #body, &#mode, #allow_type_errors, #no_check));
+                with_larger_debug_stack(|| compiles_to_ir(#name_str, #body, #options));
             } else {
-                compiles_to_ir(#name_str, #body, &#mode, #allow_type_errors, #no_check);
+                compiles_to_ir(#name_str, #body, #options);
             }
         }
     };
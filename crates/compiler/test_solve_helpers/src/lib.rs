@@ -358,6 +358,7 @@ pub fn infer_queries<'a>(
     let (
         LoadedModule {
             module_id: home,
+            mut parse_problems,
             mut can_problems,
             mut type_problems,
             mut declarations_by_id,
@@ -369,6 +370,12 @@ pub fn infer_queries<'a>(
         src,
     ) = run_load_and_infer(src, dependencies, options.no_promote, function_kind)?;
 
+    if let Some(problems) = parse_problems.remove(&home) {
+        let reports: Vec<_> = problems.into_iter().map(|problem| problem.report).collect();
+
+        return Err(format!("Parse problems: {}", reports.join("\n")).into());
+    }
+
     let declarations = declarations_by_id.remove(&home).unwrap();
     let subs = solved.inner_mut();
 
@@ -3594,6 +3594,17 @@ pub enum Mismatch {
 
 pub type DoesNotImplementAbility = Vec<(ErrorType, Symbol)>;
 
+/// One step into a type, on the way from the whole type to the part of it that failed to unify.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum MismatchStep {
+    FunctionArg(HumanIndex),
+    FunctionReturn,
+    RecordField(Lowercase),
+    TupleElem(HumanIndex),
+    TagPayload(TagName, HumanIndex),
+    TypeArg(Symbol, HumanIndex),
+}
+
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub enum ErrorType {
     Infinite,
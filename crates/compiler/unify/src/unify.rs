@@ -1,3 +1,4 @@
+use roc_collections::all::HumanIndex;
 use roc_collections::VecMap;
 use roc_debug_flags::{dbg_do, dbg_set};
 #[cfg(debug_assertions)]
@@ -16,7 +17,8 @@ use roc_types::subs::{
     UnionLabels, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
 };
 use roc_types::types::{
-    AliasKind, DoesNotImplementAbility, ErrorType, Mismatch, Polarity, RecordField, Uls,
+    AliasKind, DoesNotImplementAbility, ErrorType, Mismatch, MismatchStep, Polarity, RecordField,
+    Uls,
 };
 
 use crate::env::Env;
@@ -178,7 +180,14 @@ pub enum Unified<M: MetaCollector = NoCollector> {
         /// polymorphic over metadata collection to avoid unnecessary memory usage.
         extra_metadata: M,
     },
-    Failure(Pool, ErrorType, ErrorType, DoesNotImplementAbility),
+    /// The last field is where in the two types unification first failed, outermost step first.
+    Failure(
+        Pool,
+        ErrorType,
+        ErrorType,
+        DoesNotImplementAbility,
+        Vec<MismatchStep>,
+    ),
 }
 
 impl<M: MetaCollector> Unified<M> {
@@ -252,6 +261,8 @@ impl MustImplementConstraints {
 #[derive(Debug, Default)]
 pub struct Outcome<M: MetaCollector> {
     mismatches: Vec<Mismatch>,
+    /// Where the first mismatch is in the types being unified, innermost step first.
+    mismatch_path: Vec<MismatchStep>,
     /// We defer these checks until the end of a solving phase.
     /// NOTE: this vector is almost always empty!
     must_implement_ability: MustImplementConstraints,
@@ -267,12 +278,16 @@ impl<M: MetaCollector> Outcome<M> {
     fn union(&mut self, other: Self) {
         let Self {
             mismatches,
+            mismatch_path,
             must_implement_ability,
             lambda_sets_to_specialize,
             has_changed,
             extra_metadata,
         } = other;
 
+        if self.mismatches.is_empty() {
+            self.mismatch_path = mismatch_path;
+        }
         self.mismatches.extend(mismatches);
         self.must_implement_ability.extend(must_implement_ability);
         self.lambda_sets_to_specialize
@@ -280,6 +295,16 @@ impl<M: MetaCollector> Outcome<M> {
         self.has_changed = self.has_changed || has_changed;
         self.extra_metadata.union(extra_metadata);
     }
+
+    /// Records that the mismatches of this outcome, if any, are under `step` of the types that
+    /// were being unified.
+    fn under(mut self, step: impl FnOnce() -> MismatchStep) -> Self {
+        if !self.mismatches.is_empty() {
+            self.mismatch_path.push(step());
+        }
+
+        self
+    }
 }
 
 /// Unifies two types.
@@ -340,6 +365,7 @@ fn unify_help<M: MetaCollector>(
     let mut vars = Vec::new();
     let Outcome {
         mismatches,
+        mut mismatch_path,
         must_implement_ability,
         lambda_sets_to_specialize,
         extra_metadata,
@@ -377,7 +403,9 @@ fn unify_help<M: MetaCollector>(
             })
             .collect();
 
-        Unified::Failure(vars, type1, type2, do_not_implement_ability)
+        mismatch_path.reverse();
+
+        Unified::Failure(vars, type1, type2, do_not_implement_ability, mismatch_path)
     }
 }
 
@@ -534,6 +562,7 @@ fn unify_context<M: MetaCollector>(env: &mut Env, pool: &mut Pool, ctx: Context)
 fn not_in_range_mismatch<M: MetaCollector>() -> Outcome<M> {
     Outcome {
         mismatches: vec![Mismatch::TypeNotInRange],
+        mismatch_path: Default::default(),
         must_implement_ability: Default::default(),
         lambda_sets_to_specialize: Default::default(),
         has_changed: false,
@@ -752,10 +781,13 @@ fn unify_two_aliases<M: MetaCollector>(
             args.all_variables_len as usize,
         );
 
-        for (l, r) in args_it {
+        for (index, (l, r)) in args_it.enumerate() {
             let l_var = env[l];
             let r_var = env[r];
-            outcome.union(unify_pool(env, pool, l_var, r_var, ctx.mode));
+            outcome.union(
+                unify_pool(env, pool, l_var, r_var, ctx.mode)
+                    .under(|| MismatchStep::TypeArg(symbol, HumanIndex::zero_based(index))),
+            );
 
             let merged_var = choose_merged_var(env, l_var, r_var);
             merged_args.push(merged_var);
@@ -2120,6 +2152,7 @@ fn unify_shared_fields<M: MetaCollector>(
     let num_shared_fields = shared_fields.len();
 
     let mut whole_outcome = Outcome::default();
+    let mut mismatch_path = None;
 
     for (name, (actual, expected)) in shared_fields {
         let local_outcome = unify_pool(
@@ -2130,7 +2163,13 @@ fn unify_shared_fields<M: MetaCollector>(
             ctx.mode,
         );
 
-        if local_outcome.mismatches.is_empty() {
+        if !local_outcome.mismatches.is_empty() {
+            mismatch_path.get_or_insert_with(|| {
+                local_outcome
+                    .under(|| MismatchStep::RecordField(name.clone()))
+                    .mismatch_path
+            });
+        } else {
             use RecordField::*;
 
             // Unification of optional fields
@@ -2234,7 +2273,9 @@ fn unify_shared_fields<M: MetaCollector>(
         whole_outcome.union(merge_outcome);
         whole_outcome
     } else {
-        mismatch!("in unify_shared_fields")
+        let mut outcome = mismatch!("in unify_shared_fields");
+        outcome.mismatch_path = mismatch_path.unwrap_or_default();
+        outcome
     }
 }
 
@@ -2258,11 +2299,18 @@ fn unify_shared_tuple_elems<M: MetaCollector>(
     let num_shared_elems = shared_elems.len();
 
     let mut whole_outcome = Outcome::default();
+    let mut mismatch_path = None;
 
     for (name, (actual, expected)) in shared_elems {
         let local_outcome = unify_pool(env, pool, actual, expected, ctx.mode);
 
-        if local_outcome.mismatches.is_empty() {
+        if !local_outcome.mismatches.is_empty() {
+            mismatch_path.get_or_insert_with(|| {
+                local_outcome
+                    .under(|| MismatchStep::TupleElem(HumanIndex::zero_based(name)))
+                    .mismatch_path
+            });
+        } else {
             let actual = choose_merged_var(env, actual, expected);
 
             matching_elems.push((name, actual));
@@ -2317,7 +2365,9 @@ fn unify_shared_tuple_elems<M: MetaCollector>(
         whole_outcome.union(merge_outcome);
         whole_outcome
     } else {
-        mismatch!("in unify_shared_tuple_elems")
+        let mut outcome = mismatch!("in unify_shared_tuple_elems");
+        outcome.mismatch_path = mismatch_path.unwrap_or_default();
+        outcome
     }
 }
 
@@ -3029,7 +3079,10 @@ fn unify_shared_tags<M: MetaCollector>(
         let actual_len = actual_vars.len();
         let expected_len = expected_vars.len();
 
-        for (actual_index, expected_index) in actual_vars.into_iter().zip(expected_vars.into_iter())
+        for (index, (actual_index, expected_index)) in actual_vars
+            .into_iter()
+            .zip(expected_vars.into_iter())
+            .enumerate()
         {
             let actual = env[actual_index];
             let expected = env[expected_index];
@@ -3074,7 +3127,11 @@ fn unify_shared_tags<M: MetaCollector>(
                 matching_vars.push(merged_var);
             }
 
-            total_outcome.union(outcome);
+            total_outcome.union(
+                outcome.under(|| {
+                    MismatchStep::TagPayload(name.clone(), HumanIndex::zero_based(index))
+                }),
+            );
         }
 
         // only do this check after unification so the error message has more info
@@ -3137,11 +3194,13 @@ fn unify_shared_tags<M: MetaCollector>(
         total_outcome.union(merge_outcome);
         total_outcome
     } else {
-        mismatch!(
+        let mut outcome = mismatch!(
             "Problem with Tag Union\nThere should be {:?} matching tags, but I only got \n{:?}",
             num_shared_tags,
             &matching_tags
-        )
+        );
+        outcome.mismatch_path = total_outcome.mismatch_path;
+        outcome
     }
 }
 
@@ -3240,7 +3299,9 @@ fn unify_flat_type<M: MetaCollector>(
         }
 
         (Apply(l_symbol, l_args), Apply(r_symbol, r_args)) if l_symbol == r_symbol => {
-            let mut outcome = unify_zip_slices(env, pool, *l_args, *r_args, ctx.mode);
+            let mut outcome = unify_zip_slices(env, pool, *l_args, *r_args, ctx.mode, |index| {
+                MismatchStep::TypeArg(*l_symbol, index)
+            });
 
             if outcome.mismatches.is_empty() {
                 let chosen_args = SubsSlice::reserve_into_subs(env, l_args.len());
@@ -3262,8 +3323,16 @@ fn unify_flat_type<M: MetaCollector>(
         (Func(l_args, l_closure, l_ret), Func(r_args, r_closure, r_ret))
             if l_args.len() == r_args.len() =>
         {
-            let arg_outcome = unify_zip_slices(env, pool, *l_args, *r_args, ctx.mode);
-            let ret_outcome = unify_pool(env, pool, *l_ret, *r_ret, ctx.mode);
+            let arg_outcome = unify_zip_slices(
+                env,
+                pool,
+                *l_args,
+                *r_args,
+                ctx.mode,
+                MismatchStep::FunctionArg,
+            );
+            let ret_outcome = unify_pool(env, pool, *l_ret, *r_ret, ctx.mode)
+                .under(|| MismatchStep::FunctionReturn);
             let closure_outcome = unify_pool(env, pool, *l_closure, *r_closure, ctx.mode);
 
             let mut outcome = ret_outcome;
@@ -3388,16 +3457,19 @@ fn unify_zip_slices<M: MetaCollector>(
     left: SubsSlice<Variable>,
     right: SubsSlice<Variable>,
     mode: UnificationMode,
+    step: impl Fn(HumanIndex) -> MismatchStep,
 ) -> Outcome<M> {
     let mut outcome = Outcome::default();
 
     let it = left.into_iter().zip(right);
 
-    for (l_index, r_index) in it {
+    for (index, (l_index, r_index)) in it.enumerate() {
         let l_var = env[l_index];
         let r_var = env[r_index];
 
-        outcome.union(unify_pool(env, pool, l_var, r_var, mode));
+        outcome.union(
+            unify_pool(env, pool, l_var, r_var, mode).under(|| step(HumanIndex::zero_based(index))),
+        );
     }
 
     outcome
@@ -441,6 +441,9 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        inline_and_fold: false,
+        eval_constants: false,
+        stack_allocate: false,
     };
     match roc_load::load_and_typecheck(
         &arena,
@@ -448,7 +451,14 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
         load_config,
     ) {
-        Ok(loaded) => loaded,
+        Ok(loaded) if loaded.parse_problems.is_empty() => loaded,
+        Ok(loaded) => {
+            // Docs for a module with syntax errors would be missing the broken defs
+            for problem in loaded.parse_problems.values().flatten() {
+                eprintln!("{}", problem.report);
+            }
+            std::process::exit(1);
+        }
         Err(LoadingProblem::FormattedReport(report)) => {
             eprintln!("{report}");
             std::process::exit(1);
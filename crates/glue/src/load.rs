@@ -58,6 +58,9 @@ pub fn generate(
                 emit_debug_info: false,
                 emit_llvm_ir: false,
                 fuzz: false,
+                instrument_rc: false,
+                specialization_report: false,
+                wasm_features: Default::default(),
             };
 
             let load_config = standard_load_config(
@@ -90,6 +93,8 @@ pub fn generate(
                     RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                     load_config,
                     Some(dylib_dir.path()),
+                    &Default::default(),
+                    false,
                 ),
                 Err(_) => {
                     eprintln!("`roc glue` was unable to create a tempdir.");
@@ -187,9 +192,15 @@ pub fn generate(
 
                     Ok(0)
                 }
-                Err(BuildFileError::ErrorModule { module, total_time }) => {
-                    handle_error_module(module, total_time, spec_path.as_os_str(), true)
-                }
+                Err(BuildFileError::ErrorModule { module, total_time }) => handle_error_module(
+                    module,
+                    total_time,
+                    spec_path.as_os_str(),
+                    true,
+                    RenderTarget::ColorTerminal,
+                    &Default::default(),
+                    false,
+                ),
                 Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
             };
 
@@ -261,7 +272,12 @@ fn call_roc_make_glue(
             files
         }
 
-        CodeGenBackend::Wasm => todo!(),
+        CodeGenBackend::Wasm | CodeGenBackend::WasiCommand => {
+            eprintln!(
+                "`roc glue` does not support the wasm backends, because it runs the glue spec as a shared library."
+            );
+            process::exit(1);
+        }
     };
 
     match Result::from(roc_call_result) {
@@ -405,6 +421,7 @@ pub fn load_types(
     let arena = &Bump::new();
     let LoadedModule {
         module_id: home,
+        mut parse_problems,
         mut can_problems,
         mut type_problems,
         mut declarations_by_id,
@@ -423,6 +440,9 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            inline_and_fold: false,
+            eval_constants: false,
+            stack_allocate: false,
         },
     )
     .unwrap_or_else(|problem| match problem {
@@ -439,6 +459,14 @@ pub fn load_types(
     let decls = declarations_by_id.remove(&home).unwrap();
     let subs = solved.inner_mut();
 
+    if let Some(reports) = parse_problems.remove(&home) {
+        for problem in reports {
+            eprintln!("{}", problem.report);
+        }
+
+        process::exit(1);
+    }
+
     let can_problems = can_problems.remove(&home).unwrap_or_default();
     let type_problems = type_problems.remove(&home).unwrap_or_default();
 
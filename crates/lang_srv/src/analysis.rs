@@ -7,7 +7,7 @@ use roc_load::{CheckedModule, LoadedModule};
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_region::all::LineInfo;
-use roc_reporting::report::RocDocAllocator;
+use roc_reporting::report::{RecoveredParseProblem, RocDocAllocator};
 use roc_solve_problem::TypeError;
 use roc_types::subs::Subs;
 
@@ -85,6 +85,7 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
 
     let LoadedModule {
         interns,
+        mut parse_problems,
         mut can_problems,
         mut type_problems,
         mut declarations_by_id,
@@ -103,6 +104,7 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
     let mut builder = AnalyzedDocumentBuilder {
         interns: &interns,
         module_id_to_url: module_id_to_url_from_sources(&sources),
+        parse_problems: &mut parse_problems,
         can_problems: &mut can_problems,
         type_problems: &mut type_problems,
         declarations_by_id: &mut declarations_by_id,
@@ -160,6 +162,7 @@ struct RootModule {
 struct AnalyzedDocumentBuilder<'a> {
     interns: &'a Interns,
     module_id_to_url: ModuleIdToUrl,
+    parse_problems: &'a mut MutMap<ModuleId, Vec<RecoveredParseProblem>>,
     can_problems: &'a mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &'a mut MutMap<ModuleId, Vec<TypeError>>,
     declarations_by_id: &'a mut MutMap<ModuleId, Declarations>,
@@ -234,10 +237,18 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             path: source_path,
         };
 
+        let parse_problems = self.parse_problems.remove(&module_id).unwrap_or_default();
+
         let can_problems = self.can_problems.remove(&module_id).unwrap_or_default();
 
         let type_problems = self.type_problems.remove(&module_id).unwrap_or_default();
 
+        for parse_problem in parse_problems {
+            if let Some(diag) = parse_problem.into_lsp_diagnostic(&fmt) {
+                all_problems.push(diag);
+            }
+        }
+
         for can_problem in can_problems {
             if let Some(diag) = can_problem.into_lsp_diagnostic(&fmt) {
                 all_problems.push(diag);
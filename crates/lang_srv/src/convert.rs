@@ -76,8 +76,8 @@ pub(crate) mod diag {
     use roc_solve_problem::TypeError;
 
     use roc_problem::Severity;
-    use roc_reporting::report::RocDocAllocator;
-    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+    use roc_reporting::report::{RecoveredParseProblem, RocDocAllocator};
+    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 
     use super::ToRange;
 
@@ -167,6 +167,26 @@ pub(crate) mod diag {
         }
     }
 
+    impl<'a> IntoLspDiagnostic<'a> for RecoveredParseProblem {
+        type Feed = ProblemFmt<'a>;
+
+        fn into_lsp_diagnostic(self, fmt: &'a ProblemFmt<'a>) -> Option<Diagnostic> {
+            let range = self.region.to_range(fmt.line_info);
+
+            Some(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(self.code.code.to_owned())),
+                code_description: None,
+                source: Some("parse".to_owned()),
+                message: self.report,
+                related_information: None,
+                tags: None,
+                data: None,
+            })
+        }
+    }
+
     pub struct ProblemFmt<'a> {
         pub alloc: &'a RocDocAllocator<'a>,
         pub line_info: &'a LineInfo,
@@ -108,6 +108,9 @@ pub fn generate_stub_lib(
             palette: DEFAULT_PALETTE,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Executable,
+            inline_and_fold: false,
+            eval_constants: false,
+            stack_allocate: false,
         },
     )
     .unwrap_or_else(|problem| todo!("{:?}", problem));
@@ -219,6 +219,7 @@ fn mono_module_to_dylib_llvm<'a>(
         module,
         target_info,
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
+        instrument_rc: false,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
     };
@@ -315,6 +316,7 @@ fn mono_module_to_dylib_asm<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Repl,
+        instrument_rc: false,
     };
 
     let target = target_lexicon::Triple::host();
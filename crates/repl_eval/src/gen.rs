@@ -68,6 +68,9 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             palette,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            inline_and_fold: false,
+            eval_constants: false,
+            stack_allocate: false,
         },
     );
 
@@ -76,7 +79,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         Err(LoadMonomorphizedError::ErrorModule(m)) => {
             todo!(
                 "error while loading module: {:?}",
-                (m.can_problems, m.type_problems)
+                (m.parse_problems, m.can_problems, m.type_problems)
             );
         }
         Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
@@ -96,6 +99,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     let MonomorphizedModule {
         interns,
         sources,
+        parse_problems,
         can_problems,
         type_problems,
         ..
@@ -107,15 +111,19 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     let warnings = &mut problems.warnings;
 
     for (home, (module_path, src)) in sources.iter() {
+        let parse_probs = parse_problems.remove(home).unwrap_or_default();
         let can_probs = can_problems.remove(home).unwrap_or_default();
         let type_probs = type_problems.remove(home).unwrap_or_default();
 
-        let error_count = can_probs.len() + type_probs.len();
+        let error_count = parse_probs.len() + can_probs.len() + type_probs.len();
 
         if error_count == 0 {
             continue;
         }
 
+        // These were rendered for the terminal when the module was loaded
+        errors.extend(parse_probs.into_iter().map(|problem| problem.report));
+
         let line_info = LineInfo::new(module_src);
         let src_lines: Vec<&str> = src.split('\n').collect();
 
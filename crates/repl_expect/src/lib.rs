@@ -128,6 +128,9 @@ mod test {
             palette: DEFAULT_PALETTE,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            inline_and_fold: false,
+            eval_constants: false,
+            stack_allocate: false,
         };
         let loaded = match roc_load::load_and_monomorphize_from_str(
             arena,
@@ -139,7 +142,7 @@ mod test {
         ) {
             Ok(m) => m,
             Err(LoadMonomorphizedError::ErrorModule(m)) => {
-                internal_error!("{:?}", (m.can_problems, m.type_problems))
+                internal_error!("{:?}", (m.parse_problems, m.can_problems, m.type_problems))
             }
             Err(e) => internal_error!("{e:?}"),
         };
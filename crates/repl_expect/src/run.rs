@@ -658,6 +658,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         module,
         target_info,
         mode,
+        instrument_rc: false,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
     };
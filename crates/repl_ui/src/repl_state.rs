@@ -131,6 +131,9 @@ impl ReplState {
                     ValueDef::ExpectFx { .. } => {
                         todo!("handle receiving an `expect-fx` - what should the repl do for that?")
                     }
+                    ValueDef::Malformed(_) => {
+                        unreachable!("the repl parses defs without recovering from errors")
+                    }
                 }
             }
             ParseOutcome::TypeDef(TypeDef::Alias {
@@ -3,9 +3,13 @@ use std::path::PathBuf;
 use roc_collections::MutMap;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_problem::can::Problem;
+use roc_problem::lint::{Lint, LintDirectives, LintLevel, LintLevels};
+use roc_problem::mono::UnoptimizedTailCall;
 use roc_region::all::LineInfo;
 use roc_solve_problem::TypeError;
 
+use crate::report::{RecoveredParseProblem, RenderTarget};
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
     pub fatally_errored: bool,
@@ -54,18 +58,121 @@ impl Problems {
     }
 }
 
+/// The rendered reports, split by whether they are errors or warnings
+#[derive(Debug, Default)]
+pub struct RenderedProblems {
+    pub fatally_errored: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn report_problems(
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
+    parse_problems: &mut MutMap<ModuleId, Vec<RecoveredParseProblem>>,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    unoptimized_tail_calls: &[UnoptimizedTailCall],
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
 ) -> Problems {
-    use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
+    use crate::report::{Report, DEFAULT_PALETTE};
+
+    let palette = DEFAULT_PALETTE;
+
+    let RenderedProblems {
+        fatally_errored,
+        errors,
+        warnings,
+    } = render_problems(
+        sources,
+        interns,
+        parse_problems,
+        can_problems,
+        type_problems,
+        unoptimized_tail_calls,
+        render,
+        lint_levels,
+        verbose_errors,
+    );
+
+    if render == RenderTarget::Json {
+        // Tools consuming JSON can filter by severity themselves, so we print everything.
+        // Each report is already a line of JSON, so there are no separators.
+        for report in errors.iter().chain(warnings.iter()) {
+            print!("{report}");
+        }
+
+        return Problems {
+            fatally_errored,
+            errors: errors.len(),
+            warnings: warnings.len(),
+        };
+    }
+
+    let problems_reported;
+
+    // Only print warnings if there are no errors
+    if errors.is_empty() {
+        problems_reported = warnings.len();
+
+        for warning in warnings.iter() {
+            println!("\n{warning}\n");
+        }
+    } else {
+        problems_reported = errors.len();
+
+        for error in errors.iter() {
+            println!("\n{error}\n");
+        }
+    }
+
+    // If we printed any problems, print a horizontal rule at the end,
+    // and then clear any ANSI escape codes (e.g. colors) we've used.
+    //
+    // The horizontal rule is nice when running the program right after
+    // compiling it, as it lets you clearly see where the compiler
+    // errors/warnings end and the program output begins.
+    if problems_reported > 0 {
+        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+    }
+
+    Problems {
+        fatally_errored,
+        errors: errors.len(),
+        warnings: warnings.len(),
+    }
+}
+
+/// Render the problems like [report_problems], without printing them
+#[allow(clippy::too_many_arguments)]
+pub fn render_problems(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    parse_problems: &mut MutMap<ModuleId, Vec<RecoveredParseProblem>>,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    unoptimized_tail_calls: &[UnoptimizedTailCall],
+    render: RenderTarget,
+    lint_levels: &LintLevels,
+    verbose_errors: bool,
+) -> RenderedProblems {
+    use crate::error::mono::unoptimized_tail_call;
+    use crate::error::r#type::missing_patterns;
+    use crate::report::{
+        can_problem, mismatch_trace, type_problem, RocDocAllocator, DEFAULT_PALETTE,
+    };
     use roc_problem::Severity::*;
 
     let palette = DEFAULT_PALETTE;
     let mut total_problems = 0;
 
+    for problems in parse_problems.values() {
+        total_problems += problems.len();
+    }
+
     for problems in can_problems.values() {
         total_problems += problems.len();
     }
@@ -74,11 +181,18 @@ pub fn report_problems(
         total_problems += problems.len();
     }
 
+    // The builtins have no sources, and we don't report their tail calls
+    total_problems += unoptimized_tail_calls
+        .iter()
+        .filter(|tail_call| sources.contains_key(&tail_call.caller.module_id()))
+        .count();
+
     // This will often over-allocate total memory, but it means we definitely
     // never need to re-allocate either the warnings or the errors vec!
     let mut warnings = Vec::with_capacity(total_problems);
     let mut errors = Vec::with_capacity(total_problems);
     let mut fatally_errored = false;
+    let mut allowed = 0;
 
     for (home, (module_path, src)) in sources.iter() {
         let mut src_lines: Vec<&str> = Vec::new();
@@ -89,15 +203,43 @@ pub fn report_problems(
 
         // Report parsing and canonicalization problems
         let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+        let directives = LintDirectives::parse(src);
+
+        // Parsing skipped the defs with syntax errors, so these come first; the other problems
+        // are about the rest of the module. They were rendered when the module was loaded.
+        errors.extend(
+            parse_problems
+                .remove(home)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|problem| problem.report),
+        );
 
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
-            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+            let trace = if verbose_errors {
+                mismatch_trace(&alloc, &lines, &problem)
+            } else {
+                None
+            };
+
+            let missing_patterns = missing_patterns(interns, &problem);
+
+            if let Some(mut report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                if let Some(trace) = trace {
+                    report.doc = alloc.stack([report.doc, trace]);
+                }
+
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                match render {
+                    RenderTarget::Json => {
+                        report.render_json_with_missing_patterns(&mut buf, missing_patterns)
+                    }
+                    _ => report.render(render, &mut buf, &alloc, &palette),
+                }
 
                 match severity {
                     Warning => {
@@ -131,11 +273,25 @@ pub fn report_problems(
         ordered.extend(shadowing_errs);
 
         for problem in ordered.into_iter() {
-            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+            let level = lint_levels.level(&problem, &directives, |region| {
+                lines.convert_pos(region.start()).line
+            });
+
+            let mut report = can_problem(&alloc, &lines, module_path.clone(), problem);
+
+            match level.map(LintLevel::severity) {
+                Some(None) => {
+                    allowed += 1;
+                    continue;
+                }
+                Some(Some(severity)) => report.severity = severity,
+                None => {}
+            }
+
             let severity = report.severity;
             let mut buf = String::new();
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            report.render(render, &mut buf, &alloc, &palette);
 
             match severity {
                 Warning => {
@@ -150,41 +306,51 @@ pub fn report_problems(
                 }
             }
         }
-    }
 
-    debug_assert!(can_problems.is_empty() && type_problems.is_empty(), "After reporting problems, there were {:?} can_problems and {:?} type_problems that could not be reported because they did not have corresponding entries in `sources`.", can_problems.len(), type_problems.len());
-    debug_assert_eq!(errors.len() + warnings.len(), total_problems);
+        let tail_call_level = lint_levels.module_level(Lint::UnoptimizedTailCall, &directives);
 
-    let problems_reported;
+        for tail_call in unoptimized_tail_calls {
+            if tail_call.caller.module_id() != *home {
+                continue;
+            }
 
-    // Only print warnings if there are no errors
-    if errors.is_empty() {
-        problems_reported = warnings.len();
+            let mut report = unoptimized_tail_call(&alloc, module_path.clone(), *tail_call);
 
-        for warning in warnings.iter() {
-            println!("\n{warning}\n");
-        }
-    } else {
-        problems_reported = errors.len();
+            match tail_call_level.map(LintLevel::severity) {
+                Some(None) => {
+                    allowed += 1;
+                    continue;
+                }
+                Some(Some(severity)) => report.severity = severity,
+                None => {}
+            }
 
-        for error in errors.iter() {
-            println!("\n{error}\n");
+            let severity = report.severity;
+            let mut buf = String::new();
+
+            report.render(render, &mut buf, &alloc, &palette);
+
+            match severity {
+                Warning => {
+                    warnings.push(buf);
+                }
+                RuntimeError => {
+                    errors.push(buf);
+                }
+                Fatal => {
+                    fatally_errored = true;
+                    errors.push(buf);
+                }
+            }
         }
     }
 
-    // If we printed any problems, print a horizontal rule at the end,
-    // and then clear any ANSI escape codes (e.g. colors) we've used.
-    //
-    // The horizontal rule is nice when running the program right after
-    // compiling it, as it lets you clearly see where the compiler
-    // errors/warnings end and the program output begins.
-    if problems_reported > 0 {
-        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
-    }
+    debug_assert!(parse_problems.is_empty() && can_problems.is_empty() && type_problems.is_empty(), "After reporting problems, there were {:?} parse_problems, {:?} can_problems and {:?} type_problems that could not be reported because they did not have corresponding entries in `sources`.", parse_problems.len(), can_problems.len(), type_problems.len());
+    debug_assert_eq!(errors.len() + warnings.len() + allowed, total_problems);
 
-    Problems {
+    RenderedProblems {
         fatally_errored,
-        errors: errors.len(),
-        warnings: warnings.len(),
+        errors,
+        warnings,
     }
 }
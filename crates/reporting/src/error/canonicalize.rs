@@ -11,6 +11,7 @@ use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::types::AliasKind;
 use std::path::PathBuf;
 
+use crate::error::codes::{self, ErrorCode};
 use crate::error::r#type::suggest;
 use crate::report::{to_file_problem_report, Annotation, Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::{text, DocAllocator};
@@ -66,6 +67,7 @@ pub fn can_problem<'b>(
     filename: PathBuf,
     problem: Problem,
 ) -> Report<'b> {
+    let code = codes::can_problem(&problem);
     let doc;
     let title;
     let severity = problem.severity();
@@ -417,6 +419,7 @@ pub fn can_problem<'b>(
                 alloc,
                 lines,
                 filename,
+                code,
                 field_name,
                 field_region,
                 record_region,
@@ -1098,6 +1101,7 @@ pub fn can_problem<'b>(
 
     Report {
         title,
+        code: Some(code),
         filename,
         doc,
         severity,
@@ -1117,6 +1121,7 @@ fn to_invalid_optional_value_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     field_name: Lowercase,
     field_region: Region,
     record_region: Region,
@@ -1131,6 +1136,7 @@ fn to_invalid_optional_value_report<'b>(
 
     Report {
         title: "BAD OPTIONAL VALUE".to_string(),
+        code: Some(code),
         filename,
         doc,
         severity: Severity::RuntimeError,
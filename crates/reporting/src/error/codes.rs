@@ -0,0 +1,956 @@
+//! Stable codes for each kind of problem, like `R0301` for a type mismatch.
+//!
+//! Report titles and wording change between compiler versions, but a code always refers to
+//! the same kind of problem, so it is safe to search for, link to, and use in tooling.
+//!
+//! Each variant of the problem enums (`roc_problem::can::Problem`, `RuntimeError`,
+//! `roc_solve_problem::TypeError` and the parse errors in `roc_parse::parser`) has its own
+//! code. A variant that wraps a more specific problem, like `EExpr::List`, has the code of
+//! that problem. The reports that are not about one of these enums, like a file that can't
+//! be read, have standalone codes.
+//!
+//! Codes are grouped by the phase of the compiler that reports them. Never reuse or renumber
+//! a code: new kinds of problems get the next free code in their group.
+
+use roc_exhaustive::Error as ExhaustiveError;
+use roc_parse::parser::{
+    BadInputError, EAbility, EClosure, EExpect, EExposes, EExpr, EGenerates, EGeneratesWith,
+    EHeader, EIf, EImports, EInParens, EList, ENumber, EPackageEntry, EPackageName, EPackages,
+    EPattern, EProvides, ERecord, ERequires, ESingleQuote, EString, EType, ETypeAbilityImpl,
+    ETypeApply, ETypeInParens, ETypeInlineAlias, ETypeRecord, ETypeTagUnion, ETypedIdent, EWhen,
+    PInParens, PList, PRecord, SyntaxError,
+};
+use roc_problem::can::{Problem, RuntimeError};
+use roc_solve_problem::TypeError;
+
+/// A stable code for one kind of problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    /// The code itself, like `R0301`
+    pub code: &'static str,
+    /// A short description of the problem, like `TYPE MISMATCH`
+    pub title: &'static str,
+    /// A longer explanation with examples, shown by `roc explain`
+    pub explanation: Option<&'static str>,
+}
+
+macro_rules! error_code {
+    ($code:literal, $title:literal) => {
+        ErrorCode {
+            code: $code,
+            title: $title,
+            explanation: None,
+        }
+    };
+    ($code:literal, $title:literal, explained) => {
+        ErrorCode {
+            code: $code,
+            title: $title,
+            explanation: Some(include_str!(concat!("explanations/", $code, ".md"))),
+        }
+    };
+}
+
+/// Defines a function that gives the code of each variant of a problem enum, and a table of
+/// these codes. Variants listed under `delegate` wrap a more specific problem, and have its code.
+macro_rules! variant_codes {
+    (
+        $(#[$attr:meta])*
+        pub fn $name:ident($enum:ident $(<$lt:lifetime>)?) -> $table:ident {
+            $( $variant:ident => $code:literal $title:literal $($explained:ident)?, )*
+        }
+        $( delegate {
+            $( $delegating:ident($($binding:pat),*) => $nested:ident($inner:ident), )*
+        } )?
+    ) => {
+        $(#[$attr])*
+        pub fn $name(problem: &$enum$(<$lt>)?) -> ErrorCode {
+            match problem {
+                $( $enum::$variant { .. } => error_code!($code, $title $(, $explained)?), )*
+                $($( $enum::$delegating($($binding),*) => self::$nested($inner), )*)?
+            }
+        }
+
+        pub const $table: &[ErrorCode] = &[$( error_code!($code, $title $(, $explained)?), )*];
+    };
+}
+
+/// Defines codes for reports that are not about a variant of a problem enum, and a table of
+/// these codes.
+macro_rules! standalone_codes {
+    (
+        $(#[$attr:meta])*
+        $table:ident {
+            $( $name:ident => $code:literal $title:literal $($explained:ident)?, )*
+        }
+    ) => {
+        $( pub const $name: ErrorCode = error_code!($code, $title $(, $explained)?); )*
+
+        $(#[$attr])*
+        pub const $table: &[ErrorCode] = &[$( $name, )*];
+    };
+}
+
+variant_codes! {
+    /// The code of a problem found while canonicalizing a module
+    pub fn can_problem(Problem) -> CAN_PROBLEM_CODES {
+        UnusedDef => "R0101" "UNUSED DEFINITION" explained,
+        UnusedImport => "R0102" "UNUSED IMPORT" explained,
+        UnusedModuleImport => "R0103" "UNUSED MODULE IMPORT",
+        ExposedButNotDefined => "R0104" "MISSING DEFINITION" explained,
+        UnknownGeneratesWith => "R0105" "UNKNOWN GENERATES FUNCTION",
+        UnusedArgument => "R0106" "UNUSED ARGUMENT" explained,
+        UnusedBranchDef => "R0107" "UNUSED BRANCH DEFINITION",
+        DefsOnlyUsedInRecursion => "R0108" "DEFINITIONS ONLY USED IN RECURSION",
+        PrecedenceProblem => "R0109" "MIXED NON-ASSOCIATIVE OPERATORS",
+        UnsupportedPattern => "R0110" "UNSUPPORTED PATTERN",
+        Shadowing => "R0111" "DUPLICATE NAME" explained,
+        CyclicAlias => "R0112" "CYCLIC ALIAS",
+        BadRecursion => "R0113" "CIRCULAR DEFINITION" explained,
+        PhantomTypeArgument => "R0114" "UNUSED TYPE ALIAS PARAMETER",
+        UnboundTypeVariable => "R0115" "UNBOUND TYPE VARIABLE" explained,
+        DuplicateRecordFieldValue => "R0116" "DUPLICATE RECORD FIELD",
+        DuplicateRecordFieldType => "R0117" "DUPLICATE RECORD TYPE FIELD",
+        InvalidOptionalValue => "R0118" "BAD OPTIONAL VALUE",
+        DuplicateTag => "R0119" "DUPLICATE TAG NAME",
+        SignatureDefMismatch => "R0120" "ANNOTATION AND DEFINITION NAMES DIFFER",
+        InvalidAliasRigid => "R0121" "INVALID ALIAS TYPE VARIABLE",
+        InvalidInterpolation => "R0122" "INVALID INTERPOLATION",
+        InvalidHexadecimal => "R0123" "INVALID HEXADECIMAL ESCAPE",
+        InvalidUnicodeCodePt => "R0124" "INVALID UNICODE CODE POINT",
+        NestedDatatype => "R0125" "NESTED DATATYPE",
+        InvalidExtensionType => "R0126" "INVALID EXTENSION TYPE",
+        AbilityHasTypeVariables => "R0127" "ABILITY HAS TYPE VARIABLES",
+        ImplementsClauseIsNotAbility => "R0128" "IMPLEMENTS CLAUSE IS NOT AN ABILITY",
+        IllegalImplementsClause => "R0129" "ILLEGAL IMPLEMENTS CLAUSE",
+        DuplicateImplementsAbility => "R0130" "DUPLICATE BOUND ABILITY",
+        AbilityMemberMissingImplementsClause => "R0131" "ABILITY MEMBER MISSING IMPLEMENTS CLAUSE",
+        AbilityMemberMultipleBoundVars => "R0132" "ABILITY MEMBER BINDS MULTIPLE VARIABLES",
+        AbilityNotOnToplevel => "R0133" "ABILITY NOT ON TOP-LEVEL",
+        AbilityUsedAsType => "R0134" "ABILITY USED AS TYPE",
+        NestedSpecialization => "R0135" "SPECIALIZATION NOT ON TOP-LEVEL",
+        IllegalDerivedAbility => "R0136" "ILLEGAL DERIVE" explained,
+        ImplementationNotFound => "R0137" "IMPLEMENTATION NOT FOUND" explained,
+        NotAnAbilityMember => "R0138" "NOT AN ABILITY MEMBER",
+        OptionalAbilityImpl => "R0139" "OPTIONAL ABILITY IMPLEMENTATION",
+        QualifiedAbilityImpl => "R0140" "QUALIFIED ABILITY IMPLEMENTATION",
+        AbilityImplNotIdent => "R0141" "ABILITY IMPLEMENTATION NOT IDENTIFIER",
+        DuplicateImpl => "R0142" "DUPLICATE IMPLEMENTATION",
+        NotAnAbility => "R0143" "NOT AN ABILITY",
+        ImplementsNonRequired => "R0144" "UNNECESSARY IMPLEMENTATIONS",
+        DoesNotImplementAbility => "R0145" "INCOMPLETE ABILITY IMPLEMENTATION" explained,
+        NotBoundInAllPatterns => "R0146" "NAME NOT BOUND IN ALL PATTERNS" explained,
+        NoIdentifiersIntroduced => "R0147" "UNNECESSARY DEFINITION" explained,
+        OverloadedSpecialization => "R0148" "OVERLOADED SPECIALIZATION",
+        UnnecessaryOutputWildcard => "R0149" "UNNECESSARY WILDCARD",
+        MultipleListRestPattern => "R0150" "MULTIPLE LIST REST PATTERNS",
+        BadTypeArguments => "R0151" "WRONG NUMBER OF TYPE ARGUMENTS",
+        UnappliedCrash => "R0152" "UNAPPLIED CRASH",
+        OverAppliedCrash => "R0153" "OVERAPPLIED CRASH",
+        FileProblem => "R0154" "FILE PROBLEM",
+    }
+    delegate {
+        RuntimeError(runtime_error) => runtime_error(runtime_error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem that makes an expression crash at runtime
+    pub fn runtime_error(RuntimeError) -> RUNTIME_ERROR_CODES {
+        Shadowing => "R0201" "DUPLICATE NAME AT RUNTIME",
+        InvalidOptionalValue => "R0202" "BAD OPTIONAL VALUE AT RUNTIME",
+        UnsupportedPattern => "R0203" "UNSUPPORTED PATTERN AT RUNTIME",
+        MalformedPattern => "R0204" "MALFORMED PATTERN",
+        UnresolvedTypeVar => "R0205" "UNRESOLVED TYPE VARIABLE",
+        ErroneousType => "R0206" "ERRONEOUS TYPE",
+        LookupNotInScope => "R0207" "UNRECOGNIZED NAME" explained,
+        OpaqueNotDefined => "R0208" "OPAQUE TYPE NOT DEFINED",
+        OpaqueOutsideScope => "R0209" "OPAQUE TYPE DECLARED OUTSIDE SCOPE",
+        OpaqueNotApplied => "R0210" "OPAQUE TYPE NOT APPLIED",
+        OpaqueAppliedToMultipleArgs => "R0211" "OPAQUE TYPE APPLIED TO TOO MANY ARGS",
+        ValueNotExposed => "R0212" "NOT EXPOSED" explained,
+        ModuleNotImported => "R0213" "MODULE NOT IMPORTED" explained,
+        InvalidPrecedence => "R0214" "INVALID PRECEDENCE",
+        MalformedIdentifier => "R0215" "MALFORMED IDENTIFIER",
+        MalformedTypeName => "R0216" "MALFORMED TYPE NAME",
+        MalformedClosure => "R0217" "MALFORMED FUNCTION",
+        InvalidRecordUpdate => "R0218" "BAD RECORD UPDATE",
+        InvalidFloat => "R0219" "INVALID FRACTION LITERAL",
+        InvalidInt => "R0220" "INVALID INTEGER LITERAL",
+        CircularDef => "R0221" "CIRCULAR DEFINITION AT RUNTIME",
+        NonExhaustivePattern => "R0222" "NON-EXHAUSTIVE PATTERN",
+        InvalidInterpolation => "R0223" "INVALID INTERPOLATION AT RUNTIME",
+        InvalidHexadecimal => "R0224" "INVALID HEXADECIMAL ESCAPE AT RUNTIME",
+        InvalidUnicodeCodePt => "R0225" "INVALID UNICODE CODE POINT AT RUNTIME",
+        NoImplementationNamed => "R0226" "NO IMPLEMENTATION NAMED",
+        NoImplementation => "R0227" "NO IMPLEMENTATION",
+        VoidValue => "R0228" "VOID VALUE",
+        ExposedButNotDefined => "R0229" "MISSING DEFINITION AT RUNTIME",
+        EmptySingleQuote => "R0230" "EMPTY CHARACTER LITERAL",
+        MultipleCharsInSingleQuote => "R0231" "MULTIPLE CHARACTERS IN CHARACTER LITERAL",
+        DegenerateBranch => "R0232" "DEGENERATE BRANCH",
+        MultipleRecordBuilders => "R0233" "MULTIPLE RECORD BUILDERS",
+        UnappliedRecordBuilder => "R0234" "UNAPPLIED RECORD BUILDER",
+    }
+}
+
+variant_codes! {
+    /// The code of a problem found while solving types
+    pub fn type_error(TypeError) -> TYPE_ERROR_CODES {
+        BadExpr => "R0301" "TYPE MISMATCH" explained,
+        BadPattern => "R0302" "PATTERN TYPE MISMATCH",
+        CircularType => "R0303" "CIRCULAR TYPE" explained,
+        CircularDef => "R0304" "CIRCULAR DEFINITION IN TYPES",
+        UnexposedLookup => "R0305" "UNEXPOSED LOOKUP",
+        UnfulfilledAbility => "R0306" "UNFULFILLED ABILITY",
+        BadExprMissingAbility => "R0307" "EXPRESSION MISSING ABILITY",
+        BadPatternMissingAbility => "R0308" "PATTERN MISSING ABILITY",
+        StructuralSpecialization => "R0309" "ILLEGAL SPECIALIZATION",
+        WrongSpecialization => "R0310" "WRONG SPECIALIZATION TYPE",
+        IngestedFileBadUtf8 => "R0311" "INVALID UTF-8",
+        IngestedFileUnsupportedType => "R0312" "INVALID TYPE FOR INGESTED FILE",
+        TypedHole => "R0313" "TYPED HOLE" explained,
+    }
+    delegate {
+        Exhaustive(error) => exhaustive_error(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem found while checking patterns for exhaustiveness
+    pub fn exhaustive_error(ExhaustiveError) -> EXHAUSTIVE_ERROR_CODES {
+        Incomplete => "R0351" "UNSAFE PATTERN" explained,
+        Redundant => "R0352" "REDUNDANT PATTERN" explained,
+        Unmatchable => "R0353" "UNMATCHABLE PATTERN",
+    }
+}
+
+variant_codes! {
+    /// The code of a syntax error
+    pub fn syntax_error(SyntaxError<'_>) -> SYNTAX_ERROR_CODES {
+        Unexpected => "R1001" "UNEXPECTED",
+        OutdentedTooFar => "R1002" "OUTDENTED TOO FAR",
+        Eof => "R1003" "EOF",
+        InvalidPattern => "R1004" "INVALID PATTERN",
+        BadUtf8 => "R1005" "BAD UTF8",
+        ReservedKeyword => "R1006" "RESERVED KEYWORD",
+        ArgumentsBeforeEquals => "R1007" "ARGUMENTS BEFORE EQUALS",
+        NotYetImplemented => "R1008" "NOT YET IMPLEMENTED",
+        Todo => "R1009" "TODO",
+        NotEndOfFile => "R1010" "NOT END OF FILE",
+    }
+    delegate {
+        Type(error) => annotation(error),
+        Pattern(error) => pattern(error),
+        Expr(error, _) => expr(error),
+        Header(error) => header(error),
+        Space(error) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a module header
+    pub fn header(EHeader<'_>) -> HEADER_CODES {
+        Start => "R1011" "START IN HEADER",
+        ModuleName => "R1012" "MODULE NAME IN HEADER",
+        AppName => "R1013" "APP NAME IN HEADER",
+        PackageName => "R1014" "PACKAGE NAME IN HEADER",
+        PlatformName => "R1015" "PLATFORM NAME IN HEADER",
+        IndentStart => "R1016" "INDENT START IN HEADER",
+        InconsistentModuleName => "R1017" "INCONSISTENT MODULE NAME IN HEADER",
+    }
+    delegate {
+        Provides(error, _) => provides(error),
+        Exposes(error, _) => exposes(error),
+        Imports(error, _) => imports(error),
+        Requires(error, _) => requires(error),
+        Packages(error, _) => packages(error),
+        Generates(error, _) => generates(error),
+        GeneratesWith(error, _) => generates_with(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `provides` of a header
+    pub fn provides(EProvides<'_>) -> PROVIDES_CODES {
+        Provides => "R1018" "PROVIDES IN PROVIDES",
+        Open => "R1019" "OPEN IN PROVIDES",
+        To => "R1020" "TO IN PROVIDES",
+        IndentProvides => "R1021" "INDENT PROVIDES IN PROVIDES",
+        IndentTo => "R1022" "INDENT TO IN PROVIDES",
+        IndentListStart => "R1023" "INDENT LIST START IN PROVIDES",
+        IndentPackage => "R1024" "INDENT PACKAGE IN PROVIDES",
+        ListStart => "R1025" "LIST START IN PROVIDES",
+        ListEnd => "R1026" "LIST END IN PROVIDES",
+        Identifier => "R1027" "IDENTIFIER IN PROVIDES",
+    }
+    delegate {
+        Package(error, _) => package_name(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `exposes` of a header
+    pub fn exposes(EExposes) -> EXPOSES_CODES {
+        Exposes => "R1028" "EXPOSES IN EXPOSES",
+        Open => "R1029" "OPEN IN EXPOSES",
+        IndentExposes => "R1030" "INDENT EXPOSES IN EXPOSES",
+        IndentListStart => "R1031" "INDENT LIST START IN EXPOSES",
+        ListStart => "R1032" "LIST START IN EXPOSES",
+        ListEnd => "R1033" "LIST END IN EXPOSES",
+        Identifier => "R1034" "IDENTIFIER IN EXPOSES",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `requires` of a header
+    pub fn requires(ERequires<'_>) -> REQUIRES_CODES {
+        Requires => "R1035" "REQUIRES IN REQUIRES",
+        Open => "R1036" "OPEN IN REQUIRES",
+        IndentRequires => "R1037" "INDENT REQUIRES IN REQUIRES",
+        IndentListStart => "R1038" "INDENT LIST START IN REQUIRES",
+        ListStart => "R1039" "LIST START IN REQUIRES",
+        ListEnd => "R1040" "LIST END IN REQUIRES",
+        Rigid => "R1041" "RIGID IN REQUIRES",
+    }
+    delegate {
+        TypedIdent(error, _) => typed_ident(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a typed name of a header
+    pub fn typed_ident(ETypedIdent<'_>) -> TYPED_IDENT_CODES {
+        HasType => "R1042" "HAS TYPE IN TYPED IDENTIFIER",
+        IndentHasType => "R1043" "INDENT HAS TYPE IN TYPED IDENTIFIER",
+        Name => "R1044" "NAME IN TYPED IDENTIFIER",
+        IndentType => "R1045" "INDENT TYPE IN TYPED IDENTIFIER",
+        Identifier => "R1046" "IDENTIFIER IN TYPED IDENTIFIER",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Type(error, _) => annotation(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `packages` of a header
+    pub fn packages(EPackages<'_>) -> PACKAGES_CODES {
+        Open => "R1047" "OPEN IN PACKAGES",
+        Packages => "R1048" "PACKAGES IN PACKAGES",
+        IndentPackages => "R1049" "INDENT PACKAGES IN PACKAGES",
+        ListStart => "R1050" "LIST START IN PACKAGES",
+        ListEnd => "R1051" "LIST END IN PACKAGES",
+        IndentListStart => "R1052" "INDENT LIST START IN PACKAGES",
+        IndentListEnd => "R1053" "INDENT LIST END IN PACKAGES",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        PackageEntry(error, _) => package_entry(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a package name
+    pub fn package_name(EPackageName<'_>) -> PACKAGE_NAME_CODES {
+        Escapes => "R1054" "ESCAPES IN PACKAGE NAME",
+        Multiline => "R1055" "MULTILINE IN PACKAGE NAME",
+    }
+    delegate {
+        BadPath(error, _) => string(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an entry of `packages`
+    pub fn package_entry(EPackageEntry<'_>) -> PACKAGE_ENTRY_CODES {
+        Shorthand => "R1056" "SHORTHAND IN PACKAGE ENTRY",
+        Colon => "R1057" "COLON IN PACKAGE ENTRY",
+        IndentPackage => "R1058" "INDENT PACKAGE IN PACKAGE ENTRY",
+    }
+    delegate {
+        BadPackage(error, _) => package_name(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `imports` of a header
+    pub fn imports(EImports) -> IMPORTS_CODES {
+        Open => "R1059" "OPEN IN IMPORTS",
+        Imports => "R1060" "IMPORTS IN IMPORTS",
+        IndentImports => "R1061" "INDENT IMPORTS IN IMPORTS",
+        IndentListStart => "R1062" "INDENT LIST START IN IMPORTS",
+        IndentListEnd => "R1063" "INDENT LIST END IN IMPORTS",
+        ListStart => "R1064" "LIST START IN IMPORTS",
+        ListEnd => "R1065" "LIST END IN IMPORTS",
+        Identifier => "R1066" "IDENTIFIER IN IMPORTS",
+        ExposingDot => "R1067" "EXPOSING DOT IN IMPORTS",
+        ShorthandDot => "R1068" "SHORTHAND DOT IN IMPORTS",
+        Shorthand => "R1069" "SHORTHAND IN IMPORTS",
+        ModuleName => "R1070" "MODULE NAME IN IMPORTS",
+        IndentSetStart => "R1071" "INDENT SET START IN IMPORTS",
+        SetStart => "R1072" "SET START IN IMPORTS",
+        SetEnd => "R1073" "SET END IN IMPORTS",
+        TypedIdent => "R1074" "TYPED IDENT IN IMPORTS",
+        AsKeyword => "R1075" "AS KEYWORD IN IMPORTS",
+        StrLiteral => "R1076" "STR LITERAL IN IMPORTS",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `generates` of a header
+    pub fn generates(EGenerates) -> GENERATES_CODES {
+        Open => "R1077" "OPEN IN GENERATES",
+        Generates => "R1078" "GENERATES IN GENERATES",
+        IndentGenerates => "R1079" "INDENT GENERATES IN GENERATES",
+        Identifier => "R1080" "IDENTIFIER IN GENERATES",
+        IndentTypeStart => "R1081" "INDENT TYPE START IN GENERATES",
+        IndentTypeEnd => "R1082" "INDENT TYPE END IN GENERATES",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the `with` of `generates`
+    pub fn generates_with(EGeneratesWith) -> GENERATES_WITH_CODES {
+        Open => "R1083" "OPEN IN GENERATES WITH",
+        With => "R1084" "WITH IN GENERATES WITH",
+        IndentWith => "R1085" "INDENT WITH IN GENERATES WITH",
+        IndentListStart => "R1086" "INDENT LIST START IN GENERATES WITH",
+        IndentListEnd => "R1087" "INDENT LIST END IN GENERATES WITH",
+        ListStart => "R1088" "LIST START IN GENERATES WITH",
+        ListEnd => "R1089" "LIST END IN GENERATES WITH",
+        Identifier => "R1090" "IDENTIFIER IN GENERATES WITH",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem with whitespace or comments
+    pub fn bad_input(BadInputError) -> BAD_INPUT_CODES {
+        HasTab => "R1091" "TAB CHARACTER" explained,
+        HasMisplacedCarriageReturn => "R1092" "HAS MISPLACED CARRIAGE RETURN",
+        HasAsciiControl => "R1093" "HAS ASCII CONTROL",
+        BadUtf8 => "R1094" "BAD UTF8",
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an expression
+    pub fn expr(EExpr<'_>) -> EXPR_CODES {
+        TrailingOperator => "R1095" "TRAILING OPERATOR IN EXPRESSION",
+        Start => "R1096" "START IN EXPRESSION",
+        End => "R1097" "END IN EXPRESSION",
+        BadExprEnd => "R1098" "BAD EXPR END IN EXPRESSION",
+        Dot => "R1099" "DOT IN EXPRESSION",
+        Access => "R1100" "ACCESS IN EXPRESSION",
+        UnaryNot => "R1101" "UNARY NOT IN EXPRESSION",
+        UnaryNegate => "R1102" "UNARY NEGATE IN EXPRESSION",
+        BadOperator => "R1103" "BAD OPERATOR IN EXPRESSION",
+        DefMissingFinalExpr => "R1104" "MISSING FINAL EXPRESSION" explained,
+        IndentDefBody => "R1105" "INDENT DEF BODY IN EXPRESSION",
+        IndentEquals => "R1106" "INDENT EQUALS IN EXPRESSION",
+        IndentAnnotation => "R1107" "INDENT ANNOTATION IN EXPRESSION",
+        Equals => "R1108" "EQUALS IN EXPRESSION",
+        Colon => "R1109" "COLON IN EXPRESSION",
+        DoubleColon => "R1110" "DOUBLE COLON IN EXPRESSION",
+        Ident => "R1111" "IDENT IN EXPRESSION",
+        ElmStyleFunction => "R1112" "ELM STYLE FUNCTION IN EXPRESSION",
+        MalformedPattern => "R1113" "MALFORMED PATTERN IN EXPRESSION",
+        QualifiedTag => "R1114" "QUALIFIED TAG IN EXPRESSION",
+        BackpassComma => "R1115" "BACKPASS COMMA IN EXPRESSION",
+        BackpassArrow => "R1116" "BACKPASS ARROW IN EXPRESSION",
+        Underscore => "R1117" "UNDERSCORE IN EXPRESSION",
+        Crash => "R1118" "CRASH IN EXPRESSION",
+        OptionalValueInRecordBuilder => "R1119" "OPTIONAL VALUE IN RECORD BUILDER IN EXPRESSION",
+        RecordUpdateBuilder => "R1120" "RECORD UPDATE BUILDER IN EXPRESSION",
+        IndentStart => "R1121" "INDENT START IN EXPRESSION",
+        IndentEnd => "R1122" "INDENT END IN EXPRESSION",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        DefMissingFinalExpr2(error, _) => expr(error),
+        Type(error, _) => annotation(error),
+        Pattern(error, _) => pattern(error),
+        Ability(error, _) => ability(error),
+        When(error, _) => when(error),
+        If(error, _) => if_then_else(error),
+        Expect(error, _) => expect(error),
+        Dbg(error, _) => expect(error),
+        Closure(error, _) => closure(error),
+        InParens(error, _) => in_parens(error),
+        Record(error, _) => record(error),
+        Str(error, _) => string(error),
+        Number(error, _) => number(error),
+        List(error, _) => list(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a number literal
+    pub fn number(ENumber) -> NUMBER_CODES {
+        End => "R1123" "END IN NUMBER",
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a string
+    pub fn string(EString<'_>) -> STRING_CODES {
+        Open => "R1124" "OPEN IN STRING",
+        CodePtOpen => "R1125" "CODE PT OPEN IN STRING",
+        CodePtEnd => "R1126" "CODE PT END IN STRING",
+        EndlessSingleLine => "R1127" "ENDLESS SINGLE LINE IN STRING",
+        EndlessMultiLine => "R1128" "ENDLESS MULTI LINE IN STRING",
+        EndlessSingleQuote => "R1129" "ENDLESS SINGLE QUOTE IN STRING",
+        UnknownEscape => "R1130" "UNKNOWN ESCAPE IN STRING",
+        FormatEnd => "R1131" "FORMAT END IN STRING",
+        MultilineInsufficientIndent => "R1132" "MULTILINE INSUFFICIENT INDENT IN STRING",
+        ExpectedDoubleQuoteGotSingleQuote => "R1133" "EXPECTED DOUBLE QUOTE GOT SINGLE QUOTE IN STRING",
+    }
+    delegate {
+        InvalidSingleQuote(error, _) => single_quote(error),
+        Space(error, _) => bad_input(error),
+        Format(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a character literal
+    pub fn single_quote(ESingleQuote) -> SINGLE_QUOTE_CODES {
+        Empty => "R1134" "EMPTY IN CHARACTER LITERAL",
+        TooLong => "R1135" "TOO LONG IN CHARACTER LITERAL",
+        InterpolationNotAllowed => "R1136" "INTERPOLATION NOT ALLOWED IN CHARACTER LITERAL",
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a record
+    pub fn record(ERecord<'_>) -> RECORD_CODES {
+        End => "R1137" "END IN RECORD",
+        Open => "R1138" "OPEN IN RECORD",
+        Updateable => "R1139" "UPDATEABLE IN RECORD",
+        Field => "R1140" "FIELD IN RECORD",
+        Colon => "R1141" "COLON IN RECORD",
+        QuestionMark => "R1142" "QUESTION MARK IN RECORD",
+        Arrow => "R1143" "ARROW IN RECORD",
+        Ampersand => "R1144" "AMPERSAND IN RECORD",
+    }
+    delegate {
+        Expr(error, _) => expr(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in parentheses
+    pub fn in_parens(EInParens<'_>) -> IN_PARENS_CODES {
+        End => "R1145" "END IN PARENTHESES",
+        Open => "R1146" "OPEN IN PARENTHESES",
+        Empty => "R1147" "EMPTY IN PARENTHESES",
+    }
+    delegate {
+        Expr(error, _) => expr(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an anonymous function
+    pub fn closure(EClosure<'_>) -> CLOSURE_CODES {
+        Start => "R1148" "START IN FUNCTION",
+        Arrow => "R1149" "ARROW IN FUNCTION",
+        Comma => "R1150" "COMMA IN FUNCTION",
+        Arg => "R1151" "ARG IN FUNCTION",
+        IndentArrow => "R1152" "INDENT ARROW IN FUNCTION",
+        IndentBody => "R1153" "INDENT BODY IN FUNCTION",
+        IndentArg => "R1154" "INDENT ARG IN FUNCTION",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Pattern(error, _) => pattern(error),
+        Body(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a list
+    pub fn list(EList<'_>) -> LIST_CODES {
+        Open => "R1155" "OPEN IN LIST",
+        End => "R1156" "UNFINISHED LIST" explained,
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Expr(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a `when`
+    pub fn when(EWhen<'_>) -> WHEN_CODES {
+        When => "R1157" "WHEN IN WHEN",
+        Is => "R1158" "IS IN WHEN",
+        Arrow => "R1159" "ARROW IN WHEN",
+        Bar => "R1160" "BAR IN WHEN",
+        IfToken => "R1161" "IF TOKEN IN WHEN",
+        IndentCondition => "R1162" "INDENT CONDITION IN WHEN",
+        IndentPattern => "R1163" "INDENT PATTERN IN WHEN",
+        IndentArrow => "R1164" "INDENT ARROW IN WHEN",
+        IndentBranch => "R1165" "INDENT BRANCH IN WHEN",
+        IndentIfGuard => "R1166" "INDENT IF GUARD IN WHEN",
+        PatternAlignment => "R1167" "PATTERN ALIGNMENT IN WHEN",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Pattern(error, _) => pattern(error),
+        IfGuard(error, _) => expr(error),
+        Condition(error, _) => expr(error),
+        Branch(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an ability definition
+    pub fn ability(EAbility<'_>) -> ABILITY_CODES {
+        DemandAlignment => "R1168" "DEMAND ALIGNMENT IN ABILITY",
+        DemandName => "R1169" "DEMAND NAME IN ABILITY",
+        DemandColon => "R1170" "DEMAND COLON IN ABILITY",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Type(error, _) => annotation(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an `if`
+    pub fn if_then_else(EIf<'_>) -> IF_THEN_ELSE_CODES {
+        If => "R1171" "IF IN IF",
+        Then => "R1172" "THEN IN IF",
+        Else => "R1173" "ELSE IN IF",
+        IndentCondition => "R1174" "INDENT CONDITION IN IF",
+        IndentIf => "R1175" "INDENT IF IN IF",
+        IndentThenToken => "R1176" "INDENT THEN TOKEN IN IF",
+        IndentElseToken => "R1177" "INDENT ELSE TOKEN IN IF",
+        IndentThenBranch => "R1178" "INDENT THEN BRANCH IN IF",
+        IndentElseBranch => "R1179" "INDENT ELSE BRANCH IN IF",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Condition(error, _) => expr(error),
+        ThenBranch(error, _) => expr(error),
+        ElseBranch(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an `expect` or `dbg`
+    pub fn expect(EExpect<'_>) -> EXPECT_CODES {
+        Dbg => "R1180" "DBG IN EXPECT OR DBG",
+        Expect => "R1181" "EXPECT IN EXPECT OR DBG",
+        IndentCondition => "R1182" "INDENT CONDITION IN EXPECT OR DBG",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        Condition(error, _) => expr(error),
+        Continuation(error, _) => expr(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a pattern
+    pub fn pattern(EPattern<'_>) -> PATTERN_CODES {
+        AsKeyword => "R1183" "AS KEYWORD IN PATTERN",
+        AsIdentifier => "R1184" "AS IDENTIFIER IN PATTERN",
+        Underscore => "R1185" "UNDERSCORE IN PATTERN",
+        NotAPattern => "R1186" "NOT APATTERN IN PATTERN",
+        Start => "R1187" "START IN PATTERN",
+        End => "R1188" "END IN PATTERN",
+        IndentStart => "R1189" "INDENT START IN PATTERN",
+        IndentEnd => "R1190" "INDENT END IN PATTERN",
+        AsIndentStart => "R1191" "AS INDENT START IN PATTERN",
+        AccessorFunction => "R1192" "ACCESSOR FUNCTION IN PATTERN",
+    }
+    delegate {
+        Record(error, _) => record_pattern(error),
+        List(error, _) => list_pattern(error),
+        Space(error, _) => bad_input(error),
+        PInParens(error, _) => pattern_in_parens(error),
+        NumLiteral(error, _) => number(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a record pattern
+    pub fn record_pattern(PRecord<'_>) -> RECORD_PATTERN_CODES {
+        End => "R1193" "END IN RECORD PATTERN",
+        Open => "R1194" "OPEN IN RECORD PATTERN",
+        Field => "R1195" "FIELD IN RECORD PATTERN",
+        Colon => "R1196" "COLON IN RECORD PATTERN",
+        Optional => "R1197" "OPTIONAL IN RECORD PATTERN",
+    }
+    delegate {
+        Pattern(error, _) => pattern(error),
+        Expr(error, _) => expr(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a list pattern
+    pub fn list_pattern(PList<'_>) -> LIST_PATTERN_CODES {
+        End => "R1198" "END IN LIST PATTERN",
+        Open => "R1199" "OPEN IN LIST PATTERN",
+        Rest => "R1200" "REST IN LIST PATTERN",
+    }
+    delegate {
+        Pattern(error, _) => pattern(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a parenthesized pattern
+    pub fn pattern_in_parens(PInParens<'_>) -> PATTERN_IN_PARENS_CODES {
+        Empty => "R1201" "EMPTY IN PARENTHESIZED PATTERN",
+        End => "R1202" "END IN PARENTHESIZED PATTERN",
+        Open => "R1203" "OPEN IN PARENTHESIZED PATTERN",
+    }
+    delegate {
+        Pattern(error, _) => pattern(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a type annotation
+    pub fn annotation(EType<'_>) -> ANNOTATION_CODES {
+        UnderscoreSpacing => "R1204" "UNDERSCORE SPACING IN TYPE",
+        TBadTypeVariable => "R1205" "TBAD TYPE VARIABLE IN TYPE",
+        TWildcard => "R1206" "TWILDCARD IN TYPE",
+        TInferred => "R1207" "TINFERRED IN TYPE",
+        TStart => "R1208" "TSTART IN TYPE",
+        TEnd => "R1209" "TEND IN TYPE",
+        TFunctionArgument => "R1210" "TFUNCTION ARGUMENT IN TYPE",
+        TWhereBar => "R1211" "TWHERE BAR IN TYPE",
+        TImplementsClause => "R1212" "TIMPLEMENTS CLAUSE IN TYPE",
+        TIndentStart => "R1213" "TINDENT START IN TYPE",
+        TIndentEnd => "R1214" "TINDENT END IN TYPE",
+        TAsIndentStart => "R1215" "TAS INDENT START IN TYPE",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+        TRecord(error, _) => record_type(error),
+        TTagUnion(error, _) => tag_union_type(error),
+        TInParens(error, _) => type_in_parens(error),
+        TApply(error, _) => type_apply(error),
+        TInlineAlias(error, _) => inline_alias(error),
+        TAbilityImpl(error, _) => ability_impl(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a record type
+    pub fn record_type(ETypeRecord<'_>) -> RECORD_TYPE_CODES {
+        End => "R1216" "END IN RECORD TYPE",
+        Open => "R1217" "OPEN IN RECORD TYPE",
+        Field => "R1218" "FIELD IN RECORD TYPE",
+        Colon => "R1219" "COLON IN RECORD TYPE",
+        Optional => "R1220" "OPTIONAL IN RECORD TYPE",
+        IndentOpen => "R1221" "INDENT OPEN IN RECORD TYPE",
+        IndentColon => "R1222" "INDENT COLON IN RECORD TYPE",
+        IndentOptional => "R1223" "INDENT OPTIONAL IN RECORD TYPE",
+        IndentEnd => "R1224" "RECORD TYPE END NOT INDENTED" explained,
+    }
+    delegate {
+        Type(error, _) => annotation(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a tag union type
+    pub fn tag_union_type(ETypeTagUnion<'_>) -> TAG_UNION_TYPE_CODES {
+        End => "R1225" "END IN TAG UNION TYPE",
+        Open => "R1226" "OPEN IN TAG UNION TYPE",
+    }
+    delegate {
+        Type(error, _) => annotation(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a parenthesized type
+    pub fn type_in_parens(ETypeInParens<'_>) -> TYPE_IN_PARENS_CODES {
+        Empty => "R1227" "EMPTY IN PARENTHESIZED TYPE",
+        End => "R1228" "END IN PARENTHESIZED TYPE",
+        Open => "R1229" "OPEN IN PARENTHESIZED TYPE",
+        IndentOpen => "R1230" "INDENT OPEN IN PARENTHESIZED TYPE",
+        IndentEnd => "R1231" "INDENT END IN PARENTHESIZED TYPE",
+    }
+    delegate {
+        Type(error, _) => annotation(error),
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in a type application
+    pub fn type_apply(ETypeApply) -> TYPE_APPLY_CODES {
+        StartNotUppercase => "R1232" "START NOT UPPERCASE IN TYPE APPLICATION",
+        End => "R1233" "END IN TYPE APPLICATION",
+        DoubleDot => "R1234" "DOUBLE DOT IN TYPE APPLICATION",
+        TrailingDot => "R1235" "TRAILING DOT IN TYPE APPLICATION",
+        StartIsNumber => "R1236" "START IS NUMBER IN TYPE APPLICATION",
+    }
+    delegate {
+        Space(error, _) => bad_input(error),
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in an inline type alias
+    pub fn inline_alias(ETypeInlineAlias) -> INLINE_ALIAS_CODES {
+        NotAnAlias => "R1237" "NOT AN ALIAS IN INLINE ALIAS",
+        Qualified => "R1238" "QUALIFIED IN INLINE ALIAS",
+        ArgumentNotLowercase => "R1239" "ARGUMENT NOT LOWERCASE IN INLINE ALIAS",
+    }
+}
+
+variant_codes! {
+    /// The code of a problem in the abilities implemented by a type
+    pub fn ability_impl(ETypeAbilityImpl<'_>) -> ABILITY_IMPL_CODES {
+        End => "R1240" "END IN ABILITY IMPLEMENTATION",
+        Open => "R1241" "OPEN IN ABILITY IMPLEMENTATION",
+        Field => "R1242" "FIELD IN ABILITY IMPLEMENTATION",
+        Colon => "R1243" "COLON IN ABILITY IMPLEMENTATION",
+        Arrow => "R1244" "ARROW IN ABILITY IMPLEMENTATION",
+        Optional => "R1245" "OPTIONAL IN ABILITY IMPLEMENTATION",
+        Updateable => "R1246" "UPDATEABLE IN ABILITY IMPLEMENTATION",
+        QuestionMark => "R1247" "QUESTION MARK IN ABILITY IMPLEMENTATION",
+        Ampersand => "R1248" "AMPERSAND IN ABILITY IMPLEMENTATION",
+        IndentBar => "R1249" "INDENT BAR IN ABILITY IMPLEMENTATION",
+        IndentAmpersand => "R1250" "INDENT AMPERSAND IN ABILITY IMPLEMENTATION",
+    }
+    delegate {
+        Type(error, _) => annotation(error),
+        Space(error, _) => bad_input(error),
+        Expr(error, _) => expr(error),
+    }
+}
+
+standalone_codes! {
+    /// Problems with files, modules and packages
+    FILE_CODES {
+        FILE_NOT_FOUND => "R0401" "FILE NOT FOUND" explained,
+        FILE_PERMISSION_DENIED => "R0402" "FILE PERMISSION DENIED",
+        FILE_PROBLEM => "R0403" "FILE PROBLEM",
+        FILE_TOO_LARGE => "R0404" "FILE TOO LARGE",
+        HTTP_ERROR => "R0405" "HTTP ERROR",
+        HTTPS_MANDATORY => "R0406" "HTTPS MANDATORY",
+        IMPORT_CYCLE => "R0407" "IMPORT CYCLE" explained,
+        INCORRECT_MODULE_NAME => "R0408" "INCORRECT MODULE NAME",
+        INVALID_CONTENT_HASH => "R0409" "INVALID CONTENT HASH",
+        INVALID_EXTENSION => "R0410" "INVALID EXTENSION",
+        INVALID_EXTENSION_SUFFIX => "R0411" "INVALID EXTENSION SUFFIX",
+        INVALID_FRAGMENT => "R0412" "INVALID FRAGMENT",
+        IO_ERROR => "R0413" "IO ERROR",
+        MISLEADING_CHARACTERS => "R0414" "MISLEADING CHARACTERS",
+        MISSING_PACKAGE_HASH => "R0415" "MISSING PACKAGE HASH",
+        MULTIPLE_ENCODINGS => "R0416" "MULTIPLE ENCODINGS",
+        NO_PLATFORM => "R0417" "NO PLATFORM",
+        NOT_A_ROC_FILE => "R0418" "NOT A ROC FILE",
+        UNSUPPORTED_ENCODING => "R0419" "UNSUPPORTED ENCODING",
+        FILE_SYSTEM_ERROR => "R0420" "FILE SYSTEM ERROR",
+        PACKAGE_NOT_FOUND => "R0421" "PACKAGE NOT FOUND",
+        ROOT_IS_INTERFACE => "R0422" "INTERFACE MODULE RUN AS APP",
+        ROOT_IS_HOSTED => "R0423" "HOSTED MODULE RUN AS APP",
+        ROOT_IS_PLATFORM => "R0424" "PLATFORM MODULE RUN AS APP",
+    }
+}
+
+standalone_codes! {
+    /// Problems found while running expectations
+    EXPECTATION_CODES {
+        EXPECT_FAILED => "R0501" "EXPECT FAILED" explained,
+        EXPECT_PANICKED => "R0502" "EXPECT PANICKED",
+    }
+}
+
+standalone_codes! {
+    /// Problems found while specializing and optimizing the program
+    MONO_CODES {
+        UNOPTIMIZED_TAIL_CALL => "R0601" "UNOPTIMIZED TAIL CALL",
+    }
+}
+
+/// Every table of codes
+pub const ERROR_CODES: &[&[ErrorCode]] = &[
+    CAN_PROBLEM_CODES,
+    RUNTIME_ERROR_CODES,
+    TYPE_ERROR_CODES,
+    EXHAUSTIVE_ERROR_CODES,
+    SYNTAX_ERROR_CODES,
+    HEADER_CODES,
+    PROVIDES_CODES,
+    EXPOSES_CODES,
+    REQUIRES_CODES,
+    TYPED_IDENT_CODES,
+    PACKAGES_CODES,
+    PACKAGE_NAME_CODES,
+    PACKAGE_ENTRY_CODES,
+    IMPORTS_CODES,
+    GENERATES_CODES,
+    GENERATES_WITH_CODES,
+    BAD_INPUT_CODES,
+    EXPR_CODES,
+    NUMBER_CODES,
+    STRING_CODES,
+    SINGLE_QUOTE_CODES,
+    RECORD_CODES,
+    IN_PARENS_CODES,
+    CLOSURE_CODES,
+    LIST_CODES,
+    WHEN_CODES,
+    ABILITY_CODES,
+    IF_THEN_ELSE_CODES,
+    EXPECT_CODES,
+    PATTERN_CODES,
+    RECORD_PATTERN_CODES,
+    LIST_PATTERN_CODES,
+    PATTERN_IN_PARENS_CODES,
+    ANNOTATION_CODES,
+    RECORD_TYPE_CODES,
+    TAG_UNION_TYPE_CODES,
+    TYPE_IN_PARENS_CODES,
+    TYPE_APPLY_CODES,
+    INLINE_ALIAS_CODES,
+    ABILITY_IMPL_CODES,
+    FILE_CODES,
+    EXPECTATION_CODES,
+    MONO_CODES,
+];
+
+/// Look up a code like `R0301`. Case doesn't matter, and the leading zeros can be left out.
+pub fn lookup(code: &str) -> Option<&'static ErrorCode> {
+    let number: u32 = code.strip_prefix(|c| c == 'R' || c == 'r')?.parse().ok()?;
+
+    ERROR_CODES
+        .iter()
+        .flat_map(|codes| codes.iter())
+        .find(|error_code| error_code.code[1..].parse() == Ok(number))
+}
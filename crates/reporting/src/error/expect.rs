@@ -10,6 +10,7 @@ use roc_types::{
     types::{ErrorType, Polarity},
 };
 
+use crate::error::codes;
 use crate::report::{RenderTarget, RocDocAllocator, RocDocBuilder};
 
 pub struct Renderer<'a> {
@@ -153,6 +154,7 @@ impl<'a> Renderer<'a> {
 
         let report = Report {
             title: "EXPECT FAILED".into(),
+            code: Some(codes::EXPECT_FAILED),
             doc,
             filename: self.filename.clone(),
             severity: Severity::RuntimeError,
@@ -224,6 +226,7 @@ impl<'a> Renderer<'a> {
 
         let report = Report {
             title: "EXPECT PANICKED".into(),
+            code: Some(codes::EXPECT_PANICKED),
             doc,
             filename: self.filename.clone(),
             severity: Severity::RuntimeError,
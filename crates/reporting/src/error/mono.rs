@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use roc_problem::mono::UnoptimizedTailCall;
+use roc_problem::Severity;
+use ven_pretty::DocAllocator;
+
+use crate::error::codes;
+use crate::report::{Report, RocDocAllocator};
+
+pub fn unoptimized_tail_call<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    filename: PathBuf,
+    tail_call: UnoptimizedTailCall,
+) -> Report<'b> {
+    let UnoptimizedTailCall { caller, callee } = tail_call;
+
+    let doc = alloc.stack([
+        alloc.concat([
+            alloc.reflow("The call from "),
+            alloc.symbol_unqualified(caller),
+            alloc.reflow(" to "),
+            alloc.symbol_foreign_qualified(callee),
+            alloc.reflow(
+                " is in tail position, but I could not turn it into a jump. Every time it is made, the stack grows, so deep recursion through it can overflow the stack.",
+            ),
+        ]),
+        alloc.reflow(
+            "This can happen when one of the functions that call each other in tail position also builds up a data structure with recursive calls.",
+        ),
+        alloc.tip().append(alloc.reflow(
+            "If the recursion never gets deep, you can silence this warning by adding this comment to the module:",
+        )),
+        alloc
+            .parser_suggestion("# lint-module: allow unoptimized-tail-call")
+            .indent(4),
+    ]);
+
+    Report {
+        title: codes::UNOPTIMIZED_TAIL_CALL.title.to_string(),
+        code: Some(codes::UNOPTIMIZED_TAIL_CALL),
+        filename,
+        doc,
+        severity: Severity::Warning,
+    }
+}
@@ -3,6 +3,7 @@ use roc_problem::Severity;
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
 use std::path::PathBuf;
 
+use crate::error::codes::{self, ErrorCode};
 use crate::report::{Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::DocAllocator;
 
@@ -60,10 +61,13 @@ fn to_syntax_report<'a>(
 ) -> Report<'a> {
     use SyntaxError::*;
 
+    let code = codes::syntax_error(parse_problem);
+
     let report = |doc| Report {
         filename: filename.clone(),
         doc,
         title: "PARSE PROBLEM".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     };
 
@@ -78,6 +82,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -110,6 +115,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "NOT END OF FILE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -123,6 +129,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -133,6 +140,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -183,6 +191,8 @@ fn to_expr_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EExpr;
 
+    let code = codes::expr(parse_problem);
+
     match parse_problem {
         EExpr::If(if_, pos) => to_if_report(alloc, lines, filename, context, if_, *pos),
         EExpr::When(when, pos) => to_when_report(alloc, lines, filename, context, when, *pos),
@@ -214,6 +224,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -252,7 +263,9 @@ fn to_expr_report<'a>(
                 ])],
                 "->" => match context {
                     Context::InNode(Node::WhenBranch, _pos, _) => {
-                        return to_unexpected_arrow_report(alloc, lines, filename, *pos, start);
+                        return to_unexpected_arrow_report(
+                            alloc, lines, filename, code, *pos, start,
+                        );
                     }
 
                     Context::InDef(_pos) => {
@@ -328,6 +341,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "UNKNOWN OPERATOR".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -354,6 +368,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IDENTIFIER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -436,6 +451,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: title.to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -464,6 +480,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "MISSING FINAL EXPRESSION".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -495,6 +512,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "SYNTAX PROBLEM".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -518,6 +536,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -536,6 +555,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "BAD BACKPASSING ARROW".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -554,6 +574,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "RECORD PARSE PROBLEM".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -574,6 +595,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "BAD RECORD BUILDER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -594,6 +616,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "BAD RECORD UPDATE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -601,7 +624,7 @@ fn to_expr_report<'a>(
         EExpr::Space(error, pos) => to_space_report(alloc, lines, filename, error, *pos),
 
         &EExpr::Number(ENumber::End, pos) => {
-            to_malformed_number_literal_report(alloc, lines, filename, pos)
+            to_malformed_number_literal_report(alloc, lines, filename, code, pos)
         }
 
         EExpr::Ability(err, pos) => to_ability_def_report(alloc, lines, filename, err, *pos),
@@ -660,6 +683,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "INDENT ENDS AFTER EXPRESSION".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -684,6 +708,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "TRAILING OPERATOR".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -701,6 +726,8 @@ fn to_lambda_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EClosure;
 
+    let code = codes::closure(parse_problem);
+
     match *parse_problem {
         EClosure::Arrow(pos) => match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
             Next::Token("=>") => {
@@ -722,6 +749,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD ARROW".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -744,6 +772,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -769,6 +798,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD ARROW".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -791,6 +821,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -815,6 +846,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED ARGUMENT LIST".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -836,6 +868,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -855,6 +888,7 @@ fn to_lambda_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -868,6 +902,7 @@ fn to_lambda_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -881,6 +916,7 @@ fn to_lambda_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -897,6 +933,7 @@ fn to_unfinished_lambda_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     pos: Position,
     start: Position,
     message: RocDocBuilder<'a>,
@@ -917,6 +954,7 @@ fn to_unfinished_lambda_report<'a>(
         filename,
         doc,
         title: "UNFINISHED FUNCTION".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     }
 }
@@ -931,6 +969,8 @@ fn to_str_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EString;
 
+    let code = codes::string(parse_problem);
+
     match *parse_problem {
         EString::Open(_pos) => unreachable!("another branch would be taken"),
         EString::Format(expr, pos) => to_expr_report(
@@ -983,6 +1023,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD ESCAPE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1009,6 +1050,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD CODE POINT".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1030,6 +1072,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS FORMAT".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1053,6 +1096,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS SCALAR".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1119,6 +1163,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "INVALID SCALAR".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1142,6 +1187,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS STRING".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1166,6 +1212,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "EXPECTED STRING".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1189,6 +1236,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS STRING".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1210,6 +1258,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "INSUFFICIENT INDENT IN MULTI-LINE STRING".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1225,6 +1274,8 @@ fn to_expr_in_parens_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EInParens;
 
+    let code = codes::in_parens(parse_problem);
+
     match *parse_problem {
         EInParens::Space(error, pos) => to_space_report(alloc, lines, filename, &error, pos),
         EInParens::Expr(expr, pos) => to_expr_report(
@@ -1252,6 +1303,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1276,6 +1328,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1301,6 +1354,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1317,6 +1371,8 @@ fn to_list_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EList;
 
+    let code = codes::list(parse_problem);
+
     match *parse_problem {
         EList::Space(error, pos) => to_space_report(alloc, lines, filename, &error, pos),
 
@@ -1351,6 +1407,7 @@ fn to_list_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED LIST".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -1384,6 +1441,7 @@ fn to_list_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED LIST".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -1432,6 +1490,8 @@ fn to_if_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EIf;
 
+    let code = codes::if_then_else(parse_problem);
+
     match *parse_problem {
         EIf::Space(error, pos) => to_space_report(alloc, lines, filename, &error, pos),
 
@@ -1470,6 +1530,7 @@ fn to_if_report<'a>(
                 alloc,
                 lines,
                 filename,
+                code,
                 pos,
                 start,
                 alloc.concat([
@@ -1485,6 +1546,7 @@ fn to_if_report<'a>(
                 alloc,
                 lines,
                 filename,
+                code,
                 pos,
                 start,
                 alloc.concat([
@@ -1499,6 +1561,7 @@ fn to_if_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([alloc.reflow(r"I was expecting to see a expression next")]),
@@ -1510,6 +1573,7 @@ fn to_unfinished_if_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     pos: Position,
     start: Position,
     message: RocDocBuilder<'a>,
@@ -1531,6 +1595,7 @@ fn to_unfinished_if_report<'a>(
         filename,
         doc,
         title: "UNFINISHED IF".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     }
 }
@@ -1545,6 +1610,8 @@ fn to_when_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EWhen;
 
+    let code = codes::when(parse_problem);
+
     match *parse_problem {
         EWhen::IfGuard(nested, pos) => {
             match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
@@ -1564,6 +1631,7 @@ fn to_when_report<'a>(
                         filename,
                         doc,
                         title: "IF GUARD NO CONDITION".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -1596,6 +1664,7 @@ fn to_when_report<'a>(
                 filename,
                 doc,
                 title: "MISSING ARROW".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1624,6 +1693,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1640,6 +1710,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1653,6 +1724,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([alloc.reflow(r"I was expecting to see a expression next")]),
@@ -1662,6 +1734,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([alloc.reflow(r"I was expecting to see a pattern next")]),
@@ -1671,6 +1744,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1684,6 +1758,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1697,6 +1772,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1709,6 +1785,7 @@ fn to_when_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             pos,
             start,
             alloc.concat([
@@ -1725,12 +1802,13 @@ fn to_unfinished_when_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     pos: Position,
     start: Position,
     message: RocDocBuilder<'a>,
 ) -> Report<'a> {
     match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
-        Next::Token("->") => to_unexpected_arrow_report(alloc, lines, filename, pos, start),
+        Next::Token("->") => to_unexpected_arrow_report(alloc, lines, filename, code, pos, start),
 
         _ => {
             let surroundings = Region::new(start, pos);
@@ -1751,6 +1829,7 @@ fn to_unfinished_when_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED WHEN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1761,6 +1840,7 @@ fn to_unexpected_arrow_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     pos: Position,
     start: Position,
 ) -> Report<'a> {
@@ -1791,6 +1871,7 @@ fn to_unexpected_arrow_report<'a>(
         filename,
         doc,
         title: "UNEXPECTED ARROW".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     }
 }
@@ -1852,6 +1933,8 @@ fn to_pattern_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EPattern;
 
+    let code = codes::pattern(parse_problem);
+
     match parse_problem {
         EPattern::Start(pos) => {
             let surroundings = Region::new(start, *pos);
@@ -1867,6 +1950,7 @@ fn to_pattern_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PATTERN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -1876,7 +1960,7 @@ fn to_pattern_report<'a>(
             to_pattern_in_parens_report(alloc, lines, filename, inparens, *pos)
         }
         &EPattern::NumLiteral(ENumber::End, pos) => {
-            to_malformed_number_literal_report(alloc, lines, filename, pos)
+            to_malformed_number_literal_report(alloc, lines, filename, code, pos)
         }
         _ => todo!("unhandled parse error: {:?}", parse_problem),
     }
@@ -1891,6 +1975,8 @@ fn to_precord_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::PRecord;
 
+    let code = codes::record_pattern(parse_problem);
+
     match *parse_problem {
         PRecord::Open(pos) => match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
             Next::Keyword(keyword) => {
@@ -1911,6 +1997,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -1928,6 +2015,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -1953,6 +2041,7 @@ fn to_precord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD PATTERN".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -1973,6 +2062,7 @@ fn to_precord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD PATTERN".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -1998,6 +2088,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2023,6 +2114,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "PROBLEM IN RECORD PATTERN".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2061,6 +2153,7 @@ fn to_plist_report<'a>(
     parse_problem: &PList<'a>,
     start: Position,
 ) -> Report<'a> {
+    let code = codes::list_pattern(parse_problem);
     match *parse_problem {
         PList::Open(pos) => {
             let surroundings = Region::new(start, pos);
@@ -2076,6 +2169,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED LIST PATTERN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2098,6 +2192,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED LIST PATTERN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2120,6 +2215,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "INCORRECT REST PATTERN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2139,6 +2235,8 @@ fn to_pattern_in_parens_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::PInParens;
 
+    let code = codes::pattern_in_parens(parse_problem);
+
     match *parse_problem {
         PInParens::Open(pos) => {
             // `Open` case is for exhaustiveness, this case shouldn not be reachable practically.
@@ -2163,6 +2261,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2184,6 +2283,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2208,6 +2308,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2222,6 +2323,7 @@ fn to_malformed_number_literal_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     start: Position,
 ) -> Report<'a> {
     let surroundings = Region::new(start, start);
@@ -2236,6 +2338,7 @@ fn to_malformed_number_literal_report<'a>(
         filename,
         doc,
         title: "INVALID NUMBER LITERAL".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     }
 }
@@ -2249,6 +2352,8 @@ fn to_type_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EType;
 
+    let code = codes::annotation(parse_problem);
+
     match parse_problem {
         EType::TRecord(record, pos) => to_trecord_report(alloc, lines, filename, record, *pos),
         EType::TTagUnion(tag_union, pos) => {
@@ -2276,6 +2381,7 @@ fn to_type_report<'a>(
                         filename,
                         doc,
                         title: "DOUBLE COMMA".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2303,6 +2409,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2321,6 +2428,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2339,6 +2447,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2357,6 +2466,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED INLINE ALIAS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2374,6 +2484,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "BAD TYPE VARIABLE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2391,6 +2502,8 @@ fn to_trecord_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ETypeRecord;
 
+    let code = codes::record_type(parse_problem);
+
     match *parse_problem {
         ETypeRecord::Open(pos) => match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
             Next::Keyword(keyword) => {
@@ -2411,6 +2524,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2432,6 +2546,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2457,6 +2572,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2477,6 +2593,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2502,6 +2619,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2527,6 +2645,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "PROBLEM IN RECORD TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2560,6 +2679,7 @@ fn to_trecord_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED RECORD TYPE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2584,6 +2704,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "NEED MORE INDENTATION".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2609,6 +2730,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2636,6 +2758,8 @@ fn to_ttag_union_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ETypeTagUnion;
 
+    let code = codes::tag_union_type(parse_problem);
+
     match *parse_problem {
         ETypeTagUnion::Open(pos) => match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
             Next::Keyword(keyword) => {
@@ -2656,6 +2780,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2678,6 +2803,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD TAG NAME".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2699,6 +2825,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    code: Some(code),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -2725,6 +2852,7 @@ fn to_ttag_union_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2745,6 +2873,7 @@ fn to_ttag_union_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED TAG UNION TYPE".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2766,6 +2895,8 @@ fn to_tinparens_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ETypeInParens;
 
+    let code = codes::type_in_parens(parse_problem);
+
     match *parse_problem {
         ETypeInParens::Open(pos) => {
             match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
@@ -2788,6 +2919,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2810,6 +2942,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2833,6 +2966,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2856,6 +2990,7 @@ fn to_tinparens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2882,6 +3017,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2902,6 +3038,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2930,6 +3067,7 @@ fn to_tinparens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -2954,6 +3092,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "NEED MORE INDENTATION".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2979,6 +3118,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(code),
                         severity: Severity::RuntimeError,
                     }
                 }
@@ -2998,6 +3138,8 @@ fn to_tapply_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ETypeApply;
 
+    let code = codes::type_apply(parse_problem);
+
     match *parse_problem {
         ETypeApply::DoubleDot(pos) => {
             let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
@@ -3012,6 +3154,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "DOUBLE DOT".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3034,6 +3177,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "TRAILING DOT".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3056,6 +3200,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD QUALIFIED NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3078,6 +3223,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD QUALIFIED NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3096,6 +3242,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "END OF FILE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3112,6 +3259,8 @@ fn to_talias_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ETypeInlineAlias;
 
+    let code = codes::inline_alias(parse_problem);
+
     match *parse_problem {
         ETypeInlineAlias::NotAnAlias(pos) => {
             let region = Region::from_pos(pos);
@@ -3136,6 +3285,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "NOT AN INLINE ALIAS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3152,6 +3302,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "QUALIFIED ALIAS NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3168,6 +3319,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "TYPE ARGUMENT NOT LOWERCASE".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3183,6 +3335,8 @@ fn to_header_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EHeader;
 
+    let code = codes::header(parse_problem);
+
     match parse_problem {
         EHeader::Provides(provides, pos) => {
             to_provides_report(alloc, lines, filename, provides, *pos)
@@ -3214,6 +3368,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INCOMPLETE HEADER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3252,6 +3407,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "MISSING HEADER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3276,6 +3432,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3303,6 +3460,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3327,6 +3485,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD APP NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3349,6 +3508,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INVALID PACKAGE NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3372,6 +3532,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INVALID PLATFORM NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3395,6 +3556,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD GENERATED TYPE NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3413,6 +3575,8 @@ fn to_generates_with_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EGeneratesWith;
 
+    let code = codes::generates_with(parse_problem);
+
     match *parse_problem {
         EGeneratesWith::ListEnd(pos) | // TODO: give this its own error message
         EGeneratesWith::Identifier(pos) => {
@@ -3435,6 +3599,7 @@ fn to_generates_with_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD GENERATES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3460,6 +3625,7 @@ fn to_generates_with_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD GENERATES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3479,6 +3645,8 @@ fn to_provides_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EProvides;
 
+    let code = codes::provides(parse_problem);
+
     match *parse_problem {
         EProvides::ListEnd(pos) | // TODO: give this its own error message
         EProvides::Identifier(pos) => {
@@ -3501,6 +3669,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3526,6 +3695,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3553,6 +3723,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3574,6 +3745,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3591,6 +3763,8 @@ fn to_exposes_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EExposes;
 
+    let code = codes::exposes(parse_problem);
+
     match *parse_problem {
         EExposes::ListEnd(pos) | // TODO: give this its own error message
         EExposes::Identifier(pos) => {
@@ -3612,6 +3786,7 @@ fn to_exposes_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3637,6 +3812,7 @@ fn to_exposes_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3656,6 +3832,8 @@ fn to_imports_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EImports;
 
+    let code = codes::imports(parse_problem);
+
     match *parse_problem {
         EImports::Identifier(pos) => {
             let surroundings = Region::new(start, pos);
@@ -3676,6 +3854,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3701,6 +3880,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3727,6 +3907,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3746,6 +3927,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3763,6 +3945,8 @@ fn to_requires_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::ERequires;
 
+    let code = codes::requires(parse_problem);
+
     match *parse_problem {
         ERequires::Requires(pos) => {
             let surroundings = Region::new(start, pos);
@@ -3785,6 +3969,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "MISSING REQUIRES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3812,6 +3997,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "MISSING REQUIRES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3841,6 +4027,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "BAD REQUIRES RIGIDS".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3870,6 +4057,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "BAD REQUIRES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3887,6 +4075,8 @@ fn to_packages_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EPackages;
 
+    let code = codes::packages(parse_problem);
+
     match *parse_problem {
         EPackages::Packages(pos) => {
             let surroundings = Region::new(start, pos);
@@ -3907,6 +4097,7 @@ fn to_packages_report<'a>(
                 filename,
                 doc,
                 title: "MISSING PACKAGES".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3929,6 +4120,7 @@ fn to_packages_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PACKAGES LIST".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3948,6 +4140,8 @@ fn to_space_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::BadInputError;
 
+    let code = codes::bad_input(parse_problem);
+
     match parse_problem {
         BadInputError::HasTab => {
             let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
@@ -3962,6 +4156,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "TAB CHARACTER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3979,6 +4174,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "ASCII CONTROL CHARACTER".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -3996,6 +4192,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "MISPLACED CARRIAGE RETURN".to_string(),
+                code: Some(code),
                 severity: Severity::RuntimeError,
             }
         }
@@ -4013,6 +4210,8 @@ fn to_ability_def_report<'a>(
 ) -> Report<'a> {
     use roc_parse::parser::EAbility;
 
+    let code = codes::ability(problem);
+
     match problem {
         EAbility::Space(error, pos) => to_space_report(alloc, lines, filename, error, *pos),
         EAbility::Type(tipe, pos) => to_type_report(alloc, lines, filename, tipe, *pos),
@@ -4031,12 +4230,13 @@ fn to_ability_def_report<'a>(
                 alloc.reflow(" spaces)"),
             ]);
 
-            to_unfinished_ability_report(alloc, lines, filename, *pos, start, msg)
+            to_unfinished_ability_report(alloc, lines, filename, code, *pos, start, msg)
         }
         EAbility::DemandName(pos) => to_unfinished_ability_report(
             alloc,
             lines,
             filename,
+            code,
             *pos,
             start,
             alloc.reflow("I was expecting to see a value signature next."),
@@ -4045,6 +4245,7 @@ fn to_ability_def_report<'a>(
             alloc,
             lines,
             filename,
+            code,
             *pos,
             start,
             alloc.concat([
@@ -4060,6 +4261,7 @@ fn to_unfinished_ability_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
+    code: ErrorCode,
     pos: Position,
     start: Position,
     message: RocDocBuilder<'a>,
@@ -4077,6 +4279,7 @@ fn to_unfinished_ability_report<'a>(
         filename,
         doc,
         title: "UNFINISHED ABILITY".to_string(),
+        code: Some(code),
         severity: Severity::RuntimeError,
     }
 }
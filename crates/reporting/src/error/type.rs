@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
+use crate::error::codes::{self, ErrorCode};
 use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
@@ -11,18 +12,19 @@ use roc_error_macros::internal_error;
 use roc_exhaustive::{CtorName, ListArity};
 use roc_module::called_via::{BinOp, CalledVia};
 use roc_module::ident::{IdentStr, Lowercase, TagName};
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{Interns, Symbol};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Region};
 use roc_solve_problem::{
-    NotDerivableContext, NotDerivableDecode, NotDerivableEncode, NotDerivableEq, TypeError,
-    UnderivableReason, Unfulfilled,
+    MismatchTrace, NotDerivableContext, NotDerivableDecode, NotDerivableDefault,
+    NotDerivableEncode, NotDerivableEq, NotDerivableSort, TypeError, UnderivableReason,
+    Unfulfilled,
 };
 use roc_std::RocDec;
 use roc_types::pretty_print::{Parens, WILDCARD};
 use roc_types::types::{
-    AbilitySet, AliasKind, Category, ErrorType, IndexOrField, PatternCategory, Polarity, Reason,
-    RecordField, TypeExt,
+    AbilitySet, AliasKind, Category, ErrorType, IndexOrField, MismatchStep, PatternCategory,
+    Polarity, Reason, RecordField, TypeExt,
 };
 use std::path::PathBuf;
 use ven_pretty::{text, DocAllocator};
@@ -44,6 +46,7 @@ pub fn type_problem<'b>(
     use TypeError::*;
 
     let severity = problem.severity();
+    let code = codes::type_error(&problem);
 
     let report =
         move |title: String, doc: RocDocBuilder<'b>, filename: PathBuf| -> Option<Report<'b>> {
@@ -51,22 +54,24 @@ pub fn type_problem<'b>(
                 title,
                 filename,
                 doc,
+                code: Some(code),
                 severity,
             })
         };
 
     match problem {
-        BadExpr(region, category, found, expected) => Some(to_expr_report(
-            alloc, lines, filename, severity, region, category, found, expected,
+        BadExpr(region, category, found, expected, _trace) => Some(to_expr_report(
+            alloc, lines, filename, severity, code, region, category, found, expected,
         )),
-        BadPattern(region, category, found, expected) => Some(to_pattern_report(
-            alloc, lines, filename, severity, region, category, found, expected,
+        BadPattern(region, category, found, expected, _trace) => Some(to_pattern_report(
+            alloc, lines, filename, severity, code, region, category, found, expected,
         )),
         CircularType(region, symbol, overall_type) => Some(to_circular_report(
             alloc,
             lines,
             filename,
             severity,
+            code,
             region,
             symbol,
             overall_type,
@@ -112,6 +117,7 @@ pub fn type_problem<'b>(
                 title: "TYPE MISMATCH".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             };
             Some(report)
@@ -134,6 +140,7 @@ pub fn type_problem<'b>(
                 title: "TYPE MISMATCH".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             };
             Some(report)
@@ -147,6 +154,7 @@ pub fn type_problem<'b>(
                 title,
                 filename,
                 doc,
+                code: Some(code),
                 severity,
             })
         }
@@ -177,6 +185,7 @@ pub fn type_problem<'b>(
                 title: "ILLEGAL SPECIALIZATION".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             })
         }
@@ -206,6 +215,7 @@ pub fn type_problem<'b>(
                 title: "WRONG SPECIALIZATION TYPE".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             })
         }
@@ -222,6 +232,7 @@ pub fn type_problem<'b>(
                 title: "INVALID UTF-8".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             })
         }
@@ -245,10 +256,296 @@ pub fn type_problem<'b>(
                 title: "INVALID TYPE FOR INGESTED FILE".to_string(),
                 filename,
                 doc: alloc.stack(stack),
+                code: Some(code),
                 severity,
             })
         }
+        TypedHole {
+            region,
+            typ,
+            locals,
+            candidates,
+        } => Some(Report {
+            title: "TYPED HOLE".to_string(),
+            filename,
+            doc: to_typed_hole_doc(alloc, lines, region, typ, locals, candidates),
+            code: Some(code),
+            severity,
+        }),
+    }
+}
+
+/// The most steps into the two types that a mismatch trace shows
+const MAX_MISMATCH_STEPS: usize = 3;
+
+/// Explains how a type mismatch came about: where the expected type comes from, where the
+/// function it comes from is defined, and where in the two types they first differ. These notes
+/// are only shown with `--verbose-errors`, so they are not part of the report itself.
+pub fn mismatch_trace<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    lines: &LineInfo,
+    problem: &TypeError,
+) -> Option<RocDocBuilder<'b>> {
+    let line_of =
+        |region: Region| alloc.string((lines.convert_region(region).start.line + 1).to_string());
+
+    let (expected_origin, trace) = match problem {
+        TypeError::BadExpr(_, _, _, expected, trace) => (Some(expected), trace),
+        TypeError::BadPattern(_, _, _, _, trace) => (None, trace),
+        _ => return None,
+    };
+    let MismatchTrace { path, origin_def } = trace;
+
+    let mut steps = Vec::new();
+
+    match expected_origin {
+        Some(Expected::FromAnnotation(name, _, _, _)) => {
+            steps.push(alloc.concat([
+                alloc.reflow("It has to match the type annotation"),
+                match pattern_to_doc(alloc, &name.value) {
+                    Some(doc) => alloc.concat([alloc.reflow(" of "), doc]),
+                    None => alloc.nil(),
+                },
+                alloc.reflow(" on line "),
+                line_of(name.region),
+                alloc.text("."),
+            ]));
+        }
+        Some(Expected::ForReason(reason, _, region)) => {
+            let (usage, origin) = reason_to_usage_doc(alloc, reason);
+
+            steps.push(alloc.concat([
+                usage,
+                alloc.reflow(" on line "),
+                line_of(*region),
+                alloc.text("."),
+            ]));
+
+            if let (Some(origin), Some(def_region)) = (origin, origin_def) {
+                steps.push(alloc.concat([
+                    alloc.symbol_unqualified(origin),
+                    alloc.reflow(" is defined on line "),
+                    line_of(*def_region),
+                    alloc.text("."),
+                ]));
+            }
+        }
+        Some(Expected::NoExpectation(_)) | None => {}
+    }
+
+    if !path.is_empty() {
+        let path_docs = path
+            .iter()
+            .take(MAX_MISMATCH_STEPS)
+            .map(|step| mismatch_step_to_doc(alloc, step));
+
+        steps.push(alloc.concat([
+            alloc.reflow("The two types first differ in "),
+            alloc.intersperse(path_docs, alloc.reflow(", then ")),
+            alloc.text(if path.len() > MAX_MISMATCH_STEPS {
+                ", and deeper."
+            } else {
+                "."
+            }),
+        ]));
+    }
+
+    if steps.is_empty() {
+        return None;
+    }
+
+    Some(
+        alloc.vcat([
+            alloc.note("Here is how I came to expect that type:"),
+            alloc
+                .vcat(steps.into_iter().map(|step| alloc.text("- ").append(step)))
+                .indent(4),
+        ]),
+    )
+}
+
+/// How the expected type of an expression is used, and the value it comes from, if any
+fn reason_to_usage_doc<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    reason: &Reason,
+) -> (RocDocBuilder<'b>, Option<Symbol>) {
+    match reason {
+        Reason::FnArg {
+            name, arg_index, ..
+        }
+        | Reason::TypedArg { name, arg_index } => {
+            let function = match name {
+                Some(symbol) => alloc.symbol_unqualified(*symbol),
+                None => alloc.reflow("a function"),
+            };
+
+            (
+                alloc.concat([
+                    alloc.reflow("It is the "),
+                    alloc.string(arg_index.ordinal()),
+                    alloc.reflow(" argument to "),
+                    function,
+                ]),
+                *name,
+            )
+        }
+        Reason::FnCall {
+            name: Some(symbol), ..
+        } => (
+            alloc.concat([
+                alloc.reflow("It is called as "),
+                alloc.symbol_unqualified(*symbol),
+            ]),
+            Some(*symbol),
+        ),
+        Reason::IfCondition => (
+            alloc.concat([
+                alloc.reflow("It is the condition of an "),
+                alloc.keyword("if"),
+            ]),
+            None,
+        ),
+        Reason::WhenGuard => (
+            alloc.concat([
+                alloc.reflow("It is a "),
+                alloc.keyword("when"),
+                alloc.text(" guard"),
+            ]),
+            None,
+        ),
+        Reason::IfBranch { index, .. } => (
+            alloc.concat([
+                alloc.reflow("It is the "),
+                alloc.string(index.ordinal()),
+                alloc.reflow(" branch of an "),
+                alloc.keyword("if"),
+                alloc.reflow(", which must have the same type as the branches before it,"),
+            ]),
+            None,
+        ),
+        Reason::WhenBranch { index } => (
+            alloc.concat([
+                alloc.reflow("It is the "),
+                alloc.string(index.ordinal()),
+                alloc.reflow(" branch of a "),
+                alloc.keyword("when"),
+                alloc.reflow(", which must have the same type as the branches before it,"),
+            ]),
+            None,
+        ),
+        Reason::ElemInList { index } => (
+            alloc.concat([
+                alloc.reflow("It is the "),
+                alloc.string(index.ordinal()),
+                alloc.reflow(
+                    " element of a list, which must have the same type as the elements before it,",
+                ),
+            ]),
+            None,
+        ),
+        Reason::RecordUpdateValue(field) => (
+            alloc.concat([
+                alloc.reflow("It updates the "),
+                alloc.record_field(field.clone()),
+                alloc.reflow(" field"),
+            ]),
+            None,
+        ),
+        _ => (alloc.reflow("It is used"), None),
+    }
+}
+
+fn mismatch_step_to_doc<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    step: &MismatchStep,
+) -> RocDocBuilder<'b> {
+    match step {
+        MismatchStep::FunctionArg(index) => alloc.concat([
+            alloc.reflow("the "),
+            alloc.string(index.ordinal()),
+            alloc.reflow(" argument"),
+        ]),
+        MismatchStep::FunctionReturn => alloc.reflow("the return type"),
+        MismatchStep::RecordField(field) => alloc.concat([
+            alloc.reflow("the "),
+            alloc.record_field(field.clone()),
+            alloc.reflow(" field"),
+        ]),
+        MismatchStep::TupleElem(index) => alloc.concat([
+            alloc.reflow("the "),
+            alloc.string(index.ordinal()),
+            alloc.reflow(" element"),
+        ]),
+        MismatchStep::TagPayload(tag_name, index) => alloc.concat([
+            alloc.reflow("the "),
+            alloc.string(index.ordinal()),
+            alloc.reflow(" payload of "),
+            alloc.tag_name(tag_name.clone()),
+        ]),
+        MismatchStep::TypeArg(symbol, index) => alloc.concat([
+            alloc.reflow("the "),
+            alloc.string(index.ordinal()),
+            alloc.reflow(" type argument of "),
+            alloc.symbol_unqualified(*symbol),
+        ]),
+    }
+}
+
+fn to_typed_hole_doc<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    lines: &LineInfo,
+    region: roc_region::all::Region,
+    typ: ErrorType,
+    locals: Vec<(Symbol, ErrorType)>,
+    candidates: Vec<Symbol>,
+) -> RocDocBuilder<'b> {
+    // Compiler-generated names can't be written in source, so they're no help here.
+    let is_nameable = |symbol: &Symbol| {
+        symbol
+            .as_str(alloc.interns)
+            .starts_with(|c: char| c.is_ascii_lowercase())
+    };
+
+    let mut stack = vec![
+        alloc.reflow("This hole needs to be filled in:"),
+        alloc.region(lines.convert_region(region)),
+        alloc.reflow("It is expected to have the type:"),
+        alloc.type_block(error_type_to_doc(alloc, typ)),
+    ];
+
+    let locals: Vec<_> = locals
+        .into_iter()
+        .filter(|(symbol, _)| is_nameable(symbol))
+        .map(|(symbol, local_type)| {
+            alloc.concat([
+                alloc.symbol_unqualified(symbol),
+                alloc.text(" : "),
+                error_type_to_doc(alloc, local_type),
+            ])
+        })
+        .collect();
+
+    if !locals.is_empty() {
+        stack.push(alloc.reflow("These values are in scope:"));
+        stack.push(alloc.type_block(alloc.vcat(locals)));
+    }
+
+    let candidates: Vec<_> = candidates
+        .into_iter()
+        .filter(is_nameable)
+        .map(|symbol| alloc.symbol_foreign_qualified(symbol))
+        .collect();
+
+    if !candidates.is_empty() {
+        stack.push(alloc.reflow("These values in scope have a type that fits the hole:"));
+        stack.push(alloc.type_block(alloc.vcat(candidates)));
     }
+
+    stack.push(alloc.note("").append(alloc.reflow(
+        "A program that reaches a hole while running will crash, so every hole needs to be filled in eventually.",
+    )));
+
+    alloc.stack(stack)
 }
 
 fn report_unfulfilled_ability<'a>(
@@ -461,6 +758,34 @@ fn underivable_hint<'b>(
                 ])))
             }
         },
+        NotDerivableContext::Sort(reason) => match reason {
+            NotDerivableSort::FloatingPoint => {
+                Some(alloc.note("").append(alloc.concat([
+                    alloc.reflow("I can't derive "),
+                    alloc.symbol_qualified(Symbol::SORT_COMPARE),
+                    alloc.reflow(" for floating-point types. That's because Roc's floating-point numbers cannot be put in a total order - in Roc, `NaN` is neither less than, equal to, nor greater than any other number."),
+                    alloc.reflow(" If a type doesn't have a total order, it cannot support the "),
+                    alloc.symbol_unqualified(Symbol::SORT_SORT_ABILITY),
+                    alloc.reflow(" ability!"),
+                ])))
+            }
+        },
+        NotDerivableContext::Default(reason) => match reason {
+            NotDerivableDefault::NotARecord => Some(alloc.note("").append(alloc.concat([
+                alloc.reflow("I can only derive "),
+                alloc.symbol_qualified(Symbol::DEFAULT_DEFAULT),
+                alloc.reflow(" for records, because there is no single obvious default for a tag union or a tuple."),
+            ]))),
+            NotDerivableDefault::OptionalRecordField(field) => {
+                Some(alloc.note("").append(alloc.concat([
+                    alloc.reflow("I can't derive "),
+                    alloc.symbol_qualified(Symbol::DEFAULT_DEFAULT),
+                    alloc.reflow(" for a record with an optional field, which in this case is "),
+                    alloc.record_field(field),
+                    alloc.reflow(". Optional fields only get their default where the record is destructured, so there is no value I could put in it here."),
+                ])))
+            }
+        },
     }
 }
 
@@ -526,6 +851,7 @@ fn report_mismatch<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     category: &Category,
     found: ErrorType,
     expected_type: ErrorType,
@@ -562,6 +888,7 @@ fn report_mismatch<'b>(
         title: "TYPE MISMATCH".to_string(),
         filename,
         doc: alloc.stack(lines),
+        code: Some(code),
         severity,
     }
 }
@@ -571,6 +898,7 @@ fn report_bad_type<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     category: &Category,
     found: ErrorType,
     expected_type: ErrorType,
@@ -605,6 +933,7 @@ fn report_bad_type<'b>(
         title: "TYPE MISMATCH".to_string(),
         filename,
         doc: alloc.stack(lines),
+        code: Some(code),
         severity,
     }
 }
@@ -634,6 +963,7 @@ fn to_expr_report<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     expr_region: roc_region::all::Region,
     category: Category,
     found: ErrorType,
@@ -662,6 +992,7 @@ fn to_expr_report<'b>(
                             lines,
                             filename,
                             severity,
+                            code,
                             opt_sym,
                             ".",
                             field,
@@ -692,6 +1023,7 @@ fn to_expr_report<'b>(
                     alloc.region(lines.convert_region(expr_region)),
                     comparison,
                 ]),
+                code: Some(code),
                 severity,
             }
         }
@@ -815,6 +1147,7 @@ fn to_expr_report<'b>(
                     },
                     comparison,
                 ]),
+                code: Some(code),
                 severity,
             }
         }
@@ -833,6 +1166,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -873,6 +1207,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -912,6 +1247,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -941,6 +1277,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -976,6 +1313,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -1002,6 +1340,7 @@ fn to_expr_report<'b>(
                 lines,
                 filename,
                 severity,
+                code,
                 &category,
                 found,
                 expected_type,
@@ -1042,6 +1381,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -1058,6 +1398,7 @@ fn to_expr_report<'b>(
                 lines,
                 filename,
                 severity,
+                code,
                 &category,
                 found,
                 expected_type,
@@ -1094,6 +1435,7 @@ fn to_expr_report<'b>(
                                 lines,
                                 filename,
                                 severity,
+                                code,
                                 &category,
                                 found,
                                 expected_type,
@@ -1113,6 +1455,7 @@ fn to_expr_report<'b>(
                                 lines,
                                 filename,
                                 severity,
+                                code,
                                 Some(symbol),
                                 "",
                                 field,
@@ -1128,6 +1471,7 @@ fn to_expr_report<'b>(
                         lines,
                         filename,
                         severity,
+                        code,
                         &category,
                         found,
                         expected_type,
@@ -1206,6 +1550,7 @@ fn to_expr_report<'b>(
                         filename,
                         title: "TOO MANY ARGS".to_string(),
                         doc,
+                        code: Some(code),
                         severity,
                     }
                 }
@@ -1241,6 +1586,7 @@ fn to_expr_report<'b>(
                             filename,
                             title: "TOO MANY ARGS".to_string(),
                             doc: alloc.stack(lines),
+                            code: Some(code),
                             severity,
                         }
                     } else {
@@ -1268,6 +1614,7 @@ fn to_expr_report<'b>(
                             filename,
                             title: "TOO FEW ARGS".to_string(),
                             doc: alloc.stack(lines),
+                            code: Some(code),
                             severity,
                         }
                     }
@@ -1298,6 +1645,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -1323,6 +1671,7 @@ fn to_expr_report<'b>(
                 lines,
                 filename,
                 severity,
+                code,
                 &category,
                 found,
                 expected_type,
@@ -1375,6 +1724,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -1416,6 +1766,7 @@ fn to_expr_report<'b>(
                     lines,
                     filename,
                     severity,
+                    code,
                     &category,
                     found,
                     expected_type,
@@ -1469,6 +1820,7 @@ fn to_expr_report<'b>(
                     title: "TYPE MISMATCH".to_string(),
                     filename,
                     doc: alloc.stack(lines),
+                    code: Some(code),
                     severity,
                 }
             }
@@ -1506,6 +1858,7 @@ fn to_expr_report<'b>(
                     filename,
                     title: "TYPE MISMATCH".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -1542,6 +1895,7 @@ fn to_expr_report<'b>(
                     filename,
                     title: "TYPE MISMATCH".to_string(),
                     doc: alloc.stack(lines),
+                    code: Some(code),
                     severity,
                 }
             }
@@ -1929,6 +2283,7 @@ fn to_pattern_report<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     expr_region: roc_region::all::Region,
     category: PatternCategory,
     found: ErrorType,
@@ -1955,6 +2310,7 @@ fn to_pattern_report<'b>(
                 filename,
                 title: "TYPE MISMATCH".to_string(),
                 doc,
+                code: Some(code),
                 severity,
             }
         }
@@ -1998,6 +2354,7 @@ fn to_pattern_report<'b>(
                     filename,
                     title: "TYPE MISMATCH".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -2078,6 +2435,7 @@ fn to_pattern_report<'b>(
                     filename,
                     title: "TYPE MISMATCH".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -2105,6 +2463,7 @@ fn to_pattern_report<'b>(
                     filename,
                     title: "TYPE MISMATCH".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -2182,6 +2541,7 @@ fn to_circular_report<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     region: roc_region::all::Region,
     symbol: Symbol,
     overall_type: ErrorType,
@@ -2206,6 +2566,7 @@ fn to_circular_report<'b>(
                 ]),
             ])
         },
+        code: Some(code),
         severity,
     }
 }
@@ -4801,6 +5162,7 @@ fn report_record_field_typo<'b>(
     lines: &LineInfo,
     filename: PathBuf,
     severity: Severity,
+    code: ErrorCode,
     opt_sym: Option<Symbol>,
     field_prefix: &str,
     field: &Lowercase,
@@ -4880,6 +5242,7 @@ fn report_record_field_typo<'b>(
         filename,
         title: "TYPE MISMATCH".to_string(),
         doc,
+        code: Some(code),
         severity,
     }
 }
@@ -4894,6 +5257,7 @@ fn exhaustive_problem<'a>(
     use roc_exhaustive::Error::*;
 
     let severity = problem.severity();
+    let code = codes::exhaustive_error(&problem);
 
     match problem {
         Incomplete(region, context, missing) => match context {
@@ -4917,6 +5281,7 @@ fn exhaustive_problem<'a>(
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -4941,6 +5306,7 @@ fn exhaustive_problem<'a>(
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -4965,6 +5331,7 @@ fn exhaustive_problem<'a>(
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
                     doc,
+                    code: Some(code),
                     severity,
                 }
             }
@@ -4994,6 +5361,7 @@ fn exhaustive_problem<'a>(
                 filename,
                 title: "REDUNDANT PATTERN".to_string(),
                 doc,
+                code: Some(code),
                 severity,
             }
         }
@@ -5022,6 +5390,7 @@ fn exhaustive_problem<'a>(
                 filename,
                 title: "UNMATCHABLE PATTERN".to_string(),
                 doc,
+                code: Some(code),
                 severity,
             }
         }
@@ -5203,3 +5572,139 @@ fn pattern_to_doc_help<'b>(
         }
     }
 }
+
+/// The patterns a `when` or destructure is missing, written as Roc source, for the JSON output of
+/// an exhaustiveness problem. Empty for every other problem.
+pub fn missing_patterns(interns: &Interns, problem: &TypeError) -> Vec<String> {
+    match problem {
+        TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(_, _, missing)) => missing
+            .iter()
+            .map(|pattern| missing_pattern_to_source(interns, pattern))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Writes a missing pattern as Roc source on one line, so that tools can insert it as a branch.
+/// A missing guard is written as the pattern it guards.
+fn missing_pattern_to_source(interns: &Interns, pattern: &roc_exhaustive::Pattern) -> String {
+    let mut buf = String::new();
+    pattern_to_source_help(interns, pattern, false, &mut buf);
+    buf
+}
+
+fn pattern_to_source_help(
+    interns: &Interns,
+    pattern: &roc_exhaustive::Pattern,
+    in_type_param: bool,
+    buf: &mut String,
+) {
+    use roc_can::exhaustive::NONEXHAUSIVE_CTOR;
+    use roc_exhaustive::Literal::*;
+    use roc_exhaustive::Pattern::*;
+    use roc_exhaustive::RenderAs;
+
+    let push_all = |buf: &mut String, patterns: &[roc_exhaustive::Pattern], separator: &str| {
+        for (i, pattern) in patterns.iter().enumerate() {
+            if i > 0 {
+                buf.push_str(separator);
+            }
+            pattern_to_source_help(interns, pattern, false, buf);
+        }
+    };
+
+    match pattern {
+        Anything => buf.push('_'),
+        Literal(l) => match l {
+            Int(i) => buf.push_str(&i128::from_ne_bytes(*i).to_string()),
+            U128(i) => buf.push_str(&u128::from_ne_bytes(*i).to_string()),
+            Bit(true) => buf.push_str("Bool.true"),
+            Bit(false) => buf.push_str("Bool.false"),
+            Byte(b) => buf.push_str(&b.to_string()),
+            Float(f) => buf.push_str(&f.to_string()),
+            Decimal(d) => buf.push_str(&RocDec::from_ne_bytes(*d).to_string()),
+            Str(s) => {
+                buf.push('"');
+                for c in s.chars() {
+                    match c {
+                        '"' | '\\' => {
+                            buf.push('\\');
+                            buf.push(c);
+                        }
+                        _ => buf.push(c),
+                    }
+                }
+                buf.push('"');
+            }
+        },
+        List(arity, patterns) => {
+            buf.push('[');
+            match arity {
+                ListArity::Exact(_) => push_all(buf, patterns, ", "),
+                ListArity::Slice(num_before, _) => {
+                    let (before, after) = patterns.split_at(*num_before);
+                    push_all(buf, before, ", ");
+                    if !before.is_empty() {
+                        buf.push_str(", ");
+                    }
+                    buf.push_str("..");
+                    if !after.is_empty() {
+                        buf.push_str(", ");
+                        push_all(buf, after, ", ");
+                    }
+                }
+            }
+            buf.push(']');
+        }
+        Ctor(union, tag_id, args) => match &union.render_as {
+            // #Guard <fake-condition-tag> <unexhausted-pattern>
+            RenderAs::Guard => pattern_to_source_help(interns, &args[1], in_type_param, buf),
+            RenderAs::Record(field_names) => {
+                buf.push_str("{ ");
+                for (i, (label, v)) in field_names.iter().zip(args.iter()).enumerate() {
+                    if i > 0 {
+                        buf.push_str(", ");
+                    }
+                    buf.push_str(label.as_str());
+                    if !matches!(v, Anything) {
+                        buf.push_str(": ");
+                        pattern_to_source_help(interns, v, false, buf);
+                    }
+                }
+                buf.push_str(" }");
+            }
+            RenderAs::Tuple => {
+                buf.push_str("( ");
+                push_all(buf, args, ", ");
+                buf.push_str(" )");
+            }
+            RenderAs::Tag | RenderAs::Opaque => {
+                let ctor = &union.alternatives[tag_id.0 as usize];
+                if matches!(&ctor.name, CtorName::Tag(TagName(name)) if name.as_str() == NONEXHAUSIVE_CTOR)
+                {
+                    buf.push('_');
+                    return;
+                }
+
+                let parenthesize = in_type_param && !args.is_empty();
+                if parenthesize {
+                    buf.push('(');
+                }
+                match &ctor.name {
+                    CtorName::Tag(TagName(name)) => buf.push_str(name.as_str()),
+                    CtorName::Opaque(opaque) => {
+                        buf.push('@');
+                        buf.push_str(opaque.as_str(interns));
+                    }
+                }
+                for arg in args.iter() {
+                    buf.push(' ');
+                    pattern_to_source_help(interns, arg, true, buf);
+                }
+                if parenthesize {
+                    buf.push(')');
+                }
+            }
+        },
+    }
+}
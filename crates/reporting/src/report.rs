@@ -1,8 +1,10 @@
+use crate::error::codes::{self, ErrorCode};
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
 use roc_problem::Severity;
-use roc_region::all::LineColumnRegion;
+use roc_region::all::{LineColumnRegion, Region};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 use std::{fmt, io};
 use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder, Render, RenderAnnotated};
@@ -14,7 +16,7 @@ use roc_packaging::https::Problem;
 
 pub use crate::error::canonicalize::can_problem;
 pub use crate::error::parse::parse_problem;
-pub use crate::error::r#type::type_problem;
+pub use crate::error::r#type::{mismatch_trace, type_problem};
 
 #[cfg(windows)]
 const CYCLE_ELEMENTS: [&str; 4] = ["+-----+", "|     ", "|     |", "+-<---+"];
@@ -108,15 +110,30 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
     header
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RenderTarget {
     ColorTerminal,
     Generic,
+    /// One JSON object per report, each on its own line. For editors and CI tools.
+    Json,
+}
+
+/// A syntax error that parsing recovered from by skipping the def it was in. The error borrows
+/// the source of its module, so it is rendered when the module is loaded.
+#[derive(Debug, Clone)]
+pub struct RecoveredParseProblem {
+    /// The def that was skipped
+    pub region: Region,
+    /// The stable code of the syntax error
+    pub code: ErrorCode,
+    pub report: String,
 }
 
 /// A textual report.
 pub struct Report<'b> {
     pub title: String,
+    /// The stable code for this kind of problem, if it has one
+    pub code: Option<ErrorCode>,
     pub filename: PathBuf,
     pub doc: RocDocBuilder<'b>,
     pub severity: Severity,
@@ -126,14 +143,89 @@ impl<'b> Report<'b> {
     pub fn render(
         self,
         target: RenderTarget,
-        buf: &'b mut String,
+        buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
         palette: &'b Palette,
     ) {
         match target {
             RenderTarget::Generic => self.render_ci(buf, alloc),
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
+            RenderTarget::Json => self.render_json(buf),
+        }
+    }
+
+    /// Render as a single line of JSON, followed by a newline.
+    ///
+    /// Line and column numbers are 1-based, and the end of a range is exclusive.
+    pub fn render_json(self, buf: &mut String) {
+        self.render_json_with_missing_patterns(buf, vec![])
+    }
+
+    /// Render as JSON, with the patterns that an exhaustiveness problem is missing.
+    /// See [`crate::error::r#type::missing_patterns`].
+    pub fn render_json_with_missing_patterns(
+        self,
+        buf: &mut String,
+        missing_patterns: Vec<String>,
+    ) {
+        let code = self.code();
+        let severity = match self.severity {
+            Severity::RuntimeError => "error",
+            Severity::Warning => "warning",
+            Severity::Fatal => "fatal",
+        };
+        let filename = self.filename.to_string_lossy().into_owned();
+        let title = self.title.clone();
+
+        // Render the body without the header, then split the tips and hints out of it
+        let mut text = String::new();
+        let mut writer = RegionWrite::new(CiWrite::new(&mut text));
+        self.doc
+            .1
+            .render_raw(70, &mut writer)
+            .expect("<buffer is not a utf-8 encoded string>");
+        let regions = writer.regions;
+
+        let mut message_paragraphs = Vec::new();
+        let mut hints = Vec::new();
+        for paragraph in text.split("\n\n") {
+            let trimmed = paragraph.trim_matches('\n');
+            if trimmed.trim().is_empty() {
+                continue;
+            }
+            let hint = ["Tip", "Hint", "Note"].iter().find_map(|kind| {
+                let rest = trimmed.strip_prefix(kind)?.strip_prefix(':')?;
+                Some(JsonHint {
+                    kind: kind.to_lowercase(),
+                    message: rest.trim().to_string(),
+                })
+            });
+            match hint {
+                Some(hint) => hints.push(hint),
+                None => message_paragraphs.push(trimmed),
+            }
         }
+
+        let ranges: Vec<JsonRange> = regions.into_iter().map(JsonRange::from).collect();
+        let diagnostic = JsonDiagnostic {
+            severity,
+            code,
+            title,
+            file: filename,
+            range: ranges.first().copied(),
+            ranges,
+            message: message_paragraphs.join("\n\n"),
+            hints,
+            missing_patterns,
+        };
+
+        buf.push_str(&serde_json::to_string(&diagnostic).expect("diagnostics are serializable"));
+        buf.push('\n');
+    }
+
+    /// The stable code for this kind of report, like `R0301`. See [`crate::error::codes`].
+    pub fn code(&self) -> Option<&'static str> {
+        self.code.map(|error_code| error_code.code)
     }
 
     /// Render to CI console output, where no colors are available.
@@ -166,10 +258,15 @@ impl<'b> Report<'b> {
         if self.title.is_empty() {
             self.doc
         } else {
+            let title = match self.code() {
+                Some(code) => format!("{} [{}]", self.title, code),
+                None => self.title.clone(),
+            };
+
             let header = if self.filename == PathBuf::from("replfile.roc") {
-                crate::report::pretty_header(&self.title)
+                crate::report::pretty_header(&title)
             } else {
-                crate::report::pretty_header_with_path(&self.title, &self.filename)
+                crate::report::pretty_header_with_path(&title, &self.filename)
             };
 
             alloc.stack([alloc.text(header).annotate(Annotation::Header), self.doc])
@@ -181,6 +278,57 @@ impl<'b> Report<'b> {
     }
 }
 
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    severity: &'static str,
+    code: Option<&'static str>,
+    title: String,
+    file: String,
+    /// The main region the problem is about, if there is one
+    range: Option<JsonRange>,
+    /// All highlighted regions, starting with the main one
+    ranges: Vec<JsonRange>,
+    message: String,
+    hints: Vec<JsonHint>,
+    /// Patterns a `when` or destructure is missing, written as Roc source, so that tools can
+    /// generate the missing branches
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_patterns: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct JsonHint {
+    kind: String,
+    message: String,
+}
+
+#[derive(Serialize, Clone, Copy)]
+struct JsonRange {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+#[derive(Serialize, Clone, Copy)]
+struct JsonPosition {
+    line: u32,
+    column: u32,
+}
+
+impl From<LineColumnRegion> for JsonRange {
+    fn from(region: LineColumnRegion) -> Self {
+        JsonRange {
+            start: JsonPosition {
+                line: region.start().line + 1,
+                column: region.start().column + 1,
+            },
+            end: JsonPosition {
+                line: region.end().line + 1,
+                column: region.end().column + 1,
+            },
+        }
+    }
+}
+
 /// This struct is a combination of several things
 /// 1. A set of StyleCodes suitable for the environment we're running in (web or terminal)
 /// 2. A set of colors we decided to use
@@ -651,7 +799,10 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result.annotate(Annotation::CodeBlock)
+        result
+            .annotate(Annotation::CodeBlock)
+            .annotate(Annotation::SourceRegion(sub_region2))
+            .annotate(Annotation::SourceRegion(sub_region1))
     }
 
     pub fn region_with_subregion(
@@ -758,7 +909,7 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result
+        result.annotate(Annotation::SourceRegion(sub_region))
     }
 
     pub fn region(&'a self, region: LineColumnRegion) -> DocBuilder<'a, Self, Annotation> {
@@ -867,6 +1018,8 @@ pub enum Annotation {
     Tip,
     Header,
     ParserSuggestion,
+    /// Marks a source snippet with the region it points at. Not visible when rendered as text.
+    SourceRegion(LineColumnRegion),
 }
 
 /// Render with minimal formatting
@@ -888,6 +1041,21 @@ impl<W> CiWrite<W> {
     }
 }
 
+/// Render with minimal formatting, and remember which source regions were shown
+struct RegionWrite<W> {
+    ci: CiWrite<W>,
+    regions: Vec<LineColumnRegion>,
+}
+
+impl<W> RegionWrite<W> {
+    fn new(ci: CiWrite<W>) -> RegionWrite<W> {
+        RegionWrite {
+            ci,
+            regions: vec![],
+        }
+    }
+}
+
 /// Render with fancy formatting
 pub struct ColorWrite<'a, W> {
     style_stack: Vec<Annotation>,
@@ -992,6 +1160,39 @@ where
     }
 }
 
+impl<W> Render for RegionWrite<W>
+where
+    W: fmt::Write,
+{
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<usize, fmt::Error> {
+        self.ci.write_str(s)
+    }
+
+    fn write_str_all(&mut self, s: &str) -> fmt::Result {
+        self.ci.write_str_all(s)
+    }
+}
+
+impl<W> RenderAnnotated<Annotation> for RegionWrite<W>
+where
+    W: fmt::Write,
+{
+    fn push_annotation(&mut self, annotation: &Annotation) -> Result<(), Self::Error> {
+        if let Annotation::SourceRegion(region) = annotation {
+            if !self.regions.contains(region) {
+                self.regions.push(*region);
+            }
+        }
+        self.ci.push_annotation(annotation)
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        self.ci.pop_annotation()
+    }
+}
+
 impl<'a, W> Render for ColorWrite<'a, W>
 where
     W: fmt::Write,
@@ -1077,7 +1278,8 @@ where
             ParserSuggestion => {
                 self.write_str(self.palette.parser_suggestion)?;
             }
-            TypeBlock | InlineTypeBlock | Tag | RecordField | TupleElem => { /* nothing yet */ }
+            TypeBlock | InlineTypeBlock | Tag | RecordField | TupleElem | SourceRegion(_) => { /* nothing yet */
+            }
         }
         self.style_stack.push(*annotation);
         Ok(())
@@ -1095,8 +1297,8 @@ where
                     self.write_str(self.palette.reset)?;
                 }
 
-                TypeBlock | InlineTypeBlock | Tag | Opaque | RecordField | TupleElem => { /* nothing yet */
-                }
+                TypeBlock | InlineTypeBlock | Tag | Opaque | RecordField | TupleElem
+                | SourceRegion(_) => { /* nothing yet */ }
             },
         }
         Ok(())
@@ -1165,6 +1367,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "UNSUPPORTED ENCODING".to_string(),
+                code: Some(codes::UNSUPPORTED_ENCODING),
                 severity: Severity::Fatal,
             }
         }
@@ -1200,6 +1403,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MULTIPLE ENCODINGS".to_string(),
+                code: Some(codes::MULTIPLE_ENCODINGS),
                 severity: Severity::Fatal,
             }
         }
@@ -1235,6 +1439,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID CONTENT HASH".to_string(),
+                code: Some(codes::INVALID_CONTENT_HASH),
                 severity: Severity::Fatal,
             }
         }
@@ -1252,6 +1457,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "NOTFOUND".to_string(),
+                code: Some(codes::PACKAGE_NOT_FOUND),
                 severity: Severity::Fatal,
             }
         }
@@ -1279,6 +1485,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "IO ERROR".to_string(),
+                code: Some(codes::IO_ERROR),
                 severity: Severity::Fatal,
             }
         }
@@ -1306,6 +1513,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "IO ERROR".to_string(),
+                code: Some(codes::FILE_SYSTEM_ERROR),
                 severity: Severity::Fatal,
             }
         }
@@ -1335,6 +1543,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "HTTP ERROR".to_string(),
+                code: Some(codes::HTTP_ERROR),
                 severity: Severity::Fatal,
             }
         }
@@ -1376,6 +1585,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID EXTENSION SUFFIX".to_string(),
+                code: Some(codes::INVALID_EXTENSION_SUFFIX),
                 severity: Severity::Fatal,
             }
         }
@@ -1409,6 +1619,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID EXTENSION".to_string(),
+                code: Some(codes::INVALID_EXTENSION),
                 severity: Severity::Fatal,
             }
         }
@@ -1447,6 +1658,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID FRAGMENT".to_string(),
+                code: Some(codes::INVALID_FRAGMENT),
                 severity: Severity::Fatal,
             }
         }
@@ -1485,6 +1697,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MISSING PACKAGE HASH".to_string(),
+                code: Some(codes::MISSING_PACKAGE_HASH),
                 severity: Severity::Fatal,
             }
         }
@@ -1511,6 +1724,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "HTTPS MANDATORY".to_string(),
+                code: Some(codes::HTTPS_MANDATORY),
                 severity: Severity::Fatal,
             }
         }
@@ -1554,6 +1768,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MISLEADING CHARACTERS".to_string(),
+                code: Some(codes::MISLEADING_CHARACTERS),
                 severity: Severity::Fatal,
             }
         }
@@ -1584,6 +1799,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE TOO LARGE".to_string(),
+                code: Some(codes::FILE_TOO_LARGE),
                 severity: Severity::Fatal,
             }
         }
@@ -1631,6 +1847,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE NOT FOUND".to_string(),
+                code: Some(codes::FILE_NOT_FOUND),
                 severity: Severity::Fatal,
             }
         }
@@ -1649,6 +1866,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE PERMISSION DENIED".to_string(),
+                code: Some(codes::FILE_PERMISSION_DENIED),
                 severity: Severity::Fatal,
             }
         }
@@ -1685,6 +1903,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "NOT A ROC FILE".to_string(),
+                code: Some(codes::NOT_A_ROC_FILE),
                 severity: Severity::Fatal,
             }
         }
@@ -1705,6 +1924,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE PROBLEM".to_string(),
+                code: Some(codes::FILE_PROBLEM),
                 severity: Severity::Fatal,
             }
         }
@@ -0,0 +1,566 @@
+//! Interactive step debugger, for the `--debugger` CLI option.
+//!
+//! Pauses on entry to functions named in the name section, single-steps instructions,
+//! and can decode Roc data structures (`Str`, `List`) from linear memory.
+//! All output goes to stderr, since the program being debugged may be using stdout.
+
+use std::fmt::{self, Write};
+use std::io::{self, BufRead};
+
+use roc_wasm_module::opcodes::OpCode;
+use roc_wasm_module::{Value, WasmModule};
+
+use crate::instance::Instance;
+use crate::ImportDispatcher;
+
+const PROMPT: &str = "(wasm-dbg) ";
+
+/// Roc data structures are three 32-bit words on wasm32: pointer, length, capacity
+const ROC_STR_SIZE: u32 = 12;
+const ROC_STR_SMALL_CAPACITY: usize = ROC_STR_SIZE as usize - 1;
+const SMALL_STR_BIT: u8 = 0x80;
+const SEAMLESS_SLICE_BIT: u32 = 0x8000_0000;
+
+/// Don't flood the terminal when someone inspects a huge list
+const MAX_LIST_ELEMENTS: u32 = 64;
+
+const HELP: &str = "\
+Commands:
+  s, step [n]        execute the next n instructions (default 1)
+  c, continue        run until the next breakpoint
+  b, break <fn>      pause on entry to the named function
+  d, delete <fn>     remove a breakpoint
+  breakpoints        list breakpoints
+  bt, backtrace      show the call stack
+  l, locals          show the arguments and locals of the current function
+  stack              show the value stack of the current function
+  x <addr> [len]     dump memory as hex (default 64 bytes)
+  str <addr>         decode a Roc Str
+  list <addr> <elem> decode a Roc List. <elem> is one of u8, i32, i64, f32, f64, str
+  q, quit            stop the program
+  help               show this message
+An <addr> is a number (decimal or 0x hex), or $N for the value of local variable N.
+An empty line repeats the previous command.
+";
+
+#[derive(Debug)]
+pub struct Debugger {
+    /// Names of functions to pause at, on entry
+    breakpoints: Vec<String>,
+    /// Number of instructions to execute before pausing. `None` means run until a breakpoint.
+    steps_remaining: Option<u32>,
+    last_command: Option<Command>,
+}
+
+impl Debugger {
+    /// Create a debugger with some initial breakpoints.
+    /// If there are none, pause before the first instruction, so the user can set some.
+    pub fn new(breakpoints: Vec<String>) -> Self {
+        let steps_remaining = if breakpoints.is_empty() {
+            Some(0)
+        } else {
+            None
+        };
+        Debugger {
+            breakpoints,
+            steps_remaining,
+            last_command: None,
+        }
+    }
+
+    pub fn has_breakpoint(&self, fn_name: &str) -> bool {
+        self.breakpoints.iter().any(|b| b == fn_name)
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.steps_remaining = Some(0);
+    }
+
+    /// Check whether to pause before the next instruction, and count down any steps
+    pub(crate) fn should_pause(&mut self) -> bool {
+        match self.steps_remaining {
+            Some(0) => true,
+            Some(n) => {
+                self.steps_remaining = Some(n - 1);
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// What to do after the user's command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DebuggerAction {
+    /// Keep reading commands
+    Prompt,
+    /// Execute more instructions
+    Resume,
+    /// Stop the program
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    Step(u32),
+    Continue,
+    Break(String),
+    Delete(String),
+    Breakpoints,
+    Backtrace,
+    Locals,
+    Stack,
+    Memory(Address, u32),
+    Str(Address),
+    List(Address, ElemFormat),
+    Help,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Address {
+    Literal(u32),
+    Local(u32),
+}
+
+/// Element types that we know how to display from a Roc List
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ElemFormat {
+    U8,
+    I32,
+    I64,
+    F32,
+    F64,
+    Str,
+}
+
+impl ElemFormat {
+    fn size(self) -> u32 {
+        match self {
+            Self::U8 => 1,
+            Self::I32 | Self::F32 => 4,
+            Self::I64 | Self::F64 => 8,
+            Self::Str => ROC_STR_SIZE,
+        }
+    }
+}
+
+impl Command {
+    pub(crate) fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or("");
+        let mut next_arg = |what: &str| {
+            words
+                .next()
+                .ok_or_else(|| format!("`{name}` needs {what}. Type `help` for usage."))
+        };
+
+        let command = match name {
+            "s" | "step" => match next_arg("a count") {
+                Ok(n) => Command::Step(parse_u32(n)?.max(1)),
+                Err(_) => Command::Step(1),
+            },
+            "c" | "continue" => Command::Continue,
+            "b" | "break" => Command::Break(next_arg("a function name")?.to_string()),
+            "d" | "delete" => Command::Delete(next_arg("a function name")?.to_string()),
+            "breakpoints" => Command::Breakpoints,
+            "bt" | "backtrace" => Command::Backtrace,
+            "l" | "locals" => Command::Locals,
+            "stack" => Command::Stack,
+            "x" => {
+                let addr = Address::parse(next_arg("an address")?)?;
+                let len = match next_arg("a length") {
+                    Ok(len) => parse_u32(len)?,
+                    Err(_) => 64,
+                };
+                Command::Memory(addr, len)
+            }
+            "str" => Command::Str(Address::parse(next_arg("an address")?)?),
+            "list" => {
+                let addr = Address::parse(next_arg("an address")?)?;
+                let elem = match next_arg("an element type")? {
+                    "u8" => ElemFormat::U8,
+                    "i32" => ElemFormat::I32,
+                    "i64" => ElemFormat::I64,
+                    "f32" => ElemFormat::F32,
+                    "f64" => ElemFormat::F64,
+                    "str" => ElemFormat::Str,
+                    other => return Err(format!("Unknown list element type `{other}`")),
+                };
+                Command::List(addr, elem)
+            }
+            "help" | "h" | "?" => Command::Help,
+            "q" | "quit" => Command::Quit,
+            other => return Err(format!("Unknown command `{other}`. Type `help` for usage.")),
+        };
+
+        Ok(command)
+    }
+}
+
+impl Address {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s.strip_prefix('$') {
+            Some(local) => Ok(Address::Local(parse_u32(local)?)),
+            None => Ok(Address::Literal(parse_u32(s)?)),
+        }
+    }
+}
+
+fn parse_u32(s: &str) -> Result<u32, String> {
+    let result = match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse::<u32>(),
+    };
+    result.map_err(|_| format!("`{s}` is not a valid number"))
+}
+
+fn read_u32(memory: &[u8], addr: u32) -> Result<u32, String> {
+    let start = addr as usize;
+    match memory.get(start..start + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes(bytes.try_into().unwrap())),
+        None => Err(format!("Address {addr:#x} is out of bounds")),
+    }
+}
+
+fn read_bytes(memory: &[u8], addr: u32, len: u32) -> Result<&[u8], String> {
+    let start = addr as usize;
+    memory
+        .get(start..start + len as usize)
+        .ok_or_else(|| format!("{len} bytes at {addr:#x} are out of bounds"))
+}
+
+/// Pointers come from the program's memory, so they may be garbage
+fn offset_addr(addr: u32, offset: u32) -> Result<u32, String> {
+    addr.checked_add(offset)
+        .ok_or_else(|| format!("Address {addr:#x} + {offset:#x} is out of bounds"))
+}
+
+/// A Roc Str, decoded from Wasm memory
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum DecodedStr {
+    Small(String),
+    Heap {
+        text: String,
+        elements: u32,
+        capacity: u32,
+        is_seamless_slice: bool,
+    },
+}
+
+impl DecodedStr {
+    pub(crate) fn text(&self) -> &str {
+        match self {
+            Self::Small(text) | Self::Heap { text, .. } => text,
+        }
+    }
+}
+
+impl fmt::Display for DecodedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Small(text) => write!(f, "{text:?} (small string, len {})", text.len()),
+            Self::Heap {
+                text,
+                elements,
+                capacity,
+                is_seamless_slice,
+            } => {
+                let kind = if *is_seamless_slice {
+                    "seamless slice"
+                } else {
+                    "heap"
+                };
+                write!(
+                    f,
+                    "{text:?} ({kind} at {elements:#x}, len {}, capacity {capacity})",
+                    text.len()
+                )
+            }
+        }
+    }
+}
+
+pub(crate) fn decode_roc_str(memory: &[u8], addr: u32) -> Result<DecodedStr, String> {
+    let bytes = read_bytes(memory, addr, ROC_STR_SIZE)?;
+    let last_byte = bytes[ROC_STR_SMALL_CAPACITY];
+
+    if last_byte & SMALL_STR_BIT != 0 {
+        let len = (last_byte & !SMALL_STR_BIT) as usize;
+        if len > ROC_STR_SMALL_CAPACITY {
+            return Err(format!("Invalid small string length {len}"));
+        }
+        let text = String::from_utf8_lossy(&bytes[..len]).into_owned();
+        return Ok(DecodedStr::Small(text));
+    }
+
+    let elements = read_u32(memory, addr)?;
+    let length = read_u32(memory, offset_addr(addr, 4)?)?;
+    let capacity = read_u32(memory, offset_addr(addr, 8)?)?;
+    let is_seamless_slice = length & SEAMLESS_SLICE_BIT != 0;
+    let len = length & !SEAMLESS_SLICE_BIT;
+    let text = String::from_utf8_lossy(read_bytes(memory, elements, len)?).into_owned();
+
+    Ok(DecodedStr::Heap {
+        text,
+        elements,
+        capacity,
+        is_seamless_slice,
+    })
+}
+
+/// Display a Roc List from Wasm memory, e.g. `[1, 2, 3] (at 0x1234, len 3, capacity 4)`
+pub(crate) fn display_roc_list(
+    memory: &[u8],
+    addr: u32,
+    elem: ElemFormat,
+    out: &mut String,
+) -> Result<(), String> {
+    let elements = read_u32(memory, addr)?;
+    let len = read_u32(memory, offset_addr(addr, 4)?)? & !SEAMLESS_SLICE_BIT;
+    let capacity = read_u32(memory, offset_addr(addr, 8)?)?;
+    let elem_size = elem.size();
+
+    // Only print the list once all its elements could be read
+    let mut list = String::from("[");
+    for i in 0..len.min(MAX_LIST_ELEMENTS) {
+        if i > 0 {
+            list.push_str(", ");
+        }
+        let elem_addr = i
+            .checked_mul(elem_size)
+            .and_then(|offset| elements.checked_add(offset))
+            .ok_or_else(|| format!("Element {i} of the list at {elements:#x} is out of bounds"))?;
+        let bytes = read_bytes(memory, elem_addr, elem_size)?;
+        match elem {
+            ElemFormat::U8 => write!(list, "{}", bytes[0]),
+            ElemFormat::I32 => write!(list, "{}", i32::from_le_bytes(bytes.try_into().unwrap())),
+            ElemFormat::I64 => write!(list, "{}", i64::from_le_bytes(bytes.try_into().unwrap())),
+            ElemFormat::F32 => write!(list, "{}", f32::from_le_bytes(bytes.try_into().unwrap())),
+            ElemFormat::F64 => write!(list, "{}", f64::from_le_bytes(bytes.try_into().unwrap())),
+            ElemFormat::Str => write!(list, "{:?}", decode_roc_str(memory, elem_addr)?.text()),
+        }
+        .unwrap();
+    }
+    if len > MAX_LIST_ELEMENTS {
+        write!(list, ", ... {} more", len - MAX_LIST_ELEMENTS).unwrap();
+    }
+    out.push_str(&list);
+    writeln!(out, "] (at {elements:#x}, len {len}, capacity {capacity})").unwrap();
+
+    Ok(())
+}
+
+/// Hex dump with ASCII, 16 bytes per line
+pub(crate) fn display_memory(
+    memory: &[u8],
+    addr: u32,
+    len: u32,
+    out: &mut String,
+) -> Result<(), String> {
+    let bytes = read_bytes(memory, addr, len)?;
+    for (i, line) in bytes.chunks(16).enumerate() {
+        write!(out, "{:08x} ", addr as usize + i * 16).unwrap();
+        for b in line {
+            write!(out, " {b:02x}").unwrap();
+        }
+        let padding = 3 * (16 - line.len());
+        let ascii: String = line
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    *b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(out, "{:padding$}  |{ascii}|", "").unwrap();
+    }
+    Ok(())
+}
+
+impl<'a, I: ImportDispatcher> Instance<'a, I> {
+    /// Called on entry to every Wasm function, to check for breakpoints
+    pub(crate) fn debugger_enter_function(&mut self, fn_index: usize) {
+        let fn_name = self.debug_function_name(fn_index);
+        if let Some(debugger) = self.debugger.as_mut() {
+            if debugger.has_breakpoint(fn_name) {
+                debugger.pause();
+            }
+        }
+    }
+
+    /// Called before every instruction. Interacts with the user if we're paused.
+    pub(crate) fn debugger_before_instruction(
+        &mut self,
+        module: &WasmModule<'a>,
+    ) -> DebuggerAction {
+        let is_paused = match self.debugger.as_mut() {
+            Some(debugger) => debugger.should_pause(),
+            None => false,
+        };
+        if !is_paused {
+            return DebuggerAction::Resume;
+        }
+
+        eprintln!("{}", self.debugger_location(module));
+
+        let stdin = io::stdin();
+        let mut line = String::new();
+        loop {
+            eprint!("{PROMPT}");
+            line.clear();
+            match stdin.lock().read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    // End of input. Let the program run to completion.
+                    self.debugger = None;
+                    return DebuggerAction::Resume;
+                }
+                Ok(_) => {}
+            }
+
+            let command = if line.trim().is_empty() {
+                match self.debugger.as_ref().and_then(|d| d.last_command.clone()) {
+                    Some(command) => command,
+                    None => continue,
+                }
+            } else {
+                match Command::parse(&line) {
+                    Ok(command) => command,
+                    Err(message) => {
+                        eprintln!("{message}");
+                        continue;
+                    }
+                }
+            };
+
+            let mut output = String::new();
+            let action = self.run_debugger_command(module, &command, &mut output);
+            eprint!("{output}");
+            if let Some(debugger) = self.debugger.as_mut() {
+                debugger.last_command = Some(command);
+            }
+            if action != DebuggerAction::Prompt {
+                return action;
+            }
+        }
+    }
+
+    /// Describe the next instruction, e.g. `00012a  func[7] List.len  I32LOAD`
+    fn debugger_location(&self, module: &WasmModule<'a>) -> String {
+        let fn_index = self.current_frame.fn_index;
+        let file_offset = self.program_counter + module.code.section_offset as usize;
+        let op_code = OpCode::from(module.code.bytes[self.program_counter]);
+        format!(
+            "{file_offset:06x}  func[{fn_index}] {}  {op_code:?}",
+            self.debug_function_name(fn_index)
+        )
+    }
+
+    pub(crate) fn run_debugger_command(
+        &mut self,
+        module: &WasmModule<'a>,
+        command: &Command,
+        out: &mut String,
+    ) -> DebuggerAction {
+        let result = match command {
+            Command::Step(n) => {
+                if let Some(debugger) = self.debugger.as_mut() {
+                    debugger.steps_remaining = Some(n - 1);
+                }
+                return DebuggerAction::Resume;
+            }
+            Command::Continue => {
+                if let Some(debugger) = self.debugger.as_mut() {
+                    debugger.steps_remaining = None;
+                }
+                return DebuggerAction::Resume;
+            }
+            Command::Quit => return DebuggerAction::Quit,
+            Command::Help => {
+                out.push_str(HELP);
+                Ok(())
+            }
+            Command::Break(name) => {
+                let exists = module.names.function_names.iter().any(|(_, n)| n == name);
+                if !exists {
+                    writeln!(
+                        out,
+                        "Warning: there is no function named `{name}` in the name section"
+                    )
+                    .unwrap();
+                }
+                if let Some(debugger) = self.debugger.as_mut() {
+                    if !debugger.has_breakpoint(name) {
+                        debugger.breakpoints.push(name.clone());
+                    }
+                }
+                Ok(())
+            }
+            Command::Delete(name) => {
+                if let Some(debugger) = self.debugger.as_mut() {
+                    debugger.breakpoints.retain(|b| b != name);
+                }
+                Ok(())
+            }
+            Command::Breakpoints => {
+                if let Some(debugger) = self.debugger.as_ref() {
+                    for name in debugger.breakpoints.iter() {
+                        writeln!(out, "{name}").unwrap();
+                    }
+                }
+                Ok(())
+            }
+            Command::Backtrace => {
+                self.debug_stack_trace(out).unwrap();
+                Ok(())
+            }
+            Command::Locals => {
+                let frame = &self.current_frame;
+                for index in 0..frame.locals_count {
+                    let value = frame.get_local(&self.value_store, index as u32);
+                    writeln!(out, "${index} = {value:?}").unwrap();
+                }
+                Ok(())
+            }
+            Command::Stack => {
+                let base = self.current_frame.locals_start + self.current_frame.locals_count;
+                let slice = self.value_store.get_slice(base);
+                writeln!(out, "{slice:?}").unwrap();
+                Ok(())
+            }
+            Command::Memory(addr, len) => self
+                .debugger_address(*addr)
+                .and_then(|a| display_memory(&self.memory, a, *len, out)),
+            Command::Str(addr) => self.debugger_address(*addr).and_then(|a| {
+                let decoded = decode_roc_str(&self.memory, a)?;
+                writeln!(out, "{decoded}").unwrap();
+                Ok(())
+            }),
+            Command::List(addr, elem) => self
+                .debugger_address(*addr)
+                .and_then(|a| display_roc_list(&self.memory, a, *elem, out)),
+        };
+
+        if let Err(message) = result {
+            writeln!(out, "{message}").unwrap();
+        }
+        DebuggerAction::Prompt
+    }
+
+    fn debugger_address(&self, addr: Address) -> Result<u32, String> {
+        match addr {
+            Address::Literal(a) => Ok(a),
+            Address::Local(index) => {
+                if index as usize >= self.current_frame.locals_count {
+                    return Err(format!("There is no local variable ${index}"));
+                }
+                match self.current_frame.get_local(&self.value_store, index) {
+                    Value::I32(a) => Ok(a as u32),
+                    other => Err(format!("${index} is {other:?}, which is not an address")),
+                }
+            }
+        }
+    }
+}
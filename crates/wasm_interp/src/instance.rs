@@ -8,7 +8,9 @@ use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
 use roc_wasm_module::{ExportType, WasmModule};
 use roc_wasm_module::{Value, ValueType};
 
+use crate::debugger::{Debugger, DebuggerAction};
 use crate::frame::Frame;
+use crate::profiler::Profiler;
 use crate::value_store::ValueStore;
 use crate::{Error, ImportDispatcher};
 
@@ -66,6 +68,10 @@ pub struct Instance<'a, I: ImportDispatcher> {
     import_arguments: Vec<'a, Value>,
     /// temporary storage for output using the --debug option
     debug_string: Option<String>,
+    /// Interactive debugger state, for the --debugger option
+    pub(crate) debugger: Option<Debugger>,
+    /// Instruction and call counts, for the --profile option
+    profiler: Option<Profiler>,
 }
 
 impl<'a, I: ImportDispatcher> Instance<'a, I> {
@@ -95,6 +101,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string: Some(String::new()),
+            debugger: None,
+            profiler: None,
         }
     }
 
@@ -163,14 +171,38 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string,
+            debugger: None,
+            profiler: None,
         })
     }
 
+    /// Pause execution at breakpoints and let the user inspect the program state
+    pub fn attach_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Count instructions and calls per function
+    pub fn attach_profiler(&mut self, profiler: Profiler) {
+        self.profiler = Some(profiler);
+    }
+
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Write out the profile, if we're profiling. This happens automatically if the program exits via WASI.
+    pub fn finish_profile(&mut self) -> std::io::Result<()> {
+        match self.profiler.take() {
+            Some(profiler) => profiler.finish(self),
+            None => Ok(()),
+        }
+    }
+
     pub fn call_export<A>(&mut self, fn_name: &str, arg_values: A) -> Result<Option<Value>, String>
     where
         A: IntoIterator<Item = Value>,
     {
-        let (fn_index, param_type_iter, ret_type) =
+        let (fn_index, param_type_iter, return_count) =
             self.call_export_help_before_arg_load(self.module, fn_name)?;
         let n_args = param_type_iter.len();
 
@@ -184,7 +216,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.value_store.push(value);
         }
 
-        self.call_export_help_after_arg_load(self.module, fn_index, n_args, ret_type)
+        self.call_export_help_after_arg_load(self.module, fn_index, n_args, return_count)
     }
 
     pub fn call_export_from_cli(
@@ -206,7 +238,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         // Implement the "basic numbers" CLI
         // Check if the called Wasm function takes numeric arguments, and if so, try to parse them from the CLI.
-        let (fn_index, param_type_iter, ret_type) =
+        let (fn_index, param_type_iter, return_count) =
             self.call_export_help_before_arg_load(module, fn_name)?;
         let n_args = param_type_iter.len();
         for (value_bytes, value_type) in arg_strings
@@ -225,14 +257,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.value_store.push(value);
         }
 
-        self.call_export_help_after_arg_load(module, fn_index, n_args, ret_type)
+        self.call_export_help_after_arg_load(module, fn_index, n_args, return_count)
     }
 
     fn call_export_help_before_arg_load<'m>(
         &mut self,
         module: &'m WasmModule<'a>,
         fn_name: &str,
-    ) -> Result<(usize, SignatureParamsIter<'m>, Option<ValueType>), String> {
+    ) -> Result<(usize, SignatureParamsIter<'m>, usize), String> {
         let fn_index = {
             let mut export_iter = module.export.exports.iter();
             export_iter
@@ -272,10 +304,9 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             cursor
         };
 
-        let (param_type_iter, return_type) = {
-            let signature_index = module.function.signatures[internal_fn_index];
-            module.types.look_up(signature_index)
-        };
+        let signature_index = module.function.signatures[internal_fn_index];
+        let param_type_iter = module.types.look_up(signature_index).0;
+        let return_count = module.types.look_up_ret_types(signature_index).len();
 
         if self.debug_string.is_some() {
             println!(
@@ -286,7 +317,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             );
         }
 
-        Ok((fn_index, param_type_iter, return_type))
+        Ok((fn_index, param_type_iter, return_count))
     }
 
     fn call_export_help_after_arg_load(
@@ -294,7 +325,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         module: &WasmModule<'a>,
         fn_index: usize,
         n_args: usize,
-        return_type: Option<ValueType>,
+        return_count: usize,
     ) -> Result<Option<Value>, String> {
         self.previous_frames.clear();
         self.blocks.clear();
@@ -307,7 +338,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             0, // return_addr
             self.blocks.len(),
             n_args,
-            return_type,
+            return_count,
             &module.code.bytes,
             &mut self.value_store,
             &mut self.program_counter,
@@ -316,8 +347,17 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             ty: BlockType::FunctionBody(fn_index),
             vstack: self.value_store.depth(),
         });
+        self.debugger_enter_function(fn_index);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.enter_function(fn_index);
+        }
 
         loop {
+            if self.debugger.is_some()
+                && self.debugger_before_instruction(module) == DebuggerAction::Quit
+            {
+                return Err("The program was stopped by the debugger".into());
+            }
             match self.execute_next_instruction(module) {
                 Ok(Action::Continue) => {}
                 Ok(Action::Break) => {
@@ -355,21 +395,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         let Frame {
             return_addr,
             body_block_index,
-            return_type,
+            return_count,
             ..
         } = self.current_frame;
 
-        // Throw away all locals and values except the return value
+        // Throw away all locals and values except the return values
         let locals_block_index = body_block_index - 1;
-        let locals_block = &self.blocks[locals_block_index];
-        let new_stack_depth = if return_type.is_some() {
-            self.value_store
-                .set(locals_block.vstack, self.value_store.peek());
-            locals_block.vstack + 1
-        } else {
-            locals_block.vstack
-        };
-        self.value_store.truncate(new_stack_depth);
+        let locals_block_vstack = self.blocks[locals_block_index].vstack;
+        self.move_values_down(locals_block_vstack, return_count);
 
         // Resume executing at the next instruction in the caller function
         let new_block_len = locals_block_index; // don't need a -1 because one is a length and the other is an index!
@@ -378,6 +411,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         // self.debug_values_and_blocks("end do_return");
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit_function();
+        }
+
         if let Some(caller_frame) = self.previous_frames.pop() {
             self.current_frame = caller_frame;
             Action::Continue
@@ -387,6 +424,18 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
     }
 
+    /// Move the top `count` values on the value stack down to `new_base`, discarding everything in between
+    fn move_values_down(&mut self, new_base: usize, count: usize) {
+        let old_base = self.value_store.depth() - count;
+        if old_base != new_base {
+            for i in 0..count {
+                let value = *self.value_store.get(old_base + i).unwrap();
+                self.value_store.set(new_base + i, value);
+            }
+        }
+        self.value_store.truncate(new_base + count);
+    }
+
     fn get_load_address(&mut self, module: &WasmModule<'a>) -> Result<u32, Error> {
         // Alignment is not used in the execution steps from the spec! Maybe it's just an optimization hint?
         // https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions
@@ -483,11 +532,38 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         self.blocks.truncate(target_block_depth);
     }
 
+    /// Tail call (`return_call`): the callee replaces the current frame, and returns directly to our caller
+    fn do_return_call(
+        &mut self,
+        expected_signature: Option<u32>,
+        fn_index: usize,
+        module: &WasmModule<'a>,
+    ) -> Result<Action, Error> {
+        if fn_index < self.import_count {
+            // Imports don't have frames to replace ours. Just call it and return its result.
+            self.do_call(expected_signature, fn_index, module, false)?;
+            return Ok(self.do_return());
+        }
+
+        let signature_index = module.function.signatures[fn_index - self.import_count];
+        let n_args = module.types.look_up(signature_index).0.len();
+
+        // Throw away our locals and blocks, keeping only the callee's arguments
+        let locals_block_index = self.current_frame.body_block_index - 1;
+        let locals_block_vstack = self.blocks[locals_block_index].vstack;
+        self.move_values_down(locals_block_vstack, n_args);
+        self.blocks.truncate(locals_block_index);
+
+        self.do_call(expected_signature, fn_index, module, true)?;
+        Ok(Action::Continue)
+    }
+
     fn do_call(
         &mut self,
         expected_signature: Option<u32>,
         fn_index: usize,
         module: &WasmModule<'a>,
+        is_tail_call: bool,
     ) -> Result<(), Error> {
         // self.debug_values_and_blocks(&format!("start do_call {}", fn_index));
 
@@ -514,6 +590,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         let (arg_type_iter, ret_type) = module.types.look_up(signature_index);
         let n_args = arg_type_iter.len();
+        let return_count = module.types.look_up_ret_types(signature_index).len();
         if self.debug_string.is_some() {
             self.debug_call(n_args, ret_type);
         }
@@ -531,6 +608,17 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.import_arguments[i] = arg;
             }
 
+            if let Some(profiler) = self.profiler.as_mut() {
+                profiler.call_import(fn_index);
+            }
+            if import.module == crate::wasi::MODULE_NAME && import.name == "proc_exit" {
+                // The dispatcher will exit the process, so this is our last chance.
+                // Failing to write the profile shouldn't change how the program exits.
+                if let Err(e) = self.finish_profile() {
+                    eprintln!("Failed to write the profile: {e}");
+                }
+            }
+
             let optional_return_val = self.import_dispatcher.dispatch(
                 import.module,
                 import.name,
@@ -544,7 +632,11 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 write!(debug_string, " {}.{}", import.module, import.name).unwrap();
             }
         } else {
-            let return_addr = self.program_counter;
+            let return_addr = if is_tail_call {
+                self.current_frame.return_addr
+            } else {
+                self.program_counter
+            };
             // set PC to start of function bytes
             let internal_fn_index = fn_index - self.import_count;
             self.program_counter = module.code.function_offsets[internal_fn_index] as usize;
@@ -562,18 +654,28 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 return_addr,
                 body_block_index,
                 n_args,
-                ret_type,
+                return_count,
                 &module.code.bytes,
                 &mut self.value_store,
                 &mut self.program_counter,
             );
             std::mem::swap(&mut swap_frame, &mut self.current_frame);
-            self.previous_frames.push(swap_frame);
+            if !is_tail_call {
+                self.previous_frames.push(swap_frame);
+            }
 
             self.blocks.push(Block {
                 ty: BlockType::FunctionBody(fn_index),
                 vstack: self.value_store.depth(),
             });
+            self.debugger_enter_function(fn_index);
+            if let Some(profiler) = self.profiler.as_mut() {
+                if is_tail_call {
+                    profiler.tail_call(fn_index);
+                } else {
+                    profiler.enter_function(fn_index);
+                }
+            }
         }
         // self.debug_values_and_blocks("end do_call");
 
@@ -615,6 +717,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.write_debug(op_code);
         }
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.count_instruction();
+        }
+
         let mut action = Action::Continue;
         let mut implicit_return = false;
 
@@ -732,7 +838,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             }
             CALL => {
                 let fn_index = self.fetch_immediate_u32(module) as usize;
-                self.do_call(None, fn_index, module)?;
+                self.do_call(None, fn_index, module, false)?;
             }
             CALLINDIRECT => {
                 let expected_signature = self.fetch_immediate_u32(module);
@@ -752,7 +858,30 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                     )
                 });
 
-                self.do_call(Some(expected_signature), fn_index as usize, module)?;
+                self.do_call(Some(expected_signature), fn_index as usize, module, false)?;
+            }
+            RETURNCALL => {
+                let fn_index = self.fetch_immediate_u32(module) as usize;
+                action = self.do_return_call(None, fn_index, module)?;
+            }
+            RETURNCALLINDIRECT => {
+                let expected_signature = self.fetch_immediate_u32(module);
+                let table_index = self.fetch_immediate_u32(module);
+                let element_index = self.value_store.pop_u32()?;
+
+                assert_eq!(
+                    table_index, 0,
+                    "Table index {table_index} not supported at file offset {file_offset:#x}. This interpreter only supports Wasm MVP."
+                );
+
+                let fn_index = module.element.lookup(element_index).unwrap_or_else(|| {
+                    panic!(
+                        "Indirect function call failed. There is no function with element index {element_index}"
+                    )
+                });
+
+                action =
+                    self.do_return_call(Some(expected_signature), fn_index as usize, module)?;
             }
             DROP => {
                 self.value_store.pop();
@@ -1751,7 +1880,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
 
         if let Some(debug_string) = &self.debug_string {
-            if matches!(op_code, CALL | CALLINDIRECT) {
+            if matches!(
+                op_code,
+                CALL | CALLINDIRECT | RETURNCALL | RETURNCALLINDIRECT
+            ) {
                 eprintln!("\n{file_offset:06x} {debug_string}");
             } else {
                 // For calls, we print special debug stuff in do_call
@@ -1812,7 +1944,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
     ///   locals   2: I32(412), 3: F64(3.14)
     ///   stack    [I64(111), F64(3.14)]
     /// --------------
-    fn debug_stack_trace(&self, buffer: &mut String) -> fmt::Result {
+    pub(crate) fn debug_stack_trace(&self, buffer: &mut String) -> fmt::Result {
         let divider = "-------------------";
         writeln!(buffer, "{divider}")?;
 
@@ -1853,14 +1985,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.module.types.look_up(signature_index).0.len()
             };
 
-            let fn_name = self
-                .module
-                .names
-                .function_names
-                .iter()
-                .find(|(idx, _)| *idx == *fn_index as u32)
-                .map(|(_, name)| *name)
-                .unwrap_or("");
+            let fn_name = self.debug_function_name(*fn_index);
 
             // Function and address match wasm-objdump formatting, for easy copy & find
             writeln!(buffer, "func[{fn_index}]  {fn_name}")?;
@@ -1896,6 +2021,17 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(())
     }
 
+    /// Look up a function name in the name section, if present
+    pub(crate) fn debug_function_name(&self, fn_index: usize) -> &'a str {
+        self.module
+            .names
+            .function_names
+            .iter()
+            .find(|(idx, _)| *idx == fn_index as u32)
+            .map(|(_, name)| *name)
+            .unwrap_or("")
+    }
+
     // Call address is more intuitive than the return address in the stack trace. Search backward for it.
     fn debug_return_addr_to_call_addr(&self, return_addr: usize) -> usize {
         // return_addr is pointing at the next instruction after the CALL/CALLINDIRECT.
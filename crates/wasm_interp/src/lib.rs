@@ -1,5 +1,7 @@
+mod debugger;
 mod frame;
 mod instance;
+mod profiler;
 #[cfg(test)]
 mod tests;
 
@@ -7,8 +9,10 @@ mod value_store;
 pub mod wasi;
 
 // Main external interface
+pub use debugger::Debugger;
 pub use instance::Instance;
-pub use wasi::{WasiDispatcher, WasiFile};
+pub use profiler::{FunctionStats, Profiler};
+pub use wasi::{FileSystem, MemoryFs, WasiDispatcher, WasiFile};
 
 pub use roc_wasm_module::Value;
 use roc_wasm_module::ValueType;
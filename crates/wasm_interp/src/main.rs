@@ -1,17 +1,27 @@
 use bumpalo::{collections::Vec, Bump};
 use clap::ArgAction;
-use clap::{Arg, Command};
+use clap::{value_parser, Arg, Command};
 use std::fs;
 use std::io;
 use std::iter::once;
+use std::path::PathBuf;
 use std::process;
 
-use roc_wasm_interp::{DefaultImportDispatcher, Instance};
+use roc_wasm_interp::{
+    Debugger, DefaultImportDispatcher, FileSystem, Instance, MemoryFs, Profiler,
+};
 use roc_wasm_module::WasmModule;
 
 pub const FLAG_FUNCTION: &str = "function";
 pub const FLAG_DEBUG: &str = "debug";
+pub const FLAG_DEBUGGER: &str = "debugger";
+pub const FLAG_BREAK: &str = "break";
+pub const FLAG_PROFILE: &str = "profile";
 pub const FLAG_HEX: &str = "hex";
+pub const FLAG_DIR: &str = "dir";
+pub const FLAG_MEMDIR: &str = "memdir";
+pub const FLAG_ENV: &str = "env";
+pub const FLAG_RANDOM_SEED: &str = "random-seed";
 pub const WASM_FILE: &str = "WASM_FILE";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
@@ -32,12 +42,59 @@ fn main() -> io::Result<()> {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_debugger = Arg::new(FLAG_DEBUGGER)
+        .long(FLAG_DEBUGGER)
+        .help("Run in an interactive step debugger. Type `help` at the prompt for a list of commands.")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_break = Arg::new(FLAG_BREAK)
+        .long(FLAG_BREAK)
+        .help("Pause in the debugger on entry to this function (uses names from the name section).\nCan be repeated. Implies --debugger.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_profile = Arg::new(FLAG_PROFILE)
+        .long(FLAG_PROFILE)
+        .value_name("FILE")
+        .help("Count instructions and calls per function. Print a summary, and write the call tree\nto FILE in folded-stacks format, for flamegraph tools.")
+        .value_parser(value_parser!(PathBuf))
+        .required(false);
+
     let flag_hex = Arg::new(FLAG_HEX)
         .long(FLAG_HEX)
         .help("If the called function returns a value, print it in hexadecimal format.")
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_dir = Arg::new(FLAG_DIR)
+        .long(FLAG_DIR)
+        .value_name("HOST_DIR[::GUEST_DIR]")
+        .help("Give the WASI program access to a host directory. Can be repeated.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_memdir = Arg::new(FLAG_MEMDIR)
+        .long(FLAG_MEMDIR)
+        .value_name("HOST_DIR[::GUEST_DIR]")
+        .help("Give the WASI program an in-memory copy of a host directory.\nThe program can write to it, but changes are never saved to the host. Can be repeated.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_env = Arg::new(FLAG_ENV)
+        .long(FLAG_ENV)
+        .value_name("KEY=VALUE")
+        .help("Set an environment variable for the WASI program. Can be repeated.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_random_seed = Arg::new(FLAG_RANDOM_SEED)
+        .long(FLAG_RANDOM_SEED)
+        .value_name("SEED")
+        .help("Make the WASI random_get function deterministic, using this seed")
+        .value_parser(value_parser!(u64))
+        .required(false);
+
     let wasm_file_to_run = Arg::new(WASM_FILE)
         .help("The .wasm file to run")
         .required(true);
@@ -50,7 +107,14 @@ fn main() -> io::Result<()> {
         .about("Run the given .wasm file")
         .arg(flag_function)
         .arg(flag_debug)
+        .arg(flag_debugger)
+        .arg(flag_break)
+        .arg(flag_profile)
         .arg(flag_hex)
+        .arg(flag_dir)
+        .arg(flag_memdir)
+        .arg(flag_env)
+        .arg(flag_random_seed)
         .arg(wasm_file_to_run)
         .arg(args_for_app);
 
@@ -59,6 +123,13 @@ fn main() -> io::Result<()> {
     let matches = app.get_matches();
     let start_fn_name = matches.get_one::<String>(FLAG_FUNCTION).unwrap();
     let is_debug_mode = matches.get_flag(FLAG_DEBUG);
+    let breakpoints: std::vec::Vec<String> = matches
+        .get_many::<String>(FLAG_BREAK)
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let is_debugger_mode = matches.get_flag(FLAG_DEBUGGER) || !breakpoints.is_empty();
+    let profile_path = matches.get_one::<PathBuf>(FLAG_PROFILE);
     let is_hex_format = matches.get_flag(FLAG_HEX);
     let start_arg_strings = matches.get_many::<String>(ARGS_FOR_APP).unwrap_or_default();
     let wasm_path = matches.get_one::<String>(WASM_FILE).unwrap();
@@ -87,17 +158,55 @@ fn main() -> io::Result<()> {
 
     // Create an execution instance
 
-    let dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    let mut dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+
+    for dir in matches.get_many::<String>(FLAG_DIR).unwrap_or_default() {
+        let (host_dir, guest_dir) = split_dir_mapping(dir);
+        let fs = FileSystem::Host(PathBuf::from(host_dir));
+        dispatcher.wasi.preopen(guest_dir, fs);
+    }
+    for dir in matches.get_many::<String>(FLAG_MEMDIR).unwrap_or_default() {
+        let (host_dir, guest_dir) = split_dir_mapping(dir);
+        let memory_fs = MemoryFs::from_host_dir(host_dir.as_ref()).unwrap_or_else(|e| {
+            eprintln!("I couldn't copy the directory {host_dir} into memory: {e}");
+            process::exit(1);
+        });
+        dispatcher
+            .wasi
+            .preopen(guest_dir, FileSystem::Memory(memory_fs));
+    }
+    for var in matches.get_many::<String>(FLAG_ENV).unwrap_or_default() {
+        match var.split_once('=') {
+            Some((key, value)) => dispatcher.wasi.set_env(key, value),
+            None => {
+                eprintln!("Environment variables should be in the form KEY=VALUE, but I got {var}");
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(seed) = matches.get_one::<u64>(FLAG_RANDOM_SEED) {
+        dispatcher.wasi.set_random_seed(*seed);
+    }
+
     let mut inst =
         Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap_or_else(|e| {
             eprintln!("{e}");
             process::exit(2);
         });
 
+    if is_debugger_mode {
+        inst.attach_debugger(Debugger::new(breakpoints));
+    }
+    if let Some(path) = profile_path {
+        inst.attach_profiler(Profiler::new(path.clone()));
+    }
+
     // Run
 
     let result = inst.call_export_from_cli(&module, start_fn_name, &wasi_argv);
 
+    inst.finish_profile()?;
+
     // Print out return value, if any
 
     match result {
@@ -117,3 +226,8 @@ fn main() -> io::Result<()> {
 
     Ok(())
 }
+
+/// Split `HOST_DIR::GUEST_DIR`. If there's no guest name, the program sees the host name.
+fn split_dir_mapping(mapping: &str) -> (&str, &str) {
+    mapping.split_once("::").unwrap_or((mapping, mapping))
+}
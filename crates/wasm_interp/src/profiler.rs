@@ -0,0 +1,197 @@
+//! Execution profiler, for the `--profile` CLI option.
+//!
+//! Counts executed instructions and calls per function. The interpreter is deterministic,
+//! so the counts are exactly reproducible, unlike timings.
+//!
+//! The call tree is written in the "folded stacks" format used by flamegraph tools
+//! (https://github.com/brendangregg/FlameGraph), with instruction counts as sample values:
+//! `main;List.map;#UserApp_f 1234`
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::PathBuf;
+
+use crate::{ImportDispatcher, Instance};
+
+/// A node in the call tree. Each distinct call stack has its own node.
+#[derive(Debug)]
+struct CallNode {
+    fn_index: usize,
+    parent: Option<usize>,
+    children: HashMap<usize, usize>,
+    /// Instructions executed in this function, not including callees
+    instructions: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionStats {
+    pub calls: u64,
+    /// Instructions executed in this function, not including callees
+    pub instructions: u64,
+}
+
+#[derive(Debug)]
+pub struct Profiler {
+    output_path: PathBuf,
+    nodes: Vec<CallNode>,
+    current_node: Option<usize>,
+    /// Indexed by function index
+    functions: Vec<FunctionStats>,
+}
+
+impl Profiler {
+    pub fn new(output_path: PathBuf) -> Self {
+        Profiler {
+            output_path,
+            nodes: Vec::new(),
+            current_node: None,
+            functions: Vec::new(),
+        }
+    }
+
+    fn stats_mut(&mut self, fn_index: usize) -> &mut FunctionStats {
+        if fn_index >= self.functions.len() {
+            self.functions
+                .resize(fn_index + 1, FunctionStats::default());
+        }
+        &mut self.functions[fn_index]
+    }
+
+    pub(crate) fn enter_function(&mut self, fn_index: usize) {
+        self.stats_mut(fn_index).calls += 1;
+
+        let existing_child = self
+            .current_node
+            .and_then(|parent| self.nodes[parent].children.get(&fn_index).copied());
+
+        let node = existing_child.unwrap_or_else(|| {
+            let new_node = self.nodes.len();
+            self.nodes.push(CallNode {
+                fn_index,
+                parent: self.current_node,
+                children: HashMap::new(),
+                instructions: 0,
+            });
+            if let Some(parent) = self.current_node {
+                self.nodes[parent].children.insert(fn_index, new_node);
+            }
+            new_node
+        });
+
+        self.current_node = Some(node);
+    }
+
+    pub(crate) fn exit_function(&mut self) {
+        if let Some(node) = self.current_node {
+            self.current_node = self.nodes[node].parent;
+        }
+    }
+
+    /// A tail call replaces the current function on the call stack
+    pub(crate) fn tail_call(&mut self, fn_index: usize) {
+        self.exit_function();
+        self.enter_function(fn_index);
+    }
+
+    /// Imported functions don't execute any Wasm instructions, but we still count their calls
+    pub(crate) fn call_import(&mut self, fn_index: usize) {
+        self.stats_mut(fn_index).calls += 1;
+    }
+
+    pub(crate) fn count_instruction(&mut self) {
+        if let Some(node) = self.current_node {
+            let fn_index = self.nodes[node].fn_index;
+            self.nodes[node].instructions += 1;
+            self.stats_mut(fn_index).instructions += 1;
+        }
+    }
+
+    pub fn function_stats(&self, fn_index: usize) -> FunctionStats {
+        self.functions.get(fn_index).copied().unwrap_or_default()
+    }
+
+    pub fn total_instructions(&self) -> u64 {
+        self.nodes.iter().map(|node| node.instructions).sum()
+    }
+
+    /// Folded stacks, sorted so that the output is stable across runs
+    pub fn folded_stacks<I: ImportDispatcher>(&self, inst: &Instance<'_, I>) -> String {
+        let mut lines = Vec::with_capacity(self.nodes.len());
+        let mut stack = Vec::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.instructions == 0 {
+                continue;
+            }
+            stack.clear();
+            let mut current = Some(index);
+            while let Some(i) = current {
+                // Flamegraph tools use semicolons and spaces as separators
+                let name = function_name(inst, self.nodes[i].fn_index);
+                stack.push(name.replace([';', ' '], "_"));
+                current = self.nodes[i].parent;
+            }
+            stack.reverse();
+            lines.push(format!("{} {}", stack.join(";"), node.instructions));
+        }
+        lines.sort();
+
+        let mut output = lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// A table of per-function counts, with the most expensive functions first
+    pub fn summary<I: ImportDispatcher>(&self, inst: &Instance<'_, I>) -> String {
+        let mut rows: Vec<(usize, FunctionStats)> = self
+            .functions
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, stats)| stats.calls > 0)
+            .collect();
+        rows.sort_by(|(a_index, a), (b_index, b)| {
+            b.instructions
+                .cmp(&a.instructions)
+                .then(b.calls.cmp(&a.calls))
+                .then(a_index.cmp(b_index))
+        });
+
+        let mut output = String::new();
+        writeln!(output, "{:>14} {:>10}  function", "instructions", "calls").unwrap();
+        for (fn_index, stats) in rows {
+            writeln!(
+                output,
+                "{:>14} {:>10}  {}",
+                stats.instructions,
+                stats.calls,
+                function_name(inst, fn_index)
+            )
+            .unwrap();
+        }
+        writeln!(
+            output,
+            "{:>14} {:>10}  total",
+            self.total_instructions(),
+            ""
+        )
+        .unwrap();
+        output
+    }
+
+    /// Write the folded stacks to the output file, and the summary to stderr
+    pub fn finish<I: ImportDispatcher>(&self, inst: &Instance<'_, I>) -> io::Result<()> {
+        std::fs::write(&self.output_path, self.folded_stacks(inst))?;
+        eprint!("{}", self.summary(inst));
+        eprintln!("Wrote profile to {}", self.output_path.display());
+        Ok(())
+    }
+}
+
+/// Function name from the name section, or a placeholder like the one wasm-objdump uses
+fn function_name<I: ImportDispatcher>(inst: &Instance<'_, I>, fn_index: usize) -> String {
+    match inst.debug_function_name(fn_index) {
+        "" => format!("func[{fn_index}]"),
+        name => name.to_string(),
+    }
+}
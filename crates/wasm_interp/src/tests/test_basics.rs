@@ -9,8 +9,8 @@ use crate::{DefaultImportDispatcher, ImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::sections::{Import, ImportDesc};
 use roc_wasm_module::{
-    opcodes::OpCode, sections::ElementSegment, Export, ExportType, SerialBuffer, Serialize,
-    Signature, Value, ValueType, WasmModule,
+    opcodes::OpCode, sections::ElementSegment, Export, ExportType, MultiValueSignature,
+    SerialBuffer, Serialize, Signature, Value, ValueType, WasmModule,
 };
 
 #[test]
@@ -700,6 +700,121 @@ fn test_call_return_with_args() {
     assert_eq!(result, Value::I32(4));
 }
 
+#[test]
+fn test_return_call() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Mutually recursive functions, deep enough that real calls would use a lot of frames
+    let signature = || Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+
+    // Function 0: is_even(n) = if n == 0 then 1 else is_odd(n - 1)
+    create_exported_function_no_locals(&mut module, "is_even", signature(), |buf| {
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.push(0);
+        buf.push(OpCode::I32EQZ as u8);
+        buf.push(OpCode::IF as u8);
+        buf.push(ValueType::VOID);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(1);
+        buf.push(OpCode::RETURN as u8);
+        buf.push(OpCode::END as u8);
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.push(0);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(1);
+        buf.push(OpCode::I32SUB as u8);
+        buf.push(OpCode::RETURNCALL as u8);
+        buf.push(1);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: is_odd(n) = if n == 0 then 0 else is_even(n - 1)
+    create_exported_function_no_locals(&mut module, "is_odd", signature(), |buf| {
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.push(0);
+        buf.push(OpCode::I32EQZ as u8);
+        buf.push(OpCode::IF as u8);
+        buf.push(ValueType::VOID);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(0);
+        buf.push(OpCode::RETURN as u8);
+        buf.push(OpCode::END as u8);
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.push(0);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(1);
+        buf.push(OpCode::I32SUB as u8);
+        buf.push(OpCode::RETURNCALL as u8);
+        buf.push(0);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+
+    let result = inst.call_export("is_even", [Value::I32(100_001)]).unwrap();
+    assert_eq!(result, Some(Value::I32(0)));
+
+    let result = inst.call_export("is_odd", [Value::I32(100_001)]).unwrap();
+    assert_eq!(result, Some(Value::I32(1)));
+}
+
+#[test]
+fn test_multi_value_return() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0: call function 1 and subtract its two return values
+    let signature0 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: Some(ValueType::I64),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature0, |buf| {
+        buf.push(OpCode::CALL as u8);
+        buf.push(1);
+        buf.push(OpCode::I64SUB as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: return two values, with a block and some locals to clean up
+    let func1_offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(func1_offset);
+    module.code.function_count += 1;
+    module.add_multi_value_function_signature(MultiValueSignature {
+        param_types: bumpalo::vec![in &arena],
+        ret_types: &[ValueType::I64, ValueType::I64],
+    });
+    [
+        1, // 1 group of locals
+        2, // 2 locals
+        ValueType::I32 as u8,
+        OpCode::BLOCK as u8,
+        ValueType::VOID,
+        OpCode::I32CONST as u8,
+        9,
+        OpCode::SETLOCAL as u8,
+        0,
+        OpCode::END as u8,
+        OpCode::I64CONST as u8,
+        50,
+        OpCode::I64CONST as u8,
+        8,
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    let result = inst.call_export("test", []).unwrap();
+
+    assert_eq!(result, Some(Value::I64(42)));
+    assert!(inst.value_store.is_empty());
+}
+
 #[test]
 fn test_call_indirect_ok() {
     let result = test_call_indirect_help(0, 0);
@@ -839,13 +954,13 @@ fn test_set_get_local() {
     let return_addr = 0x1234;
     let return_block_depth = 0;
     let n_args = 0;
-    let ret_type = Some(ValueType::I32);
+    let return_count = 1;
     inst.current_frame = Frame::enter(
         fn_index,
         return_addr,
         return_block_depth,
         n_args,
-        ret_type,
+        return_count,
         &buffer,
         &mut inst.value_store,
         &mut cursor,
@@ -886,13 +1001,13 @@ fn test_tee_get_local() {
     let return_addr = 0x1234;
     let return_block_depth = 0;
     let n_args = 0;
-    let ret_type = Some(ValueType::I32);
+    let return_count = 1;
     inst.current_frame = Frame::enter(
         fn_index,
         return_addr,
         return_block_depth,
         n_args,
-        ret_type,
+        return_count,
         &buffer,
         &mut inst.value_store,
         &mut cursor,
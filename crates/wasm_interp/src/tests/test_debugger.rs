@@ -0,0 +1,222 @@
+use super::default_state;
+use crate::debugger::{
+    decode_roc_str, display_memory, display_roc_list, Address, Command, DebuggerAction, DecodedStr,
+    ElemFormat,
+};
+use crate::frame::Frame;
+use crate::Debugger;
+use bumpalo::Bump;
+use roc_wasm_module::{Serialize, Value, ValueType, WasmModule};
+
+fn write_u32(memory: &mut [u8], addr: usize, value: u32) {
+    memory[addr..addr + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(Command::parse("s"), Ok(Command::Step(1)));
+    assert_eq!(Command::parse("step 10"), Ok(Command::Step(10)));
+    assert_eq!(Command::parse("c"), Ok(Command::Continue));
+    assert_eq!(
+        Command::parse("break List.len"),
+        Ok(Command::Break("List.len".into()))
+    );
+    assert_eq!(
+        Command::parse("x 0x100"),
+        Ok(Command::Memory(Address::Literal(0x100), 64))
+    );
+    assert_eq!(
+        Command::parse("x 256 16"),
+        Ok(Command::Memory(Address::Literal(256), 16))
+    );
+    assert_eq!(
+        Command::parse("str $2"),
+        Ok(Command::Str(Address::Local(2)))
+    );
+    assert_eq!(
+        Command::parse("list $0 i64"),
+        Ok(Command::List(Address::Local(0), ElemFormat::I64))
+    );
+    assert!(Command::parse("break").is_err());
+    assert!(Command::parse("list $0 u128").is_err());
+    assert!(Command::parse("x zzz").is_err());
+    assert!(Command::parse("frobnicate").is_err());
+}
+
+#[test]
+fn test_decode_small_str() {
+    let mut memory = vec![0; 64];
+    memory[16..21].copy_from_slice(b"hello");
+    memory[27] = 0x80 | 5;
+
+    assert_eq!(
+        decode_roc_str(&memory, 16),
+        Ok(DecodedStr::Small("hello".into()))
+    );
+}
+
+#[test]
+fn test_decode_heap_str() {
+    let mut memory = vec![0; 128];
+    let text = b"This string is too long to be small";
+    memory[64..64 + text.len()].copy_from_slice(text);
+    write_u32(&mut memory, 0, 64);
+    write_u32(&mut memory, 4, text.len() as u32);
+    write_u32(&mut memory, 8, 40);
+
+    // seamless slice of the first 4 bytes
+    write_u32(&mut memory, 12, 64);
+    write_u32(&mut memory, 16, 0x8000_0000 | 4);
+    write_u32(&mut memory, 20, 60 >> 1);
+
+    assert_eq!(
+        decode_roc_str(&memory, 0),
+        Ok(DecodedStr::Heap {
+            text: String::from_utf8(text.to_vec()).unwrap(),
+            elements: 64,
+            capacity: 40,
+            is_seamless_slice: false,
+        })
+    );
+    assert_eq!(decode_roc_str(&memory, 12).unwrap().text(), "This");
+}
+
+#[test]
+fn test_decode_str_out_of_bounds() {
+    let mut memory = vec![0; 32];
+    write_u32(&mut memory, 0, 1000);
+    write_u32(&mut memory, 4, 20);
+    write_u32(&mut memory, 8, 20);
+
+    assert!(decode_roc_str(&memory, 0).is_err());
+    assert!(decode_roc_str(&memory, 30).is_err());
+}
+
+#[test]
+fn test_display_list() {
+    let mut memory = vec![0; 128];
+    write_u32(&mut memory, 0, 32);
+    write_u32(&mut memory, 4, 3);
+    write_u32(&mut memory, 8, 4);
+    for (i, x) in [10, -20, 30].iter().enumerate() {
+        memory[32 + 4 * i..36 + 4 * i].copy_from_slice(&i32::to_le_bytes(*x));
+    }
+
+    let mut out = String::new();
+    display_roc_list(&memory, 0, ElemFormat::I32, &mut out).unwrap();
+    assert_eq!(out, "[10, -20, 30] (at 0x20, len 3, capacity 4)\n");
+}
+
+#[test]
+fn test_display_list_of_str() {
+    let mut memory = vec![0; 128];
+    write_u32(&mut memory, 0, 32);
+    write_u32(&mut memory, 4, 2);
+    write_u32(&mut memory, 8, 2);
+    memory[32..34].copy_from_slice(b"hi");
+    memory[43] = 0x80 | 2;
+    memory[44..47].copy_from_slice(b"roc");
+    memory[55] = 0x80 | 3;
+
+    let mut out = String::new();
+    display_roc_list(&memory, 0, ElemFormat::Str, &mut out).unwrap();
+    assert_eq!(out, "[\"hi\", \"roc\"] (at 0x20, len 2, capacity 2)\n");
+}
+
+#[test]
+fn test_display_list_with_bad_pointer() {
+    let mut memory = vec![0; 32];
+    write_u32(&mut memory, 0, u32::MAX - 3);
+    write_u32(&mut memory, 4, 3);
+    write_u32(&mut memory, 8, 3);
+
+    let mut out = String::new();
+    assert!(display_roc_list(&memory, 0, ElemFormat::I64, &mut out).is_err());
+    assert!(display_roc_list(&memory, u32::MAX - 3, ElemFormat::I64, &mut out).is_err());
+    assert_eq!(out, "");
+}
+
+#[test]
+fn test_display_memory() {
+    let mut memory = vec![0; 64];
+    memory[0..4].copy_from_slice(b"Roc!");
+
+    let mut out = String::new();
+    display_memory(&memory, 0, 20, &mut out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("00000000  52 6f 63 21 00"));
+    assert!(lines[0].ends_with("|Roc!............|"));
+    assert!(lines[1].starts_with("00000010  00 00 00 00"));
+}
+
+#[test]
+fn test_breakpoint_pauses_on_function_entry() {
+    let arena = Bump::new();
+    let mut inst = default_state(&arena);
+    let mut module = WasmModule::new(&arena);
+    module.names.append_function(0, "main");
+    module.names.append_function(1, "helper");
+    inst.module = arena.alloc(module);
+
+    inst.attach_debugger(Debugger::new(vec!["helper".into()]));
+
+    inst.debugger_enter_function(0);
+    assert!(!inst.debugger.as_mut().unwrap().should_pause());
+
+    inst.debugger_enter_function(1);
+    assert!(inst.debugger.as_mut().unwrap().should_pause());
+}
+
+#[test]
+fn test_step_count() {
+    let arena = Bump::new();
+    let mut inst = default_state(&arena);
+    let module = WasmModule::new(&arena);
+    inst.attach_debugger(Debugger::new(vec![]));
+
+    // With no breakpoints, we start paused
+    assert!(inst.debugger.as_mut().unwrap().should_pause());
+
+    let mut out = String::new();
+    let action = inst.run_debugger_command(&module, &Command::Step(3), &mut out);
+    assert_eq!(action, DebuggerAction::Resume);
+
+    // The current instruction and 2 more run before we pause again
+    let debugger = inst.debugger.as_mut().unwrap();
+    assert!(!debugger.should_pause());
+    assert!(!debugger.should_pause());
+    assert!(debugger.should_pause());
+}
+
+#[test]
+fn test_inspect_str_in_local() {
+    let arena = Bump::new();
+    let mut inst = default_state(&arena);
+    let module = WasmModule::new(&arena);
+    inst.attach_debugger(Debugger::new(vec![]));
+
+    let str_addr = 0x100;
+    inst.memory[str_addr..str_addr + 3].copy_from_slice(b"abc");
+    inst.memory[str_addr + 11] = 0x80 | 3;
+
+    let mut buffer = vec![];
+    let mut cursor = 0;
+    [(1u32, ValueType::I32), (1u32, ValueType::I64)].serialize(&mut buffer);
+    inst.current_frame = Frame::enter(0, 0, 0, 0, 0, &buffer, &mut inst.value_store, &mut cursor);
+    inst.current_frame
+        .set_local(&mut inst.value_store, 0, Value::I32(str_addr as i32));
+
+    let mut out = String::new();
+    let action = inst.run_debugger_command(&module, &Command::Str(Address::Local(0)), &mut out);
+    assert_eq!(action, DebuggerAction::Prompt);
+    assert_eq!(out, "\"abc\" (small string, len 3)\n");
+
+    out.clear();
+    inst.run_debugger_command(&module, &Command::Str(Address::Local(1)), &mut out);
+    assert_eq!(out, "$1 is I64(0), which is not an address\n");
+
+    out.clear();
+    inst.run_debugger_command(&module, &Command::Locals, &mut out);
+    assert_eq!(out, "$0 = I32(256)\n$1 = I64(0)\n");
+}
@@ -0,0 +1,110 @@
+use super::create_exported_function_no_locals;
+use crate::{DefaultImportDispatcher, FunctionStats, Instance, Profiler};
+use bumpalo::Bump;
+use roc_wasm_module::{opcodes::OpCode, Signature, Value, ValueType, WasmModule};
+
+fn create_test_module(arena: &Bump) -> WasmModule<'_> {
+    let mut module = WasmModule::new(arena);
+    let signature = || Signature {
+        param_types: bumpalo::vec![in arena],
+        ret_type: Some(ValueType::I32),
+    };
+
+    // Function 0: call the helper twice
+    create_exported_function_no_locals(&mut module, "main", signature(), |buf| {
+        buf.push(OpCode::CALL as u8);
+        buf.push(1);
+        buf.push(OpCode::CALL as u8);
+        buf.push(1);
+        buf.push(OpCode::I32ADD as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: return a constant
+    create_exported_function_no_locals(&mut module, "helper", signature(), |buf| {
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(5);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 2: tail call to the helper
+    create_exported_function_no_locals(&mut module, "bounce", signature(), |buf| {
+        buf.push(OpCode::RETURNCALL as u8);
+        buf.push(1);
+        buf.push(OpCode::END as u8);
+    });
+
+    module.names.append_function(0, "main");
+    module.names.append_function(1, "helper");
+    module.names.append_function(2, "bounce");
+    module
+}
+
+#[test]
+fn test_profile_calls() {
+    let arena = Bump::new();
+    let module = create_test_module(&arena);
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.attach_profiler(Profiler::new("unused.folded".into()));
+
+    let result = inst.call_export("main", []).unwrap();
+    assert_eq!(result, Some(Value::I32(10)));
+
+    let profiler = inst.profiler().unwrap();
+    assert_eq!(
+        profiler.function_stats(0),
+        FunctionStats {
+            calls: 1,
+            instructions: 4
+        }
+    );
+    assert_eq!(
+        profiler.function_stats(1),
+        FunctionStats {
+            calls: 2,
+            instructions: 4
+        }
+    );
+    assert_eq!(profiler.total_instructions(), 8);
+    assert_eq!(profiler.folded_stacks(&inst), "main 4\nmain;helper 4\n");
+}
+
+#[test]
+fn test_profile_tail_call() {
+    let arena = Bump::new();
+    let module = create_test_module(&arena);
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.attach_profiler(Profiler::new("unused.folded".into()));
+
+    let result = inst.call_export("bounce", []).unwrap();
+    assert_eq!(result, Some(Value::I32(5)));
+
+    // The helper replaces `bounce` on the call stack
+    let profiler = inst.profiler().unwrap();
+    assert_eq!(profiler.folded_stacks(&inst), "bounce 1\nhelper 2\n");
+}
+
+#[test]
+fn test_profile_summary() {
+    let arena = Bump::new();
+    let module = create_test_module(&arena);
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.attach_profiler(Profiler::new("unused.folded".into()));
+
+    inst.call_export("main", []).unwrap();
+
+    let summary = inst.profiler().unwrap().summary(&inst);
+    let lines: Vec<&str> = summary.lines().map(|line| line.trim()).collect();
+    assert_eq!(
+        lines,
+        [
+            "instructions      calls  function",
+            "4          2  helper",
+            "4          1  main",
+            "8             total",
+        ]
+    );
+}
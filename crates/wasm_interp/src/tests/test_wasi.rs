@@ -0,0 +1,474 @@
+use crate::wasi::{Errno, FileSystem, MemoryFs, WasiDispatcher};
+use roc_wasm_module::Value;
+
+// Memory layout for these tests
+const OUT_PARAM: usize = 0;
+const IOVECS: usize = 64;
+const PATH: usize = 128;
+const DATA: usize = 256;
+const BUFFER: usize = 1024;
+
+const OFLAGS_CREAT: i32 = 1;
+const OFLAGS_TRUNC: i32 = 8;
+const RIGHTS_READ_WRITE: i64 = (1 << 1) | (1 << 6);
+
+/// Call a WASI function and return the error code
+fn call(wasi: &mut WasiDispatcher, name: &str, args: &[Value], memory: &mut [u8]) -> i32 {
+    let errno = wasi.dispatch(name, args, memory).unwrap();
+    errno.expect_i32().unwrap()
+}
+
+fn read_u32(memory: &[u8], addr: usize) -> u32 {
+    u32::from_le_bytes(memory[addr..][..4].try_into().unwrap())
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    u64::from_le_bytes(memory[addr..][..8].try_into().unwrap())
+}
+
+fn write_u32(memory: &mut [u8], addr: usize, value: u32) {
+    memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn i32(x: usize) -> Value {
+    Value::I32(x as i32)
+}
+
+fn open(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    dir_fd: usize,
+    path: &str,
+    oflags: i32,
+) -> Result<usize, i32> {
+    memory[PATH..][..path.len()].copy_from_slice(path.as_bytes());
+    let args = [
+        i32(dir_fd),
+        Value::I32(0),
+        i32(PATH),
+        i32(path.len()),
+        Value::I32(oflags),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I32(0),
+        i32(OUT_PARAM),
+    ];
+    match call(wasi, "path_open", &args, memory) {
+        0 => Ok(read_u32(memory, OUT_PARAM) as usize),
+        errno => Err(errno),
+    }
+}
+
+fn path_call(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    name: &str,
+    dir_fd: usize,
+    path: &str,
+) -> i32 {
+    memory[PATH..][..path.len()].copy_from_slice(path.as_bytes());
+    call(
+        wasi,
+        name,
+        &[i32(dir_fd), i32(PATH), i32(path.len())],
+        memory,
+    )
+}
+
+/// Read using two iovecs, to check that we step through the array
+fn read(wasi: &mut WasiDispatcher, memory: &mut [u8], fd: usize, iov_len: usize) -> Vec<u8> {
+    write_u32(memory, IOVECS, BUFFER as u32);
+    write_u32(memory, IOVECS + 4, iov_len as u32);
+    write_u32(memory, IOVECS + 8, (BUFFER + iov_len) as u32);
+    write_u32(memory, IOVECS + 12, iov_len as u32);
+    let args = [i32(fd), i32(IOVECS), Value::I32(2), i32(OUT_PARAM)];
+    assert_eq!(call(wasi, "fd_read", &args, memory), Errno::Success as i32);
+    let n_read = read_u32(memory, OUT_PARAM) as usize;
+    memory[BUFFER..][..n_read].to_vec()
+}
+
+fn write(wasi: &mut WasiDispatcher, memory: &mut [u8], fd: usize, bytes: &[u8]) {
+    memory[DATA..][..bytes.len()].copy_from_slice(bytes);
+    write_u32(memory, IOVECS, DATA as u32);
+    write_u32(memory, IOVECS + 4, bytes.len() as u32);
+    let args = [i32(fd), i32(IOVECS), Value::I32(1), i32(OUT_PARAM)];
+    assert_eq!(call(wasi, "fd_write", &args, memory), Errno::Success as i32);
+    assert_eq!(read_u32(memory, OUT_PARAM) as usize, bytes.len());
+}
+
+fn read_dir_names(wasi: &mut WasiDispatcher, memory: &mut [u8], fd: usize) -> Vec<String> {
+    let buf_len = 512;
+    let args = [
+        i32(fd),
+        i32(BUFFER),
+        i32(buf_len),
+        Value::I64(0),
+        i32(OUT_PARAM),
+    ];
+    assert_eq!(
+        call(wasi, "fd_readdir", &args, memory),
+        Errno::Success as i32
+    );
+    let used = read_u32(memory, OUT_PARAM) as usize;
+    assert!(used < buf_len);
+
+    let mut names = vec![];
+    let mut ptr = BUFFER;
+    while ptr < BUFFER + used {
+        let name_len = read_u32(memory, ptr + 16) as usize;
+        let name = &memory[ptr + 24..][..name_len];
+        names.push(String::from_utf8(name.to_vec()).unwrap());
+        ptr += 24 + name_len;
+    }
+    names
+}
+
+fn memory_wasi() -> WasiDispatcher<'static> {
+    let mut memory_fs = MemoryFs::new();
+    memory_fs.create_file("input.txt", b"hello world".to_vec());
+    memory_fs.create_file("sub/nested.txt", b"nested".to_vec());
+
+    let mut wasi = WasiDispatcher::default();
+    let fd = wasi.preopen("/data", FileSystem::Memory(memory_fs));
+    assert_eq!(fd, 3);
+    wasi
+}
+
+#[test]
+fn test_preopen_names() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    assert_eq!(
+        call(
+            &mut wasi,
+            "fd_prestat_get",
+            &[i32(3), i32(OUT_PARAM)],
+            &mut memory
+        ),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_PARAM), 0);
+    assert_eq!(read_u32(&memory, OUT_PARAM + 4), 5);
+
+    let args = [i32(3), i32(BUFFER), Value::I32(5)];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_dir_name", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(&memory[BUFFER..][..5], b"/data");
+
+    // WASI libc stops looking for preopens at the first Badf
+    assert_eq!(
+        call(
+            &mut wasi,
+            "fd_prestat_get",
+            &[i32(4), i32(OUT_PARAM)],
+            &mut memory
+        ),
+        Errno::Badf as i32
+    );
+}
+
+#[test]
+fn test_read_memory_file() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    let fd = open(&mut wasi, &mut memory, 3, "input.txt", 0).unwrap();
+    assert_eq!(fd, 4);
+    assert_eq!(read(&mut wasi, &mut memory, fd, 4), b"hello wo");
+    assert_eq!(read(&mut wasi, &mut memory, fd, 4), b"rld");
+    assert_eq!(read(&mut wasi, &mut memory, fd, 4), b"");
+
+    let nested = open(&mut wasi, &mut memory, 3, "sub/../sub/nested.txt", 0).unwrap();
+    assert_eq!(nested, 5);
+    assert_eq!(read(&mut wasi, &mut memory, nested, 8), b"nested");
+
+    // Closed file descriptors are reused
+    assert_eq!(
+        call(&mut wasi, "fd_close", &[i32(fd)], &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(open(&mut wasi, &mut memory, 3, "input.txt", 0), Ok(4));
+}
+
+#[test]
+fn test_open_errors() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    assert_eq!(
+        open(&mut wasi, &mut memory, 3, "missing.txt", 0),
+        Err(Errno::Noent as i32)
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, 3, "../escape.txt", OFLAGS_CREAT),
+        Err(Errno::Notcapable as i32)
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, 3, "/etc/passwd", 0),
+        Err(Errno::Notcapable as i32)
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, 3, "input.txt/child", OFLAGS_CREAT),
+        Err(Errno::Notdir as i32)
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, 1, "input.txt", 0),
+        Err(Errno::Notdir as i32)
+    );
+}
+
+#[test]
+fn test_write_memory_file() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    assert_eq!(
+        open(&mut wasi, &mut memory, 3, "out/result.txt", OFLAGS_CREAT),
+        Err(Errno::Noent as i32)
+    );
+    assert_eq!(
+        path_call(&mut wasi, &mut memory, "path_create_directory", 3, "out"),
+        Errno::Success as i32
+    );
+    let fd = open(&mut wasi, &mut memory, 3, "out/result.txt", OFLAGS_CREAT).unwrap();
+    write(&mut wasi, &mut memory, fd, b"abc");
+
+    let seek_args = [i32(fd), Value::I64(1), Value::I32(0), i32(OUT_PARAM)];
+    assert_eq!(
+        call(&mut wasi, "fd_seek", &seek_args, &mut memory),
+        Errno::Success as i32
+    );
+    write(&mut wasi, &mut memory, fd, b"Z");
+    assert_eq!(
+        call(
+            &mut wasi,
+            "fd_tell",
+            &[i32(fd), i32(OUT_PARAM)],
+            &mut memory
+        ),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u64(&memory, OUT_PARAM), 2);
+
+    let memory_fs = wasi.memory_fs("/data").unwrap();
+    assert_eq!(memory_fs.read_file("out/result.txt"), Some(&b"aZc"[..]));
+
+    // Truncate
+    let fd = open(&mut wasi, &mut memory, 3, "input.txt", OFLAGS_TRUNC).unwrap();
+    write(&mut wasi, &mut memory, fd, b"bye");
+    let memory_fs = wasi.memory_fs("/data").unwrap();
+    assert_eq!(memory_fs.read_file("input.txt"), Some(&b"bye"[..]));
+}
+
+#[test]
+fn test_filestat() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    let args = [i32(3), Value::I32(0), i32(PATH), Value::I32(9), i32(BUFFER)];
+    memory[PATH..][..9].copy_from_slice(b"input.txt");
+    assert_eq!(
+        call(&mut wasi, "path_filestat_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(memory[BUFFER + 16], 4); // regular file
+    assert_eq!(read_u64(&memory, BUFFER + 32), 11); // size
+
+    let fd = open(&mut wasi, &mut memory, 3, "sub", 0).unwrap();
+    let args = [i32(fd), i32(BUFFER)];
+    assert_eq!(
+        call(&mut wasi, "fd_filestat_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(memory[BUFFER + 16], 3); // directory
+}
+
+#[test]
+fn test_readdir() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    assert_eq!(
+        read_dir_names(&mut wasi, &mut memory, 3),
+        [".", "..", "input.txt", "sub"]
+    );
+
+    // If the buffer is too small, it gets filled, and the program can continue from a cookie
+    let args = [
+        i32(3),
+        i32(BUFFER),
+        Value::I32(30),
+        Value::I64(1),
+        i32(OUT_PARAM),
+    ];
+    assert_eq!(
+        call(&mut wasi, "fd_readdir", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_PARAM), 30);
+    assert_eq!(read_u64(&memory, BUFFER), 2); // d_next
+    assert_eq!(&memory[BUFFER + 24..][..2], b"..");
+}
+
+#[test]
+fn test_unlink_and_rename() {
+    let mut wasi = memory_wasi();
+    let mut memory = vec![0; 4096];
+
+    assert_eq!(
+        path_call(&mut wasi, &mut memory, "path_remove_directory", 3, "sub"),
+        Errno::Notempty as i32
+    );
+    assert_eq!(
+        path_call(&mut wasi, &mut memory, "path_unlink_file", 3, "sub"),
+        Errno::Isdir as i32
+    );
+
+    let old = "sub/nested.txt";
+    let new = "moved.txt";
+    memory[PATH..][..old.len()].copy_from_slice(old.as_bytes());
+    memory[DATA..][..new.len()].copy_from_slice(new.as_bytes());
+    let args = [
+        i32(3),
+        i32(PATH),
+        i32(old.len()),
+        i32(3),
+        i32(DATA),
+        i32(new.len()),
+    ];
+    assert_eq!(
+        call(&mut wasi, "path_rename", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        path_call(&mut wasi, &mut memory, "path_remove_directory", 3, "sub"),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        path_call(&mut wasi, &mut memory, "path_unlink_file", 3, "input.txt"),
+        Errno::Success as i32
+    );
+
+    assert_eq!(read_dir_names(&mut wasi, &mut memory, 3), [".", "..", new]);
+    let memory_fs = wasi.memory_fs("/data").unwrap();
+    assert_eq!(memory_fs.read_file(new), Some(&b"nested"[..]));
+}
+
+#[test]
+fn test_host_dir() {
+    let host_dir =
+        std::env::temp_dir().join(format!("roc_wasm_interp_wasi_{}", std::process::id()));
+    std::fs::create_dir_all(&host_dir).unwrap();
+    std::fs::write(host_dir.join("input.txt"), "from the host").unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen(".", FileSystem::Host(host_dir.clone()));
+    let mut memory = vec![0; 4096];
+
+    let fd = open(&mut wasi, &mut memory, 3, "input.txt", 0).unwrap();
+    assert_eq!(read(&mut wasi, &mut memory, fd, 8), b"from the host");
+
+    let fd = open(&mut wasi, &mut memory, 3, "output.txt", OFLAGS_CREAT).unwrap();
+    write(&mut wasi, &mut memory, fd, b"from the guest");
+    assert_eq!(
+        call(&mut wasi, "fd_close", &[i32(fd)], &mut memory),
+        Errno::Success as i32
+    );
+
+    assert_eq!(
+        read_dir_names(&mut wasi, &mut memory, 3),
+        [".", "..", "input.txt", "output.txt"]
+    );
+    let written = std::fs::read_to_string(host_dir.join("output.txt"));
+    std::fs::remove_dir_all(&host_dir).unwrap();
+    assert_eq!(written.unwrap(), "from the guest");
+}
+
+#[test]
+fn test_environ() {
+    let mut wasi = WasiDispatcher::default();
+    wasi.set_env("HOME", "/home/roc");
+    wasi.set_env("LANG", "C");
+    wasi.set_env("HOME", "/data");
+    let mut memory = vec![0; 4096];
+
+    let args = [i32(OUT_PARAM), i32(OUT_PARAM + 4)];
+    assert_eq!(
+        call(&mut wasi, "environ_sizes_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_PARAM), 2);
+    assert_eq!(read_u32(&memory, OUT_PARAM + 4), 18);
+
+    let args = [i32(OUT_PARAM), i32(BUFFER)];
+    assert_eq!(
+        call(&mut wasi, "environ_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_PARAM), BUFFER as u32);
+    assert_eq!(read_u32(&memory, OUT_PARAM + 4), BUFFER as u32 + 7);
+    assert_eq!(&memory[BUFFER..][..18], b"LANG=C\0HOME=/data\0");
+}
+
+#[test]
+fn test_clocks_and_poll() {
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 4096];
+
+    let monotonic = 1;
+    let args = [Value::I32(monotonic), Value::I64(0), i32(OUT_PARAM)];
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    let before = read_u64(&memory, OUT_PARAM);
+
+    // Two relative clock subscriptions. Only the shorter one fires.
+    let timeouts = [(42u64, 1_000_000u64), (43, 60_000_000_000)];
+    for (i, (userdata, timeout)) in timeouts.iter().enumerate() {
+        let ptr = DATA + 48 * i;
+        memory[ptr..][..8].copy_from_slice(&userdata.to_le_bytes());
+        memory[ptr + 8] = 0; // clock
+        memory[ptr + 16..][..4].copy_from_slice(&(monotonic as u32).to_le_bytes());
+        memory[ptr + 24..][..8].copy_from_slice(&timeout.to_le_bytes());
+    }
+    let args = [i32(DATA), i32(BUFFER), Value::I32(2), i32(OUT_PARAM)];
+    assert_eq!(
+        call(&mut wasi, "poll_oneoff", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_PARAM), 1);
+    assert_eq!(read_u64(&memory, BUFFER), 42);
+
+    let args = [Value::I32(monotonic), Value::I64(0), i32(OUT_PARAM)];
+    call(&mut wasi, "clock_time_get", &args, &mut memory);
+    let after = read_u64(&memory, OUT_PARAM);
+    assert!(after - before >= 1_000_000);
+
+    let args = [Value::I32(99), Value::I64(0), i32(OUT_PARAM)];
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &args, &mut memory),
+        Errno::Inval as i32
+    );
+}
+
+#[test]
+fn test_random_seed() {
+    let mut memory = vec![0; 4096];
+    let mut outputs = vec![];
+    for _ in 0..2 {
+        let mut wasi = WasiDispatcher::default();
+        wasi.set_random_seed(1234);
+        let args = [i32(BUFFER), Value::I32(16)];
+        assert_eq!(
+            call(&mut wasi, "random_get", &args, &mut memory),
+            Errno::Success as i32
+        );
+        outputs.push(memory[BUFFER..][..16].to_vec());
+    }
+    assert_eq!(outputs[0], outputs[1]);
+    assert_ne!(outputs[0], vec![0; 16]);
+}
@@ -1,14 +1,27 @@
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use roc_wasm_module::Value;
-use std::io::{self, Read, StderrLock, StdoutLock, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, StderrLock, StdoutLock, Write};
 use std::process::exit;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub mod vfs;
+
+use vfs::{errno_from_io, host_path, resolve_path, MemoryNode};
+pub use vfs::{FileSystem, MemoryFs, Preopen};
 
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
 pub struct WasiDispatcher<'a> {
     pub args: &'a [&'a [u8]],
-    pub rng: ThreadRng,
+    /// Environment variables, as `KEY=VALUE` strings
+    pub env: Vec<String>,
+    pub rng: StdRng,
+    /// Indexed by file descriptor
     pub files: Vec<WasiFile>,
+    pub preopens: Vec<Preopen>,
+    start_time: Instant,
 }
 
 impl Default for WasiDispatcher<'_> {
@@ -22,12 +35,75 @@ pub enum WasiFile {
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    /// A directory inside one of the preopened filesystems. `path` is relative to the preopen.
+    Directory {
+        preopen: usize,
+        path: Vec<String>,
+    },
+    /// A regular file in an in-memory filesystem
+    MemoryFile {
+        preopen: usize,
+        inode: usize,
+        position: usize,
+        append: bool,
+    },
+    /// A regular file in a preopened host directory
+    HostFile(File),
+    Closed,
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    MemoryFile {
+        content: &'a mut Vec<u8>,
+        position: &'a mut usize,
+    },
+    HostFile(&'a mut File),
+}
+
+// https://github.com/WebAssembly/WASI/blob/snapshot-01/phases/snapshot/docs.md
+const CLOCK_REALTIME: u32 = 0;
+const CLOCK_MONOTONIC: u32 = 1;
+const CLOCK_PROCESS_CPUTIME: u32 = 2;
+const CLOCK_THREAD_CPUTIME: u32 = 3;
+
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+const OFLAGS_CREAT: u32 = 1;
+const OFLAGS_DIRECTORY: u32 = 2;
+const OFLAGS_EXCL: u32 = 4;
+const OFLAGS_TRUNC: u32 = 8;
+
+const FDFLAGS_APPEND: u32 = 1;
+
+const RIGHTS_FD_READ: u64 = 1 << 1;
+const RIGHTS_FD_WRITE: u64 = 1 << 6;
+const RIGHTS_ALL: u64 = (1 << 30) - 1;
+
+const WHENCE_SET: u8 = 0;
+const WHENCE_CUR: u8 = 1;
+const WHENCE_END: u8 = 2;
+
+const EVENTTYPE_CLOCK: u8 = 0;
+const EVENTTYPE_FD_READ: u8 = 1;
+const EVENTTYPE_FD_WRITE: u8 = 2;
+const SUBCLOCKFLAGS_ABSTIME: u16 = 1;
+
+const SUBSCRIPTION_SIZE: usize = 48;
+const EVENT_SIZE: usize = 32;
+const DIRENT_HEADER_SIZE: usize = 24;
+const FILESTAT_SIZE: usize = 64;
+
+struct FileStat {
+    filetype: u8,
+    inode: u64,
+    size: u64,
+    modified_ns: u64,
 }
 
 /// Implementation of WASI syscalls
@@ -38,15 +114,52 @@ impl<'a> WasiDispatcher<'a> {
     pub fn new(args: &'a [&'a [u8]]) -> Self {
         WasiDispatcher {
             args,
-            rng: thread_rng(),
+            env: Vec::new(),
+            rng: StdRng::from_entropy(),
             files: vec![
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
             ],
+            preopens: Vec::new(),
+            start_time: Instant::now(),
         }
     }
 
+    /// Make a directory available to the program under the name `guest_path`.
+    /// Returns its file descriptor.
+    pub fn preopen(&mut self, guest_path: impl Into<String>, fs: FileSystem) -> usize {
+        let preopen = self.preopens.len();
+        self.preopens.push(Preopen {
+            guest_path: guest_path.into(),
+            fs,
+        });
+        self.files.push(WasiFile::Directory {
+            preopen,
+            path: Vec::new(),
+        });
+        self.files.len() - 1
+    }
+
+    /// The in-memory filesystem preopened as `guest_path`, if any
+    pub fn memory_fs(&self, guest_path: &str) -> Option<&MemoryFs> {
+        self.preopens.iter().find_map(|p| match &p.fs {
+            FileSystem::Memory(memory_fs) if p.guest_path == guest_path => Some(memory_fs),
+            _ => None,
+        })
+    }
+
+    pub fn set_env(&mut self, key: &str, value: &str) {
+        let prefix = format!("{key}=");
+        self.env.retain(|var| !var.starts_with(&prefix));
+        self.env.push(format!("{key}={value}"));
+    }
+
+    /// Make `random_get` deterministic, for reproducible tests
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     pub fn dispatch(
         &mut self,
         function_name: &str,
@@ -54,22 +167,18 @@ impl<'a> WasiDispatcher<'a> {
         memory: &mut [u8],
     ) -> Option<Value> {
         let success_code = Some(Value::I32(Errno::Success as i32));
+        let arg_usize = |i: usize| arguments[i].expect_i32().unwrap() as u32 as usize;
+        let arg_u64 = |i: usize| arguments[i].expect_i64().unwrap() as u64;
+
         match function_name {
             "args_get" => {
                 // uint8_t ** argv,
-                let mut ptr_ptr_argv = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_ptr_argv = arguments[0].expect_i32().unwrap() as usize;
                 // uint8_t * argv_buf
-                let mut ptr_argv_buf = arguments[1].expect_i32().unwrap() as usize;
-
-                for arg in self.args {
-                    write_u32(memory, ptr_ptr_argv, ptr_argv_buf as u32);
-                    let bytes_target = &mut memory[ptr_argv_buf..][..arg.len()];
-                    bytes_target.copy_from_slice(arg);
-                    memory[ptr_argv_buf + arg.len()] = 0; // C string zero termination
-                    ptr_argv_buf += arg.len() + 1;
-                    ptr_ptr_argv += 4;
-                }
+                let ptr_argv_buf = arguments[1].expect_i32().unwrap() as usize;
 
+                let args = self.args.iter().copied();
+                write_string_array(memory, ptr_ptr_argv, ptr_argv_buf, args);
                 success_code
             }
             "args_sizes_get" => {
@@ -89,30 +198,74 @@ impl<'a> WasiDispatcher<'a> {
                 success_code
             }
             "environ_get" => {
-                // `environ_sizes_get` always reports 0 environment variables
-                // so we don't have to do anything here.
+                // Same memory layout as args_get
+                let ptr_ptr_environ = arg_usize(0);
+                let ptr_environ_buf = arg_usize(1);
 
+                let env = self.env.iter().map(|var| var.as_bytes());
+                write_string_array(memory, ptr_ptr_environ, ptr_environ_buf, env);
                 success_code
             }
             "environ_sizes_get" => {
-                let num_env_ptr = arguments[0].expect_i32().unwrap() as usize;
-                let size_env_ptr = arguments[1].expect_i32().unwrap() as usize;
+                let num_env_ptr = arg_usize(0);
+                let size_env_ptr = arg_usize(1);
 
-                // Calculate the total size required for environment variables
-                let total_size = 0;
-                let count = 0;
+                let count = self.env.len() as u32;
+                let total_size: u32 = self.env.iter().map(|var| 1 + var.len() as u32).sum();
 
                 write_u32(memory, num_env_ptr, count);
-                write_u32(memory, size_env_ptr, total_size as u32);
+                write_u32(memory, size_env_ptr, total_size);
 
                 success_code
             }
-            "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
-            "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "clock_res_get" => {
+                let clock_id = arg_usize(0) as u32;
+                let ptr_resolution = arg_usize(1);
+                if clock_id > CLOCK_THREAD_CPUTIME {
+                    return errno_value(Errno::Inval);
+                }
+                // Both of the Rust clocks we use have nanosecond precision
+                write_u64(memory, ptr_resolution, 1);
+                success_code
+            }
+            "clock_time_get" => {
+                let clock_id = arg_usize(0) as u32;
+                // arguments[1] is the maximum lag (precision), which we can ignore
+                let ptr_time = arg_usize(2);
+                match self.now(clock_id) {
+                    Ok(time) => {
+                        write_u64(memory, ptr_time, time);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
+            "fd_advise" => self.fd_only(arg_usize(0)),
+            "fd_allocate" => {
+                let fd = arg_usize(0);
+                let end = arg_u64(1) + arg_u64(2);
+                result_value(self.set_file_size(fd, end, false))
+            }
+            "fd_close" => {
+                let fd = arg_usize(0);
+                match self.files.get_mut(fd) {
+                    None | Some(WasiFile::Closed) => errno_value(Errno::Badf),
+                    Some(file) => {
+                        *file = WasiFile::Closed;
+                        success_code
+                    }
+                }
+            }
+            "fd_datasync" | "fd_sync" => {
+                let fd = arg_usize(0);
+                match self.files.get_mut(fd) {
+                    None | Some(WasiFile::Closed) => errno_value(Errno::Badf),
+                    Some(WasiFile::HostFile(file)) => {
+                        result_value(file.sync_all().map_err(|e| errno_from_io(&e)))
+                    }
+                    Some(_) => success_code,
+                }
+            }
             "fd_fdstat_get" => {
                 // (i32, i32) -> i32
 
@@ -121,9 +274,21 @@ impl<'a> WasiDispatcher<'a> {
                 // ptr to a wasi_fdstat_t
                 let stat_mut_ptr = arguments[1].expect_i32().unwrap() as usize;
 
-                match fd {
-                    1 => {
-                        // Tell WASI that stdout is a tty (no seek or tell)
+                let (filetype, flags, rights) = match self.files.get(fd) {
+                    None | Some(WasiFile::Closed) => return errno_value(Errno::Badf),
+                    Some(WasiFile::Directory { .. }) => (FILETYPE_DIRECTORY, 0, RIGHTS_ALL),
+                    Some(WasiFile::MemoryFile { append, .. }) => {
+                        let flags = if *append { FDFLAGS_APPEND as u16 } else { 0 };
+                        (FILETYPE_REGULAR_FILE, flags, RIGHTS_ALL)
+                    }
+                    Some(WasiFile::HostFile(_)) => (FILETYPE_REGULAR_FILE, 0, RIGHTS_ALL),
+                    Some(
+                        WasiFile::HostSystemFile
+                        | WasiFile::ReadOnly(_)
+                        | WasiFile::WriteOnly(_)
+                        | WasiFile::ReadWrite(_),
+                    ) => {
+                        // Tell WASI that stdio streams are a tty (no seek or tell)
                         // https://github.com/WebAssembly/wasi-libc/blob/659ff414560721b1660a19685110e484a081c3d4/libc-bottom-half/sources/isatty.c
                         // *Not* a tty if:
                         //     (statbuf.fs_filetype != __WASI_FILETYPE_CHARACTER_DEVICE ||
@@ -131,25 +296,66 @@ impl<'a> WasiDispatcher<'a> {
                         // So it's sufficient to set:
                         //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
                         //     .fs_rights_base = 0
+                        (FILETYPE_CHARACTER_DEVICE, 0, 0)
+                    }
+                };
 
-                        const WASI_FILETYPE_CHARACTER_DEVICE: u8 = 2;
-                        memory[stat_mut_ptr] = WASI_FILETYPE_CHARACTER_DEVICE;
+                // struct fdstat { u8 filetype; u16 flags; u64 rights_base; u64 rights_inheriting; }
+                memory[stat_mut_ptr..][..24].fill(0);
+                memory[stat_mut_ptr] = filetype;
+                memory[stat_mut_ptr + 2..][..2].copy_from_slice(&flags.to_le_bytes());
+                write_u64(memory, stat_mut_ptr + 8, rights);
+                write_u64(memory, stat_mut_ptr + 16, rights);
 
-                        for b in memory[stat_mut_ptr + 1..stat_mut_ptr + 24].iter_mut() {
-                            *b = 0;
-                        }
+                success_code
+            }
+            "fd_fdstat_set_flags" => {
+                let fd = arg_usize(0);
+                let flags = arg_usize(1) as u32;
+                match self.files.get_mut(fd) {
+                    None | Some(WasiFile::Closed) => errno_value(Errno::Badf),
+                    Some(WasiFile::MemoryFile { append, .. }) => {
+                        *append = flags & FDFLAGS_APPEND != 0;
+                        success_code
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
+                    Some(_) => success_code,
                 }
+            }
+            // We don't enforce rights, and we don't track file times
+            "fd_fdstat_set_rights" | "fd_filestat_set_times" => self.fd_only(arg_usize(0)),
+            "fd_filestat_get" => {
+                let fd = arg_usize(0);
+                let ptr_buf = arg_usize(1);
+                match self.fd_filestat(fd) {
+                    Ok(stat) => {
+                        write_filestat(memory, ptr_buf, &stat);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
+            "fd_filestat_set_size" => {
+                let fd = arg_usize(0);
+                let size = arg_u64(1);
+                result_value(self.set_file_size(fd, size, true))
+            }
+            "fd_pread" => {
+                let fd = arg_usize(0);
+                let iovs = read_iovecs(memory, arg_usize(1), arg_usize(2));
+                let offset = arg_u64(3);
+                let ptr_nread = arg_usize(4);
 
-                success_code
+                let result = self.with_position(fd, offset, |this, fd| {
+                    this.read_iovecs_from_file(fd, &iovs, memory)
+                });
+                match result {
+                    Ok(n_read) => {
+                        write_u32(memory, ptr_nread, n_read as u32);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_prestat_get" => {
                 // The preopened file descriptor to query
                 let fd = arguments[0].expect_i32().unwrap() as usize;
@@ -157,19 +363,54 @@ impl<'a> WasiDispatcher<'a> {
                 //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
                 //  preopen name length: 4 bytes
                 let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {fd} does not exist");
-                    Some(Value::I32(Errno::Badf as i32))
+
+                // WASI libc finds the preopens by querying every fd from 3 upwards, until it gets Badf
+                match self.preopen_of_fd(fd) {
+                    Some(preopen) => {
+                        let name_len = preopen.guest_path.len() as u32;
+                        write_u32(memory, ptr_buf, 0);
+                        write_u32(memory, ptr_buf + 4, name_len);
+                        success_code
+                    }
+                    None => errno_value(Errno::Badf),
                 }
             }
             "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
+                let fd = arg_usize(0);
+                let ptr_path = arg_usize(1);
+                let path_len = arg_usize(2);
+                match self.preopen_of_fd(fd) {
+                    Some(preopen) => {
+                        let name = preopen.guest_path.as_bytes();
+                        let len = name.len().min(path_len);
+                        memory[ptr_path..][..len].copy_from_slice(&name[..len]);
+                        success_code
+                    }
+                    None => errno_value(Errno::Badf),
+                }
+            }
+            "fd_pwrite" => {
+                let fd = arg_usize(0);
+                let iovs = read_iovecs(memory, arg_usize(1), arg_usize(2));
+                let offset = arg_u64(3);
+                let ptr_nwritten = arg_usize(4);
+
+                let result = self.with_position(fd, offset, |this, fd| {
+                    let mut n_written = 0;
+                    for &(iov_base, iov_len) in iovs.iter() {
+                        this.write_to_file(fd, &memory[iov_base..][..iov_len])?;
+                        n_written += iov_len;
+                    }
+                    Ok(n_written)
+                });
+                match result {
+                    Ok(n_written) => {
+                        write_u32(memory, ptr_nwritten, n_written as u32);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
             }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -178,7 +419,7 @@ impl<'a> WasiDispatcher<'a> {
                 // Array of IO vectors
                 let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
                 // Length of array
-                let iovs_len = arguments[2].expect_i32().unwrap();
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
                 // Out param: number of bytes read
                 let ptr_nread = arguments[3].expect_i32().unwrap() as usize;
 
@@ -187,30 +428,31 @@ impl<'a> WasiDispatcher<'a> {
                 //     void  *iov_base;    /* Starting address */
                 //     size_t iov_len;     /* Number of bytes to transfer */
                 // };
+                let iovs = read_iovecs(memory, ptr_iovs, iovs_len);
 
                 let mut n_read: usize = 0;
-                match self.files.get(fd) {
+                match self.files.get_mut(fd) {
                     Some(ReadOnly(content) | ReadWrite(content)) => {
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            let remaining = content.len() - n_read;
-                            let len = remaining.min(iov_len);
+                        // Reading consumes the bytes, like a pipe
+                        for (iov_base, iov_len) in iovs {
+                            let len = content.len().min(iov_len);
                             if len == 0 {
                                 break;
                             }
-                            memory[iov_base..][..len].copy_from_slice(&content[n_read..][..len]);
+                            memory[iov_base..][..len].copy_from_slice(&content[..len]);
+                            content.drain(..len);
                             n_read += len;
                         }
                     }
                     Some(HostSystemFile) if fd == 0 => {
                         let mut stdin = io::stdin();
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
+                        for (iov_base, iov_len) in iovs {
                             match stdin.read(&mut memory[iov_base..][..iov_len]) {
                                 Ok(n) => {
                                     n_read += n;
+                                    if n < iov_len {
+                                        break;
+                                    }
                                 }
                                 Err(_) => {
                                     break;
@@ -218,17 +460,87 @@ impl<'a> WasiDispatcher<'a> {
                             }
                         }
                     }
+                    Some(MemoryFile { .. } | HostFile(_) | Directory { .. }) => {
+                        match self.read_iovecs_from_file(fd, &iovs, memory) {
+                            Ok(n) => n_read = n,
+                            Err(errno) => return errno_value(errno),
+                        }
+                    }
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
-                memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
+                write_u32(memory, ptr_nread, n_read as u32);
+                success_code
+            }
+            "fd_readdir" => {
+                let fd = arg_usize(0);
+                let ptr_buf = arg_usize(1);
+                let buf_len = arg_usize(2);
+                let cookie = arg_u64(3) as usize;
+                let ptr_bufused = arg_usize(4);
+
+                let entries = match self.read_dir(fd) {
+                    Ok(entries) => entries,
+                    Err(errno) => return errno_value(errno),
+                };
+
+                // If the last entry doesn't fit, we write as much as we can. The program knows
+                // there's more to read because the buffer is full, and calls us again.
+                let mut used = 0;
+                for (index, (name, stat)) in entries.iter().enumerate().skip(cookie) {
+                    let mut dirent = Vec::with_capacity(DIRENT_HEADER_SIZE + name.len());
+                    dirent.extend_from_slice(&(index as u64 + 1).to_le_bytes()); // d_next
+                    dirent.extend_from_slice(&stat.inode.to_le_bytes());
+                    dirent.extend_from_slice(&(name.len() as u32).to_le_bytes());
+                    dirent.extend_from_slice(&[stat.filetype, 0, 0, 0]);
+                    dirent.extend_from_slice(name.as_bytes());
+
+                    let len = dirent.len().min(buf_len - used);
+                    memory[ptr_buf + used..][..len].copy_from_slice(&dirent[..len]);
+                    used += len;
+                    if used == buf_len {
+                        break;
+                    }
+                }
+
+                write_u32(memory, ptr_bufused, used as u32);
+                success_code
+            }
+            "fd_renumber" => {
+                let from = arg_usize(0);
+                let to = arg_usize(1);
+                let is_open =
+                    |file: Option<&WasiFile>| !matches!(file, None | Some(WasiFile::Closed));
+                if !is_open(self.files.get(from)) || !is_open(self.files.get(to)) {
+                    return errno_value(Errno::Badf);
+                }
+                self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
                 success_code
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_seek" => {
+                let fd = arg_usize(0);
+                let offset = arguments[1].expect_i64().unwrap();
+                let whence = arg_usize(2) as u8;
+                let ptr_new_offset = arg_usize(3);
+                match self.seek(fd, offset, whence) {
+                    Ok(new_offset) => {
+                        write_u64(memory, ptr_new_offset, new_offset);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
+            "fd_tell" => {
+                let fd = arg_usize(0);
+                let ptr_offset = arg_usize(1);
+                match self.seek(fd, 0, WHENCE_CUR) {
+                    Ok(offset) => {
+                        write_u64(memory, ptr_offset, offset);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
             "fd_write" => {
                 use WasiFile::*;
 
@@ -243,7 +555,10 @@ impl<'a> WasiDispatcher<'a> {
 
                 // Grab a lock for stdout/stderr before the loop rather than re-acquiring over and over.
                 // Not really necessary for other files, but it's easier to use the same structure.
-                let mut write_lock = match self.files.get_mut(fd) {
+                let Self {
+                    files, preopens, ..
+                } = self;
+                let mut write_lock = match files.get_mut(fd) {
                     Some(HostSystemFile) => match fd {
                         1 => WriteLock::StdOut(io::stdout().lock()),
                         2 => WriteLock::Stderr(io::stderr().lock()),
@@ -252,6 +567,20 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(MemoryFile {
+                        preopen,
+                        inode,
+                        position,
+                        append,
+                    }) => {
+                        let content = memory_file_content(preopens, *preopen, *inode);
+                        if *append {
+                            *position = content.len();
+                        }
+                        WriteLock::MemoryFile { content, position }
+                    }
+                    Some(HostFile(file)) => WriteLock::HostFile(file),
+                    Some(Directory { .. }) => return errno_value(Errno::Isdir),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -280,6 +609,12 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::MemoryFile { content, position } => {
+                            write_at(content, **position, bytes);
+                            **position += bytes.len();
+                            Ok(())
+                        }
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -300,39 +635,765 @@ impl<'a> WasiDispatcher<'a> {
                     Err(_) => Some(Value::I32(Errno::Io as i32)),
                 }
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
-            "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => {
+                let fd = arg_usize(0);
+                let result = read_path(memory, arg_usize(1), arg_usize(2))
+                    .and_then(|path| self.create_directory(fd, path));
+                result_value(result)
+            }
+            "path_filestat_get" => {
+                let fd = arg_usize(0);
+                // arguments[1] is lookupflags. We don't have symlinks, so there's nothing to follow.
+                let ptr_buf = arg_usize(4);
+                let result = read_path(memory, arg_usize(2), arg_usize(3))
+                    .and_then(|path| self.path_filestat(fd, path));
+                match result {
+                    Ok(stat) => {
+                        write_filestat(memory, ptr_buf, &stat);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
+            "path_filestat_set_times" => {
+                let fd = arg_usize(0);
+                let result = read_path(memory, arg_usize(2), arg_usize(3))
+                    .and_then(|path| self.path_filestat(fd, path));
+                result_value(result.map(|_| ()))
+            }
+            "path_open" => {
+                let fd = arg_usize(0);
+                // arguments[1] is dirflags (symlink following), which doesn't apply to us
+                let oflags = arg_usize(4) as u32;
+                let rights = arg_u64(5);
+                let fdflags = arg_usize(7) as u32;
+                let ptr_fd = arg_usize(8);
+
+                let result = read_path(memory, arg_usize(2), arg_usize(3))
+                    .and_then(|path| self.open(fd, path, oflags, rights, fdflags));
+                match result {
+                    Ok(new_fd) => {
+                        write_u32(memory, ptr_fd, new_fd as u32);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
+            // Links are not supported by the in-memory filesystem, so we don't support them for host files either
+            "path_link" | "path_readlink" | "path_symlink" => errno_value(Errno::Notsup),
+            "path_remove_directory" => {
+                let fd = arg_usize(0);
+                let result = read_path(memory, arg_usize(1), arg_usize(2))
+                    .and_then(|path| self.remove(fd, path, true));
+                result_value(result)
+            }
+            "path_rename" => {
+                let old_fd = arg_usize(0);
+                let new_fd = arg_usize(3);
+                let result = read_path(memory, arg_usize(1), arg_usize(2)).and_then(|old_path| {
+                    let new_path = read_path(memory, arg_usize(4), arg_usize(5))?;
+                    self.rename(old_fd, old_path, new_fd, new_path)
+                });
+                result_value(result)
+            }
+            "path_unlink_file" => {
+                let fd = arg_usize(0);
+                let result = read_path(memory, arg_usize(1), arg_usize(2))
+                    .and_then(|path| self.remove(fd, path, false));
+                result_value(result)
+            }
+            "poll_oneoff" => {
+                let ptr_subscriptions = arg_usize(0);
+                let ptr_events = arg_usize(1);
+                let n_subscriptions = arg_usize(2);
+                let ptr_n_events = arg_usize(3);
+                match self.poll_oneoff(memory, ptr_subscriptions, ptr_events, n_subscriptions) {
+                    Ok(n_events) => {
+                        write_u32(memory, ptr_n_events, n_events as u32);
+                        success_code
+                    }
+                    Err(errno) => errno_value(errno),
+                }
+            }
             "proc_exit" => {
                 let exit_code = arguments[0].expect_i32().unwrap();
                 exit(exit_code);
             }
-            "proc_raise" => todo!("WASI {}({:?})", function_name, arguments),
-            "sched_yield" => todo!("WASI {}({:?})", function_name, arguments),
+            "proc_raise" => errno_value(Errno::Nosys),
+            "sched_yield" => success_code,
             "random_get" => {
                 // A pointer to a buffer where the random bytes will be written
                 let ptr_buf = arguments[0].expect_i32().unwrap() as usize;
                 // The number of bytes that will be written
                 let buf_len = arguments[1].expect_i32().unwrap() as usize;
-                for i in 0..buf_len {
-                    memory[ptr_buf + i] = self.rng.gen();
-                }
+                self.rng.fill_bytes(&mut memory[ptr_buf..][..buf_len]);
                 success_code
             }
-            "sock_recv" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_send" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_shutdown" => todo!("WASI {}({:?})", function_name, arguments),
+            // We never create any sockets
+            "sock_recv" | "sock_send" | "sock_shutdown" => errno_value(Errno::Notsock),
             _ => panic!("Unknown WASI function {function_name}({arguments:?})"),
         }
     }
+
+    fn now(&self, clock_id: u32) -> Result<u64, Errno> {
+        match clock_id {
+            CLOCK_REALTIME => {
+                let since_epoch = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|_| Errno::Overflow)?;
+                Ok(since_epoch.as_nanos() as u64)
+            }
+            // The interpreter is a single thread in a single process,
+            // so CPU time is the same as elapsed time, as far as the program can tell.
+            CLOCK_MONOTONIC | CLOCK_PROCESS_CPUTIME | CLOCK_THREAD_CPUTIME => {
+                Ok(self.start_time.elapsed().as_nanos() as u64)
+            }
+            _ => Err(Errno::Inval),
+        }
+    }
+
+    /// Validate the file descriptor for syscalls that have nothing else to do
+    fn fd_only(&self, fd: usize) -> Option<Value> {
+        match self.files.get(fd) {
+            None | Some(WasiFile::Closed) => errno_value(Errno::Badf),
+            Some(_) => Some(Value::I32(Errno::Success as i32)),
+        }
+    }
+
+    fn preopen_of_fd(&self, fd: usize) -> Option<&Preopen> {
+        match self.files.get(fd) {
+            Some(WasiFile::Directory { preopen, path }) if path.is_empty() => {
+                Some(&self.preopens[*preopen])
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolve a path relative to a directory file descriptor.
+    /// Returns the preopen index and the path components from the preopen root.
+    fn resolve(&self, dir_fd: usize, path: &str) -> Result<(usize, Vec<String>), Errno> {
+        match self.files.get(dir_fd) {
+            Some(WasiFile::Directory {
+                preopen,
+                path: base,
+            }) => Ok((*preopen, resolve_path(base, path)?)),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Notdir),
+        }
+    }
+
+    /// Put a new file in the lowest free file descriptor, like POSIX does
+    fn allocate_fd(&mut self, file: WasiFile) -> usize {
+        let free_slot = self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed));
+        match free_slot {
+            Some(fd) => {
+                self.files[fd] = file;
+                fd
+            }
+            None => {
+                self.files.push(file);
+                self.files.len() - 1
+            }
+        }
+    }
+
+    fn open(
+        &mut self,
+        dir_fd: usize,
+        path: &str,
+        oflags: u32,
+        rights: u64,
+        fdflags: u32,
+    ) -> Result<usize, Errno> {
+        let (preopen, components) = self.resolve(dir_fd, path)?;
+        let append = fdflags & FDFLAGS_APPEND != 0;
+
+        let file = match &mut self.preopens[preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let existing = memory_fs.lookup(&components);
+                match existing {
+                    Some(_) if oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL != 0 => {
+                        return Err(Errno::Exist)
+                    }
+                    Some(inode) => match memory_fs.node_mut(inode) {
+                        MemoryNode::Directory(_) => {
+                            if oflags & OFLAGS_TRUNC != 0 {
+                                return Err(Errno::Isdir);
+                            }
+                            WasiFile::Directory {
+                                preopen,
+                                path: components,
+                            }
+                        }
+                        MemoryNode::File(content) => {
+                            if oflags & OFLAGS_DIRECTORY != 0 {
+                                return Err(Errno::Notdir);
+                            }
+                            if oflags & OFLAGS_TRUNC != 0 {
+                                content.clear();
+                            }
+                            WasiFile::MemoryFile {
+                                preopen,
+                                inode,
+                                position: 0,
+                                append,
+                            }
+                        }
+                    },
+                    None if oflags & OFLAGS_CREAT != 0 => {
+                        let (parent, name) = memory_parent(memory_fs, &components)?;
+                        let inode = memory_fs.insert(parent, name, MemoryNode::File(Vec::new()));
+                        WasiFile::MemoryFile {
+                            preopen,
+                            inode,
+                            position: 0,
+                            append,
+                        }
+                    }
+                    None => return Err(Errno::Noent),
+                }
+            }
+            FileSystem::Host(root) => {
+                let path = host_path(root, &components);
+                if path.is_dir() {
+                    if oflags & OFLAGS_TRUNC != 0 {
+                        return Err(Errno::Isdir);
+                    }
+                    WasiFile::Directory {
+                        preopen,
+                        path: components,
+                    }
+                } else {
+                    if oflags & OFLAGS_DIRECTORY != 0 {
+                        return Err(if path.exists() {
+                            Errno::Notdir
+                        } else {
+                            Errno::Noent
+                        });
+                    }
+                    let write = rights & RIGHTS_FD_WRITE != 0 || oflags & OFLAGS_TRUNC != 0;
+                    let file = OpenOptions::new()
+                        .read(rights & RIGHTS_FD_READ != 0 || !write)
+                        .write(write && !append)
+                        .append(append)
+                        .create(oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL == 0)
+                        .create_new(oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL != 0)
+                        .truncate(oflags & OFLAGS_TRUNC != 0)
+                        .open(path)
+                        .map_err(|e| errno_from_io(&e))?;
+                    WasiFile::HostFile(file)
+                }
+            }
+        };
+
+        Ok(self.allocate_fd(file))
+    }
+
+    fn read_from_file(&mut self, fd: usize, buf: &mut [u8]) -> Result<usize, Errno> {
+        let Self {
+            files, preopens, ..
+        } = self;
+        match files.get_mut(fd) {
+            Some(WasiFile::MemoryFile {
+                preopen,
+                inode,
+                position,
+                ..
+            }) => {
+                let content = memory_file_content(preopens, *preopen, *inode);
+                let start = (*position).min(content.len());
+                let len = buf.len().min(content.len() - start);
+                buf[..len].copy_from_slice(&content[start..][..len]);
+                *position = start + len;
+                Ok(len)
+            }
+            Some(WasiFile::HostFile(file)) => file.read(buf).map_err(|e| errno_from_io(&e)),
+            Some(WasiFile::Directory { .. }) => Err(Errno::Isdir),
+            _ => Err(Errno::Badf),
+        }
+    }
+
+    fn read_iovecs_from_file(
+        &mut self,
+        fd: usize,
+        iovs: &[(usize, usize)],
+        memory: &mut [u8],
+    ) -> Result<usize, Errno> {
+        let mut n_read = 0;
+        for &(iov_base, iov_len) in iovs {
+            let n = self.read_from_file(fd, &mut memory[iov_base..][..iov_len])?;
+            n_read += n;
+            if n < iov_len {
+                break;
+            }
+        }
+        Ok(n_read)
+    }
+
+    fn write_to_file(&mut self, fd: usize, bytes: &[u8]) -> Result<(), Errno> {
+        let Self {
+            files, preopens, ..
+        } = self;
+        match files.get_mut(fd) {
+            Some(WasiFile::MemoryFile {
+                preopen,
+                inode,
+                position,
+                ..
+            }) => {
+                let content = memory_file_content(preopens, *preopen, *inode);
+                write_at(content, *position, bytes);
+                *position += bytes.len();
+                Ok(())
+            }
+            Some(WasiFile::HostFile(file)) => file.write_all(bytes).map_err(|e| errno_from_io(&e)),
+            Some(WasiFile::Directory { .. }) => Err(Errno::Isdir),
+            _ => Err(Errno::Badf),
+        }
+    }
+
+    /// Run a read or write at a given offset, without changing the file position (for pread & pwrite)
+    fn with_position(
+        &mut self,
+        fd: usize,
+        offset: u64,
+        f: impl FnOnce(&mut Self, usize) -> Result<usize, Errno>,
+    ) -> Result<usize, Errno> {
+        let saved = self.seek(fd, 0, WHENCE_CUR)?;
+        self.seek(fd, offset as i64, WHENCE_SET)?;
+        let result = f(self, fd);
+        self.seek(fd, saved as i64, WHENCE_SET)?;
+        result
+    }
+
+    fn seek(&mut self, fd: usize, offset: i64, whence: u8) -> Result<u64, Errno> {
+        let Self {
+            files, preopens, ..
+        } = self;
+        match files.get_mut(fd) {
+            Some(WasiFile::MemoryFile {
+                preopen,
+                inode,
+                position,
+                ..
+            }) => {
+                let base = match whence {
+                    WHENCE_SET => 0,
+                    WHENCE_CUR => *position as i64,
+                    WHENCE_END => memory_file_content(preopens, *preopen, *inode).len() as i64,
+                    _ => return Err(Errno::Inval),
+                };
+                let new_position = base.checked_add(offset).ok_or(Errno::Overflow)?;
+                if new_position < 0 {
+                    return Err(Errno::Inval);
+                }
+                *position = new_position as usize;
+                Ok(new_position as u64)
+            }
+            Some(WasiFile::HostFile(file)) => {
+                let seek_from = match whence {
+                    WHENCE_SET => SeekFrom::Start(offset as u64),
+                    WHENCE_CUR => SeekFrom::Current(offset),
+                    WHENCE_END => SeekFrom::End(offset),
+                    _ => return Err(Errno::Inval),
+                };
+                file.seek(seek_from).map_err(|e| errno_from_io(&e))
+            }
+            Some(WasiFile::Directory { .. }) => Err(Errno::Isdir),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Spipe),
+        }
+    }
+
+    /// Set the size of a file. If `can_shrink` is false, only grow it.
+    fn set_file_size(&mut self, fd: usize, size: u64, can_shrink: bool) -> Result<(), Errno> {
+        let Self {
+            files, preopens, ..
+        } = self;
+        match files.get_mut(fd) {
+            Some(WasiFile::MemoryFile { preopen, inode, .. }) => {
+                let content = memory_file_content(preopens, *preopen, *inode);
+                if can_shrink || size as usize > content.len() {
+                    content.resize(size as usize, 0);
+                }
+                Ok(())
+            }
+            Some(WasiFile::HostFile(file)) => {
+                let current = file.metadata().map_err(|e| errno_from_io(&e))?.len();
+                if can_shrink || size > current {
+                    file.set_len(size).map_err(|e| errno_from_io(&e))?;
+                }
+                Ok(())
+            }
+            Some(WasiFile::Directory { .. }) => Err(Errno::Isdir),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Inval),
+        }
+    }
+
+    fn fd_filestat(&self, fd: usize) -> Result<FileStat, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::MemoryFile { preopen, inode, .. }) => {
+                match &self.preopens[*preopen].fs {
+                    FileSystem::Memory(memory_fs) => Ok(memory_filestat(memory_fs, *inode)),
+                    FileSystem::Host(_) => unreachable!(),
+                }
+            }
+            Some(WasiFile::HostFile(file)) => {
+                let metadata = file.metadata().map_err(|e| errno_from_io(&e))?;
+                Ok(host_filestat(&metadata))
+            }
+            Some(WasiFile::Directory { preopen, path }) => self.stat_path(*preopen, path),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(WasiFile::ReadOnly(content) | WasiFile::ReadWrite(content)) => Ok(FileStat {
+                filetype: FILETYPE_CHARACTER_DEVICE,
+                inode: 0,
+                size: content.len() as u64,
+                modified_ns: 0,
+            }),
+            Some(WasiFile::WriteOnly(_) | WasiFile::HostSystemFile) => Ok(FileStat {
+                filetype: FILETYPE_CHARACTER_DEVICE,
+                inode: 0,
+                size: 0,
+                modified_ns: 0,
+            }),
+        }
+    }
+
+    fn path_filestat(&self, dir_fd: usize, path: &str) -> Result<FileStat, Errno> {
+        let (preopen, components) = self.resolve(dir_fd, path)?;
+        self.stat_path(preopen, &components)
+    }
+
+    fn stat_path(&self, preopen: usize, components: &[String]) -> Result<FileStat, Errno> {
+        match &self.preopens[preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let inode = memory_fs.lookup(components).ok_or(Errno::Noent)?;
+                Ok(memory_filestat(memory_fs, inode))
+            }
+            FileSystem::Host(root) => {
+                let metadata = std::fs::metadata(host_path(root, components))
+                    .map_err(|e| errno_from_io(&e))?;
+                Ok(host_filestat(&metadata))
+            }
+        }
+    }
+
+    /// Directory entries, including `.` and `..`, in a stable order
+    fn read_dir(&self, fd: usize) -> Result<Vec<(String, FileStat)>, Errno> {
+        let (preopen, path) = match self.files.get(fd) {
+            Some(WasiFile::Directory { preopen, path }) => (*preopen, path),
+            None | Some(WasiFile::Closed) => return Err(Errno::Badf),
+            Some(_) => return Err(Errno::Notdir),
+        };
+
+        let this_dir = self.stat_path(preopen, path)?;
+        let parent_dir = match path.split_last() {
+            Some((_, parent)) => self.stat_path(preopen, parent)?,
+            None => self.stat_path(preopen, path)?,
+        };
+        let mut entries = vec![(".".to_string(), this_dir), ("..".to_string(), parent_dir)];
+
+        match &self.preopens[preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let inode = memory_fs.lookup(path).ok_or(Errno::Noent)?;
+                if let MemoryNode::Directory(children) = memory_fs.node(inode) {
+                    for (name, child) in children {
+                        entries.push((name.clone(), memory_filestat(memory_fs, *child)));
+                    }
+                }
+            }
+            FileSystem::Host(root) => {
+                let read_dir =
+                    std::fs::read_dir(host_path(root, path)).map_err(|e| errno_from_io(&e))?;
+                let mut children = Vec::new();
+                for entry in read_dir {
+                    let entry = entry.map_err(|e| errno_from_io(&e))?;
+                    let metadata = entry.metadata().map_err(|e| errno_from_io(&e))?;
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    children.push((name, host_filestat(&metadata)));
+                }
+                children.sort_by(|(a, _), (b, _)| a.cmp(b));
+                entries.extend(children);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn create_directory(&mut self, dir_fd: usize, path: &str) -> Result<(), Errno> {
+        let (preopen, components) = self.resolve(dir_fd, path)?;
+        match &mut self.preopens[preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let (parent, name) = memory_parent(memory_fs, &components)?;
+                if memory_fs.child(parent, &name).is_some() {
+                    return Err(Errno::Exist);
+                }
+                memory_fs.insert(parent, name, MemoryNode::Directory(Default::default()));
+                Ok(())
+            }
+            FileSystem::Host(root) => {
+                std::fs::create_dir(host_path(root, &components)).map_err(|e| errno_from_io(&e))
+            }
+        }
+    }
+
+    fn remove(&mut self, dir_fd: usize, path: &str, is_directory: bool) -> Result<(), Errno> {
+        let (preopen, components) = self.resolve(dir_fd, path)?;
+        match &mut self.preopens[preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let (parent, name) = memory_parent(memory_fs, &components)?;
+                let inode = memory_fs.child(parent, &name).ok_or(Errno::Noent)?;
+                match (memory_fs.node(inode), is_directory) {
+                    (MemoryNode::Directory(children), true) if !children.is_empty() => {
+                        return Err(Errno::Notempty)
+                    }
+                    (MemoryNode::Directory(_), false) => return Err(Errno::Isdir),
+                    (MemoryNode::File(_), true) => return Err(Errno::Notdir),
+                    _ => {}
+                }
+                memory_fs.remove(parent, &name);
+                Ok(())
+            }
+            FileSystem::Host(root) => {
+                let path = host_path(root, &components);
+                let result = if is_directory {
+                    std::fs::remove_dir(path)
+                } else {
+                    std::fs::remove_file(path)
+                };
+                result.map_err(|e| errno_from_io(&e))
+            }
+        }
+    }
+
+    fn rename(
+        &mut self,
+        old_dir_fd: usize,
+        old_path: &str,
+        new_dir_fd: usize,
+        new_path: &str,
+    ) -> Result<(), Errno> {
+        let (old_preopen, old_components) = self.resolve(old_dir_fd, old_path)?;
+        let (new_preopen, new_components) = self.resolve(new_dir_fd, new_path)?;
+        if old_preopen != new_preopen {
+            return Err(Errno::Xdev);
+        }
+        match &mut self.preopens[old_preopen].fs {
+            FileSystem::Memory(memory_fs) => {
+                let (old_parent, old_name) = memory_parent(memory_fs, &old_components)?;
+                let (new_parent, new_name) = memory_parent(memory_fs, &new_components)?;
+                let inode = memory_fs
+                    .remove(old_parent, &old_name)
+                    .ok_or(Errno::Noent)?;
+                memory_fs.link(new_parent, new_name, inode);
+                Ok(())
+            }
+            FileSystem::Host(root) => std::fs::rename(
+                host_path(root, &old_components),
+                host_path(root, &new_components),
+            )
+            .map_err(|e| errno_from_io(&e)),
+        }
+    }
+
+    /// Wait for events. Files are always ready, so we only ever block on clocks.
+    fn poll_oneoff(
+        &self,
+        memory: &mut [u8],
+        ptr_subscriptions: usize,
+        ptr_events: usize,
+        n_subscriptions: usize,
+    ) -> Result<usize, Errno> {
+        if n_subscriptions == 0 {
+            return Err(Errno::Inval);
+        }
+
+        // (userdata, event type, error)
+        let mut events: Vec<(u64, u8, Errno)> = Vec::new();
+        // (userdata, nanoseconds from now)
+        let mut timers: Vec<(u64, u64)> = Vec::new();
+
+        for i in 0..n_subscriptions {
+            // struct subscription { u64 userdata; u8 tag; union { clock, fd_readwrite } u; }
+            let ptr = ptr_subscriptions + i * SUBSCRIPTION_SIZE;
+            let userdata = read_u64(memory, ptr);
+            let tag = memory[ptr + 8];
+            match tag {
+                EVENTTYPE_CLOCK => {
+                    // struct subscription_clock { u32 id; u64 timeout; u64 precision; u16 flags; }
+                    let clock_id = read_u32(memory, ptr + 16);
+                    let timeout = read_u64(memory, ptr + 24);
+                    let flags = u16::from_le_bytes([memory[ptr + 40], memory[ptr + 41]]);
+                    let delay = if flags & SUBCLOCKFLAGS_ABSTIME != 0 {
+                        timeout.saturating_sub(self.now(clock_id)?)
+                    } else {
+                        self.now(clock_id)?;
+                        timeout
+                    };
+                    timers.push((userdata, delay));
+                }
+                EVENTTYPE_FD_READ | EVENTTYPE_FD_WRITE => {
+                    let fd = read_u32(memory, ptr + 16) as usize;
+                    let error = match self.files.get(fd) {
+                        None | Some(WasiFile::Closed) => Errno::Badf,
+                        Some(_) => Errno::Success,
+                    };
+                    events.push((userdata, tag, error));
+                }
+                _ => return Err(Errno::Inval),
+            }
+        }
+
+        if events.is_empty() {
+            let shortest = timers.iter().map(|(_, delay)| *delay).min().unwrap();
+            std::thread::sleep(Duration::from_nanos(shortest));
+            for (userdata, delay) in timers {
+                if delay == shortest {
+                    events.push((userdata, EVENTTYPE_CLOCK, Errno::Success));
+                }
+            }
+        }
+
+        for (i, (userdata, event_type, error)) in events.iter().enumerate() {
+            // struct event { u64 userdata; u16 error; u8 type; fd_readwrite { u64 nbytes; u16 flags; } }
+            let ptr = ptr_events + i * EVENT_SIZE;
+            memory[ptr..][..EVENT_SIZE].fill(0);
+            write_u64(memory, ptr, *userdata);
+            memory[ptr + 8..][..2].copy_from_slice(&(*error as u16).to_le_bytes());
+            memory[ptr + 10] = *event_type;
+        }
+
+        Ok(events.len())
+    }
+}
+
+fn errno_value(errno: Errno) -> Option<Value> {
+    Some(Value::I32(errno as i32))
+}
+
+fn result_value(result: Result<(), Errno>) -> Option<Value> {
+    errno_value(result.err().unwrap_or(Errno::Success))
+}
+
+/// Write an array of C strings, and an array of pointers to them, as used by args_get and environ_get
+fn write_string_array<'b>(
+    memory: &mut [u8],
+    mut ptr_ptrs: usize,
+    mut ptr_buf: usize,
+    strings: impl Iterator<Item = &'b [u8]>,
+) {
+    for s in strings {
+        write_u32(memory, ptr_ptrs, ptr_buf as u32);
+        memory[ptr_buf..][..s.len()].copy_from_slice(s);
+        memory[ptr_buf + s.len()] = 0; // C string zero termination
+        ptr_buf += s.len() + 1;
+        ptr_ptrs += 4;
+    }
+}
+
+fn read_iovecs(memory: &[u8], ptr_iovs: usize, iovs_len: usize) -> Vec<(usize, usize)> {
+    (0..iovs_len)
+        .map(|i| {
+            let ptr_iov = ptr_iovs + 8 * i;
+            let iov_base = read_u32(memory, ptr_iov) as usize;
+            let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+            (iov_base, iov_len)
+        })
+        .collect()
+}
+
+fn read_path(memory: &[u8], ptr: usize, len: usize) -> Result<&str, Errno> {
+    std::str::from_utf8(&memory[ptr..][..len]).map_err(|_| Errno::Ilseq)
+}
+
+fn memory_file_content(preopens: &mut [Preopen], preopen: usize, inode: usize) -> &mut Vec<u8> {
+    match &mut preopens[preopen].fs {
+        FileSystem::Memory(memory_fs) => match memory_fs.node_mut(inode) {
+            MemoryNode::File(content) => content,
+            MemoryNode::Directory(_) => unreachable!("MemoryFile fd refers to a directory"),
+        },
+        FileSystem::Host(_) => unreachable!("MemoryFile fd refers to a host directory"),
+    }
+}
+
+/// The parent directory inode and the file name, for a path in an in-memory filesystem
+fn memory_parent(memory_fs: &MemoryFs, components: &[String]) -> Result<(usize, String), Errno> {
+    let (name, parent_path) = components.split_last().ok_or(Errno::Inval)?;
+    let parent = memory_fs.lookup(parent_path).ok_or(Errno::Noent)?;
+    match memory_fs.node(parent) {
+        MemoryNode::Directory(_) => Ok((parent, name.clone())),
+        MemoryNode::File(_) => Err(Errno::Notdir),
+    }
+}
+
+fn write_at(content: &mut Vec<u8>, position: usize, bytes: &[u8]) {
+    let end = position + bytes.len();
+    if content.len() < end {
+        content.resize(end, 0);
+    }
+    content[position..end].copy_from_slice(bytes);
+}
+
+fn memory_filestat(memory_fs: &MemoryFs, inode: usize) -> FileStat {
+    let (filetype, size) = match memory_fs.node(inode) {
+        MemoryNode::File(content) => (FILETYPE_REGULAR_FILE, content.len() as u64),
+        MemoryNode::Directory(_) => (FILETYPE_DIRECTORY, 0),
+    };
+    FileStat {
+        filetype,
+        inode: inode as u64,
+        size,
+        modified_ns: 0,
+    }
+}
+
+fn host_filestat(metadata: &std::fs::Metadata) -> FileStat {
+    let file_type = metadata.file_type();
+    let filetype = if file_type.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if file_type.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_REGULAR_FILE
+    };
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0);
+    FileStat {
+        filetype,
+        inode: 0,
+        size: metadata.len(),
+        modified_ns,
+    }
+}
+
+fn write_filestat(memory: &mut [u8], ptr: usize, stat: &FileStat) {
+    // struct filestat { u64 dev; u64 ino; u8 filetype; u64 nlink; u64 size; u64 atim; u64 mtim; u64 ctim; }
+    memory[ptr..][..FILESTAT_SIZE].fill(0);
+    write_u64(memory, ptr + 8, stat.inode);
+    memory[ptr + 16] = stat.filetype;
+    write_u64(memory, ptr + 24, 1);
+    write_u64(memory, ptr + 32, stat.size);
+    write_u64(memory, ptr + 40, stat.modified_ns);
+    write_u64(memory, ptr + 48, stat.modified_ns);
+    write_u64(memory, ptr + 56, stat.modified_ns);
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&memory[addr..][..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
 }
 
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
@@ -360,7 +1421,7 @@ fn write_i32(memory: &mut [u8], addr: usize, value: i32) {
 /// API; some are used in higher-level library layers, and others are provided
 /// merely for alignment with POSIX.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Errno {
     /// No error occurred. System call completed successfully.
     Success,
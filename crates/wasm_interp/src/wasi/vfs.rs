@@ -0,0 +1,195 @@
+//! Filesystems that a WASI program can access through preopened directories.
+//! Either a directory on the host, or an in-memory tree for hermetic tests.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::Errno;
+
+#[derive(Debug)]
+pub enum FileSystem {
+    /// A directory on the host machine. The program can read and write real files inside it.
+    Host(PathBuf),
+    /// A filesystem that only exists in memory. The host machine is never touched.
+    Memory(MemoryFs),
+}
+
+/// A directory made available to the WASI program at startup, under the name `guest_path`
+#[derive(Debug)]
+pub struct Preopen {
+    pub guest_path: String,
+    pub fs: FileSystem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryNode {
+    File(Vec<u8>),
+    Directory(BTreeMap<String, usize>),
+}
+
+/// An in-memory tree of files and directories. Inode numbers are indices into `nodes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryFs {
+    nodes: Vec<MemoryNode>,
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFs {
+    pub const ROOT: usize = 0;
+
+    pub fn new() -> Self {
+        MemoryFs {
+            nodes: vec![MemoryNode::Directory(BTreeMap::new())],
+        }
+    }
+
+    /// Copy a host directory into memory. Changes made by the program are not written back.
+    pub fn from_host_dir(host_dir: &Path) -> io::Result<Self> {
+        let mut memory_fs = Self::new();
+        memory_fs.copy_host_dir(host_dir, Self::ROOT)?;
+        Ok(memory_fs)
+    }
+
+    fn copy_host_dir(&mut self, host_dir: &Path, dir_inode: usize) -> io::Result<()> {
+        for entry in fs::read_dir(host_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_dir() {
+                let inode = self.insert(dir_inode, name, MemoryNode::Directory(BTreeMap::new()));
+                self.copy_host_dir(&entry.path(), inode)?;
+            } else {
+                let content = fs::read(entry.path())?;
+                self.insert(dir_inode, name, MemoryNode::File(content));
+            }
+        }
+        Ok(())
+    }
+
+    /// Create a file, and any missing parent directories. Replaces any existing file.
+    pub fn create_file(&mut self, path: &str, content: Vec<u8>) {
+        let mut components = resolve_path(&[], path).unwrap();
+        let name = components.pop().expect("File path must not be empty");
+        let parent = self.create_dir_all(&components);
+        self.insert(parent, name, MemoryNode::File(content));
+    }
+
+    /// Read a file, for example to check what a program wrote
+    pub fn read_file(&self, path: &str) -> Option<&[u8]> {
+        let components = resolve_path(&[], path).ok()?;
+        match self.lookup(&components).map(|inode| &self.nodes[inode]) {
+            Some(MemoryNode::File(content)) => Some(content),
+            _ => None,
+        }
+    }
+
+    fn create_dir_all(&mut self, components: &[String]) -> usize {
+        let mut inode = Self::ROOT;
+        for name in components {
+            inode = match self.child(inode, name) {
+                Some(child) => child,
+                None => self.insert(inode, name.clone(), MemoryNode::Directory(BTreeMap::new())),
+            };
+        }
+        inode
+    }
+
+    pub(crate) fn node(&self, inode: usize) -> &MemoryNode {
+        &self.nodes[inode]
+    }
+
+    pub(crate) fn node_mut(&mut self, inode: usize) -> &mut MemoryNode {
+        &mut self.nodes[inode]
+    }
+
+    pub(crate) fn child(&self, dir_inode: usize, name: &str) -> Option<usize> {
+        match &self.nodes[dir_inode] {
+            MemoryNode::Directory(entries) => entries.get(name).copied(),
+            MemoryNode::File(_) => None,
+        }
+    }
+
+    pub(crate) fn lookup(&self, components: &[String]) -> Option<usize> {
+        let mut inode = Self::ROOT;
+        for name in components {
+            inode = self.child(inode, name)?;
+        }
+        Some(inode)
+    }
+
+    /// Add a node to a directory, replacing any existing entry with the same name
+    pub(crate) fn insert(&mut self, dir_inode: usize, name: String, node: MemoryNode) -> usize {
+        let inode = self.nodes.len();
+        self.nodes.push(node);
+        match &mut self.nodes[dir_inode] {
+            MemoryNode::Directory(entries) => {
+                entries.insert(name, inode);
+            }
+            MemoryNode::File(_) => panic!("Cannot insert into a file"),
+        }
+        inode
+    }
+
+    /// Remove a directory entry. The node itself stays allocated, in case a file descriptor refers to it.
+    pub(crate) fn remove(&mut self, dir_inode: usize, name: &str) -> Option<usize> {
+        match &mut self.nodes[dir_inode] {
+            MemoryNode::Directory(entries) => entries.remove(name),
+            MemoryNode::File(_) => None,
+        }
+    }
+
+    /// Move an existing node to a new directory entry
+    pub(crate) fn link(&mut self, dir_inode: usize, name: String, inode: usize) {
+        if let MemoryNode::Directory(entries) = &mut self.nodes[dir_inode] {
+            entries.insert(name, inode);
+        }
+    }
+}
+
+/// Resolve a guest path relative to a directory, without escaping the preopened root.
+/// Returns the path components from the root.
+pub fn resolve_path(base: &[String], path: &str) -> Result<Vec<String>, Errno> {
+    if path.starts_with('/') {
+        return Err(Errno::Notcapable);
+    }
+    let mut components = base.to_vec();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(Errno::Notcapable);
+                }
+            }
+            name => components.push(name.to_string()),
+        }
+    }
+    Ok(components)
+}
+
+pub fn host_path(root: &Path, components: &[String]) -> PathBuf {
+    let mut path = root.to_path_buf();
+    path.extend(components);
+    path
+}
+
+pub fn errno_from_io(error: &io::Error) -> Errno {
+    use io::ErrorKind::*;
+    match error.kind() {
+        NotFound => Errno::Noent,
+        PermissionDenied => Errno::Access,
+        AlreadyExists => Errno::Exist,
+        InvalidInput => Errno::Inval,
+        UnexpectedEof => Errno::Io,
+        Interrupted => Errno::Intr,
+        Unsupported => Errno::Notsup,
+        OutOfMemory => Errno::Nomem,
+        _ => Errno::Io,
+    }
+}
@@ -9,7 +9,9 @@ use std::iter::repeat;
 pub use linking::{OffsetRelocType, RelocationEntry, SymInfo};
 use opcodes::OpCode;
 use roc_error_macros::internal_error;
-pub use sections::{ConstExpr, Export, ExportType, Global, GlobalType, Signature};
+pub use sections::{
+    ConstExpr, Export, ExportType, Global, GlobalType, MultiValueSignature, Signature,
+};
 
 use bitvec::vec::BitVec;
 use bumpalo::{collections::Vec, Bump};
@@ -18,8 +20,8 @@ use self::linking::{IndexRelocType, LinkingSection, RelocationSection, WasmObjec
 use self::parse::{Parse, ParseError};
 use self::sections::{
     CodeSection, DataSection, ElementSection, ExportSection, FunctionSection, GlobalSection,
-    ImportDesc, ImportSection, MemorySection, NameSection, OpaqueSection, Section, SectionId,
-    TableSection, TypeSection,
+    Import, ImportDesc, ImportSection, MemorySection, NameSection, OpaqueSection, Section,
+    SectionId, TableSection, TypeSection,
 };
 pub use self::serialize::{SerialBuffer, Serialize};
 
@@ -76,6 +78,12 @@ impl<'a> WasmModule<'a> {
         self.function.add_sig(index);
     }
 
+    /// Create entries in the Type and Function sections for a function with multiple return values
+    pub fn add_multi_value_function_signature(&mut self, signature: MultiValueSignature<'a>) {
+        let index = self.types.insert_multi_value(signature);
+        self.function.add_sig(index);
+    }
+
     /// Serialize the module to bytes
     pub fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
         buffer.append_u8(0);
@@ -626,6 +634,53 @@ impl<'a> WasmModule<'a> {
         }
     }
 
+    /// Turn one of the dummy functions left behind by `link_host_to_app_calls` back into an import.
+    /// The dummies sit directly after the imported functions, so function indices don't change
+    /// and no relocations are needed. Calls to the new import should use `call_import`, so that
+    /// dead code elimination can still remove or reindex it.
+    /// Returns None if there are no dummy functions left to recycle.
+    pub fn import_fn_into_dummy_slot(
+        &mut self,
+        module: &'a str,
+        name: &'a str,
+        signature: Signature<'a>,
+    ) -> Option<u32> {
+        if self.code.dead_import_dummy_count == 0 {
+            return None;
+        }
+
+        let fn_index = self.import.function_count() as u32;
+        let signature_index = self.types.insert(signature);
+
+        self.import.imports.push(Import {
+            module,
+            name,
+            description: ImportDesc::Func { signature_index },
+        });
+
+        // The dummies' signatures are at the start of the FunctionSection
+        self.code.dead_import_dummy_count -= 1;
+        self.function.signatures.remove(0);
+
+        self.linking
+            .symbol_table
+            .push(SymInfo::Function(WasmObjectSymbol::ImplicitlyNamed {
+                flags: linking::WASM_SYM_UNDEFINED,
+                index: fn_index,
+            }));
+
+        if let Some((_, debug_name)) = self
+            .names
+            .function_names
+            .iter_mut()
+            .find(|(i, _)| *i == fn_index)
+        {
+            *debug_name = name;
+        }
+
+        Some(fn_index)
+    }
+
     /// Create a name->index lookup table for host functions that may be called from the app
     pub fn get_host_function_lookup(&self, arena: &'a Bump) -> Vec<'a, (&'a str, u32)> {
         // Functions beginning with `roc_` go first, since they're most likely to be called
@@ -19,6 +19,8 @@ pub enum OpCode {
     RETURN = 0x0f,
     CALL = 0x10,
     CALLINDIRECT = 0x11,
+    RETURNCALL = 0x12,
+    RETURNCALLINDIRECT = 0x13,
     DROP = 0x1a,
     SELECT = 0x1b,
     GETLOCAL = 0x20,
@@ -211,6 +213,8 @@ pub const LOOKUP_TABLE: [Option<OpCode>; 256] = {
     result[0x0f] = Some(RETURN);
     result[0x10] = Some(CALL);
     result[0x11] = Some(CALLINDIRECT);
+    result[0x12] = Some(RETURNCALL);
+    result[0x13] = Some(RETURNCALLINDIRECT);
     result[0x1a] = Some(DROP);
     result[0x1b] = Some(SELECT);
     result[0x20] = Some(GETLOCAL);
@@ -450,8 +454,8 @@ fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
         BR | BRIF => Leb32x1,
         BRTABLE => BrTable,
         RETURN => NoImmediate,
-        CALL => Leb32x1,
-        CALLINDIRECT => Leb32x2,
+        CALL | RETURNCALL => Leb32x1,
+        CALLINDIRECT | RETURNCALLINDIRECT => Leb32x2,
         DROP => NoImmediate,
         SELECT => NoImmediate,
         GETLOCAL | SETLOCAL | TEELOCAL => Leb32x1,
@@ -213,6 +213,22 @@ impl<'a> Serialize for Signature<'a> {
     }
 }
 
+/// A function type with any number of return values, from the multi-value proposal.
+/// Most functions have at most one return value, so they use `Signature` instead.
+#[derive(PartialEq, Eq, Debug)]
+pub struct MultiValueSignature<'a> {
+    pub param_types: Vec<'a, ValueType>,
+    pub ret_types: &'a [ValueType],
+}
+
+impl<'a> Serialize for MultiValueSignature<'a> {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        buffer.append_u8(Signature::SEPARATOR);
+        self.param_types.serialize(buffer);
+        self.ret_types.serialize(buffer);
+    }
+}
+
 #[derive(Debug)]
 pub struct SignatureParamsIter<'a> {
     bytes: &'a [u8],
@@ -274,7 +290,18 @@ impl<'a> TypeSection<'a> {
     pub fn insert(&mut self, signature: Signature<'a>) -> u32 {
         let mut sig_bytes = Vec::with_capacity_in(signature.param_types.len() + 4, self.arena);
         signature.serialize(&mut sig_bytes);
+        self.insert_bytes(sig_bytes)
+    }
 
+    /// Find a matching multi-value signature or insert a new one. Return the index.
+    pub fn insert_multi_value(&mut self, signature: MultiValueSignature<'a>) -> u32 {
+        let capacity = signature.param_types.len() + signature.ret_types.len() + 3;
+        let mut sig_bytes = Vec::with_capacity_in(capacity, self.arena);
+        signature.serialize(&mut sig_bytes);
+        self.insert_bytes(sig_bytes)
+    }
+
+    fn insert_bytes(&mut self, sig_bytes: Vec<'a, u8>) -> u32 {
         let sig_len = sig_bytes.len();
         let bytes_len = self.bytes.len();
 
@@ -317,6 +344,21 @@ impl<'a> TypeSection<'a> {
         };
         (params_iter, return_type)
     }
+
+    /// Look up all of the return types of a signature.
+    /// Unlike `look_up`, this works for multi-value signatures.
+    pub fn look_up_ret_types(&'a self, sig_index: u32) -> SignatureParamsIter<'a> {
+        let mut offset = self.offsets[sig_index as usize];
+        offset += 1; // separator
+        let param_count = u32::parse((), &self.bytes, &mut offset).unwrap() as usize;
+        offset += param_count;
+        let ret_count = u32::parse((), &self.bytes, &mut offset).unwrap() as usize;
+        SignatureParamsIter {
+            bytes: &self.bytes[offset..][..ret_count],
+            index: 0,
+            end: ret_count,
+        }
+    }
 }
 
 impl<'a> Section<'a> for TypeSection<'a> {